@@ -0,0 +1,205 @@
+//! Command line tool for inspecting fonts.
+//!
+//! Usage: `inspect <command> <font> [args]` where command is one of:
+//!
+//! * `info`: summary, attributes and version information
+//! * `axes`: variation axes
+//! * `instances`: named variation instances
+//! * `metrics [size]`: global font metrics
+//! * `layout`: scripts and features of the `GSUB` and `GPOS` tables with
+//!   statistics for the lookups of each feature
+//! * `charmap [text]`: number of mapped codepoints or the glyphs for the
+//!   characters of the given text
+//! * `outline <glyph_id> [size] [tag=value,...]`: glyph outline as SVG
+
+use fello::{
+    meta::{info_strings::StringId, summary::Summary, variations::VariationSetting},
+    raw::{types::Tag, FontRef},
+    scale::outline_to_svg,
+    GlyphId, MetadataProvider, NormalizedCoords, Size,
+};
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.len() < 2 {
+        return usage();
+    }
+    let data = match std::fs::read(&args[1]) {
+        Ok(data) => data,
+        Err(e) => return eprintln!("failed to read {}: {e}", args[1]),
+    };
+    let font = match FontRef::new(&data) {
+        Ok(font) => font,
+        Err(e) => return eprintln!("failed to parse {}: {e}", args[1]),
+    };
+    let rest = &args[2..];
+    match args[0].as_str() {
+        "info" => info(&font),
+        "axes" => axes(&font),
+        "instances" => instances(&font),
+        "metrics" => metrics(&font, rest),
+        "layout" => layout(&font),
+        "charmap" => charmap(&font, rest),
+        "outline" => outline(&font, rest),
+        _ => usage(),
+    }
+}
+
+fn usage() {
+    eprintln!("usage: inspect <command> <font> [args]");
+    eprintln!("commands:");
+    eprintln!("  info");
+    eprintln!("  axes");
+    eprintln!("  instances");
+    eprintln!("  metrics [size]");
+    eprintln!("  layout");
+    eprintln!("  charmap [text]");
+    eprintln!("  outline <glyph_id> [size] [tag=value,...]");
+}
+
+fn string(font: &FontRef, id: StringId) -> String {
+    font.string(id, &["en-US"])
+        .and_then(|string| Some(string.string()?.to_string()))
+        .unwrap_or_default()
+}
+
+fn info(font: &FontRef) {
+    let summary = Summary::new(font);
+    println!("family: {}", string(font, StringId::FAMILY_NAME));
+    println!("subfamily: {}", string(font, StringId::SUBFAMILY_NAME));
+    println!("flavor: {:?}", summary.flavor);
+    println!("glyphs: {}", summary.glyph_count);
+    println!("units per em: {}", summary.units_per_em);
+    println!("color: {}", summary.has_color);
+    println!("variable: {}", summary.is_variable);
+    let attributes = font.attributes();
+    println!("stretch: {}", attributes.stretch.ratio());
+    println!("style: {:?}", attributes.style);
+    println!("weight: {}", attributes.weight.value());
+    let version = font.version_info();
+    println!("revision: {}", version.revision);
+    if let Some((major, minor)) = version.version {
+        println!("version: {major}.{minor}");
+    }
+    println!("created: {}", version.created.unix_secs());
+    println!("modified: {}", version.modified.unix_secs());
+    print!("tables:");
+    for (tag, len) in &summary.tables {
+        print!(" {}({len})", Tag::new(tag));
+    }
+    println!();
+}
+
+fn axes(font: &FontRef) {
+    for axis in font.axes().iter() {
+        println!(
+            "{} \"{}\" min={} default={} max={}{}",
+            axis.tag(),
+            string(font, axis.name_id()),
+            axis.min_value(),
+            axis.default_value(),
+            axis.max_value(),
+            if axis.is_hidden() { " hidden" } else { "" }
+        );
+    }
+}
+
+fn instances(font: &FontRef) {
+    let strings = font.info_strings();
+    for instance in font.instances().iter() {
+        let coords: Vec<String> = instance.coords().map(|c| c.to_string()).collect();
+        match instance.names(&strings, &["en-US"]) {
+            Some(names) => println!(
+                "\"{}\" ({}) [{}]",
+                names.display_name,
+                names.post_script_name,
+                coords.join(", ")
+            ),
+            None => println!(
+                "\"{}\" [{}]",
+                string(font, instance.subfamily_name_id()),
+                coords.join(", ")
+            ),
+        }
+    }
+}
+
+fn metrics(font: &FontRef, args: &[String]) {
+    let size = parse_size(args.first());
+    let metrics = font.metrics(size, NormalizedCoords::default());
+    println!("{metrics:#?}");
+}
+
+fn layout(font: &FontRef) {
+    for script in font.features().scripts() {
+        let tags: Vec<String> = script
+            .features
+            .iter()
+            .map(|feature| format!("{}({:?})", feature.tag, feature.table))
+            .collect();
+        println!("script {}: {}", script.script, tags.join(" "));
+    }
+    let stats = font.layout_stats();
+    for feature in stats.features() {
+        println!(
+            "feature {} {:?}: lookups={} subtables={} rules={} coverage={} context={}",
+            feature.tag,
+            feature.table,
+            feature.lookup_count,
+            feature.subtable_count,
+            feature.rule_count,
+            feature.coverage,
+            feature.max_context
+        );
+    }
+    println!("max context: {}", stats.max_context());
+}
+
+fn charmap(font: &FontRef, args: &[String]) {
+    let charmap = font.charmap();
+    match args.first() {
+        Some(text) => {
+            for ch in text.chars() {
+                match charmap.map(ch) {
+                    Some(gid) => println!("U+{:04X} {ch} -> {}", ch as u32, gid.to_u16()),
+                    None => println!("U+{:04X} {ch} -> missing", ch as u32),
+                }
+            }
+        }
+        None => {
+            let count = (0..=0x10FFFF_u32)
+                .filter(|codepoint| charmap.map(*codepoint).is_some())
+                .count();
+            println!("mapped codepoints: {count}");
+        }
+    }
+}
+
+fn outline(font: &FontRef, args: &[String]) {
+    let Some(gid) = args.first().and_then(|arg| arg.parse::<u16>().ok()) else {
+        return usage();
+    };
+    let gid = GlyphId::new(gid);
+    let size = parse_size(args.get(1));
+    let variations: Vec<VariationSetting> = args
+        .get(2)
+        .map(|arg| {
+            arg.split(',')
+                .filter_map(|setting| {
+                    let (tag, value) = setting.split_once('=')?;
+                    Some((tag, value.parse::<f32>().ok()?).into())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let coords: Vec<_> = font.axes().normalize(variations).collect();
+    match outline_to_svg(font, gid, size, NormalizedCoords::new(&coords)) {
+        Ok(svg) => println!("{svg}"),
+        Err(e) => eprintln!("failed to load glyph {}: {e}", gid.to_u16()),
+    }
+}
+
+fn parse_size(arg: Option<&String>) -> Size {
+    arg.and_then(|arg| arg.parse().ok())
+        .map(Size::new)
+        .unwrap_or(Size::unscaled())
+}
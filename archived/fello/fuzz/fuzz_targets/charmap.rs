@@ -0,0 +1,19 @@
+#![no_main]
+
+use fello::{raw::FontRef, MetadataProvider};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(font) = FontRef::new(data) else {
+        return;
+    };
+    let charmap = font.charmap();
+    // Cover the BMP boundaries and the start of the supplementary planes
+    // rather than every codepoint to keep iterations fast.
+    for codepoint in (0..0x3000).chain(0xFFF0..0x10100).chain([0x10FFFF]) {
+        charmap.map(codepoint);
+    }
+    for ch in ['a', '\u{308}', '\u{1F600}'] {
+        charmap.map_with_decomposition(ch);
+    }
+});
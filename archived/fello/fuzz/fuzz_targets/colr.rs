@@ -0,0 +1,46 @@
+#![no_main]
+
+use fello::{
+    raw::FontRef,
+    scale::{Context, Pen},
+    GlyphId, MetadataProvider, NormalizedCoord, Size,
+};
+use libfuzzer_sys::fuzz_target;
+
+struct NullPen;
+
+impl Pen for NullPen {
+    fn move_to(&mut self, _x: f32, _y: f32) {}
+    fn line_to(&mut self, _x: f32, _y: f32) {}
+    fn quad_to(&mut self, _cx0: f32, _cy0: f32, _x: f32, _y: f32) {}
+    fn curve_to(&mut self, _cx0: f32, _cy0: f32, _cx1: f32, _cy1: f32, _x: f32, _y: f32) {}
+    fn close(&mut self) {}
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(font) = FontRef::new(data) else {
+        return;
+    };
+    let axis_count = font.axes().len();
+    let coords: Vec<_> = (0..axis_count)
+        .map(|i| NormalizedCoord::from_f32(if i % 2 == 0 { 0.5 } else { -1.0 }))
+        .collect();
+    let glyph_count = font
+        .glyph_metrics(Size::unscaled(), Default::default())
+        .glyph_count()
+        .min(64);
+    let mut context = Context::new();
+    for coords in [&[][..], &coords[..]] {
+        for size in [Size::unscaled(), Size::new(16.0)] {
+            let mut scaler = context.new_scaler().size(size).coords(coords).build(&font);
+            for gid in 0..glyph_count {
+                let gid = GlyphId::new(gid);
+                // Variable clip boxes read the COLR variation store.
+                let _ = scaler.color_clip_box(gid);
+                // Empty outlines look up the glyph in the COLR base glyph
+                // records and lists.
+                let _ = scaler.outline(gid, &mut NullPen);
+            }
+        }
+    }
+});
@@ -0,0 +1,27 @@
+#![no_main]
+
+use fello::{raw::FontRef, GlyphId, MetadataProvider, NormalizedCoord, NormalizedCoords, Size};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(font) = FontRef::new(data) else {
+        return;
+    };
+    let axis_count = font.axes().len();
+    let coords: Vec<_> = (0..axis_count)
+        .map(|i| NormalizedCoord::from_f32(if i % 2 == 0 { 0.5 } else { -1.0 }))
+        .collect();
+    for coords in [NormalizedCoords::default(), NormalizedCoords::new(&coords)] {
+        for size in [Size::unscaled(), Size::new(16.0), Size::new(0.0)] {
+            font.metrics(size, coords);
+            let glyph_metrics = font.glyph_metrics(size, coords);
+            for gid in 0..glyph_metrics.glyph_count().min(64) {
+                let gid = GlyphId::new(gid);
+                glyph_metrics.advance_width(gid);
+                glyph_metrics.left_side_bearing(gid);
+                glyph_metrics.vertical_metrics(gid);
+                glyph_metrics.bounds(gid);
+            }
+        }
+    }
+});
@@ -0,0 +1,40 @@
+#![no_main]
+
+use fello::{
+    meta::summary::Summary,
+    raw::FontRef,
+    scale::{Context, Hinting, Pen},
+    GlyphId, Size,
+};
+use libfuzzer_sys::fuzz_target;
+
+struct NullPen;
+
+impl Pen for NullPen {
+    fn move_to(&mut self, _x: f32, _y: f32) {}
+    fn line_to(&mut self, _x: f32, _y: f32) {}
+    fn quad_to(&mut self, _cx0: f32, _cy0: f32, _x: f32, _y: f32) {}
+    fn curve_to(&mut self, _cx0: f32, _cy0: f32, _cx1: f32, _cy1: f32, _x: f32, _y: f32) {}
+    fn close(&mut self) {}
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(font) = FontRef::new(data) else {
+        return;
+    };
+    let glyph_count = Summary::new(&font).glyph_count.min(32);
+    let mut context = Context::new();
+    for hint in [None, Some(Hinting::Full), Some(Hinting::VerticalSubpixel)] {
+        for size in [Size::unscaled(), Size::new(12.0)] {
+            let mut scaler = context
+                .new_scaler()
+                .size(size)
+                .hint(hint)
+                .variations([("wght", 700.0)])
+                .build(&font);
+            for gid in 0..glyph_count {
+                let _ = scaler.outline(GlyphId::new(gid), &mut NullPen);
+            }
+        }
+    }
+});
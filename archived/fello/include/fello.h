@@ -0,0 +1,162 @@
+#ifndef FELLO_H
+#define FELLO_H
+
+/* Generated by cbindgen from src/capi.rs. Do not edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Opaque font object.
+typedef struct FelloFont FelloFont;
+
+// Global metrics for a font.
+typedef struct FelloMetrics {
+  // Number of font design units per em unit.
+  uint16_t units_per_em;
+  // Number of glyphs in the font.
+  uint16_t glyph_count;
+  // True if the font is not proportionally spaced.
+  bool is_monospace;
+  // Italic angle in counter-clockwise degrees from the vertical.
+  float italic_angle;
+  // Distance from the baseline to the top of the alignment box.
+  float ascent;
+  // Distance from the baseline to the bottom of the alignment box.
+  float descent;
+  // Recommended additional spacing between lines.
+  float leading;
+} FelloMetrics;
+
+// Variation axis of a font.
+typedef struct FelloAxis {
+  // Tag identifying the axis as a big endian 32-bit integer.
+  uint32_t tag;
+  // Minimum value of the axis.
+  float min_value;
+  // Default value of the axis.
+  float default_value;
+  // Maximum value of the axis.
+  float max_value;
+} FelloAxis;
+
+// Set of callbacks for receiving glyph outlines.
+//
+// Any callback may be null, in which case the corresponding command is
+// ignored. The `context` pointer is passed as the first argument to each
+// callback.
+typedef struct FelloPen {
+  // User data passed to each callback.
+  void *context;
+  // Begins a new contour at the given point.
+  void (*move_to)(void*, float, float);
+  // Adds a line to the given point.
+  void (*line_to)(void*, float, float);
+  // Adds a quadratic curve with the given control and end points.
+  void (*quad_to)(void*, float, float, float, float);
+  // Adds a cubic curve with the given control and end points.
+  void (*curve_to)(void*, float, float, float, float, float, float);
+  // Closes the current contour.
+  void (*close)(void*);
+} FelloPen;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a new font object from a copy of the given data.
+//
+// Returns null if the data does not contain a valid font at the given
+// index. The result must be released with [`fello_font_free`].
+//
+// # Safety
+//
+// `data` must point to `len` readable bytes.
+FelloFont *fello_font_new(const uint8_t *data, size_t len, uint32_t index);
+
+// Releases a font object.
+//
+// # Safety
+//
+// `font` must be null or a pointer returned by [`fello_font_new`] that has
+// not yet been freed.
+void fello_font_free(FelloFont *font);
+
+// Retrieves the global metrics for the font at the given size in pixels
+// per em and normalized variation coordinates.
+//
+// A size of zero produces metrics in font units. Returns false on failure.
+//
+// # Safety
+//
+// `font` must be a valid font object, `coords` must be null or point to
+// `coord_count` values and `metrics` must be a valid pointer.
+bool fello_font_metrics(const FelloFont *font,
+                        float size,
+                        const float *coords,
+                        size_t coord_count,
+                        FelloMetrics *metrics);
+
+// Returns the number of variation axes in the font.
+//
+// # Safety
+//
+// `font` must be a valid font object.
+uint32_t fello_font_axis_count(const FelloFont *font);
+
+// Retrieves the variation axis at the given index.
+//
+// Returns false if the index is out of bounds.
+//
+// # Safety
+//
+// `font` must be a valid font object and `axis` must be a valid pointer.
+bool fello_font_axis(const FelloFont *font, uint32_t index, FelloAxis *axis);
+
+// Maps a Unicode codepoint to a nominal glyph identifier.
+//
+// Returns 0 (the `.notdef` glyph) if the codepoint is not mapped.
+//
+// # Safety
+//
+// `font` must be a valid font object.
+uint16_t fello_font_map_char(const FelloFont *font, uint32_t codepoint);
+
+// Returns the advance width of a glyph at the given size in pixels per em
+// and normalized variation coordinates.
+//
+// A size of zero produces an advance in font units. Returns 0 if the glyph
+// does not exist.
+//
+// # Safety
+//
+// `font` must be a valid font object and `coords` must be null or point
+// to `coord_count` values.
+float fello_font_advance_width(const FelloFont *font,
+                               uint16_t glyph_id,
+                               float size,
+                               const float *coords,
+                               size_t coord_count);
+
+// Loads the outline for a glyph at the given size in pixels per em and
+// normalized variation coordinates and emits it to the pen.
+//
+// A size of zero produces an outline in font units. Returns false if the
+// outline could not be loaded.
+//
+// # Safety
+//
+// `font` must be a valid font object, `coords` must be null or point to
+// `coord_count` values and `pen` must be a valid pointer.
+bool fello_font_outline(FelloFont *font,
+                        uint16_t glyph_id,
+                        float size,
+                        const float *coords,
+                        size_t coord_count,
+                        const FelloPen *pen);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif // FELLO_H
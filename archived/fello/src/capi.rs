@@ -0,0 +1,507 @@
+/*! C API for metadata and outline extraction.
+
+This module exposes a small, stable C ABI over the metadata and scaling
+functionality of this crate. It is enabled with the `capi` feature.
+
+A shared or static library suitable for linking from C, C++ or Swift can be
+produced with:
+
+```text
+cargo rustc --release --features capi --crate-type cdylib
+```
+
+All functions are prefixed with `fello_` and all types with `Fello`. Font
+objects own a copy of the font data and are not thread safe; a single font
+object must not be used concurrently from multiple threads.
+
+The C declarations are checked in at `include/fello.h`. After changing this
+module, regenerate the header with:
+
+```text
+cbindgen --config cbindgen.toml --output include/fello.h
+```
+*/
+
+use super::{
+    meta::MetadataProvider,
+    scale::{Context, Pen},
+    GlyphId, NormalizedCoord, Size,
+};
+use core::ffi::c_void;
+use read_fonts::FontRef;
+
+/// Opaque font object.
+pub struct FelloFont {
+    data: Box<[u8]>,
+    index: u32,
+    context: Context,
+}
+
+impl FelloFont {
+    fn font_ref(&self) -> Option<FontRef> {
+        FontRef::from_index(&self.data, self.index).ok()
+    }
+}
+
+/// Global metrics for a font.
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct FelloMetrics {
+    /// Number of font design units per em unit.
+    pub units_per_em: u16,
+    /// Number of glyphs in the font.
+    pub glyph_count: u16,
+    /// True if the font is not proportionally spaced.
+    pub is_monospace: bool,
+    /// Italic angle in counter-clockwise degrees from the vertical.
+    pub italic_angle: f32,
+    /// Distance from the baseline to the top of the alignment box.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the alignment box.
+    pub descent: f32,
+    /// Recommended additional spacing between lines.
+    pub leading: f32,
+}
+
+/// Variation axis of a font.
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug)]
+pub struct FelloAxis {
+    /// Tag identifying the axis as a big endian 32-bit integer.
+    pub tag: u32,
+    /// Minimum value of the axis.
+    pub min_value: f32,
+    /// Default value of the axis.
+    pub default_value: f32,
+    /// Maximum value of the axis.
+    pub max_value: f32,
+}
+
+/// Set of callbacks for receiving glyph outlines.
+///
+/// Any callback may be null, in which case the corresponding command is
+/// ignored. The `context` pointer is passed as the first argument to each
+/// callback.
+#[repr(C)]
+pub struct FelloPen {
+    /// User data passed to each callback.
+    pub context: *mut c_void,
+    /// Begins a new contour at the given point.
+    pub move_to: Option<extern "C" fn(*mut c_void, f32, f32)>,
+    /// Adds a line to the given point.
+    pub line_to: Option<extern "C" fn(*mut c_void, f32, f32)>,
+    /// Adds a quadratic curve with the given control and end points.
+    pub quad_to: Option<extern "C" fn(*mut c_void, f32, f32, f32, f32)>,
+    /// Adds a cubic curve with the given control and end points.
+    pub curve_to: Option<extern "C" fn(*mut c_void, f32, f32, f32, f32, f32, f32)>,
+    /// Closes the current contour.
+    pub close: Option<extern "C" fn(*mut c_void)>,
+}
+
+impl Pen for &FelloPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        if let Some(f) = self.move_to {
+            f(self.context, x, y);
+        }
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        if let Some(f) = self.line_to {
+            f(self.context, x, y);
+        }
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        if let Some(f) = self.quad_to {
+            f(self.context, cx0, cy0, x, y);
+        }
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        if let Some(f) = self.curve_to {
+            f(self.context, cx0, cy0, cx1, cy1, x, y);
+        }
+    }
+
+    fn close(&mut self) {
+        if let Some(f) = self.close {
+            f(self.context);
+        }
+    }
+}
+
+/// Creates a new font object from a copy of the given data.
+///
+/// Returns null if the data does not contain a valid font at the given
+/// index. The result must be released with [`fello_font_free`].
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_new(data: *const u8, len: usize, index: u32) -> *mut FelloFont {
+    if data.is_null() {
+        return core::ptr::null_mut();
+    }
+    let data: Box<[u8]> = core::slice::from_raw_parts(data, len).into();
+    if FontRef::from_index(&data, index).is_err() {
+        return core::ptr::null_mut();
+    }
+    Box::into_raw(Box::new(FelloFont {
+        data,
+        index,
+        context: Context::new(),
+    }))
+}
+
+/// Releases a font object.
+///
+/// # Safety
+///
+/// `font` must be null or a pointer returned by [`fello_font_new`] that has
+/// not yet been freed.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_free(font: *mut FelloFont) {
+    if !font.is_null() {
+        drop(Box::from_raw(font));
+    }
+}
+
+/// Retrieves the global metrics for the font at the given size in pixels
+/// per em and normalized variation coordinates.
+///
+/// A size of zero produces metrics in font units. Returns false on failure.
+///
+/// # Safety
+///
+/// `font` must be a valid font object, `coords` must be null or point to
+/// `coord_count` values and `metrics` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_metrics(
+    font: *const FelloFont,
+    size: f32,
+    coords: *const f32,
+    coord_count: usize,
+    metrics: *mut FelloMetrics,
+) -> bool {
+    let (Some(font), false) = (font.as_ref(), metrics.is_null()) else {
+        return false;
+    };
+    let Some(font_ref) = font.font_ref() else {
+        return false;
+    };
+    let coords = normalized_coords(coords, coord_count);
+    let m = font_ref.metrics(Size::new(size), coords.as_slice().into());
+    *metrics = FelloMetrics {
+        units_per_em: m.units_per_em,
+        glyph_count: m.glyph_count,
+        is_monospace: m.is_monospace,
+        italic_angle: m.italic_angle,
+        ascent: m.ascent,
+        descent: m.descent,
+        leading: m.leading,
+    };
+    true
+}
+
+/// Returns the number of variation axes in the font.
+///
+/// # Safety
+///
+/// `font` must be a valid font object.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_axis_count(font: *const FelloFont) -> u32 {
+    font.as_ref()
+        .and_then(|font| font.font_ref())
+        .map(|font_ref| font_ref.axes().len() as u32)
+        .unwrap_or_default()
+}
+
+/// Retrieves the variation axis at the given index.
+///
+/// Returns false if the index is out of bounds.
+///
+/// # Safety
+///
+/// `font` must be a valid font object and `axis` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_axis(
+    font: *const FelloFont,
+    index: u32,
+    axis: *mut FelloAxis,
+) -> bool {
+    let (Some(font), false) = (font.as_ref(), axis.is_null()) else {
+        return false;
+    };
+    let Some(a) = font
+        .font_ref()
+        .and_then(|font_ref| font_ref.axes().get(index as usize))
+    else {
+        return false;
+    };
+    *axis = FelloAxis {
+        tag: u32::from_be_bytes(a.tag().to_be_bytes()),
+        min_value: a.min_value(),
+        default_value: a.default_value(),
+        max_value: a.max_value(),
+    };
+    true
+}
+
+/// Maps a Unicode codepoint to a nominal glyph identifier.
+///
+/// Returns 0 (the `.notdef` glyph) if the codepoint is not mapped.
+///
+/// # Safety
+///
+/// `font` must be a valid font object.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_map_char(font: *const FelloFont, codepoint: u32) -> u16 {
+    font.as_ref()
+        .and_then(|font| font.font_ref())
+        .and_then(|font_ref| font_ref.charmap().map(codepoint))
+        .map(|glyph_id| glyph_id.to_u16())
+        .unwrap_or_default()
+}
+
+/// Returns the advance width of a glyph at the given size in pixels per em
+/// and normalized variation coordinates.
+///
+/// A size of zero produces an advance in font units. Returns 0 if the glyph
+/// does not exist.
+///
+/// # Safety
+///
+/// `font` must be a valid font object and `coords` must be null or point
+/// to `coord_count` values.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_advance_width(
+    font: *const FelloFont,
+    glyph_id: u16,
+    size: f32,
+    coords: *const f32,
+    coord_count: usize,
+) -> f32 {
+    let Some(font_ref) = font.as_ref().and_then(|font| font.font_ref()) else {
+        return 0.0;
+    };
+    let coords = normalized_coords(coords, coord_count);
+    font_ref
+        .glyph_metrics(Size::new(size), coords.as_slice().into())
+        .advance_width(GlyphId::new(glyph_id))
+        .unwrap_or_default()
+}
+
+/// Loads the outline for a glyph at the given size in pixels per em and
+/// normalized variation coordinates and emits it to the pen.
+///
+/// A size of zero produces an outline in font units. Returns false if the
+/// outline could not be loaded.
+///
+/// # Safety
+///
+/// `font` must be a valid font object, `coords` must be null or point to
+/// `coord_count` values and `pen` must be a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn fello_font_outline(
+    font: *mut FelloFont,
+    glyph_id: u16,
+    size: f32,
+    coords: *const f32,
+    coord_count: usize,
+    pen: *const FelloPen,
+) -> bool {
+    let (Some(font), Some(mut pen)) = (font.as_mut(), pen.as_ref()) else {
+        return false;
+    };
+    let Ok(font_ref) = FontRef::from_index(&font.data, font.index) else {
+        return false;
+    };
+    let coords = normalized_coords(coords, coord_count);
+    let mut scaler = font
+        .context
+        .new_scaler()
+        .size(Size::new(size))
+        .coords(&coords)
+        .build(&font_ref);
+    scaler.outline(GlyphId::new(glyph_id), &mut pen).is_ok()
+}
+
+/// Converts an array of floating point normalized coordinates.
+///
+/// # Safety
+///
+/// `coords` must be null or point to `count` values.
+unsafe fn normalized_coords(coords: *const f32, count: usize) -> Vec<NormalizedCoord> {
+    if coords.is_null() {
+        return Vec::new();
+    }
+    core::slice::from_raw_parts(coords, count)
+        .iter()
+        .map(|coord| NormalizedCoord::from_f32(*coord))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{FontBuilder, SQUARE};
+    use core::ptr::{null, null_mut};
+    use read_fonts::types::Tag;
+
+    fn build_font() -> Vec<u8> {
+        let mut builder = FontBuilder::new(1000);
+        builder.vertical_metrics(800, -200, 0);
+        let glyph_id = builder.add_glyph(600, SQUARE);
+        builder.map('A', glyph_id);
+        builder.add_axis(Tag::new(b"wght"), 100.0, 400.0, 900.0);
+        builder.build()
+    }
+
+    #[derive(Clone, PartialEq, Debug)]
+    enum Command {
+        MoveTo(f32, f32),
+        LineTo(f32, f32),
+        Close,
+    }
+
+    extern "C" fn move_to(context: *mut c_void, x: f32, y: f32) {
+        let commands = unsafe { &mut *(context as *mut Vec<Command>) };
+        commands.push(Command::MoveTo(x, y));
+    }
+
+    extern "C" fn line_to(context: *mut c_void, x: f32, y: f32) {
+        let commands = unsafe { &mut *(context as *mut Vec<Command>) };
+        commands.push(Command::LineTo(x, y));
+    }
+
+    extern "C" fn close(context: *mut c_void) {
+        let commands = unsafe { &mut *(context as *mut Vec<Command>) };
+        commands.push(Command::Close);
+    }
+
+    /// Loads an outline through a pen without curve callbacks.
+    unsafe fn outline(font: *mut FelloFont, glyph_id: u16, size: f32) -> Option<Vec<Command>> {
+        let mut commands = Vec::new();
+        let pen = FelloPen {
+            context: &mut commands as *mut Vec<Command> as *mut c_void,
+            move_to: Some(move_to),
+            line_to: Some(line_to),
+            quad_to: None,
+            curve_to: None,
+            close: Some(close),
+        };
+        fello_font_outline(font, glyph_id, size, null(), 0, &pen).then_some(commands)
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let data = build_font();
+        let garbage = [0xFFu8; 64];
+        unsafe {
+            assert!(fello_font_new(null(), 16, 0).is_null());
+            assert!(fello_font_new(garbage.as_ptr(), garbage.len(), 0).is_null());
+            assert!(fello_font_new(data.as_ptr(), 8, 0).is_null());
+            assert!(fello_font_new(data.as_ptr(), data.len(), 1).is_null());
+            fello_font_free(null_mut());
+            let mut metrics = FelloMetrics::default();
+            let mut axis = FelloAxis::default();
+            assert!(!fello_font_metrics(null(), 0.0, null(), 0, &mut metrics));
+            assert_eq!(fello_font_axis_count(null()), 0);
+            assert!(!fello_font_axis(null(), 0, &mut axis));
+            assert_eq!(fello_font_map_char(null(), 'A' as u32), 0);
+            assert_eq!(fello_font_advance_width(null(), 1, 0.0, null(), 0), 0.0);
+            assert!(outline(null_mut(), 1, 0.0).is_none());
+            let font = fello_font_new(data.as_ptr(), data.len(), 0);
+            assert!(!font.is_null());
+            assert!(!fello_font_metrics(font, 0.0, null(), 0, null_mut()));
+            assert!(!fello_font_axis(font, 0, null_mut()));
+            assert!(!fello_font_outline(font, 1, 0.0, null(), 0, null()));
+            fello_font_free(font);
+        }
+    }
+
+    #[test]
+    fn font_queries() {
+        let data = build_font();
+        unsafe {
+            let font = fello_font_new(data.as_ptr(), data.len(), 0);
+            assert!(!font.is_null());
+            // The font object owns a copy of the data.
+            drop(data);
+            let mut metrics = FelloMetrics::default();
+            assert!(fello_font_metrics(font, 0.0, null(), 0, &mut metrics));
+            assert_eq!(metrics.units_per_em, 1000);
+            assert_eq!(metrics.glyph_count, 2);
+            assert_eq!((metrics.ascent, metrics.descent), (800.0, -200.0));
+            assert!(fello_font_metrics(font, 2000.0, null(), 0, &mut metrics));
+            assert_eq!(metrics.ascent, 1600.0);
+            assert_eq!(fello_font_axis_count(font), 1);
+            let mut axis = FelloAxis::default();
+            assert!(fello_font_axis(font, 0, &mut axis));
+            assert_eq!(axis.tag, u32::from_be_bytes(*b"wght"));
+            assert_eq!(
+                (axis.min_value, axis.default_value, axis.max_value),
+                (100.0, 400.0, 900.0)
+            );
+            assert!(!fello_font_axis(font, 1, &mut axis));
+            assert_eq!(fello_font_map_char(font, 'A' as u32), 1);
+            assert_eq!(fello_font_map_char(font, 'B' as u32), 0);
+            assert_eq!(fello_font_advance_width(font, 1, 0.0, null(), 0), 600.0);
+            assert_eq!(fello_font_advance_width(font, 1, 2000.0, null(), 0), 1200.0);
+            let coords = [0.5f32];
+            assert_eq!(
+                fello_font_advance_width(font, 1, 0.0, coords.as_ptr(), coords.len()),
+                600.0
+            );
+            assert_eq!(fello_font_advance_width(font, 7, 0.0, null(), 0), 0.0);
+            fello_font_free(font);
+        }
+    }
+
+    #[test]
+    fn outlines() {
+        let data = build_font();
+        unsafe {
+            let font = fello_font_new(data.as_ptr(), data.len(), 0);
+            let unscaled = outline(font, 1, 0.0).unwrap();
+            assert_eq!(unscaled.first(), Some(&Command::MoveTo(0.0, 0.0)));
+            assert_eq!(unscaled.last(), Some(&Command::Close));
+            assert!(unscaled.contains(&Command::LineTo(500.0, 500.0)));
+            let scaled = outline(font, 1, 2000.0).unwrap();
+            let doubled = unscaled
+                .iter()
+                .map(|command| match command {
+                    Command::MoveTo(x, y) => Command::MoveTo(x * 2.0, y * 2.0),
+                    Command::LineTo(x, y) => Command::LineTo(x * 2.0, y * 2.0),
+                    Command::Close => Command::Close,
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(scaled, doubled);
+            // The empty notdef glyph succeeds without any commands.
+            assert_eq!(outline(font, 0, 0.0), Some(vec![]));
+            assert!(outline(font, 7, 0.0).is_none());
+            fello_font_free(font);
+        }
+    }
+
+    #[test]
+    fn header_declares_functions() {
+        let header = include_str!("../include/fello.h");
+        for name in [
+            "fello_font_new",
+            "fello_font_free",
+            "fello_font_metrics",
+            "fello_font_axis_count",
+            "fello_font_axis",
+            "fello_font_map_char",
+            "fello_font_advance_width",
+            "fello_font_outline",
+            "struct FelloMetrics",
+            "struct FelloAxis",
+            "struct FelloPen",
+        ] {
+            assert!(header.contains(name), "missing {name} in fello.h");
+        }
+    }
+}
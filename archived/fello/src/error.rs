@@ -0,0 +1,136 @@
+//! Crate wide error type.
+
+use read_fonts::{
+    types::{GlyphId, Tag},
+    ReadError,
+};
+
+use core::fmt;
+
+/// Errors that may occur when loading, scaling or subsetting fonts.
+///
+/// The metadata APIs in [`meta`](crate::meta) don't use this type; they
+/// return `Option` or fall back to default values when the font data is
+/// missing or malformed.
+///
+/// The modules of this crate that report detailed errors, such as
+/// [`scale`](crate::scale) and [`subset`](crate::subset), define their own
+/// error types which convert into this one with the `?` operator. Errors
+/// that fall into one of the general categories below are mapped to that
+/// category and all others are preserved in the variant for their module.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A table required for the operation was not present in the font.
+    TableMissing(Tag),
+    /// Error occurred when reading font data.
+    Read(ReadError),
+    /// The font uses a format or outline type that is not supported for
+    /// the operation.
+    UnsupportedFormat,
+    /// The glyph identifier is not valid for the font.
+    GlyphNotFound(GlyphId),
+    /// Processing exceeded a limit, such as the maximum depth of nested
+    /// composite glyphs.
+    BudgetExceeded,
+    /// Error occurred when scaling a glyph.
+    #[cfg(feature = "scale")]
+    Scale(crate::scale::Error),
+    /// Error occurred when subsetting a font.
+    Subset(crate::subset::Error),
+}
+
+impl From<ReadError> for Error {
+    fn from(e: ReadError) -> Self {
+        match e {
+            ReadError::TableIsMissing(tag) => Self::TableMissing(tag),
+            e => Self::Read(e),
+        }
+    }
+}
+
+#[cfg(feature = "scale")]
+impl From<crate::scale::Error> for Error {
+    fn from(e: crate::scale::Error) -> Self {
+        use crate::scale::Error as ScaleError;
+        match e {
+            ScaleError::NoSources => Self::UnsupportedFormat,
+            ScaleError::GlyphNotFound(gid) => Self::GlyphNotFound(gid),
+            ScaleError::RecursionLimitExceeded(_) => Self::BudgetExceeded,
+            ScaleError::Read(e) => e.into(),
+            e => Self::Scale(e),
+        }
+    }
+}
+
+impl From<crate::subset::Error> for Error {
+    fn from(e: crate::subset::Error) -> Self {
+        use crate::subset::Error as SubsetError;
+        match e {
+            SubsetError::UnsupportedOutlines => Self::UnsupportedFormat,
+            SubsetError::Read(e) => e.into(),
+            e => Self::Subset(e),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TableMissing(tag) => write!(f, "The {tag} table is missing from the font"),
+            Self::Read(e) => write!(f, "{e}"),
+            Self::UnsupportedFormat => write!(f, "The font uses an unsupported format"),
+            Self::GlyphNotFound(gid) => write!(f, "Glyph {gid} was not found in the font"),
+            Self::BudgetExceeded => write!(f, "A processing limit was exceeded"),
+            #[cfg(feature = "scale")]
+            Self::Scale(e) => write!(f, "{e}"),
+            Self::Subset(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Result type for errors that may occur when using this crate.
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{subset, SharedFontRef};
+
+    #[test]
+    fn categorize_module_errors() {
+        let tag = Tag::new(b"glyf");
+        assert!(matches!(
+            Error::from(ReadError::TableIsMissing(tag)),
+            Error::TableMissing(t) if t == tag
+        ));
+        assert!(matches!(
+            Error::from(subset::Error::Read(ReadError::TableIsMissing(tag))),
+            Error::TableMissing(t) if t == tag
+        ));
+        assert!(matches!(
+            Error::from(subset::Error::UnsupportedOutlines),
+            Error::UnsupportedFormat
+        ));
+        assert!(matches!(
+            Error::from(subset::Error::InvalidGlyph(GlyphId::new(1))),
+            Error::Subset(subset::Error::InvalidGlyph(_))
+        ));
+        #[cfg(feature = "scale")]
+        assert!(matches!(
+            Error::from(crate::scale::Error::RecursionLimitExceeded(GlyphId::new(1))),
+            Error::BudgetExceeded
+        ));
+    }
+
+    #[test]
+    fn invalid_font_data() {
+        assert!(matches!(
+            SharedFontRef::new(&b"not a font"[..], 0),
+            Err(Error::Read(_))
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+//! Fonts with shared ownership of their data.
+
+use alloc::{sync::Arc, vec::Vec};
+use read_fonts::{types::Tag, FontData, FontRef, TableProvider};
+
+use crate::Result;
+
+/// Font that shares ownership of its data.
+///
+/// This pairs reference counted font data with the index of a font in a
+/// collection so that fonts can be stored and cached without tracking the
+/// lifetime of the data. The font is validated and its table directory is
+/// read on construction, so table lookups don't parse the font again.
+///
+/// A reference to this type implements [`TableProvider`], and therefore
+/// [`MetadataProvider`](crate::MetadataProvider), so it can be used wherever
+/// a [`FontRef`] is accepted.
+#[derive(Clone)]
+pub struct SharedFontRef {
+    data: Arc<[u8]>,
+    index: u32,
+    /// Tag, offset and length of each table, sorted by tag.
+    tables: Arc<[(Tag, usize, usize)]>,
+}
+
+impl SharedFontRef {
+    /// Creates a new font from the given data and the index of the font in
+    /// a collection.
+    ///
+    /// The index is ignored if the data contains a single font.
+    pub fn new(data: impl Into<Arc<[u8]>>, index: u32) -> Result<Self> {
+        let data = data.into();
+        let font = FontRef::from_index(&data, index)?;
+        let mut tables: Vec<_> = font
+            .table_directory
+            .table_records()
+            .iter()
+            .map(|record| {
+                (
+                    record.tag(),
+                    record.offset() as usize,
+                    record.length() as usize,
+                )
+            })
+            .collect();
+        tables.sort_unstable_by_key(|(tag, ..)| *tag);
+        Ok(Self {
+            data,
+            index,
+            tables: tables.into(),
+        })
+    }
+
+    /// Returns the underlying font data.
+    pub fn data(&self) -> &Arc<[u8]> {
+        &self.data
+    }
+
+    /// Returns the index of the font in a collection.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns a font reference that borrows the shared data, for the
+    /// functions that need the table directory.
+    ///
+    /// This parses the font header again.
+    pub fn font_ref(&self) -> Result<FontRef> {
+        Ok(FontRef::from_index(&self.data, self.index)?)
+    }
+
+    /// Returns the data for the table with the specified tag, if present.
+    pub fn table_data(&self, tag: Tag) -> Option<FontData> {
+        let index = self
+            .tables
+            .binary_search_by_key(&tag, |(tag, ..)| *tag)
+            .ok()?;
+        let (_, offset, len) = self.tables[index];
+        self.data
+            .get(offset..offset.checked_add(len)?)
+            .map(FontData::new)
+    }
+}
+
+impl<'a> TableProvider<'a> for &'a SharedFontRef {
+    fn data_for_tag(&self, tag: Tag) -> Option<FontData<'a>> {
+        (*self).table_data(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MetadataProvider, NormalizedCoords, Size};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn shared_matches_font_ref() {
+        let data: Vec<u8> = font_test_data::VAZIRMATN_VAR.into();
+        let shared = SharedFontRef::new(data, 0).unwrap();
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let size = Size::new(16.0);
+        let coords = NormalizedCoords::default();
+        assert_eq!((&shared).metrics(size, coords), font.metrics(size, coords));
+        assert_eq!((&shared).axes().len(), font.axes().len());
+        for record in font.table_directory.table_records() {
+            let tag = record.tag();
+            assert_eq!(
+                shared.table_data(tag).map(|data| data.as_bytes()),
+                font.data_for_tag(tag).map(|data| data.as_bytes())
+            );
+        }
+        assert!(shared.table_data(Tag::new(b"ZZZZ")).is_none());
+        // Clones share the same data.
+        let clone = shared.clone();
+        assert!(Arc::ptr_eq(shared.data(), clone.data()));
+    }
+
+    #[test]
+    fn invalid_data() {
+        assert!(SharedFontRef::new(&b"not a font"[..], 0).is_err());
+    }
+}
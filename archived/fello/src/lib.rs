@@ -1,20 +1,37 @@
-// #![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "capi"), forbid(unsafe_code))]
 // TODO: this is temporary-- remove when hinting is added.
 #![allow(dead_code, unused_imports, unused_variables)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 /// Expose our "raw" underlying parser crate.
 pub extern crate read_fonts as raw;
 
+mod error;
+mod location;
 mod setting;
+mod write;
 
+pub mod font;
 pub mod meta;
+pub mod subset;
 
 #[cfg(feature = "scale")]
 pub mod scale;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+
 /// Limit for recursion when loading TrueType composite glyphs.
 const GLYF_COMPOSITE_RECURSION_LIMIT: usize = 32;
 
+pub use error::{Error, Result};
+pub use font::SharedFontRef;
+pub use location::Location;
 pub use setting::Setting;
 
 /// Type for a normalized variation coordinate.
@@ -83,9 +100,15 @@ impl<'a> IntoIterator for &'_ NormalizedCoords<'a> {
 /// in CSS (assuming a DPI scale factor of 1.0).
 ///
 /// To retrieve metrics and outlines in font units, use the [unscaled](Self::unscaled)
-/// construtor on this type.
+/// constructor on this type. Callers that compute their own scale factor can
+/// use the [fixed_scale](Self::fixed_scale) constructor to bypass the units
+/// per em value of the font.
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
-pub struct Size(f32);
+pub struct Size {
+    value: f32,
+    /// True if the value is a scale factor rather than pixels per em.
+    is_fixed_scale: bool,
+}
 
 impl Size {
     /// Creates a new font size from the given value in pixels per em units.
@@ -93,30 +116,63 @@ impl Size {
     /// Providing a value `<= 0.0` is equivalent to creating an unscaled size
     /// and will result in metrics and outlines generated in font units.
     pub fn new(ppem: f32) -> Self {
-        Self(ppem)
+        Self {
+            value: ppem,
+            is_fixed_scale: false,
+        }
     }
 
     /// Creates a new font size for generating unscaled metrics or outlines in
     /// font units.
     pub fn unscaled() -> Self {
-        Self(0.0)
+        Self::new(0.0)
+    }
+
+    /// Creates a new font size from a linear scale factor that converts font
+    /// units to pixels, regardless of the units per em value of the font.
+    ///
+    /// Providing a value `<= 0.0` is equivalent to creating an unscaled size.
+    pub fn fixed_scale(scale: f32) -> Self {
+        Self {
+            value: scale,
+            is_fixed_scale: true,
+        }
     }
 
     /// Returns the raw size in pixels per em units.
     ///
-    /// Results in `None` if the size is unscaled.
+    /// Results in `None` if the size is unscaled or was created from a
+    /// [fixed scale](Self::fixed_scale). Use [`ppem_for`](Self::ppem_for) for
+    /// the latter.
     pub fn ppem(self) -> Option<f32> {
-        (self.0 > 0.0).then_some(self.0)
+        (self.value > 0.0 && !self.is_fixed_scale).then_some(self.value)
+    }
+
+    /// Returns the size in pixels per em units for a font with the given
+    /// units per em value.
+    ///
+    /// Results in `None` if the size is unscaled.
+    pub fn ppem_for(self, units_per_em: u16) -> Option<f32> {
+        if self.is_fixed_scale {
+            (self.value > 0.0).then_some(self.value * units_per_em as f32)
+        } else {
+            self.ppem()
+        }
     }
 
     /// Computes a linear scale factor for this font size and the given units
     /// per em value which can be retrieved from the [Metrics](crate::meta::metrics::Metrics)
     /// type or from the [head](read_fonts::tables::head::Head) table.
     ///
-    /// Returns 1.0 for an unscaled size or when `units_per_em` is 0.
+    /// Returns 1.0 for an unscaled size or when `units_per_em` is 0. A
+    /// [fixed scale](Self::fixed_scale) is returned as is.
     pub fn linear_scale(self, units_per_em: u16) -> f32 {
-        if self.0 > 0.0 && units_per_em != 0 {
-            self.0 / units_per_em as f32
+        if self.value <= 0.0 {
+            1.0
+        } else if self.is_fixed_scale {
+            self.value
+        } else if units_per_em != 0 {
+            self.value / units_per_em as f32
         } else {
             1.0
         }
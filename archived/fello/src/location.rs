@@ -0,0 +1,191 @@
+/*! Owned positions in the variation space of a font.
+
+*/
+
+use alloc::vec::Vec;
+
+use crate::{NormalizedCoord, NormalizedCoords};
+
+/// Position in the variation space of a font that owns its normalized
+/// coordinates.
+///
+/// This is the owned counterpart of [`NormalizedCoords`] and follows the
+/// same conventions: each coordinate corresponds to an axis in the order of
+/// the `fvar` table and missing coordinates are at the default position.
+///
+/// A location can be collected from the result of
+/// [`Axes::normalize`](crate::meta::variations::axis::Axes::normalize) and
+/// supports interpolation for applications that animate between variation
+/// states.
+#[derive(Clone, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Location {
+    coords: Vec<NormalizedCoord>,
+}
+
+impl Location {
+    /// Creates a new location with the given number of axes, all at their
+    /// default positions.
+    pub fn new(axis_count: usize) -> Self {
+        Self {
+            coords: alloc::vec![NormalizedCoord::default(); axis_count],
+        }
+    }
+
+    /// Returns the normalized coordinates of the location.
+    pub fn coords(&self) -> &[NormalizedCoord] {
+        &self.coords
+    }
+
+    /// Returns the normalized coordinates of the location for modification.
+    pub fn coords_mut(&mut self) -> &mut [NormalizedCoord] {
+        &mut self.coords
+    }
+
+    /// Returns the location as a borrowed sequence of normalized
+    /// coordinates.
+    pub fn normalized_coords(&self) -> NormalizedCoords {
+        NormalizedCoords::new(&self.coords)
+    }
+
+    /// Returns true if all coordinates are at the default position.
+    pub fn is_default(&self) -> bool {
+        self.coords
+            .iter()
+            .all(|coord| *coord == NormalizedCoord::ZERO)
+    }
+
+    /// Returns the location that is linearly interpolated between this
+    /// location and `other` by the factor `t`.
+    ///
+    /// A factor of 0.0 produces this location and 1.0 produces `other`.
+    /// Factors outside of that range extrapolate, which is useful for
+    /// easing functions that overshoot, and the resulting coordinates are
+    /// clamped to the normalized range. The result has as many coordinates
+    /// as the longer of the two locations.
+    pub fn lerp(&self, other: &Location, t: f32) -> Location {
+        let len = self.coords.len().max(other.coords.len());
+        let coord = |coords: &[NormalizedCoord], i: usize| {
+            coords.get(i).copied().unwrap_or_default().to_f32()
+        };
+        let coords = (0..len)
+            .map(|i| {
+                let start = coord(&self.coords, i);
+                let end = coord(&other.coords, i);
+                to_normalized(start + (end - start) * t)
+            })
+            .collect();
+        Self { coords }
+    }
+
+    /// Clamps each coordinate to the range given by the corresponding
+    /// coordinates of `min` and `max`.
+    ///
+    /// Coordinates of axes beyond the length of a bound are left unbounded
+    /// on that side, so axes missing from both bounds are left unchanged as
+    /// with [`clamp_to_ranges`](Self::clamp_to_ranges).
+    pub fn clamp(&mut self, min: &Location, max: &Location) {
+        for (i, coord) in self.coords.iter_mut().enumerate() {
+            if let Some(min) = min.coords.get(i) {
+                *coord = (*coord).max(*min);
+            }
+            if let Some(max) = max.coords.get(i) {
+                *coord = (*coord).min(*max);
+            }
+        }
+    }
+
+    /// Clamps each coordinate to the range of the corresponding pair of
+    /// normalized coordinates in `ranges`.
+    ///
+    /// Coordinates without a range are left unchanged. This is useful for
+    /// limiting a location to a subset of the design space, such as the
+    /// range of weights that a user interface allows.
+    pub fn clamp_to_ranges(&mut self, ranges: &[(NormalizedCoord, NormalizedCoord)]) {
+        for (coord, (min, max)) in self.coords.iter_mut().zip(ranges) {
+            *coord = (*coord).max(*min).min(*max);
+        }
+    }
+}
+
+/// Converts a value to a normalized coordinate, clamping to `[-1..1]`.
+fn to_normalized(value: f32) -> NormalizedCoord {
+    NormalizedCoord::from_f32(value.clamp(-1.0, 1.0))
+}
+
+impl From<&[NormalizedCoord]> for Location {
+    fn from(value: &[NormalizedCoord]) -> Self {
+        Self {
+            coords: value.to_vec(),
+        }
+    }
+}
+
+impl From<Vec<NormalizedCoord>> for Location {
+    fn from(value: Vec<NormalizedCoord>) -> Self {
+        Self { coords: value }
+    }
+}
+
+impl<'a> From<NormalizedCoords<'a>> for Location {
+    fn from(value: NormalizedCoords<'a>) -> Self {
+        value.inner().into()
+    }
+}
+
+impl FromIterator<NormalizedCoord> for Location {
+    fn from_iter<T: IntoIterator<Item = NormalizedCoord>>(iter: T) -> Self {
+        Self {
+            coords: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a Location> for NormalizedCoords<'a> {
+    fn from(value: &'a Location) -> Self {
+        value.normalized_coords()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(values: &[f32]) -> Location {
+        values
+            .iter()
+            .map(|value| NormalizedCoord::from_f32(*value))
+            .collect()
+    }
+
+    #[test]
+    fn lerp() {
+        let a = location(&[0.0, -1.0]);
+        let b = location(&[1.0, 0.0, 0.5]);
+        assert_eq!(a.lerp(&b, 0.0), location(&[0.0, -1.0, 0.0]));
+        assert_eq!(a.lerp(&b, 1.0), b);
+        assert_eq!(a.lerp(&b, 0.5), location(&[0.5, -0.5, 0.25]));
+        // Extrapolation is clamped to the normalized range.
+        assert_eq!(a.lerp(&b, 2.0), location(&[1.0, 1.0, 1.0]));
+        assert_eq!(a.lerp(&b, -1.0), location(&[-1.0, -1.0, -0.5]));
+    }
+
+    #[test]
+    fn clamp() {
+        let mut a = location(&[0.75, -0.75, 0.5]);
+        a.clamp(&location(&[-0.5, -0.5]), &location(&[0.5, 0.5]));
+        assert_eq!(a, location(&[0.5, -0.5, 0.5]));
+        // Only the lower bound applies to the second axis.
+        let mut c = location(&[0.75, 0.75]);
+        c.clamp(&location(&[0.0, 0.0]), &location(&[0.5]));
+        assert_eq!(c, location(&[0.5, 0.75]));
+        let mut b = location(&[0.75, -0.75, 0.5]);
+        let range = (
+            NormalizedCoord::from_f32(0.0),
+            NormalizedCoord::from_f32(0.25),
+        );
+        b.clamp_to_ranges(&[range, range]);
+        assert_eq!(b, location(&[0.25, 0.0, 0.5]));
+        assert!(!b.is_default());
+        assert!(Location::new(2).is_default());
+    }
+}
@@ -0,0 +1,394 @@
+//! Apple Advanced Typography positioning data.
+//!
+//! Fonts shaped with AAT, such as many macOS system fonts, may store their
+//! kerning in the [`kerx`](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6kerx.html)
+//! table and its attachment points in the
+//! [`ankr`](https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6ankr.html)
+//! table rather than in `GPOS`. [`Kerning`] and [`AatAnchors`] expose these
+//! tables so that such fonts still provide kerning data to consumers.
+//!
+//! Values are in font units.
+
+use read_fonts::{
+    types::{GlyphId, Tag},
+    FontData, TableProvider,
+};
+
+/// Kerning subtables of the `kerx` table.
+#[derive(Clone)]
+pub struct Kerning<'a> {
+    data: FontData<'a>,
+}
+
+impl<'a> Kerning<'a> {
+    /// Creates kerning for the given font.
+    ///
+    /// The result is empty if the font lacks a `kerx` table.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let data = font
+            .data_for_tag(Tag::new(b"kerx"))
+            .filter(|data| matches!(data.read_at::<u16>(0), Ok(2..=4)))
+            .unwrap_or_default();
+        Self { data }
+    }
+
+    /// Returns true if the font has no kerning subtables.
+    pub fn is_empty(&self) -> bool {
+        self.subtables().next().is_none()
+    }
+
+    /// Returns an iterator over the kerning subtables.
+    pub fn subtables(&self) -> impl Iterator<Item = KerningSubtable<'a>> + 'a + Clone {
+        let data = self.data;
+        let count = data.read_at::<u32>(4).unwrap_or_default();
+        let mut offset = 8;
+        (0..count).map_while(move |_| {
+            let len = data.read_at::<u32>(offset).ok()? as usize;
+            let subtable = data.slice(offset..offset.checked_add(len)?)?;
+            if len < KerningSubtable::HEADER_LEN {
+                return None;
+            }
+            offset += len;
+            Some(KerningSubtable { data: subtable })
+        })
+    }
+
+    /// Returns the sum of the horizontal kerning adjustments for the given
+    /// pair of glyphs.
+    ///
+    /// Only pair kerning subtables that are neither vertical, cross-stream
+    /// nor variable contribute. Returns `None` if no such subtable contains
+    /// the pair.
+    pub fn pair(&self, left: GlyphId, right: GlyphId) -> Option<i32> {
+        self.subtables()
+            .filter(|subtable| {
+                !subtable.is_vertical() && !subtable.is_cross_stream() && !subtable.is_variable()
+            })
+            .filter_map(|subtable| subtable.pair(left, right))
+            .fold(None, |sum, value| Some(sum.unwrap_or(0) + value as i32))
+    }
+}
+
+/// Subtable of the `kerx` table.
+#[derive(Copy, Clone)]
+pub struct KerningSubtable<'a> {
+    data: FontData<'a>,
+}
+
+impl<'a> KerningSubtable<'a> {
+    /// Length of the subtable header containing the length, coverage and
+    /// tuple count.
+    const HEADER_LEN: usize = 12;
+    const VERTICAL: u32 = 0x80000000;
+    const CROSS_STREAM: u32 = 0x40000000;
+    const VARIATION: u32 = 0x20000000;
+
+    fn coverage(&self) -> u32 {
+        self.data.read_at::<u32>(4).unwrap_or_default()
+    }
+
+    /// Returns the format of the subtable.
+    ///
+    /// Formats 0 and 2 contain pair kerning while formats 1, 4 and 6 use
+    /// state machines, control points or anchors.
+    pub fn format(&self) -> u8 {
+        self.coverage() as u8
+    }
+
+    /// Returns true if the subtable contains kerning for vertical text.
+    pub fn is_vertical(&self) -> bool {
+        self.coverage() & Self::VERTICAL != 0
+    }
+
+    /// Returns true if the subtable adjusts glyphs perpendicular to the
+    /// direction of the text.
+    pub fn is_cross_stream(&self) -> bool {
+        self.coverage() & Self::CROSS_STREAM != 0
+    }
+
+    /// Returns true if the subtable contains kerning values for variations.
+    pub fn is_variable(&self) -> bool {
+        self.coverage() & Self::VARIATION != 0
+    }
+
+    /// Returns the number of tuples for variation kerning values.
+    pub fn tuple_count(&self) -> u32 {
+        self.data.read_at::<u32>(8).unwrap_or_default()
+    }
+
+    /// Returns the kerning adjustment for the given pair of glyphs.
+    ///
+    /// Returns `None` if the pair is not kerned or the subtable is not a
+    /// pair kerning subtable of format 0 or 2.
+    pub fn pair(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        let (left, right) = (left.to_u16(), right.to_u16());
+        let data = self.data;
+        let value = match self.format() {
+            0 => {
+                // Pairs of left and right glyphs followed by the value,
+                // sorted by the glyph pair.
+                const PAIR_LEN: usize = 6;
+                let count = data.read_at::<u32>(Self::HEADER_LEN).ok()? as usize;
+                let pairs = data.split_off(Self::HEADER_LEN + 16)?;
+                let key = (left as u32) << 16 | right as u32;
+                let (mut lo, mut hi) = (0, count.min(pairs.len() / PAIR_LEN));
+                loop {
+                    if lo >= hi {
+                        return None;
+                    }
+                    let mid = (lo + hi) / 2;
+                    let pair = pairs.read_at::<u32>(mid * PAIR_LEN).ok()?;
+                    match pair.cmp(&key) {
+                        core::cmp::Ordering::Less => lo = mid + 1,
+                        core::cmp::Ordering::Greater => hi = mid,
+                        core::cmp::Ordering::Equal => {
+                            break pairs.read_at::<u16>(mid * PAIR_LEN + 4).ok()?
+                        }
+                    }
+                }
+            }
+            2 => {
+                // Class lookups produce row and column offsets into the
+                // array of values, counted in values.
+                let table = |position| {
+                    let offset = data.read_at::<u32>(position).ok()? as usize;
+                    data.split_off(offset)
+                };
+                let row = lookup_value(table(Self::HEADER_LEN + 4)?, left).unwrap_or(0);
+                let column = lookup_value(table(Self::HEADER_LEN + 8)?, right).unwrap_or(0);
+                let values = table(Self::HEADER_LEN + 12)?;
+                values
+                    .read_at::<u16>((row as usize + column as usize) * 2)
+                    .ok()?
+            }
+            _ => return None,
+        };
+        match value as i16 {
+            0 => None,
+            value => Some(value),
+        }
+    }
+}
+
+/// Anchor points from the `ankr` table.
+///
+/// These are referenced by index from kerning subtables of format 4.
+#[derive(Clone)]
+pub struct AatAnchors<'a> {
+    lookup: FontData<'a>,
+    glyph_data: FontData<'a>,
+}
+
+impl<'a> AatAnchors<'a> {
+    /// Creates anchors for the given font.
+    ///
+    /// The result is empty if the font lacks an `ankr` table.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let tables = font
+            .data_for_tag(Tag::new(b"ankr"))
+            .filter(|data| matches!(data.read_at::<u16>(0), Ok(0)))
+            .and_then(|data| {
+                let lookup = data.split_off(data.read_at::<u32>(4).ok()? as usize)?;
+                let glyph_data = data.split_off(data.read_at::<u32>(8).ok()? as usize)?;
+                Some((lookup, glyph_data))
+            });
+        let (lookup, glyph_data) = tables.unwrap_or_default();
+        Self { lookup, glyph_data }
+    }
+
+    /// Returns an iterator over the anchor points of the given glyph as
+    /// `(x, y)` pairs.
+    pub fn anchors(&self, glyph_id: GlyphId) -> impl Iterator<Item = (i16, i16)> + 'a + Clone {
+        let points = lookup_value(self.lookup, glyph_id.to_u16())
+            .and_then(|offset| self.glyph_data.split_off(offset as usize))
+            .unwrap_or_default();
+        let count = points.read_at::<u32>(0).unwrap_or_default();
+        (0..count as usize).map_while(move |i| {
+            let x = points.read_at::<u16>(4 + i * 4).ok()?;
+            let y = points.read_at::<u16>(6 + i * 4).ok()?;
+            Some((x as i16, y as i16))
+        })
+    }
+
+    /// Returns the anchor point of the given glyph with the given index.
+    pub fn get(&self, glyph_id: GlyphId, index: u16) -> Option<(i16, i16)> {
+        self.anchors(glyph_id).nth(index as usize)
+    }
+}
+
+/// Returns the 16-bit value for the glyph from an AAT lookup table.
+///
+/// See <https://developer.apple.com/fonts/TrueType-Reference-Manual/RM06/Chap6Tables.html>
+fn lookup_value(data: FontData, glyph_id: u16) -> Option<u16> {
+    // Binary search header following the format.
+    let unit_size = || data.read_at::<u16>(2).ok().map(usize::from);
+    let unit_count = || data.read_at::<u16>(4).ok().map(usize::from);
+    // Finds the index of the first unit with a last glyph not less than the
+    // glyph, ignoring the optional 0xFFFF terminator.
+    let search = |unit_size: usize, unit_count: usize| {
+        let (mut lo, mut hi) = (0, unit_count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if data.read_at::<u16>(12 + mid * unit_size).ok()? < glyph_id {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        (lo < unit_count).then_some(12 + lo * unit_size)
+    };
+    match data.read_at::<u16>(0).ok()? {
+        // Simple array indexed by glyph.
+        0 => data.read_at::<u16>(2 + glyph_id as usize * 2).ok(),
+        // Segments of last glyph, first glyph and a single value.
+        2 => {
+            let unit = search(unit_size()?, unit_count()?)?;
+            if data.read_at::<u16>(unit + 2).ok()? > glyph_id {
+                return None;
+            }
+            data.read_at::<u16>(unit + 4).ok()
+        }
+        // Segments of last glyph, first glyph and an offset to an array of
+        // values.
+        4 => {
+            let unit = search(unit_size()?, unit_count()?)?;
+            let first = data.read_at::<u16>(unit + 2).ok()?;
+            if first > glyph_id {
+                return None;
+            }
+            let values = data.read_at::<u16>(unit + 4).ok()? as usize;
+            data.read_at::<u16>(values + (glyph_id - first) as usize * 2)
+                .ok()
+        }
+        // Pairs of glyph and value.
+        6 => {
+            let unit = search(unit_size()?, unit_count()?)?;
+            if data.read_at::<u16>(unit).ok()? != glyph_id {
+                return None;
+            }
+            data.read_at::<u16>(unit + 2).ok()
+        }
+        // Trimmed array starting at the first glyph.
+        8 => {
+            let index = glyph_id.checked_sub(data.read_at::<u16>(2).ok()?)?;
+            if index >= data.read_at::<u16>(4).ok()? {
+                return None;
+            }
+            data.read_at::<u16>(6 + index as usize * 2).ok()
+        }
+        // Trimmed array with values of the given size.
+        10 => {
+            let value_size = data.read_at::<u16>(2).ok()? as usize;
+            let index = glyph_id.checked_sub(data.read_at::<u16>(4).ok()?)?;
+            if index >= data.read_at::<u16>(6).ok()? {
+                return None;
+            }
+            let offset = 8 + index as usize * value_size;
+            match value_size {
+                1 => data.read_at::<u8>(offset).ok().map(u16::from),
+                2 => data.read_at::<u16>(offset).ok(),
+                4 => data.read_at::<u32>(offset).ok().map(|value| value as u16),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{FontBuilder, SQUARE},
+        write::Writer,
+        MetadataProvider,
+    };
+    use read_fonts::FontRef;
+
+    #[test]
+    fn kerx_pairs() {
+        let mut builder = FontBuilder::new(1000);
+        let [a, b, c] = [0; 3].map(|_| builder.add_glyph(500, SQUARE).to_u16());
+        let mut kerx = Writer::default();
+        kerx.u16(2);
+        kerx.u16(0);
+        kerx.u32(2);
+        // Format 0 subtable with two pairs.
+        kerx.u32(12 + 16 + 2 * 6);
+        kerx.u32(0);
+        kerx.u32(0);
+        kerx.u32(2);
+        kerx.0.resize(kerx.0.len() + 12, 0);
+        for (left, right, value) in [(a, b, -50), (b, c, 20)] {
+            kerx.u16(left);
+            kerx.u16(right);
+            kerx.i16(value);
+        }
+        // Format 2 subtable with format 8 class lookups. Glyph c has row
+        // offset 2 and glyph a has column 1, selecting the last value.
+        let start = kerx.0.len();
+        kerx.u32(12 + 16 + 10 + 10 + 8);
+        kerx.u32(2);
+        kerx.u32(0);
+        kerx.u32(2);
+        kerx.u32(28);
+        kerx.u32(38);
+        kerx.u32(48);
+        for (first, values) in [(b, [0, 2]), (a, [1, 0])] {
+            kerx.u16(8);
+            kerx.u16(first);
+            kerx.u16(2);
+            kerx.u16(values[0]);
+            kerx.u16(values[1]);
+        }
+        for value in [0, 0, 0, -30] {
+            kerx.i16(value);
+        }
+        assert_eq!(kerx.0.len() - start, 12 + 16 + 10 + 10 + 8);
+        builder.add_table(Tag::new(b"kerx"), kerx.0);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let kerning = font.kerning();
+        assert_eq!(kerning.subtables().count(), 2);
+        let pair = |left, right| kerning.pair(GlyphId::new(left), GlyphId::new(right));
+        assert_eq!(pair(a, b), Some(-50));
+        assert_eq!(pair(b, c), Some(20));
+        assert_eq!(pair(c, a), Some(-30));
+        assert_eq!(pair(a, c), None);
+    }
+
+    #[test]
+    fn ankr_points() {
+        let mut builder = FontBuilder::new(1000);
+        let glyph_id = builder.add_glyph(500, SQUARE);
+        let mut ankr = Writer::default();
+        ankr.u16(0);
+        ankr.u16(0);
+        ankr.u32(12);
+        ankr.u32(28);
+        // Format 6 lookup with a single glyph mapped to offset 0.
+        ankr.u16(6);
+        ankr.u16(4);
+        ankr.u16(1);
+        ankr.u16(4);
+        ankr.u16(0);
+        ankr.u16(0);
+        ankr.u16(glyph_id.to_u16());
+        ankr.u16(0);
+        ankr.u32(2);
+        ankr.i16(100);
+        ankr.i16(200);
+        ankr.i16(-10);
+        ankr.i16(0);
+        builder.add_table(Tag::new(b"ankr"), ankr.0);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let anchors = font.aat_anchors();
+        assert_eq!(
+            anchors.anchors(glyph_id).collect::<Vec<_>>(),
+            [(100, 200), (-10, 0)]
+        );
+        assert_eq!(anchors.get(glyph_id, 1), Some((-10, 0)));
+        assert_eq!(anchors.get(GlyphId::new(0), 0), None);
+    }
+}
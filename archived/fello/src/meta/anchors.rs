@@ -0,0 +1,590 @@
+//! Mark attachment anchors.
+//!
+//! [`Anchors`] collects the anchors defined by the mark-to-base,
+//! mark-to-ligature and mark-to-mark lookups of the `GPOS` table and can
+//! export them as JSON or as simple SVG overlays. This allows mark
+//! positioning data to be audited visually straight from a compiled font.
+//!
+//! Variable anchors are evaluated with the variation store of the `GDEF`
+//! table at any number of locations. Adjustments from classic device
+//! tables are kept as [`DeviceDeltas`].
+
+use alloc::vec::Vec;
+use core::fmt;
+use read_fonts::{
+    tables::{
+        gpos::{
+            AnchorTable, CoverageTable, Device, DeviceOrVariationIndex, MarkArray,
+            MarkBasePosFormat1, MarkLigPosFormat1, MarkMarkPosFormat1, PositionSubtables,
+        },
+        variations::{DeltaSetIndex, ItemVariationStore},
+    },
+    types::GlyphId,
+    ReadError, TableProvider,
+};
+
+use crate::NormalizedCoords;
+
+/// Role of the glyph that an anchor is attached to.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum AnchorRole {
+    /// Attachment point on a base glyph.
+    Base,
+    /// Attachment point on a component of a ligature glyph.
+    Ligature {
+        /// Index of the ligature component.
+        component: u16,
+    },
+    /// Attachment point on a mark glyph for a mark-to-mark lookup.
+    BaseMark,
+    /// Attachment point on a mark glyph that is positioned relative to a
+    /// base, ligature or mark.
+    Mark,
+}
+
+impl AnchorRole {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Base => "base",
+            Self::Ligature { .. } => "ligature",
+            Self::BaseMark => "basemark",
+            Self::Mark => "mark",
+        }
+    }
+}
+
+/// Anchor defined by a mark attachment lookup.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Anchor {
+    /// Glyph that contains the anchor.
+    pub glyph_id: GlyphId,
+    /// Role of the glyph in the attachment.
+    pub role: AnchorRole,
+    /// Index of the lookup that defines the anchor.
+    pub lookup_index: u16,
+    /// Mark class that the anchor belongs to.
+    pub class: u16,
+    /// Horizontal position in font units at the default location.
+    pub x: i16,
+    /// Vertical position in font units at the default location.
+    pub y: i16,
+    /// Delta set indices for the horizontal and vertical positions.
+    deltas: [Option<(u16, u16)>; 2],
+    /// Indices of the device tables for the horizontal and vertical
+    /// positions.
+    devices: [Option<u16>; 2],
+}
+
+impl Anchor {
+    /// Returns true if the position of the anchor is varied.
+    pub fn is_variable(&self) -> bool {
+        self.deltas.iter().any(Option::is_some)
+    }
+}
+
+/// Pixel adjustments for specific sizes from a device table.
+///
+/// Fonts hinted for low resolution screens use device tables to refine
+/// positions at particular ppem sizes.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct DeviceDeltas {
+    /// Nonzero adjustments in pixels, sorted by ppem.
+    deltas: Vec<(u16, i8)>,
+}
+
+impl DeviceDeltas {
+    /// Decodes the nonzero adjustments of a device table.
+    ///
+    /// See <https://learn.microsoft.com/en-us/typography/opentype/spec/chapter2#device-and-variationindex-tables>
+    pub(crate) fn new(device: &Device) -> Self {
+        if device.start_size() > device.end_size() {
+            return Self::default();
+        }
+        let deltas = (device.start_size()..=device.end_size())
+            .zip(device.iter())
+            .filter(|(_, delta)| *delta != 0)
+            .collect();
+        Self { deltas }
+    }
+
+    /// Returns the adjustment in pixels for the given size.
+    pub fn get(&self, ppem: u16) -> i8 {
+        self.deltas
+            .binary_search_by_key(&ppem, |(size, _)| *size)
+            .map(|index| self.deltas[index].1)
+            .unwrap_or_default()
+    }
+
+    /// Returns an iterator over the sizes with nonzero adjustments, along
+    /// with the adjustments in pixels, sorted by size.
+    pub fn iter(&self) -> impl Iterator<Item = (u16, i8)> + '_ {
+        self.deltas.iter().copied()
+    }
+
+    /// Returns true if the table contains no nonzero adjustments.
+    pub fn is_empty(&self) -> bool {
+        self.deltas.is_empty()
+    }
+}
+
+/// Collection of the mark attachment anchors in a font.
+#[derive(Clone)]
+pub struct Anchors<'a> {
+    anchors: Vec<Anchor>,
+    devices: Vec<DeviceDeltas>,
+    var_store: Option<ItemVariationStore<'a>>,
+}
+
+impl<'a> Anchors<'a> {
+    /// Collects the anchors from the `GPOS` table of the given font.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let mut collector = Collector::default();
+        if let Ok(lookups) = font.gpos().and_then(|gpos| gpos.lookup_list()) {
+            for (lookup_index, lookup) in lookups.lookups().iter().enumerate() {
+                collector.lookup_index = lookup_index as u16;
+                let Ok(subtables) = lookup.and_then(|lookup| lookup.subtables()) else {
+                    continue;
+                };
+                // Malformed subtables are skipped so that the anchors of
+                // the remaining subtables are still collected.
+                let _ = match subtables {
+                    PositionSubtables::MarkToBase(subtables) => subtables
+                        .iter()
+                        .flatten()
+                        .try_for_each(|subtable| collector.mark_to_base(&subtable)),
+                    PositionSubtables::MarkToLig(subtables) => subtables
+                        .iter()
+                        .flatten()
+                        .try_for_each(|subtable| collector.mark_to_ligature(&subtable)),
+                    PositionSubtables::MarkToMark(subtables) => subtables
+                        .iter()
+                        .flatten()
+                        .try_for_each(|subtable| collector.mark_to_mark(&subtable)),
+                    _ => Ok(()),
+                };
+            }
+        }
+        let Collector {
+            mut anchors,
+            devices,
+            ..
+        } = collector;
+        anchors.sort_by_key(|anchor| {
+            (
+                anchor.glyph_id,
+                anchor.lookup_index,
+                anchor.role,
+                anchor.class,
+            )
+        });
+        anchors.dedup();
+        Self {
+            anchors,
+            devices,
+            var_store: font
+                .gdef()
+                .ok()
+                .and_then(|gdef| gdef.item_var_store()?.ok()),
+        }
+    }
+
+    /// Returns all anchors, sorted by glyph identifier and lookup index.
+    pub fn anchors(&self) -> &[Anchor] {
+        &self.anchors
+    }
+
+    /// Returns the anchors for the given glyph.
+    pub fn glyph(&self, glyph_id: GlyphId) -> &[Anchor] {
+        let start = self
+            .anchors
+            .partition_point(|anchor| anchor.glyph_id < glyph_id);
+        let len = self.anchors[start..]
+            .iter()
+            .take_while(|anchor| anchor.glyph_id == glyph_id)
+            .count();
+        &self.anchors[start..start + len]
+    }
+
+    /// Returns the position of the anchor in font units at the given
+    /// normalized variation coordinates.
+    pub fn position(&self, anchor: &Anchor, coords: NormalizedCoords) -> (f32, f32) {
+        let delta = |index: Option<(u16, u16)>| {
+            let (outer, inner) = index?;
+            let coords = coords.inner();
+            if coords.is_empty() {
+                return None;
+            }
+            self.var_store
+                .as_ref()?
+                .compute_delta(DeltaSetIndex { outer, inner }, coords)
+                .ok()
+        };
+        (
+            anchor.x as f32 + delta(anchor.deltas[0]).unwrap_or_default() as f32,
+            anchor.y as f32 + delta(anchor.deltas[1]).unwrap_or_default() as f32,
+        )
+    }
+
+    /// Returns the device table adjustments for the horizontal and
+    /// vertical positions of the anchor.
+    pub fn device_deltas(&self, anchor: &Anchor) -> [Option<&DeviceDeltas>; 2] {
+        anchor
+            .devices
+            .map(|index| self.devices.get(index? as usize))
+    }
+
+    /// Writes all anchors as a JSON document.
+    ///
+    /// The document contains an array of glyphs, each with its anchors.
+    /// The `positions` array of an anchor has one `[x, y]` entry for each
+    /// of the given locations, or a single entry for the default location
+    /// if none are given. Anchors with device tables also have a `device`
+    /// object mapping each axis to an array of `[ppem, delta]` entries.
+    pub fn write_json(
+        &self,
+        out: &mut impl fmt::Write,
+        locations: &[NormalizedCoords],
+    ) -> fmt::Result {
+        let default_locations = [NormalizedCoords::default()];
+        let locations = if locations.is_empty() {
+            &default_locations[..]
+        } else {
+            locations
+        };
+        out.write_str("{\"glyphs\":[")?;
+        let mut anchors = self.anchors.iter().peekable();
+        let mut first_glyph = true;
+        while let Some(glyph_id) = anchors.peek().map(|anchor| anchor.glyph_id) {
+            if !first_glyph {
+                out.write_char(',')?;
+            }
+            first_glyph = false;
+            write!(out, "{{\"glyph\":{},\"anchors\":[", glyph_id.to_u16())?;
+            let mut first_anchor = true;
+            while let Some(anchor) = anchors.next_if(|anchor| anchor.glyph_id == glyph_id) {
+                if !first_anchor {
+                    out.write_char(',')?;
+                }
+                first_anchor = false;
+                write!(
+                    out,
+                    "{{\"lookup\":{},\"role\":\"{}\",",
+                    anchor.lookup_index,
+                    anchor.role.name()
+                )?;
+                if let AnchorRole::Ligature { component } = anchor.role {
+                    write!(out, "\"component\":{component},")?;
+                }
+                write!(out, "\"class\":{},\"positions\":[", anchor.class)?;
+                for (i, coords) in locations.iter().enumerate() {
+                    if i != 0 {
+                        out.write_char(',')?;
+                    }
+                    let (x, y) = self.position(anchor, *coords);
+                    write!(out, "[{x},{y}]")?;
+                }
+                out.write_char(']')?;
+                let devices = self.device_deltas(anchor);
+                if devices.iter().any(Option::is_some) {
+                    out.write_str(",\"device\":{")?;
+                    let mut first_axis = true;
+                    for (axis, device) in ["x", "y"].iter().zip(devices) {
+                        let Some(device) = device else {
+                            continue;
+                        };
+                        if !first_axis {
+                            out.write_char(',')?;
+                        }
+                        first_axis = false;
+                        write!(out, "\"{axis}\":[")?;
+                        for (i, (ppem, delta)) in device.iter().enumerate() {
+                            if i != 0 {
+                                out.write_char(',')?;
+                            }
+                            write!(out, "[{ppem},{delta}]")?;
+                        }
+                        out.write_char(']')?;
+                    }
+                    out.write_char('}')?;
+                }
+                out.write_char('}')?;
+            }
+            out.write_str("]}")?;
+        }
+        out.write_str("]}")
+    }
+
+    /// Writes the anchors of a glyph at the given location as an SVG
+    /// document for overlaying on a rendering of the glyph.
+    ///
+    /// The view box is specified in font units with the y axis pointing up,
+    /// as `(x_min, y_min, width, height)`. Each anchor is drawn as a circle
+    /// labeled with its role and mark class.
+    pub fn write_svg(
+        &self,
+        out: &mut impl fmt::Write,
+        glyph_id: GlyphId,
+        coords: NormalizedCoords,
+        view_box: (f32, f32, f32, f32),
+    ) -> fmt::Result {
+        let (x_min, y_min, width, height) = view_box;
+        let radius = width.max(height) / 100.0;
+        write!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+            x_min,
+            -(y_min + height),
+            width,
+            height
+        )?;
+        for anchor in self.glyph(glyph_id) {
+            let (x, y) = self.position(anchor, coords);
+            // Flip the y axis to match SVG coordinates.
+            let y = -y;
+            let color = match anchor.role {
+                AnchorRole::Mark => "red",
+                _ => "blue",
+            };
+            write!(
+                out,
+                "<circle cx=\"{x}\" cy=\"{y}\" r=\"{radius}\" fill=\"{color}\"/>\
+                 <text x=\"{}\" y=\"{y}\" font-size=\"{}\" fill=\"{color}\">{}",
+                x + radius * 1.5,
+                radius * 3.0,
+                anchor.role.name()
+            )?;
+            if let AnchorRole::Ligature { component } = anchor.role {
+                write!(out, " {component}")?;
+            }
+            write!(out, " {}</text>", anchor.class)?;
+        }
+        out.write_str("</svg>")
+    }
+}
+
+/// Accumulates the anchors of mark attachment subtables.
+#[derive(Default)]
+struct Collector {
+    lookup_index: u16,
+    anchors: Vec<Anchor>,
+    devices: Vec<DeviceDeltas>,
+}
+
+impl Collector {
+    fn mark_to_base(&mut self, subtable: &MarkBasePosFormat1) -> Result<(), ReadError> {
+        self.add_marks(&subtable.mark_coverage()?, &subtable.mark_array()?);
+        let base_array = subtable.base_array()?;
+        let records = base_array.base_records();
+        for (glyph_id, record) in subtable.base_coverage()?.iter().zip(records.iter()) {
+            let anchors = record?.base_anchors(base_array.offset_data());
+            for (class, anchor) in anchors.iter().enumerate() {
+                self.add(glyph_id.into(), AnchorRole::Base, class as u16, anchor);
+            }
+        }
+        Ok(())
+    }
+
+    fn mark_to_ligature(&mut self, subtable: &MarkLigPosFormat1) -> Result<(), ReadError> {
+        self.add_marks(&subtable.mark_coverage()?, &subtable.mark_array()?);
+        let ligature_array = subtable.ligature_array()?;
+        let attaches = ligature_array.ligature_attaches();
+        for (glyph_id, attach) in subtable.ligature_coverage()?.iter().zip(attaches.iter()) {
+            let Ok(attach) = attach else {
+                continue;
+            };
+            for (component, record) in attach.component_records().iter().enumerate() {
+                let role = AnchorRole::Ligature {
+                    component: component as u16,
+                };
+                let anchors = record?.ligature_anchors(attach.offset_data());
+                for (class, anchor) in anchors.iter().enumerate() {
+                    self.add(glyph_id.into(), role, class as u16, anchor);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn mark_to_mark(&mut self, subtable: &MarkMarkPosFormat1) -> Result<(), ReadError> {
+        self.add_marks(&subtable.mark1_coverage()?, &subtable.mark1_array()?);
+        let mark2_array = subtable.mark2_array()?;
+        let records = mark2_array.mark2_records();
+        for (glyph_id, record) in subtable.mark2_coverage()?.iter().zip(records.iter()) {
+            let anchors = record?.mark2_anchors(mark2_array.offset_data());
+            for (class, anchor) in anchors.iter().enumerate() {
+                self.add(glyph_id.into(), AnchorRole::BaseMark, class as u16, anchor);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the anchors of the marks, which are in coverage index order.
+    fn add_marks(&mut self, coverage: &CoverageTable, marks: &MarkArray) {
+        for (glyph_id, record) in coverage.iter().zip(marks.mark_records()) {
+            let anchor = record.mark_anchor(marks.offset_data());
+            self.add(
+                glyph_id.into(),
+                AnchorRole::Mark,
+                record.mark_class(),
+                Some(anchor),
+            );
+        }
+    }
+
+    /// Adds an anchor table, ignoring null and malformed anchors.
+    ///
+    /// Contour point anchors (format 2) are reported at their design
+    /// position.
+    fn add(
+        &mut self,
+        glyph_id: GlyphId,
+        role: AnchorRole,
+        class: u16,
+        anchor: Option<Result<AnchorTable, ReadError>>,
+    ) {
+        let Some(Ok(anchor)) = anchor else {
+            return;
+        };
+        let mut deltas = [None; 2];
+        let mut device_indices = [None; 2];
+        let tables = [anchor.x_device(), anchor.y_device()];
+        let slots = deltas.iter_mut().zip(device_indices.iter_mut());
+        for ((delta, device_index), table) in slots.zip(tables) {
+            match table {
+                Some(Ok(DeviceOrVariationIndex::VariationIndex(index))) => {
+                    *delta = Some((index.delta_set_outer_index(), index.delta_set_inner_index()));
+                }
+                Some(Ok(DeviceOrVariationIndex::Device(device))) => {
+                    let device = DeviceDeltas::new(&device);
+                    if device.is_empty() {
+                        continue;
+                    }
+                    // Hinted fonts often share device tables between anchors.
+                    let index = match self.devices.iter().position(|existing| *existing == device) {
+                        Some(index) => index,
+                        None => {
+                            self.devices.push(device);
+                            self.devices.len() - 1
+                        }
+                    };
+                    *device_index = u16::try_from(index).ok();
+                }
+                _ => {}
+            }
+        }
+        self.anchors.push(Anchor {
+            glyph_id,
+            role,
+            lookup_index: self.lookup_index,
+            class,
+            x: anchor.x_coordinate(),
+            y: anchor.y_coordinate(),
+            deltas,
+            devices: device_indices,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{subtables, FontBuilder, LayoutBuilder},
+        write::Writer,
+        MetadataProvider,
+    };
+    use alloc::{string::String, vec};
+    use read_fonts::{types::Tag, FontData, FontRead, FontRef};
+
+    /// Builds a font with a `GPOS` table containing a single mark-to-base
+    /// lookup that attaches mark glyph 2 to base glyph 1.
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::with_squares(3);
+        let mut gpos = LayoutBuilder::new();
+        gpos.add_lookup(
+            4,
+            vec![subtables::mark_base_pos(
+                subtables::coverage(&[GlyphId::new(2)]),
+                subtables::coverage(&[GlyphId::new(1)]),
+                &[(0, (250, 500))],
+                &[&[(250, -20)]],
+            )],
+        );
+        builder.add_table(Tag::new(b"GPOS"), gpos.build());
+        builder.build()
+    }
+
+    #[test]
+    fn mark_to_base_anchors() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let anchors = font.anchors();
+        assert_eq!(anchors.anchors().len(), 2);
+        let base = anchors.glyph(GlyphId::new(1));
+        assert_eq!(base.len(), 1);
+        assert_eq!(base[0].role, AnchorRole::Base);
+        assert_eq!((base[0].x, base[0].y), (250, -20));
+        assert!(!base[0].is_variable());
+        let mark = anchors.glyph(GlyphId::new(2));
+        assert_eq!(mark.len(), 1);
+        assert_eq!(mark[0].role, AnchorRole::Mark);
+        assert_eq!(mark[0].class, 0);
+        assert_eq!((mark[0].x, mark[0].y), (250, 500));
+        assert!(anchors.glyph(GlyphId::new(0)).is_empty());
+    }
+
+    #[test]
+    fn device_deltas() {
+        let mut device = Writer::default();
+        // Sizes 11 to 14 with 4-bit deltas of 1, -1, 0 and -8
+        device.u16(11);
+        device.u16(14);
+        device.u16(2);
+        device.u16(0x1F08);
+        let deltas = DeviceDeltas::new(&Device::read(FontData::new(&device.0)).unwrap());
+        assert_eq!(
+            deltas.iter().collect::<Vec<_>>(),
+            [(11, 1), (12, -1), (14, -8)]
+        );
+        assert_eq!(deltas.get(13), 0);
+        assert_eq!(deltas.get(14), -8);
+        let mut device = Writer::default();
+        // Sizes 9 and 10 with 2-bit deltas of -2 and 1
+        device.u16(9);
+        device.u16(10);
+        device.u16(1);
+        device.u16(0x9000);
+        let deltas = DeviceDeltas::new(&Device::read(FontData::new(&device.0)).unwrap());
+        assert_eq!(deltas.iter().collect::<Vec<_>>(), [(9, -2), (10, 1)]);
+    }
+
+    #[test]
+    fn export_anchors() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let anchors = font.anchors();
+        let mut json = String::new();
+        anchors.write_json(&mut json, &[]).unwrap();
+        assert_eq!(
+            json,
+            "{\"glyphs\":[\
+             {\"glyph\":1,\"anchors\":[{\"lookup\":0,\"role\":\"base\",\"class\":0,\"positions\":[[250,-20]]}]},\
+             {\"glyph\":2,\"anchors\":[{\"lookup\":0,\"role\":\"mark\",\"class\":0,\"positions\":[[250,500]]}]}\
+             ]}"
+        );
+        let mut svg = String::new();
+        anchors
+            .write_svg(
+                &mut svg,
+                GlyphId::new(1),
+                Default::default(),
+                (0.0, -200.0, 1000.0, 1000.0),
+            )
+            .unwrap();
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 -800 1000 1000\">"
+        ));
+        assert!(svg.contains("<circle cx=\"250\" cy=\"20\" r=\"10\" fill=\"blue\"/>"));
+        assert!(svg.ends_with("base 0</text></svg>"));
+    }
+}
@@ -22,6 +22,7 @@ use read_fonts::{
 /// [head](https://learn.microsoft.com/en-us/typography/opentype/spec/head)
 /// table.
 #[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Attributes {
     pub stretch: Stretch,
     pub style: Style,
@@ -95,6 +96,7 @@ impl Attributes {
 ///
 /// See <https://fonts.google.com/knowledge/glossary/width>
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stretch(f32);
 
 impl Stretch {
@@ -180,6 +182,7 @@ impl Default for Stretch {
 ///
 /// See <https://fonts.google.com/knowledge/glossary/style>
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Style {
     /// An upright or "roman" style.
     #[default]
@@ -198,6 +201,7 @@ pub enum Style {
 ///
 /// See <https://fonts.google.com/knowledge/glossary/weight>
 #[derive(Copy, Clone, PartialEq, PartialOrd, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Weight(f32);
 
 impl Weight {
@@ -2,12 +2,17 @@
 
 */
 
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 use read_fonts::{
     tables::cmap::{self, Cmap, Cmap14, CmapSubtable, PlatformId},
     types::{GlyphId, Uint24},
-    TableProvider,
+    FontData, TableProvider,
 };
 
+use super::decomposition::{COMPOSITIONS, DECOMPOSITIONS, MAX_DECOMPOSITION_LEN};
+use super::legacy_encoding::{BIG5, SHIFT_JIS};
+
 pub use read_fonts::tables::cmap::MapVariant;
 
 /// Indices of selected mapping subtables.
@@ -19,27 +24,132 @@ pub struct SelectedMaps {
     pub is_symbol: bool,
     /// Index of Unicode variation selector sutable.
     pub variants: Option<u16>,
+    /// Legacy encoding of the mapping subtable if no Unicode or symbol
+    /// subtable was found.
+    pub encoding: Option<LegacyEncoding>,
+}
+
+/// Policy for selecting the mapping subtable of a character map.
+///
+/// All strategies fall back to subtables in a
+/// [legacy encoding](LegacyEncoding) if no Unicode or symbol subtable is
+/// found, except for [`Subtable`](Self::Subtable).
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum MappingStrategy {
+    /// Prefer a Windows symbol subtable, then a full Unicode subtable, then
+    /// any Unicode subtable. This matches HarfBuzz.
+    #[default]
+    PreferSymbol,
+    /// Prefer a full Unicode (UCS-4) subtable, then any Unicode subtable,
+    /// then a Windows symbol subtable. This matches FreeType.
+    PreferFullRepertoire,
+    /// Prefer a Unicode subtable limited to the Basic Multilingual Plane,
+    /// then any Unicode subtable, then a Windows symbol subtable.
+    PreferBmp,
+    /// Use the subtable at the given index of the encoding records. No
+    /// mapping is selected if the subtable has an unsupported format.
+    Subtable(u16),
+}
+
+/// Legacy multibyte encodings supported for mapping subtables.
+///
+/// Older CJK fonts may only contain subtables that map character codes in
+/// one of these encodings. Codepoints are converted before lookup in such
+/// subtables.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum LegacyEncoding {
+    /// Shift-JIS encoding of JIS X 0208 for Japanese.
+    ShiftJis,
+    /// Big5 encoding for traditional Chinese.
+    Big5,
+}
+
+impl LegacyEncoding {
+    /// Converts a Unicode codepoint to a character code in this encoding.
+    /// Returns `None` if the codepoint is not representable.
+    ///
+    /// Single byte codes are returned in the low byte.
+    pub fn encode(self, codepoint: u32) -> Option<u16> {
+        if codepoint < 0x80 {
+            return Some(codepoint as u16);
+        }
+        let codepoint = u16::try_from(codepoint).ok()?;
+        let table = self.table();
+        table
+            .binary_search_by(|entry| entry.0.cmp(&codepoint))
+            .ok()
+            .map(|index| table[index].1)
+    }
+
+    /// Converts a character code in this encoding to a Unicode character.
+    /// Returns `None` if the code is not mapped.
+    ///
+    /// Single byte codes are expected in the low byte. Where several
+    /// codepoints map to the same code, the lowest one is returned.
+    pub fn decode(self, code: u16) -> Option<char> {
+        if code < 0x80 {
+            return char::from_u32(code as u32);
+        }
+        // The tables are sorted by codepoint so this requires a linear
+        // search.
+        self.table()
+            .iter()
+            .find(|(_, mapped)| *mapped == code)
+            .and_then(|(codepoint, _)| char::from_u32(*codepoint as u32))
+    }
+
+    fn table(self) -> &'static [(u16, u16)] {
+        match self {
+            Self::ShiftJis => &SHIFT_JIS,
+            Self::Big5 => &BIG5,
+        }
+    }
+}
+
+enum Subtable<'a> {
+    Standard(CmapSubtable<'a>),
+    HighByte(HighByteMapping<'a>),
 }
 
 struct Map<'a> {
-    map: CmapSubtable<'a>,
+    map: Subtable<'a>,
     index: u16,
     is_symbol: bool,
+    encoding: Option<LegacyEncoding>,
 }
 
 impl<'a> Map<'a> {
     fn new(map: CmapSubtable<'a>, index: u16, is_symbol: bool) -> Self {
         Self {
-            map,
+            map: Subtable::Standard(map),
             index,
             is_symbol,
+            encoding: None,
+        }
+    }
+
+    fn with_encoding(map: Subtable<'a>, index: u16, encoding: LegacyEncoding) -> Self {
+        Self {
+            map,
+            index,
+            is_symbol: false,
+            encoding: Some(encoding),
         }
     }
 
     fn map(&self, codepoint: u32) -> Option<GlyphId> {
+        let codepoint = match self.encoding {
+            Some(encoding) => encoding.encode(codepoint)? as u32,
+            None => codepoint,
+        };
         match &self.map {
-            cmap::CmapSubtable::Format4(subtable) => subtable.map_codepoint(codepoint),
-            cmap::CmapSubtable::Format12(subtable) => subtable.map_codepoint(codepoint),
+            Subtable::Standard(cmap::CmapSubtable::Format4(subtable)) => {
+                subtable.map_codepoint(codepoint)
+            }
+            Subtable::Standard(cmap::CmapSubtable::Format12(subtable)) => {
+                subtable.map_codepoint(codepoint)
+            }
+            Subtable::HighByte(subtable) => subtable.map(codepoint),
             _ => None,
         }
     }
@@ -59,6 +169,67 @@ impl<'a> Map<'a> {
     }
 }
 
+/// Format 2 subtable that maps mixed 8/16-bit character codes.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/cmap#format-2-high-byte-mapping-through-table>
+struct HighByteMapping<'a> {
+    data: FontData<'a>,
+}
+
+impl<'a> HighByteMapping<'a> {
+    /// Offset of the first subheader, following the format, length and
+    /// language fields and the 256 subheader keys.
+    const SUBHEADERS_OFFSET: usize = 518;
+    const SUBHEADER_LEN: usize = 8;
+
+    fn new(data: FontData<'a>) -> Option<Self> {
+        if data.read_at::<u16>(0).ok()? != 2
+            || data.len() < Self::SUBHEADERS_OFFSET + Self::SUBHEADER_LEN
+        {
+            return None;
+        }
+        Some(Self { data })
+    }
+
+    fn map(&self, code: u32) -> Option<GlyphId> {
+        let [high, low] = u16::try_from(code).ok()?.to_be_bytes();
+        let key = |byte: u8| self.data.read_at::<u16>(6 + byte as usize * 2).ok();
+        // Single byte codes use the first subheader but must not be the
+        // first byte of a two byte code. Two byte codes must not use the
+        // first subheader.
+        let subheader = if high == 0 {
+            if key(low)? != 0 {
+                return None;
+            }
+            0
+        } else {
+            match key(high)? as usize / Self::SUBHEADER_LEN {
+                0 => return None,
+                index => index,
+            }
+        };
+        let base = Self::SUBHEADERS_OFFSET + subheader * Self::SUBHEADER_LEN;
+        let first_code = self.data.read_at::<u16>(base).ok()?;
+        let entry_count = self.data.read_at::<u16>(base + 2).ok()?;
+        let id_delta = self.data.read_at::<i16>(base + 4).ok()?;
+        let id_range_offset = self.data.read_at::<u16>(base + 6).ok()?;
+        let index = (low as u16).checked_sub(first_code)?;
+        if index >= entry_count {
+            return None;
+        }
+        // The range offset is relative to its own location.
+        let glyph_offset = base + 6 + id_range_offset as usize + index as usize * 2;
+        let glyph_id = self.data.read_at::<u16>(glyph_offset).ok()?;
+        if glyph_id == 0 {
+            return None;
+        }
+        match glyph_id.wrapping_add(id_delta as u16) {
+            0 => None,
+            glyph_id => Some(GlyphId::new(glyph_id)),
+        }
+    }
+}
+
 /// Mapping of codepoints to nominal glyph identifiers.
 // #[derive(Clone)]
 pub struct Charmap<'a> {
@@ -69,9 +240,15 @@ pub struct Charmap<'a> {
 impl<'a> Charmap<'a> {
     /// Creates a new character map from the specified table provider.
     pub fn new(font: &impl TableProvider<'a>) -> Self {
+        Self::with_strategy(font, MappingStrategy::default())
+    }
+
+    /// Creates a new character map from the specified table provider,
+    /// selecting the mapping subtable with the given strategy.
+    pub fn with_strategy(font: &impl TableProvider<'a>, strategy: MappingStrategy) -> Self {
         let (map, vs_map) = if let Ok(cmap) = font.cmap() {
             (
-                find_symbol_or_unicode_subtable(&cmap),
+                find_symbol_or_unicode_subtable(&cmap, strategy),
                 find_variant_selector_subtable(&cmap),
             )
         } else {
@@ -82,16 +259,17 @@ impl<'a> Charmap<'a> {
 
     /// Returns the selected mapping subtables.
     pub fn selected_maps(&self) -> SelectedMaps {
-        let (mapping, is_symbol) = self
+        let (mapping, is_symbol, encoding) = self
             .map
             .as_ref()
-            .map(|map| (Some(map.index), map.is_symbol))
+            .map(|map| (Some(map.index), map.is_symbol, map.encoding))
             .unwrap_or_default();
 
         SelectedMaps {
             mapping,
             is_symbol,
             variants: self.vs_map.as_ref().map(|map| map.1),
+            encoding,
         }
     }
 
@@ -114,6 +292,159 @@ impl<'a> Charmap<'a> {
         None
     }
 
+    /// Maps each codepoint in `codepoints` to a nominal glyph identifier
+    /// in the corresponding position of `glyph_ids`, storing the notdef
+    /// glyph for codepoints without a mapping.
+    ///
+    /// This produces the same results as calling [`map`](Self::map) for
+    /// each codepoint but is faster for text. For format 12 subtables, the
+    /// group of the previous codepoint and the group following it are
+    /// checked before falling back to a binary search, which avoids most
+    /// searches since neighboring characters tend to be close.
+    ///
+    /// Only the first `min(codepoints.len(), glyph_ids.len())` entries are
+    /// processed.
+    pub fn map_batch(&self, codepoints: &[u32], glyph_ids: &mut [GlyphId]) {
+        let pairs = codepoints.iter().zip(glyph_ids.iter_mut());
+        let groups = match &self.map {
+            Some(Map {
+                map: Subtable::Standard(CmapSubtable::Format12(subtable)),
+                is_symbol: false,
+                encoding: None,
+                ..
+            }) => subtable.groups(),
+            _ => {
+                for (codepoint, glyph_id) in pairs {
+                    *glyph_id = self.map(*codepoint).unwrap_or(GlyphId::NOTDEF);
+                }
+                return;
+            }
+        };
+        let mut last = 0;
+        for (&codepoint, glyph_id) in pairs {
+            let contains = |index: usize| {
+                groups.get(index).map_or(false, |group| {
+                    (group.start_char_code()..=group.end_char_code()).contains(&codepoint)
+                })
+            };
+            let index = if contains(last) {
+                Some(last)
+            } else if contains(last + 1) {
+                Some(last + 1)
+            } else {
+                groups
+                    .binary_search_by(|group| {
+                        if codepoint < group.start_char_code() {
+                            Ordering::Greater
+                        } else if codepoint > group.end_char_code() {
+                            Ordering::Less
+                        } else {
+                            Ordering::Equal
+                        }
+                    })
+                    .ok()
+            };
+            *glyph_id = match index {
+                Some(index) => {
+                    last = index;
+                    let group = &groups[index];
+                    let id = group.start_glyph_id() as u64
+                        + (codepoint - group.start_char_code()) as u64;
+                    u16::try_from(id).map_or(GlyphId::NOTDEF, GlyphId::new)
+                }
+                None => GlyphId::NOTDEF,
+            };
+        }
+    }
+
+    /// Invokes the function for each codepoint and nominal glyph identifier
+    /// in the selected Unicode or symbol mapping subtable.
+    ///
+    /// Subtables in legacy encodings are skipped.
+    pub(crate) fn for_each_mapping(&self, mut f: impl FnMut(u32, GlyphId)) {
+        let Some(Map {
+            map: Subtable::Standard(subtable),
+            ..
+        }) = &self.map
+        else {
+            return;
+        };
+        match subtable {
+            CmapSubtable::Format4(subtable) => {
+                for (start, end) in subtable.start_code().iter().zip(subtable.end_code()) {
+                    for codepoint in start.get()..=end.get() {
+                        if let Some(glyph_id) = subtable.map_codepoint(codepoint) {
+                            f(codepoint as u32, glyph_id);
+                        }
+                    }
+                }
+            }
+            CmapSubtable::Format12(subtable) => {
+                for group in subtable.groups() {
+                    let start = group.start_char_code();
+                    let end = group.end_char_code().min(char::MAX as u32);
+                    for codepoint in start..=end {
+                        let glyph_id = group.start_glyph_id() as u64 + (codepoint - start) as u64;
+                        if let Ok(glyph_id) = u16::try_from(glyph_id) {
+                            f(codepoint, GlyphId::new(glyph_id));
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the sorted set of codepoints in the selected Unicode or symbol
+    /// mapping subtable that map to a glyph other than `.notdef`.
+    ///
+    /// Subtables in legacy encodings are skipped.
+    pub fn codepoints(&self) -> Vec<u32> {
+        let mut codepoints = Vec::new();
+        self.for_each_mapping(|codepoint, glyph_id| {
+            if glyph_id != GlyphId::NOTDEF {
+                codepoints.push(codepoint);
+            }
+        });
+        // Segments and groups may overlap.
+        codepoints.sort_unstable();
+        codepoints.dedup();
+        codepoints
+    }
+
+    /// Compares the codepoint coverage of this character map with another.
+    ///
+    /// This is useful for deciding whether a fallback font adds anything
+    /// over the primary font before inserting it into a cascade, in which
+    /// case the primary font should be `self` and
+    /// [`only_other`](CoverageComparison::only_other) is non-zero.
+    pub fn compare_coverage(&self, other: &Charmap) -> CoverageComparison {
+        let ours = self.codepoints();
+        let theirs = other.codepoints();
+        let mut result = CoverageComparison::default();
+        let (mut i, mut j) = (0, 0);
+        while i < ours.len() && j < theirs.len() {
+            match ours[i].cmp(&theirs[j]) {
+                Ordering::Less => {
+                    result.only_self += 1;
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    result.only_other += 1;
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    result.common += 1;
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        result.only_self += ours.len() - i;
+        result.only_other += theirs.len() - j;
+        result
+    }
+
     /// Maps a codepoint and variation selector to a nominal glyph identifier.
     pub fn map_variant(
         &self,
@@ -123,71 +454,333 @@ impl<'a> Charmap<'a> {
         let map = &self.vs_map.as_ref()?.0;
         map.map_variant(codepoint, selector)
     }
-}
 
-/// Find the best subtable that supports a Unicode mapping.
-///
-/// The strategy is a combination of those used in FreeType and HarfBuzz.
-fn find_symbol_or_unicode_subtable<'a>(cmap: &Cmap<'a>) -> Option<Map<'a>> {
-    const ENCODING_MS_SYMBOL: u16 = 0;
-    const ENCODING_MS_UNICODE_CS: u16 = 1;
-    const ENCODING_MS_ID_UCS_4: u16 = 10;
-    const ENCODING_APPLE_ID_UNICODE_32: u16 = 4;
-    let records = cmap.encoding_records();
-    // HarfBuzz prefers a symbol subtable.
-    for (i, rec) in records.iter().enumerate() {
-        if let (PlatformId::Windows, ENCODING_MS_SYMBOL) = (rec.platform_id(), rec.encoding_id()) {
-            if let Ok(subtable) = rec.subtable(cmap.offset_data()) {
-                match subtable {
-                    CmapSubtable::Format4(_) | CmapSubtable::Format12(_) => {
-                        return Some(Map::new(subtable, i as u16, true));
-                    }
-                    _ => {}
+    /// Maps a character to a sequence of nominal glyph identifiers, falling
+    /// back to the canonical decomposition of the character if it is not
+    /// directly mapped.
+    ///
+    /// When falling back, each run of decomposed characters is recomposed as
+    /// far as possible so that precomposed forms in the font are preferred.
+    /// For example, U+1E09 (LATIN SMALL LETTER C WITH CEDILLA AND ACUTE) maps
+    /// to the glyphs for U+00E7 and U+0301 if the font supports those but not
+    /// the fully precomposed character. Hangul syllables decompose
+    /// algorithmically into their conjoining jamo. Returns `None` if any part
+    /// of the decomposition is not mapped.
+    pub fn map_with_decomposition(&self, ch: char) -> Option<DecomposedGlyphs> {
+        let mut result = DecomposedGlyphs::default();
+        if let Some(glyph_id) = self.map(ch) {
+            result.push(glyph_id);
+            return Some(result);
+        }
+        let mut chars = [0u32; MAX_DECOMPOSITION_LEN];
+        let mut len = 0;
+        decompose(ch as u32, &mut chars, &mut len);
+        if len == 1 && chars[0] == ch as u32 {
+            return None;
+        }
+        let chars = &chars[..len];
+        let mut i = 0;
+        while i < chars.len() {
+            // Find the longest recomposition starting at i that is mapped.
+            let mut composed = chars[i];
+            let mut best = self.map(composed).map(|glyph_id| (glyph_id, i + 1));
+            for (j, next) in chars.iter().enumerate().skip(i + 1) {
+                let Some(c) = compose(composed, *next) else {
+                    break;
+                };
+                composed = c;
+                if let Some(glyph_id) = self.map(composed) {
+                    best = Some((glyph_id, j + 1));
                 }
             }
+            let (glyph_id, next) = best?;
+            result.push(glyph_id);
+            i = next;
+        }
+        Some(result)
+    }
+}
+
+/// Codepoint counts produced by [`Charmap::compare_coverage`].
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct CoverageComparison {
+    /// Number of codepoints mapped by both character maps.
+    pub common: usize,
+    /// Number of codepoints mapped only by the compared character map.
+    pub only_self: usize,
+    /// Number of codepoints mapped only by the other character map.
+    pub only_other: usize,
+}
+
+impl CoverageComparison {
+    /// Returns the number of codepoints mapped by either character map.
+    pub fn union(&self) -> usize {
+        self.common + self.only_self + self.only_other
+    }
+
+    /// Returns true if the other character map maps codepoints that the
+    /// compared one does not.
+    pub fn other_adds_coverage(&self) -> bool {
+        self.only_other != 0
+    }
+}
+
+/// Sequence of nominal glyph identifiers produced by
+/// [`Charmap::map_with_decomposition`].
+#[derive(Copy, Clone, Debug)]
+pub struct DecomposedGlyphs {
+    glyphs: [GlyphId; MAX_DECOMPOSITION_LEN],
+    len: usize,
+}
+
+impl DecomposedGlyphs {
+    /// Returns the glyph identifiers.
+    pub fn glyphs(&self) -> &[GlyphId] {
+        &self.glyphs[..self.len]
+    }
+
+    fn push(&mut self, glyph_id: GlyphId) {
+        self.glyphs[self.len] = glyph_id;
+        self.len += 1;
+    }
+}
+
+impl Default for DecomposedGlyphs {
+    fn default() -> Self {
+        Self {
+            glyphs: [GlyphId::NOTDEF; MAX_DECOMPOSITION_LEN],
+            len: 0,
+        }
+    }
+}
+
+// Constants for algorithmic Hangul syllable decomposition. See section 3.12
+// of the Unicode Standard.
+const HANGUL_S_BASE: u32 = 0xAC00;
+const HANGUL_L_BASE: u32 = 0x1100;
+const HANGUL_V_BASE: u32 = 0x1161;
+const HANGUL_T_BASE: u32 = 0x11A7;
+const HANGUL_L_COUNT: u32 = 19;
+const HANGUL_V_COUNT: u32 = 21;
+const HANGUL_T_COUNT: u32 = 28;
+const HANGUL_N_COUNT: u32 = HANGUL_V_COUNT * HANGUL_T_COUNT;
+const HANGUL_S_COUNT: u32 = HANGUL_L_COUNT * HANGUL_N_COUNT;
+
+/// Recursively applies canonical decomposition to the codepoint.
+fn decompose(codepoint: u32, chars: &mut [u32; MAX_DECOMPOSITION_LEN], len: &mut usize) {
+    let s_index = codepoint.wrapping_sub(HANGUL_S_BASE);
+    if s_index < HANGUL_S_COUNT {
+        let l = HANGUL_L_BASE + s_index / HANGUL_N_COUNT;
+        let v = HANGUL_V_BASE + (s_index % HANGUL_N_COUNT) / HANGUL_T_COUNT;
+        let t = HANGUL_T_BASE + s_index % HANGUL_T_COUNT;
+        decompose(l, chars, len);
+        decompose(v, chars, len);
+        if t != HANGUL_T_BASE {
+            decompose(t, chars, len);
+        }
+        return;
+    }
+    match DECOMPOSITIONS.binary_search_by(|entry| entry.0.cmp(&codepoint)) {
+        Ok(index) => {
+            let (_, first, second) = DECOMPOSITIONS[index];
+            decompose(first, chars, len);
+            if second != 0 {
+                decompose(second, chars, len);
+            }
+        }
+        Err(_) => {
+            if *len < MAX_DECOMPOSITION_LEN {
+                chars[*len] = codepoint;
+                *len += 1;
+            }
         }
     }
-    // First, search for a UCS4 mapping.
+}
+
+/// Returns the primary composite for the pair of codepoints.
+fn compose(first: u32, second: u32) -> Option<u32> {
+    let l_index = first.wrapping_sub(HANGUL_L_BASE);
+    let v_index = second.wrapping_sub(HANGUL_V_BASE);
+    if l_index < HANGUL_L_COUNT && v_index < HANGUL_V_COUNT {
+        return Some(HANGUL_S_BASE + (l_index * HANGUL_V_COUNT + v_index) * HANGUL_T_COUNT);
+    }
+    let s_index = first.wrapping_sub(HANGUL_S_BASE);
+    let t_index = second.wrapping_sub(HANGUL_T_BASE);
+    if s_index < HANGUL_S_COUNT
+        && s_index % HANGUL_T_COUNT == 0
+        && (1..HANGUL_T_COUNT).contains(&t_index)
+    {
+        return Some(first + t_index);
+    }
+    COMPOSITIONS
+        .binary_search_by(|entry| (entry.0, entry.1).cmp(&(first, second)))
+        .ok()
+        .map(|index| COMPOSITIONS[index].2)
+}
+
+const ENCODING_MS_SYMBOL: u16 = 0;
+const ENCODING_MS_UNICODE_CS: u16 = 1;
+const ENCODING_MS_SHIFT_JIS: u16 = 2;
+const ENCODING_MS_BIG5: u16 = 4;
+const ENCODING_MS_ID_UCS_4: u16 = 10;
+const ENCODING_APPLE_ID_UNICODE_32: u16 = 4;
+const ENCODING_MAC_JAPANESE: u16 = 1;
+const ENCODING_MAC_TRADITIONAL_CHINESE: u16 = 2;
+
+/// Find the best subtable that supports a Unicode mapping according to the
+/// given strategy.
+///
+/// The default strategy is a combination of those used in FreeType and
+/// HarfBuzz.
+fn find_symbol_or_unicode_subtable<'a>(
+    cmap: &Cmap<'a>,
+    strategy: MappingStrategy,
+) -> Option<Map<'a>> {
+    let symbol = || {
+        find_subtable(cmap, false, true, |platform, encoding| {
+            matches!(
+                (platform, encoding),
+                (PlatformId::Windows, ENCODING_MS_SYMBOL)
+            )
+        })
+    };
     // According to FreeType, the most interesting table (Windows, UCS4) often appears
     // last, so search in reverse order.
-    for (i, rec) in records.iter().enumerate().rev() {
-        match (rec.platform_id(), rec.encoding_id()) {
-            (PlatformId::Windows, ENCODING_MS_ID_UCS_4)
-            | (PlatformId::Unicode, ENCODING_APPLE_ID_UNICODE_32) => {
-                if let Ok(subtable) = rec.subtable(cmap.offset_data()) {
-                    match subtable {
-                        CmapSubtable::Format4(_) | CmapSubtable::Format12(_) => {
-                            return Some(Map::new(subtable, i as u16, false));
-                        }
-                        _ => {}
-                    }
-                }
+    let ucs4 = || {
+        find_subtable(cmap, true, false, |platform, encoding| {
+            matches!(
+                (platform, encoding),
+                (PlatformId::Windows, ENCODING_MS_ID_UCS_4)
+                    | (PlatformId::Unicode, ENCODING_APPLE_ID_UNICODE_32)
+            )
+        })
+    };
+    let bmp = || {
+        find_subtable(cmap, true, false, |platform, encoding| {
+            matches!(
+                (platform, encoding),
+                (PlatformId::ISO, _)
+                    | (PlatformId::Unicode, 0..=3)
+                    | (PlatformId::Windows, ENCODING_MS_UNICODE_CS)
+            )
+        })
+    };
+    let unicode = || {
+        find_subtable(cmap, true, false, |platform, encoding| {
+            matches!(
+                (platform, encoding),
+                (PlatformId::ISO, _)
+                    | (PlatformId::Unicode, _)
+                    | (PlatformId::Windows, ENCODING_MS_ID_UCS_4)
+                    | (PlatformId::Windows, ENCODING_MS_UNICODE_CS)
+            )
+        })
+    };
+    let map = match strategy {
+        // HarfBuzz prefers a symbol subtable.
+        MappingStrategy::PreferSymbol => symbol().or_else(ucs4).or_else(unicode),
+        MappingStrategy::PreferFullRepertoire => ucs4().or_else(unicode).or_else(symbol),
+        MappingStrategy::PreferBmp => bmp().or_else(unicode).or_else(symbol),
+        MappingStrategy::Subtable(index) => return subtable_at(cmap, index),
+    };
+    map.or_else(|| find_legacy_subtable(cmap))
+}
+
+/// Searches for a format 4 or 12 subtable with an encoding record that
+/// satisfies the predicate, optionally in reverse order of the records.
+fn find_subtable<'a>(
+    cmap: &Cmap<'a>,
+    reverse: bool,
+    is_symbol: bool,
+    predicate: impl Fn(PlatformId, u16) -> bool,
+) -> Option<Map<'a>> {
+    let records = cmap.encoding_records();
+    let count = records.len();
+    for j in 0..count {
+        let i = if reverse { count - 1 - j } else { j };
+        let rec = &records[i];
+        if !predicate(rec.platform_id(), rec.encoding_id()) {
+            continue;
+        }
+        if let Ok(subtable) = rec.subtable(cmap.offset_data()) {
+            if let CmapSubtable::Format4(_) | CmapSubtable::Format12(_) = subtable {
+                return Some(Map::new(subtable, i as u16, is_symbol));
             }
-            _ => {}
         }
     }
-    // Now simply search for any Unicode mapping, again in reverse.
-    for (i, rec) in records.iter().enumerate().rev() {
-        match (rec.platform_id(), rec.encoding_id()) {
-            (PlatformId::ISO, _)
-            | (PlatformId::Unicode, _)
-            | (PlatformId::Windows, ENCODING_MS_ID_UCS_4)
-            | (PlatformId::Windows, ENCODING_MS_UNICODE_CS) => {
-                if let Ok(subtable) = rec.subtable(cmap.offset_data()) {
-                    match subtable {
-                        CmapSubtable::Format4(_) | CmapSubtable::Format12(_) => {
-                            return Some(Map::new(subtable, i as u16, false));
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            _ => {}
+    None
+}
+
+/// Returns the subtable at the given index of the encoding records if it
+/// is supported.
+fn subtable_at<'a>(cmap: &Cmap<'a>, index: u16) -> Option<Map<'a>> {
+    let rec = cmap.encoding_records().get(index as usize)?;
+    if let Some(encoding) = legacy_encoding(rec.platform_id(), rec.encoding_id()) {
+        return legacy_map(cmap, rec, index, encoding);
+    }
+    let is_symbol = matches!(
+        (rec.platform_id(), rec.encoding_id()),
+        (PlatformId::Windows, ENCODING_MS_SYMBOL)
+    );
+    match rec.subtable(cmap.offset_data()).ok()? {
+        subtable @ (CmapSubtable::Format4(_) | CmapSubtable::Format12(_)) => {
+            Some(Map::new(subtable, index, is_symbol))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the legacy encoding for the given platform and encoding
+/// identifiers.
+fn legacy_encoding(platform_id: PlatformId, encoding_id: u16) -> Option<LegacyEncoding> {
+    match (platform_id, encoding_id) {
+        (PlatformId::Windows, ENCODING_MS_SHIFT_JIS)
+        | (PlatformId::Macintosh, ENCODING_MAC_JAPANESE) => Some(LegacyEncoding::ShiftJis),
+        (PlatformId::Windows, ENCODING_MS_BIG5)
+        | (PlatformId::Macintosh, ENCODING_MAC_TRADITIONAL_CHINESE) => Some(LegacyEncoding::Big5),
+        _ => None,
+    }
+}
+
+/// Searches for a subtable in a legacy CJK encoding, as found in older
+/// fonts without a Unicode mapping.
+fn find_legacy_subtable<'a>(cmap: &Cmap<'a>) -> Option<Map<'a>> {
+    for (i, rec) in cmap.encoding_records().iter().enumerate() {
+        let Some(encoding) = legacy_encoding(rec.platform_id(), rec.encoding_id()) else {
+            continue;
+        };
+        if let Some(map) = legacy_map(cmap, rec, i as u16, encoding) {
+            return Some(map);
         }
     }
     None
 }
 
+/// Creates a mapping for a subtable in a legacy encoding.
+fn legacy_map<'a>(
+    cmap: &Cmap<'a>,
+    rec: &cmap::EncodingRecord,
+    index: u16,
+    encoding: LegacyEncoding,
+) -> Option<Map<'a>> {
+    let data = cmap
+        .offset_data()
+        .split_off(rec.subtable_offset().to_u32() as usize);
+    if let Some(subtable) = data.and_then(HighByteMapping::new) {
+        return Some(Map::with_encoding(
+            Subtable::HighByte(subtable),
+            index,
+            encoding,
+        ));
+    }
+    match rec.subtable(cmap.offset_data()).ok()? {
+        subtable @ (CmapSubtable::Format4(_) | CmapSubtable::Format12(_)) => Some(
+            Map::with_encoding(Subtable::Standard(subtable), index, encoding),
+        ),
+        _ => None,
+    }
+}
+
 /// Searches for a format 14 subtable for mapping variant selector sequences.
 fn find_variant_selector_subtable<'a>(cmap: &Cmap<'a>) -> Option<(Cmap14<'a>, u16)> {
     const ENCODING_APPLE_ID_VARIANT_SELECTOR: u16 = 5;
@@ -202,3 +795,256 @@ fn find_variant_selector_subtable<'a>(cmap: &Cmap<'a>) -> Option<(Cmap14<'a>, u1
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{FontBuilder, SQUARE},
+        write::Writer,
+    };
+    use read_fonts::{FontRead, FontRef};
+
+    fn glyphs(charmap: &Charmap, ch: char) -> Option<Vec<u16>> {
+        charmap
+            .map_with_decomposition(ch)
+            .map(|glyphs| glyphs.glyphs().iter().map(|gid| gid.to_u16()).collect())
+    }
+
+    #[test]
+    fn map_with_decomposition() {
+        let mut builder = FontBuilder::new(1000);
+        let c = builder.add_glyph(500, SQUARE);
+        let c_cedilla = builder.add_glyph(500, SQUARE);
+        let e = builder.add_glyph(500, SQUARE);
+        let acute = builder.add_glyph(0, SQUARE);
+        builder
+            .map('c', c)
+            .map('\u{e7}', c_cedilla)
+            .map('e', e)
+            .map('\u{301}', acute);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let charmap = Charmap::new(&font);
+        // Directly mapped
+        assert_eq!(glyphs(&charmap, 'e'), Some(vec![3]));
+        // LATIN SMALL LETTER E WITH ACUTE
+        assert_eq!(glyphs(&charmap, '\u{e9}'), Some(vec![3, 4]));
+        // LATIN SMALL LETTER C WITH CEDILLA AND ACUTE recomposes to c with
+        // cedilla
+        assert_eq!(glyphs(&charmap, '\u{1e09}'), Some(vec![2, 4]));
+        // LATIN SMALL LETTER E WITH GRAVE has an unmapped component
+        assert_eq!(glyphs(&charmap, '\u{e8}'), None);
+        // No decomposition
+        assert_eq!(glyphs(&charmap, 'x'), None);
+    }
+
+    #[test]
+    fn map_hangul_with_decomposition() {
+        let mut builder = FontBuilder::new(1000);
+        let ga = builder.add_glyph(1000, SQUARE);
+        let h = builder.add_glyph(500, SQUARE);
+        let a = builder.add_glyph(500, SQUARE);
+        let n = builder.add_glyph(500, SQUARE);
+        // HANGUL SYLLABLE GA, CHOSEONG HIEUH, JUNGSEONG A and JONGSEONG NIEUN
+        builder
+            .map('\u{ac00}', ga)
+            .map('\u{1112}', h)
+            .map('\u{1161}', a)
+            .map('\u{11ab}', n);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let charmap = Charmap::new(&font);
+        // HANGUL SYLLABLE GAN recomposes to GA followed by NIEUN
+        assert_eq!(glyphs(&charmap, '\u{ac04}'), Some(vec![1, 4]));
+        // HANGUL SYLLABLE HA decomposes to HIEUH and A
+        assert_eq!(glyphs(&charmap, '\u{d558}'), Some(vec![2, 3]));
+        // HANGUL SYLLABLE HAN
+        assert_eq!(glyphs(&charmap, '\u{d55c}'), Some(vec![2, 3, 4]));
+        // HANGUL SYLLABLE GAG has an unmapped final consonant
+        assert_eq!(glyphs(&charmap, '\u{ac01}'), None);
+        // HANGUL SYLLABLE HIH is the last syllable and contains an unmapped
+        // vowel
+        assert_eq!(glyphs(&charmap, '\u{d7a3}'), None);
+    }
+
+    #[test]
+    fn compare_coverage() {
+        let build = |chars: &[char]| {
+            let mut builder = FontBuilder::new(1000);
+            for ch in chars {
+                let glyph_id = builder.add_glyph(500, SQUARE);
+                builder.map(*ch, glyph_id);
+            }
+            builder.build()
+        };
+        let primary = build(&['a', 'b', 'c']);
+        let fallback = build(&['b', 'c', 'd', 'e']);
+        let primary = FontRef::new(&primary).unwrap();
+        let fallback = FontRef::new(&fallback).unwrap();
+        let primary = Charmap::new(&primary);
+        let fallback = Charmap::new(&fallback);
+        assert_eq!(primary.codepoints(), vec![0x61, 0x62, 0x63]);
+        let comparison = primary.compare_coverage(&fallback);
+        assert_eq!(
+            comparison,
+            CoverageComparison {
+                common: 2,
+                only_self: 1,
+                only_other: 2,
+            }
+        );
+        assert_eq!(comparison.union(), 5);
+        assert!(comparison.other_adds_coverage());
+        assert!(!fallback.compare_coverage(&fallback).other_adds_coverage());
+    }
+
+    #[test]
+    fn map_batch() {
+        let mut builder = FontBuilder::new(1000);
+        for ch in ['a', 'b', 'c', 'x', 'y', '\u{1F600}'] {
+            let glyph_id = builder.add_glyph(500, SQUARE);
+            builder.map(ch, glyph_id);
+        }
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let charmap = Charmap::new(&font);
+        let codepoints: Vec<u32> = "abcxyz\u{1F600}cab b".chars().map(|ch| ch as u32).collect();
+        let mut glyph_ids = vec![GlyphId::new(0xFFFF); codepoints.len()];
+        charmap.map_batch(&codepoints, &mut glyph_ids);
+        let expected: Vec<GlyphId> = codepoints
+            .iter()
+            .map(|codepoint| charmap.map(*codepoint).unwrap_or(GlyphId::NOTDEF))
+            .collect();
+        assert_eq!(glyph_ids, expected);
+        assert_eq!(glyph_ids[5], GlyphId::NOTDEF);
+    }
+
+    #[test]
+    fn legacy_encoding() {
+        assert_eq!(LegacyEncoding::ShiftJis.encode('A' as u32), Some(0x41));
+        // HIRAGANA LETTER A
+        assert_eq!(LegacyEncoding::ShiftJis.encode(0x3042), Some(0x82A0));
+        // HALFWIDTH KATAKANA LETTER A
+        assert_eq!(LegacyEncoding::ShiftJis.encode(0xFF71), Some(0xB1));
+        // CJK UNIFIED IDEOGRAPH-4E00
+        assert_eq!(LegacyEncoding::Big5.encode(0x4E00), Some(0xA440));
+        // HANGUL SYLLABLE GA
+        assert_eq!(LegacyEncoding::Big5.encode(0xAC00), None);
+    }
+
+    #[test]
+    fn legacy_encoding_round_trip() {
+        for encoding in [LegacyEncoding::ShiftJis, LegacyEncoding::Big5] {
+            for &(codepoint, code) in encoding.table() {
+                assert_eq!(encoding.encode(codepoint as u32), Some(code));
+                // Codes that several codepoints map to decode to one of them.
+                let decoded = encoding.decode(code).unwrap();
+                assert_eq!(encoding.encode(decoded as u32), Some(code));
+            }
+            for byte in 0..0x80 {
+                assert_eq!(encoding.decode(byte), char::from_u32(byte as u32));
+            }
+        }
+        // YEN SIGN shares its code with REVERSE SOLIDUS, which the code decodes to
+        assert_eq!(LegacyEncoding::ShiftJis.encode(0xA5), Some(0x5C));
+        assert_eq!(LegacyEncoding::ShiftJis.decode(0x5C), Some('\\'));
+        // HALFWIDTH KATAKANA LETTER A and HIRAGANA LETTER A
+        assert_eq!(LegacyEncoding::ShiftJis.decode(0xB1), Some('\u{ff71}'));
+        assert_eq!(LegacyEncoding::ShiftJis.decode(0x82A0), Some('\u{3042}'));
+        // Unmapped single byte, trail byte and user defined codes
+        for code in [0x80, 0xA0, 0xFD, 0x8120, 0x82F2, 0xF040] {
+            assert_eq!(LegacyEncoding::ShiftJis.decode(code), None);
+        }
+        for code in [0x80, 0xA1, 0xA13F, 0xFEFE] {
+            assert_eq!(LegacyEncoding::Big5.decode(code), None);
+        }
+    }
+
+    #[test]
+    fn high_byte_mapping() {
+        let mut w = Writer::default();
+        w.u16(0);
+        // numTables
+        w.u16(1);
+        // Windows Shift-JIS encoding
+        w.u16(3);
+        w.u16(2);
+        w.u32(12);
+        // Format 2 subtable with a subheader for single byte codes and
+        // one for two byte codes with a first byte of 0x82.
+        w.u16(2);
+        w.u16(1050);
+        w.u16(0);
+        for byte in 0..=255u16 {
+            w.u16(if byte == 0x82 { 8 } else { 0 });
+        }
+        // firstCode, entryCount, idDelta, idRangeOffset
+        w.u16(0);
+        w.u16(256);
+        w.i16(0);
+        w.u16(10);
+        w.u16(0x9F);
+        w.u16(2);
+        w.i16(1);
+        w.u16(514);
+        for code in 0..=255u16 {
+            w.u16(if code == 0x41 { 5 } else { 0 });
+        }
+        w.u16(10);
+        w.u16(11);
+        let cmap = Cmap::read(FontData::new(&w.0)).unwrap();
+        let map = find_symbol_or_unicode_subtable(&cmap, MappingStrategy::default()).unwrap();
+        assert_eq!(map.encoding, Some(LegacyEncoding::ShiftJis));
+        assert_eq!(map.map('A' as u32), Some(GlyphId::new(5)));
+        // HIRAGANA LETTER SMALL A and HIRAGANA LETTER A
+        assert_eq!(map.map(0x3041), Some(GlyphId::new(11)));
+        assert_eq!(map.map(0x3042), Some(GlyphId::new(12)));
+        assert_eq!(map.map('B' as u32), None);
+        // HIRAGANA LETTER SMALL I is outside the subheader range
+        assert_eq!(map.map(0x3043), None);
+    }
+
+    #[test]
+    fn mapping_strategy() {
+        let mut w = Writer::default();
+        w.u16(0);
+        // numTables
+        w.u16(2);
+        // Windows symbol and UCS-4 encodings
+        w.u16(3);
+        w.u16(0);
+        w.u32(20);
+        w.u16(3);
+        w.u16(10);
+        w.u32(48);
+        // Format 12 subtables with a single group, mapping U+F041 to glyph 1
+        // and U+0041 to glyph 2.
+        for (codepoint, glyph_id) in [(0xF041, 1), (0x41, 2)] {
+            w.u16(12);
+            w.u16(0);
+            w.u32(28);
+            w.u32(0);
+            w.u32(1);
+            w.u32(codepoint);
+            w.u32(codepoint);
+            w.u32(glyph_id);
+        }
+        let cmap = Cmap::read(FontData::new(&w.0)).unwrap();
+        let map_a = |strategy| {
+            find_symbol_or_unicode_subtable(&cmap, strategy).and_then(|map| {
+                map.map('A' as u32)
+                    .or_else(|| map.map(map.adjust_symbol_pua('A' as u32)))
+            })
+        };
+        assert_eq!(map_a(MappingStrategy::PreferSymbol), Some(GlyphId::new(1)));
+        assert_eq!(
+            map_a(MappingStrategy::PreferFullRepertoire),
+            Some(GlyphId::new(2))
+        );
+        assert_eq!(map_a(MappingStrategy::PreferBmp), Some(GlyphId::new(2)));
+        assert_eq!(map_a(MappingStrategy::Subtable(0)), Some(GlyphId::new(1)));
+        assert_eq!(map_a(MappingStrategy::Subtable(1)), Some(GlyphId::new(2)));
+        assert_eq!(map_a(MappingStrategy::Subtable(2)), None);
+    }
+}
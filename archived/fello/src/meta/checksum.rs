@@ -0,0 +1,232 @@
+/*! Verification of table checksums.
+
+*/
+
+use alloc::vec::Vec;
+use read_fonts::{types::Tag, FontRef, TableProvider};
+
+/// Magic value used to compute the `checkSumAdjustment` field of the `head`
+/// table.
+///
+/// See <https://learn.microsoft.com/en-us/typography/opentype/spec/head>
+pub(crate) const CHECKSUM_ADJUSTMENT_MAGIC: u32 = 0xB1B0AFBA;
+
+/// Byte offset of the `checkSumAdjustment` field in the `head` table.
+pub(crate) const HEAD_CHECKSUM_ADJUSTMENT_OFFSET: usize = 8;
+
+/// Table whose computed checksum does not match the value stored in the
+/// table directory.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ChecksumMismatch {
+    /// Tag of the table.
+    pub tag: Tag,
+    /// Checksum recorded in the table directory.
+    pub expected: u32,
+    /// Checksum computed from the table data.
+    pub actual: u32,
+}
+
+/// Result of verifying the checksums of a font.
+#[derive(Clone, Default, Debug)]
+pub struct Checksums {
+    mismatches: Vec<ChecksumMismatch>,
+    missing: Vec<Tag>,
+    adjustment: Option<(u32, u32)>,
+}
+
+impl Checksums {
+    /// Returns true if all table checksums and the `head` checksum adjustment
+    /// are correct.
+    pub fn is_valid(&self) -> bool {
+        self.mismatches.is_empty() && self.missing.is_empty() && self.adjustment_matches()
+    }
+
+    /// Returns the set of tables with checksums that do not match the values
+    /// recorded in the table directory.
+    pub fn mismatches(&self) -> &[ChecksumMismatch] {
+        &self.mismatches
+    }
+
+    /// Returns the set of tables that are listed in the table directory but
+    /// reference data outside the bounds of the font.
+    pub fn missing(&self) -> &[Tag] {
+        &self.missing
+    }
+
+    /// Returns an iterator over the tags of all tables that are considered
+    /// corrupt, either due to a checksum mismatch or invalid bounds.
+    pub fn corrupt_tables(&self) -> impl Iterator<Item = Tag> + '_ + Clone {
+        self.mismatches
+            .iter()
+            .map(|mismatch| mismatch.tag)
+            .chain(self.missing.iter().copied())
+    }
+
+    /// Returns the expected and actual values of the `checkSumAdjustment`
+    /// field of the `head` table.
+    ///
+    /// Returns `None` if the font does not contain a `head` table.
+    pub fn adjustment(&self) -> Option<(u32, u32)> {
+        self.adjustment
+    }
+
+    /// Returns true if the `checkSumAdjustment` field of the `head` table
+    /// matches the computed value or if the `head` table is missing.
+    pub fn adjustment_matches(&self) -> bool {
+        self.adjustment
+            .map(|(expected, actual)| expected == actual)
+            .unwrap_or(true)
+    }
+}
+
+/// Verifies the checksum of each table in the font along with the
+/// `checkSumAdjustment` field of the `head` table.
+///
+/// The checksum of the `head` table is computed with the adjustment field
+/// treated as zero, as required by the specification.
+///
+/// Note that the checksum adjustment is only meaningful for fonts that
+/// are not members of a collection.
+pub fn validate_checksums(font: &FontRef) -> Checksums {
+    let mut result = Checksums::default();
+    let directory = &font.table_directory;
+    let records = directory.table_records();
+    // Begin the whole font sum with the words of the table directory itself.
+    let mut font_sum = directory.sfnt_version();
+    font_sum = font_sum
+        .wrapping_add(((directory.num_tables() as u32) << 16) | directory.search_range() as u32);
+    font_sum = font_sum
+        .wrapping_add(((directory.entry_selector() as u32) << 16) | directory.range_shift() as u32);
+    let head_tag = Tag::new(b"head");
+    for record in records {
+        let tag = record.tag();
+        font_sum = font_sum
+            .wrapping_add(u32::from_be_bytes(tag.to_be_bytes()))
+            .wrapping_add(record.checksum())
+            .wrapping_add(record.offset())
+            .wrapping_add(record.length());
+        let Some(data) = font.table_data(tag) else {
+            result.missing.push(tag);
+            continue;
+        };
+        let data = data.as_bytes();
+        let actual = if tag == head_tag {
+            head_checksum(data)
+        } else {
+            checksum(data)
+        };
+        font_sum = font_sum.wrapping_add(actual);
+        if actual != record.checksum() {
+            result.mismatches.push(ChecksumMismatch {
+                tag,
+                expected: record.checksum(),
+                actual,
+            });
+        }
+    }
+    if let Ok(head) = font.head() {
+        let expected = CHECKSUM_ADJUSTMENT_MAGIC.wrapping_sub(font_sum);
+        result.adjustment = Some((expected, head.checksum_adjustment()));
+    }
+    result
+}
+
+/// Computes the checksum of the given table data.
+///
+/// The data is treated as a sequence of big endian 32-bit integers and
+/// is implicitly padded with zeros to a multiple of four bytes.
+pub fn checksum(data: &[u8]) -> u32 {
+    let mut chunks = data.chunks_exact(4);
+    let mut sum = 0u32;
+    for chunk in &mut chunks {
+        sum = sum.wrapping_add(u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+    }
+    let rem = chunks.remainder();
+    if !rem.is_empty() {
+        let mut last = [0u8; 4];
+        last[..rem.len()].copy_from_slice(rem);
+        sum = sum.wrapping_add(u32::from_be_bytes(last));
+    }
+    sum
+}
+
+/// Computes the checksum of the `head` table with the `checkSumAdjustment`
+/// field treated as zero.
+fn head_checksum(data: &[u8]) -> u32 {
+    let sum = checksum(data);
+    match data.get(HEAD_CHECKSUM_ADJUSTMENT_OFFSET..HEAD_CHECKSUM_ADJUSTMENT_OFFSET + 4) {
+        Some(adjustment) => sum.wrapping_sub(u32::from_be_bytes([
+            adjustment[0],
+            adjustment[1],
+            adjustment[2],
+            adjustment[3],
+        ])),
+        None => sum,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksum_pads_with_zeros() {
+        assert_eq!(checksum(&[]), 0);
+        assert_eq!(checksum(&[0, 0, 0, 1, 0, 0, 0, 2]), 3);
+        assert_eq!(checksum(&[0, 0, 0, 1, 0x80]), 0x80000001);
+    }
+
+    #[test]
+    fn checksum_wraps() {
+        assert_eq!(checksum(&[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 2]), 1);
+    }
+
+    #[test]
+    fn head_checksum_ignores_adjustment() {
+        let mut head = [0u8; 16];
+        head[3] = 7;
+        head[8..12].copy_from_slice(&0x12345678u32.to_be_bytes());
+        assert_eq!(head_checksum(&head), 7);
+    }
+
+    #[test]
+    fn test_data_fonts() {
+        for data in [
+            font_test_data::SIMPLE_GLYF,
+            font_test_data::VAZIRMATN_VAR,
+            font_test_data::CMAP12_FONT1,
+        ] {
+            let font = FontRef::new(data).unwrap();
+            let checksums = validate_checksums(&font);
+            assert!(checksums.missing().is_empty());
+            assert!(checksums.mismatches().is_empty());
+            assert!(checksums.adjustment().is_some());
+            assert!(checksums.adjustment_matches());
+            assert!(checksums.is_valid());
+        }
+    }
+
+    #[test]
+    fn corrupt_table() {
+        let glyf = Tag::new(b"glyf");
+        let mut data = font_test_data::SIMPLE_GLYF.to_vec();
+        let (offset, expected) = FontRef::new(&data)
+            .unwrap()
+            .table_directory
+            .table_records()
+            .iter()
+            .find(|record| record.tag() == glyf)
+            .map(|record| (record.offset() as usize, record.checksum()))
+            .unwrap();
+        data[offset] ^= 0x40;
+        let font = FontRef::new(&data).unwrap();
+        let checksums = validate_checksums(&font);
+        assert!(!checksums.is_valid());
+        assert_eq!(checksums.corrupt_tables().collect::<Vec<_>>(), [glyf]);
+        let mismatch = checksums.mismatches()[0];
+        assert_eq!(mismatch.expected, expected);
+        assert_ne!(mismatch.actual, mismatch.expected);
+        // The corrupt table also invalidates the whole font checksum.
+        assert!(!checksums.adjustment_matches());
+    }
+}
@@ -0,0 +1,942 @@
+//! Typographic features supported by a font.
+//!
+//! [`Features`] lists the features in the `GSUB` and `GPOS` tables for each
+//! script along with the number of glyphs they apply to. This allows
+//! applications to enable controls for features such as small capitals
+//! (`smcp`, `c2sc`) or numeral styles (`onum`, `lnum`, `tnum`, `frac`)
+//! without inspecting the layout tables directly.
+//!
+//! [`LayoutStats`] summarizes the lookups behind each feature, providing a
+//! cheap structural fingerprint for comparing builds of a font.
+//!
+//! [`default_features`] lists the features that shapers enable by default
+//! for a script, which lets a feature UI distinguish features that are
+//! already on from optional ones.
+
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+use read_fonts::{
+    tables::{
+        gpos::{
+            CursivePosFormat1, MarkBasePosFormat1, MarkLigPosFormat1, MarkMarkPosFormat1, PairPos,
+            PositionSubtables, SinglePos,
+        },
+        gsub::{
+            AlternateSubstFormat1, LigatureSubstFormat1, MultipleSubstFormat1,
+            ReverseChainSingleSubstFormat1, SingleSubst, SubstitutionSubtables,
+        },
+        layout::{
+            ChainedSequenceContext, CoverageTable, FeatureList, FeatureRecord, LangSys, ScriptList,
+            SequenceContext,
+        },
+    },
+    types::Tag,
+    ReadError, TableProvider,
+};
+
+/// Tag of the default script.
+const DFLT: Tag = Tag::new(b"DFLT");
+
+/// Layout table that contains a feature.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum LayoutTable {
+    /// Glyph substitution table.
+    Gsub,
+    /// Glyph positioning table.
+    Gpos,
+}
+
+/// Feature available for a script.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Feature {
+    /// Tag of the feature.
+    pub tag: Tag,
+    /// Table that contains the feature.
+    pub table: LayoutTable,
+    /// Number of distinct glyphs that the feature applies to.
+    ///
+    /// This counts the glyphs in the coverage of the first input position
+    /// of each lookup referenced by the feature. A feature with a coverage
+    /// of zero has no effect.
+    pub coverage: u32,
+}
+
+/// Features available for a script.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScriptFeatures {
+    /// Tag of the script.
+    pub script: Tag,
+    /// Features for all language systems of the script, sorted by tag and
+    /// table.
+    pub features: Vec<Feature>,
+}
+
+impl ScriptFeatures {
+    /// Returns the features with the given tag.
+    pub fn get(&self, tag: Tag) -> impl Iterator<Item = &Feature> + '_ {
+        self.features
+            .iter()
+            .skip_while(move |feature| feature.tag < tag)
+            .take_while(move |feature| feature.tag == tag)
+    }
+}
+
+/// Collection of features supported by a font, grouped by script.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Features {
+    scripts: Vec<ScriptFeatures>,
+}
+
+impl Features {
+    /// Collects the features from the `GSUB` and `GPOS` tables of the given
+    /// font.
+    pub fn new<'a>(font: &impl TableProvider<'a>) -> Self {
+        let mut scripts: Vec<ScriptFeatures> = Vec::new();
+        for table in [LayoutTable::Gsub, LayoutTable::Gpos] {
+            let Ok(layout) = Layout::new(font, table) else {
+                continue;
+            };
+            let records = layout.feature_list.feature_records();
+            let mut coverages = vec![None; records.len()];
+            for (script, feature_indices) in layout.scripts() {
+                let entry = match scripts.binary_search_by(|entry| entry.script.cmp(&script)) {
+                    Ok(index) => &mut scripts[index],
+                    Err(index) => {
+                        scripts.insert(
+                            index,
+                            ScriptFeatures {
+                                script,
+                                features: Vec::new(),
+                            },
+                        );
+                        &mut scripts[index]
+                    }
+                };
+                for feature_index in feature_indices {
+                    let Some(record) = records.get(feature_index as usize) else {
+                        continue;
+                    };
+                    let coverage = *coverages[feature_index as usize]
+                        .get_or_insert_with(|| layout.feature_coverage(record));
+                    let feature = Feature {
+                        tag: record.feature_tag(),
+                        table,
+                        coverage,
+                    };
+                    let key = |feature: &Feature| (feature.tag, feature.table);
+                    match entry
+                        .features
+                        .binary_search_by(|probe| key(probe).cmp(&key(&feature)))
+                    {
+                        // Different language systems may select different
+                        // features with the same tag.
+                        Ok(index) => {
+                            let existing = &mut entry.features[index];
+                            existing.coverage = existing.coverage.max(coverage);
+                        }
+                        Err(index) => entry.features.insert(index, feature),
+                    }
+                }
+            }
+        }
+        Self { scripts }
+    }
+
+    /// Returns the features for each script, sorted by script tag.
+    pub fn scripts(&self) -> &[ScriptFeatures] {
+        &self.scripts
+    }
+
+    /// Returns the features for the given script.
+    ///
+    /// Falls back to the default script if the font has no entry for the
+    /// script, matching the selection made when shaping.
+    pub fn script(&self, script: Tag) -> Option<&ScriptFeatures> {
+        let find = |script: Tag| {
+            self.scripts
+                .binary_search_by(|entry| entry.script.cmp(&script))
+                .ok()
+                .map(|index| &self.scripts[index])
+        };
+        find(script).or_else(|| find(DFLT))
+    }
+
+    /// Returns true if the feature is available for the given script and
+    /// applies to at least one glyph.
+    pub fn supports(&self, script: Tag, feature: Tag) -> bool {
+        self.script(script)
+            .map(|entry| entry.get(feature).any(|feature| feature.coverage != 0))
+            .unwrap_or(false)
+    }
+}
+
+/// Features that are enabled by default for horizontal text in all scripts.
+///
+/// This includes the positioning features `kern`, `mark` and `mkmk` along
+/// with the required and standard ligatures.
+pub const COMMON_DEFAULT_FEATURES: &[Tag] = &[
+    Tag::new(b"abvm"),
+    Tag::new(b"blwm"),
+    Tag::new(b"calt"),
+    Tag::new(b"ccmp"),
+    Tag::new(b"clig"),
+    Tag::new(b"curs"),
+    Tag::new(b"dist"),
+    Tag::new(b"kern"),
+    Tag::new(b"liga"),
+    Tag::new(b"locl"),
+    Tag::new(b"mark"),
+    Tag::new(b"mkmk"),
+    Tag::new(b"rclt"),
+    Tag::new(b"rlig"),
+    Tag::new(b"rvrn"),
+];
+
+/// Positional forms applied by joining scripts such as Arabic and Syriac.
+const JOINING_FEATURES: &[Tag] = &[
+    Tag::new(b"isol"),
+    Tag::new(b"fina"),
+    Tag::new(b"fin2"),
+    Tag::new(b"fin3"),
+    Tag::new(b"medi"),
+    Tag::new(b"med2"),
+    Tag::new(b"init"),
+    Tag::new(b"mset"),
+];
+
+/// Basic and presentation forms applied by the Indic scripts.
+const INDIC_FEATURES: &[Tag] = &[
+    Tag::new(b"nukt"),
+    Tag::new(b"akhn"),
+    Tag::new(b"rphf"),
+    Tag::new(b"rkrf"),
+    Tag::new(b"pref"),
+    Tag::new(b"blwf"),
+    Tag::new(b"abvf"),
+    Tag::new(b"half"),
+    Tag::new(b"pstf"),
+    Tag::new(b"vatu"),
+    Tag::new(b"cjct"),
+    Tag::new(b"init"),
+    Tag::new(b"pres"),
+    Tag::new(b"abvs"),
+    Tag::new(b"blws"),
+    Tag::new(b"psts"),
+    Tag::new(b"haln"),
+];
+
+const KHMER_FEATURES: &[Tag] = &[
+    Tag::new(b"pref"),
+    Tag::new(b"blwf"),
+    Tag::new(b"abvf"),
+    Tag::new(b"pstf"),
+    Tag::new(b"cfar"),
+    Tag::new(b"pres"),
+    Tag::new(b"abvs"),
+    Tag::new(b"blws"),
+    Tag::new(b"psts"),
+];
+
+const MYANMAR_FEATURES: &[Tag] = &[
+    Tag::new(b"rphf"),
+    Tag::new(b"pref"),
+    Tag::new(b"blwf"),
+    Tag::new(b"pstf"),
+    Tag::new(b"pres"),
+    Tag::new(b"abvs"),
+    Tag::new(b"blws"),
+    Tag::new(b"psts"),
+];
+
+const HANGUL_FEATURES: &[Tag] = &[Tag::new(b"ljmo"), Tag::new(b"vjmo"), Tag::new(b"tjmo")];
+
+const TIBETAN_FEATURES: &[Tag] = &[Tag::new(b"abvs"), Tag::new(b"blws")];
+
+/// Returns the features that are enabled by default for the given
+/// OpenType script tag but are not in [`COMMON_DEFAULT_FEATURES`].
+///
+/// These are the script specific features applied by the shaping engine
+/// for the script, such as the positional forms of Arabic or the conjunct
+/// forms of Devanagari. Returns an empty slice for scripts without specific
+/// features, such as Latin.
+pub fn script_default_features(script: Tag) -> &'static [Tag] {
+    match &script.to_be_bytes() {
+        b"arab" | b"syrc" | b"mong" | b"nko " | b"phag" | b"mand" | b"mani" | b"adlm" | b"rohg"
+        | b"sogd" => JOINING_FEATURES,
+        b"deva" | b"dev2" | b"beng" | b"bng2" | b"guru" | b"gur2" | b"gujr" | b"gjr2" | b"orya"
+        | b"ory2" | b"taml" | b"tml2" | b"telu" | b"tel2" | b"knda" | b"knd2" | b"mlym"
+        | b"mlm2" => INDIC_FEATURES,
+        b"khmr" => KHMER_FEATURES,
+        b"mymr" | b"mym2" => MYANMAR_FEATURES,
+        b"hang" => HANGUL_FEATURES,
+        b"tibt" => TIBETAN_FEATURES,
+        _ => &[],
+    }
+}
+
+/// Returns an iterator over all features that are enabled by default for
+/// the given OpenType script tag.
+///
+/// This yields the [common](COMMON_DEFAULT_FEATURES) features followed by
+/// the [script specific](script_default_features) ones.
+pub fn default_features(script: Tag) -> impl Iterator<Item = Tag> + Clone {
+    COMMON_DEFAULT_FEATURES
+        .iter()
+        .chain(script_default_features(script))
+        .copied()
+}
+
+/// Returns true if the feature is enabled by default for the given
+/// OpenType script tag.
+pub fn is_default_feature(script: Tag, feature: Tag) -> bool {
+    default_features(script).any(|tag| tag == feature)
+}
+
+/// Structural summary of the lookups referenced by a feature.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct FeatureStats {
+    /// Tag of the feature.
+    pub tag: Tag,
+    /// Table that contains the feature.
+    pub table: LayoutTable,
+    /// Number of distinct lookups referenced by all records of the
+    /// feature.
+    pub lookup_count: u32,
+    /// Number of subtables in the referenced lookups.
+    pub subtable_count: u32,
+    /// Number of rules in the referenced lookups.
+    ///
+    /// A rule is a single substitution or positioning record, such as one
+    /// covered glyph, one ligature, one glyph or class pair or one
+    /// contextual sequence.
+    pub rule_count: u32,
+    /// Number of distinct glyphs covered by the first input position of
+    /// the referenced lookups.
+    pub coverage: u32,
+    /// Maximum number of glyphs, including backtrack and lookahead, that
+    /// a single rule matches.
+    pub max_context: u32,
+}
+
+/// Summary of the features and lookups in the `GSUB` and `GPOS` tables of
+/// a font.
+///
+/// Comparing the statistics of two builds of a font is a cheap way to
+/// detect changes in feature compilation and to track the growth of
+/// individual features.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct LayoutStats {
+    features: Vec<FeatureStats>,
+}
+
+impl LayoutStats {
+    /// Computes statistics for the `GSUB` and `GPOS` tables of the given
+    /// font.
+    pub fn new<'a>(font: &impl TableProvider<'a>) -> Self {
+        let mut features = Vec::new();
+        for table in [LayoutTable::Gsub, LayoutTable::Gpos] {
+            if let Ok(layout) = Layout::new(font, table) {
+                features.extend(layout.stats());
+            }
+        }
+        features.sort_by_key(|stats| (stats.tag, stats.table));
+        Self { features }
+    }
+
+    /// Returns the statistics for each feature, sorted by tag and table.
+    pub fn features(&self) -> &[FeatureStats] {
+        &self.features
+    }
+
+    /// Returns the statistics for the feature with the given tag in the
+    /// given table.
+    pub fn get(&self, tag: Tag, table: LayoutTable) -> Option<&FeatureStats> {
+        self.features
+            .binary_search_by(|stats| (stats.tag, stats.table).cmp(&(tag, table)))
+            .ok()
+            .map(|index| &self.features[index])
+    }
+
+    /// Returns the maximum context length of all features.
+    pub fn max_context(&self) -> u32 {
+        self.features
+            .iter()
+            .map(|stats| stats.max_context)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Script and feature lists of a layout table along with summaries of its
+/// lookups.
+struct Layout<'a> {
+    table: LayoutTable,
+    script_list: ScriptList<'a>,
+    feature_list: FeatureList<'a>,
+    /// Summaries of the lookups, indexed by lookup index.
+    lookups: Vec<LookupSummary<'a>>,
+}
+
+impl<'a> Layout<'a> {
+    fn new(font: &impl TableProvider<'a>, table: LayoutTable) -> Result<Self, ReadError> {
+        match table {
+            LayoutTable::Gsub => {
+                let gsub = font.gsub()?;
+                let lookups = gsub
+                    .lookup_list()?
+                    .lookups()
+                    .iter()
+                    .map(|lookup| {
+                        lookup
+                            .and_then(|lookup| lookup.subtables())
+                            .map(LookupSummary::from_gsub)
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                Ok(Self {
+                    table,
+                    script_list: gsub.script_list()?,
+                    feature_list: gsub.feature_list()?,
+                    lookups,
+                })
+            }
+            LayoutTable::Gpos => {
+                let gpos = font.gpos()?;
+                let lookups = gpos
+                    .lookup_list()?
+                    .lookups()
+                    .iter()
+                    .map(|lookup| {
+                        lookup
+                            .and_then(|lookup| lookup.subtables())
+                            .map(LookupSummary::from_gpos)
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                Ok(Self {
+                    table,
+                    script_list: gpos.script_list()?,
+                    feature_list: gpos.feature_list()?,
+                    lookups,
+                })
+            }
+        }
+    }
+
+    /// Returns each script with the indices of the features referenced by
+    /// all of its language systems.
+    fn scripts(&self) -> impl Iterator<Item = (Tag, BTreeSet<u16>)> + 'a {
+        let data = self.script_list.offset_data();
+        self.script_list
+            .script_records()
+            .iter()
+            .filter_map(move |record| {
+                let script = record.script(data).ok()?;
+                let mut features = BTreeSet::new();
+                if let Some(Ok(lang_sys)) = script.default_lang_sys() {
+                    lang_sys_features(&lang_sys, &mut features);
+                }
+                for lang_sys in script.lang_sys_records() {
+                    if let Ok(lang_sys) = lang_sys.lang_sys(script.offset_data()) {
+                        lang_sys_features(&lang_sys, &mut features);
+                    }
+                }
+                Some((record.script_tag(), features))
+            })
+    }
+
+    /// Returns the indices of the lookups referenced by the feature.
+    fn feature_lookups(&self, record: &FeatureRecord) -> impl Iterator<Item = u16> + 'a {
+        record
+            .feature(self.feature_list.offset_data())
+            .map(|feature| feature.lookup_list_indices())
+            .unwrap_or_default()
+            .iter()
+            .map(|index| index.get())
+    }
+
+    /// Returns the number of distinct glyphs covered by the first input
+    /// position of the lookups referenced by the feature.
+    fn feature_coverage(&self, record: &FeatureRecord) -> u32 {
+        let mut glyphs = BTreeSet::new();
+        for lookup_index in self.feature_lookups(record) {
+            if let Some(lookup) = self.lookups.get(lookup_index as usize) {
+                lookup.add_coverage(&mut glyphs);
+            }
+        }
+        glyphs.len() as u32
+    }
+
+    /// Returns statistics for each feature tag in the table.
+    fn stats(&self) -> Vec<FeatureStats> {
+        let mut features: BTreeMap<Tag, BTreeSet<u16>> = BTreeMap::new();
+        for record in self.feature_list.feature_records() {
+            features
+                .entry(record.feature_tag())
+                .or_default()
+                .extend(self.feature_lookups(record));
+        }
+        features
+            .into_iter()
+            .map(|(tag, lookups)| {
+                let mut stats = FeatureStats {
+                    tag,
+                    table: self.table,
+                    lookup_count: lookups.len() as u32,
+                    subtable_count: 0,
+                    rule_count: 0,
+                    coverage: 0,
+                    max_context: 0,
+                };
+                let mut glyphs = BTreeSet::new();
+                for lookup in lookups
+                    .into_iter()
+                    .filter_map(|index| self.lookups.get(index as usize))
+                {
+                    stats.subtable_count += lookup.subtable_count;
+                    stats.rule_count += lookup.rule_count;
+                    stats.max_context = stats.max_context.max(lookup.max_context);
+                    lookup.add_coverage(&mut glyphs);
+                }
+                stats.coverage = glyphs.len() as u32;
+                stats
+            })
+            .collect()
+    }
+}
+
+/// Structural summary of the subtables of a lookup.
+#[derive(Default)]
+struct LookupSummary<'a> {
+    subtable_count: u32,
+    rule_count: u32,
+    max_context: u32,
+    /// Coverage tables for the first input position of the subtables.
+    coverages: Vec<CoverageTable<'a>>,
+}
+
+impl<'a> LookupSummary<'a> {
+    fn from_gsub(subtables: SubstitutionSubtables<'a>) -> Self {
+        let mut summary = Self::default();
+        match subtables {
+            SubstitutionSubtables::Single(subtables) => summary.add(subtables.iter()),
+            SubstitutionSubtables::Multiple(subtables) => summary.add(subtables.iter()),
+            SubstitutionSubtables::Alternate(subtables) => summary.add(subtables.iter()),
+            SubstitutionSubtables::Ligature(subtables) => summary.add(subtables.iter()),
+            SubstitutionSubtables::Contextual(subtables) => summary.add(subtables.iter()),
+            SubstitutionSubtables::ChainContextual(subtables) => summary.add(subtables.iter()),
+            SubstitutionSubtables::Reverse(subtables) => summary.add(subtables.iter()),
+        }
+        summary
+    }
+
+    fn from_gpos(subtables: PositionSubtables<'a>) -> Self {
+        let mut summary = Self::default();
+        match subtables {
+            PositionSubtables::Single(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::Pair(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::Cursive(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::MarkToBase(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::MarkToLig(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::MarkToMark(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::Contextual(subtables) => summary.add(subtables.iter()),
+            PositionSubtables::ChainContextual(subtables) => summary.add(subtables.iter()),
+        }
+        summary
+    }
+
+    /// Adds the subtables of the lookup. Malformed subtables are counted
+    /// but contribute no rules or coverage.
+    fn add<T: Subtable<'a>>(&mut self, subtables: impl Iterator<Item = Result<T, ReadError>>) {
+        for subtable in subtables {
+            self.subtable_count += 1;
+            let Ok(subtable) = subtable else {
+                continue;
+            };
+            let (rules, context) = subtable.rules().unwrap_or_default();
+            self.rule_count += rules;
+            self.max_context = self.max_context.max(context);
+            if let Ok(coverage) = subtable.first_coverage() {
+                self.coverages.push(coverage);
+            }
+        }
+    }
+
+    /// Adds the glyphs covered by the first input position of the
+    /// subtables to the set.
+    fn add_coverage(&self, glyphs: &mut BTreeSet<u16>) {
+        for coverage in &self.coverages {
+            glyphs.extend(coverage.iter().map(|glyph_id| glyph_id.to_u16()));
+        }
+    }
+}
+
+/// Structure of a lookup subtable.
+///
+/// A rule is a single substitution or positioning record, such as one
+/// covered glyph, one ligature, one glyph or class pair or one contextual
+/// sequence.
+trait Subtable<'a> {
+    /// Returns the coverage table for the first input position.
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError>;
+
+    /// Returns the number of rules in the subtable and the maximum number
+    /// of glyphs matched by any of them.
+    fn rules(&self) -> Result<(u32, u32), ReadError>;
+}
+
+impl<'a> Subtable<'a> for SingleSubst<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            Self::Format1(subtable) => subtable.coverage(),
+            Self::Format2(subtable) => subtable.coverage(),
+        }
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        Ok((coverage_len(&self.first_coverage()?), 1))
+    }
+}
+
+impl<'a> Subtable<'a> for MultipleSubstFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        Ok((self.sequence_count() as u32, 1))
+    }
+}
+
+impl<'a> Subtable<'a> for AlternateSubstFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        Ok((self.alternate_set_count() as u32, 1))
+    }
+}
+
+impl<'a> Subtable<'a> for LigatureSubstFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let (mut rules, mut context) = (0, 0);
+        for set in self.ligature_sets().iter().flatten() {
+            for ligature in set.ligatures().iter().flatten() {
+                rules += 1;
+                context = context.max(ligature.component_count() as u32);
+            }
+        }
+        Ok((rules, context))
+    }
+}
+
+impl<'a> Subtable<'a> for ReverseChainSingleSubstFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let context = self.backtrack_glyph_count() as u32 + 1 + self.lookahead_glyph_count() as u32;
+        Ok((coverage_len(&self.coverage()?), context))
+    }
+}
+
+impl<'a> Subtable<'a> for SequenceContext<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            Self::Format1(subtable) => subtable.coverage(),
+            Self::Format2(subtable) => subtable.coverage(),
+            Self::Format3(subtable) => subtable.coverages().get(0),
+        }
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        // Rule glyph counts include the first input glyph, which rules in
+        // formats 1 and 2 match with the coverage table.
+        let (mut rules, mut context) = (0, 0);
+        match self {
+            Self::Format1(subtable) => {
+                for set in subtable.seq_rule_sets().iter().flatten().flatten() {
+                    for rule in set.seq_rules().iter().flatten() {
+                        rules += 1;
+                        context = context.max(rule.glyph_count() as u32);
+                    }
+                }
+            }
+            Self::Format2(subtable) => {
+                for set in subtable.class_seq_rule_sets().iter().flatten().flatten() {
+                    for rule in set.class_seq_rules().iter().flatten() {
+                        rules += 1;
+                        context = context.max(rule.glyph_count() as u32);
+                    }
+                }
+            }
+            Self::Format3(subtable) => return Ok((1, subtable.glyph_count() as u32)),
+        }
+        Ok((rules, context))
+    }
+}
+
+impl<'a> Subtable<'a> for ChainedSequenceContext<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            Self::Format1(subtable) => subtable.coverage(),
+            Self::Format2(subtable) => subtable.coverage(),
+            Self::Format3(subtable) => subtable.input_coverages().get(0),
+        }
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let len = |backtrack: u16, input: u16, lookahead: u16| {
+            backtrack as u32 + input as u32 + lookahead as u32
+        };
+        let (mut rules, mut context) = (0, 0);
+        match self {
+            Self::Format1(subtable) => {
+                for set in subtable.chained_seq_rule_sets().iter().flatten().flatten() {
+                    for rule in set.chained_seq_rules().iter().flatten() {
+                        rules += 1;
+                        context = context.max(len(
+                            rule.backtrack_glyph_count(),
+                            rule.input_glyph_count(),
+                            rule.lookahead_glyph_count(),
+                        ));
+                    }
+                }
+            }
+            Self::Format2(subtable) => {
+                let sets = subtable.chained_class_seq_rule_sets();
+                for set in sets.iter().flatten().flatten() {
+                    for rule in set.chained_class_seq_rules().iter().flatten() {
+                        rules += 1;
+                        context = context.max(len(
+                            rule.backtrack_glyph_count(),
+                            rule.input_glyph_count(),
+                            rule.lookahead_glyph_count(),
+                        ));
+                    }
+                }
+            }
+            Self::Format3(subtable) => {
+                let context = len(
+                    subtable.backtrack_glyph_count(),
+                    subtable.input_glyph_count(),
+                    subtable.lookahead_glyph_count(),
+                );
+                return Ok((1, context));
+            }
+        }
+        Ok((rules, context))
+    }
+}
+
+impl<'a> Subtable<'a> for SinglePos<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            Self::Format1(subtable) => subtable.coverage(),
+            Self::Format2(subtable) => subtable.coverage(),
+        }
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        Ok((coverage_len(&self.first_coverage()?), 1))
+    }
+}
+
+impl<'a> Subtable<'a> for PairPos<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        match self {
+            Self::Format1(subtable) => subtable.coverage(),
+            Self::Format2(subtable) => subtable.coverage(),
+        }
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let rules = match self {
+            Self::Format1(subtable) => subtable
+                .pair_sets()
+                .iter()
+                .flatten()
+                .map(|set| set.pair_value_count() as u32)
+                .sum(),
+            Self::Format2(subtable) => {
+                subtable.class1_count() as u32 * subtable.class2_count() as u32
+            }
+        };
+        Ok((rules, 2))
+    }
+}
+
+impl<'a> Subtable<'a> for CursivePosFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        Ok((self.entry_exit_count() as u32, 1))
+    }
+}
+
+impl<'a> Subtable<'a> for MarkBasePosFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.mark_coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let marks = self.mark_array().map_or(0, |array| array.mark_count());
+        let bases = self.base_array().map_or(0, |array| array.base_count());
+        Ok((marks as u32 + bases as u32, 1))
+    }
+}
+
+impl<'a> Subtable<'a> for MarkLigPosFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.mark_coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let marks = self.mark_array().map_or(0, |array| array.mark_count());
+        let ligatures = self
+            .ligature_array()
+            .map_or(0, |array| array.ligature_count());
+        Ok((marks as u32 + ligatures as u32, 1))
+    }
+}
+
+impl<'a> Subtable<'a> for MarkMarkPosFormat1<'a> {
+    fn first_coverage(&self) -> Result<CoverageTable<'a>, ReadError> {
+        self.mark1_coverage()
+    }
+
+    fn rules(&self) -> Result<(u32, u32), ReadError> {
+        let marks = self.mark1_array().map_or(0, |array| array.mark_count());
+        let bases = self.mark2_array().map_or(0, |array| array.mark2_count());
+        Ok((marks as u32 + bases as u32, 1))
+    }
+}
+
+/// Returns the number of glyphs in a coverage table.
+fn coverage_len(coverage: &CoverageTable) -> u32 {
+    coverage.iter().count() as u32
+}
+
+/// Adds the required and optional feature indices of a language system.
+fn lang_sys_features(lang_sys: &LangSys, features: &mut BTreeSet<u16>) {
+    let required = lang_sys.required_feature_index();
+    if required != 0xFFFF {
+        features.insert(required);
+    }
+    features.extend(lang_sys.feature_indices().iter().map(|index| index.get()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{subtables, FontBuilder, LayoutBuilder},
+        MetadataProvider,
+    };
+    use read_fonts::{types::GlyphId, FontRef};
+
+    /// Builds a font with a `GSUB` table for the Latin script containing
+    /// an `onum` feature that covers three glyphs and an `smcp` feature
+    /// that covers two.
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::with_squares(6);
+        let glyph = GlyphId::new;
+        let mut gsub = LayoutBuilder::new();
+        let onum = gsub.add_lookup(
+            1,
+            vec![subtables::single_subst_delta(
+                subtables::range_coverage(glyph(1), glyph(3)),
+                3,
+            )],
+        );
+        let smcp = gsub.add_lookup(
+            1,
+            vec![subtables::single_subst(
+                subtables::coverage(&[glyph(1), glyph(2)]),
+                &[glyph(5), glyph(6)],
+            )],
+        );
+        let onum = gsub.add_feature(Tag::new(b"onum"), &[onum]);
+        let smcp = gsub.add_feature(Tag::new(b"smcp"), &[smcp]);
+        gsub.add_script(Tag::new(b"latn"), &[onum, smcp]);
+        builder.add_table(Tag::new(b"GSUB"), gsub.build());
+        builder.build()
+    }
+
+    #[test]
+    fn script_features() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let features = font.features();
+        assert_eq!(features.scripts().len(), 1);
+        let latn = features.script(Tag::new(b"latn")).unwrap();
+        assert_eq!(
+            latn.features,
+            [
+                Feature {
+                    tag: Tag::new(b"onum"),
+                    table: LayoutTable::Gsub,
+                    coverage: 3,
+                },
+                Feature {
+                    tag: Tag::new(b"smcp"),
+                    table: LayoutTable::Gsub,
+                    coverage: 2,
+                },
+            ]
+        );
+        assert!(features.supports(Tag::new(b"latn"), Tag::new(b"smcp")));
+        assert!(!features.supports(Tag::new(b"latn"), Tag::new(b"c2sc")));
+        // No default script to fall back to
+        assert!(!features.supports(Tag::new(b"cyrl"), Tag::new(b"smcp")));
+    }
+
+    #[test]
+    fn layout_stats() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let stats = font.layout_stats();
+        assert_eq!(stats.features().len(), 2);
+        assert_eq!(
+            stats.get(Tag::new(b"onum"), LayoutTable::Gsub),
+            Some(&FeatureStats {
+                tag: Tag::new(b"onum"),
+                table: LayoutTable::Gsub,
+                lookup_count: 1,
+                subtable_count: 1,
+                rule_count: 3,
+                coverage: 3,
+                max_context: 1,
+            })
+        );
+        let smcp = stats.get(Tag::new(b"smcp"), LayoutTable::Gsub).unwrap();
+        assert_eq!(smcp.rule_count, 2);
+        assert_eq!(smcp.coverage, 2);
+        assert!(stats.get(Tag::new(b"smcp"), LayoutTable::Gpos).is_none());
+        assert_eq!(stats.max_context(), 1);
+    }
+
+    #[test]
+    fn default_feature_sets() {
+        let tag = |tag: &[u8; 4]| Tag::new(tag);
+        let latin: Vec<_> = default_features(tag(b"latn")).collect();
+        assert_eq!(latin, COMMON_DEFAULT_FEATURES);
+        assert!(is_default_feature(tag(b"latn"), tag(b"liga")));
+        assert!(!is_default_feature(tag(b"latn"), tag(b"smcp")));
+        assert!(!is_default_feature(tag(b"latn"), tag(b"init")));
+        assert!(is_default_feature(tag(b"arab"), tag(b"init")));
+        assert!(is_default_feature(tag(b"dev2"), tag(b"half")));
+        assert!(is_default_feature(tag(b"hang"), tag(b"ljmo")));
+    }
+}
@@ -0,0 +1,280 @@
+//! Mapping glyph identifiers to names.
+//!
+//! [`GlyphNames`] prefers the names in the `post` table, followed by the
+//! charset of a `CFF ` table. Glyphs without a stored name, such as those
+//! in fonts with a version 3.0 `post` table, receive a name synthesized
+//! from the character that maps to the glyph (`uniXXXX` or `uXXXXX`) or,
+//! failing that, from the glyph identifier (`glyphNNNNN`).
+
+use alloc::vec::Vec;
+use core::fmt::{self, Write as _};
+use read_fonts::{
+    tables::{
+        cff::Cff,
+        post::Post,
+        postscript::{dict, STANDARD_STRINGS},
+    },
+    types::{GlyphId, Version16Dot16},
+    FontData, TableProvider,
+};
+
+use super::charmap::Charmap;
+
+/// Name of a glyph along with its source.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GlyphName<'a> {
+    /// Name from the `post` table.
+    Post(&'a str),
+    /// Name from the charset of the `CFF ` table, encoded as Latin-1.
+    Cff(&'a [u8]),
+    /// Name synthesized from the character that maps to the glyph.
+    Unicode(u32),
+    /// Name synthesized from the glyph identifier.
+    Synthesized(GlyphId),
+}
+
+impl fmt::Display for GlyphName<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Post(name) => f.write_str(name),
+            Self::Cff(name) => {
+                for byte in name.iter() {
+                    f.write_char(*byte as char)?;
+                }
+                Ok(())
+            }
+            // Follows the conventions of the Adobe Glyph List
+            // specification.
+            Self::Unicode(codepoint) if *codepoint <= 0xFFFF => write!(f, "uni{codepoint:04X}"),
+            Self::Unicode(codepoint) => write!(f, "u{codepoint:X}"),
+            Self::Synthesized(glyph_id) => write!(f, "glyph{:05}", glyph_id.to_u16()),
+        }
+    }
+}
+
+/// Mapping of glyph identifiers to names.
+#[derive(Clone)]
+pub struct GlyphNames<'a> {
+    glyph_count: u16,
+    post: Option<Post<'a>>,
+    cff: Option<(Cff<'a>, Charset<'a>)>,
+    /// Lowest codepoint mapped to each glyph, sorted by glyph identifier.
+    /// Only populated when neither `post` nor `CFF ` provide names.
+    codepoints: Vec<(GlyphId, u32)>,
+}
+
+impl<'a> GlyphNames<'a> {
+    /// Creates a new glyph name mapping for the given font.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let glyph_count = font
+            .maxp()
+            .map(|maxp| maxp.num_glyphs())
+            .unwrap_or_default();
+        let post = font.post().ok().filter(|post| {
+            // Version 3.0 contains no names and version 2.5 is deprecated.
+            post.version() == Version16Dot16::VERSION_1_0
+                || post.version() == Version16Dot16::VERSION_2_0
+        });
+        let cff = font
+            .cff()
+            .ok()
+            .and_then(|cff| Some((cff.clone(), Charset::new(&cff)?)));
+        let mut codepoints = Vec::new();
+        if post.is_none() && cff.is_none() {
+            Charmap::new(font).for_each_mapping(|codepoint, glyph_id| {
+                if glyph_id != GlyphId::NOTDEF {
+                    codepoints.push((glyph_id, codepoint));
+                }
+            });
+            // Keep the lowest codepoint for each glyph.
+            codepoints.sort_unstable();
+            codepoints.dedup_by_key(|(glyph_id, _)| *glyph_id);
+        }
+        Self {
+            glyph_count,
+            post,
+            cff,
+            codepoints,
+        }
+    }
+
+    /// Returns the number of glyphs in the font.
+    pub fn len(&self) -> usize {
+        self.glyph_count as usize
+    }
+
+    /// Returns true if the font contains no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.glyph_count == 0
+    }
+
+    /// Returns the name for the given glyph identifier.
+    ///
+    /// Returns `None` if the identifier is not less than the number of
+    /// glyphs in the font.
+    pub fn get(&self, glyph_id: GlyphId) -> Option<GlyphName<'_>> {
+        if glyph_id.to_u16() >= self.glyph_count {
+            return None;
+        }
+        if let Some(name) = self
+            .post
+            .as_ref()
+            .and_then(|post| post.glyph_name(glyph_id))
+            .filter(|name| !name.is_empty())
+        {
+            return Some(GlyphName::Post(name));
+        }
+        if let Some(name) = self
+            .cff
+            .as_ref()
+            .and_then(|(cff, charset)| charset_name(cff, charset.sid(glyph_id)?))
+        {
+            return Some(GlyphName::Cff(name));
+        }
+        if let Ok(index) = self
+            .codepoints
+            .binary_search_by_key(&glyph_id, |(glyph_id, _)| *glyph_id)
+        {
+            return Some(GlyphName::Unicode(self.codepoints[index].1));
+        }
+        Some(GlyphName::Synthesized(glyph_id))
+    }
+
+    /// Returns an iterator over the names of all glyphs, in order of glyph
+    /// identifier.
+    pub fn iter(&self) -> impl Iterator<Item = (GlyphId, GlyphName<'_>)> + '_ {
+        (0..self.glyph_count).filter_map(|glyph_id| {
+            let glyph_id = GlyphId::new(glyph_id);
+            Some((glyph_id, self.get(glyph_id)?))
+        })
+    }
+}
+
+/// Mapping of glyph identifiers to string identifiers in a `CFF ` table.
+///
+/// See <https://adobe-type-tools.github.io/font-tech-notes/pdfs/5176.CFF.pdf#page=21>
+#[derive(Clone)]
+enum Charset<'a> {
+    /// Predefined ISOAdobe charset where the string identifier matches the
+    /// glyph identifier.
+    IsoAdobe,
+    /// Custom charset in one of the three formats. The data begins after
+    /// the format byte.
+    Custom(u8, FontData<'a>),
+}
+
+impl<'a> Charset<'a> {
+    /// Highest glyph identifier covered by the ISOAdobe charset.
+    const ISO_ADOBE_LAST_GLYPH: u16 = 228;
+
+    fn new(cff: &Cff<'a>) -> Option<Self> {
+        let top_dict = cff.top_dicts().get(0).ok()?;
+        // A missing charset operator selects the ISOAdobe charset.
+        let mut offset = 0;
+        for entry in dict::entries(top_dict, None) {
+            if let Ok(dict::Entry::Charset(charset_offset)) = entry {
+                offset = charset_offset;
+            }
+        }
+        match offset {
+            0 => Some(Self::IsoAdobe),
+            // The predefined expert charsets are not supported.
+            1 | 2 => None,
+            _ => {
+                let data = cff.offset_data().split_off(offset)?;
+                let format = data.read_at::<u8>(0).ok()?;
+                Some(Self::Custom(format, data.split_off(1)?))
+            }
+        }
+    }
+
+    /// Returns the string identifier for the name of the glyph.
+    fn sid(&self, glyph_id: GlyphId) -> Option<u16> {
+        let glyph_id = glyph_id.to_u16();
+        if glyph_id == 0 {
+            // The notdef glyph is omitted from all charsets.
+            return Some(0);
+        }
+        match self {
+            Self::IsoAdobe => (glyph_id <= Self::ISO_ADOBE_LAST_GLYPH).then_some(glyph_id),
+            Self::Custom(0, data) => data.read_at::<u16>((glyph_id as usize - 1) * 2).ok(),
+            Self::Custom(format @ (1 | 2), data) => {
+                // Ranges of consecutive string identifiers starting at
+                // glyph 1, with an 8 or 16-bit count of additional glyphs.
+                let record_len = if *format == 1 { 3 } else { 4 };
+                let mut first_glyph = 1u16;
+                for record in data.as_bytes().chunks_exact(record_len) {
+                    let first_sid = u16::from_be_bytes([record[0], record[1]]);
+                    let left = if *format == 1 {
+                        record[2] as u16
+                    } else {
+                        u16::from_be_bytes([record[2], record[3]])
+                    };
+                    if glyph_id - first_glyph <= left {
+                        return first_sid.checked_add(glyph_id - first_glyph);
+                    }
+                    first_glyph = first_glyph.checked_add(left)?.checked_add(1)?;
+                    if first_glyph > glyph_id {
+                        return None;
+                    }
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the string for the given string identifier, which is either one
+/// of the standard strings or an entry in the string index.
+fn charset_name<'a>(cff: &Cff<'a>, sid: u16) -> Option<&'a [u8]> {
+    let sid = sid as usize;
+    match STANDARD_STRINGS.get(sid) {
+        Some(name) => Some(name.as_bytes()),
+        None => cff.strings().get(sid - STANDARD_STRINGS.len()).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::FontBuilder, MetadataProvider};
+    use alloc::string::ToString;
+    use read_fonts::FontRef;
+
+    #[test]
+    fn charset_names() {
+        let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
+        let names = font.glyph_names();
+        assert_eq!(names.get(GlyphId::new(0)).unwrap().to_string(), ".notdef");
+        assert!(names.get(GlyphId::new(names.len() as u16)).is_none());
+        assert_eq!(names.iter().count(), names.len());
+    }
+
+    #[test]
+    fn fallback_names() {
+        let mut builder = FontBuilder::with_squares(3);
+        builder.map('a', GlyphId::new(1));
+        builder.map('A', GlyphId::new(1));
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let names = font.glyph_names();
+        assert_eq!(names.get(GlyphId::new(1)), Some(GlyphName::Unicode(0x41)));
+        assert_eq!(
+            names.get(GlyphId::new(2)),
+            Some(GlyphName::Synthesized(GlyphId::new(2)))
+        );
+        assert!(names.get(GlyphId::new(3)).is_none());
+    }
+
+    #[test]
+    fn synthesized_names() {
+        assert_eq!(GlyphName::Unicode(0x41).to_string(), "uni0041");
+        assert_eq!(GlyphName::Unicode(0x1F600).to_string(), "u1F600");
+        assert_eq!(
+            GlyphName::Synthesized(GlyphId::new(42)).to_string(),
+            "glyph00042"
+        );
+        assert_eq!(GlyphName::Cff(b"A\xE9").to_string(), "A\u{e9}");
+    }
+}
@@ -9,6 +9,14 @@ use read_fonts::{
 
 use core::fmt;
 
+use super::{
+    charmap::LegacyEncoding,
+    legacy_encoding::{
+        MAC_CENTRAL_EUROPEAN, MAC_CROATIAN, MAC_CYRILLIC, MAC_GREEK, MAC_ICELANDIC, MAC_ROMAN,
+        MAC_ROMANIAN, MAC_TURKISH,
+    },
+};
+
 pub type StringId = read_fonts::types::NameId;
 
 /// String containing a name or other font metadata in a specific language.
@@ -51,13 +59,59 @@ impl<'a> LocalizedString<'a> {
     }
 
     /// Returns the encoded string.
+    ///
+    /// Strings for the Macintosh platform are decoded from the Mac OS
+    /// Roman, Icelandic, Turkish, Croatian, Romanian, Greek, Cyrillic,
+    /// Central European, Japanese and traditional Chinese script encodings.
     pub fn string(&self) -> Option<Encoded<'a>> {
+        let data = self.name.string_data();
+        if self.record.platform_id() == 1 {
+            if let Some(encoding) =
+                MacEncoding::new(self.record.encoding_id(), self.record.language_id())
+            {
+                let start = self.record.string_offset().to_u32() as usize;
+                let end = start + self.record.length() as usize;
+                let bytes = data.as_bytes().get(start..end)?;
+                return Some(Encoded(EncodedInner::Mac(bytes, encoding)));
+            }
+        }
         Some(Encoded(EncodedInner::Encoded(
-            self.record.string(self.name.string_data()).ok()?,
+            self.record.string(data).ok()?,
         )))
     }
 }
 
+/// Legacy script encoding of a Macintosh platform string.
+#[derive(Copy, Clone)]
+enum MacEncoding {
+    /// Single byte encoding with mappings for the codes 0x80 to 0xFF.
+    SingleByte(&'static [u16; 128]),
+    ShiftJis,
+    Big5,
+}
+
+impl MacEncoding {
+    fn new(encoding_id: u16, language_id: u16) -> Option<Self> {
+        Some(match encoding_id {
+            // Regional variants of the Roman script are selected by
+            // language.
+            0 => Self::SingleByte(match language_id {
+                15 => &MAC_ICELANDIC,
+                17 => &MAC_TURKISH,
+                18 => &MAC_CROATIAN,
+                37 => &MAC_ROMANIAN,
+                _ => &MAC_ROMAN,
+            }),
+            1 => Self::ShiftJis,
+            2 => Self::Big5,
+            6 => Self::SingleByte(&MAC_GREEK),
+            7 => Self::SingleByte(&MAC_CYRILLIC),
+            29 => Self::SingleByte(&MAC_CENTRAL_EUROPEAN),
+            _ => return None,
+        })
+    }
+}
+
 /// Representation of the encoded data in a localized string.
 #[derive(Clone)]
 pub struct Encoded<'a>(EncodedInner<'a>);
@@ -66,6 +120,7 @@ pub struct Encoded<'a>(EncodedInner<'a>);
 enum EncodedInner<'a> {
     Str(&'a str),
     Encoded(NameString<'a>),
+    Mac(&'a [u8], MacEncoding),
 }
 
 impl<'a> Encoded<'a> {
@@ -74,9 +129,34 @@ impl<'a> Encoded<'a> {
         let inner = match &self.0 {
             EncodedInner::Str(s) => CharsInner::Str(s.chars()),
             EncodedInner::Encoded(s) => CharsInner::Encoded(s.chars()),
+            EncodedInner::Mac(data, encoding) => CharsInner::Mac(*data, *encoding),
         };
         Chars { inner }
     }
+
+    /// Returns an iterator over the string encoded as UTF-16 code units.
+    pub fn encode_utf16(&self) -> impl Iterator<Item = u16> + 'a + Clone {
+        self.chars().flat_map(|ch| {
+            let mut buf = [0; 2];
+            let len = ch.encode_utf16(&mut buf).len();
+            buf.into_iter().take(len)
+        })
+    }
+
+    /// Writes the string to the given formatter or buffer without
+    /// allocating.
+    pub fn write_to(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        match &self.0 {
+            EncodedInner::Str(s) => w.write_str(s),
+            _ => self.chars().try_for_each(|ch| w.write_char(ch)),
+        }
+    }
+}
+
+impl fmt::Display for Encoded<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
 }
 
 impl PartialEq for Encoded<'_> {
@@ -105,6 +185,12 @@ impl PartialEq<&str> for Encoded<'_> {
     }
 }
 
+impl PartialEq<str> for Encoded<'_> {
+    fn eq(&self, other: &str) -> bool {
+        self.chars().eq(other.chars())
+    }
+}
+
 impl PartialOrd<&str> for Encoded<'_> {
     fn partial_cmp(&self, other: &&str) -> Option<core::cmp::Ordering> {
         Some(self.chars().cmp(other.chars()))
@@ -121,6 +207,7 @@ pub struct Chars<'a> {
 enum CharsInner<'a> {
     Str(core::str::Chars<'a>),
     Encoded(CharIter<'a>),
+    Mac(&'a [u8], MacEncoding),
 }
 
 impl<'a> Iterator for Chars<'a> {
@@ -130,10 +217,53 @@ impl<'a> Iterator for Chars<'a> {
         match &mut self.inner {
             CharsInner::Str(iter) => iter.next(),
             CharsInner::Encoded(iter) => iter.next(),
+            CharsInner::Mac(data, encoding) => next_mac_char(data, *encoding),
         }
     }
 }
 
+/// Decodes the next character of a Macintosh platform string and advances
+/// the data past it.
+fn next_mac_char(data: &mut &[u8], encoding: MacEncoding) -> Option<char> {
+    let (&byte, rest) = data.split_first()?;
+    *data = rest;
+    if byte < 0x80 {
+        return Some(byte as char);
+    }
+    let (encoding, code) = match encoding {
+        MacEncoding::SingleByte(table) => {
+            return Some(
+                char::from_u32(table[byte as usize - 0x80] as u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+            );
+        }
+        // Half-width katakana are single byte codes.
+        MacEncoding::ShiftJis if (0xA1..=0xDF).contains(&byte) => {
+            (LegacyEncoding::ShiftJis, byte as u16)
+        }
+        // Bytes that cannot start a two byte code are replaced on their own
+        // so that the following byte is not consumed.
+        MacEncoding::ShiftJis if !matches!(byte, 0x81..=0x9F | 0xE0..=0xFC) => {
+            return Some(char::REPLACEMENT_CHARACTER);
+        }
+        MacEncoding::Big5 if byte == 0x80 || byte == 0xFF => {
+            return Some(char::REPLACEMENT_CHARACTER);
+        }
+        MacEncoding::ShiftJis | MacEncoding::Big5 => {
+            let Some((&trail, rest)) = data.split_first() else {
+                return Some(char::REPLACEMENT_CHARACTER);
+            };
+            *data = rest;
+            let encoding = match encoding {
+                MacEncoding::ShiftJis => LegacyEncoding::ShiftJis,
+                _ => LegacyEncoding::Big5,
+            };
+            (encoding, u16::from_be_bytes([byte, trail]))
+        }
+    };
+    Some(encoding.decode(code).unwrap_or(char::REPLACEMENT_CHARACTER))
+}
+
 /// Collection of informational strings.
 #[derive(Clone)]
 pub struct InfoStrings<'a> {
@@ -172,6 +302,63 @@ impl<'a> InfoStrings<'a> {
     pub fn iter(&self) -> Iter<'a> {
         self.clone().into_iter()
     }
+
+    /// Returns the string with the given identifier that best matches the
+    /// BCP 47 language tags, which are given in order of preference.
+    ///
+    /// For each language in turn, prefers an exact match and then a string
+    /// with the same primary language subtag. Falls back to English and
+    /// finally to the first string with the identifier. Tags are compared
+    /// case insensitively.
+    pub fn find(&self, id: StringId, languages: &[&str]) -> Option<LocalizedString<'a>> {
+        let mut best: Option<(usize, LocalizedString<'a>)> = None;
+        for string in self.iter().filter(|string| string.id() == id) {
+            if string.string().is_none() {
+                continue;
+            }
+            let score = language_score(string.language(), languages);
+            if best.as_ref().map_or(true, |best| score > best.0) {
+                best = Some((score, string));
+            }
+        }
+        best.map(|best| best.1)
+    }
+}
+
+/// Ranks how well a string language satisfies the language preferences,
+/// with higher values for better matches.
+fn language_score(language: Option<Encoded>, languages: &[&str]) -> usize {
+    let Some(language) = language else {
+        return 0;
+    };
+    let full = |tag: &str| eq_ignore_case(language.chars(), tag.chars());
+    let primary = |tag: &str| {
+        let subtag = tag.split('-').next().unwrap_or_default();
+        eq_ignore_case(language.chars().take_while(|ch| *ch != '-'), subtag.chars())
+    };
+    for (i, tag) in languages.iter().enumerate() {
+        // Every preference ranks above the English fallbacks and above all
+        // later preferences.
+        let base = 3 + (languages.len() - i) * 2;
+        if full(tag) {
+            return base + 1;
+        }
+        if primary(tag) {
+            return base;
+        }
+    }
+    if full("en-US") {
+        2
+    } else if primary("en") {
+        1
+    } else {
+        0
+    }
+}
+
+fn eq_ignore_case(a: impl Iterator<Item = char>, b: impl Iterator<Item = char>) -> bool {
+    a.map(|ch| ch.to_ascii_lowercase())
+        .eq(b.map(|ch| ch.to_ascii_lowercase()))
 }
 
 /// Iterator over a collection of informational strings.
@@ -564,3 +751,66 @@ const LANGUAGE_ID_TO_BCP47: &[(u16, &str)] = &[
     (0x500a, "es-PR"),        //Spanish
     (0x540a, "es-US"),        //Spanish
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{string::String, vec::Vec};
+
+    fn decode(data: &[u8], encoding: MacEncoding) -> String {
+        let mut data = data;
+        core::iter::from_fn(|| next_mac_char(&mut data, encoding)).collect()
+    }
+
+    #[test]
+    fn mac_roman_round_trip() {
+        let encoding = MacEncoding::new(0, 0).unwrap();
+        let bytes: Vec<u8> = (0..=255).collect();
+        let decoded = decode(&bytes, encoding);
+        assert_eq!(decoded.chars().count(), 256);
+        // Every byte maps to a distinct character so each one can be
+        // recovered from the decoded string.
+        for (byte, ch) in decoded.chars().enumerate() {
+            let encoded = if (ch as u32) < 0x80 {
+                ch as usize
+            } else {
+                MAC_ROMAN
+                    .iter()
+                    .position(|mapped| *mapped as u32 == ch as u32)
+                    .unwrap()
+                    + 0x80
+            };
+            assert_eq!(encoded, byte);
+        }
+        assert_eq!(
+            decode(b"Caf\x8E \xA9 \xDB \xF0", encoding),
+            "Caf\u{e9} \u{a9} \u{20ac} \u{f8ff}"
+        );
+        // Icelandic selects a regional variant
+        let icelandic = MacEncoding::new(0, 15).unwrap();
+        assert_eq!(decode(b"\xA0\xDC", icelandic), "\u{dd}\u{d0}");
+    }
+
+    #[test]
+    fn mac_japanese() {
+        let encoding = MacEncoding::new(1, 11).unwrap();
+        // HIRAGANA LETTER A and HALFWIDTH KATAKANA LETTER A
+        assert_eq!(decode(b"\x82\xA0\xB1A\\", encoding), "\u{3042}\u{ff71}A\\");
+        // Bytes that cannot start a code are replaced without consuming the
+        // following byte.
+        assert_eq!(
+            decode(b"\x80A\xA0B\xFDC", encoding),
+            "\u{fffd}A\u{fffd}B\u{fffd}C"
+        );
+        // Unmapped two byte code and truncated code
+        assert_eq!(decode(b"\x81\x20\x82", encoding), "\u{fffd}\u{fffd}");
+    }
+
+    #[test]
+    fn mac_traditional_chinese() {
+        let encoding = MacEncoding::new(2, 19).unwrap();
+        // CJK UNIFIED IDEOGRAPH-4E00
+        assert_eq!(decode(b"\xA4\x40", encoding), "\u{4e00}");
+        assert_eq!(decode(b"\x80A\xFEZ", encoding), "\u{fffd}A\u{fffd}");
+    }
+}
@@ -22,21 +22,50 @@
 //! as an argument for this parameter.
 //!
 
+use alloc::vec::Vec;
 use read_fonts::{
-    tables::{glyf::Glyf, hmtx::LongMetric, hvar::Hvar, loca::Loca, os2::SelectionFlags},
-    types::{BigEndian, GlyphId},
+    tables::{
+        glyf::Glyf,
+        hmtx::LongMetric,
+        hvar::Hvar,
+        loca::Loca,
+        mvar::tags::*,
+        os2::{Os2, SelectionFlags},
+        variations::{DeltaSetIndex, ItemVariationStore},
+    },
+    types::{BigEndian, Fixed, GlyphId, Tag},
     TableProvider,
 };
 
 use crate::{NormalizedCoord, NormalizedCoords, Size};
+#[cfg(feature = "scale")]
+use {
+    super::charmap::Charmap,
+    crate::scale::{self, glyf},
+    core::cell::RefCell,
+    read_fonts::types::Pen,
+};
 
-pub type BoundingBox = read_fonts::types::BoundingBox<f32>;
+/// Minimum and maximum extents of a rectangular region.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoundingBox {
+    /// Minimum extent in the x direction, the left side of the region.
+    pub x_min: f32,
+    /// Minimum extent in the y direction, the bottom of the region.
+    pub y_min: f32,
+    /// Maximum extent in the x direction, the right side of the region.
+    pub x_max: f32,
+    /// Maximum extent in the y direction, the top of the region.
+    pub y_max: f32,
+}
 
 /// Metrics for a text decoration.
 ///
 /// This represents the suggested offset and thickness of an underline
 /// or strikeout text decoration.
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Decoration {
     /// Offset to the top of the decoration from the baseline.
     pub offset: f32,
@@ -60,11 +89,23 @@ pub struct Decoration {
 ///
 /// For variable fonts, deltas are computed using the  [MVAR](https://learn.microsoft.com/en-us/typography/opentype/spec/MVAR)
 /// table.
+///
+/// When the OS/2 table doesn't provide the `cap_height` or `x_height`, they are
+/// `None`. With the `scale` feature, [`Metrics::measure_missing_heights`]
+/// measures them from the flat tops of the outlines of "H" and "x"
+/// respectively.
 #[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metrics {
     /// Number of font design units per em unit.
+    ///
+    /// When the `head` table is missing or specifies zero, this is 1000 for
+    /// fonts with PostScript outlines and 2048 otherwise.
     pub units_per_em: u16,
     /// Number of glyphs in the font.
+    ///
+    /// This is the count from the `maxp` table, clamped to the number of
+    /// glyphs in the `loca` table for fonts with TrueType outlines.
     pub glyph_count: u16,
     /// True if the font is not proportionally spaced.
     pub is_monospace: bool,
@@ -82,6 +123,12 @@ pub struct Metrics {
     /// Distance from the baseline to the top of the lowercase "x" or
     /// similar character.
     pub x_height: Option<f32>,
+    /// True if `cap_height` was measured from the outline of "H" rather
+    /// than read from the OS/2 table.
+    pub cap_height_measured: bool,
+    /// True if `x_height` was measured from the outline of "x" rather than
+    /// read from the OS/2 table.
+    pub x_height_measured: bool,
     /// Average width of all non-zero width characters in the font.
     pub average_width: Option<f32>,
     /// Maximum advance width of all characters in the font.
@@ -104,9 +151,8 @@ impl Metrics {
         size: Size,
         coords: NormalizedCoords<'a>,
     ) -> Self {
-        let head = font.head();
         let mut metrics = Metrics {
-            units_per_em: head.map(|head| head.units_per_em()).unwrap_or_default(),
+            units_per_em: units_per_em(font),
             ..Default::default()
         };
         let coords = coords.inner();
@@ -119,9 +165,7 @@ impl Metrics {
                 y_max: head.y_max() as f32 * scale,
             });
         }
-        if let Ok(maxp) = font.maxp() {
-            metrics.glyph_count = maxp.num_glyphs();
-        }
+        metrics.glyph_count = glyph_count(font);
         if let Ok(post) = font.post() {
             metrics.is_monospace = post.is_fixed_pitch() != 0;
             metrics.italic_angle = post.italic_angle().to_f64() as f32;
@@ -134,31 +178,11 @@ impl Metrics {
         if let Ok(hhea) = &hhea {
             metrics.max_width = Some(hhea.advance_width_max().to_u16() as f32 * scale);
         }
-        // Choosing proper line metrics is a challenge due to the changing
-        // spec, backward compatibility and broken fonts.
-        //
-        // We use the same strategy as FreeType:
-        // 1. Use the OS/2 metrics if the table exists and the USE_TYPO_METRICS
-        //    flag is set.
-        // 2. Otherwise, use the hhea metrics.
-        // 3. If hhea metrics are zero and the OS/2 table exists:
-        //    3a. Use the typo metrics if they are non-zero
-        //    3b. Otherwise, use the win metrics
-        //
-        // See: https://github.com/freetype/freetype/blob/5c37b6406258ec0d7ab64b8619c5ea2c19e3c69a/src/sfnt/sfobjs.c#L1311
-        let os2 = font.os2().ok();
-        let mut used_typo_metrics = false;
-        if let Some(os2) = &os2 {
-            const USE_TYPO_METRICS: u16 = 1 << 7;
-            if os2
-                .fs_selection()
-                .contains(SelectionFlags::USE_TYPO_METRICS)
-            {
-                metrics.ascent = os2.s_typo_ascender() as f32 * scale;
-                metrics.descent = os2.s_typo_descender() as f32 * scale;
-                metrics.leading = os2.s_typo_line_gap() as f32 * scale;
-                used_typo_metrics = true;
-            }
+        let [ascent, descent, leading] = line_metrics(font);
+        metrics.ascent = ascent as f32 * scale;
+        metrics.descent = descent as f32 * scale;
+        metrics.leading = leading as f32 * scale;
+        if let Ok(os2) = font.os2() {
             metrics.average_width = Some(os2.x_avg_char_width() as f32 * scale);
             metrics.cap_height = os2.s_cap_height().map(|v| v as f32 * scale);
             metrics.x_height = os2.sx_height().map(|v| v as f32 * scale);
@@ -167,57 +191,401 @@ impl Metrics {
                 thickness: os2.y_strikeout_size() as f32 * scale,
             });
         }
-        if !used_typo_metrics {
-            if let Ok(hhea) = font.hhea() {
-                metrics.ascent = hhea.ascender().to_i16() as f32 * scale;
-                metrics.descent = hhea.descender().to_i16() as f32 * scale;
-                metrics.leading = hhea.line_gap().to_i16() as f32 * scale;
+        if !coords.is_empty() {
+            if let Some(deltas) = MetricDeltas::new(font) {
+                deltas.apply(&mut metrics, coords, scale);
             }
-            if metrics.ascent == 0.0 && metrics.descent == 0.0 {
-                if let Some(os2) = &os2 {
-                    if os2.s_typo_ascender() != 0 || os2.s_typo_descender() != 0 {
-                        metrics.ascent = os2.s_typo_ascender() as f32 * scale;
-                        metrics.descent = os2.s_typo_descender() as f32 * scale;
-                        metrics.leading = os2.s_typo_line_gap() as f32 * scale;
-                    } else {
-                        metrics.ascent = os2.us_win_ascent() as f32 * scale;
-                        // Win descent is always positive while other descent values are negative. Negate it
-                        // to ensure we return consistent metrics.
-                        metrics.descent = -(os2.us_win_descent() as f32 * scale);
-                    }
+        }
+        metrics
+    }
+
+    /// Creates new metrics for the given font and size at each of the
+    /// given locations in variation space.
+    ///
+    /// This produces the same results as calling [`Metrics::new`] for each
+    /// location but only parses the font tables and the
+    /// [MVAR](https://learn.microsoft.com/en-us/typography/opentype/spec/MVAR)
+    /// item variation store once, which is useful when previewing many
+    /// instances of a variable font.
+    pub fn new_multi<'a>(
+        font: &impl TableProvider<'a>,
+        size: Size,
+        locations: &[NormalizedCoords<'a>],
+    ) -> Vec<Self> {
+        let base = Self::new(font, size, NormalizedCoords::default());
+        let scale = size.linear_scale(base.units_per_em);
+        let deltas = MetricDeltas::new(font);
+        locations
+            .iter()
+            .map(|coords| {
+                let mut metrics = base;
+                let coords = coords.inner();
+                if let (Some(deltas), false) = (&deltas, coords.is_empty()) {
+                    deltas.apply(&mut metrics, coords, scale);
                 }
-            }
+                metrics
+            })
+            .collect()
+    }
+
+    /// Measures the cap height and x-height from the outlines of "H" and
+    /// "x" if they are missing from the OS/2 table, setting
+    /// `cap_height_measured` and `x_height_measured`.
+    ///
+    /// The size and coordinates should match those the metrics were created
+    /// with. Heights that were measured before, such as at another location,
+    /// are measured again.
+    ///
+    /// Like browsers, this uses the highest horizontal segment of the "H"
+    /// and "x" outlines so that overshoots are ignored, falling back to the
+    /// top of the outline when there is no such segment.
+    #[cfg(feature = "scale")]
+    pub fn measure_missing_heights<'a>(
+        &mut self,
+        font: &impl TableProvider<'a>,
+        size: Size,
+        coords: NormalizedCoords<'a>,
+    ) {
+        let scale = size.linear_scale(self.units_per_em);
+        let coords = coords.inner();
+        let measure_cap_height = self.cap_height.is_none() || self.cap_height_measured;
+        let measure_x_height = self.x_height.is_none() || self.x_height_measured;
+        if !measure_cap_height && !measure_x_height {
+            return;
         }
-        if let (Ok(mvar), true) = (font.mvar(), !coords.is_empty()) {
-            use read_fonts::tables::mvar::tags::*;
-            let metric_delta =
-                |tag| mvar.metric_delta(tag, coords).unwrap_or_default().to_f64() as f32 * scale;
-            metrics.ascent += metric_delta(HASC);
-            metrics.descent += metric_delta(HDSC);
-            metrics.leading += metric_delta(HLGP);
-            if let Some(cap_height) = &mut metrics.cap_height {
-                *cap_height += metric_delta(CPHT);
-            }
-            if let Some(x_height) = &mut metrics.x_height {
-                *x_height += metric_delta(XHGT);
+        let charmap = Charmap::new(font);
+        let mut context = scale::Context::new();
+        let mut scaler = context.new_scaler().coords(coords).build(font);
+        let mut measure = |ch: char| {
+            let glyph_id = charmap.map(ch)?;
+            let mut pen = FlatTopPen::default();
+            scaler.outline(glyph_id, &mut pen).ok()?;
+            pen.top().map(|top| top * scale)
+        };
+        if measure_cap_height {
+            if let Some(cap_height) = measure('H') {
+                self.cap_height = Some(cap_height);
+                self.cap_height_measured = true;
             }
-            if let Some(underline) = &mut metrics.underline {
-                underline.offset += metric_delta(UNDO);
-                underline.thickness += metric_delta(UNDS);
+        }
+        if measure_x_height {
+            if let Some(x_height) = measure('x') {
+                self.x_height = Some(x_height);
+                self.x_height_measured = true;
             }
-            if let Some(strikeout) = &mut metrics.strikeout {
-                strikeout.offset += metric_delta(STRO);
-                strikeout.thickness += metric_delta(STRS);
+        }
+    }
+}
+
+/// Returns the ascent, descent and leading in font units.
+///
+/// Choosing proper line metrics is a challenge due to the changing spec,
+/// backward compatibility and broken fonts.
+///
+/// We use the same strategy as FreeType:
+/// 1. Use the OS/2 metrics if the table exists and the USE_TYPO_METRICS
+///    flag is set.
+/// 2. Otherwise, use the hhea metrics.
+/// 3. If hhea metrics are zero and the OS/2 table exists:
+///    3a. Use the typo metrics if they are non-zero
+///    3b. Otherwise, use the win metrics
+///
+/// See: https://github.com/freetype/freetype/blob/5c37b6406258ec0d7ab64b8619c5ea2c19e3c69a/src/sfnt/sfobjs.c#L1311
+fn line_metrics<'a>(font: &impl TableProvider<'a>) -> [i32; 3] {
+    let os2 = font.os2().ok();
+    let typo_metrics = |os2: &Os2| {
+        [
+            os2.s_typo_ascender() as i32,
+            os2.s_typo_descender() as i32,
+            os2.s_typo_line_gap() as i32,
+        ]
+    };
+    if let Some(os2) = &os2 {
+        if os2
+            .fs_selection()
+            .contains(SelectionFlags::USE_TYPO_METRICS)
+        {
+            return typo_metrics(os2);
+        }
+    }
+    let mut metrics = font
+        .hhea()
+        .map(|hhea| {
+            [
+                hhea.ascender().to_i16() as i32,
+                hhea.descender().to_i16() as i32,
+                hhea.line_gap().to_i16() as i32,
+            ]
+        })
+        .unwrap_or_default();
+    if metrics[0] == 0 && metrics[1] == 0 {
+        if let Some(os2) = &os2 {
+            if os2.s_typo_ascender() != 0 || os2.s_typo_descender() != 0 {
+                metrics = typo_metrics(os2);
+            } else {
+                metrics[0] = os2.us_win_ascent() as i32;
+                // Win descent is always positive while other descent values are negative. Negate it
+                // to ensure we return consistent metrics.
+                metrics[1] = -(os2.us_win_descent() as i32);
             }
         }
-        metrics
     }
+    metrics
+}
+
+/// Pen that finds the highest horizontal line segment of an outline.
+#[cfg(feature = "scale")]
+#[derive(Default)]
+struct FlatTopPen {
+    start: (f32, f32),
+    current: (f32, f32),
+    /// Highest horizontal line segment.
+    flat_top: Option<f32>,
+    /// Highest on-curve point.
+    top: Option<f32>,
+}
+
+#[cfg(feature = "scale")]
+impl FlatTopPen {
+    fn top(&self) -> Option<f32> {
+        self.flat_top.or(self.top)
+    }
+
+    fn point(&mut self, x: f32, y: f32) {
+        self.top = Some(self.top.map_or(y, |top| top.max(y)));
+        self.current = (x, y);
+    }
+
+    fn line(&mut self, x: f32, y: f32) {
+        if y == self.current.1 && x != self.current.0 {
+            self.flat_top = Some(self.flat_top.map_or(y, |top| top.max(y)));
+        }
+        self.point(x, y);
+    }
+}
+
+#[cfg(feature = "scale")]
+impl Pen for FlatTopPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = (x, y);
+        self.point(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.line(x, y);
+    }
+
+    fn quad_to(&mut self, _cx0: f32, _cy0: f32, x: f32, y: f32) {
+        self.point(x, y);
+    }
+
+    fn curve_to(&mut self, _cx0: f32, _cy0: f32, _cx1: f32, _cy1: f32, x: f32, y: f32) {
+        self.point(x, y);
+    }
+
+    fn close(&mut self) {
+        // The closing segment may also be horizontal.
+        let (x, y) = self.start;
+        self.line(x, y);
+    }
+}
+
+/// Units per em assumed for fonts with PostScript outlines that do not
+/// specify a valid value.
+const DEFAULT_CFF_UNITS_PER_EM: u16 = 1000;
+
+/// Units per em assumed for all other fonts that do not specify a valid
+/// value.
+const DEFAULT_UNITS_PER_EM: u16 = 2048;
+
+/// Returns the units per em value from the `head` table, substituting a
+/// default based on the outline format when the table is missing or the
+/// value is zero.
+pub(crate) fn units_per_em<'a>(font: &impl TableProvider<'a>) -> u16 {
+    match font.head().map(|head| head.units_per_em()) {
+        Ok(upem) if upem != 0 => upem,
+        _ if font.data_for_tag(Tag::new(b"CFF ")).is_some()
+            || font.data_for_tag(Tag::new(b"CFF2")).is_some() =>
+        {
+            DEFAULT_CFF_UNITS_PER_EM
+        }
+        _ => DEFAULT_UNITS_PER_EM,
+    }
+}
+
+/// Returns the number of glyphs in the font.
+///
+/// This is the count from the `maxp` table, clamped to the number of glyphs
+/// that the `loca` table has offsets for so that glyph identifiers below the
+/// count can always be looked up.
+pub(crate) fn glyph_count<'a>(font: &impl TableProvider<'a>) -> u16 {
+    let count = font
+        .maxp()
+        .map(|maxp| maxp.num_glyphs())
+        .unwrap_or_default();
+    let loca_count = font.head().ok().and_then(|head| {
+        let loca = font.data_for_tag(Tag::new(b"loca"))?;
+        let offset_size = if head.index_to_loc_format() == 0 {
+            2
+        } else {
+            4
+        };
+        // The last offset marks the end of the final glyph.
+        Some((loca.len() / offset_size).saturating_sub(1))
+    });
+    match loca_count {
+        Some(loca_count) => count.min(loca_count.min(u16::MAX as usize) as u16),
+        None => count,
+    }
+}
+
+/// Delta set indices for the metrics that are varied by the `MVAR` table.
+struct MetricDeltas<'a> {
+    ivs: ItemVariationStore<'a>,
+    /// Indices for each tag in [`MetricDeltas::TAGS`].
+    indices: [Option<DeltaSetIndex>; 9],
+}
+
+impl<'a> MetricDeltas<'a> {
+    const TAGS: [Tag; 9] = [HASC, HDSC, HLGP, CPHT, XHGT, UNDO, UNDS, STRO, STRS];
+
+    fn new(font: &impl TableProvider<'a>) -> Option<Self> {
+        let mvar = font.mvar().ok()?;
+        let ivs = mvar.item_variation_store()?.ok()?;
+        let records = mvar.value_records();
+        let indices = Self::TAGS.map(|tag| {
+            let ix = records
+                .binary_search_by(|record| record.value_tag().cmp(&tag))
+                .ok()?;
+            let record = records.get(ix)?;
+            Some(DeltaSetIndex {
+                outer: record.delta_set_outer_index(),
+                inner: record.delta_set_inner_index(),
+            })
+        });
+        Some(Self { ivs, indices })
+    }
+
+    /// Returns the scaled deltas for each tag in [`MetricDeltas::TAGS`].
+    fn deltas(&self, coords: &[NormalizedCoord], scale: f32) -> [f32; 9] {
+        self.indices.map(|index| {
+            index
+                .and_then(|index| self.ivs.compute_delta(index, coords).ok())
+                .map(|delta| Fixed::from_i32(delta).to_f64() as f32 * scale)
+                .unwrap_or_default()
+        })
+    }
+
+    fn apply(&self, metrics: &mut Metrics, coords: &[NormalizedCoord], scale: f32) {
+        let [hasc, hdsc, hlgp, cpht, xhgt, undo, unds, stro, strs] = self.deltas(coords, scale);
+        metrics.ascent += hasc;
+        metrics.descent += hdsc;
+        metrics.leading += hlgp;
+        if let Some(cap_height) = &mut metrics.cap_height {
+            *cap_height += cpht;
+        }
+        if let Some(x_height) = &mut metrics.x_height {
+            *x_height += xhgt;
+        }
+        if let Some(underline) = &mut metrics.underline {
+            underline.offset += undo;
+            underline.thickness += unds;
+        }
+        if let Some(strikeout) = &mut metrics.strikeout {
+            strikeout.offset += stro;
+            strikeout.thickness += strs;
+        }
+    }
+}
+
+/// Metrics for laying out a glyph in vertical text.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VerticalMetrics {
+    /// Distance between the vertical origins of consecutive glyphs.
+    pub advance_height: f32,
+    /// Distance from the vertical origin to the top of the glyph bounding
+    /// box, if the bounding box is known.
+    pub top_side_bearing: Option<f32>,
+    /// Position of the vertical origin as `(x, y)` relative to the
+    /// horizontal origin.
+    pub origin: (f32, f32),
+    /// True if the metrics were synthesized from the horizontal metrics
+    /// because the font lacks the `vhea` and `vmtx` tables.
+    pub synthesized: bool,
+}
+
+/// Policy for rounding glyph advances to whole pixels.
+///
+/// Renderers that draw hinted outlines generally expect integral advances
+/// since hinting snaps the outlines to the pixel grid. Mixing hinted outlines
+/// with fractional advances leads to uneven spacing.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum AdvanceRounding {
+    /// Advances are kept fractional.
+    #[default]
+    Fractional,
+    /// Advances are always rounded to the nearest pixel.
+    Pixel,
+    /// Advances are rounded to the nearest pixel only when outlines are
+    /// hinted.
+    WhenHinted,
+}
+
+impl AdvanceRounding {
+    /// Returns true if advances should be rounded for outlines with the
+    /// given hinting state.
+    pub fn should_round(self, is_hinted: bool) -> bool {
+        match self {
+            Self::Fractional => false,
+            Self::Pixel => true,
+            Self::WhenHinted => is_hinted,
+        }
+    }
+}
+
+/// Arithmetic used to compute glyph metrics, for matching the results of
+/// other libraries.
+///
+/// Applications that position glyphs with metrics from a shaper and draw
+/// them with metrics from this crate, or the reverse, see differences of a
+/// unit when the two round differently. Selecting the mode that matches
+/// the other library avoids these differences.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum MetricsCompatibility {
+    /// Matches FreeType: variation deltas from the `HVAR` table are
+    /// truncated and values are scaled with floating point arithmetic.
+    #[default]
+    FreeType,
+    /// Matches HarfBuzz: variation deltas from the `HVAR` table are rounded
+    /// to the nearest unit before they are added and values are scaled
+    /// with the 16.16 fixed point multiplier of the font and rounded.
+    ///
+    /// HarfBuzz produces integral positions in the scale set on the font.
+    /// The scale is taken to be `units_per_pixel` times the size in pixels
+    /// per em and metrics are returned in pixels, so for a font with its
+    /// scale set to the size in 26.6 fixed point, `units_per_pixel` is 64.
+    /// For unscaled metrics, the scale is the units per em of the font
+    /// multiplied by `units_per_pixel`.
+    HarfBuzz {
+        /// Number of HarfBuzz position units in a pixel.
+        units_per_pixel: u32,
+    },
+}
+
+/// Scaler font and buffers for loading the phantom points of glyphs.
+#[cfg(feature = "scale")]
+#[derive(Clone)]
+struct PhantomLoader<'a> {
+    font: glyf::ScalerFont<'a>,
+    buffers: RefCell<(glyf::Context, glyf::Outline)>,
 }
 
 /// Glyph specific metrics.
 #[derive(Clone)]
 pub struct GlyphMetrics<'a> {
     glyph_count: u16,
+    units_per_em: u16,
     scale: f32,
     h_metrics: &'a [LongMetric],
     default_advance_width: u16,
@@ -225,6 +593,17 @@ pub struct GlyphMetrics<'a> {
     hvar: Option<Hvar<'a>>,
     loca_glyf: Option<(Loca<'a>, Glyf<'a>)>,
     coords: &'a [NormalizedCoord],
+    v_metrics: &'a [LongMetric],
+    tsbs: &'a [BigEndian<i16>],
+    ascent: f32,
+    descent: f32,
+    /// Glyph loader for variable fonts without an `HVAR` table, where the
+    /// advances are varied through the phantom points in `gvar`.
+    #[cfg(feature = "scale")]
+    phantom_loader: Option<PhantomLoader<'a>>,
+    round_advances: bool,
+    /// Multiplier and units per pixel for HarfBuzz compatible scaling.
+    harfbuzz: Option<(i64, f32)>,
 }
 
 impl<'a> GlyphMetrics<'a> {
@@ -233,15 +612,9 @@ impl<'a> GlyphMetrics<'a> {
     ///
     /// For details on these parameters, see [selecting an instance](crate::meta::metrics#selecting-an-instance).
     pub fn new(font: &impl TableProvider<'a>, size: Size, coords: NormalizedCoords<'a>) -> Self {
-        let glyph_count = font
-            .maxp()
-            .map(|maxp| maxp.num_glyphs())
-            .unwrap_or_default();
-        let upem = font
-            .head()
-            .map(|head| head.units_per_em())
-            .unwrap_or_default();
-        let scale = size.linear_scale(upem);
+        let glyph_count = glyph_count(font);
+        let units_per_em = units_per_em(font);
+        let scale = size.linear_scale(units_per_em);
         let coords = coords.inner();
         let (h_metrics, default_advance_width, lsbs) = font
             .hmtx()
@@ -253,6 +626,31 @@ impl<'a> GlyphMetrics<'a> {
             })
             .unwrap_or_default();
         let hvar = font.hvar().ok();
+        #[cfg(feature = "scale")]
+        let phantom_loader = if hvar.is_none() && !coords.is_empty() && font.gvar().is_ok() {
+            glyf::ScalerFont::new(font, None, 0.0, coords)
+                .ok()
+                .map(|font| PhantomLoader {
+                    font,
+                    buffers: Default::default(),
+                })
+        } else {
+            None
+        };
+        let (v_metrics, tsbs) = font
+            .vmtx()
+            .map(|vmtx| (vmtx.v_metrics(), vmtx.top_side_bearings()))
+            .unwrap_or_default();
+        let [ascent, descent, _] = line_metrics(font);
+        let mut ascent = ascent as f32 * scale;
+        let mut descent = descent as f32 * scale;
+        if !coords.is_empty() {
+            if let Some(deltas) = MetricDeltas::new(font) {
+                let [hasc, hdsc, ..] = deltas.deltas(coords, scale);
+                ascent += hasc;
+                descent += hdsc;
+            }
+        }
         let loca_glyf = if let (Ok(loca), Ok(glyf)) = (font.loca(None), font.glyf()) {
             Some((loca, glyf))
         } else {
@@ -260,6 +658,7 @@ impl<'a> GlyphMetrics<'a> {
         };
         Self {
             glyph_count,
+            units_per_em,
             scale,
             h_metrics,
             default_advance_width,
@@ -267,9 +666,46 @@ impl<'a> GlyphMetrics<'a> {
             hvar,
             loca_glyf,
             coords,
+            v_metrics,
+            tsbs,
+            ascent,
+            descent,
+            #[cfg(feature = "scale")]
+            phantom_loader,
+            round_advances: false,
+            harfbuzz: None,
         }
     }
 
+    /// Sets the policy for rounding advances to whole pixels.
+    ///
+    /// `is_hinted` should be true if the outlines drawn with these metrics
+    /// are hinted and is used by [`AdvanceRounding::WhenHinted`].
+    pub fn with_advance_rounding(mut self, rounding: AdvanceRounding, is_hinted: bool) -> Self {
+        self.round_advances = rounding.should_round(is_hinted);
+        self
+    }
+
+    /// Sets the arithmetic used to compute the metrics.
+    ///
+    /// The default is [`MetricsCompatibility::FreeType`]. Advances are
+    /// rounded to whole pixels after scaling if required by the
+    /// [rounding policy](Self::with_advance_rounding).
+    pub fn with_compatibility(mut self, compatibility: MetricsCompatibility) -> Self {
+        self.harfbuzz = match compatibility {
+            MetricsCompatibility::FreeType => None,
+            MetricsCompatibility::HarfBuzz { units_per_pixel } => {
+                let upem = self.units_per_em.max(1) as i64;
+                let units_per_pixel = units_per_pixel.max(1) as f32;
+                // The scale of a HarfBuzz font is an integer.
+                let font_scale = round(self.scale * upem as f32 * units_per_pixel) as i64;
+                // See hb_font_t::mults_changed.
+                Some(((font_scale << 16) / upem, units_per_pixel))
+            }
+        };
+        self
+    }
+
     /// Returns the number of available glyphs in the font.
     pub fn glyph_count(&self) -> u16 {
         self.glyph_count
@@ -278,7 +714,9 @@ impl<'a> GlyphMetrics<'a> {
     /// Returns the advance width for the specified glyph.
     ///
     /// If normalized coordinates were provided when constructing glyph metrics and
-    /// an `HVAR` table is present, applies the appropriate delta.
+    /// an `HVAR` table is present, applies the appropriate delta. Otherwise, if
+    /// the `scale` feature is enabled and a `gvar` table is present, the glyph
+    /// is loaded to compute the advance from its varied phantom points.
     pub fn advance_width(&self, glyph_id: GlyphId) -> Option<f32> {
         if glyph_id.to_u16() >= self.glyph_count {
             return None;
@@ -291,18 +729,110 @@ impl<'a> GlyphMetrics<'a> {
         if let Some(hvar) = &self.hvar {
             advance += hvar
                 .advance_width_delta(glyph_id, self.coords)
-                // FreeType truncates metric deltas...
-                // https://github.com/freetype/freetype/blob/7838c78f53f206ac5b8e9cefde548aa81cb00cf4/src/truetype/ttgxvar.c#L1027
-                .map(|delta| delta.to_f64() as i32)
+                .map(|delta| self.delta_units(delta))
                 .unwrap_or(0);
         }
-        Some(advance as f32 * self.scale)
+        #[cfg(feature = "scale")]
+        if let Some(phantom_advance) = self.phantom_advance(glyph_id) {
+            advance = phantom_advance;
+        }
+        Some(self.round_advance(self.scale_units(advance)))
+    }
+
+    /// Rounds the given advance to the nearest pixel if required by the
+    /// rounding policy.
+    fn round_advance(&self, advance: f32) -> f32 {
+        if !self.round_advances {
+            return advance;
+        }
+        round(advance)
+    }
+
+    /// Converts a metric variation delta to whole font units.
+    fn delta_units(&self, delta: Fixed) -> i32 {
+        if self.harfbuzz.is_some() {
+            // HarfBuzz rounds deltas half away from zero with roundf.
+            let bits = delta.to_bits();
+            let rounded = (bits.unsigned_abs() + 0x8000) >> 16;
+            if bits < 0 {
+                -(rounded as i32)
+            } else {
+                rounded as i32
+            }
+        } else {
+            // FreeType truncates metric deltas...
+            // https://github.com/freetype/freetype/blob/7838c78f53f206ac5b8e9cefde548aa81cb00cf4/src/truetype/ttgxvar.c#L1027
+            delta.to_f64() as i32
+        }
+    }
+
+    /// Scales a value in font units.
+    fn scale_units(&self, value: i32) -> f32 {
+        match self.harfbuzz {
+            // See hb_font_t::em_mult.
+            Some((mult, units_per_pixel)) => {
+                ((value as i64 * mult + 32768) >> 16) as f32 / units_per_pixel
+            }
+            None => value as f32 * self.scale,
+        }
+    }
+
+    /// Loads the glyph with `gvar` deltas applied and passes the outline,
+    /// in font units, to the given function.
+    ///
+    /// Returns `None` for fonts with an `HVAR` table and for empty glyphs,
+    /// which have no phantom points.
+    #[cfg(feature = "scale")]
+    fn with_phantom_outline<R>(
+        &self,
+        glyph_id: GlyphId,
+        f: impl FnOnce(&glyf::Outline) -> R,
+    ) -> Option<R> {
+        let loader = self.phantom_loader.as_ref()?;
+        let mut buffers = loader.buffers.borrow_mut();
+        let (context, outline) = &mut *buffers;
+        glyf::Scaler::from_font(
+            context,
+            loader.font.clone(),
+            #[cfg(feature = "hinting")]
+            None,
+            #[cfg(feature = "hinting")]
+            Default::default(),
+        )
+        .load(glyph_id, outline)
+        .ok()?;
+        if outline.points.is_empty() && outline.phantom == Default::default() {
+            return None;
+        }
+        Some(f(outline))
+    }
+
+    /// Returns the advance width in font units computed from the varied
+    /// phantom points of the glyph.
+    #[cfg(feature = "scale")]
+    fn phantom_advance(&self, glyph_id: GlyphId) -> Option<i32> {
+        self.with_phantom_outline(glyph_id, |outline| {
+            let [origin, advance, ..] = outline.phantom;
+            (advance.x - origin.x).to_i32()
+        })
+    }
+
+    /// Returns the left side bearing in font units computed from the varied
+    /// points of the glyph, relative to the first phantom point.
+    #[cfg(feature = "scale")]
+    fn phantom_lsb(&self, glyph_id: GlyphId) -> Option<i32> {
+        self.with_phantom_outline(glyph_id, |outline| {
+            let x_min = outline.points.iter().map(|point| point.x).min()?;
+            Some((x_min - outline.phantom[0].x).to_i32())
+        })?
     }
 
     /// Returns the left side bearing for the specified glyph.
     ///
     /// If normalized coordinates were provided when constructing glyph metrics and
-    /// an `HVAR` table is present, applies the appropriate delta.
+    /// an `HVAR` table is present, applies the appropriate delta. Otherwise, if
+    /// the `scale` feature is enabled and a `gvar` table is present, the glyph
+    /// is loaded to compute the side bearing from its varied outline.
     pub fn left_side_bearing(&self, glyph_id: GlyphId) -> Option<f32> {
         if glyph_id.to_u16() >= self.glyph_count {
             return None;
@@ -321,12 +851,66 @@ impl<'a> GlyphMetrics<'a> {
         if let Some(hvar) = &self.hvar {
             lsb += hvar
                 .lsb_delta(glyph_id, self.coords)
-                // FreeType truncates metric deltas...
-                // https://github.com/freetype/freetype/blob/7838c78f53f206ac5b8e9cefde548aa81cb00cf4/src/truetype/ttgxvar.c#L1027
-                .map(|delta| delta.to_f64() as i32)
+                .map(|delta| self.delta_units(delta))
                 .unwrap_or(0);
         }
-        Some(lsb as f32 * self.scale)
+        #[cfg(feature = "scale")]
+        if let Some(phantom_lsb) = self.phantom_lsb(glyph_id) {
+            lsb = phantom_lsb;
+        }
+        Some(self.scale_units(lsb))
+    }
+
+    /// Returns the metrics for laying out the specified glyph in vertical
+    /// text.
+    ///
+    /// The vertical origin is centered horizontally on the advance width.
+    /// When the font has `vhea` and `vmtx` tables, it sits above the top of
+    /// the glyph bounding box by the top side bearing. Otherwise, the metrics
+    /// are synthesized: the advance height is the distance between the
+    /// ascent and descent of the font and the origin sits at the ascent.
+    ///
+    /// Variations of the vertical metrics from a `VVAR` table are not
+    /// applied.
+    pub fn vertical_metrics(&self, glyph_id: GlyphId) -> Option<VerticalMetrics> {
+        let advance_width = self.advance_width(glyph_id)?;
+        let bounds = self.bounds(glyph_id);
+        if self.v_metrics.is_empty() {
+            return Some(VerticalMetrics {
+                advance_height: self.round_advance(self.ascent - self.descent),
+                top_side_bearing: bounds.map(|bounds| self.ascent - bounds.y_max),
+                origin: (advance_width / 2.0, self.ascent),
+                synthesized: true,
+            });
+        }
+        let gid_index = glyph_id.to_u16() as usize;
+        // Glyphs past the long metrics share the last advance and have
+        // their top side bearings stored in a trailing array.
+        let advance = self
+            .v_metrics
+            .get(gid_index)
+            .or(self.v_metrics.last())?
+            .advance();
+        let tsb = self
+            .v_metrics
+            .get(gid_index)
+            .map(|metric| metric.side_bearing())
+            .unwrap_or_else(|| {
+                self.tsbs
+                    .get(gid_index - self.v_metrics.len())
+                    .map(|tsb| tsb.get())
+                    .unwrap_or_default()
+            });
+        let tsb = self.scale_units(tsb as i32);
+        Some(VerticalMetrics {
+            advance_height: self.round_advance(self.scale_units(advance as i32)),
+            top_side_bearing: bounds.map(|_| tsb),
+            origin: (
+                advance_width / 2.0,
+                bounds.map_or(self.ascent, |bounds| bounds.y_max + tsb),
+            ),
+            synthesized: false,
+        })
     }
 
     /// Returns the bounding box for the specified glyph.
@@ -334,6 +918,9 @@ impl<'a> GlyphMetrics<'a> {
     /// Note that variations are not reflected in the bounding box returned by
     /// this method.
     pub fn bounds(&self, glyph_id: GlyphId) -> Option<BoundingBox> {
+        if glyph_id.to_u16() >= self.glyph_count {
+            return None;
+        }
         let (loca, glyf) = self.loca_glyf.as_ref()?;
         Some(match loca.get_glyf(glyph_id, glyf).ok()? {
             Some(glyph) => BoundingBox {
@@ -348,6 +935,18 @@ impl<'a> GlyphMetrics<'a> {
     }
 }
 
+/// Rounds to the nearest integer, with halves rounded up.
+// Avoid f32::round which is not available in core.
+fn round(value: f32) -> f32 {
+    let shifted = value + 0.5;
+    let truncated = shifted as i32;
+    if truncated as f32 > shifted {
+        (truncated - 1) as f32
+    } else {
+        truncated as f32
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -372,6 +971,8 @@ mod tests {
             max_width: None,
             x_height: Some(512.0),
             cap_height: Some(717.0),
+            cap_height_measured: false,
+            x_height_measured: false,
             is_monospace: false,
             italic_angle: 0.0,
             ascent: 950.0,
@@ -403,6 +1004,8 @@ mod tests {
             max_width: Some(1336.0),
             x_height: None,
             cap_height: None,
+            cap_height_measured: false,
+            x_height_measured: false,
             is_monospace: false,
             italic_angle: 0.0,
             ascent: 2100.0,
@@ -414,6 +1017,52 @@ mod tests {
         assert_eq!(metrics, expected);
     }
 
+    #[cfg(feature = "scale")]
+    #[test]
+    fn measured_heights() {
+        use crate::testing::FontBuilder;
+        let mut builder = FontBuilder::new(1000);
+        let cap_h = builder.add_glyph(600, &[&[(0, 0), (0, 700), (500, 700), (500, 0)]]);
+        // The peak above the flat top is ignored.
+        let x = builder.add_glyph(
+            500,
+            &[&[(0, 0), (100, 520), (200, 500), (400, 500), (500, 0)]],
+        );
+        builder.map('H', cap_h).map('x', x);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        // Measuring is opt-in.
+        let mut metrics = font.metrics(Size::unscaled(), NormalizedCoords::default());
+        assert_eq!(metrics.cap_height, None);
+        assert_eq!(metrics.x_height, None);
+        metrics.measure_missing_heights(&font, Size::unscaled(), NormalizedCoords::default());
+        assert_eq!(metrics.cap_height, Some(700.0));
+        assert!(metrics.cap_height_measured);
+        assert_eq!(metrics.x_height, Some(500.0));
+        assert!(metrics.x_height_measured);
+        let size = Size::new(10.0);
+        let mut metrics = font.metrics(size, NormalizedCoords::default());
+        metrics.measure_missing_heights(&font, size, NormalizedCoords::default());
+        assert_eq!(metrics.cap_height, Some(7.0));
+        assert_eq!(metrics.x_height, Some(5.0));
+    }
+
+    #[test]
+    fn metrics_multi() {
+        let font = FontRef::new(VAZIRMATN_VAR).unwrap();
+        let coords = [-1.0, -0.5, 0.0, 0.3, 1.0].map(|c| [NormalizedCoord::from_f32(c)]);
+        let locations = coords
+            .iter()
+            .map(|coords| NormalizedCoords::new(coords))
+            .collect::<Vec<_>>();
+        let size = Size::new(16.0);
+        let multi = Metrics::new_multi(&font, size, &locations);
+        assert_eq!(multi.len(), locations.len());
+        for (metrics, coords) in multi.iter().zip(&locations) {
+            assert_eq!(*metrics, font.metrics(size, *coords));
+        }
+    }
+
     #[test]
     fn glyph_metrics() {
         let font = FontRef::new(VAZIRMATN_VAR).unwrap();
@@ -436,6 +1085,53 @@ mod tests {
         assert_eq!(expected, &result[..]);
     }
 
+    #[test]
+    fn vertical_metrics() {
+        use crate::{testing::FontBuilder, write::Writer};
+        let mut builder = FontBuilder::new(1000);
+        let glyph_id = builder.add_glyph(600, &[&[(50, 0), (300, 700), (550, 0)]]);
+        builder.vertical_metrics(800, -200, 0);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let metrics = font
+            .glyph_metrics(Size::unscaled(), NormalizedCoords::default())
+            .vertical_metrics(glyph_id)
+            .unwrap();
+        let expected = VerticalMetrics {
+            advance_height: 1000.0,
+            top_side_bearing: Some(100.0),
+            origin: (300.0, 800.0),
+            synthesized: true,
+        };
+        assert_eq!(metrics, expected);
+        // Version 1.0 vhea with a single long metric.
+        let mut vhea = Writer::default();
+        vhea.u32(0x00010000);
+        vhea.0.resize(34, 0);
+        vhea.u16(1);
+        // Advance of 1200 with a top side bearing of 150, followed by a
+        // top side bearing of 50 for the remaining glyph.
+        let mut vmtx = Writer::default();
+        vmtx.u16(1200);
+        vmtx.u16(150);
+        vmtx.u16(50);
+        builder.add_table(Tag::new(b"vhea"), vhea.0);
+        builder.add_table(Tag::new(b"vmtx"), vmtx.0);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let metrics = font
+            .glyph_metrics(Size::new(500.0), NormalizedCoords::default())
+            .vertical_metrics(glyph_id)
+            .unwrap();
+        let expected = VerticalMetrics {
+            advance_height: 600.0,
+            top_side_bearing: Some(25.0),
+            origin: (150.0, 375.0),
+            synthesized: false,
+        };
+        assert_eq!(metrics, expected);
+    }
+
     #[test]
     fn glyph_metrics_var() {
         let font = FontRef::new(VAZIRMATN_VAR).unwrap();
@@ -458,4 +1154,152 @@ mod tests {
             .collect::<Vec<_>>();
         assert_eq!(expected, &result[..]);
     }
+
+    #[test]
+    #[cfg(feature = "scale")]
+    fn glyph_metrics_var_without_hvar() {
+        // Hide the HVAR table by renaming it in the table directory. The
+        // replacement tag keeps the records sorted.
+        let mut data = VAZIRMATN_VAR.to_vec();
+        let pos = data
+            .windows(4)
+            .take(12 + 16 * 32)
+            .position(|tag| tag == b"HVAR")
+            .unwrap();
+        data[pos + 3] = b'S';
+        let font = FontRef::new(&data).unwrap();
+        assert!(font.hvar().is_err());
+        let coords = &[NormalizedCoord::from_f32(-0.8)];
+        let glyph_metrics = font.glyph_metrics(Size::unscaled(), NormalizedCoords::new(coords));
+        // Phantom point deltas are rounded while HVAR deltas are truncated
+        // so allow for a difference of one unit.
+        let expected = [908.0, 1246.0, 1246.0, 556.0];
+        for (i, expected) in expected.iter().enumerate() {
+            let advance_width = glyph_metrics.advance_width(GlyphId::new(i as u16)).unwrap();
+            assert!((advance_width - expected).abs() <= 1.0);
+        }
+        // Side bearings are the distance from the first phantom point to
+        // the left of the varied outline, which is shifted to put that
+        // point at the origin.
+        let mut context = glyf::Context::new();
+        let mut scaler = glyf::Scaler::new(
+            &mut context,
+            &font,
+            None,
+            0.0,
+            #[cfg(feature = "hinting")]
+            None,
+            #[cfg(feature = "hinting")]
+            Default::default(),
+            coords,
+        )
+        .unwrap();
+        let mut outline = glyf::Outline::new();
+        for gid in 1..4 {
+            let gid = GlyphId::new(gid);
+            scaler.load(gid, &mut outline).unwrap();
+            let x_min = outline
+                .unscaled_points()
+                .map(|point| point.x)
+                .min()
+                .unwrap();
+            assert_eq!(glyph_metrics.left_side_bearing(gid), Some(x_min as f32));
+        }
+    }
+
+    #[test]
+    fn zero_units_per_em() {
+        let mut builder = crate::testing::FontBuilder::new(0);
+        let gid = builder.add_glyph(1024, &[&[(0, 0), (512, 1024), (1024, 0)]]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        // TrueType fonts default to 2048 units per em.
+        assert_eq!(font.units_per_em(), 2048);
+        let metrics = font.metrics(Size::new(16.0), NormalizedCoords::default());
+        assert_eq!(metrics.units_per_em, 2048);
+        let glyph_metrics = font.glyph_metrics(Size::new(16.0), NormalizedCoords::default());
+        assert_eq!(glyph_metrics.advance_width(gid), Some(8.0));
+    }
+
+    #[test]
+    fn fixed_scale() {
+        let font = FontRef::new(SIMPLE_GLYF).unwrap();
+        let size = Size::fixed_scale(0.5);
+        assert_eq!(size.ppem(), None);
+        assert_eq!(size.ppem_for(1024), Some(512.0));
+        assert_eq!(size.linear_scale(1024), 0.5);
+        let metrics = font.metrics(size, NormalizedCoords::default());
+        assert_eq!(metrics.ascent, 475.0);
+        assert_eq!(metrics.descent, -125.0);
+        // Equivalent to the same scale expressed in pixels per em.
+        let ppem_metrics = font.metrics(Size::new(512.0), NormalizedCoords::default());
+        assert_eq!(metrics, ppem_metrics);
+    }
+
+    #[test]
+    fn advance_rounding() {
+        let mut builder = crate::testing::FontBuilder::new(1024);
+        let gid = builder.add_glyph(600, &[&[(0, 0), (300, 700), (600, 0)]]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let size = Size::new(16.0);
+        let advance = |rounding, is_hinted| {
+            font.glyph_metrics(size, NormalizedCoords::default())
+                .with_advance_rounding(rounding, is_hinted)
+                .advance_width(gid)
+        };
+        assert_eq!(advance(AdvanceRounding::Fractional, true), Some(9.375));
+        assert_eq!(advance(AdvanceRounding::Pixel, false), Some(9.0));
+        assert_eq!(advance(AdvanceRounding::WhenHinted, false), Some(9.375));
+        assert_eq!(advance(AdvanceRounding::WhenHinted, true), Some(9.0));
+    }
+
+    #[test]
+    fn harfbuzz_scaling() {
+        let mut builder = crate::testing::FontBuilder::new(1000);
+        let gid = builder.add_glyph(600, &[&[(50, 0), (300, 700), (550, 0)]]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let metrics = |size, compatibility| {
+            let glyph_metrics = font
+                .glyph_metrics(size, NormalizedCoords::default())
+                .with_compatibility(compatibility);
+            (
+                glyph_metrics.advance_width(gid).unwrap(),
+                glyph_metrics.left_side_bearing(gid).unwrap(),
+            )
+        };
+        // A 26.6 scale of 832 gives a multiplier of 54525, so the advance
+        // is (600 * 54525 + 32768) >> 16 = 499 and the side bearing is 42.
+        let hb = MetricsCompatibility::HarfBuzz {
+            units_per_pixel: 64,
+        };
+        assert_eq!(metrics(Size::new(13.0), hb), (499.0 / 64.0, 42.0 / 64.0));
+        let (advance, lsb) = metrics(Size::new(13.0), MetricsCompatibility::FreeType);
+        assert!((advance - 7.8).abs() < 1e-4);
+        assert!((lsb - 0.65).abs() < 1e-4);
+        // Scaling to units per em is exact.
+        let hb = MetricsCompatibility::HarfBuzz { units_per_pixel: 1 };
+        assert_eq!(metrics(Size::unscaled(), hb), (600.0, 50.0));
+    }
+
+    #[test]
+    fn harfbuzz_delta_rounding() {
+        let font = FontRef::new(VAZIRMATN_VAR).unwrap();
+        let coords = &[NormalizedCoord::from_f32(-0.8)];
+        let hvar = font.hvar().unwrap();
+        let h_metrics = font.hmtx().unwrap().h_metrics();
+        let glyph_metrics = font
+            .glyph_metrics(Size::unscaled(), NormalizedCoords::new(coords))
+            .with_compatibility(MetricsCompatibility::HarfBuzz { units_per_pixel: 1 });
+        for (i, metric) in h_metrics.iter().take(4).enumerate() {
+            let gid = GlyphId::new(i as u16);
+            // Deltas are rounded before they are added to the base values.
+            let delta = |delta: Result<Fixed, _>| delta.map_or(0.0, |d| d.to_f64().round());
+            let advance = metric.advance() as f64 + delta(hvar.advance_width_delta(gid, coords));
+            let lsb = metric.side_bearing() as f64 + delta(hvar.lsb_delta(gid, coords));
+            assert_eq!(glyph_metrics.advance_width(gid), Some(advance as f32));
+            assert_eq!(glyph_metrics.left_side_bearing(gid), Some(lsb as f32));
+        }
+    }
 }
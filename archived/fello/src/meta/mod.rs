@@ -1,11 +1,25 @@
 //! High level interface to font metadata.
 
+pub mod aat;
+pub mod anchors;
 pub mod attributes;
 pub mod charmap;
+pub mod checksum;
+pub mod features;
+pub mod glyph_names;
 pub mod info_strings;
 pub mod metrics;
+pub mod optical_size;
+pub mod pair_adjustments;
+pub mod palettes;
+pub mod pdf;
+pub mod quirks;
+pub mod summary;
 pub mod variations;
+pub mod version;
 
+mod decomposition;
+mod legacy_encoding;
 mod provider;
 
 pub use provider::MetadataProvider;
@@ -0,0 +1,456 @@
+//! Optical size metadata.
+//!
+//! Fonts describe the point sizes they are designed for in several ways.
+//! Static fonts in an optical size family use the parameters of the `size`
+//! feature in the `GPOS` table to record a design size and the range of
+//! sizes that the font is intended for. Newer static fonts record the same
+//! information as an axis value for the optical size axis in the `STAT`
+//! table or as a range in version 5 of the `OS/2` table. Variable fonts
+//! provide an `opsz` axis whose values are point sizes.
+//!
+//! [`OpticalSize`] combines these sources so that applications can select a
+//! font or axis position automatically for the point size of the text.
+//!
+//! See <https://learn.microsoft.com/en-us/typography/opentype/spec/features_pt#size>
+
+use read_fonts::{
+    tables::{
+        layout::{self, FeatureParams},
+        stat::AxisValue,
+    },
+    types::{Fixed, Tag},
+    FontRead, TableProvider,
+};
+
+use super::{
+    info_strings::StringId,
+    variations::{axis::Axis, VariationSetting},
+    MetadataProvider,
+};
+
+/// Tag of the optical size feature.
+const SIZE: Tag = Tag::new(b"size");
+
+/// Tag of the optical size axis.
+const OPSZ: Tag = Tag::new(b"opsz");
+
+/// Parameters of the `size` feature.
+///
+/// Sizes are in points, converted from the decipoints stored in the font.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SizeParams {
+    /// Size for which the font was designed.
+    pub design_size: f32,
+    /// Identifier shared by the fonts of a family that differ only in
+    /// their intended size. Zero if the font is not part of such a family.
+    pub subfamily_id: u16,
+    /// Localized string identifier for the name of the subfamily, which
+    /// is displayed in menus instead of the style name.
+    pub subfamily_name_id: Option<StringId>,
+    /// Range of sizes for which the font is intended. The start of the
+    /// range is exclusive and the end is inclusive.
+    pub range: Option<(f32, f32)>,
+}
+
+impl SizeParams {
+    fn new<'a>(font: &impl TableProvider<'a>) -> Option<Self> {
+        let feature_list = font.gpos().ok()?.feature_list().ok()?;
+        let record = feature_list
+            .feature_records()
+            .iter()
+            .find(|record| record.feature_tag() == SIZE)?;
+        let feature = record.feature(feature_list.offset_data()).ok()?;
+        if let Some(Ok(FeatureParams::Size(params))) = feature.feature_params() {
+            if let Some(params) = Self::parse(&params) {
+                return Some(params);
+            }
+        }
+        // Early versions of the specification described the offset as
+        // relative to the feature list and some fonts still follow them.
+        let offset = feature.feature_params_offset().offset().to_u32() as usize;
+        if offset == 0 {
+            return None;
+        }
+        let data = feature_list.offset_data().split_off(offset)?;
+        Self::parse(&layout::SizeParams::read(data).ok()?)
+    }
+
+    /// Validates the parameters in the same way as HarfBuzz.
+    fn parse(params: &layout::SizeParams) -> Option<Self> {
+        let design_size = params.design_size();
+        let subfamily_id = params.identifier();
+        let subfamily_name_id = params.name_entry();
+        let range_start = params.range_start();
+        let range_end = params.range_end();
+        if design_size == 0 {
+            return None;
+        }
+        let decipoints = |value: u16| value as f32 / 10.0;
+        if subfamily_id == 0 && subfamily_name_id == 0 && range_start == 0 && range_end == 0 {
+            return Some(Self {
+                design_size: decipoints(design_size),
+                subfamily_id: 0,
+                subfamily_name_id: None,
+                range: None,
+            });
+        }
+        if design_size < range_start
+            || design_size > range_end
+            || !(256..=32767).contains(&subfamily_name_id)
+        {
+            return None;
+        }
+        Some(Self {
+            design_size: decipoints(design_size),
+            subfamily_id,
+            subfamily_name_id: Some(StringId::new(subfamily_name_id)),
+            range: Some((decipoints(range_start), decipoints(range_end))),
+        })
+    }
+
+    /// Returns true if the font is intended for the given point size.
+    pub fn contains(&self, point_size: f32) -> bool {
+        self.range.map_or(false, |(start, end)| {
+            point_size > start && point_size <= end
+        })
+    }
+}
+
+/// Optical size of a static font from an axis value for the optical size
+/// axis in the `STAT` table.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StatOpticalSize {
+    /// Size for which the font was designed.
+    pub design_size: f32,
+    /// Minimum and maximum sizes of the range that the axis value applies
+    /// to. Only present for format 2 axis values.
+    pub range: Option<(f32, f32)>,
+}
+
+impl StatOpticalSize {
+    /// Returns the first axis value for the optical size axis.
+    fn new<'a>(font: &impl TableProvider<'a>) -> Option<Self> {
+        let stat = font.stat().ok()?;
+        let axis_index = stat
+            .design_axes()
+            .ok()?
+            .iter()
+            .position(|axis| axis.axis_tag() == OPSZ)? as u16;
+        let to_f32 = |value: Fixed| value.to_f64() as f32;
+        let values = stat.offset_to_axis_values()?.ok()?;
+        let size = values
+            .axis_values()
+            .iter()
+            .flatten()
+            .find_map(|value| match value {
+                AxisValue::Format1(value) if value.axis_index() == axis_index => {
+                    Some((value.value(), None))
+                }
+                AxisValue::Format2(value) if value.axis_index() == axis_index => Some((
+                    value.nominal_value(),
+                    Some((value.range_min_value(), value.range_max_value())),
+                )),
+                AxisValue::Format3(value) if value.axis_index() == axis_index => {
+                    Some((value.value(), None))
+                }
+                _ => None,
+            });
+        size.map(|(design_size, range)| Self {
+            design_size: to_f32(design_size),
+            range: range.map(|(min, max)| (to_f32(min), to_f32(max))),
+        })
+    }
+}
+
+/// Optical size metadata for a font.
+#[derive(Clone)]
+pub struct OpticalSize {
+    params: Option<SizeParams>,
+    axis: Option<Axis>,
+    stat: Option<StatOpticalSize>,
+    os2_range: Option<(f32, f32)>,
+}
+
+impl OpticalSize {
+    /// Creates optical size metadata from the given table provider.
+    pub fn new<'a>(font: &impl MetadataProvider<'a>) -> Self {
+        let axis = font.axes().get_by_tag(OPSZ);
+        // Variable fonts list a value for each named instance so only
+        // static fonts use the STAT table.
+        let stat = if axis.is_none() {
+            StatOpticalSize::new(font)
+        } else {
+            None
+        };
+        // The OS/2 table stores sizes in twentieths of a point.
+        let os2_range = font.os2().ok().and_then(|os2| {
+            let lower = os2.us_lower_optical_point_size()?;
+            let upper = os2.us_upper_optical_point_size()?;
+            Some((lower as f32 / 20.0, upper as f32 / 20.0))
+        });
+        Self {
+            params: SizeParams::new(font),
+            axis,
+            stat,
+            os2_range,
+        }
+    }
+
+    /// Returns true if the font has no optical size metadata.
+    pub fn is_empty(&self) -> bool {
+        self.params.is_none()
+            && self.axis.is_none()
+            && self.stat.is_none()
+            && self.os2_range.is_none()
+    }
+
+    /// Returns the parameters of the `size` feature.
+    pub fn params(&self) -> Option<&SizeParams> {
+        self.params.as_ref()
+    }
+
+    /// Returns the optical size axis.
+    pub fn axis(&self) -> Option<&Axis> {
+        self.axis.as_ref()
+    }
+
+    /// Returns the optical size from the `STAT` table of a static font.
+    pub fn stat(&self) -> Option<&StatOpticalSize> {
+        self.stat.as_ref()
+    }
+
+    /// Returns the range of sizes from the `OS/2` table. The start of the
+    /// range is inclusive and the end is exclusive.
+    pub fn os2_range(&self) -> Option<(f32, f32)> {
+        self.os2_range
+    }
+
+    /// Returns the size for which the font was designed.
+    ///
+    /// This is the default value of the optical size axis or, for static
+    /// fonts, the design size of the `size` feature or the `STAT` table.
+    pub fn design_size(&self) -> Option<f32> {
+        self.axis
+            .as_ref()
+            .map(|axis| axis.default_value())
+            .or_else(|| self.params.map(|params| params.design_size))
+            .or_else(|| self.stat.map(|stat| stat.design_size))
+    }
+
+    /// Returns the range of sizes that the font supports through the
+    /// optical size axis or, for static fonts, the first of the ranges
+    /// from the `size` feature, the `STAT` table and the `OS/2` table.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.axis
+            .as_ref()
+            .map(|axis| (axis.min_value(), axis.max_value()))
+            .or_else(|| self.params.and_then(|params| params.range))
+            .or_else(|| self.stat.and_then(|stat| stat.range))
+            .or(self.os2_range)
+    }
+
+    /// Returns true if the font is intended for the given point size.
+    ///
+    /// For fonts with an optical size axis, the size must be within the
+    /// range of the axis. Otherwise, the size must be within the first
+    /// range from the `size` feature, the `STAT` table and the `OS/2`
+    /// table, following the conventions of each.
+    pub fn supports(&self, point_size: f32) -> bool {
+        if let Some(axis) = &self.axis {
+            return point_size >= axis.min_value() && point_size <= axis.max_value();
+        }
+        if let Some(params) = self.params.filter(|params| params.range.is_some()) {
+            return params.contains(point_size);
+        }
+        if let Some((min, max)) = self.stat.and_then(|stat| stat.range) {
+            return point_size >= min && point_size <= max;
+        }
+        self.os2_range.map_or(false, |(lower, upper)| {
+            point_size >= lower && point_size < upper
+        })
+    }
+
+    /// Returns the setting of the optical size axis for the given point
+    /// size, clamped to the range of the axis.
+    ///
+    /// Returns `None` if the font has no optical size axis.
+    pub fn setting(&self, point_size: f32) -> Option<VariationSetting> {
+        let axis = self.axis.as_ref()?;
+        let value = point_size.max(axis.min_value()).min(axis.max_value());
+        Some((OPSZ, value).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::FontBuilder, write::Writer};
+    use read_fonts::FontRef;
+
+    /// Builds a `GPOS` table with a `size` feature and the given
+    /// parameters, which are 6 bytes from the feature table and 14 bytes
+    /// from the feature list.
+    fn build_gpos(params_offset: u16, params: [u16; 5]) -> Vec<u8> {
+        let mut gpos = Writer::default();
+        // Header: version 1.0, script list, feature list, lookup list
+        gpos.u32(0x00010000);
+        gpos.u16(10);
+        gpos.u16(12);
+        gpos.u16(24);
+        // Empty script list at 10
+        gpos.u16(0);
+        // Feature list at 12 with one feature
+        gpos.u16(1);
+        gpos.u32(u32::from_be_bytes(*b"size"));
+        gpos.u16(8);
+        // Feature at 20 with no lookups
+        gpos.u16(params_offset);
+        gpos.u16(0);
+        // Empty lookup list at 24, followed by the parameters at 26
+        gpos.u16(0);
+        for value in params {
+            gpos.u16(value);
+        }
+        gpos.0
+    }
+
+    fn build_font(gpos: Option<Vec<u8>>, opsz: Option<(f32, f32, f32)>) -> Vec<u8> {
+        let mut builder = FontBuilder::with_squares(1);
+        if let Some(gpos) = gpos {
+            builder.add_table(Tag::new(b"GPOS"), gpos);
+        }
+        if let Some((min, default, max)) = opsz {
+            builder.add_axis(OPSZ, min, default, max);
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn size_feature() {
+        let data = build_font(Some(build_gpos(6, [100, 1, 256, 80, 140])), None);
+        let font = FontRef::new(&data).unwrap();
+        let optical_size = font.optical_size();
+        let params = optical_size.params().unwrap();
+        assert_eq!(params.design_size, 10.0);
+        assert_eq!(params.subfamily_id, 1);
+        assert_eq!(params.subfamily_name_id, Some(StringId::new(256)));
+        assert_eq!(params.range, Some((8.0, 14.0)));
+        assert_eq!(optical_size.design_size(), Some(10.0));
+        assert!(!optical_size.supports(8.0));
+        assert!(optical_size.supports(14.0));
+        assert!(optical_size.setting(12.0).is_none());
+    }
+
+    #[test]
+    fn size_feature_relative_to_feature_list() {
+        let data = build_font(Some(build_gpos(14, [120, 0, 0, 0, 0])), None);
+        let font = FontRef::new(&data).unwrap();
+        let optical_size = font.optical_size();
+        let params = optical_size.params().unwrap();
+        assert_eq!(params.design_size, 12.0);
+        assert_eq!(params.subfamily_name_id, None);
+        assert_eq!(params.range, None);
+        assert!(!optical_size.supports(12.0));
+    }
+
+    #[test]
+    fn invalid_size_feature() {
+        // Design size outside of the range
+        let data = build_font(Some(build_gpos(6, [200, 1, 256, 80, 140])), None);
+        let font = FontRef::new(&data).unwrap();
+        assert!(font.optical_size().is_empty());
+    }
+
+    #[test]
+    fn opsz_axis() {
+        let data = build_font(
+            Some(build_gpos(6, [100, 1, 256, 80, 140])),
+            Some((6.0, 12.0, 72.0)),
+        );
+        let font = FontRef::new(&data).unwrap();
+        let optical_size = font.optical_size();
+        // The axis takes precedence over the feature parameters.
+        assert_eq!(optical_size.design_size(), Some(12.0));
+        assert_eq!(optical_size.range(), Some((6.0, 72.0)));
+        assert!(optical_size.supports(48.0));
+        assert!(!optical_size.supports(96.0));
+        let setting = optical_size.setting(96.0).unwrap();
+        assert_eq!(setting.selector, OPSZ);
+        assert_eq!(setting.value, 72.0);
+        assert_eq!(optical_size.setting(9.0).unwrap().value, 9.0);
+    }
+
+    /// Builds a `STAT` table with a format 2 axis value for the optical
+    /// size axis.
+    fn build_stat(nominal: i32, min: i32, max: i32) -> Vec<u8> {
+        let mut stat = Writer::default();
+        // Version 1.1, design axis size and count, design axes offset
+        stat.u16(1);
+        stat.u16(1);
+        stat.u16(8);
+        stat.u16(1);
+        stat.u32(20);
+        // Axis value count and offset, elided fallback name
+        stat.u16(1);
+        stat.u32(28);
+        stat.u16(2);
+        // Design axis at 20
+        stat.u32(u32::from_be_bytes(*b"opsz"));
+        stat.u16(256);
+        stat.u16(0);
+        // Axis value offsets at 28, followed by the axis value at 30
+        stat.u16(2);
+        stat.u16(2);
+        stat.u16(0);
+        stat.u16(0);
+        stat.u16(257);
+        for value in [nominal, min, max] {
+            stat.u32((value << 16) as u32);
+        }
+        stat.0
+    }
+
+    /// Builds a version 5 `OS/2` table with the given optical point sizes
+    /// in twentieths of a point.
+    fn build_os2(lower: u16, upper: u16) -> Vec<u8> {
+        let mut os2 = Writer::default();
+        os2.u16(5);
+        os2.0.resize(96, 0);
+        os2.u16(lower);
+        os2.u16(upper);
+        os2.0
+    }
+
+    #[test]
+    fn stat_and_os2() {
+        let mut builder = FontBuilder::with_squares(1);
+        builder.add_table(Tag::new(b"STAT"), build_stat(12, 9, 18));
+        builder.add_table(Tag::new(b"OS/2"), build_os2(180, 400));
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let optical_size = font.optical_size();
+        assert_eq!(
+            optical_size.stat(),
+            Some(&StatOpticalSize {
+                design_size: 12.0,
+                range: Some((9.0, 18.0)),
+            })
+        );
+        assert_eq!(optical_size.os2_range(), Some((9.0, 20.0)));
+        assert_eq!(optical_size.design_size(), Some(12.0));
+        // The STAT range takes precedence over the OS/2 range.
+        assert_eq!(optical_size.range(), Some((9.0, 18.0)));
+        assert!(optical_size.supports(18.0));
+        assert!(!optical_size.supports(19.0));
+        // Only the OS/2 range
+        let mut builder = FontBuilder::with_squares(1);
+        builder.add_table(Tag::new(b"OS/2"), build_os2(180, 400));
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let optical_size = font.optical_size();
+        assert!(!optical_size.is_empty());
+        assert_eq!(optical_size.design_size(), None);
+        assert!(optical_size.supports(9.0));
+        assert!(!optical_size.supports(20.0));
+    }
+}
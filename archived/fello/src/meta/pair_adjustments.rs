@@ -0,0 +1,214 @@
+//! Pair positioning adjustments.
+//!
+//! [`PairAdjustments`] looks up the value records that the pair adjustment
+//! lookups of the `GPOS` table, such as those of the `kern` feature, apply
+//! to a pair of glyphs. Fonts hinted for low resolution screens refine
+//! kerning at particular sizes with classic device tables; these are kept
+//! as [`DeviceDeltas`] on each [`ValueRecord`].
+
+use alloc::vec::Vec;
+use read_fonts::{
+    tables::gpos::{
+        DeviceOrVariationIndex, PairPos, PositionSubtables, ValueRecord as RawValueRecord,
+    },
+    types::GlyphId,
+    FontData, ReadError, TableProvider,
+};
+
+use super::anchors::DeviceDeltas;
+
+/// Adjustments to the position and advance of a glyph, in font units at
+/// the default location.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct ValueRecord {
+    /// Horizontal adjustment for placement.
+    pub x_placement: i16,
+    /// Vertical adjustment for placement.
+    pub y_placement: i16,
+    /// Horizontal adjustment for advance.
+    pub x_advance: i16,
+    /// Vertical adjustment for advance.
+    pub y_advance: i16,
+    /// Device table adjustments for the horizontal and vertical placement.
+    pub placement_devices: [Option<DeviceDeltas>; 2],
+    /// Device table adjustments for the horizontal and vertical advance.
+    pub advance_devices: [Option<DeviceDeltas>; 2],
+}
+
+impl ValueRecord {
+    /// Converts a value record whose device offsets are relative to the
+    /// given data.
+    ///
+    /// Variation index tables are ignored.
+    fn new(record: &RawValueRecord, data: FontData) -> Self {
+        let device = |table: Option<Result<DeviceOrVariationIndex, ReadError>>| match table {
+            Some(Ok(DeviceOrVariationIndex::Device(device))) => {
+                Some(DeviceDeltas::new(&device)).filter(|deltas| !deltas.is_empty())
+            }
+            _ => None,
+        };
+        Self {
+            x_placement: record.x_placement().unwrap_or_default(),
+            y_placement: record.y_placement().unwrap_or_default(),
+            x_advance: record.x_advance().unwrap_or_default(),
+            y_advance: record.y_advance().unwrap_or_default(),
+            placement_devices: [
+                device(record.x_placement_device(data)),
+                device(record.y_placement_device(data)),
+            ],
+            advance_devices: [
+                device(record.x_advance_device(data)),
+                device(record.y_advance_device(data)),
+            ],
+        }
+    }
+}
+
+/// Adjustments for the first and second glyphs of a pair.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct PairAdjustment {
+    /// Adjustments for the first glyph.
+    pub first: ValueRecord,
+    /// Adjustments for the second glyph.
+    pub second: ValueRecord,
+}
+
+/// Collection of the pair adjustment subtables in a font.
+#[derive(Clone)]
+pub struct PairAdjustments<'a> {
+    subtables: Vec<PairPos<'a>>,
+}
+
+impl<'a> PairAdjustments<'a> {
+    /// Collects the pair adjustment subtables from the `GPOS` table of the
+    /// given font, in lookup order.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let mut subtables = Vec::new();
+        if let Ok(lookups) = font.gpos().and_then(|gpos| gpos.lookup_list()) {
+            for lookup in lookups.lookups().iter().flatten() {
+                if let Ok(PositionSubtables::Pair(pair_subtables)) = lookup.subtables() {
+                    subtables.extend(pair_subtables.iter().flatten());
+                }
+            }
+        }
+        Self { subtables }
+    }
+
+    /// Returns the adjustments for the given pair of glyphs from the first
+    /// subtable that contains the pair.
+    ///
+    /// Adjustments from separate lookups are not combined.
+    pub fn get(&self, first: GlyphId, second: GlyphId) -> Option<PairAdjustment> {
+        self.subtables
+            .iter()
+            .find_map(|subtable| pair_adjustment(subtable, first, second).ok()?)
+    }
+}
+
+fn pair_adjustment(
+    subtable: &PairPos,
+    first: GlyphId,
+    second: GlyphId,
+) -> Result<Option<PairAdjustment>, ReadError> {
+    // Device table offsets are from the start of the subtable.
+    match subtable {
+        PairPos::Format1(subtable) => {
+            let Some(index) = subtable.coverage()?.get(first) else {
+                return Ok(None);
+            };
+            let pair_set = subtable.pair_sets().get(index as usize)?;
+            for record in pair_set.pair_value_records().iter() {
+                let record = record?;
+                if record.second_glyph() == second {
+                    let data = subtable.offset_data();
+                    return Ok(Some(PairAdjustment {
+                        first: ValueRecord::new(record.value_record1(), data),
+                        second: ValueRecord::new(record.value_record2(), data),
+                    }));
+                }
+            }
+            Ok(None)
+        }
+        PairPos::Format2(subtable) => {
+            if subtable.coverage()?.get(first).is_none() {
+                return Ok(None);
+            }
+            let class1 = subtable.class_def1()?.get(first);
+            let class2 = subtable.class_def2()?.get(second);
+            if class1 >= subtable.class1_count() || class2 >= subtable.class2_count() {
+                return Ok(None);
+            }
+            let class1_record = subtable.class1_records().get(class1 as usize)?;
+            let record = class1_record.class2_records().get(class2 as usize)?;
+            let data = subtable.offset_data();
+            Ok(Some(PairAdjustment {
+                first: ValueRecord::new(record.value_record1(), data),
+                second: ValueRecord::new(record.value_record2(), data),
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{subtables, FontBuilder, LayoutBuilder},
+        MetadataProvider,
+    };
+    use alloc::vec;
+    use read_fonts::{types::Tag, FontRef};
+
+    fn build_test_font(device: Option<(u16, u16, u16, &[u16])>) -> Vec<u8> {
+        let mut builder = FontBuilder::with_squares(3);
+        let mut gpos = LayoutBuilder::new();
+        gpos.add_lookup(
+            2,
+            vec![subtables::pair_pos(
+                GlyphId::new(1),
+                GlyphId::new(2),
+                -50,
+                device,
+            )],
+        );
+        builder.add_table(Tag::new(b"GPOS"), gpos.build());
+        builder.build()
+    }
+
+    #[test]
+    fn kerning_pair() {
+        let data = build_test_font(None);
+        let font = FontRef::new(&data).unwrap();
+        let adjustments = font.pair_adjustments();
+        let pair = adjustments.get(GlyphId::new(1), GlyphId::new(2)).unwrap();
+        assert_eq!(pair.first.x_advance, -50);
+        assert_eq!(pair.first.advance_devices, [None, None]);
+        assert_eq!(pair.second, ValueRecord::default());
+        assert!(adjustments.get(GlyphId::new(2), GlyphId::new(1)).is_none());
+    }
+
+    #[test]
+    fn kerning_pair_with_device_table() {
+        // Sizes 11 to 14 with 4-bit deltas of 1, -1, 0 and -8
+        let data = build_test_font(Some((11, 14, 2, &[0x1F08])));
+        let font = FontRef::new(&data).unwrap();
+        let pair = font
+            .pair_adjustments()
+            .get(GlyphId::new(1), GlyphId::new(2))
+            .unwrap();
+        assert_eq!(pair.first.x_advance, -50);
+        let device = pair.first.advance_devices[0].as_ref().unwrap();
+        assert_eq!(
+            device.iter().collect::<Vec<_>>(),
+            [(11, 1), (12, -1), (14, -8)]
+        );
+        assert_eq!(pair.first.advance_devices[1], None);
+        assert_eq!(pair.first.placement_devices, [None, None]);
+        // The second class of the first glyph has no device table.
+        let other = font
+            .pair_adjustments()
+            .get(GlyphId::new(1), GlyphId::new(0))
+            .unwrap();
+        assert_eq!(other.first, ValueRecord::default());
+    }
+}
@@ -0,0 +1,382 @@
+//! Color palettes from the `CPAL` table.
+//!
+//! Color glyphs refer to palette entries by index. The special index
+//! [`FOREGROUND_INDEX`] selects the text foreground color, which is not
+//! stored in the font and is surfaced as [`PaletteColor::Foreground`]. An
+//! index past the end of a palette is clamped to the last entry and the
+//! original index is recorded in the [`ResolvedColor`] so that callers can
+//! report the problem.
+//!
+//! Version 1 of the table marks palettes as usable with light or dark
+//! backgrounds, which [`Palettes::select`] takes into account when choosing
+//! a palette. [`Palette::to_rgba8`] produces the colors of a palette in a
+//! form that can be uploaded as a texture for rendering color glyphs on the
+//! GPU.
+
+use alloc::vec::Vec;
+use read_fonts::{
+    tables::cpal::{ColorRecord, PaletteType},
+    types::BigEndian,
+    TableProvider,
+};
+
+/// Palette entry index that selects the text foreground color.
+pub const FOREGROUND_INDEX: u16 = 0xFFFF;
+
+/// Color with 8-bit sRGB components and straight alpha.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct Color {
+    /// Red component.
+    pub red: u8,
+    /// Green component.
+    pub green: u8,
+    /// Blue component.
+    pub blue: u8,
+    /// Alpha component, where 0 is fully transparent.
+    pub alpha: u8,
+}
+
+/// Backgrounds that a palette is declared to be usable with.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub struct PaletteUsability {
+    /// The palette is appropriate for a light background.
+    pub light_background: bool,
+    /// The palette is appropriate for a dark background.
+    pub dark_background: bool,
+}
+
+impl PaletteUsability {
+    fn from_type(palette_type: PaletteType) -> Self {
+        Self {
+            light_background: palette_type.contains(PaletteType::USABLE_WITH_LIGHT_BACKGROUND),
+            dark_background: palette_type.contains(PaletteType::USABLE_WITH_DARK_BACKGROUND),
+        }
+    }
+}
+
+/// Color selected by a palette entry index.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PaletteColor {
+    /// Color stored in the palette.
+    Color(Color),
+    /// Placeholder for the text foreground color, which is chosen by the
+    /// application.
+    Foreground,
+}
+
+/// Result of resolving a palette entry index.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct ResolvedColor {
+    /// Color for the entry.
+    pub color: PaletteColor,
+    /// The requested index if it was past the end of the palette and was
+    /// clamped to the last entry.
+    pub clamped_from: Option<u16>,
+}
+
+/// Collection of the color palettes in a font.
+#[derive(Copy, Clone, Default)]
+pub struct Palettes<'a> {
+    records: &'a [ColorRecord],
+    first_records: &'a [BigEndian<u16>],
+    types: &'a [BigEndian<PaletteType>],
+    entry_count: u16,
+}
+
+impl<'a> Palettes<'a> {
+    /// Creates a new palette collection for the given font.
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        let Ok(cpal) = font.cpal() else {
+            return Self::default();
+        };
+        Self {
+            records: cpal
+                .color_records_array()
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+            first_records: cpal.color_record_indices(),
+            // Palette types are present from version 1.
+            types: cpal
+                .palette_types_array()
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+            entry_count: cpal.num_palette_entries(),
+        }
+    }
+
+    /// Returns the number of palettes.
+    pub fn len(&self) -> usize {
+        self.first_records.len()
+    }
+
+    /// Returns true if the font contains no palettes.
+    pub fn is_empty(&self) -> bool {
+        self.first_records.is_empty()
+    }
+
+    /// Returns the number of entries in each palette.
+    pub fn entry_count(&self) -> usize {
+        self.entry_count as usize
+    }
+
+    /// Returns the palette at the given index.
+    pub fn get(&self, index: usize) -> Option<Palette<'a>> {
+        let first_record = self.first_records.get(index)?.get() as usize;
+        // Palettes that extend past the color records are truncated.
+        let records = self.records.get(first_record..).unwrap_or_default();
+        let len = records.len().min(self.entry_count as usize);
+        let usability = self
+            .types
+            .get(index)
+            .map(|palette_type| PaletteUsability::from_type(palette_type.get()))
+            .unwrap_or_default();
+        Some(Palette {
+            records: &records[..len],
+            usability,
+        })
+    }
+
+    /// Returns the palette to use for text drawn on a light or dark
+    /// background.
+    ///
+    /// This is the first palette that is declared usable with the
+    /// background. When no palette declares its usability for the
+    /// background, the first palette is returned since it is the default.
+    pub fn select(&self, dark_background: bool) -> Option<Palette<'a>> {
+        self.iter()
+            .find(|palette| {
+                let usability = palette.usability();
+                if dark_background {
+                    usability.dark_background
+                } else {
+                    usability.light_background
+                }
+            })
+            .or_else(|| self.get(0))
+    }
+
+    /// Returns an iterator over all palettes.
+    pub fn iter(&self) -> impl Iterator<Item = Palette<'a>> + 'a + Clone {
+        let copy = *self;
+        (0..self.len()).filter_map(move |index| copy.get(index))
+    }
+}
+
+/// Sequence of colors that can be referenced by color glyphs.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Palette<'a> {
+    records: &'a [ColorRecord],
+    usability: PaletteUsability,
+}
+
+impl<'a> Palette<'a> {
+    /// Returns the number of entries in the palette.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if the palette contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns the backgrounds that the palette is declared to be usable
+    /// with.
+    ///
+    /// Both are false for palettes in version 0 tables and palettes
+    /// without declared usability.
+    pub fn usability(&self) -> PaletteUsability {
+        self.usability
+    }
+
+    /// Returns the color for the entry at the given index.
+    pub fn get(&self, index: u16) -> Option<Color> {
+        let record = self.records.get(index as usize)?;
+        Some(Color {
+            red: record.red(),
+            green: record.green(),
+            blue: record.blue(),
+            alpha: record.alpha(),
+        })
+    }
+
+    /// Returns an iterator over the colors in the palette.
+    pub fn colors(&self) -> impl Iterator<Item = Color> + 'a + Clone {
+        let copy = *self;
+        (0..self.len() as u16).filter_map(move |index| copy.get(index))
+    }
+
+    /// Returns the colors of the palette as 8-bit RGBA components with
+    /// straight alpha, four bytes per entry in entry order.
+    ///
+    /// The buffer can be uploaded as a texture and indexed by palette entry
+    /// when rendering color glyphs on the GPU. The foreground color is not
+    /// part of the palette and must be supplied separately.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        self.records
+            .iter()
+            .flat_map(|record| [record.red(), record.green(), record.blue(), record.alpha()])
+            .collect()
+    }
+
+    /// Resolves a palette entry index as referenced by a color glyph.
+    ///
+    /// [`FOREGROUND_INDEX`] produces [`PaletteColor::Foreground`]. Other
+    /// indices past the end of the palette are clamped to the last entry,
+    /// or resolve to the foreground color if the palette is empty.
+    pub fn resolve(&self, index: u16) -> ResolvedColor {
+        if index == FOREGROUND_INDEX {
+            return ResolvedColor {
+                color: PaletteColor::Foreground,
+                clamped_from: None,
+            };
+        }
+        if let Some(color) = self.get(index) {
+            return ResolvedColor {
+                color: PaletteColor::Color(color),
+                clamped_from: None,
+            };
+        }
+        let color = self
+            .len()
+            .checked_sub(1)
+            .and_then(|last| self.get(last as u16))
+            .map(PaletteColor::Color)
+            .unwrap_or(PaletteColor::Foreground);
+        ResolvedColor {
+            color,
+            clamped_from: Some(index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::FontBuilder, write::Writer, MetadataProvider};
+    use alloc::vec::Vec;
+    use read_fonts::{types::Tag, FontRef};
+
+    /// Builds a font with two palettes of two entries each.
+    fn build_test_font() -> Vec<u8> {
+        let mut cpal = Writer::default();
+        // Version 0 with 2 entries per palette, 2 palettes and 4 records
+        cpal.u16(0);
+        cpal.u16(2);
+        cpal.u16(2);
+        cpal.u16(4);
+        // Color records at 16
+        cpal.u32(16);
+        cpal.u16(0);
+        cpal.u16(2);
+        for record in [
+            [0, 0, 255, 255],
+            [255, 0, 0, 128],
+            [0, 255, 0, 255],
+            [0, 0, 0, 0],
+        ] {
+            cpal.0.extend_from_slice(&record);
+        }
+        let mut builder = FontBuilder::new(1000);
+        builder.add_table(Tag::new(b"CPAL"), cpal.0);
+        builder.build()
+    }
+
+    #[test]
+    fn palette_colors() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let palettes = font.palettes();
+        assert_eq!(palettes.len(), 2);
+        assert_eq!(palettes.entry_count(), 2);
+        let red = Color {
+            red: 255,
+            green: 0,
+            blue: 0,
+            alpha: 255,
+        };
+        let palette = palettes.get(0).unwrap();
+        assert_eq!(palette.get(0), Some(red));
+        assert_eq!(palette.get(2), None);
+        let palette = palettes.get(1).unwrap();
+        assert_eq!(palette.colors().count(), 2);
+        assert_eq!(palette.get(1), Some(Color::default()));
+        assert!(palettes.get(2).is_none());
+    }
+
+    #[test]
+    fn resolve_entries() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let palette = font.palettes().get(0).unwrap();
+        assert_eq!(
+            palette.resolve(FOREGROUND_INDEX),
+            ResolvedColor {
+                color: PaletteColor::Foreground,
+                clamped_from: None
+            }
+        );
+        let last = palette.get(1).unwrap();
+        assert_eq!(
+            palette.resolve(7),
+            ResolvedColor {
+                color: PaletteColor::Color(last),
+                clamped_from: Some(7)
+            }
+        );
+        let empty = Palette {
+            records: &[],
+            usability: PaletteUsability::default(),
+        };
+        assert_eq!(empty.resolve(0).color, PaletteColor::Foreground);
+    }
+
+    #[test]
+    fn select_by_usability() {
+        let mut cpal = Writer::default();
+        // Version 1 with 1 entry per palette, 2 palettes and 2 records
+        cpal.u16(1);
+        cpal.u16(1);
+        cpal.u16(2);
+        cpal.u16(2);
+        // Color records at 28
+        cpal.u32(28);
+        cpal.u16(0);
+        cpal.u16(1);
+        // Palette types at 36, no labels
+        cpal.u32(36);
+        cpal.u32(0);
+        cpal.u32(0);
+        cpal.0.extend_from_slice(&[16, 32, 48, 255]);
+        cpal.0.extend_from_slice(&[200, 210, 220, 128]);
+        // Light and dark palettes
+        cpal.u32(1);
+        cpal.u32(2);
+        let mut builder = FontBuilder::new(1000);
+        builder.add_table(Tag::new(b"CPAL"), cpal.0);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let palettes = font.palettes();
+        let light = palettes.select(false).unwrap();
+        assert!(light.usability().light_background);
+        assert_eq!(light.to_rgba8(), [48, 32, 16, 255]);
+        let dark = palettes.select(true).unwrap();
+        assert_eq!(
+            dark.usability(),
+            PaletteUsability {
+                light_background: false,
+                dark_background: true
+            }
+        );
+        assert_eq!(dark.to_rgba8(), [220, 210, 200, 128]);
+        // Version 0 tables fall back to the first palette.
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let palettes = font.palettes();
+        assert_eq!(palettes.select(true), palettes.get(0));
+        assert_eq!(
+            palettes.get(0).unwrap().to_rgba8(),
+            [255, 0, 0, 255, 0, 0, 255, 128]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Values for embedding fonts in PDF documents.
+//!
+//! PDF expresses font metrics in glyph space units, where one em is 1000
+//! units, so all values here are scaled accordingly regardless of the units
+//! per em of the font.
+//!
+//! See section 9.8 "Font Descriptors" and 9.7.4.3 "Glyph Metrics in CIDFonts"
+//! of the PDF 32000-1:2008 specification.
+
+use alloc::vec::Vec;
+use read_fonts::{
+    tables::{cmap::PlatformId, os2::SelectionFlags},
+    types::GlyphId,
+    TableProvider,
+};
+
+use super::metrics::{BoundingBox, GlyphMetrics, Metrics};
+use crate::{NormalizedCoords, Size};
+
+/// Number of glyph space units per em.
+const GLYPH_SPACE_UNITS: f32 = 1000.0;
+
+/// Flags that specify characteristics of a font in a PDF font descriptor.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorFlags(pub u32);
+
+impl DescriptorFlags {
+    /// All glyphs have the same width.
+    pub const FIXED_PITCH: Self = Self(1 << 0);
+    /// Glyphs have serifs.
+    pub const SERIF: Self = Self(1 << 1);
+    /// Font contains glyphs outside the Adobe standard Latin character set.
+    pub const SYMBOLIC: Self = Self(1 << 2);
+    /// Glyphs resemble cursive handwriting.
+    pub const SCRIPT: Self = Self(1 << 3);
+    /// Font uses the Adobe standard Latin character set or a subset of it.
+    pub const NONSYMBOLIC: Self = Self(1 << 5);
+    /// Glyphs have dominant vertical strokes that are slanted.
+    pub const ITALIC: Self = Self(1 << 6);
+
+    /// Returns true if all flags in `other` are set.
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for DescriptorFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl core::ops::BitOrAssign for DescriptorFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Values of a PDF `FontDescriptor` dictionary.
+///
+/// All metrics are in glyph space units.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FontDescriptor {
+    /// Characteristics of the font for the `Flags` entry.
+    pub flags: DescriptorFlags,
+    /// Union of the bounding boxes of all glyphs for the `FontBBox` entry.
+    pub bbox: BoundingBox,
+    /// Italic angle in counter-clockwise degrees from the vertical for the
+    /// `ItalicAngle` entry.
+    pub italic_angle: f32,
+    /// Distance from the baseline to the top of the alignment box for the
+    /// `Ascent` entry.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the alignment box for
+    /// the `Descent` entry. This is usually negative.
+    pub descent: f32,
+    /// Height of flat capital letters for the `CapHeight` entry. Falls back
+    /// to the ascent when the font does not specify it.
+    pub cap_height: f32,
+    /// Height of flat lowercase letters for the `XHeight` entry.
+    pub x_height: Option<f32>,
+    /// Estimated thickness of dominant vertical stems for the `StemV` entry.
+    pub stem_v: f32,
+    /// Average glyph width for the `AvgWidth` entry.
+    pub average_width: Option<f32>,
+    /// Maximum glyph width for the `MaxWidth` entry.
+    pub max_width: Option<f32>,
+}
+
+impl FontDescriptor {
+    /// Computes the font descriptor for the given font at the specified
+    /// normalized variation coordinates.
+    pub fn new<'a>(font: &impl TableProvider<'a>, coords: NormalizedCoords<'a>) -> Self {
+        let metrics = Metrics::new(font, Size::new(GLYPH_SPACE_UNITS), coords);
+        let os2 = font.os2().ok();
+        let mut flags = DescriptorFlags::default();
+        if metrics.is_monospace {
+            flags |= DescriptorFlags::FIXED_PITCH;
+        }
+        // The high byte of sFamilyClass is the IBM font class.
+        // See <https://learn.microsoft.com/en-us/typography/opentype/spec/ibmfc>
+        match os2.as_ref().map(|os2| os2.s_family_class() >> 8) {
+            Some(1..=5 | 7) => flags |= DescriptorFlags::SERIF,
+            Some(10) => flags |= DescriptorFlags::SCRIPT,
+            _ => {}
+        }
+        // Fonts with a symbol character map are addressed by glyph codes
+        // rather than the standard Latin character set.
+        const ENCODING_MS_SYMBOL: u16 = 0;
+        let is_symbol = font.cmap().map_or(false, |cmap| {
+            cmap.encoding_records().iter().any(|record| {
+                record.platform_id() == PlatformId::Windows
+                    && record.encoding_id() == ENCODING_MS_SYMBOL
+            })
+        });
+        flags |= if is_symbol {
+            DescriptorFlags::SYMBOLIC
+        } else {
+            DescriptorFlags::NONSYMBOLIC
+        };
+        let is_italic = os2.as_ref().map_or(false, |os2| {
+            os2.fs_selection().contains(SelectionFlags::ITALIC)
+        });
+        if is_italic || metrics.italic_angle != 0.0 {
+            flags |= DescriptorFlags::ITALIC;
+        }
+        // PDF does not require an accurate value so estimate the stem
+        // width from the weight class, which yields about 88 for regular
+        // and 166 for bold weights.
+        let weight = os2
+            .as_ref()
+            .map(|os2| os2.us_weight_class() as f32)
+            .unwrap_or(400.0);
+        let stem_v = 50.0 + (weight / 65.0) * (weight / 65.0);
+        Self {
+            flags,
+            bbox: metrics.bounds.unwrap_or_default(),
+            italic_angle: metrics.italic_angle,
+            ascent: metrics.ascent,
+            descent: metrics.descent,
+            cap_height: metrics.cap_height.unwrap_or(metrics.ascent),
+            x_height: metrics.x_height,
+            stem_v,
+            average_width: metrics.average_width,
+            max_width: metrics.max_width,
+        }
+    }
+}
+
+/// Run of consecutive glyphs and their widths, matching the `c [w1 w2 ...]`
+/// form of the `W` array of a CIDFont.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WidthRun {
+    /// Identifier of the first glyph in the run.
+    pub first: u16,
+    /// Advance widths of the glyphs in the run, in glyph space units.
+    pub widths: Vec<f32>,
+}
+
+/// Returns the advance widths of the given glyph subset at the specified
+/// normalized variation coordinates, grouped into runs of consecutive glyph
+/// identifiers.
+///
+/// The glyph identifiers may be given in any order and may contain
+/// duplicates. Glyphs without metrics have a width of zero.
+pub fn glyph_widths<'a>(
+    font: &impl TableProvider<'a>,
+    coords: NormalizedCoords<'a>,
+    glyph_ids: impl IntoIterator<Item = GlyphId>,
+) -> Vec<WidthRun> {
+    let metrics = GlyphMetrics::new(font, Size::new(GLYPH_SPACE_UNITS), coords);
+    let mut glyph_ids: Vec<u16> = glyph_ids.into_iter().map(|gid| gid.to_u16()).collect();
+    glyph_ids.sort_unstable();
+    glyph_ids.dedup();
+    let mut runs: Vec<WidthRun> = Vec::new();
+    for gid in glyph_ids {
+        let width = metrics.advance_width(GlyphId::new(gid)).unwrap_or_default();
+        match runs.last_mut() {
+            Some(run) if run.first as usize + run.widths.len() == gid as usize => {
+                run.widths.push(width);
+            }
+            _ => runs.push(WidthRun {
+                first: gid,
+                widths: alloc::vec![width],
+            }),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MetadataProvider;
+    use read_fonts::FontRef;
+
+    #[test]
+    fn font_descriptor() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let descriptor = font.font_descriptor(NormalizedCoords::default());
+        let scale = GLYPH_SPACE_UNITS / font.head().unwrap().units_per_em() as f32;
+        let metrics = font.metrics(Size::unscaled(), NormalizedCoords::default());
+        assert_eq!(descriptor.ascent, metrics.ascent * scale);
+        assert_eq!(descriptor.descent, metrics.descent * scale);
+        assert!(descriptor.flags.contains(DescriptorFlags::NONSYMBOLIC));
+        assert!(!descriptor.flags.contains(DescriptorFlags::SYMBOLIC));
+        assert!(!descriptor.flags.contains(DescriptorFlags::ITALIC));
+        assert!(descriptor.stem_v > 0.0);
+    }
+
+    #[test]
+    fn width_runs() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let gids = [3, 1, 2, 2, 5].map(GlyphId::new);
+        let runs = glyph_widths(&font, NormalizedCoords::default(), gids);
+        let metrics = font.glyph_metrics(Size::new(GLYPH_SPACE_UNITS), NormalizedCoords::default());
+        let width = |gid| metrics.advance_width(GlyphId::new(gid)).unwrap();
+        assert_eq!(
+            runs,
+            [
+                WidthRun {
+                    first: 1,
+                    widths: alloc::vec![width(1), width(2), width(3)],
+                },
+                WidthRun {
+                    first: 5,
+                    widths: alloc::vec![width(5)],
+                },
+            ]
+        );
+    }
+}
@@ -1,15 +1,44 @@
 use super::{
+    aat::{AatAnchors, Kerning},
+    anchors::Anchors,
     attributes::Attributes,
     charmap::Charmap,
-    info_strings::InfoStrings,
-    metrics::{GlyphMetrics, Metrics},
+    features::{Features, LayoutStats},
+    glyph_names::GlyphNames,
+    info_strings::{InfoStrings, LocalizedString, StringId},
+    metrics::{self, GlyphMetrics, Metrics},
+    optical_size::OpticalSize,
+    pair_adjustments::PairAdjustments,
+    palettes::Palettes,
+    pdf::FontDescriptor,
     variations::{axis::Axes, instance::Instances},
+    version::VersionInfo,
 };
 
-use crate::{NormalizedCoord, NormalizedCoords, Size};
+use crate::{GlyphId, NormalizedCoord, NormalizedCoords, Size};
 
 /// Interface for types that can provide font metadata.
 pub trait MetadataProvider<'a>: raw::TableProvider<'a> + Sized {
+    /// Returns the mark attachment anchors defined in the `GPOS` table.
+    fn anchors(&self) -> Anchors<'a> {
+        Anchors::new(self)
+    }
+
+    /// Returns the pair adjustments defined in the `GPOS` table.
+    fn pair_adjustments(&self) -> PairAdjustments<'a> {
+        PairAdjustments::new(self)
+    }
+
+    /// Returns the anchor points defined in the AAT `ankr` table.
+    fn aat_anchors(&self) -> AatAnchors<'a> {
+        AatAnchors::new(self)
+    }
+
+    /// Returns the kerning defined in the AAT `kerx` table.
+    fn kerning(&self) -> Kerning<'a> {
+        Kerning::new(self)
+    }
+
     /// Returns the stretch, style and weight attributes.
     fn attributes(&self) -> Attributes {
         Attributes::new(self)
@@ -30,6 +59,34 @@ pub trait MetadataProvider<'a>: raw::TableProvider<'a> + Sized {
         InfoStrings::new(self)
     }
 
+    /// Returns the informational string with the given identifier that best
+    /// matches the BCP 47 language tags, which are given in order of
+    /// preference.
+    ///
+    /// See [`InfoStrings::find`] for the matching rules.
+    fn string(&self, id: StringId, languages: &[&str]) -> Option<LocalizedString<'a>> {
+        self.info_strings().find(id, languages)
+    }
+
+    /// Returns the number of font design units per em unit.
+    ///
+    /// When the `head` table is missing or specifies zero, this is 1000 for
+    /// fonts with PostScript outlines and 2048 otherwise.
+    fn units_per_em(&self) -> u16 {
+        metrics::units_per_em(self)
+    }
+
+    /// Returns true if the glyph identifier refers to a glyph in the font.
+    ///
+    /// Glyph identifiers from untrusted sources, such as the output of a
+    /// shaper run on a different font, should be checked with this and
+    /// replaced with [`GlyphId::NOTDEF`] when invalid. Metrics and outlines
+    /// are not available for invalid identifiers: [`GlyphMetrics`] returns
+    /// `None` and loading an outline fails with a glyph not found error.
+    fn is_valid_glyph(&self, glyph_id: GlyphId) -> bool {
+        glyph_id.to_u16() < metrics::glyph_count(self)
+    }
+
     /// Returns the global font metrics for the specified size and normalized variation
     /// coordinates.
     fn metrics(&self, size: Size, coords: NormalizedCoords<'a>) -> Metrics {
@@ -42,10 +99,49 @@ pub trait MetadataProvider<'a>: raw::TableProvider<'a> + Sized {
         GlyphMetrics::new(self, size, coords)
     }
 
+    /// Returns the values of a PDF font descriptor for the specified
+    /// normalized variation coordinates.
+    fn font_descriptor(&self, coords: NormalizedCoords<'a>) -> FontDescriptor {
+        FontDescriptor::new(self, coords)
+    }
+
     /// Returns the codepoint to nominal glyph identifier mapping.
     fn charmap(&self) -> Charmap<'a> {
         Charmap::new(self)
     }
+
+    /// Returns the typographic features supported for each script.
+    fn features(&self) -> Features {
+        Features::new(self)
+    }
+
+    /// Returns structural statistics for the lookups of each feature in
+    /// the layout tables.
+    fn layout_stats(&self) -> LayoutStats {
+        LayoutStats::new(self)
+    }
+
+    /// Returns the optical size metadata from the `size` feature and the
+    /// `opsz` axis.
+    fn optical_size(&self) -> OpticalSize {
+        OpticalSize::new(self)
+    }
+
+    /// Returns the mapping of glyph identifiers to names.
+    fn glyph_names(&self) -> GlyphNames<'a> {
+        GlyphNames::new(self)
+    }
+
+    /// Returns the color palettes defined in the `CPAL` table.
+    fn palettes(&self) -> Palettes<'a> {
+        Palettes::new(self)
+    }
+
+    /// Returns the font revision, the version parsed from the version
+    /// string and the creation and modification dates.
+    fn version_info(&self) -> VersionInfo {
+        VersionInfo::new(self)
+    }
 }
 
 /// Blanket implementation of `MetadataProvider` for any type that implements
@@ -0,0 +1,223 @@
+//! Corrections for fonts with known defects.
+//!
+//! Some widely shipped fonts have metrics that disagree with how platforms
+//! lay them out, such as ascents that clip accents or line gaps that were
+//! only ever honored by one platform. Applications that need to match the
+//! platform behavior can describe the corrections as [`Quirk`]s keyed by
+//! family name and revision and apply them with [`Quirks::metrics`].
+//!
+//! No quirks are built in; the data is supplied by the application.
+
+use alloc::{string::String, vec::Vec};
+use read_fonts::TableProvider;
+
+use super::{
+    info_strings::{InfoStrings, StringId},
+    metrics::Metrics,
+};
+use crate::{NormalizedCoords, Size};
+
+/// Replacement values for global metrics, in font units.
+///
+/// Values follow the conventions of [`Metrics`], so the descent is
+/// negative for descenders below the baseline. Fields that are `None` are
+/// left unchanged.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetricsQuirk {
+    /// Replacement for the ascent.
+    pub ascent: Option<i16>,
+    /// Replacement for the descent.
+    pub descent: Option<i16>,
+    /// Replacement for the leading.
+    pub leading: Option<i16>,
+    /// Replacement for the cap height.
+    pub cap_height: Option<i16>,
+    /// Replacement for the x-height.
+    pub x_height: Option<i16>,
+}
+
+impl MetricsQuirk {
+    /// Applies the replacements to metrics computed for the given size.
+    ///
+    /// The replacements are not varied, so they apply to all locations of
+    /// a variable font.
+    pub fn apply(&self, metrics: &mut Metrics, size: Size) {
+        let scale = size.linear_scale(metrics.units_per_em);
+        let scaled = |value: i16| value as f32 * scale;
+        if let Some(ascent) = self.ascent {
+            metrics.ascent = scaled(ascent);
+        }
+        if let Some(descent) = self.descent {
+            metrics.descent = scaled(descent);
+        }
+        if let Some(leading) = self.leading {
+            metrics.leading = scaled(leading);
+        }
+        if let Some(cap_height) = self.cap_height {
+            metrics.cap_height = Some(scaled(cap_height));
+            metrics.cap_height_measured = false;
+        }
+        if let Some(x_height) = self.x_height {
+            metrics.x_height = Some(scaled(x_height));
+            metrics.x_height_measured = false;
+        }
+    }
+}
+
+/// Correction for the fonts of a family.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quirk {
+    /// Name of the family, compared case insensitively with the
+    /// typographic family name or, if missing, the family name of a font.
+    pub family: String,
+    /// Inclusive range of revisions, as stored in the `fontRevision` field
+    /// of the `head` table, that the quirk applies to. Applies to all
+    /// revisions if `None`.
+    pub revisions: Option<(f64, f64)>,
+    /// Corrections for the global metrics.
+    pub metrics: MetricsQuirk,
+}
+
+/// Collection of quirks supplied by the application.
+#[derive(Clone, Default, Debug)]
+pub struct Quirks {
+    quirks: Vec<Quirk>,
+}
+
+impl Quirks {
+    /// Creates an empty collection of quirks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a quirk to the collection.
+    ///
+    /// When several quirks match a font, the one added first is used.
+    pub fn add(&mut self, quirk: Quirk) -> &mut Self {
+        self.quirks.push(quirk);
+        self
+    }
+
+    /// Returns the quirks in the collection.
+    pub fn quirks(&self) -> &[Quirk] {
+        &self.quirks
+    }
+
+    /// Returns the first quirk that matches the family name and revision
+    /// of the font.
+    pub fn find<'a>(&self, font: &impl TableProvider<'a>) -> Option<&Quirk> {
+        if self.quirks.is_empty() {
+            return None;
+        }
+        let strings = InfoStrings::new(font);
+        let family = strings
+            .find(StringId::TYPOGRAPHIC_FAMILY_NAME, &[])
+            .or_else(|| strings.find(StringId::FAMILY_NAME, &[]))?;
+        let family = family.string()?;
+        let revision = font
+            .head()
+            .map(|head| head.font_revision().to_f64())
+            .unwrap_or_default();
+        self.quirks.iter().find(|quirk| {
+            quirk
+                .revisions
+                .map_or(true, |(min, max)| revision >= min && revision <= max)
+                && eq_ignore_case(quirk.family.chars(), family.chars())
+        })
+    }
+
+    /// Returns the global metrics for the given font, size and normalized
+    /// variation coordinates with the corrections of the matching quirk
+    /// applied.
+    pub fn metrics<'a>(
+        &self,
+        font: &impl TableProvider<'a>,
+        size: Size,
+        coords: NormalizedCoords<'a>,
+    ) -> Metrics {
+        let mut metrics = Metrics::new(font, size, coords);
+        if let Some(quirk) = self.find(font) {
+            quirk.metrics.apply(&mut metrics, size);
+        }
+        metrics
+    }
+}
+
+fn eq_ignore_case(a: impl Iterator<Item = char>, b: impl Iterator<Item = char>) -> bool {
+    a.flat_map(char::to_lowercase)
+        .eq(b.flat_map(char::to_lowercase))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::FontBuilder, write::Writer, MetadataProvider};
+    use read_fonts::{types::Tag, FontRef};
+
+    /// Builds a font named "Quirky Sans" with an ascent of 800 and a
+    /// descent of -200.
+    fn build_font() -> Vec<u8> {
+        let family: Vec<u16> = "Quirky Sans".encode_utf16().collect();
+        let mut name = Writer::default();
+        // Format 0 with one record and strings at offset 18
+        name.u16(0);
+        name.u16(1);
+        name.u16(18);
+        // Windows, Unicode BMP, English (US), family name
+        name.u16(3);
+        name.u16(1);
+        name.u16(0x409);
+        name.u16(1);
+        name.u16(family.len() as u16 * 2);
+        name.u16(0);
+        for unit in family {
+            name.u16(unit);
+        }
+        let mut builder = FontBuilder::new(1000);
+        builder.add_table(Tag::new(b"name"), name.0);
+        builder.build()
+    }
+
+    fn quirk(family: &str, revisions: Option<(f64, f64)>) -> Quirk {
+        Quirk {
+            family: family.into(),
+            revisions,
+            metrics: MetricsQuirk {
+                ascent: Some(900),
+                leading: Some(100),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn apply_matching_quirk() {
+        let data = build_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut quirks = Quirks::new();
+        quirks.add(quirk("quirky sans", Some((1.0, 1.5))));
+        let size = Size::new(10.0);
+        let metrics = quirks.metrics(&font, size, Default::default());
+        let expected = font.metrics(size, Default::default());
+        assert_eq!(metrics.ascent, 9.0);
+        assert_eq!(metrics.descent, expected.descent);
+        assert_eq!(metrics.leading, 1.0);
+    }
+
+    #[test]
+    fn skip_other_fonts() {
+        let data = build_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut quirks = Quirks::new();
+        quirks
+            .add(quirk("Quirky Serif", None))
+            .add(quirk("Quirky Sans", Some((2.0, 3.0))));
+        assert!(quirks.find(&font).is_none());
+        assert_eq!(
+            quirks.metrics(&font, Size::unscaled(), Default::default()),
+            font.metrics(Size::unscaled(), Default::default())
+        );
+    }
+}
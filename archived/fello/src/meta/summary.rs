@@ -0,0 +1,114 @@
+//! Lightweight overview of a font.
+//!
+//! A [`Summary`] captures the basic properties needed to list a font in a
+//! user interface, such as a font manager, without loading heavier metadata
+//! like strings or variation data.
+
+use alloc::vec::Vec;
+use read_fonts::{types::Tag, FontRef, TableProvider};
+
+use super::metrics::{glyph_count, units_per_em};
+
+/// Tables that contain color glyph data.
+const COLOR_TABLES: &[&[u8; 4]] = &[b"COLR", b"CBDT", b"sbix", b"SVG "];
+
+/// Format of the glyph outlines in a font.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Flavor {
+    /// TrueType outlines in the `glyf` table.
+    TrueType,
+    /// PostScript outlines in the `CFF ` table.
+    Cff,
+    /// PostScript outlines in the `CFF2` table.
+    Cff2,
+    /// The font does not contain outlines.
+    #[default]
+    None,
+}
+
+/// Basic properties of a font.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Summary {
+    /// Format of the glyph outlines.
+    pub flavor: Flavor,
+    /// Number of glyphs in the font.
+    pub glyph_count: u16,
+    /// Number of font design units per em unit.
+    pub units_per_em: u16,
+    /// True if the font contains color glyphs in any of the `COLR`, `CBDT`,
+    /// `sbix` or `SVG ` tables.
+    pub has_color: bool,
+    /// True if the font contains variation data.
+    pub is_variable: bool,
+    /// Bytes of the tags of the tables present in the font with their sizes
+    /// in bytes, sorted by tag.
+    pub tables: Vec<([u8; 4], u32)>,
+}
+
+impl Summary {
+    /// Creates a summary for the given font.
+    ///
+    /// All tables in the table directory are listed in
+    /// [`tables`](Self::tables), including private tables.
+    pub fn new(font: &FontRef) -> Self {
+        let has_table = |tag: &[u8; 4]| font.data_for_tag(Tag::new(tag)).is_some();
+        let flavor = if has_table(b"glyf") {
+            Flavor::TrueType
+        } else if has_table(b"CFF ") {
+            Flavor::Cff
+        } else if has_table(b"CFF2") {
+            Flavor::Cff2
+        } else {
+            Flavor::None
+        };
+        let mut tables: Vec<_> = font
+            .table_directory
+            .table_records()
+            .iter()
+            .map(|record| (record.tag().to_be_bytes(), record.length()))
+            .collect();
+        // The directory should already be sorted but this isn't enforced.
+        tables.sort_unstable_by_key(|(tag, _)| *tag);
+        Self {
+            flavor,
+            glyph_count: glyph_count(font),
+            units_per_em: units_per_em(font),
+            has_color: COLOR_TABLES.iter().any(|tag| has_table(tag)),
+            is_variable: has_table(b"fvar"),
+            tables,
+        }
+    }
+
+    /// Returns the size in bytes of the table with the given tag if it is
+    /// present.
+    pub fn table_len(&self, tag: Tag) -> Option<u32> {
+        self.tables
+            .binary_search_by(|entry| entry.0.cmp(&tag.to_be_bytes()))
+            .ok()
+            .map(|index| self.tables[index].1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let summary = Summary::new(&font);
+        assert_eq!(summary.flavor, Flavor::TrueType);
+        assert_eq!(summary.glyph_count, font.maxp().unwrap().num_glyphs());
+        assert_eq!(summary.units_per_em, font.head().unwrap().units_per_em());
+        assert!(summary.is_variable);
+        assert!(!summary.has_color);
+        let records = font.table_directory.table_records();
+        assert_eq!(summary.tables.len(), records.len());
+        for record in records {
+            assert_eq!(summary.table_len(record.tag()), Some(record.length()));
+        }
+        assert!(summary.tables.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+}
@@ -2,8 +2,10 @@
 
 */
 
+use alloc::vec::Vec;
 use read_fonts::{
-    tables::avar::Avar,
+    array::VarLenArray,
+    tables::avar::{Avar, AxisValueMap, SegmentMaps},
     tables::fvar::{self, Fvar},
     types::{Fixed, Tag},
     TableProvider,
@@ -13,6 +15,28 @@ use crate::{meta::info_strings::StringId, NormalizedCoord};
 
 use super::VariationSetting;
 
+/// Properties of a variation axis that can be stored independently of the
+/// font.
+///
+/// The tag and name identifier are stored as plain values so the type can
+/// be serialized without support from `read-fonts`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AxisInfo {
+    /// Bytes of the tag that identifies the axis.
+    pub tag: [u8; 4],
+    /// Localized string identifier for the name of the axis.
+    pub name_id: u16,
+    /// True if the axis should be hidden in user interfaces.
+    pub is_hidden: bool,
+    /// Minimum value of the axis.
+    pub min_value: f32,
+    /// Default value of the axis.
+    pub default_value: f32,
+    /// Maximum value of the axis.
+    pub max_value: f32,
+}
+
 /// Axis of variation in a variable font.
 #[derive(Clone)]
 pub struct Axis {
@@ -44,7 +68,7 @@ impl Axis {
 
     /// Returns the minimum value of the axis.
     pub fn min_value(&self) -> f32 {
-        self.record.max_value().to_f64() as _
+        self.record.min_value().to_f64() as _
     }
 
     /// Returns the default value of the axis.
@@ -66,6 +90,35 @@ impl Axis {
             .normalize(Fixed::from_f64(coord as _))
             .to_f2dot14()
     }
+
+    /// Returns the user coordinate for the given normalized coordinate.
+    ///
+    /// This is the inverse of [`normalize`](Self::normalize) and likewise
+    /// does not apply any axis variation remapping.
+    pub fn denormalize(&self, coord: NormalizedCoord) -> f32 {
+        self.denormalize_f32(coord.to_f32())
+    }
+
+    fn denormalize_f32(&self, coord: f32) -> f32 {
+        let default = self.default_value();
+        if coord < 0.0 {
+            default + coord * (default - self.min_value())
+        } else {
+            default + coord * (self.max_value() - default)
+        }
+    }
+
+    /// Returns the properties of the axis as a plain value.
+    pub fn info(&self) -> AxisInfo {
+        AxisInfo {
+            tag: self.tag().to_be_bytes(),
+            name_id: self.name_id().to_u16(),
+            is_hidden: self.is_hidden(),
+            min_value: self.min_value(),
+            default_value: self.default_value(),
+            max_value: self.max_value(),
+        }
+    }
 }
 
 /// Collection of variation axes.
@@ -139,6 +192,21 @@ impl<'a> Axes<'a> {
         Normalize { storage, pos: 0 }
     }
 
+    /// Given a sequence of normalized design space coordinates, returns an
+    /// iterator over the corresponding variation settings in user space for
+    /// all axes in order.
+    ///
+    /// This is the inverse of [`normalize`](Self::normalize), including the
+    /// axis variation remapping. Axes without a coordinate in the sequence
+    /// produce their default values.
+    pub fn denormalize<'c>(&self, coords: &'c [NormalizedCoord]) -> Denormalize<'a, 'c> {
+        Denormalize {
+            axes: self.iter(),
+            avar_mappings: self.avar.as_ref().map(|avar| avar.axis_segment_maps()),
+            coords,
+        }
+    }
+
     /// Returns an iterator over the axes
     pub fn iter(&self) -> Iter<'a> {
         self.clone().into_iter()
@@ -190,6 +258,63 @@ impl Iterator for Normalize {
     }
 }
 
+/// Result of the [Axes::denormalize] method.
+#[derive(Clone)]
+pub struct Denormalize<'a, 'c> {
+    axes: Iter<'a>,
+    avar_mappings: Option<VarLenArray<'a, SegmentMaps<'a>>>,
+    coords: &'c [NormalizedCoord],
+}
+
+impl<'a, 'c> Iterator for Denormalize<'a, 'c> {
+    type Item = VariationSetting;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let axis = self.axes.next()?;
+        let index = axis.index();
+        let coord = self
+            .coords
+            .get(index)
+            .map(|coord| coord.to_f32())
+            .unwrap_or_default();
+        let coord = self
+            .avar_mappings
+            .as_ref()
+            .and_then(|mappings| mappings.get(index).transpose().ok())
+            .flatten()
+            .map(|mapping| unmap_segments(mapping.axis_value_maps(), coord))
+            .unwrap_or(coord);
+        Some((axis.tag(), axis.denormalize_f32(coord)).into())
+    }
+}
+
+/// Applies the inverse of the piecewise linear mapping defined by the
+/// segments of an `avar` table.
+///
+/// The forward mapping interpolates between the `to` coordinates of
+/// adjacent segments so this swaps the roles of the two coordinates. Values
+/// outside of the segments are left unchanged, as in the forward mapping.
+fn unmap_segments(maps: &[AxisValueMap], coord: f32) -> f32 {
+    let mut prev: Option<(f32, f32)> = None;
+    for map in maps {
+        let from = map.from_coordinate().to_f32();
+        let to = map.to_coordinate().to_f32();
+        if to == coord {
+            return from;
+        }
+        if to > coord {
+            return match prev {
+                Some((prev_from, prev_to)) if to > prev_to => {
+                    prev_from + (from - prev_from) * (coord - prev_to) / (to - prev_to)
+                }
+                _ => coord,
+            };
+        }
+        prev = Some((from, to));
+    }
+    coord
+}
+
 const MAX_INLINE_COORD_STORAGE: usize = 32;
 
 enum CoordStorage {
@@ -222,3 +347,36 @@ impl CoordStorage {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::MetadataProvider as _;
+    use read_fonts::{types::Tag, FontRef};
+
+    #[test]
+    fn denormalize_round_trip() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let axes = font.axes();
+        let wght = Tag::new(b"wght");
+        for value in [100.0, 250.0, 400.0, 650.0, 900.0] {
+            let coords: Vec<_> = axes.normalize([(wght, value)]).collect();
+            let settings: Vec<_> = axes.denormalize(&coords).collect();
+            assert_eq!(settings.len(), 1);
+            assert_eq!(settings[0].selector, wght);
+            assert!((settings[0].value - value).abs() < 0.5);
+        }
+        // Missing coordinates produce the default value.
+        let axis = axes.get(0).unwrap();
+        let settings: Vec<_> = axes.denormalize(&[]).collect();
+        assert_eq!(settings[0].value, axis.default_value());
+    }
+
+    #[test]
+    fn denormalize_axis() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let axis = font.axes().get(0).unwrap();
+        for value in [axis.min_value(), axis.default_value(), axis.max_value()] {
+            assert_eq!(axis.denormalize(axis.normalize(value)), value);
+        }
+    }
+}
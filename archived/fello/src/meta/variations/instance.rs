@@ -2,12 +2,44 @@
 
 */
 
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use read_fonts::{
     tables::fvar::{self, Fvar},
     TableProvider,
 };
 
-use crate::meta::info_strings::StringId;
+use crate::meta::info_strings::{InfoStrings, StringId};
+
+/// Maximum length of a PostScript name.
+const MAX_POST_SCRIPT_NAME_LEN: usize = 127;
+
+/// Resolved names for a named instance, suitable for populating menus.
+#[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceNames {
+    /// Typographic family name followed by the subfamily name of the
+    /// instance.
+    pub display_name: String,
+    /// PostScript name of the instance, either from the font or generated
+    /// from the family and subfamily names.
+    pub post_script_name: String,
+}
+
+/// Properties of a named instance that can be stored independently of the
+/// font.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InstanceInfo {
+    /// Localized string identifier for the subfamily name.
+    pub subfamily_name_id: u16,
+    /// String identifier for the PostScript name.
+    pub post_script_name_id: Option<u16>,
+    /// User space coordinates of the instance, one per axis.
+    pub coords: Vec<f32>,
+}
 
 /// Named instance of a variation.
 #[derive(Clone)]
@@ -26,6 +58,54 @@ impl<'a> Instance<'a> {
         self.record.post_script_name_id
     }
 
+    /// Returns the display and PostScript names of the instance.
+    ///
+    /// The display name joins the typographic family name, or the family
+    /// name if absent, with the subfamily name of the instance using the
+    /// strings that best match the BCP 47 language tags, which are given in
+    /// order of preference.
+    ///
+    /// The PostScript name comes from the font when the instance specifies
+    /// one. Otherwise it is generated following Adobe Technical Note #5902
+    /// from the variations PostScript name prefix, or the family name, and
+    /// the English subfamily name, keeping only ASCII letters and digits.
+    /// Generated names longer than 127 characters are truncated.
+    ///
+    /// Returns `None` if the font lacks a family or subfamily name.
+    pub fn names(&self, strings: &InfoStrings<'a>, languages: &[&str]) -> Option<InstanceNames> {
+        let string = |id, languages: &[&str]| -> Option<String> {
+            Some(strings.find(id, languages)?.string()?.to_string())
+        };
+        let family = |languages: &[&str]| {
+            string(StringId::TYPOGRAPHIC_FAMILY_NAME, languages)
+                .or_else(|| string(StringId::FAMILY_NAME, languages))
+        };
+        let subfamily_id = self.subfamily_name_id();
+        let mut display_name = family(languages)?;
+        let subfamily = string(subfamily_id, languages)?;
+        if !subfamily.is_empty() {
+            display_name.push(' ');
+            display_name.push_str(&subfamily);
+        }
+        let post_script_name = match self.post_script_name_id().and_then(|id| string(id, &[])) {
+            Some(name) => name,
+            None => {
+                let prefix = string(StringId::VARIATIONS_POSTSCRIPT_NAME_PREFIX, &[])
+                    .or_else(|| family(&[]))?;
+                let subfamily = string(subfamily_id, &[])?;
+                let mut name: String = prefix.chars().filter(char::is_ascii_alphanumeric).collect();
+                name.push('-');
+                name.extend(subfamily.chars().filter(char::is_ascii_alphanumeric));
+                name.truncate(MAX_POST_SCRIPT_NAME_LEN);
+                name
+            }
+        };
+        Some(InstanceNames {
+            display_name,
+            post_script_name,
+        })
+    }
+
     /// Returns an iterator over the sequence of user space coordinates that define
     /// the instance, one coordinate per axis.
     pub fn coords(&self) -> impl Iterator<Item = f32> + 'a + Clone {
@@ -34,6 +114,15 @@ impl<'a> Instance<'a> {
             .iter()
             .map(|coord| coord.get().to_f64() as _)
     }
+
+    /// Returns the properties of the instance as a plain value.
+    pub fn info(&self) -> InstanceInfo {
+        InstanceInfo {
+            subfamily_name_id: self.subfamily_name_id().to_u16(),
+            post_script_name_id: self.post_script_name_id().map(|id| id.to_u16()),
+            coords: self.coords().collect(),
+        }
+    }
 }
 
 /// Collection of named variation instances.
@@ -0,0 +1,129 @@
+//! Font revision and dates.
+//!
+//! The revision and dates come from the `head` table while the version
+//! string comes from the `name` table. Font managers can use these to
+//! choose between several copies of the same font.
+
+use core::iter::Peekable;
+use read_fonts::TableProvider;
+
+use super::info_strings::{InfoStrings, StringId};
+
+/// Point in time measured in seconds since 12:00 midnight, January 1, 1904
+/// UTC, as stored in fonts.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Number of seconds from the font epoch to the Unix epoch.
+    const UNIX_EPOCH: i64 = 2_082_844_800;
+
+    /// Creates a timestamp from the number of seconds since January 1, 1904.
+    pub fn from_secs_since_1904(secs: i64) -> Self {
+        Self(secs)
+    }
+
+    /// Returns the number of seconds since January 1, 1904.
+    pub fn secs_since_1904(self) -> i64 {
+        self.0
+    }
+
+    /// Returns the number of seconds since the Unix epoch, January 1, 1970.
+    ///
+    /// The result is negative for earlier dates.
+    pub fn unix_secs(self) -> i64 {
+        self.0.saturating_sub(Self::UNIX_EPOCH)
+    }
+}
+
+/// Revision, version and dates of a font.
+#[derive(Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VersionInfo {
+    /// Revision set by the font manufacturer, converted exactly from the
+    /// fixed point value in the font.
+    pub revision: f64,
+    /// Date the font was created.
+    pub created: Timestamp,
+    /// Date the font was last modified.
+    pub modified: Timestamp,
+    /// Major and minor version numbers parsed from the version string.
+    pub version: Option<(u16, u16)>,
+}
+
+impl VersionInfo {
+    /// Creates the version information for the given font.
+    ///
+    /// The revision and dates are zero if the font lacks a `head` table.
+    pub fn new<'a>(font: &impl TableProvider<'a>) -> Self {
+        let mut info = Self::default();
+        if let Ok(head) = font.head() {
+            info.revision = head.font_revision().to_f64();
+            info.created = Timestamp(head.created().as_secs());
+            info.modified = Timestamp(head.modified().as_secs());
+        }
+        info.version = InfoStrings::new(font)
+            .find(StringId::VERSION_STRING, &[])
+            .and_then(|string| parse_version(string.string()?.chars()));
+        info
+    }
+}
+
+/// Parses the major and minor version numbers from a version string such as
+/// "Version 1.002; ttfautohint (v1.8.3)".
+///
+/// The numbers are read from the first run of digits. A missing minor
+/// number is zero and the digits of the minor number are read as an integer,
+/// so "1.002" produces `(1, 2)`.
+pub fn parse_version(chars: impl Iterator<Item = char>) -> Option<(u16, u16)> {
+    let mut chars = chars.skip_while(|ch| !ch.is_ascii_digit()).peekable();
+    let major = parse_number(&mut chars)?;
+    let minor = match chars.next() {
+        Some('.') => parse_number(&mut chars).unwrap_or(0),
+        _ => 0,
+    };
+    Some((major, minor))
+}
+
+/// Reads a run of decimal digits, saturating on overflow.
+fn parse_number(chars: &mut Peekable<impl Iterator<Item = char>>) -> Option<u16> {
+    let mut value: Option<u16> = None;
+    while let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(10)) {
+        chars.next();
+        value = Some(
+            value
+                .unwrap_or(0)
+                .saturating_mul(10)
+                .saturating_add(digit as u16),
+        );
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_strings() {
+        let parse = |s: &str| parse_version(s.chars());
+        assert_eq!(parse("Version 1.002; ttfautohint (v1.8.3)"), Some((1, 2)));
+        assert_eq!(parse("Version 2.10"), Some((2, 10)));
+        assert_eq!(parse("3"), Some((3, 0)));
+        assert_eq!(parse("Version 4."), Some((4, 0)));
+        assert_eq!(parse("Version"), None);
+    }
+
+    #[test]
+    fn unix_epoch() {
+        assert_eq!(
+            Timestamp::from_secs_since_1904(2_082_844_800).unix_secs(),
+            0
+        );
+        assert_eq!(
+            Timestamp::from_secs_since_1904(0).unix_secs(),
+            -2_082_844_800
+        );
+    }
+}
@@ -1,6 +1,6 @@
 //! Scaler for CFF outlines.
 
-use std::ops::Range;
+use core::ops::Range;
 
 use read_fonts::{
     tables::{
@@ -17,6 +17,7 @@ use read_fonts::{
 };
 
 use super::hint::{HintParams, HintState};
+use crate::meta::metrics;
 
 /// Type for loading, scaling and hinting outlines in CFF/CFF2 tables.
 ///
@@ -56,6 +57,7 @@ use super::hint::{HintParams, HintState};
 /// # Ok(())
 /// # }
 /// ```
+#[derive(Clone)]
 pub struct Scaler<'a> {
     version: Version<'a>,
     top_dict: ScalerTopDict<'a>,
@@ -68,7 +70,7 @@ impl<'a> Scaler<'a> {
     /// This will choose an underyling CFF2 or CFF table from the font, in that
     /// order.
     pub fn new(font: &impl TableProvider<'a>) -> Result<Self, Error> {
-        let units_per_em = font.head()?.units_per_em();
+        let units_per_em = metrics::units_per_em(font);
         if let Ok(cff2) = font.cff2() {
             Self::from_cff2(cff2, units_per_em)
         } else {
@@ -105,6 +107,10 @@ impl<'a> Scaler<'a> {
         })
     }
 
+    pub fn units_per_em(&self) -> u16 {
+        self.units_per_em
+    }
+
     pub fn is_cff2(&self) -> bool {
         matches!(self.version, Version::Version2(_))
     }
@@ -155,6 +161,7 @@ impl<'a> Scaler<'a> {
         let mut hint_params = HintParams::default();
         let mut subrs_offset = None;
         let mut store_index = 0;
+        let mut std_vw = None;
         let blend_state = self
             .top_dict
             .var_store
@@ -175,6 +182,7 @@ impl<'a> Scaler<'a> {
                 // Subrs offset is relative to the private DICT
                 SubrsOffset(offset) => subrs_offset = Some(private_dict_range.start + offset),
                 VariationStoreIndex(index) => store_index = index,
+                StdVw(width) => std_vw = Some(width),
                 _ => {}
             }
         }
@@ -194,6 +202,7 @@ impl<'a> Scaler<'a> {
             subrs_offset,
             hint_state,
             store_index,
+            std_vw,
         })
     }
 
@@ -292,6 +301,7 @@ impl<'a> Scaler<'a> {
     }
 }
 
+#[derive(Clone)]
 enum Version<'a> {
     /// <https://learn.microsoft.com/en-us/typography/opentype/spec/cff>
     Version1(Cff<'a>),
@@ -315,6 +325,7 @@ pub struct ScalerSubfont {
     subrs_offset: Option<usize>,
     hint_state: HintState,
     store_index: u16,
+    std_vw: Option<Fixed>,
 }
 
 impl ScalerSubfont {
@@ -326,6 +337,12 @@ impl ScalerSubfont {
         self.size
     }
 
+    /// Returns the standard vertical stem width in font units, if
+    /// specified by the Private DICT.
+    pub fn std_vw(&self) -> Option<Fixed> {
+        self.std_vw
+    }
+
     /// Returns the local subroutine index.
     pub fn subrs<'a>(&self, scaler: &Scaler<'a>) -> Result<Option<Index<'a>>, Error> {
         if let Some(subrs_offset) = self.subrs_offset {
@@ -354,7 +371,7 @@ impl ScalerSubfont {
 
 /// Entries that we parse from the Top DICT that are required to support
 /// charstring evaluation.
-#[derive(Default)]
+#[derive(Clone, Default)]
 struct ScalerTopDict<'a> {
     charstrings: Option<Index<'a>>,
     font_dicts: Option<Index<'a>>,
@@ -0,0 +1,383 @@
+//! Detection of color representations for glyphs.
+
+use alloc::collections::BTreeMap;
+use read_fonts::{
+    tables::{
+        cblc::Cblc,
+        colr::{ClipBox, Colr},
+        sbix::Sbix,
+        variations::{DeltaSetIndex, DeltaSetIndexMap, ItemVariationStore},
+    },
+    types::{GlyphId, Tag},
+    FontData, TableProvider,
+};
+
+use super::NormalizedCoord;
+use crate::meta::metrics::{units_per_em, BoundingBox};
+
+/// Variation index that marks a value without variations.
+const NO_VARIATION_INDEX: u32 = 0xFFFFFFFF;
+
+/// Size of an entry in the document index of the `SVG ` table.
+const SVG_DOCUMENT_RECORD_LEN: usize = 12;
+
+/// Color glyph sources of a font.
+#[derive(Clone, Default)]
+pub(super) struct ColorGlyphs<'a> {
+    colr: Option<Colr<'a>>,
+    /// Location table for the `CBDT` table.
+    cblc: Option<Cblc<'a>>,
+    sbix: Option<Sbix<'a>>,
+    /// Document index of the `SVG ` table.
+    svg_documents: Option<FontData<'a>>,
+    units_per_em: u16,
+}
+
+impl<'a> ColorGlyphs<'a> {
+    pub fn new(font: &impl TableProvider<'a>) -> Self {
+        Self {
+            colr: font.colr().ok(),
+            cblc: font
+                .cblc()
+                .ok()
+                .filter(|_| font.data_for_tag(Tag::new(b"CBDT")).is_some()),
+            sbix: font.sbix().ok(),
+            svg_documents: svg_documents(font),
+            units_per_em: units_per_em(font),
+        }
+    }
+
+    /// Returns true if the glyph has a color representation in the `COLR`,
+    /// `CBDT`, `sbix` or `SVG ` tables.
+    pub fn contains(&self, glyph_id: GlyphId) -> bool {
+        self.colr
+            .as_ref()
+            .map_or(false, |colr| has_colr(colr, glyph_id))
+            || self
+                .cblc
+                .as_ref()
+                .map_or(false, |cblc| has_cbdt(cblc, glyph_id))
+            || self
+                .sbix
+                .as_ref()
+                .map_or(false, |sbix| has_sbix(sbix, glyph_id))
+            || self
+                .svg_documents
+                .map_or(false, |documents| has_svg(documents, glyph_id))
+    }
+
+    /// Returns the clip box of a `COLR` version 1 glyph at the given size
+    /// and location.
+    pub fn clip_box(
+        &self,
+        glyph_id: GlyphId,
+        size: f32,
+        coords: &[NormalizedCoord],
+    ) -> Option<BoundingBox> {
+        let colr = self.colr.as_ref()?;
+        let list = colr.clip_list()?.ok()?;
+        let gid = glyph_id.to_u16();
+        let clips = list.clips();
+        let index = clips
+            .binary_search_by(|clip| {
+                if gid < clip.start_glyph_id().to_u16() {
+                    core::cmp::Ordering::Greater
+                } else if gid > clip.end_glyph_id().to_u16() {
+                    core::cmp::Ordering::Less
+                } else {
+                    core::cmp::Ordering::Equal
+                }
+            })
+            .ok()?;
+        let clip_box = clips.get(index)?.clip_box(list.offset_data()).ok()?;
+        let (x_min, y_min, x_max, y_max, var_index_base) = match &clip_box {
+            ClipBox::Format1(clip_box) => (
+                clip_box.x_min(),
+                clip_box.y_min(),
+                clip_box.x_max(),
+                clip_box.y_max(),
+                NO_VARIATION_INDEX,
+            ),
+            ClipBox::Format2(clip_box) => (
+                clip_box.x_min(),
+                clip_box.y_min(),
+                clip_box.x_max(),
+                clip_box.y_max(),
+                clip_box.var_index_base(),
+            ),
+        };
+        let mut deltas = ColrDeltas::new(colr, coords);
+        let scale = if size == 0.0 {
+            1.0
+        } else {
+            size / self.units_per_em.max(1) as f32
+        };
+        let mut value = |value: i16, offset: u32| {
+            (value as i32 + deltas.delta(var_index_base, offset)) as f32 * scale
+        };
+        Some(BoundingBox {
+            x_min: value(x_min.to_i16(), 0),
+            y_min: value(y_min.to_i16(), 1),
+            x_max: value(x_max.to_i16(), 2),
+            y_max: value(y_max.to_i16(), 3),
+        })
+    }
+}
+
+/// Variation deltas of a `COLR` table at a fixed location.
+///
+/// Variable clip boxes and paints refer to a run of delta sets through a
+/// base variation index. The delta set indices of nearby values frequently
+/// coincide, such as for the offsets of gradient stops that move together,
+/// so deltas are memoized by delta set index to evaluate the region
+/// scalars of each delta set once while loading a glyph.
+pub(super) struct ColrDeltas<'a, 'c> {
+    index_map: Option<DeltaSetIndexMap<'a>>,
+    store: Option<ItemVariationStore<'a>>,
+    coords: &'c [NormalizedCoord],
+    cache: BTreeMap<(u16, u16), i32>,
+}
+
+impl<'a, 'c> ColrDeltas<'a, 'c> {
+    pub fn new(colr: &Colr<'a>, coords: &'c [NormalizedCoord]) -> Self {
+        // Avoid reading the variation data for default locations.
+        let is_default = coords
+            .iter()
+            .all(|coord| *coord == NormalizedCoord::default());
+        let store = colr
+            .item_variation_store()
+            .filter(|_| !is_default)
+            .and_then(|store| store.ok());
+        Self {
+            index_map: colr
+                .var_index_map()
+                .filter(|_| store.is_some())
+                .and_then(|map| map.ok()),
+            store,
+            coords,
+            cache: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the delta in font units for the value at the given offset
+    /// from a base variation index.
+    pub fn delta(&mut self, var_index_base: u32, offset: u32) -> i32 {
+        let Some(store) = &self.store else {
+            return 0;
+        };
+        if var_index_base == NO_VARIATION_INDEX {
+            return 0;
+        }
+        let var_index = var_index_base.wrapping_add(offset);
+        // Without a mapping, the variation index is split into the outer
+        // and inner indices.
+        let index = match &self.index_map {
+            Some(map) => match map.get(var_index) {
+                Ok(index) => index,
+                Err(_) => return 0,
+            },
+            None => DeltaSetIndex {
+                outer: (var_index >> 16) as u16,
+                inner: var_index as u16,
+            },
+        };
+        let coords = self.coords;
+        *self
+            .cache
+            .entry((index.outer, index.inner))
+            .or_insert_with(|| store.compute_delta(index, coords).unwrap_or_default())
+    }
+}
+
+fn has_colr(colr: &Colr, glyph_id: GlyphId) -> bool {
+    let gid = glyph_id.to_u16();
+    if let Some(Ok(records)) = colr.base_glyph_records() {
+        if records
+            .binary_search_by(|record| record.glyph_id().to_u16().cmp(&gid))
+            .is_ok()
+        {
+            return true;
+        }
+    }
+    if let Some(Ok(list)) = colr.base_glyph_list() {
+        if list
+            .base_glyph_paint_records()
+            .binary_search_by(|record| record.glyph_id().to_u16().cmp(&gid))
+            .is_ok()
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Returns true if the glyph has non-empty bitmap data in any strike.
+fn has_cbdt(cblc: &Cblc, glyph_id: GlyphId) -> bool {
+    cblc.bitmap_sizes().iter().any(|size| {
+        size.location(cblc.offset_data(), glyph_id)
+            .map_or(false, |location| !location.is_empty())
+    })
+}
+
+/// Returns true if the glyph has graphic data in any strike.
+fn has_sbix(sbix: &Sbix, glyph_id: GlyphId) -> bool {
+    sbix.strikes().iter().any(|strike| {
+        strike.map_or(false, |strike| {
+            matches!(strike.glyph_data(glyph_id), Ok(Some(_)))
+        })
+    })
+}
+
+/// Returns the document index of the `SVG ` table, starting with the
+/// number of entries.
+fn svg_documents<'a>(font: &impl TableProvider<'a>) -> Option<FontData<'a>> {
+    let data = font.data_for_tag(Tag::new(b"SVG "))?;
+    let offset = data.read_at::<u32>(2).ok()? as usize;
+    data.split_off(offset)
+}
+
+/// Returns true if the glyph is in the range of an SVG document.
+fn has_svg(documents: FontData, glyph_id: GlyphId) -> bool {
+    let gid = glyph_id.to_u16();
+    let count = documents.read_at::<u16>(0).unwrap_or_default() as usize;
+    // Records are sorted by glyph range.
+    let (mut lo, mut hi) = (0, count);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let offset = 2 + mid * SVG_DOCUMENT_RECORD_LEN;
+        let (Ok(start), Ok(end)) = (
+            documents.read_at::<u16>(offset),
+            documents.read_at::<u16>(offset + 2),
+        ) else {
+            return false;
+        };
+        if gid < start {
+            hi = mid;
+        } else if gid > end {
+            lo = mid + 1;
+        } else {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{testing::FontBuilder, write::Writer};
+    use read_fonts::FontRef;
+
+    /// Builds a font with a `COLR` table containing only a clip list with
+    /// a variable clip box for glyphs 1 to 3.
+    fn build_test_font() -> Vec<u8> {
+        let mut colr = Writer::default();
+        // Version 1 header with a clip list at 34
+        colr.u16(1);
+        colr.u16(0);
+        colr.u32(0);
+        colr.u32(0);
+        colr.u16(0);
+        colr.u32(0);
+        colr.u32(0);
+        colr.u32(34);
+        colr.u32(0);
+        colr.u32(0);
+        // Clip list format 1 with one clip
+        colr.0.push(1);
+        colr.u32(1);
+        colr.u16(1);
+        colr.u16(3);
+        // 24-bit offset to the clip box at 12
+        colr.0.extend_from_slice(&[0, 0, 12]);
+        // Clip box format 2
+        colr.0.push(2);
+        colr.i16(-100);
+        colr.i16(-200);
+        colr.i16(900);
+        colr.i16(800);
+        colr.u32(0);
+        let mut builder = FontBuilder::new(1000);
+        builder.add_table(Tag::new(b"COLR"), colr.0);
+        builder.build()
+    }
+
+    /// Builds a font with four glyphs, where glyph 1 has an `sbix` bitmap
+    /// and glyphs 2 and 3 share an SVG document.
+    fn build_bitmap_and_svg_font() -> Vec<u8> {
+        let mut sbix = Writer::default();
+        // Version 1 header with one strike at 12
+        sbix.u16(1);
+        sbix.u16(1);
+        sbix.u32(1);
+        sbix.u32(12);
+        // Strike with glyph data offsets for 4 glyphs
+        sbix.u16(20);
+        sbix.u16(72);
+        let data_offset = 4 + 5 * 4;
+        for end in [0, 0, 9, 9, 9] {
+            sbix.u32(data_offset + end);
+        }
+        // PNG glyph data with a placeholder image
+        sbix.i16(0);
+        sbix.i16(0);
+        sbix.0.extend_from_slice(b"png ");
+        sbix.0.push(0);
+        let mut svg = Writer::default();
+        // Version 0 header with a document index at 10
+        svg.u16(0);
+        svg.u32(10);
+        svg.u32(0);
+        svg.u16(1);
+        svg.u16(2);
+        svg.u16(3);
+        svg.u32(14);
+        svg.u32(6);
+        svg.0.extend_from_slice(b"<svg/>");
+        let mut builder = FontBuilder::new(1000);
+        for _ in 0..3 {
+            builder.add_glyph(500, &[]);
+        }
+        builder.add_table(Tag::new(b"sbix"), sbix.0);
+        builder.add_table(Tag::new(b"SVG "), svg.0);
+        builder.build()
+    }
+
+    #[test]
+    fn bitmap_and_svg_glyphs() {
+        let data = build_bitmap_and_svg_font();
+        let font = FontRef::new(&data).unwrap();
+        let color = ColorGlyphs::new(&font);
+        let expected = [false, true, true, true];
+        for (gid, expected) in expected.into_iter().enumerate() {
+            assert_eq!(color.contains(GlyphId::new(gid as u16)), expected);
+        }
+        assert!(!color.contains(GlyphId::new(4)));
+    }
+
+    #[test]
+    fn clip_boxes() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let color = ColorGlyphs::new(&font);
+        let expected = BoundingBox {
+            x_min: -1.0,
+            y_min: -2.0,
+            x_max: 9.0,
+            y_max: 8.0,
+        };
+        for gid in 1..=3 {
+            assert_eq!(color.clip_box(GlyphId::new(gid), 10.0, &[]), Some(expected));
+        }
+        assert_eq!(color.clip_box(GlyphId::new(4), 10.0, &[]), None);
+        // There is no variation store, so the deltas are zero at any
+        // location.
+        let coords = [NormalizedCoord::from_f32(0.5)];
+        assert_eq!(
+            color
+                .clip_box(GlyphId::new(2), 0.0, &coords)
+                .map(|b| b.x_max),
+            Some(900.0)
+        );
+    }
+}
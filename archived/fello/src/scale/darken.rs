@@ -0,0 +1,321 @@
+//! Stem darkening for small text.
+
+use super::Pen;
+use alloc::vec::Vec;
+
+/// Stem width in ems assumed for fonts that don't declare a standard
+/// vertical stem width.
+pub(super) const DEFAULT_STEM_WIDTH: f32 = 0.075;
+
+/// Parameters for darkening the stems of small text.
+///
+/// Thin stems look too light when rendered with linear alpha blending.
+/// Darkening emboldens outlines horizontally by an amount that depends on
+/// the stem width in pixels, so it fades out as the size increases.
+///
+/// The amount follows a piecewise linear curve through four control
+/// points. The default curve is the one used by the Adobe CFF rasterizer.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StemDarkening {
+    /// Control points of the darkening curve, sorted by stem width. The
+    /// first value of each point is a stem width and the second is the
+    /// total darkening amount for that width, both in thousandths of a
+    /// pixel.
+    pub curve: [(f32, f32); 4],
+}
+
+impl Default for StemDarkening {
+    fn default() -> Self {
+        Self {
+            curve: [
+                (500.0, 400.0),
+                (1000.0, 275.0),
+                (1667.0, 275.0),
+                (2333.0, 0.0),
+            ],
+        }
+    }
+}
+
+impl StemDarkening {
+    /// Returns the total darkening amount in pixels for a stem of the given
+    /// width in pixels.
+    pub fn amount(&self, stem_width: f32) -> f32 {
+        let x = stem_width * 1000.0;
+        let [first, .., last] = self.curve;
+        let y = if x < first.0 {
+            first.1
+        } else if x >= last.0 {
+            last.1
+        } else {
+            self.curve
+                .windows(2)
+                .find(|points| x < points[1].0)
+                .map(|points| {
+                    let ((x0, y0), (x1, y1)) = (points[0], points[1]);
+                    if x1 > x0 {
+                        y0 + (x - x0) * (y1 - y0) / (x1 - x0)
+                    } else {
+                        y1
+                    }
+                })
+                .unwrap_or(last.1)
+        };
+        y / 1000.0
+    }
+}
+
+/// Returns the amount in pixels by which each side of a stem moves for
+/// the given size and stem width in ems.
+pub(super) fn side_amount(darkening: &StemDarkening, size: f32, stem_width: f32) -> f32 {
+    if size <= 0.0 {
+        // Unscaled outlines are never darkened.
+        return 0.0;
+    }
+    darkening.amount(stem_width * size) / 2.0
+}
+
+#[derive(Copy, Clone)]
+enum Element {
+    MoveTo,
+    LineTo,
+    QuadTo,
+    CurveTo,
+    Close,
+}
+
+/// Pen that collects an outline and emboldens it horizontally before
+/// passing it to the inner pen in [`finish`](Self::finish).
+pub(super) struct DarkeningPen<'a, P> {
+    inner: &'a mut P,
+    amount: f32,
+    elements: Vec<Element>,
+    points: Vec<(f32, f32)>,
+    /// Index of the first point of each contour.
+    contours: Vec<usize>,
+}
+
+impl<'a, P: Pen> DarkeningPen<'a, P> {
+    pub fn new(inner: &'a mut P, amount: f32) -> Self {
+        Self {
+            inner,
+            amount,
+            elements: Vec::new(),
+            points: Vec::new(),
+            contours: Vec::new(),
+        }
+    }
+
+    /// Emboldens the collected outline and emits it to the inner pen.
+    pub fn finish(mut self) {
+        self.embolden();
+        let mut points = self.points.iter();
+        let mut next = || points.next().copied().unwrap_or_default();
+        for element in &self.elements {
+            match element {
+                Element::MoveTo => {
+                    let (x, y) = next();
+                    self.inner.move_to(x, y);
+                }
+                Element::LineTo => {
+                    let (x, y) = next();
+                    self.inner.line_to(x, y);
+                }
+                Element::QuadTo => {
+                    let ((cx0, cy0), (x, y)) = (next(), next());
+                    self.inner.quad_to(cx0, cy0, x, y);
+                }
+                Element::CurveTo => {
+                    let ((cx0, cy0), (cx1, cy1), (x, y)) = (next(), next(), next());
+                    self.inner.curve_to(cx0, cy0, cx1, cy1, x, y);
+                }
+                Element::Close => self.inner.close(),
+            }
+        }
+    }
+
+    /// Moves each point along the bisector of its adjacent edges, treating
+    /// control points as polygon vertices, and shifts the result so that
+    /// left edges stay in place.
+    fn embolden(&mut self) {
+        let amount = self.amount;
+        if amount == 0.0 || self.contours.first() != Some(&0) {
+            return;
+        }
+        // Outer contours run counter-clockwise in PostScript outlines and
+        // clockwise in TrueType outlines.
+        let direction = match self.signed_area() {
+            area if area > 0.0 => 1.0,
+            area if area < 0.0 => -1.0,
+            _ => return,
+        };
+        let mut shifted = Vec::with_capacity(self.points.len());
+        for (i, start) in self.contours.iter().enumerate() {
+            let end = self
+                .contours
+                .get(i + 1)
+                .copied()
+                .unwrap_or(self.points.len());
+            let contour = &self.points[*start..end];
+            for (j, point) in contour.iter().enumerate() {
+                let prev = contour
+                    .iter()
+                    .rev()
+                    .cycle()
+                    .skip(contour.len() - j)
+                    .take(contour.len())
+                    .find(|prev| *prev != point);
+                let next = contour
+                    .iter()
+                    .cycle()
+                    .skip(j + 1)
+                    .take(contour.len())
+                    .find(|next| *next != point);
+                let shift = match (prev, next) {
+                    (Some(prev), Some(next)) => {
+                        let incoming = unit(point.0 - prev.0, point.1 - prev.1);
+                        let outgoing = unit(next.0 - point.0, next.1 - point.1);
+                        let d = incoming.0 * outgoing.0 + incoming.1 * outgoing.1;
+                        // Skip points where the outline nearly reverses to
+                        // avoid spikes.
+                        if d > -0.9375 {
+                            direction * (incoming.1 + outgoing.1) * amount / (d + 1.0)
+                        } else {
+                            0.0
+                        }
+                    }
+                    _ => 0.0,
+                };
+                shifted.push((point.0 + amount + shift, point.1));
+            }
+        }
+        self.points = shifted;
+    }
+
+    fn signed_area(&self) -> f32 {
+        let mut area = 0.0;
+        for (i, start) in self.contours.iter().enumerate() {
+            let end = self
+                .contours
+                .get(i + 1)
+                .copied()
+                .unwrap_or(self.points.len());
+            let contour = &self.points[*start..end];
+            for (j, p0) in contour.iter().enumerate() {
+                let p1 = contour[(j + 1) % contour.len()];
+                area += p0.0 * p1.1 - p1.0 * p0.1;
+            }
+        }
+        area
+    }
+}
+
+impl<P: Pen> Pen for DarkeningPen<'_, P> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.contours.push(self.points.len());
+        self.elements.push(Element::MoveTo);
+        self.points.push((x, y));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.elements.push(Element::LineTo);
+        self.points.push((x, y));
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        self.elements.push(Element::QuadTo);
+        self.points.extend([(cx0, cy0), (x, y)]);
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        self.elements.push(Element::CurveTo);
+        self.points.extend([(cx0, cy0), (cx1, cy1), (x, y)]);
+    }
+
+    fn close(&mut self) {
+        self.elements.push(Element::Close);
+    }
+}
+
+/// Returns the vector scaled to unit length.
+fn unit(x: f32, y: f32) -> (f32, f32) {
+    let len = sqrt(x * x + y * y);
+    if len == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (x / len, y / len)
+    }
+}
+
+/// Square root for `no_std` builds where `f32::sqrt` is not available in
+/// core.
+fn sqrt(x: f32) -> f32 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    // Halve the exponent for an initial estimate and refine with Newton's
+    // method.
+    let mut y = f32::from_bits((x.to_bits() >> 1) + 0x1FBD_1DF5);
+    for _ in 0..3 {
+        y = 0.5 * (y + x / y);
+    }
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn darkening_amount() {
+        let darkening = StemDarkening::default();
+        assert_eq!(darkening.amount(0.25), 0.4);
+        assert_eq!(darkening.amount(1.2), 0.275);
+        assert_eq!(darkening.amount(3.0), 0.0);
+        assert!((darkening.amount(0.75) - 0.3375).abs() < 1e-6);
+    }
+
+    #[test]
+    fn embolden_square() {
+        #[derive(Default)]
+        struct Points(Vec<(f32, f32)>);
+
+        impl Pen for Points {
+            fn move_to(&mut self, x: f32, y: f32) {
+                self.0.push((x, y));
+            }
+
+            fn line_to(&mut self, x: f32, y: f32) {
+                self.0.push((x, y));
+            }
+
+            fn quad_to(&mut self, _cx0: f32, _cy0: f32, x: f32, y: f32) {
+                self.0.push((x, y));
+            }
+
+            fn curve_to(&mut self, _cx0: f32, _cy0: f32, _cx1: f32, _cy1: f32, x: f32, y: f32) {
+                self.0.push((x, y));
+            }
+
+            fn close(&mut self) {}
+        }
+
+        // Counter-clockwise square, as in a PostScript outline.
+        let mut points = Points::default();
+        let mut pen = DarkeningPen::new(&mut points, 0.5);
+        pen.move_to(0.0, 0.0);
+        pen.line_to(10.0, 0.0);
+        pen.line_to(10.0, 10.0);
+        pen.line_to(0.0, 10.0);
+        pen.close();
+        pen.finish();
+        // The left edge stays in place and the right edge moves by twice
+        // the amount.
+        let expected = [(0.0, 0.0), (11.0, 0.0), (11.0, 10.0), (0.0, 10.0)];
+        assert_eq!(points.0.len(), expected.len());
+        for (point, expected) in points.0.iter().zip(expected) {
+            assert!((point.0 - expected.0).abs() < 1e-3);
+            assert!((point.1 - expected.1).abs() < 1e-3);
+        }
+    }
+}
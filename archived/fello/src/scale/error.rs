@@ -3,10 +3,11 @@ use read_fonts::{
     ReadError,
 };
 
-use std::fmt;
+use core::fmt;
 
 /// Errors that may occur when scaling glyphs.
 #[derive(Clone, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// No viable sources were available.
     NoSources,
@@ -68,7 +69,28 @@ impl fmt::Display for Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+/// Problems that may occur when scaling glyphs which still produce an
+/// outline, though possibly with some parts omitted.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Warning {
+    /// A component of a composite glyph referred to a glyph that was
+    /// already being loaded and was skipped to break the cycle.
+    CyclicComponent(GlyphId, GlyphId),
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::CyclicComponent(gid, component) => write!(
+                f,
+                "Skipped cyclic component {component} when loading composite glyph {gid}",
+            ),
+        }
+    }
+}
+
 /// Result type for errors that may occur when loading glyphs.
 pub type Result<T> = core::result::Result<T, Error>;
@@ -1,5 +1,9 @@
 use super::{bytecode::Definition, state::InstanceState, ScalerFont, Slot};
-use crate::{scale::Hinting, FontKey, NormalizedCoord};
+use crate::{
+    scale::{vec_memory_usage, Hinting},
+    FontKey, NormalizedCoord,
+};
+use alloc::{vec, vec::Vec};
 
 pub struct CacheEntry<'a, T> {
     pub is_current: bool,
@@ -36,6 +40,20 @@ impl Default for Cache {
 }
 
 impl Cache {
+    /// Returns the number of bytes allocated for cached font and size
+    /// entries.
+    pub fn memory_usage(&self) -> usize {
+        let font_usage = |entry: &FontEntry| vec_memory_usage(&entry.definitions);
+        let size_usage =
+            |entry: &SizeEntry| vec_memory_usage(&entry.coords) + vec_memory_usage(&entry.store);
+        vec_memory_usage(&self.fonts)
+            + vec_memory_usage(&self.sizes)
+            + self.fonts.iter().map(font_usage).sum::<usize>()
+            + self.sizes.iter().map(size_usage).sum::<usize>()
+            + font_usage(&self.uncached_font)
+            + size_usage(&self.uncached_size)
+    }
+
     pub fn find_or_create_entries(
         &mut self,
         font: &ScalerFont,
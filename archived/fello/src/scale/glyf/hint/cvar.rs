@@ -0,0 +1,290 @@
+//! Variations of the control value table.
+//!
+//! See <https://learn.microsoft.com/en-us/typography/opentype/spec/cvar>
+
+use alloc::{vec, vec::Vec};
+use read_fonts::{
+    types::{F2Dot14, Fixed},
+    FontData, ReadError,
+};
+
+/// Flag in the tuple variation count indicating that the serialized data
+/// begins with point numbers shared by all tuples.
+const SHARED_POINT_NUMBERS: u16 = 0x8000;
+/// Mask for the number of tuples in the tuple variation count.
+const COUNT_MASK: u16 = 0x0FFF;
+/// Tuple index flags.
+const EMBEDDED_PEAK_TUPLE: u16 = 0x8000;
+const INTERMEDIATE_REGION: u16 = 0x4000;
+const PRIVATE_POINT_NUMBERS: u16 = 0x2000;
+/// Packed point number flags.
+const POINTS_ARE_WORDS: u8 = 0x80;
+const POINT_RUN_COUNT_MASK: u8 = 0x7F;
+/// Packed delta flags.
+const DELTAS_ARE_ZERO: u8 = 0x80;
+const DELTAS_ARE_WORDS: u8 = 0x40;
+const DELTA_RUN_COUNT_MASK: u8 = 0x3F;
+
+/// Adds the deltas from the `cvar` table for the given normalized
+/// coordinates to the control values, which are in font units.
+///
+/// As in FreeType, the deltas for each value are accumulated in 16.16
+/// format and rounded once at the end.
+pub fn apply_deltas(
+    data: FontData,
+    axis_count: u16,
+    coords: &[F2Dot14],
+    cvt: &mut [i32],
+) -> Result<(), ReadError> {
+    let axis_count = axis_count as usize;
+    let tuple_count = data.read_at::<u16>(4)?;
+    let mut serialized = Reader::new(data, data.read_at::<u16>(6)? as usize);
+    let shared_points = if tuple_count & SHARED_POINT_NUMBERS != 0 {
+        serialized.read_points()?
+    } else {
+        None
+    };
+    let mut deltas = vec![Fixed::ZERO; cvt.len()];
+    let mut values = Vec::new();
+    let mut header = Reader::new(data, 8);
+    for _ in 0..tuple_count & COUNT_MASK {
+        let data_size = header.read_u16()? as usize;
+        let tuple_index = header.read_u16()?;
+        // There are no shared tuples in cvar so the peak must be embedded.
+        if tuple_index & EMBEDDED_PEAK_TUPLE == 0 {
+            return Err(ReadError::MalformedData("cvar tuple without a peak"));
+        }
+        let peak = header.offset;
+        header.offset += axis_count * 2;
+        let intermediate = if tuple_index & INTERMEDIATE_REGION != 0 {
+            let start = header.offset;
+            header.offset += axis_count * 4;
+            Some(start)
+        } else {
+            None
+        };
+        let mut tuple_data = Reader::new(data, serialized.offset);
+        serialized.offset += data_size;
+        let scalar = compute_scalar(data, axis_count, peak, intermediate, coords)?;
+        if scalar == Fixed::ZERO {
+            continue;
+        }
+        let private_points;
+        let points = if tuple_index & PRIVATE_POINT_NUMBERS != 0 {
+            private_points = tuple_data.read_points()?;
+            private_points.as_deref()
+        } else {
+            shared_points.as_deref()
+        };
+        let count = points.map_or(cvt.len(), |points| points.len());
+        tuple_data.read_deltas(count, &mut values)?;
+        let mut apply = |index: usize, delta: i32| {
+            if let Some(value) = deltas.get_mut(index) {
+                *value += Fixed::from_i32(delta) * scalar;
+            }
+        };
+        match points {
+            Some(points) => {
+                for (&index, &delta) in points.iter().zip(&values) {
+                    apply(index as usize, delta);
+                }
+            }
+            None => {
+                for (index, &delta) in values.iter().enumerate() {
+                    apply(index, delta);
+                }
+            }
+        }
+    }
+    for (value, delta) in cvt.iter_mut().zip(&deltas) {
+        *value += delta.to_i32();
+    }
+    Ok(())
+}
+
+/// Computes the scalar for a tuple at the given coordinates.
+///
+/// `peak` and `intermediate` are the offsets of the peak tuple and of the
+/// intermediate start and end tuples.
+fn compute_scalar(
+    data: FontData,
+    axis_count: usize,
+    peak: usize,
+    intermediate: Option<usize>,
+    coords: &[F2Dot14],
+) -> Result<Fixed, ReadError> {
+    let to_fixed = |value: F2Dot14| Fixed::from_bits(value.to_bits() as i32 * 4);
+    let mut scalar = Fixed::ONE;
+    for i in 0..axis_count {
+        let peak = to_fixed(data.read_at::<F2Dot14>(peak + i * 2)?);
+        let coord = coords.get(i).copied().map(to_fixed).unwrap_or_default();
+        if peak == Fixed::ZERO || coord == peak {
+            continue;
+        }
+        if coord == Fixed::ZERO {
+            return Ok(Fixed::ZERO);
+        }
+        if let Some(intermediate) = intermediate {
+            let start = to_fixed(data.read_at::<F2Dot14>(intermediate + i * 2)?);
+            let end = to_fixed(data.read_at::<F2Dot14>(intermediate + (axis_count + i) * 2)?);
+            if coord < start || coord > end {
+                return Ok(Fixed::ZERO);
+            }
+            scalar = if coord < peak {
+                scalar.mul_div(coord - start, peak - start)
+            } else {
+                scalar.mul_div(end - coord, end - peak)
+            };
+        } else {
+            if coord < peak.min(Fixed::ZERO) || coord > peak.max(Fixed::ZERO) {
+                return Ok(Fixed::ZERO);
+            }
+            scalar = scalar.mul_div(coord, peak);
+        }
+    }
+    Ok(scalar)
+}
+
+/// Sequential reader for the serialized variation data.
+struct Reader<'a> {
+    data: FontData<'a>,
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: FontData<'a>, offset: usize) -> Self {
+        Self { data, offset }
+    }
+
+    fn read_u8(&mut self) -> Result<u8, ReadError> {
+        let value = self.data.read_at::<u8>(self.offset)?;
+        self.offset += 1;
+        Ok(value)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, ReadError> {
+        let value = self.data.read_at::<u16>(self.offset)?;
+        self.offset += 2;
+        Ok(value)
+    }
+
+    fn read_i32(&mut self) -> Result<i32, ReadError> {
+        let value = self.data.read_at::<i32>(self.offset)?;
+        self.offset += 4;
+        Ok(value)
+    }
+
+    /// Reads packed point numbers, returning `None` if the deltas apply to
+    /// all control values.
+    fn read_points(&mut self) -> Result<Option<Vec<u16>>, ReadError> {
+        let first = self.read_u8()? as usize;
+        let count = if first & 0x80 != 0 {
+            ((first & 0x7F) << 8) | self.read_u8()? as usize
+        } else {
+            first
+        };
+        if count == 0 {
+            return Ok(None);
+        }
+        let mut points = Vec::with_capacity(count);
+        let mut point = 0u16;
+        while points.len() < count {
+            let control = self.read_u8()?;
+            let run_count = (control & POINT_RUN_COUNT_MASK) as usize + 1;
+            for _ in 0..run_count.min(count - points.len()) {
+                let delta = if control & POINTS_ARE_WORDS != 0 {
+                    self.read_u16()?
+                } else {
+                    self.read_u8()? as u16
+                };
+                point = point.wrapping_add(delta);
+                points.push(point);
+            }
+        }
+        Ok(Some(points))
+    }
+
+    /// Reads `count` packed deltas.
+    fn read_deltas(&mut self, count: usize, deltas: &mut Vec<i32>) -> Result<(), ReadError> {
+        deltas.clear();
+        while deltas.len() < count {
+            let control = self.read_u8()?;
+            let run_count = (control & DELTA_RUN_COUNT_MASK) as usize + 1;
+            for _ in 0..run_count.min(count - deltas.len()) {
+                let delta = match control & (DELTAS_ARE_ZERO | DELTAS_ARE_WORDS) {
+                    DELTAS_ARE_ZERO => 0,
+                    DELTAS_ARE_WORDS => self.read_u16()? as i16 as i32,
+                    // Both flags indicate 32-bit deltas.
+                    0xC0 => self.read_i32()?,
+                    _ => self.read_u8()? as i8 as i32,
+                };
+                deltas.push(delta);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::write::Writer;
+
+    /// Builds a cvar table for a single axis with a tuple at the maximum
+    /// that adjusts control values 1 and 3 and a tuple in the range 0..0.5
+    /// that adjusts all control values.
+    fn build_cvar() -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u16(1);
+        w.u16(0);
+        // tupleVariationCount and dataOffset
+        w.u16(2);
+        w.u16(8 + 6 + 10);
+        // Tuple peaking at 1.0 with private points.
+        w.u16(9);
+        w.u16(EMBEDDED_PEAK_TUPLE | PRIVATE_POINT_NUMBERS);
+        w.u16(F2Dot14::from_f32(1.0).to_bits() as u16);
+        // Tuple peaking at 0.25 with an intermediate region of 0..0.5.
+        w.u16(3);
+        w.u16(EMBEDDED_PEAK_TUPLE | INTERMEDIATE_REGION);
+        w.u16(F2Dot14::from_f32(0.25).to_bits() as u16);
+        w.u16(F2Dot14::from_f32(0.0).to_bits() as u16);
+        w.u16(F2Dot14::from_f32(0.5).to_bits() as u16);
+        // Points 1 and 3, then deltas of 10 and -300.
+        w.0.extend_from_slice(&[2, 1, 1, 2]);
+        w.0.push(0);
+        w.0.push(10);
+        w.0.push(DELTAS_ARE_WORDS);
+        w.i16(-300);
+        // Deltas of 0, 0, 0 and 8.
+        w.0.extend_from_slice(&[DELTAS_ARE_ZERO | 2, 0, 8]);
+        w.0
+    }
+
+    fn apply_at(coord: f32) -> [i32; 4] {
+        let data = build_cvar();
+        let mut cvt = [100, 200, 300, 400];
+        apply_deltas(
+            FontData::new(&data),
+            1,
+            &[F2Dot14::from_f32(coord)],
+            &mut cvt,
+        )
+        .unwrap();
+        cvt
+    }
+
+    #[test]
+    fn default_location() {
+        assert_eq!(apply_at(0.0), [100, 200, 300, 400]);
+    }
+
+    #[test]
+    fn peak_and_intermediate() {
+        assert_eq!(apply_at(1.0), [100, 210, 300, 100]);
+        assert_eq!(apply_at(0.5), [100, 205, 300, 250]);
+        assert_eq!(apply_at(0.25), [100, 203, 300, 333]);
+        // Negative coordinates are outside both tuples.
+        assert_eq!(apply_at(-0.5), [100, 200, 300, 400]);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{scale::Hinting, NormalizedCoord};
+use crate::{
+    scale::{Hinting, HintingLimits},
+    NormalizedCoord,
+};
 use read_fonts::tables::glyf::{PointFlags, PointMarker};
 
 use super::bytecode::{opcodes as op, DecodeError, Decoder, Definition, Program};
@@ -217,6 +220,8 @@ pub struct Interpreter<'a> {
     v35: bool,
     subpixel: bool,
     compat: bool,
+    limits: HintingLimits,
+    budget_exhausted: bool,
 }
 
 impl<'a> Interpreter<'a> {
@@ -231,6 +236,7 @@ impl<'a> Interpreter<'a> {
         glyph: Zone<'a>,
         coords: &'a [NormalizedCoord],
         axis_count: u16,
+        limits: HintingLimits,
     ) -> Self {
         Self {
             store: storage,
@@ -256,9 +262,17 @@ impl<'a> Interpreter<'a> {
             v35: false,
             subpixel: true,
             compat: false,
+            limits,
+            budget_exhausted: false,
         }
     }
 
+    /// Returns true if the last program was aborted because it exceeded
+    /// one of the execution limits.
+    pub fn is_budget_exhausted(&self) -> bool {
+        self.budget_exhausted
+    }
+
     pub fn run_fpgm<'b>(
         &mut self,
         state: &'b mut InstanceState,
@@ -408,14 +422,14 @@ impl<'a> Interpreter<'a> {
         let z = self.zone_mut(zone);
         let p = z.original_mut(point)?;
         match state {
-            CoordMode::X => p.x += distance,
-            CoordMode::Y => p.y += distance,
+            CoordMode::X => p.x = p.x.wrapping_add(distance),
+            CoordMode::Y => p.y = p.y.wrapping_add(distance),
             CoordMode::Both => {
                 if x != 0 {
-                    p.x += muldiv(distance, x as i32, fdotp);
+                    p.x = p.x.wrapping_add(muldiv(distance, x as i32, fdotp));
                 }
                 if y != 0 {
-                    p.y += muldiv(distance, y as i32, fdotp);
+                    p.y = p.y.wrapping_add(muldiv(distance, y as i32, fdotp));
                 }
             }
         }
@@ -436,26 +450,27 @@ impl<'a> Interpreter<'a> {
         match state {
             CoordMode::X => {
                 if legacy || !bc {
-                    p.x += distance;
+                    p.x = p.x.wrapping_add(distance);
                 }
                 z.flags_mut(point_ix)?.set_marker(PointMarker::TOUCHED_X);
             }
             CoordMode::Y => {
                 if !(!legacy && bc && iupx && iupy) {
-                    p.y += distance;
+                    p.y = p.y.wrapping_add(distance);
                 }
                 z.flags_mut(point_ix)?.set_marker(PointMarker::TOUCHED_Y);
             }
             CoordMode::Both => {
                 if x != 0 {
                     if legacy || !bc {
-                        p.x += muldiv(distance, x as i32, fdotp);
+                        p.x = p.x.wrapping_add(muldiv(distance, x as i32, fdotp));
                     }
                     z.flags_mut(point_ix)?.set_marker(PointMarker::TOUCHED_X);
                 }
                 if y != 0 {
                     if !(!legacy && bc && iupx && iupy) {
-                        z.point_mut(point_ix)?.y += muldiv(distance, y as i32, fdotp);
+                        let p = z.point_mut(point_ix)?;
+                        p.y = p.y.wrapping_add(muldiv(distance, y as i32, fdotp));
                     }
                     z.flags_mut(point_ix)?.set_marker(PointMarker::TOUCHED_Y);
                 }
@@ -512,7 +527,8 @@ impl<'a> Interpreter<'a> {
         let zone = self.zp2_mut();
         if fv.x != 0 {
             if v35 || !compat {
-                zone.point_mut(point_ix)?.x += dx;
+                let p = zone.point_mut(point_ix)?;
+                p.x = p.x.wrapping_add(dx);
             }
             if touch {
                 zone.flags_mut(point_ix)?.set_marker(PointMarker::TOUCHED_X);
@@ -520,7 +536,8 @@ impl<'a> Interpreter<'a> {
         }
         if fv.y != 0 {
             if !(!v35 && compat && iupx && iupy) {
-                zone.point_mut(point_ix)?.y += dy;
+                let p = zone.point_mut(point_ix)?;
+                p.y = p.y.wrapping_add(dy);
             }
             if touch {
                 zone.flags_mut(point_ix)?.set_marker(PointMarker::TOUCHED_Y);
@@ -661,13 +678,21 @@ impl<'a> Interpreter<'a> {
             definition: Default::default(),
         }; 32];
         let mut callstack_top = 0;
-        let callstack_len = callstack.len();
+        let callstack_len = callstack.len().min(self.limits.max_call_depth as usize);
+        let mut loop_iterations = 0u32;
+        self.budget_exhausted = false;
         let stack_size = stack.values.len();
         let mut rp0 = 0usize;
         let mut rp1 = 0usize;
         let mut rp2 = 0usize;
         let mut loop_counter = 1u32;
         loop {
+            if count >= self.limits.max_instructions
+                || loop_iterations > self.limits.max_loop_iterations
+            {
+                self.budget_exhausted = true;
+                return None;
+            }
             let Some(decoded) = decoder.next() else {
                 if callstack_top > 0 {
                     return None;
@@ -927,9 +952,13 @@ impl<'a> Interpreter<'a> {
                         (stack.get(a0)? as usize, 1)
                     };
                     if callstack_top >= callstack_len {
+                        self.budget_exhausted = true;
                         return None;
                     }
                     if call_count > 0 {
+                        if opcode == op::LOOPCALL {
+                            loop_iterations = loop_iterations.saturating_add(call_count as u32);
+                        }
                         let def = self.fdefs.get(def_index)?;
                         if !def.is_active {
                             return None;
@@ -1370,8 +1399,14 @@ impl<'a> Interpreter<'a> {
                 op::NOT => *stack.get_mut(a0)? = (stack.get(a0)? == 0) as i32,
                 op::SDB => state.graphics.delta_base = stack.get(a0)? as u16,
                 op::SDS => state.graphics.delta_shift = (stack.get(a0)?).min(6) as u16,
-                op::ADD => *stack.get_mut(a0)? += stack.get(a1)?,
-                op::SUB => *stack.get_mut(a0)? -= stack.get(a1)?,
+                op::ADD => {
+                    let sp = stack.get_mut(a0)?;
+                    *sp = sp.wrapping_add(stack.get(a1)?);
+                }
+                op::SUB => {
+                    let sp = stack.get_mut(a0)?;
+                    *sp = sp.wrapping_sub(stack.get(a1)?);
+                }
                 op::DIV => {
                     let d = stack.get(a1)?;
                     if d == 0 {
@@ -1381,8 +1416,8 @@ impl<'a> Interpreter<'a> {
                     *sp = muldiv_no_round(*sp, 64, d);
                 }
                 op::MUL => *stack.get_mut(a0)? = muldiv(stack.get(a0)?, stack.get(a1)?, 64),
-                op::ABS => *stack.get_mut(a0)? = (stack.get(a0)?).abs(),
-                op::NEG => *stack.get_mut(a0)? = -stack.get(a0)?,
+                op::ABS => *stack.get_mut(a0)? = (stack.get(a0)?).wrapping_abs(),
+                op::NEG => *stack.get_mut(a0)? = stack.get(a0)?.wrapping_neg(),
                 op::FLOOR => *stack.get_mut(a0)? = floor(stack.get(a0)?),
                 op::CEILING => *stack.get_mut(a0)? = ceil(stack.get(a0)?),
                 op::ROUND00..=op::ROUND11 => *stack.get_mut(a0)? = self.round(stack.get(a0)?),
@@ -1503,6 +1538,7 @@ impl<'a> Interpreter<'a> {
                             return None;
                         }
                         if o < 0 {
+                            loop_iterations = loop_iterations.saturating_add(1);
                             decoder.pc = ins.pc - (-o) as usize;
                         } else {
                             decoder.pc = ins.pc + o as usize;
@@ -1892,7 +1928,10 @@ impl<'a> Interpreter<'a> {
                                 break;
                             }
                         }
-                        if index != !0 && callstack_top < callstack_len {
+                        if index != !0 && callstack_top >= callstack_len {
+                            self.budget_exhausted = true;
+                            return None;
+                        } else if index != !0 {
                             let def = self.idefs[index];
                             let rec = CallRecord {
                                 caller_program: program,
@@ -3,7 +3,7 @@ pub fn floor(x: i32) -> i32 {
 }
 
 pub fn round(x: i32) -> i32 {
-    floor(x + 32)
+    floor(x.wrapping_add(32))
 }
 
 #[inline(always)]
@@ -12,89 +12,62 @@ pub fn mul(a: i32, b: i32) -> i32 {
     ((ab + 0x8000 - i64::from(ab < 0)) >> 16) as i32
 }
 
-pub fn div(mut a: i32, mut b: i32) -> i32 {
-    let mut sign = 1;
-    if a < 0 {
-        a = -a;
-        sign = -1;
-    }
-    if b < 0 {
-        b = -b;
-        sign = -sign;
-    }
+pub fn div(a: i32, b: i32) -> i32 {
+    let negative = (a < 0) != (b < 0);
+    let (a, b) = (a.unsigned_abs() as u64, b.unsigned_abs() as u64);
     let q = if b == 0 {
         0x7FFFFFFF
     } else {
-        ((((a as u64) << 16) + ((b as u64) >> 1)) / (b as u64)) as u32
+        (((a << 16) + (b >> 1)) / b) as u32
     };
-    if sign < 0 {
-        -(q as i32)
-    } else {
-        q as i32
-    }
+    apply_sign(q as i32, negative)
 }
 
-pub fn muldiv(mut a: i32, mut b: i32, mut c: i32) -> i32 {
-    let mut sign = 1;
-    if a < 0 {
-        a = -a;
-        sign = -1;
-    }
-    if b < 0 {
-        b = -b;
-        sign = -sign;
-    }
-    if c < 0 {
-        c = -c;
-        sign = -sign;
-    }
+pub fn muldiv(a: i32, b: i32, c: i32) -> i32 {
+    let negative = ((a < 0) != (b < 0)) != (c < 0);
+    let (a, b, c) = (
+        a.unsigned_abs() as u64,
+        b.unsigned_abs() as u64,
+        c.unsigned_abs() as u64,
+    );
     let d = if c > 0 {
-        ((a as i64) * (b as i64) + ((c as i64) >> 1)) / c as i64
+        (a * b + (c >> 1)) / c
     } else {
         0x7FFFFFFF
     };
-    if sign < 0 {
-        -(d as i32)
-    } else {
-        d as i32
-    }
+    apply_sign(d as i32, negative)
 }
 
 pub fn ceil(x: i32) -> i32 {
-    floor(x + 63)
+    floor(x.wrapping_add(63))
 }
 
 pub fn floor_pad(x: i32, n: i32) -> i32 {
-    x & !(n - 1)
+    x & !(n.wrapping_sub(1))
 }
 
 pub fn round_pad(x: i32, n: i32) -> i32 {
-    floor_pad(x + n / 2, n)
+    floor_pad(x.wrapping_add(n / 2), n)
 }
 
-pub fn muldiv_no_round(mut a: i32, mut b: i32, mut c: i32) -> i32 {
-    let mut s = 1;
-    if a < 0 {
-        a = -a;
-        s = -1;
-    }
-    if b < 0 {
-        b = -b;
-        s = -s;
-    }
-    if c < 0 {
-        c = -c;
-        s = -s;
-    }
-    let d = if c > 0 {
-        ((a as i64) * (b as i64)) / c as i64
-    } else {
-        0x7FFFFFFF
-    };
-    if s < 0 {
-        -(d as i32)
+pub fn muldiv_no_round(a: i32, b: i32, c: i32) -> i32 {
+    let negative = ((a < 0) != (b < 0)) != (c < 0);
+    let (a, b, c) = (
+        a.unsigned_abs() as u64,
+        b.unsigned_abs() as u64,
+        c.unsigned_abs() as u64,
+    );
+    let d = if c > 0 { (a * b) / c } else { 0x7FFFFFFF };
+    apply_sign(d as i32, negative)
+}
+
+/// Negates the value if requested, wrapping on overflow to match the
+/// behavior of FreeType for malformed hinting programs.
+fn apply_sign(x: i32, negative: bool) -> i32 {
+    if negative {
+        x.wrapping_neg()
     } else {
-        d as i32
+        x
     }
 }
 
@@ -107,7 +80,24 @@ pub fn mul14(a: i32, b: i32) -> i32 {
 pub fn dot14(ax: i32, ay: i32, bx: i32, by: i32) -> i32 {
     let mut v1 = ax as i64 * bx as i64;
     let v2 = ay as i64 * by as i64;
-    v1 += v2;
+    v1 = v1.wrapping_add(v2);
     v1 += 0x2000 + (v1 >> 63);
     (v1 >> 14) as i32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extreme_values_do_not_overflow() {
+        assert_eq!(muldiv(i32::MIN, 1, 1), i32::MIN);
+        assert_eq!(muldiv(i32::MIN, -1, 1), i32::MIN);
+        assert_eq!(muldiv_no_round(i32::MIN, 64, -64), i32::MIN);
+        assert_eq!(muldiv(-128, 64, 64), -128);
+        assert_eq!(div(i32::MIN, 0), -0x7FFFFFFF);
+        assert_eq!(round(i32::MAX), floor(i32::MIN + 31));
+        assert_eq!(ceil(i32::MAX), floor(i32::MIN + 62));
+        dot14(i32::MIN, i32::MIN, i32::MIN, i32::MIN);
+    }
+}
@@ -1,11 +1,13 @@
 mod bytecode;
 mod cache;
+pub mod cvar;
 mod interpret;
 mod math;
 mod state;
 
 use super::scaler::ScalerFont;
-use crate::scale::Hinting;
+use crate::scale::{vec_memory_usage, Hinting, HintingLimits};
+use alloc::vec::Vec;
 
 use interpret::{Interpreter, Stack, Zone};
 use state::InstanceState;
@@ -31,15 +33,19 @@ pub enum Slot {
 #[derive(Copy, Clone, Default, Debug)]
 pub struct HintConfig {
     hinting: Option<Hinting>,
+    limits: HintingLimits,
     is_enabled: bool,
+    budget_exhausted: bool,
     slot: Option<Slot>,
 }
 
 impl HintConfig {
-    pub fn new(hinting: Option<Hinting>) -> Self {
+    pub fn new(hinting: Option<Hinting>, limits: HintingLimits) -> Self {
         Self {
             hinting,
+            limits,
             is_enabled: hinting.is_some(),
+            budget_exhausted: false,
             slot: None,
         }
     }
@@ -48,8 +54,25 @@ impl HintConfig {
         self.is_enabled
     }
 
+    /// Returns the requested hinting mode.
+    pub fn hinting(&self) -> Option<Hinting> {
+        self.hinting
+    }
+
+    /// Returns true if the most recent failure was caused by a program
+    /// exceeding its execution budget.
+    pub fn is_budget_exhausted(&self) -> bool {
+        self.budget_exhausted
+    }
+
+    /// Disables hinting for the current glyph.
+    pub fn disable(&mut self) {
+        self.is_enabled = false;
+    }
+
     pub fn reset(&mut self) {
         self.is_enabled = self.hinting.is_some();
+        self.budget_exhausted = false;
     }
 }
 
@@ -59,7 +82,7 @@ pub struct HintGlyph<'a> {
     pub font: &'a ScalerFont<'a>,
     pub config: &'a mut HintConfig,
     pub points: &'a mut [Point<F26Dot6>],
-    pub original: &'a mut [Point<F26Dot6>],
+    pub original: &'a mut [Point<i32>],
     pub unscaled: &'a mut [Point<i32>],
     pub flags: &'a mut [PointFlags],
     pub contours: &'a mut [u16],
@@ -78,10 +101,21 @@ pub struct HintContext {
     pub twilight: Vec<Point<i32>>,
     /// Twilight zone tags.
     pub twilight_tags: Vec<PointFlags>,
+    /// Scaled glyph points in the representation used by the interpreter.
+    scaled: Vec<Point<i32>>,
     cache: cache::Cache,
 }
 
 impl HintContext {
+    /// Returns the number of bytes allocated by the context and its cache.
+    pub fn memory_usage(&self) -> usize {
+        vec_memory_usage(&self.stack)
+            + vec_memory_usage(&self.twilight)
+            + vec_memory_usage(&self.twilight_tags)
+            + vec_memory_usage(&self.scaled)
+            + self.cache.memory_usage()
+    }
+
     pub fn hint(&mut self, glyph: HintGlyph) -> bool {
         if glyph.config.slot.is_none() {
             let max_twilight = glyph.font.max_twilight as usize + 4;
@@ -119,11 +153,15 @@ impl HintContext {
                     glyph_zone,
                     glyph.font.coords,
                     glyph.font.axis_count,
+                    glyph.config.limits,
                 );
                 if !font_entry.is_current {
+                    #[cfg(feature = "trace")]
+                    tracing::debug!("running font program");
                     let mut state = InstanceState::default();
                     if !hinter.run_fpgm(&mut state, Stack::new(&mut self.stack), glyph.font.fpgm) {
                         glyph.config.is_enabled = false;
+                        glyph.config.budget_exhausted = hinter.is_budget_exhausted();
                         return false;
                     }
                 }
@@ -139,6 +177,7 @@ impl HintContext {
                         glyph.font.scale.to_bits(),
                     ) {
                         glyph.config.is_enabled = false;
+                        glyph.config.budget_exhausted = hinter.is_budget_exhausted();
                         return false;
                     }
                 }
@@ -155,18 +194,18 @@ impl HintContext {
                 *c -= point_base as u16;
             }
         }
-        let (scaled, original, phantom) = unsafe {
-            use core::slice::from_raw_parts_mut;
-            (
-                from_raw_parts_mut(glyph.points.as_mut_ptr() as *mut _, glyph.points.len()),
-                from_raw_parts_mut(glyph.original.as_mut_ptr() as *mut _, glyph.original.len()),
-                from_raw_parts_mut(glyph.phantom.as_mut_ptr() as *mut _, glyph.phantom.len()),
-            )
-        };
+        // The interpreter operates on raw 26.6 values so convert the points
+        // of the current glyph into our buffer and back again when hinting
+        // is complete.
+        let points = &mut glyph.points[point_base..];
+        self.scaled.resize(points.len(), Point::default());
+        for (dest, src) in self.scaled.iter_mut().zip(points.iter()) {
+            *dest = Point::new(src.x.to_bits(), src.y.to_bits());
+        }
         let glyph_zone = Zone::new(
             glyph.unscaled,
-            original,
-            &mut scaled[point_base..],
+            glyph.original,
+            &mut self.scaled[..],
             &mut glyph.flags[point_base..],
             &glyph.contours[glyph.contour_base..],
         );
@@ -192,6 +231,7 @@ impl HintContext {
             glyph_zone,
             glyph.font.coords,
             glyph.font.axis_count,
+            glyph.config.limits,
         );
         let result = hinter.run(
             &mut instance.state,
@@ -201,9 +241,21 @@ impl HintContext {
             glyph.ins,
             glyph.is_composite,
         );
+        if !result {
+            glyph.config.budget_exhausted = hinter.is_budget_exhausted();
+        }
+        let from_bits =
+            |p: &Point<i32>| Point::new(F26Dot6::from_bits(p.x), F26Dot6::from_bits(p.y));
+        for (dest, src) in glyph.points[point_base..].iter_mut().zip(&self.scaled) {
+            *dest = from_bits(src);
+        }
         if !instance.state.compat_enabled() {
-            for (i, p) in (scaled[scaled.len() - 4..]).iter().enumerate() {
-                phantom[i] = *p;
+            for (dest, src) in glyph
+                .phantom
+                .iter_mut()
+                .zip(&self.scaled[self.scaled.len() - 4..])
+            {
+                *dest = from_bits(src);
             }
         }
         if glyph.is_composite && point_base != 0 {
@@ -1,5 +1,6 @@
 use super::math::*;
 use crate::scale::Hinting;
+use alloc::vec::Vec;
 
 use raw::tables::glyf::PointFlags;
 
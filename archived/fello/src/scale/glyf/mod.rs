@@ -13,7 +13,11 @@ mod hint;
 pub use read_fonts::types::Point;
 pub use {outline::Outline, scaler::Scaler};
 
-use read_fonts::types::{F26Dot6, Fixed, Pen};
+pub(crate) use scaler::ScalerFont;
+
+use super::vec_memory_usage;
+use alloc::vec::Vec;
+use read_fonts::types::{F26Dot6, Fixed, GlyphId, Pen};
 
 /// Point that actually represents a vector holding a variation delta.
 pub type Delta = Point<Fixed>;
@@ -23,8 +27,8 @@ pub type Delta = Point<Fixed>;
 pub struct Context {
     /// Unscaled points.
     unscaled: Vec<Point<i32>>,
-    /// Original scaled points.
-    original: Vec<Point<F26Dot6>>,
+    /// Original scaled points as raw 26.6 values for the hinter.
+    original: Vec<Point<i32>>,
     /// Storage for simple glyph deltas.
     deltas: Vec<Delta>,
     /// Storage for composite glyph deltas.
@@ -32,6 +36,8 @@ pub struct Context {
     /// Temporary point storage that is used for storing intermediate
     /// interpolated values while computing deltas.
     working_points: Vec<Point<Fixed>>,
+    /// Composite glyphs that are currently being loaded, outermost first.
+    composites: Vec<GlyphId>,
     /// Cache and retained state for executing TrueType bytecode.
     #[cfg(feature = "hinting")]
     hint_context: hint::HintContext,
@@ -42,6 +48,21 @@ impl Context {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Returns the number of bytes allocated by the context.
+    pub fn memory_usage(&self) -> usize {
+        #[cfg(feature = "hinting")]
+        let hint_usage = self.hint_context.memory_usage();
+        #[cfg(not(feature = "hinting"))]
+        let hint_usage = 0;
+        vec_memory_usage(&self.unscaled)
+            + vec_memory_usage(&self.original)
+            + vec_memory_usage(&self.deltas)
+            + vec_memory_usage(&self.composite_deltas)
+            + vec_memory_usage(&self.working_points)
+            + vec_memory_usage(&self.composites)
+            + hint_usage
+    }
 }
 
 #[cfg(test)]
@@ -63,6 +84,7 @@ mod tests {
                 None,
                 expected_outline.size,
                 None,
+                Default::default(),
                 &expected_outline.coords,
             )
             .unwrap();
@@ -1,4 +1,5 @@
-use super::{Pen, Point};
+use super::{super::vec_memory_usage, Pen, Point};
+use alloc::vec::Vec;
 
 use read_fonts::{
     tables::glyf::{PointFlags, ToPathError},
@@ -6,6 +7,10 @@ use read_fonts::{
 };
 
 /// TrueType outline.
+///
+/// For outlines loaded at [`Size::unscaled`](crate::Size::unscaled), the
+/// points are whole font units stored in 26.6 format and are available as
+/// integers with [`unscaled_points`](Self::unscaled_points).
 #[derive(Clone, PartialEq, Eq, Default, Debug)]
 pub struct Outline {
     /// Set of points that define the shape of the outline.
@@ -14,6 +19,13 @@ pub struct Outline {
     pub flags: Vec<PointFlags>,
     /// Index of the end points for each contour in the outline.
     pub contours: Vec<u16>,
+    /// Phantom points for the horizontal origin, horizontal advance, top
+    /// origin and vertical advance, in the same units as the points.
+    ///
+    /// These are zero for glyphs without an outline.
+    ///
+    /// See <https://learn.microsoft.com/en-us/typography/opentype/spec/tt_instructing_glyphs#phantom-points>
+    pub phantom: [Point<F26Dot6>; 4],
 }
 
 impl Outline {
@@ -22,11 +34,26 @@ impl Outline {
         Self::default()
     }
 
+    /// Returns the number of bytes allocated by the outline.
+    pub fn memory_usage(&self) -> usize {
+        vec_memory_usage(&self.points)
+            + vec_memory_usage(&self.flags)
+            + vec_memory_usage(&self.contours)
+    }
+
     /// Empties the outline.
     pub fn clear(&mut self) {
         self.points.clear();
         self.flags.clear();
         self.contours.clear();
+        self.phantom = Default::default();
+    }
+
+    /// Returns an iterator over the points in font units.
+    ///
+    /// This is only meaningful for outlines loaded without scaling.
+    pub fn unscaled_points(&self) -> impl Iterator<Item = Point<i32>> + '_ {
+        self.points.iter().map(|point| point.map(F26Dot6::to_i32))
     }
 
     /// Converts the outline to a sequence of path commands and invokes the callback for
@@ -1,13 +1,17 @@
-use crate::FontKey;
+use crate::{meta::metrics, FontKey};
+use alloc::vec::Vec;
 
 use super::{
-    super::{Error, NormalizedCoord, Result, GLYF_COMPOSITE_RECURSION_LIMIT},
+    super::{Error, NormalizedCoord, Result, Warning, GLYF_COMPOSITE_RECURSION_LIMIT},
     Context, Outline, Point,
 };
 
 #[cfg(feature = "hinting")]
 use {
-    super::{super::Hinting, hint},
+    super::{
+        super::{Hinting, HintingLimits},
+        hint,
+    },
     read_fonts::tables::glyf::PointMarker,
 };
 
@@ -20,7 +24,7 @@ use read_fonts::{
         loca::Loca,
     },
     types::{BigEndian, F26Dot6, F2Dot14, Fixed, GlyphId, Tag},
-    TableProvider,
+    FontData, TableProvider,
 };
 
 /// TrueType glyph scaler for a specific font and configuration.
@@ -38,6 +42,9 @@ pub struct Scaler<'a> {
     ///
     /// See <https://learn.microsoft.com/en-us/typography/opentype/spec/tt_instructing_glyphs#phantom-points>
     phantom: [Point<F26Dot6>; 4],
+    /// First problem encountered while loading the current glyph that did
+    /// not prevent producing an outline.
+    warning: Option<Warning>,
 }
 
 impl<'a> Scaler<'a> {
@@ -49,25 +56,50 @@ impl<'a> Scaler<'a> {
         cache_key: Option<FontKey>,
         size: f32,
         #[cfg(feature = "hinting")] hinting: Option<Hinting>,
+        #[cfg(feature = "hinting")] limits: HintingLimits,
         coords: &'a [NormalizedCoord],
     ) -> Result<Self> {
         let font = ScalerFont::new(font, cache_key, size, coords)?;
-        Ok(Self {
+        Ok(Self::from_font(
+            context,
+            font,
+            #[cfg(feature = "hinting")]
+            hinting,
+            #[cfg(feature = "hinting")]
+            limits,
+        ))
+    }
+
+    /// Creates a new scaler for font data that has already been read.
+    pub(crate) fn from_font(
+        context: &'a mut Context,
+        font: ScalerFont<'a>,
+        #[cfg(feature = "hinting")] hinting: Option<Hinting>,
+        #[cfg(feature = "hinting")] limits: HintingLimits,
+    ) -> Self {
+        Self {
             context,
             font,
             #[cfg(feature = "hinting")]
-            hint_config: hint::HintConfig::new(hinting),
+            hint_config: hint::HintConfig::new(hinting, limits),
             phantom: Default::default(),
-        })
+            warning: None,
+        }
     }
 
     /// Loads an outline for the specified glyph identifier to the preallocated
     /// target.
-    pub fn load(&mut self, glyph_id: GlyphId, outline: &mut Outline) -> Result<()> {
+    ///
+    /// Components of composite glyphs that refer back to a glyph that is
+    /// already being loaded are skipped and reported in the returned
+    /// warning while the remaining components are still loaded.
+    pub fn load(&mut self, glyph_id: GlyphId, outline: &mut Outline) -> Result<Option<Warning>> {
         outline.clear();
         self.context.unscaled.clear();
         self.context.original.clear();
         self.context.deltas.clear();
+        self.context.composites.clear();
+        self.warning = None;
         if glyph_id.to_u16() >= self.font.glyph_count {
             return Err(Error::GlyphNotFound(glyph_id));
         }
@@ -76,14 +108,36 @@ impl<'a> Scaler<'a> {
             self.hint_config.reset();
         }
         self.phantom = Default::default();
-        self.load_glyph(glyph_id, outline, 0)?;
+        let result = self.load_glyph(glyph_id, outline, 0);
+        #[cfg(feature = "hinting")]
+        let result = match result {
+            Err(Error::HintingFailed(_)) if self.hint_config.is_budget_exhausted() => {
+                // The bytecode exceeded its execution budget so fall back
+                // to an unhinted outline.
+                outline.clear();
+                self.context.unscaled.clear();
+                self.context.original.clear();
+                self.context.deltas.clear();
+                self.context.composites.clear();
+                self.hint_config.disable();
+                self.phantom = Default::default();
+                self.warning = None;
+                self.load_glyph(glyph_id, outline, 0)
+            }
+            result => result,
+        };
+        result?;
         let x_shift = self.phantom[0].x;
         if x_shift != F26Dot6::ZERO {
             for point in outline.points.iter_mut() {
                 point.x -= x_shift;
             }
         }
-        Ok(())
+        outline.phantom = self.phantom;
+        for point in outline.phantom.iter_mut() {
+            point.x -= x_shift;
+        }
+        Ok(self.warning)
     }
 }
 
@@ -103,15 +157,23 @@ impl<'a> Scaler<'a> {
         };
         let glyph = match glyph {
             Some(glyph) => glyph,
-            // This is a valid empty glyph
-            None => return Ok(()),
+            // This is a valid empty glyph but it still has metrics which
+            // may be used by a composite through USE_MY_METRICS.
+            None => {
+                self.setup_phantom([0; 4], glyph_id);
+                self.scale_phantom();
+                return Ok(());
+            }
         };
         let bounds = [glyph.x_min(), glyph.x_max(), glyph.y_min(), glyph.y_max()];
         self.setup_phantom(bounds, glyph_id);
         match glyph {
             Glyph::Simple(simple) => self.load_simple(&simple, glyph_id, outline),
             Glyph::Composite(composite) => {
-                self.load_composite(&composite, glyph_id, outline, recurse_depth)
+                self.context.composites.push(glyph_id);
+                let result = self.load_composite(&composite, glyph_id, outline, recurse_depth);
+                self.context.composites.pop();
+                result
             }
         }
     }
@@ -238,14 +300,12 @@ impl<'a> Scaler<'a> {
             // Hinting requires a copy of the scaled points. These are used
             // as references when modifying an outline.
             self.context.original.clear();
-            self.context
-                .original
-                .extend_from_slice(&outline.points[point_base..point_end]);
-            // When hinting, round the components of the phantom points.
-            for point in &mut outline.points[point_end - 4..] {
-                point.x = point.x.round();
-                point.y = point.y.round();
-            }
+            self.context.original.extend(
+                outline.points[point_base..point_end]
+                    .iter()
+                    .map(|point| Point::new(point.x.to_bits(), point.y.to_bits())),
+            );
+            round_phantom(outline);
             // Apply hinting to the set of contours for this outline.
             if !self.hint(outline, point_base, contour_base, ins, false) {
                 return Err(Error::HintingFailed(glyph_id));
@@ -273,15 +333,7 @@ impl<'a> Scaler<'a> {
         let point_base = outline.points.len();
         let contour_base = outline.contours.len();
         let scale = self.font.scale;
-        if self.font.is_scaled {
-            for point in self.phantom.iter_mut() {
-                *point *= scale;
-            }
-        } else {
-            for point in self.phantom.iter_mut() {
-                *point = point.map(|x| F26Dot6::from_i32(x.to_bits()));
-            }
-        }
+        self.scale_phantom();
         // Compute the per component deltas. Since composites can be nested, we
         // use a stack and keep track of the base.
         let mut have_deltas = false;
@@ -309,6 +361,14 @@ impl<'a> Scaler<'a> {
             }
         }
         for (i, component) in composite.components().enumerate() {
+            if self.context.composites.contains(&component.glyph) {
+                // A component that refers back to a glyph on the current
+                // path would recurse until hitting the limit. Skip it and
+                // keep the remaining components, like FreeType does.
+                self.warning
+                    .get_or_insert(Warning::CyclicComponent(glyph_id, component.glyph));
+                continue;
+            }
             // Loading a component glyph will override phantom points so save a copy. We'll
             // restore them unless the USE_MY_METRICS flag is set.
             let phantom = self.phantom;
@@ -403,7 +463,11 @@ impl<'a> Scaler<'a> {
                                 .flags
                                 .contains(CompositeGlyphFlags::ROUND_XY_TO_GRID)
                         {
-                            // Only round the y-coordinate, per FreeType.
+                            // Per FreeType, the x-coordinate is only rounded
+                            // when hinting along both axes.
+                            if self.hint_config.hinting() == Some(Hinting::Full) {
+                                offset.x = offset.x.round();
+                            }
                             offset.y = offset.y.round();
                         }
                         offset
@@ -433,14 +497,18 @@ impl<'a> Scaler<'a> {
         if have_deltas {
             self.context.composite_deltas.truncate(delta_base);
         }
+        // Like FreeType, the program of a composite runs whenever the
+        // WE_HAVE_INSTRUCTIONS flag is set, even if it is empty, since the
+        // phantom points are rounded as a side effect.
         #[cfg(feature = "hinting")]
-        if self.font.is_scaled && self.hint_config.is_enabled() {
-            let ins = composite.instructions().unwrap_or_default();
-            if !ins.is_empty() {
+        if self.font.is_scaled && self.hint_config.is_enabled() && outline.points.len() > point_base
+        {
+            if let Some(ins) = composite.instructions() {
                 // Append the current phantom points to the outline.
                 self.push_phantom(outline);
                 // For composite glyphs, the unscaled and original points are simply
-                // copies of the current point set.
+                // copies of the current point set. The program operates on the
+                // already hinted and positioned components.
                 self.context.unscaled.clear();
                 self.context.unscaled.extend(
                     outline.points[point_base..]
@@ -450,15 +518,12 @@ impl<'a> Scaler<'a> {
                 self.context.original.clear();
                 self.context
                     .original
-                    .extend_from_slice(&outline.points[point_base..]);
-                let point_end = outline.points.len();
-                // Round the phantom points.
-                for p in &mut outline.points[point_end - 4..] {
-                    p.x = p.x.round();
-                    p.y = p.y.round();
-                }
-                // Clear the "touched" flags that are used during IUP processing.
-                for flag in &mut outline.flags[point_base..] {
+                    .extend_from_slice(&self.context.unscaled);
+                round_phantom(outline);
+                // Clear the "touched" flags of the component points that are
+                // used during IUP processing.
+                let point_end = outline.points.len() - 4;
+                for flag in &mut outline.flags[point_base..point_end] {
                     flag.clear_marker(PointMarker::TOUCHED);
                 }
                 if !self.hint(outline, point_base, contour_base, ins, true) {
@@ -493,6 +558,21 @@ impl<'a> Scaler<'a> {
         self.phantom[3].y = self.phantom[2].y - F26Dot6::from_bits(vadvance);
     }
 
+    /// Converts the phantom points from font units to the units of the
+    /// outline.
+    fn scale_phantom(&mut self) {
+        if self.font.is_scaled {
+            let scale = self.font.scale;
+            for point in self.phantom.iter_mut() {
+                *point *= scale;
+            }
+        } else {
+            for point in self.phantom.iter_mut() {
+                *point = point.map(|x| F26Dot6::from_i32(x.to_bits()));
+            }
+        }
+    }
+
     fn push_phantom(&mut self, outline: &mut Outline) {
         for i in 0..4 {
             outline.points.push(self.phantom[i]);
@@ -512,9 +592,24 @@ impl<'a> Scaler<'a> {
     }
 }
 
+/// Rounds the phantom points at the end of the outline before hinting.
+///
+/// As in FreeType, only the horizontal metrics of the first two points and
+/// the vertical metrics of the last two are rounded.
+#[cfg(feature = "hinting")]
+fn round_phantom(outline: &mut Outline) {
+    let phantom = outline.points.len() - 4;
+    let points = &mut outline.points[phantom..];
+    points[0].x = points[0].x.round();
+    points[1].x = points[1].x.round();
+    points[2].y = points[2].y.round();
+    points[3].y = points[3].y.round();
+}
+
 // Hinting
 #[cfg(feature = "hinting")]
 impl<'a> Scaler<'a> {
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "trace", skip_all))]
     fn hint(
         &mut self,
         outline: &mut Outline,
@@ -560,6 +655,7 @@ pub struct ScalerFont<'a> {
     pub fpgm: &'a [u8],
     pub prep: &'a [u8],
     pub cvt: &'a [BigEndian<i16>],
+    pub cvar: Option<FontData<'a>>,
     pub units_per_em: u16,
     pub glyph_count: u16,
     pub max_storage: u16,
@@ -572,7 +668,7 @@ pub struct ScalerFont<'a> {
 }
 
 impl<'a> ScalerFont<'a> {
-    fn new(
+    pub(crate) fn new(
         font: &impl TableProvider<'a>,
         key: Option<FontKey>,
         size: f32,
@@ -583,8 +679,9 @@ impl<'a> ScalerFont<'a> {
         let gvar = font.gvar().ok();
         let hmtx = font.hmtx()?;
         let hvar = font.hvar().ok();
-        let units_per_em = font.head()?.units_per_em();
-        let size = size.abs();
+        let units_per_em = metrics::units_per_em(font);
+        // Avoid f32::abs which is not available in core.
+        let size = if size < 0.0 { -size } else { size };
         let ppem = size as u16;
         let (is_scaled, scale) = if size != 0. && units_per_em != 0 {
             (
@@ -606,6 +703,7 @@ impl<'a> ScalerFont<'a> {
             .data_for_tag(Tag::new(b"cvt "))
             .and_then(|data| data.read_array(0..data.len()).ok())
             .unwrap_or_default();
+        let cvar = font.data_for_tag(Tag::new(b"cvar"));
         let maxp = font.maxp()?;
         let glyph_count = maxp.num_glyphs();
         let axis_count = font.fvar().map(|fvar| fvar.axis_count()).unwrap_or(0);
@@ -627,6 +725,7 @@ impl<'a> ScalerFont<'a> {
             fpgm,
             prep,
             cvt,
+            cvar,
             glyph_count,
             units_per_em,
             max_storage: maxp.max_storage().unwrap_or(0),
@@ -699,8 +798,16 @@ impl<'a> ScalerFont<'a> {
         if scaled_cvt.len() < self.cvt.len() {
             scaled_cvt.resize(self.cvt.len(), 0);
         }
-        for (src, dest) in self.cvt.iter().zip(scaled_cvt.iter_mut()) {
-            *dest = src.get() as i32 * 64;
+        let cvt = &mut scaled_cvt[..self.cvt.len()];
+        for (src, dest) in self.cvt.iter().zip(cvt.iter_mut()) {
+            *dest = src.get() as i32;
+        }
+        if let Some(cvar) = self.cvar.filter(|_| !self.coords.is_empty()) {
+            // Malformed variation data leaves the default values in place.
+            let _ = hint::cvar::apply_deltas(cvar, self.axis_count, self.coords, cvt);
+        }
+        for value in cvt.iter_mut() {
+            *value *= 64;
         }
         if let Some(scale) = scale {
             let scale = F26Dot6::from_bits(scale >> 6);
@@ -0,0 +1,164 @@
+//! Fonts with a resolved size, location and hinting mode.
+
+use super::{
+    cff::{Scaler as PostScriptScaler, ScalerSubfont},
+    color::ColorGlyphs,
+    glyf,
+    outline_cache::CacheConfig,
+    scaler::{Outlines, Output},
+    substitute::NominalSubstitutions,
+    Context, NormalizedCoord, Scaler, StemDarkening, Transform,
+};
+use crate::{meta::metrics::glyph_count, FontKey};
+use alloc::vec::Vec;
+use read_fonts::TableProvider;
+
+#[cfg(feature = "hinting")]
+use super::{Hinting, HintingLimits};
+
+/// Font at a specific size, location in variation space and hinting mode.
+///
+/// Building a scaler with [`ScalerBuilder::build`](super::ScalerBuilder::build)
+/// resolves the variation settings and reads the outline tables each time.
+/// An instance does this work once so that scalers can be created from it
+/// cheaply, which is useful when loading glyphs in layout loops. Instances
+/// don't borrow the context that built them and can be cloned without
+/// reading the font again.
+///
+/// Instances are created with
+/// [`ScalerBuilder::instance`](super::ScalerBuilder::instance).
+#[derive(Clone)]
+pub struct FontInstance<'a> {
+    size: f32,
+    skew: f32,
+    transform: Transform,
+    coords: Vec<NormalizedCoord>,
+    #[cfg(feature = "hinting")]
+    hint: Option<Hinting>,
+    #[cfg(feature = "hinting")]
+    hint_limits: HintingLimits,
+    darkening: Option<StemDarkening>,
+    outlines: Option<InstanceOutlines<'a>>,
+    color: ColorGlyphs<'a>,
+    glyph_count: u16,
+    substitutions: NominalSubstitutions<'a>,
+    cache_config: Option<CacheConfig>,
+}
+
+impl<'a> FontInstance<'a> {
+    pub(super) fn new(
+        font: &impl TableProvider<'a>,
+        cache_key: Option<FontKey>,
+        output: Output,
+        cache_config: Option<CacheConfig>,
+        coords: &[NormalizedCoord],
+        #[cfg(feature = "hinting")] hint: Option<Hinting>,
+        #[cfg(feature = "hinting")] hint_limits: HintingLimits,
+        darkening: Option<StemDarkening>,
+        substitutions: NominalSubstitutions<'a>,
+    ) -> Self {
+        let size = output.size;
+        // The coordinates are attached to the TrueType font data when a
+        // scaler is created since the instance owns them.
+        let outlines = if let Ok(glyf) = glyf::ScalerFont::new(font, cache_key, size, &[]) {
+            Some(InstanceOutlines::TrueType(glyf))
+        } else {
+            PostScriptScaler::new(font).ok().and_then(|scaler| {
+                let first_subfont = scaler.subfont(0, size, coords).ok()?;
+                Some(InstanceOutlines::PostScript(scaler, first_subfont))
+            })
+        };
+        Self {
+            size,
+            skew: output.skew,
+            transform: output.transform,
+            coords: coords.to_vec(),
+            #[cfg(feature = "hinting")]
+            hint,
+            #[cfg(feature = "hinting")]
+            hint_limits,
+            darkening,
+            outlines,
+            color: ColorGlyphs::new(font),
+            glyph_count: glyph_count(font),
+            substitutions,
+            cache_config,
+        }
+    }
+
+    /// Returns the size in pixels per em, or zero for unscaled outlines.
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Returns the transform that maps the emitted outlines to the
+    /// requested size and skew.
+    ///
+    /// See [`Scaler::transform`].
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Returns the normalized variation coordinates of the instance.
+    pub fn normalized_coords(&self) -> &[NormalizedCoord] {
+        &self.coords
+    }
+
+    /// Returns true if the instance has a source for simple outlines.
+    pub fn has_outlines(&self) -> bool {
+        self.outlines.is_some()
+    }
+
+    /// Creates a scaler for the instance that uses the given context for
+    /// temporary storage.
+    pub fn scaler<'b>(&'b self, context: &'b mut Context) -> Scaler<'b> {
+        let outlines = match &self.outlines {
+            Some(InstanceOutlines::TrueType(font)) => {
+                let mut font: glyf::ScalerFont<'b> = font.clone();
+                font.coords = &self.coords;
+                Some(Outlines::TrueType(
+                    glyf::Scaler::from_font(
+                        &mut context.glyf,
+                        font,
+                        #[cfg(feature = "hinting")]
+                        self.hint,
+                        #[cfg(feature = "hinting")]
+                        self.hint_limits,
+                    ),
+                    &mut context.glyf_outline,
+                ))
+            }
+            Some(InstanceOutlines::PostScript(scaler, subfont)) => {
+                Some(Outlines::PostScript(scaler.clone(), subfont.clone()))
+            }
+            None => None,
+        };
+        let cache = match &self.cache_config {
+            Some(config) => context.outline_cache.begin(config),
+            None => None,
+        };
+        Scaler {
+            size: self.size,
+            skew: self.skew,
+            transform: self.transform,
+            coords: &self.coords,
+            #[cfg(feature = "hinting")]
+            hint: self.hint,
+            darkening: self.darkening,
+            outlines,
+            color: self.color.clone(),
+            glyph_count: self.glyph_count,
+            substitutions: self.substitutions.clone(),
+            cache,
+        }
+    }
+}
+
+/// Outline sources with their tables already read.
+// Clippy doesn't like the size discrepancy between the two variants.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone)]
+enum InstanceOutlines<'a> {
+    TrueType(glyf::ScalerFont<'a>),
+    PostScript(PostScriptScaler<'a>, ScalerSubfont),
+}
@@ -3,20 +3,34 @@ Glyph loading and scaling.
 */
 
 mod cff;
+mod color;
+mod darken;
 mod error;
+mod instance;
+mod outline_cache;
 mod scaler;
+mod substitute;
+mod svg;
+mod transform;
 
 #[cfg(test)]
 mod test;
 
 pub mod glyf;
+#[cfg(feature = "std")]
+pub mod parallel;
 
 pub use read_fonts::types::Pen;
 
-pub use error::{Error, Result};
-pub use scaler::{Scaler, ScalerBuilder};
+pub use darken::StemDarkening;
+pub use error::{Error, Result, Warning};
+pub use instance::FontInstance;
+pub use scaler::{OutlineKind, Scaler, ScalerBuilder};
+pub use svg::{glyphs_to_svg, outline_to_svg, SvgPen};
+pub use transform::{Transform, TransformPen};
 
 use super::{GlyphId, NormalizedCoord, Setting};
+use alloc::vec::Vec;
 use core::str::FromStr;
 use read_fonts::types::Tag;
 
@@ -43,6 +57,36 @@ pub enum Hinting {
     VerticalSubpixel,
 }
 
+/// Execution budgets for the TrueType bytecode interpreter.
+///
+/// These guard against fonts with hinting programs that loop excessively
+/// or never terminate. When a program exceeds any of the limits, it is
+/// aborted and the glyph is loaded without hinting.
+#[cfg(feature = "hinting")]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct HintingLimits {
+    /// Maximum number of instructions executed by a single program,
+    /// including those in called functions.
+    pub max_instructions: u32,
+    /// Maximum number of loop iterations in a single program. This counts
+    /// each repetition of a `LOOPCALL` and each backward jump.
+    pub max_loop_iterations: u32,
+    /// Maximum depth of nested function calls. Values greater than 32 are
+    /// clamped.
+    pub max_call_depth: u16,
+}
+
+#[cfg(feature = "hinting")]
+impl Default for HintingLimits {
+    fn default() -> Self {
+        Self {
+            max_instructions: 1_000_000,
+            max_loop_iterations: 100_000,
+            max_call_depth: 32,
+        }
+    }
+}
+
 /// Context for loading glyphs.
 #[derive(Clone, Default, Debug)]
 pub struct Context {
@@ -54,6 +98,10 @@ pub struct Context {
     coords: Vec<NormalizedCoord>,
     /// Storage for variation settings.
     variations: Vec<Setting<f32>>,
+    /// Storage for features applied as nominal substitutions.
+    features: Vec<Tag>,
+    /// Outlines retained between scalers, if enabled.
+    outline_cache: outline_cache::OutlineCache,
 }
 
 impl Context {
@@ -66,6 +114,39 @@ impl Context {
     pub fn new_scaler(&mut self) -> ScalerBuilder {
         ScalerBuilder::new(self)
     }
+
+    /// Sets the maximum number of outlines retained by the context, or
+    /// disables the outline cache for zero, which is the default.
+    ///
+    /// When enabled, scalers for fonts with a [key](ScalerBuilder::key)
+    /// record the outlines they load and replay them for later requests
+    /// with the same glyph identifier and variation coordinates, including
+    /// requests from other scalers built from this context. This avoids
+    /// loading an outline repeatedly, such as the layers shared by color
+    /// glyphs. The cache only holds outlines produced with the same font
+    /// and settings: building a scaler with a different key, size, skew,
+    /// hinting mode, darkening or set of nominal substitutions empties it.
+    /// The cache is also emptied when it is full.
+    pub fn set_outline_cache_limit(&mut self, max_outlines: usize) {
+        self.outline_cache.set_limit(max_outlines);
+    }
+
+    /// Returns the estimated number of bytes held by the context, including
+    /// the outline buffers, the hinting cache and the outline cache.
+    pub fn memory_usage(&self) -> usize {
+        core::mem::size_of::<Self>()
+            + self.glyf.memory_usage()
+            + self.glyf_outline.memory_usage()
+            + vec_memory_usage(&self.coords)
+            + vec_memory_usage(&self.variations)
+            + vec_memory_usage(&self.features)
+            + self.outline_cache.memory_usage()
+    }
+}
+
+/// Returns the number of bytes allocated by the vector.
+pub(crate) fn vec_memory_usage<T>(vec: &Vec<T>) -> usize {
+    vec.capacity() * core::mem::size_of::<T>()
 }
 
 #[cfg(test)]
@@ -74,6 +155,21 @@ mod tests {
     use crate::Size;
     use read_fonts::FontRef;
 
+    #[test]
+    fn memory_usage() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let mut cx = Context::new();
+        let empty_usage = cx.memory_usage();
+        assert_eq!(empty_usage, core::mem::size_of::<Context>());
+        let mut path = test::Path::default();
+        cx.new_scaler()
+            .size(Size::new(16.0))
+            .build(&font)
+            .outline(GlyphId::new(1), &mut path)
+            .unwrap();
+        assert!(cx.memory_usage() > empty_usage);
+    }
+
     #[test]
     fn vazirmatin_var() {
         let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
@@ -102,4 +198,464 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn glyf_var_matches_freetype() {
+        compare_with_freetype(
+            "vazirmatn_var",
+            font_test_data::VAZIRMATN_VAR,
+            font_test_data::VAZIRMATN_VAR_GLYPHS,
+            false,
+        );
+    }
+
+    #[test]
+    fn cff_matches_freetype() {
+        compare_with_freetype(
+            "noto_serif_display",
+            font_test_data::NOTO_SERIF_DISPLAY_TRIMMED,
+            font_test_data::NOTO_SERIF_DISPLAY_TRIMMED_GLYPHS,
+            true,
+        );
+    }
+
+    #[test]
+    fn cff2_var_matches_freetype() {
+        compare_with_freetype(
+            "cantarell_vf",
+            font_test_data::CANTARELL_VF_TRIMMED,
+            font_test_data::CANTARELL_VF_TRIMMED_GLYPHS,
+            true,
+        );
+    }
+
+    #[test]
+    fn instance_outlines() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let outlines = test::parse_glyph_outlines(font_test_data::VAZIRMATN_VAR_GLYPHS);
+        let mut cx = Context::new();
+        for expected_outline in &outlines {
+            let instance = cx
+                .new_scaler()
+                .size(Size::new(expected_outline.size))
+                .coords(&expected_outline.coords)
+                .instance(&font);
+            assert_eq!(instance.normalized_coords(), &expected_outline.coords[..]);
+            let instance = instance.clone();
+            let mut path = test::Path::default();
+            instance
+                .scaler(&mut cx)
+                .outline(expected_outline.glyph_id, &mut path)
+                .unwrap();
+            assert_eq!(path.0, expected_outline.path);
+        }
+    }
+
+    #[test]
+    fn glyf_points() {
+        use crate::testing::FontBuilder;
+        let mut builder = FontBuilder::new(1000);
+        let square = builder.add_glyph(600, &[&[(50, 0), (50, 500), (550, 500), (550, 0)]]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        let mut scaler = cx.new_scaler().build(&font);
+        let outline = scaler.glyf_outline(square).unwrap();
+        assert_eq!(
+            outline
+                .unscaled_points()
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>(),
+            [(50, 0), (50, 500), (550, 500), (550, 0)]
+        );
+        assert_eq!(outline.flags.len(), 4);
+        assert_eq!(outline.contours, [3]);
+        // Horizontal origin and advance.
+        assert_eq!(outline.phantom[0].x.to_i32(), 0);
+        assert_eq!(outline.phantom[1].x.to_i32(), 600);
+    }
+
+    #[test]
+    fn font_unit_outlines() {
+        use super::{Transform, TransformPen};
+        use test::PathElement::*;
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let glyph_id = GlyphId::new(1);
+        let mut cx = Context::new();
+        let mut expected = test::Path::default();
+        cx.new_scaler()
+            .size(Size::new(24.0))
+            .skew(0.25)
+            .build(&font)
+            .outline(glyph_id, &mut expected)
+            .unwrap();
+        let mut unscaled = test::Path::default();
+        let mut scaler = cx.new_scaler().build(&font);
+        assert_eq!(scaler.transform(), Transform::IDENTITY);
+        scaler.outline(glyph_id, &mut unscaled).unwrap();
+        let mut scaler = cx
+            .new_scaler()
+            .size(Size::new(24.0))
+            .skew(0.25)
+            .font_unit_outlines(true)
+            .build(&font);
+        let mut path = test::Path::default();
+        scaler.outline(glyph_id, &mut path).unwrap();
+        assert_eq!(path.0, unscaled.0);
+        // Applying the transform matches the outline scaled to the size.
+        path.0.clear();
+        let mut pen = TransformPen::new(&mut path, scaler.transform());
+        scaler.outline(glyph_id, &mut pen).unwrap();
+        let points = |path: &test::Path| {
+            path.0
+                .iter()
+                .flat_map(|element| match element {
+                    MoveTo(points) | LineTo(points) => points.to_vec(),
+                    QuadTo(points) => points.to_vec(),
+                    CurveTo(points) => points.to_vec(),
+                })
+                .collect::<Vec<_>>()
+        };
+        let (actual, expected) = (points(&path), points(&expected));
+        assert_eq!(actual.len(), expected.len());
+        assert!(actual
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).abs() < 0.05));
+    }
+
+    #[test]
+    fn invalid_glyph_ids() {
+        use crate::MetadataProvider;
+        use read_fonts::TableProvider;
+        for data in [
+            font_test_data::VAZIRMATN_VAR,
+            font_test_data::NOTO_SERIF_DISPLAY_TRIMMED,
+        ] {
+            let font = FontRef::new(data).unwrap();
+            let glyph_count = font.maxp().unwrap().num_glyphs();
+            let last = GlyphId::new(glyph_count - 1);
+            let invalid = GlyphId::new(glyph_count);
+            assert!(font.is_valid_glyph(last));
+            assert!(!font.is_valid_glyph(invalid));
+            let mut cx = Context::new();
+            let mut scaler = cx.new_scaler().build(&font);
+            let mut path = test::Path::default();
+            assert!(scaler.outline(GlyphId::new(1), &mut path).is_ok());
+            assert!(matches!(
+                scaler.outline(invalid, &mut path),
+                Err(super::Error::GlyphNotFound(gid)) if gid == invalid
+            ));
+            let metrics = font.glyph_metrics(Size::unscaled(), Default::default());
+            assert!(metrics.advance_width(invalid).is_none());
+            assert!(metrics.bounds(invalid).is_none());
+        }
+    }
+
+    #[test]
+    fn stem_darkening() {
+        use super::StemDarkening;
+        let font = FontRef::new(font_test_data::NOTO_SERIF_DISPLAY_TRIMMED).unwrap();
+        let glyph_id = GlyphId::new(1);
+        let mut cx = Context::new();
+        let mut outline = |size: Size, darkening: Option<StemDarkening>| {
+            let mut path = test::Path::default();
+            cx.new_scaler()
+                .size(size)
+                .stem_darkening(darkening)
+                .build(&font)
+                .outline(glyph_id, &mut path)
+                .unwrap();
+            path.0
+        };
+        let darkening = Some(StemDarkening::default());
+        assert_ne!(
+            outline(Size::new(12.0), darkening),
+            outline(Size::new(12.0), None)
+        );
+        // Unscaled outlines are left alone.
+        assert_eq!(
+            outline(Size::unscaled(), darkening),
+            outline(Size::unscaled(), None)
+        );
+    }
+
+    #[test]
+    fn cyclic_composite() {
+        use super::{OutlineKind, Warning};
+        use crate::testing::{FontBuilder, SQUARE};
+        let mut builder = FontBuilder::new(1000);
+        let square = builder.add_glyph(500, SQUARE);
+        // Glyphs 2 and 3 refer to each other and both contain the square.
+        builder.add_composite_glyph(500, &[(GlyphId::new(3), 0, 0), (square, 0, 0)]);
+        builder.add_composite_glyph(500, &[(GlyphId::new(2), 0, 0), (square, 100, 0)]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        let mut scaler = cx.new_scaler().build(&font);
+        let mut path = test::Path::default();
+        let warning = scaler
+            .outline(GlyphId::new(2), &mut path)
+            .unwrap()
+            .warning();
+        assert_eq!(
+            warning,
+            Some(Warning::CyclicComponent(GlyphId::new(3), GlyphId::new(2)))
+        );
+        let mut expected = test::Path::default();
+        let mut scaler = cx.new_scaler().build(&font);
+        scaler.outline(square, &mut expected).unwrap();
+        // The square from each composite is kept.
+        assert_eq!(path.0.len(), expected.0.len() * 2);
+        // Simple glyphs produce no warnings.
+        assert_eq!(
+            scaler.outline(square, &mut expected).unwrap(),
+            OutlineKind::Drawn(None)
+        );
+    }
+
+    #[test]
+    fn empty_outline() {
+        use super::OutlineKind;
+        use crate::testing::FontBuilder;
+        let mut builder = FontBuilder::new(1000);
+        let space = builder.add_glyph(250, &[]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        let mut scaler = cx.new_scaler().build(&font);
+        let mut path = test::Path::default();
+        assert_eq!(
+            scaler.outline(space, &mut path).unwrap(),
+            OutlineKind::Empty { has_color: false }
+        );
+        assert!(path.0.is_empty());
+    }
+
+    #[test]
+    fn use_my_metrics() {
+        use crate::testing::FontBuilder;
+        use read_fonts::tables::glyf::CompositeGlyphFlags;
+        let mut builder = FontBuilder::new(1000);
+        let square = builder.add_glyph(600, &[&[(50, 0), (50, 500), (550, 500), (550, 0)]]);
+        let space = builder.add_glyph(250, &[]);
+        let plain = builder.add_composite_glyph(900, &[(square, 100, 0)]);
+        let from_square = builder.add_composite_glyph(900, &[(square, 100, 0)]);
+        builder.component_flags(from_square, 0, CompositeGlyphFlags::USE_MY_METRICS);
+        // Empty components also provide metrics.
+        let from_space = builder.add_composite_glyph(900, &[(square, 100, 0), (space, 0, 0)]);
+        builder.component_flags(from_space, 1, CompositeGlyphFlags::USE_MY_METRICS);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        let mut scaler = cx.new_scaler().build(&font);
+        let mut advance = |glyph_id| {
+            let outline = scaler.glyf_outline(glyph_id).unwrap();
+            (outline.phantom[1].x - outline.phantom[0].x).to_i32()
+        };
+        assert_eq!(advance(plain), 900);
+        assert_eq!(advance(from_square), 600);
+        assert_eq!(advance(from_space), 250);
+    }
+
+    #[cfg(feature = "hinting")]
+    #[test]
+    fn round_xy_to_grid() {
+        use super::Hinting;
+        use crate::testing::{FontBuilder, SQUARE};
+        use read_fonts::{tables::glyf::CompositeGlyphFlags, types::F26Dot6};
+        let mut builder = FontBuilder::new(1000);
+        let square = builder.add_glyph(500, SQUARE);
+        let composite = builder.add_composite_glyph(500, &[(square, 130, 270)]);
+        builder.component_flags(composite, 0, CompositeGlyphFlags::ROUND_XY_TO_GRID);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        // At 10px, the component is offset by 1.3px horizontally and 2.7px
+        // vertically.
+        let mut offset = |hinting| {
+            let mut scaler = cx
+                .new_scaler()
+                .size(Size::new(10.0))
+                .hint(Some(hinting))
+                .build(&font);
+            scaler.glyf_outline(composite).unwrap().points[0]
+        };
+        let full = offset(Hinting::Full);
+        assert_eq!(full.x, F26Dot6::from_i32(1));
+        assert_eq!(full.y, F26Dot6::from_i32(3));
+        // Only the vertical offset is rounded for the light modes.
+        let light = offset(Hinting::Light);
+        assert_ne!(light.x, light.x.round());
+        assert_eq!(light.y, F26Dot6::from_i32(3));
+    }
+
+    #[cfg(feature = "hinting")]
+    #[test]
+    fn composite_instructions() {
+        use super::Hinting;
+        use crate::testing::{FontBuilder, SQUARE};
+        use read_fonts::types::F26Dot6;
+        let mut builder = FontBuilder::new(1000);
+        let square = builder.add_glyph(500, SQUARE);
+        let composite = builder.add_composite_glyph(1600, &[(square, 130, 0), (square, 1130, 0)]);
+        // SVTCA[x], PUSHB[0] 4, MDAP[rnd]: round the first point of the
+        // second component horizontally.
+        builder.glyph_instructions(composite, &[0x01, 0xB0, 4, 0x2F]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        // At 10px, the components are offset by 1.3px and 11.3px.
+        let mut points = |hinting| {
+            let mut scaler = cx
+                .new_scaler()
+                .size(Size::new(10.0))
+                .hint(hinting)
+                .build(&font);
+            let outline = scaler.glyf_outline(composite).unwrap();
+            (outline.points[0].x, outline.points[4].x)
+        };
+        let (first, second) = points(None);
+        assert_ne!(first, first.round());
+        assert_ne!(second, second.round());
+        // The program runs on the placed components, so the point is on the
+        // grid and the other points keep their positions.
+        let (hinted_first, hinted_second) = points(Some(Hinting::Full));
+        assert_eq!(hinted_first, first);
+        assert_eq!(hinted_second, F26Dot6::from_i32(11));
+    }
+
+    #[cfg(feature = "hinting")]
+    #[test]
+    fn hinted_golden_points() {
+        use super::Hinting;
+        use crate::testing::FontBuilder;
+        use read_fonts::types::F26Dot6;
+        let mut builder = FontBuilder::new(1000);
+        let square = builder.add_glyph(600, &[&[(50, 0), (50, 530), (550, 530), (550, 0)]]);
+        // SVTCA[y], PUSHB[1] 1 2, MDAP[rnd], MDAP[rnd]: round the top of
+        // the square to the grid.
+        builder.glyph_instructions(square, &[0x00, 0xB1, 1, 2, 0x2F, 0x2F]);
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        // Unhinted and hinted heights of the top, in pixels.
+        for (ppem, unhinted, hinted) in [(11.0, 5.83, 6), (13.0, 6.89, 7), (20.0, 10.6, 11)] {
+            let mut load = |hinting| {
+                let mut scaler = cx
+                    .new_scaler()
+                    .size(Size::new(ppem))
+                    .hint(hinting)
+                    .build(&font);
+                scaler.glyf_outline(square).unwrap().points
+            };
+            let points = load(None);
+            let hinted_points = load(Some(Hinting::Full));
+            assert!((points[1].y.to_f32() - unhinted).abs() < 0.02);
+            for i in [1, 2] {
+                assert_eq!(hinted_points[i].y, F26Dot6::from_i32(hinted));
+                assert_eq!(hinted_points[i].x, points[i].x);
+            }
+            assert_eq!(hinted_points[0], points[0]);
+            assert_eq!(hinted_points[3], points[3]);
+        }
+    }
+
+    /// Compares hinted outlines of the Microsoft core fonts with those
+    /// produced by FreeType.
+    ///
+    /// The fonts can't be distributed with the crate, so this requires
+    /// `FELLO_CORE_FONTS_DIR` to name a directory containing each font,
+    /// such as `arial.ttf`, next to the outlines extracted by FreeType in
+    /// the format of the other test data, such as `arial.glyphs`. The
+    /// outlines must be loaded by the v35 interpreter with the default load
+    /// flags, which corresponds to [`Hinting::Full`](super::Hinting::Full).
+    #[cfg(feature = "hinting")]
+    #[test]
+    #[ignore = "requires FELLO_CORE_FONTS_DIR"]
+    fn core_fonts_hinted() {
+        use super::Hinting;
+        let dir = std::env::var("FELLO_CORE_FONTS_DIR").expect("FELLO_CORE_FONTS_DIR is not set");
+        let mut font_count = 0;
+        let mut cx = Context::new();
+        let mut path = test::Path::default();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let glyphs_path = entry.unwrap().path();
+            if glyphs_path.extension() != Some("glyphs".as_ref()) {
+                continue;
+            }
+            let font_data = std::fs::read(glyphs_path.with_extension("ttf")).unwrap();
+            let font = FontRef::new(&font_data).unwrap();
+            let expected = std::fs::read_to_string(&glyphs_path).unwrap();
+            for expected_outline in test::parse_glyph_outlines(&expected) {
+                path.0.clear();
+                let mut scaler = cx
+                    .new_scaler()
+                    .size(Size::new(expected_outline.size))
+                    .hint(Some(Hinting::Full))
+                    .build(&font);
+                scaler
+                    .outline(expected_outline.glyph_id, &mut path)
+                    .unwrap();
+                if path.0 != expected_outline.path {
+                    panic!(
+                        "mismatch in glyph path for {} id {} (size: {}): path: {:?} expected_path: {:?}",
+                        glyphs_path.display(),
+                        expected_outline.glyph_id,
+                        expected_outline.size,
+                        &path.0,
+                        &expected_outline.path
+                    );
+                }
+            }
+            font_count += 1;
+        }
+        assert!(font_count > 0, "no fonts found");
+    }
+
+    /// Renders every outline listed in a FreeType extraction from
+    /// `font_test_data` through the public scaler and compares text
+    /// snapshots of both.
+    ///
+    /// The FreeType extractions serve as the expected output so there are
+    /// no separately checked in snapshot files.
+    fn compare_with_freetype(name: &str, font_data: &[u8], expected_outlines: &str, is_cff: bool) {
+        let font = FontRef::new(font_data).unwrap();
+        let mut outlines = test::parse_glyph_outlines(expected_outlines);
+        // FreeType applies CFF2 variations differently for unscaled outlines.
+        if is_cff {
+            outlines.retain(|outline| outline.size != 0.0 || outline.coords.is_empty());
+        }
+        let expected = test::freetype_snapshot(&outlines);
+        let mut actual = String::new();
+        let mut cx = Context::new();
+        for outline in &outlines {
+            let mut scaler = cx
+                .new_scaler()
+                .size(Size::new(outline.size))
+                .coords(&outline.coords)
+                .build(&font);
+            let path = if is_cff {
+                let mut path = read_fonts::scaler_test::Path {
+                    elements: vec![],
+                    is_cff: true,
+                };
+                scaler.outline(outline.glyph_id, &mut path).unwrap();
+                path.elements.iter().map(test::PathElement::from).collect()
+            } else {
+                let mut path = test::Path::default();
+                scaler.outline(outline.glyph_id, &mut path).unwrap();
+                path.0
+            };
+            test::write_snapshot(
+                &mut actual,
+                outline.glyph_id,
+                outline.size,
+                &outline.coords,
+                None,
+                &path,
+            );
+        }
+        test::assert_snapshot_eq(name, &expected, &actual);
+    }
 }
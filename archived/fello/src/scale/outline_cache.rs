@@ -0,0 +1,331 @@
+//! Cache of outlines shared by the scalers built from a context.
+//!
+//! Renderers load the same glyphs repeatedly, such as the layers of color
+//! glyphs that share components (emoji with skin tone modifiers reuse the
+//! same base layers) or the glyphs of a paragraph drawn in several passes.
+//! When enabled with [`Context::set_outline_cache_limit`](super::Context::set_outline_cache_limit),
+//! the path commands of each loaded outline are recorded and replayed for
+//! later requests with the same glyph identifier and variation coordinates.
+
+use super::{NormalizedCoord, OutlineKind, Pen, StemDarkening};
+use crate::FontKey;
+use alloc::{collections::BTreeMap, vec::Vec};
+use read_fonts::types::{GlyphId, Tag};
+
+#[cfg(feature = "hinting")]
+use super::{Hinting, HintingLimits};
+
+/// Settings that affect the outlines produced by a scaler, other than the
+/// variation coordinates.
+///
+/// Outlines in the cache were all produced with the same settings. Building
+/// a scaler with different settings starts a new generation of the cache.
+#[derive(Clone, PartialEq, Debug)]
+pub(super) struct CacheConfig {
+    pub key: FontKey,
+    pub size: f32,
+    pub skew: f32,
+    #[cfg(feature = "hinting")]
+    pub hint: Option<Hinting>,
+    #[cfg(feature = "hinting")]
+    pub hint_limits: HintingLimits,
+    pub darkening: Option<StemDarkening>,
+    pub features: Vec<Tag>,
+}
+
+/// Path command recorded from an outline.
+#[derive(Copy, Clone, Debug)]
+enum Command {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    QuadTo([f32; 4]),
+    CurveTo([f32; 6]),
+    Close,
+}
+
+/// Outline of a glyph at a set of variation coordinates.
+#[derive(Clone, Debug)]
+struct CachedOutline {
+    coords: Vec<NormalizedCoord>,
+    commands: Vec<Command>,
+    kind: OutlineKind,
+}
+
+#[derive(Clone, Default, Debug)]
+pub(super) struct OutlineCache {
+    /// Maximum number of outlines, or zero if the cache is disabled.
+    limit: usize,
+    /// Settings of the current generation.
+    config: Option<CacheConfig>,
+    /// Outlines keyed by glyph identifier.
+    outlines: BTreeMap<u16, Vec<CachedOutline>>,
+    len: usize,
+}
+
+impl OutlineCache {
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        if self.len > limit {
+            self.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.limit != 0
+    }
+
+    /// Returns the number of cached outlines.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Prepares the cache for a scaler with the given settings, discarding
+    /// the outlines produced with other settings.
+    ///
+    /// Returns `None` if the cache is disabled.
+    pub fn begin(&mut self, config: &CacheConfig) -> Option<&mut Self> {
+        if !self.is_enabled() {
+            return None;
+        }
+        if self.config.as_ref() != Some(config) {
+            self.clear();
+            self.config = Some(config.clone());
+        }
+        Some(self)
+    }
+
+    /// Emits the cached outline for the glyph to the pen, returning `None`
+    /// if it hasn't been loaded at the given coordinates.
+    pub fn replay(
+        &self,
+        glyph_id: GlyphId,
+        coords: &[NormalizedCoord],
+        pen: &mut impl Pen,
+    ) -> Option<OutlineKind> {
+        let outline = self
+            .outlines
+            .get(&glyph_id.to_u16())?
+            .iter()
+            .find(|outline| outline.coords == coords)?;
+        for command in &outline.commands {
+            match *command {
+                Command::MoveTo(x, y) => pen.move_to(x, y),
+                Command::LineTo(x, y) => pen.line_to(x, y),
+                Command::QuadTo([cx0, cy0, x, y]) => pen.quad_to(cx0, cy0, x, y),
+                Command::CurveTo([cx0, cy0, cx1, cy1, x, y]) => {
+                    pen.curve_to(cx0, cy0, cx1, cy1, x, y)
+                }
+                Command::Close => pen.close(),
+            }
+        }
+        Some(outline.kind)
+    }
+
+    /// Adds the outline recorded by the pen. The cache is emptied first if
+    /// it is full.
+    pub fn insert<P>(
+        &mut self,
+        glyph_id: GlyphId,
+        coords: &[NormalizedCoord],
+        pen: RecordingPen<'_, P>,
+        kind: OutlineKind,
+    ) {
+        let Some(commands) = pen.commands else {
+            return;
+        };
+        if self.len >= self.limit {
+            self.clear();
+        }
+        self.outlines
+            .entry(glyph_id.to_u16())
+            .or_default()
+            .push(CachedOutline {
+                coords: coords.to_vec(),
+                commands,
+                kind,
+            });
+        self.len += 1;
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        let entry_size = core::mem::size_of::<(u16, Vec<CachedOutline>)>();
+        self.outlines
+            .values()
+            .map(|outlines| {
+                entry_size
+                    + super::vec_memory_usage(outlines)
+                    + outlines
+                        .iter()
+                        .map(|outline| {
+                            super::vec_memory_usage(&outline.coords)
+                                + super::vec_memory_usage(&outline.commands)
+                        })
+                        .sum::<usize>()
+            })
+            .sum::<usize>()
+            + self
+                .config
+                .as_ref()
+                .map_or(0, |config| super::vec_memory_usage(&config.features))
+    }
+
+    fn clear(&mut self) {
+        self.outlines.clear();
+        self.len = 0;
+    }
+}
+
+/// Pen that passes path commands to the inner pen, recording them if
+/// the outline will be cached.
+pub(super) struct RecordingPen<'p, P> {
+    inner: &'p mut P,
+    commands: Option<Vec<Command>>,
+}
+
+impl<'p, P> RecordingPen<'p, P> {
+    pub fn new(inner: &'p mut P, record: bool) -> Self {
+        Self {
+            inner,
+            commands: record.then(Vec::new),
+        }
+    }
+
+    fn push(&mut self, command: Command) {
+        if let Some(commands) = &mut self.commands {
+            commands.push(command);
+        }
+    }
+}
+
+impl<P: Pen> Pen for RecordingPen<'_, P> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.push(Command::MoveTo(x, y));
+        self.inner.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.push(Command::LineTo(x, y));
+        self.inner.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        self.push(Command::QuadTo([cx0, cy0, x, y]));
+        self.inner.quad_to(cx0, cy0, x, y);
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        self.push(Command::CurveTo([cx0, cy0, cx1, cy1, x, y]));
+        self.inner.curve_to(cx0, cy0, cx1, cy1, x, y);
+    }
+
+    fn close(&mut self) {
+        self.push(Command::Close);
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        scale::{test, Context},
+        testing::FontBuilder,
+        FontKey, GlyphId, Size,
+    };
+    use read_fonts::{types::F2Dot14, FontRef};
+
+    const KEY: Option<FontKey> = Some(FontKey {
+        data_id: 1,
+        index: 0,
+    });
+
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::with_squares(1);
+        builder.add_glyph(500, &[&[(100, 0), (100, 700), (400, 700), (400, 0)]]);
+        builder.build()
+    }
+
+    #[test]
+    fn replay_outlines() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        let mut expected = test::Path::default();
+        cx.new_scaler()
+            .size(Size::new(16.0))
+            .build(&font)
+            .outline(GlyphId::new(1), &mut expected)
+            .unwrap();
+        assert_eq!(cx.outline_cache.len(), 0);
+        cx.set_outline_cache_limit(16);
+        for _ in 0..2 {
+            let mut path = test::Path::default();
+            let kind = cx
+                .new_scaler()
+                .key(KEY)
+                .size(Size::new(16.0))
+                .build(&font)
+                .outline(GlyphId::new(1), &mut path)
+                .unwrap();
+            assert_eq!(kind.warning(), None);
+            assert_eq!(path.0, expected.0);
+            assert_eq!(cx.outline_cache.len(), 1);
+        }
+        // Instances share the cache with scalers built directly.
+        let instance = cx
+            .new_scaler()
+            .key(KEY)
+            .size(Size::new(16.0))
+            .instance(&font);
+        let mut path = test::Path::default();
+        instance
+            .scaler(&mut cx)
+            .outline(GlyphId::new(1), &mut path)
+            .unwrap();
+        assert_eq!(path.0, expected.0);
+        assert_eq!(cx.outline_cache.len(), 1);
+        // Outlines are keyed by glyph and coordinates.
+        let coords = [F2Dot14::from_f32(0.5)];
+        let mut scaler = cx
+            .new_scaler()
+            .key(KEY)
+            .size(Size::new(16.0))
+            .coords(&coords)
+            .build(&font);
+        scaler
+            .outline(GlyphId::new(1), &mut test::Path::default())
+            .unwrap();
+        scaler
+            .outline(GlyphId::new(2), &mut test::Path::default())
+            .unwrap();
+        assert_eq!(cx.outline_cache.len(), 3);
+    }
+
+    #[test]
+    fn generations() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        cx.set_outline_cache_limit(2);
+        let load = |cx: &mut Context, key, size, glyph_id| {
+            cx.new_scaler()
+                .key(key)
+                .size(Size::new(size))
+                .build(&font)
+                .outline(GlyphId::new(glyph_id), &mut test::Path::default())
+                .unwrap();
+            cx.outline_cache.len()
+        };
+        assert_eq!(load(&mut cx, KEY, 16.0, 1), 1);
+        assert_eq!(load(&mut cx, KEY, 16.0, 2), 2);
+        // A full cache is emptied before adding an outline.
+        assert_eq!(load(&mut cx, KEY, 16.0, 0), 1);
+        // Other settings start a new generation.
+        assert_eq!(load(&mut cx, KEY, 24.0, 1), 1);
+        assert_eq!(load(&mut cx, KEY, 24.0, 1), 1);
+        // Fonts without a key are never cached.
+        assert_eq!(load(&mut cx, None, 24.0, 2), 1);
+        cx.set_outline_cache_limit(0);
+        assert_eq!(cx.outline_cache.len(), 0);
+        assert_eq!(load(&mut cx, KEY, 24.0, 1), 0);
+    }
+}
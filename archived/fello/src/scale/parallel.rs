@@ -0,0 +1,99 @@
+//! Loading outlines for many glyphs on multiple threads.
+//!
+//! This is intended for tools that render large numbers of glyphs up front,
+//! such as when baking glyph atlases.
+
+use super::{Context, FontInstance, OutlineKind, Pen, Result};
+use crate::GlyphId;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of glyphs that a thread claims at a time.
+///
+/// Claiming small batches balances the load when some glyphs are much more
+/// expensive to load than others, as with large composites or hinted
+/// glyphs, while keeping contention on the shared counter low.
+const BATCH_SIZE: usize = 32;
+
+/// Loads the outlines of the given glyphs from the instance on all
+/// available threads.
+///
+/// Each thread uses its own [`Context`] and draws each outline into a new
+/// pen of type `P`. The sink is called on the loading thread with the
+/// position of the glyph in `glyph_ids`, the glyph identifier and the
+/// result, so results arrive in no particular order.
+///
+/// The number of threads is determined by
+/// [`std::thread::available_parallelism`] and limited so that each thread
+/// has at least one batch of glyphs to load.
+pub fn outline_glyphs<P, F>(instance: &FontInstance, glyph_ids: &[GlyphId], sink: F)
+where
+    P: Pen + Default,
+    F: Fn(usize, GlyphId, Result<(P, OutlineKind)>) + Sync,
+{
+    let thread_count = std::thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .min(glyph_ids.len().div_ceil(BATCH_SIZE));
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut context = Context::new();
+        let mut scaler = instance.scaler(&mut context);
+        loop {
+            let start = next.fetch_add(BATCH_SIZE, Ordering::Relaxed);
+            if start >= glyph_ids.len() {
+                break;
+            }
+            let end = (start + BATCH_SIZE).min(glyph_ids.len());
+            for (index, &glyph_id) in glyph_ids.iter().enumerate().take(end).skip(start) {
+                let mut pen = P::default();
+                let result = scaler.outline(glyph_id, &mut pen).map(|kind| (pen, kind));
+                sink(index, glyph_id, result);
+            }
+        }
+    };
+    if thread_count <= 1 {
+        work();
+        return;
+    }
+    std::thread::scope(|scope| {
+        // The current thread does its share of the work too.
+        for _ in 1..thread_count {
+            scope.spawn(work);
+        }
+        work();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{scale::test, Size};
+    use read_fonts::{FontRef, TableProvider};
+    use std::sync::Mutex;
+
+    #[test]
+    fn matches_sequential() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let glyph_count = font.maxp().unwrap().num_glyphs();
+        // Load each glyph twice to exercise more than one batch.
+        let glyph_ids: Vec<_> = (0..glyph_count)
+            .chain(0..glyph_count)
+            .map(GlyphId::new)
+            .collect();
+        let mut cx = Context::new();
+        let instance = cx.new_scaler().size(Size::new(24.0)).instance(&font);
+        let results = Mutex::new(vec![None; glyph_ids.len()]);
+        outline_glyphs(&instance, &glyph_ids, |index, glyph_id, result| {
+            let (path, _): (test::Path, _) = result.unwrap();
+            let mut results = results.lock().unwrap();
+            assert!(results[index].is_none());
+            results[index] = Some((glyph_id, path.0));
+        });
+        let results = results.into_inner().unwrap();
+        let mut scaler = instance.scaler(&mut cx);
+        for (&glyph_id, result) in glyph_ids.iter().zip(results) {
+            let mut path = test::Path::default();
+            scaler.outline(glyph_id, &mut path).unwrap();
+            assert_eq!(result, Some((glyph_id, path.0)));
+        }
+    }
+}
@@ -1,15 +1,26 @@
 use super::{
     cff::{Scaler as PostScriptScaler, ScalerSubfont},
-    glyf, Context, Error, NormalizedCoord, Pen, Result,
+    color::ColorGlyphs,
+    darken::{self, DarkeningPen, DEFAULT_STEM_WIDTH},
+    glyf,
+    outline_cache::{CacheConfig, OutlineCache, RecordingPen},
+    substitute::NominalSubstitutions,
+    Context, Error, FontInstance, NormalizedCoord, Pen, Result, StemDarkening, Transform, Warning,
+};
+use crate::{
+    meta::{
+        metrics::{glyph_count, units_per_em, BoundingBox},
+        variations::VariationSetting,
+    },
+    FontKey, Size,
 };
-use crate::{meta::variations::VariationSetting, FontKey, Size};
 
 #[cfg(feature = "hinting")]
-use super::Hinting;
+use super::{Hinting, HintingLimits};
 
 use core::borrow::Borrow;
 use read_fonts::{
-    types::{Fixed, GlyphId},
+    types::{Fixed, GlyphId, Tag},
     TableProvider,
 };
 
@@ -23,6 +34,11 @@ pub struct ScalerBuilder<'a> {
     size: Size,
     #[cfg(feature = "hinting")]
     hint: Option<Hinting>,
+    #[cfg(feature = "hinting")]
+    hint_limits: HintingLimits,
+    darkening: Option<StemDarkening>,
+    skew: f32,
+    font_units: bool,
 }
 
 impl<'a> ScalerBuilder<'a> {
@@ -30,12 +46,18 @@ impl<'a> ScalerBuilder<'a> {
     pub fn new(context: &'a mut Context) -> Self {
         context.coords.clear();
         context.variations.clear();
+        context.features.clear();
         Self {
             context,
             cache_key: None,
             size: Size::unscaled(),
             #[cfg(feature = "hinting")]
             hint: None,
+            #[cfg(feature = "hinting")]
+            hint_limits: HintingLimits::default(),
+            darkening: None,
+            skew: 0.0,
+            font_units: false,
         }
     }
 
@@ -64,6 +86,51 @@ impl<'a> ScalerBuilder<'a> {
         self
     }
 
+    /// Sets the execution budgets for hinting programs.
+    ///
+    /// Glyphs with programs that exceed these limits are loaded without
+    /// hinting.
+    #[cfg(feature = "hinting")]
+    pub fn hint_limits(mut self, limits: HintingLimits) -> Self {
+        self.hint_limits = limits;
+        self
+    }
+
+    /// Sets the parameters for darkening stems at small sizes.
+    ///
+    /// Passing `None` will disable darkening, which is the default.
+    /// Unscaled outlines are never darkened.
+    pub fn stem_darkening(mut self, darkening: Option<StemDarkening>) -> Self {
+        self.darkening = darkening;
+        self
+    }
+
+    /// Sets the horizontal skew applied to outlines, which can be used to
+    /// synthesize oblique styles.
+    ///
+    /// The skew is a factor of the vertical coordinate as described for
+    /// [`Transform::skew`] and is applied after hinting and darkening. The
+    /// default value is zero.
+    pub fn skew(mut self, skew: f32) -> Self {
+        self.skew = skew;
+        self
+    }
+
+    /// Sets whether outlines are emitted in font units regardless of the
+    /// requested size.
+    ///
+    /// The requested size and skew are then reported as a [`Transform`] by
+    /// [`Scaler::transform`] rather than applied to the outlines. This
+    /// allows renderers to cache a single outline per glyph and apply the
+    /// size when drawing, such as on the GPU. Hinting and stem darkening
+    /// depend on the size of the outline so they are disabled in this mode.
+    ///
+    /// The default value is false.
+    pub fn font_unit_outlines(mut self, enabled: bool) -> Self {
+        self.font_units = enabled;
+        self
+    }
+
     /// Specifies a variation with a set of normalized coordinates.
     ///
     /// This will clear any variations specified with the variations method.
@@ -100,12 +167,65 @@ impl<'a> ScalerBuilder<'a> {
         self
     }
 
+    /// Sets the `GSUB` features that are applied to glyph identifiers
+    /// before loading their outlines.
+    ///
+    /// The scaler doesn't shape text, so only lookups that substitute a
+    /// single glyph without context are applied. This covers the common
+    /// uses of features such as stylistic sets (`ss01`-`ss20`), oldstyle
+    /// figures (`onum`), small capitals (`smcp`) and access all
+    /// alternates (`aalt`), for which the first alternate is selected.
+    /// It enables previews of these glyphs in font menus and glyph
+    /// pickers. Other lookups referenced by the features are ignored.
+    pub fn nominal_substitutions<I>(self, features: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Tag>,
+    {
+        self.context.features.clear();
+        self.context
+            .features
+            .extend(features.into_iter().map(|tag| *tag.borrow()));
+        self
+    }
+
+    /// Builds a font instance using the currently configured settings
+    /// and the specified font.
+    ///
+    /// The instance does not borrow the context and can be used to create
+    /// any number of scalers without resolving the settings again.
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "debug", skip_all))]
+    pub fn instance<'f>(mut self, font: &impl TableProvider<'f>) -> FontInstance<'f> {
+        self.resolve_variations(font);
+        let output = self.resolve_output(font);
+        FontInstance::new(
+            font,
+            self.cache_key,
+            output,
+            self.cache_config(&output),
+            &self.context.coords,
+            #[cfg(feature = "hinting")]
+            self.hint,
+            #[cfg(feature = "hinting")]
+            self.hint_limits,
+            self.darkening,
+            NominalSubstitutions::new(font, &self.context.features),
+        )
+    }
+
     /// Builds a scaler using the currently configured settings
     /// and the specified font.
+    #[cfg_attr(feature = "trace", tracing::instrument(level = "debug", skip_all))]
     pub fn build(mut self, font: &impl TableProvider<'a>) -> Scaler<'a> {
         self.resolve_variations(font);
+        let output = self.resolve_output(font);
+        let cache_config = if self.context.outline_cache.is_enabled() {
+            self.cache_config(&output)
+        } else {
+            None
+        };
         let coords = &self.context.coords[..];
-        let size = self.size.ppem().unwrap_or_default();
+        let size = output.size;
         let outlines = if let Ok(glyf) = glyf::Scaler::new(
             &mut self.context.glyf,
             font,
@@ -113,6 +233,8 @@ impl<'a> ScalerBuilder<'a> {
             size,
             #[cfg(feature = "hinting")]
             self.hint,
+            #[cfg(feature = "hinting")]
+            self.hint_limits,
             coords,
         ) {
             Some(Outlines::TrueType(glyf, &mut self.context.glyf_outline))
@@ -125,16 +247,68 @@ impl<'a> ScalerBuilder<'a> {
                 })
                 .map(|(scaler, subfont)| Outlines::PostScript(scaler, subfont))
         };
+        let cache = match &cache_config {
+            Some(config) => self.context.outline_cache.begin(config),
+            None => None,
+        };
         Scaler {
             size,
+            skew: output.skew,
+            transform: output.transform,
             coords,
             #[cfg(feature = "hinting")]
             hint: self.hint,
+            darkening: self.darkening,
             outlines,
+            color: ColorGlyphs::new(font),
+            glyph_count: glyph_count(font),
+            substitutions: NominalSubstitutions::new(font, &self.context.features),
+            cache,
+        }
+    }
+
+    /// Returns the settings that identify the outlines of the scaler in
+    /// the outline cache, or `None` if the font has no key.
+    fn cache_config(&self, output: &Output) -> Option<CacheConfig> {
+        Some(CacheConfig {
+            key: self.cache_key?,
+            size: output.size,
+            skew: output.skew,
+            #[cfg(feature = "hinting")]
+            hint: self.hint,
+            #[cfg(feature = "hinting")]
+            hint_limits: self.hint_limits,
+            darkening: self.darkening,
+            features: self.context.features.clone(),
+        })
+    }
+
+    /// Returns the size at which outlines are loaded along with the
+    /// transforms for the requested size and skew.
+    fn resolve_output<'f>(&self, font: &impl TableProvider<'f>) -> Output {
+        let upem = units_per_em(font);
+        let size = self.size.ppem_for(upem).unwrap_or_default();
+        if self.font_units {
+            let scale = if size == 0.0 {
+                1.0
+            } else {
+                size / upem.max(1) as f32
+            };
+            Output {
+                size: 0.0,
+                skew: 0.0,
+                transform: Transform::skew(self.skew).then(&Transform::scale(scale)),
+            }
+        } else {
+            Output {
+                size,
+                skew: self.skew,
+                transform: Transform::IDENTITY,
+            }
         }
     }
 
-    fn resolve_variations(&mut self, font: &impl TableProvider<'a>) {
+    fn resolve_variations<'f>(&mut self, font: &impl TableProvider<'f>) {
         if self.context.variations.is_empty() {
             return; // nop
         }
@@ -174,16 +348,35 @@ impl<'a> ScalerBuilder<'a> {
     }
 }
 
+/// Size and transforms resolved from the settings of a builder.
+#[derive(Copy, Clone)]
+pub(super) struct Output {
+    /// Size in pixels per em at which outlines are loaded, or zero for
+    /// font units.
+    pub size: f32,
+    /// Skew applied to loaded outlines.
+    pub skew: f32,
+    /// Transform that maps emitted outlines to the requested size and skew.
+    pub transform: Transform,
+}
+
 /// Glyph scaler for a specific font and configuration.
 ///
 /// See the [module level documentation](crate::scale#getting-an-outline)
 /// for more detail.
 pub struct Scaler<'a> {
-    size: f32,
-    coords: &'a [NormalizedCoord],
+    pub(super) size: f32,
+    pub(super) skew: f32,
+    pub(super) transform: Transform,
+    pub(super) coords: &'a [NormalizedCoord],
     #[cfg(feature = "hinting")]
-    hint: Option<Hinting>,
-    outlines: Option<Outlines<'a>>,
+    pub(super) hint: Option<Hinting>,
+    pub(super) darkening: Option<StemDarkening>,
+    pub(super) outlines: Option<Outlines<'a>>,
+    pub(super) color: ColorGlyphs<'a>,
+    pub(super) glyph_count: u16,
+    pub(super) substitutions: NominalSubstitutions<'a>,
+    pub(super) cache: Option<&'a mut OutlineCache>,
 }
 
 impl<'a> Scaler<'a> {
@@ -197,26 +390,201 @@ impl<'a> Scaler<'a> {
         self.outlines.is_some()
     }
 
+    /// Returns the clip box of a color glyph from the `COLR` table, scaled
+    /// to the size of the scaler with variations applied.
+    ///
+    /// Renderers can use the clip box to size the surface for a color glyph
+    /// without traversing its paint graph. Returns `None` if the font has no
+    /// clip box for the glyph.
+    pub fn color_clip_box(&self, glyph_id: GlyphId) -> Option<BoundingBox> {
+        self.color.clip_box(glyph_id, self.size, self.coords)
+    }
+
+    /// Returns the transform that maps the emitted outlines to the
+    /// requested size and skew.
+    ///
+    /// This is the identity transform unless
+    /// [font unit outlines](ScalerBuilder::font_unit_outlines) were
+    /// requested.
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Returns true if outlines are hinted by the scaler.
+    ///
+    /// This can be passed to [`GlyphMetrics::with_advance_rounding`](crate::meta::metrics::GlyphMetrics::with_advance_rounding)
+    /// to match the advances to the outlines.
+    pub fn is_hinted(&self) -> bool {
+        #[cfg(feature = "hinting")]
+        {
+            self.hint.is_some() && self.size != 0.0
+        }
+        #[cfg(not(feature = "hinting"))]
+        false
+    }
+
+    /// Returns the glyph identifier that is outlined in place of the given
+    /// one after applying the
+    /// [nominal substitutions](ScalerBuilder::nominal_substitutions).
+    ///
+    /// This can be used to look up the metrics of the substituted glyph.
+    pub fn substitute(&self, glyph_id: GlyphId) -> GlyphId {
+        self.substitutions
+            .apply(glyph_id)
+            .filter(|substitute| substitute.to_u16() < self.glyph_count)
+            .unwrap_or(glyph_id)
+    }
+
     /// Loads a simple outline for the specified glyph identifier and invokes the functions
     /// in the given pen for the sequence of path commands that define the outline.
-    pub fn outline(&mut self, glyph_id: GlyphId, pen: &mut impl Pen) -> Result<()> {
-        if let Some(outlines) = &mut self.outlines {
+    ///
+    /// Returns [`OutlineKind::Drawn`] with a warning if the outline was
+    /// produced with some parts omitted, such as the components of a
+    /// composite glyph that refer to themselves. Returns
+    /// [`OutlineKind::Empty`] if the glyph has no outline, noting whether it
+    /// has a color representation that should be rendered instead.
+    ///
+    /// Returns [`Error::GlyphNotFound`] for glyph identifiers that are not
+    /// [valid](crate::MetadataProvider::is_valid_glyph) for the font,
+    /// regardless of the outline format.
+    ///
+    /// Valid glyph identifiers are first replaced according to the
+    /// [nominal substitutions](ScalerBuilder::nominal_substitutions), if any.
+    #[cfg_attr(
+        feature = "trace",
+        tracing::instrument(level = "trace", skip_all, fields(glyph_id = glyph_id.to_u16()))
+    )]
+    pub fn outline(&mut self, glyph_id: GlyphId, pen: &mut impl Pen) -> Result<OutlineKind> {
+        if glyph_id.to_u16() >= self.glyph_count {
+            return Err(Error::GlyphNotFound(glyph_id));
+        }
+        let glyph_id = self.substitute(glyph_id);
+        if let Some(cache) = &self.cache {
+            if let Some(kind) = cache.replay(glyph_id, self.coords, pen) {
+                return Ok(kind);
+            }
+        }
+        let Some(outlines) = &mut self.outlines else {
+            return Err(Error::NoSources);
+        };
+        let mut recording = RecordingPen::new(pen, self.cache.is_some());
+        let mut pen = EmptyCheckPen {
+            inner: &mut recording,
+            is_empty: true,
+            skew: self.skew,
+        };
+        let warning = outlines.outline(
+            glyph_id,
+            self.size,
+            self.coords,
             #[cfg(feature = "hinting")]
-            {
-                outlines.outline(glyph_id, self.size, self.coords, self.hint, pen)
+            self.hint,
+            self.darkening.as_ref(),
+            &mut pen,
+        )?;
+        let kind = if pen.is_empty {
+            OutlineKind::Empty {
+                has_color: self.color.contains(glyph_id),
             }
-            #[cfg(not(feature = "hinting"))]
-            outlines.outline(glyph_id, self.size, self.coords, pen)
         } else {
-            Err(Error::NoSources)
+            OutlineKind::Drawn(warning)
+        };
+        if let Some(cache) = &mut self.cache {
+            cache.insert(glyph_id, self.coords, recording, kind);
+        }
+        Ok(kind)
+    }
+
+    /// Loads the TrueType outline for the specified glyph identifier and
+    /// returns its points, flags, contour end points and phantom points.
+    ///
+    /// Outlines loaded at [`Size::unscaled`] contain the points in font
+    /// units with variations applied, which suits tools that need the raw
+    /// quadratic data rather than path commands.
+    ///
+    /// Returns [`Error::NoSources`] for fonts without a `glyf` table.
+    pub fn glyf_outline(&mut self, glyph_id: GlyphId) -> Result<&glyf::Outline> {
+        let glyph_id = self.substitute(glyph_id);
+        match &mut self.outlines {
+            Some(Outlines::TrueType(scaler, outline)) => {
+                scaler.load(glyph_id, outline)?;
+                Ok(&**outline)
+            }
+            _ => Err(Error::NoSources),
+        }
+    }
+}
+
+/// Result of loading an outline with [`Scaler::outline`].
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum OutlineKind {
+    /// The outline was drawn, possibly with some parts omitted as described
+    /// by the warning.
+    Drawn(Option<Warning>),
+    /// The glyph has no outline and nothing was drawn.
+    ///
+    /// If `has_color` is true, the glyph has a color representation in the
+    /// `COLR`, `CBDT`, `sbix` or `SVG ` tables.
+    Empty { has_color: bool },
+}
+
+impl OutlineKind {
+    /// Returns the warning for a drawn outline, if any.
+    pub fn warning(&self) -> Option<Warning> {
+        match self {
+            Self::Drawn(warning) => *warning,
+            Self::Empty { .. } => None,
         }
     }
 }
 
+/// Pen that records whether any path commands were emitted and applies
+/// the skew of the scaler.
+struct EmptyCheckPen<'p, P> {
+    inner: &'p mut P,
+    is_empty: bool,
+    skew: f32,
+}
+
+impl<P: Pen> Pen for EmptyCheckPen<'_, P> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.is_empty = false;
+        self.inner.move_to(x + y * self.skew, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.is_empty = false;
+        self.inner.line_to(x + y * self.skew, y);
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        self.is_empty = false;
+        let skew = self.skew;
+        self.inner.quad_to(cx0 + cy0 * skew, cy0, x + y * skew, y);
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        self.is_empty = false;
+        let skew = self.skew;
+        self.inner.curve_to(
+            cx0 + cy0 * skew,
+            cy0,
+            cx1 + cy1 * skew,
+            cy1,
+            x + y * skew,
+            y,
+        );
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
 // Clippy doesn't like the size discrepancy between the two variants. Ignore
 // for now: we'll replace this with a real cache.
 #[allow(clippy::large_enum_variant)]
-enum Outlines<'a> {
+pub(super) enum Outlines<'a> {
     TrueType(glyf::Scaler<'a>, &'a mut glyf::Outline),
     PostScript(PostScriptScaler<'a>, ScalerSubfont),
 }
@@ -228,12 +596,22 @@ impl<'a> Outlines<'a> {
         size: f32,
         coords: &'a [NormalizedCoord],
         #[cfg(feature = "hinting")] hint: Option<Hinting>,
+        darkening: Option<&StemDarkening>,
         pen: &mut impl Pen,
-    ) -> Result<()> {
+    ) -> Result<Option<Warning>> {
         match self {
             Self::TrueType(scaler, outline) => {
-                scaler.load(glyph_id, outline)?;
-                Ok(outline.to_path(pen)?)
+                let warning = scaler.load(glyph_id, outline)?;
+                if let Some(darkening) = darkening {
+                    // TrueType fonts don't declare a stem width.
+                    let amount = darken::side_amount(darkening, size, DEFAULT_STEM_WIDTH);
+                    let mut pen = DarkeningPen::new(pen, amount);
+                    outline.to_path(&mut pen)?;
+                    pen.finish();
+                } else {
+                    outline.to_path(pen)?;
+                }
+                Ok(warning)
             }
             Self::PostScript(scaler, subfont) => {
                 let subfont_index = scaler.subfont_index(glyph_id);
@@ -244,7 +622,19 @@ impl<'a> Outlines<'a> {
                 let hint = hint.is_some();
                 #[cfg(not(feature = "hinting"))]
                 let hint = false;
-                Ok(scaler.outline(subfont, glyph_id, coords, hint, pen)?)
+                if let Some(darkening) = darkening {
+                    let stem_width = subfont
+                        .std_vw()
+                        .map(|width| width.to_f64() as f32 / scaler.units_per_em().max(1) as f32)
+                        .unwrap_or(DEFAULT_STEM_WIDTH);
+                    let amount = darken::side_amount(darkening, size, stem_width);
+                    let mut pen = DarkeningPen::new(pen, amount);
+                    scaler.outline(subfont, glyph_id, coords, hint, &mut pen)?;
+                    pen.finish();
+                } else {
+                    scaler.outline(subfont, glyph_id, coords, hint, pen)?;
+                }
+                Ok(None)
             }
         }
     }
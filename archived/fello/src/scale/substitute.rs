@@ -0,0 +1,216 @@
+//! Nominal glyph substitution for previewing features.
+//!
+//! The scaler doesn't shape text, but glyph pickers and font menus often
+//! want to show the glyph that a stylistic set or numeral style would
+//! produce for a character. Features whose lookups substitute one glyph
+//! for another without context can be applied to a glyph in isolation,
+//! which covers `ss01`-`ss20`, `onum`, `smcp` and `aalt` in most fonts.
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use read_fonts::{
+    tables::gsub::{AlternateSubstFormat1, SingleSubst, SubstitutionSubtables},
+    types::{GlyphId, Tag},
+    ReadError, TableProvider,
+};
+
+/// Single or alternate substitution subtable.
+#[derive(Clone)]
+enum Subtable<'a> {
+    Single(SingleSubst<'a>),
+    Alternate(AlternateSubstFormat1<'a>),
+}
+
+impl Subtable<'_> {
+    /// Returns the substitute for the glyph.
+    fn substitute(&self, glyph_id: GlyphId) -> Option<GlyphId> {
+        match self {
+            Self::Single(SingleSubst::Format1(subtable)) => {
+                subtable.coverage().ok()?.get(glyph_id)?;
+                let delta = subtable.delta_glyph_id() as u16;
+                Some(GlyphId::new(glyph_id.to_u16().wrapping_add(delta)))
+            }
+            Self::Single(SingleSubst::Format2(subtable)) => {
+                let index = subtable.coverage().ok()?.get(glyph_id)?;
+                let substitute = subtable.substitute_glyph_ids().get(index as usize)?;
+                Some(GlyphId::new(substitute.get().to_u16()))
+            }
+            // The first alternate is selected, as when a shaper enables the
+            // feature with a value of 1.
+            Self::Alternate(subtable) => {
+                let index = subtable.coverage().ok()?.get(glyph_id)?;
+                let alternates = subtable.alternate_sets().get(index as usize).ok()?;
+                let alternate = alternates.alternate_glyph_ids().first()?;
+                Some(GlyphId::new(alternate.get().to_u16()))
+            }
+        }
+    }
+}
+
+/// Single and alternate substitution subtables of a set of `GSUB`
+/// features.
+///
+/// Other lookup types, including contextual lookups, are ignored since
+/// they can't be applied to a glyph without its surrounding text.
+#[derive(Clone, Default)]
+pub(crate) struct NominalSubstitutions<'a> {
+    /// Subtables with the index of their lookup, in lookup order.
+    subtables: Vec<(u16, Subtable<'a>)>,
+}
+
+impl<'a> NominalSubstitutions<'a> {
+    pub fn new(font: &impl TableProvider<'a>, features: &[Tag]) -> Self {
+        let mut subtables = Vec::new();
+        if !features.is_empty() {
+            // Malformed tables produce no substitutions.
+            let _ = collect_subtables(font, features, &mut subtables);
+        }
+        Self { subtables }
+    }
+
+    /// Returns the glyph produced by applying each lookup in turn to the
+    /// given glyph, or `None` if no lookup applies.
+    pub fn apply(&self, glyph_id: GlyphId) -> Option<GlyphId> {
+        let mut glyph = glyph_id;
+        let mut applied = None;
+        for (lookup_index, subtable) in &self.subtables {
+            // Only the first subtable of a lookup that covers the glyph
+            // applies.
+            if applied == Some(*lookup_index) {
+                continue;
+            }
+            if let Some(substitute) = subtable.substitute(glyph) {
+                glyph = substitute;
+                applied = Some(*lookup_index);
+            }
+        }
+        applied.map(|_| glyph)
+    }
+}
+
+/// Collects the single and alternate substitution subtables of the
+/// lookups referenced by the features.
+fn collect_subtables<'a>(
+    font: &impl TableProvider<'a>,
+    features: &[Tag],
+    subtables: &mut Vec<(u16, Subtable<'a>)>,
+) -> Result<(), ReadError> {
+    let gsub = font.gsub()?;
+    let feature_list = gsub.feature_list()?;
+    let mut lookups = BTreeSet::new();
+    for record in feature_list.feature_records() {
+        if features.contains(&record.feature_tag()) {
+            let feature = record.feature(feature_list.offset_data())?;
+            lookups.extend(
+                feature
+                    .lookup_list_indices()
+                    .iter()
+                    .map(|index| index.get()),
+            );
+        }
+    }
+    // Shapers apply lookups in the order of the lookup list regardless
+    // of the order of the features.
+    let lookup_list = gsub.lookup_list()?;
+    for lookup_index in lookups {
+        let Ok(lookup) = lookup_list.lookups().get(lookup_index as usize) else {
+            continue;
+        };
+        match lookup.subtables() {
+            Ok(SubstitutionSubtables::Single(single)) => subtables.extend(
+                single
+                    .iter()
+                    .flatten()
+                    .map(|subtable| (lookup_index, Subtable::Single(subtable))),
+            ),
+            Ok(SubstitutionSubtables::Alternate(alternate)) => subtables.extend(
+                alternate
+                    .iter()
+                    .flatten()
+                    .map(|subtable| (lookup_index, Subtable::Alternate(subtable))),
+            ),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        scale::{test, Context},
+        testing::{subtables, FontBuilder, LayoutBuilder, SQUARE},
+    };
+    use alloc::vec;
+    use read_fonts::FontRef;
+
+    /// Builds a font with a glyph that `ss01` replaces through a single
+    /// substitution and that `aalt` replaces with the first of two
+    /// alternates.
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::new(1000);
+        let a = builder.add_glyph(500, SQUARE);
+        let ss01 = builder.add_glyph(500, &[&[(0, 0), (0, 700), (500, 700), (500, 0)]]);
+        let aalt = builder.add_glyph(500, &[&[(100, 0), (100, 500), (400, 500), (400, 0)]]);
+        let mut gsub = LayoutBuilder::new();
+        let single = gsub.add_lookup(
+            1,
+            vec![subtables::single_subst_delta(
+                subtables::coverage(&[a]),
+                (ss01.to_u16() - a.to_u16()) as i16,
+            )],
+        );
+        let alternate = gsub.add_lookup(
+            3,
+            vec![subtables::alternate_subst(
+                subtables::coverage(&[a]),
+                &[&[aalt, ss01]],
+            )],
+        );
+        gsub.add_feature(Tag::new(b"ss01"), &[single]);
+        gsub.add_feature(Tag::new(b"aalt"), &[alternate]);
+        builder.add_table(Tag::new(b"GSUB"), gsub.build());
+        builder.build()
+    }
+
+    fn outline(font: &FontRef, features: &[&[u8; 4]], glyph_id: u16) -> test::Path {
+        let mut cx = Context::new();
+        let mut path = test::Path::default();
+        cx.new_scaler()
+            .nominal_substitutions(features.iter().map(|tag| Tag::new(tag)))
+            .build(font)
+            .outline(GlyphId::new(glyph_id), &mut path)
+            .unwrap();
+        path
+    }
+
+    #[test]
+    fn apply_features() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let substitute = |features: &[&[u8; 4]]| {
+            let features = features.iter().map(|tag| Tag::new(tag)).collect::<Vec<_>>();
+            NominalSubstitutions::new(&font, &features).apply(GlyphId::new(1))
+        };
+        assert_eq!(substitute(&[]), None);
+        assert_eq!(substitute(&[b"smcp"]), None);
+        assert_eq!(substitute(&[b"ss01"]), Some(GlyphId::new(2)));
+        assert_eq!(substitute(&[b"aalt"]), Some(GlyphId::new(3)));
+        // The alternate lookup no longer covers the glyph once ss01 has
+        // been applied.
+        assert_eq!(substitute(&[b"aalt", b"ss01"]), Some(GlyphId::new(2)));
+        assert_eq!(
+            NominalSubstitutions::new(&font, &[Tag::new(b"ss01")]).apply(GlyphId::new(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn scaler_outlines_substitute() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        assert_eq!(outline(&font, &[b"ss01"], 1).0, outline(&font, &[], 2).0);
+        assert_eq!(outline(&font, &[b"aalt"], 1).0, outline(&font, &[], 3).0);
+        assert_ne!(outline(&font, &[], 1).0, outline(&font, &[], 2).0);
+    }
+}
@@ -0,0 +1,163 @@
+//! Conversion of glyph outlines to SVG.
+
+use super::{Context, Pen, Result, ScalerBuilder};
+use crate::{
+    meta::metrics::{GlyphMetrics, Metrics},
+    GlyphId, NormalizedCoords, Size,
+};
+use alloc::string::String;
+use core::fmt::Write;
+use read_fonts::TableProvider;
+
+/// Pen that produces SVG path data.
+///
+/// The y axis is flipped to point down, as in SVG, and each point is
+/// offset by the given amount before flipping.
+#[derive(Clone, Default, Debug)]
+pub struct SvgPen {
+    data: String,
+    offset: (f32, f32),
+}
+
+impl SvgPen {
+    /// Creates a new pen with empty path data.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the offset that is added to subsequent points.
+    pub fn set_offset(&mut self, x: f32, y: f32) {
+        self.offset = (x, y);
+    }
+
+    /// Returns the path data, suitable for the `d` attribute of a `path`
+    /// element.
+    pub fn path_data(&self) -> &str {
+        self.data.trim_end()
+    }
+
+    /// Consumes the pen and returns the path data.
+    pub fn into_path_data(mut self) -> String {
+        self.data.truncate(self.data.trim_end().len());
+        self.data
+    }
+
+    fn point(&mut self, x: f32, y: f32) {
+        // Subtract from zero to avoid printing negative zero.
+        let _ = write!(
+            self.data,
+            "{},{} ",
+            x + self.offset.0,
+            0.0 - (y + self.offset.1)
+        );
+    }
+}
+
+impl Pen for SvgPen {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.data.push('M');
+        self.point(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.data.push('L');
+        self.point(x, y);
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        self.data.push('Q');
+        self.point(cx0, cy0);
+        self.point(x, y);
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        self.data.push('C');
+        self.point(cx0, cy0);
+        self.point(cx1, cy1);
+        self.point(x, y);
+    }
+
+    fn close(&mut self) {
+        self.data.push_str("Z ");
+    }
+}
+
+/// Returns an SVG document containing the outline of the given glyph.
+///
+/// The view box spans the advance width of the glyph horizontally and the
+/// ascent and descent of the font vertically.
+pub fn outline_to_svg<'a>(
+    font: &impl TableProvider<'a>,
+    glyph_id: GlyphId,
+    size: Size,
+    coords: NormalizedCoords<'a>,
+) -> Result<String> {
+    glyphs_to_svg(font, &[glyph_id], size, coords)
+}
+
+/// Returns an SVG document containing the outlines of the given glyphs
+/// placed next to each other according to their advance widths.
+///
+/// The view box spans the sum of the advance widths horizontally and the
+/// ascent and descent of the font vertically.
+pub fn glyphs_to_svg<'a>(
+    font: &impl TableProvider<'a>,
+    glyph_ids: &[GlyphId],
+    size: Size,
+    coords: NormalizedCoords<'a>,
+) -> Result<String> {
+    let metrics = Metrics::new(font, size, coords);
+    let glyph_metrics = GlyphMetrics::new(font, size, coords);
+    let mut context = Context::new();
+    let instance = ScalerBuilder::new(&mut context)
+        .size(size)
+        .coords(coords)
+        .instance(font);
+    let mut scaler = instance.scaler(&mut context);
+    let mut pen = SvgPen::new();
+    let mut x = 0.0;
+    for glyph_id in glyph_ids {
+        pen.set_offset(x, 0.0);
+        scaler.outline(*glyph_id, &mut pen)?;
+        x += glyph_metrics.advance_width(*glyph_id).unwrap_or_default();
+    }
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 {} {} {}"><path d="{}"/></svg>"#,
+        -metrics.ascent,
+        x,
+        metrics.ascent - metrics.descent,
+        pen.path_data()
+    );
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use read_fonts::FontRef;
+
+    #[test]
+    fn svg_path_data() {
+        let mut pen = SvgPen::new();
+        pen.set_offset(10.0, 0.0);
+        pen.move_to(0.0, 0.0);
+        pen.line_to(5.0, 5.0);
+        pen.quad_to(5.0, 10.0, 0.0, 10.0);
+        pen.close();
+        assert_eq!(pen.path_data(), "M10,0 L15,-5 Q15,-10 10,-10 Z");
+    }
+
+    #[test]
+    fn glyph_run() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let glyph_ids = [GlyphId::new(1), GlyphId::new(2)];
+        let single =
+            outline_to_svg(&font, glyph_ids[0], Size::new(16.0), Default::default()).unwrap();
+        let run = glyphs_to_svg(&font, &glyph_ids, Size::new(16.0), Default::default()).unwrap();
+        assert!(single.starts_with("<svg"));
+        assert!(single.ends_with("</svg>"));
+        assert!(run.len() > single.len());
+    }
+}
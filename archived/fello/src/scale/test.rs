@@ -1,7 +1,7 @@
 //! Helpers for unit testing
 
 use super::{Context, GlyphId, Pen, Scaler};
-use core::str::FromStr;
+use core::{fmt::Write, str::FromStr};
 use read_fonts::{
     tables::glyf::PointFlags,
     types::{F26Dot6, F2Dot14, Point},
@@ -20,6 +20,18 @@ pub enum PathElement {
 
 use PathElement::*;
 
+impl From<&read_fonts::scaler_test::PathElement> for PathElement {
+    fn from(element: &read_fonts::scaler_test::PathElement) -> Self {
+        use read_fonts::scaler_test::PathElement as Other;
+        match *element {
+            Other::MoveTo(points) => MoveTo(points),
+            Other::LineTo(points) => LineTo(points),
+            Other::QuadTo(points) => QuadTo(points),
+            Other::CurveTo(points) => CurveTo(points),
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Path(pub Vec<PathElement>);
 
@@ -163,3 +175,108 @@ where
     }
     points
 }
+
+/// Number of decimal places used for coordinates in snapshots.
+const SNAPSHOT_PRECISION: usize = 3;
+
+/// Appends a snapshot of an outline to the given string.
+///
+/// Each outline is written as a header line identifying the glyph, size,
+/// variation coordinates and hinting mode, followed by one line per path
+/// element with coordinates at fixed precision and a terminating `-`.
+pub fn write_snapshot(
+    out: &mut String,
+    glyph_id: GlyphId,
+    size: f32,
+    coords: &[F2Dot14],
+    hinting: Option<&str>,
+    path: &[PathElement],
+) {
+    write!(out, "glyph {} size {}", glyph_id.to_u16(), size).unwrap();
+    if !coords.is_empty() {
+        out.push_str(" coords");
+        for coord in coords {
+            write!(out, " {}", coord.to_f32()).unwrap();
+        }
+    }
+    writeln!(out, " hinting {}", hinting.unwrap_or("none")).unwrap();
+    for element in path {
+        let (verb, points) = match element {
+            MoveTo(points) => ('M', &points[..]),
+            LineTo(points) => ('L', &points[..]),
+            QuadTo(points) => ('Q', &points[..]),
+            CurveTo(points) => ('C', &points[..]),
+        };
+        out.push(verb);
+        for point in points.chunks(2) {
+            out.push(' ');
+            write_coord(out, point[0]);
+            out.push(',');
+            write_coord(out, point[1]);
+        }
+        out.push('\n');
+    }
+    out.push_str("-\n");
+}
+
+fn write_coord(out: &mut String, value: f32) {
+    let scale = 10f32.powi(SNAPSHOT_PRECISION as i32);
+    // Adding zero normalizes negative zero so that values which round to
+    // zero produce the same text.
+    let value = (value * scale).round() / scale + 0.0;
+    write!(out, "{:.*}", SNAPSHOT_PRECISION, value).unwrap();
+}
+
+/// Returns a snapshot of the unhinted outlines extracted from FreeType.
+pub fn freetype_snapshot(outlines: &[GlyphOutline]) -> String {
+    let mut out = String::new();
+    for outline in outlines {
+        write_snapshot(
+            &mut out,
+            outline.glyph_id,
+            outline.size,
+            &outline.coords,
+            None,
+            &outline.path,
+        );
+    }
+    out
+}
+
+/// Compares two snapshots line by line and panics with a report of the
+/// mismatched lines and the glyphs that contain them.
+pub fn assert_snapshot_eq(name: &str, expected: &str, actual: &str) {
+    const MAX_REPORTED: usize = 10;
+    let mut report = String::new();
+    let mut mismatches = 0;
+    let mut header = "";
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line_number = 0;
+    loop {
+        let (expected_line, actual_line) = match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => break,
+            pair => pair,
+        };
+        line_number += 1;
+        if let Some(line) = expected_line.filter(|line| line.starts_with("glyph")) {
+            header = line;
+        }
+        if expected_line == actual_line {
+            continue;
+        }
+        mismatches += 1;
+        if mismatches <= MAX_REPORTED {
+            writeln!(
+                report,
+                "  line {line_number} ({header}):\n    expected: {}\n    actual:   {}",
+                expected_line.unwrap_or("<end>"),
+                actual_line.unwrap_or("<end>"),
+            )
+            .unwrap();
+        }
+    }
+    if mismatches != 0 {
+        panic!("snapshot {name} has {mismatches} mismatched lines:\n{report}");
+    }
+}
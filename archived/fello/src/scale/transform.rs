@@ -0,0 +1,316 @@
+//! Affine transforms for outlines in font units.
+
+use super::Pen;
+use core::ops::{Mul, MulAssign};
+
+/// Affine transform that maps outlines to their final coordinates.
+///
+/// The components follow the common column major convention so that a
+/// point is transformed as:
+///
+/// ```text
+/// x' = xx * x + xy * y + dx
+/// y' = yx * x + yy * y + dy
+/// ```
+///
+/// Scalers that emit [font unit outlines](super::ScalerBuilder::font_unit_outlines)
+/// report the transform for the requested size and skew with
+/// [`Scaler::transform`](super::Scaler::transform). Renderers can cache the
+/// outlines per glyph and apply the transform when drawing, such as on the
+/// GPU.
+///
+/// Transforms convert to and from the column major `[xx, yx, xy, yy, dx, dy]`
+/// arrays used by most graphics libraries and, with the `kurbo` feature, to
+/// and from the `Affine` type of kurbo, which peniko also uses. Multiplying
+/// transforms follows the same convention as those libraries: `a * b`
+/// applies `b` first.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    /// Horizontal scale.
+    pub xx: f32,
+    /// Vertical skew.
+    pub yx: f32,
+    /// Horizontal skew.
+    pub xy: f32,
+    /// Vertical scale.
+    pub yy: f32,
+    /// Horizontal offset.
+    pub dx: f32,
+    /// Vertical offset.
+    pub dy: f32,
+}
+
+impl Transform {
+    /// Transform that leaves points unchanged.
+    pub const IDENTITY: Self = Self {
+        xx: 1.0,
+        yx: 0.0,
+        xy: 0.0,
+        yy: 1.0,
+        dx: 0.0,
+        dy: 0.0,
+    };
+
+    /// Creates a transform that scales uniformly by the given factor.
+    pub fn scale(scale: f32) -> Self {
+        Self {
+            xx: scale,
+            yy: scale,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Creates a transform that shifts points horizontally by the given
+    /// factor of their vertical coordinate.
+    ///
+    /// Positive factors slant outlines to the right. The factor is the
+    /// tangent of the slant angle, so 0.2 approximates the 12 degree
+    /// slant commonly used for synthetic obliques.
+    pub fn skew(skew: f32) -> Self {
+        Self {
+            xy: skew,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Returns the transform that applies this transform followed by
+    /// `other`.
+    pub fn then(&self, other: &Transform) -> Self {
+        Self {
+            xx: other.xx * self.xx + other.xy * self.yx,
+            yx: other.yx * self.xx + other.yy * self.yx,
+            xy: other.xx * self.xy + other.xy * self.yy,
+            yy: other.yx * self.xy + other.yy * self.yy,
+            dx: other.xx * self.dx + other.xy * self.dy + other.dx,
+            dy: other.yx * self.dx + other.yy * self.dy + other.dy,
+        }
+    }
+
+    /// Creates a transform that offsets points by the given amounts.
+    pub fn translate(dx: f32, dy: f32) -> Self {
+        Self {
+            dx,
+            dy,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Returns the determinant of the linear part of the transform.
+    pub fn determinant(&self) -> f32 {
+        self.xx * self.yy - self.xy * self.yx
+    }
+
+    /// Returns the transform that reverses this transform, or `None` if the
+    /// transform collapses points onto a line or a single point.
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let inv_det = det.recip();
+        let xx = self.yy * inv_det;
+        let yx = -self.yx * inv_det;
+        let xy = -self.xy * inv_det;
+        let yy = self.xx * inv_det;
+        Some(Self {
+            xx,
+            yx,
+            xy,
+            yy,
+            dx: -(xx * self.dx + xy * self.dy),
+            dy: -(yx * self.dx + yy * self.dy),
+        })
+    }
+
+    /// Returns the components as a column major `[xx, yx, xy, yy, dx, dy]`
+    /// array.
+    pub fn to_array(&self) -> [f32; 6] {
+        [self.xx, self.yx, self.xy, self.yy, self.dx, self.dy]
+    }
+
+    /// Returns true if the transform leaves points unchanged.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Applies the transform to a point.
+    pub fn transform_point(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            self.xx * x + self.xy * y + self.dx,
+            self.yx * x + self.yy * y + self.dy,
+        )
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<[f32; 6]> for Transform {
+    fn from([xx, yx, xy, yy, dx, dy]: [f32; 6]) -> Self {
+        Self {
+            xx,
+            yx,
+            xy,
+            yy,
+            dx,
+            dy,
+        }
+    }
+}
+
+impl From<Transform> for [f32; 6] {
+    fn from(transform: Transform) -> Self {
+        transform.to_array()
+    }
+}
+
+impl Mul for Transform {
+    type Output = Self;
+
+    /// Returns the transform that applies `rhs` followed by `self`.
+    fn mul(self, rhs: Self) -> Self {
+        rhs.then(&self)
+    }
+}
+
+impl MulAssign for Transform {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+#[cfg(feature = "kurbo")]
+impl From<Transform> for kurbo::Affine {
+    fn from(transform: Transform) -> Self {
+        Self::new(transform.to_array().map(f64::from))
+    }
+}
+
+#[cfg(feature = "kurbo")]
+impl From<kurbo::Affine> for Transform {
+    fn from(affine: kurbo::Affine) -> Self {
+        affine.as_coeffs().map(|coeff| coeff as f32).into()
+    }
+}
+
+/// Pen that applies a transform to each point before passing it to the
+/// inner pen.
+pub struct TransformPen<'a, P> {
+    inner: &'a mut P,
+    transform: Transform,
+}
+
+impl<'a, P> TransformPen<'a, P> {
+    /// Creates a new pen that applies the transform to the points passed
+    /// to the inner pen.
+    pub fn new(inner: &'a mut P, transform: Transform) -> Self {
+        Self { inner, transform }
+    }
+}
+
+impl<P: Pen> Pen for TransformPen<'_, P> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.transform.transform_point(x, y);
+        self.inner.move_to(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let (x, y) = self.transform.transform_point(x, y);
+        self.inner.line_to(x, y);
+    }
+
+    fn quad_to(&mut self, cx0: f32, cy0: f32, x: f32, y: f32) {
+        let (cx0, cy0) = self.transform.transform_point(cx0, cy0);
+        let (x, y) = self.transform.transform_point(x, y);
+        self.inner.quad_to(cx0, cy0, x, y);
+    }
+
+    fn curve_to(&mut self, cx0: f32, cy0: f32, cx1: f32, cy1: f32, x: f32, y: f32) {
+        let (cx0, cy0) = self.transform.transform_point(cx0, cy0);
+        let (cx1, cy1) = self.transform.transform_point(cx1, cy1);
+        let (x, y) = self.transform.transform_point(x, y);
+        self.inner.curve_to(cx0, cy0, cx1, cy1, x, y);
+    }
+
+    fn close(&mut self) {
+        self.inner.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose() {
+        let transform = Transform::skew(0.25).then(&Transform::scale(2.0));
+        assert_eq!(transform.transform_point(4.0, 8.0), (12.0, 16.0));
+        let mut offset = Transform::scale(0.5);
+        offset.dx = 1.0;
+        let transform = offset.then(&Transform::skew(0.5));
+        assert_eq!(transform.transform_point(2.0, 4.0), (3.0, 2.0));
+        assert!(Transform::scale(1.0)
+            .then(&Transform::skew(0.0))
+            .is_identity());
+    }
+
+    #[test]
+    fn arrays() {
+        let coeffs = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let transform = Transform::from(coeffs);
+        assert_eq!(transform.transform_point(1.0, 1.0), (9.0, 12.0));
+        assert_eq!(<[f32; 6]>::from(transform), coeffs);
+        assert_eq!(
+            Transform::IDENTITY.to_array(),
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn multiply() {
+        let skew = Transform::skew(0.25);
+        let scale = Transform::scale(2.0);
+        let translate = Transform::translate(3.0, -1.0);
+        assert_eq!(scale * skew, skew.then(&scale));
+        assert_eq!(
+            (translate * scale * skew).transform_point(4.0, 8.0),
+            (15.0, 15.0)
+        );
+        let mut transform = translate;
+        transform *= scale;
+        assert_eq!(transform, translate * scale);
+        assert_eq!(transform * Transform::IDENTITY, transform);
+    }
+
+    #[test]
+    fn invert() {
+        let transform =
+            Transform::translate(3.0, -1.0) * Transform::scale(2.0) * Transform::skew(0.5);
+        let inverse = transform.inverse().unwrap();
+        let (x, y) = transform.transform_point(4.0, 8.0);
+        assert_eq!(inverse.transform_point(x, y), (4.0, 8.0));
+        assert!((inverse * transform).is_identity());
+        assert_eq!(Transform::IDENTITY.inverse(), Some(Transform::IDENTITY));
+        assert_eq!(Transform::scale(0.0).inverse(), None);
+        let collapsed = Transform::from([1.0, 2.0, 2.0, 4.0, 0.0, 0.0]);
+        assert_eq!(collapsed.inverse(), None);
+    }
+
+    #[cfg(feature = "kurbo")]
+    #[test]
+    fn kurbo_affine() {
+        let transform = Transform::translate(3.0, -1.0) * Transform::skew(0.25);
+        let affine = kurbo::Affine::from(transform);
+        assert_eq!(affine.as_coeffs(), [1.0, 0.0, 0.25, 1.0, 3.0, -1.0]);
+        let point = affine * kurbo::Point::new(4.0, 8.0);
+        assert_eq!(
+            transform.transform_point(4.0, 8.0),
+            (point.x as f32, point.y as f32)
+        );
+        assert_eq!(Transform::from(affine), transform);
+    }
+}
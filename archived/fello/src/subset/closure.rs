@@ -0,0 +1,435 @@
+//! Glyph closure over glyph substitution (`GSUB`) lookups.
+
+use alloc::{collections::BTreeSet, vec::Vec};
+use read_fonts::{
+    tables::{
+        gsub::{
+            AlternateSubstFormat1, ExtensionSubtable, Gsub, LigatureSubstFormat1,
+            MultipleSubstFormat1, ReverseChainSingleSubstFormat1, SingleSubst, SubstitutionLookup,
+        },
+        layout::{ChainedSequenceContext, CoverageTable, SequenceContext, SequenceLookupRecord},
+    },
+    types::{GlyphId, Tag},
+    FontRef, ReadError, TableProvider,
+};
+
+/// Computes the set of glyphs reachable from the given glyphs through the
+/// `GSUB` lookups referenced by the given features.
+///
+/// An empty feature list selects all features in the font. Lookups
+/// referenced by contextual lookups are applied to the full glyph set
+/// without evaluating their context so the result may contain glyphs that
+/// are unreachable in practice but never omits a reachable glyph. Ligatures
+/// are only added when all of their components are present.
+///
+/// The result is sorted and includes the input glyphs.
+pub fn layout_closure(
+    font: &FontRef,
+    glyphs: impl IntoIterator<Item = GlyphId>,
+    features: &[Tag],
+) -> Vec<GlyphId> {
+    let mut set = glyphs
+        .into_iter()
+        .map(|glyph_id| glyph_id.to_u16())
+        .collect();
+    close_over_gsub(font, &mut set, features);
+    set.into_iter().map(GlyphId::new).collect()
+}
+
+/// Extends the glyph set with all glyphs reachable through `GSUB` lookups
+/// for the given features.
+pub(crate) fn close_over_gsub(font: &FontRef, glyphs: &mut BTreeSet<u16>, features: &[Tag]) {
+    let Ok(gsub) = font.gsub() else {
+        return;
+    };
+    let subtables = lookup_subtables(&gsub, features);
+    // Each pass can only grow the set so this terminates once a pass adds
+    // nothing new.
+    loop {
+        let count = glyphs.len();
+        for subtable in &subtables {
+            subtable.apply(glyphs);
+        }
+        if glyphs.len() == count {
+            break;
+        }
+    }
+}
+
+/// Returns the subtables of all lookups referenced by the given features,
+/// including those referenced by contextual lookups.
+fn lookup_subtables<'a>(gsub: &Gsub<'a>, features: &[Tag]) -> Vec<Subtable<'a>> {
+    let mut pending = Vec::new();
+    if let Ok(feature_list) = gsub.feature_list() {
+        for record in feature_list.feature_records() {
+            if !features.is_empty() && !features.contains(&record.feature_tag()) {
+                continue;
+            }
+            if let Ok(feature) = record.feature(feature_list.offset_data()) {
+                pending.extend(
+                    feature
+                        .lookup_list_indices()
+                        .iter()
+                        .map(|index| index.get()),
+                );
+            }
+        }
+    }
+    let Ok(lookup_list) = gsub.lookup_list() else {
+        return Vec::new();
+    };
+    let lookups = lookup_list.lookups();
+    let mut visited = BTreeSet::new();
+    let mut subtables = Vec::new();
+    while let Some(lookup_index) = pending.pop() {
+        if !visited.insert(lookup_index) {
+            continue;
+        }
+        let Ok(lookup) = lookups.get(lookup_index as usize) else {
+            continue;
+        };
+        for subtable in Subtable::resolve(lookup) {
+            subtable.nested_lookups(&mut pending);
+            subtables.push(subtable);
+        }
+    }
+    subtables
+}
+
+/// Substitution subtable with extensions resolved.
+enum Subtable<'a> {
+    Single(SingleSubst<'a>),
+    Multiple(MultipleSubstFormat1<'a>),
+    Alternate(AlternateSubstFormat1<'a>),
+    Ligature(LigatureSubstFormat1<'a>),
+    Contextual(SequenceContext<'a>),
+    ChainContextual(ChainedSequenceContext<'a>),
+    Reverse(ReverseChainSingleSubstFormat1<'a>),
+}
+
+impl<'a> Subtable<'a> {
+    /// Returns the subtables of the given lookup, skipping any that fail to
+    /// parse.
+    fn resolve(lookup: SubstitutionLookup<'a>) -> Vec<Self> {
+        match lookup {
+            SubstitutionLookup::Single(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::Single)
+                .collect(),
+            SubstitutionLookup::Multiple(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::Multiple)
+                .collect(),
+            SubstitutionLookup::Alternate(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::Alternate)
+                .collect(),
+            SubstitutionLookup::Ligature(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::Ligature)
+                .collect(),
+            SubstitutionLookup::Contextual(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::Contextual)
+                .collect(),
+            SubstitutionLookup::ChainContextual(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::ChainContextual)
+                .collect(),
+            SubstitutionLookup::Reverse(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .map(Self::Reverse)
+                .collect(),
+            SubstitutionLookup::Extension(lookup) => lookup
+                .subtables()
+                .iter()
+                .flatten()
+                .filter_map(|extension| match extension {
+                    ExtensionSubtable::Single(ext) => ext.extension().ok().map(Self::Single),
+                    ExtensionSubtable::Multiple(ext) => ext.extension().ok().map(Self::Multiple),
+                    ExtensionSubtable::Alternate(ext) => ext.extension().ok().map(Self::Alternate),
+                    ExtensionSubtable::Ligature(ext) => ext.extension().ok().map(Self::Ligature),
+                    ExtensionSubtable::Contextual(ext) => {
+                        ext.extension().ok().map(Self::Contextual)
+                    }
+                    ExtensionSubtable::ChainContextual(ext) => {
+                        ext.extension().ok().map(Self::ChainContextual)
+                    }
+                    ExtensionSubtable::Reverse(ext) => ext.extension().ok().map(Self::Reverse),
+                })
+                .collect(),
+        }
+    }
+
+    /// Adds the output glyphs of this subtable for all covered glyphs in the
+    /// set.
+    fn apply(&self, glyphs: &mut BTreeSet<u16>) {
+        match self {
+            Self::Single(SingleSubst::Format1(subtable)) => {
+                let delta = subtable.delta_glyph_id() as u16;
+                let covered = covered(subtable.coverage(), glyphs);
+                glyphs.extend(covered.iter().map(|(_, glyph)| glyph.wrapping_add(delta)));
+            }
+            Self::Single(SingleSubst::Format2(subtable)) => {
+                let substitutes = subtable.substitute_glyph_ids();
+                let covered = covered(subtable.coverage(), glyphs);
+                glyphs.extend(
+                    covered
+                        .iter()
+                        .filter_map(|(index, _)| substitutes.get(*index))
+                        .map(|glyph_id| glyph_id.get().to_u16()),
+                );
+            }
+            Self::Multiple(subtable) => {
+                let sequences = subtable.sequences();
+                for (index, _) in covered(subtable.coverage(), glyphs) {
+                    if let Ok(sequence) = sequences.get(index) {
+                        let substitutes = sequence.substitute_glyph_ids();
+                        glyphs.extend(substitutes.iter().map(|glyph_id| glyph_id.get().to_u16()));
+                    }
+                }
+            }
+            Self::Alternate(subtable) => {
+                let alternate_sets = subtable.alternate_sets();
+                for (index, _) in covered(subtable.coverage(), glyphs) {
+                    if let Ok(alternate_set) = alternate_sets.get(index) {
+                        let alternates = alternate_set.alternate_glyph_ids();
+                        glyphs.extend(alternates.iter().map(|glyph_id| glyph_id.get().to_u16()));
+                    }
+                }
+            }
+            Self::Ligature(subtable) => {
+                let ligature_sets = subtable.ligature_sets();
+                for (index, _) in covered(subtable.coverage(), glyphs) {
+                    let Ok(ligature_set) = ligature_sets.get(index) else {
+                        continue;
+                    };
+                    for ligature in ligature_set.ligatures().iter().flatten() {
+                        let all_present = ligature
+                            .component_glyph_ids()
+                            .iter()
+                            .all(|glyph_id| glyphs.contains(&glyph_id.get().to_u16()));
+                        if all_present {
+                            glyphs.insert(ligature.ligature_glyph().to_u16());
+                        }
+                    }
+                }
+            }
+            Self::Reverse(subtable) => {
+                let substitutes = subtable.substitute_glyph_ids();
+                let covered = covered(subtable.coverage(), glyphs);
+                glyphs.extend(
+                    covered
+                        .iter()
+                        .filter_map(|(index, _)| substitutes.get(*index))
+                        .map(|glyph_id| glyph_id.get().to_u16()),
+                );
+            }
+            // Contextual subtables only contribute through their nested
+            // lookups.
+            Self::Contextual(_) | Self::ChainContextual(_) => {}
+        }
+    }
+
+    /// Appends the indices of lookups referenced by this subtable if it is
+    /// contextual.
+    fn nested_lookups(&self, lookups: &mut Vec<u16>) {
+        let mut push = |records: &[SequenceLookupRecord]| {
+            lookups.extend(records.iter().map(|record| record.lookup_list_index()));
+        };
+        match self {
+            Self::Contextual(SequenceContext::Format1(subtable)) => {
+                for rule_set in subtable.seq_rule_sets().iter().flatten().flatten() {
+                    for rule in rule_set.seq_rules().iter().flatten() {
+                        push(rule.seq_lookup_records());
+                    }
+                }
+            }
+            Self::Contextual(SequenceContext::Format2(subtable)) => {
+                for rule_set in subtable.class_seq_rule_sets().iter().flatten().flatten() {
+                    for rule in rule_set.class_seq_rules().iter().flatten() {
+                        push(rule.seq_lookup_records());
+                    }
+                }
+            }
+            Self::Contextual(SequenceContext::Format3(subtable)) => {
+                push(subtable.seq_lookup_records());
+            }
+            Self::ChainContextual(ChainedSequenceContext::Format1(subtable)) => {
+                for rule_set in subtable.chained_seq_rule_sets().iter().flatten().flatten() {
+                    for rule in rule_set.chained_seq_rules().iter().flatten() {
+                        push(rule.seq_lookup_records());
+                    }
+                }
+            }
+            Self::ChainContextual(ChainedSequenceContext::Format2(subtable)) => {
+                let rule_sets = subtable.chained_class_seq_rule_sets();
+                for rule_set in rule_sets.iter().flatten().flatten() {
+                    for rule in rule_set.chained_class_seq_rules().iter().flatten() {
+                        push(rule.seq_lookup_records());
+                    }
+                }
+            }
+            Self::ChainContextual(ChainedSequenceContext::Format3(subtable)) => {
+                push(subtable.seq_lookup_records());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Returns the coverage index and glyph identifier of each glyph in the
+/// coverage table that is also in the set.
+fn covered(
+    coverage: Result<CoverageTable, ReadError>,
+    glyphs: &BTreeSet<u16>,
+) -> Vec<(usize, u16)> {
+    let Ok(coverage) = coverage else {
+        return Vec::new();
+    };
+    coverage
+        .iter()
+        .map(|glyph_id| glyph_id.to_u16())
+        .enumerate()
+        .filter(|(_, glyph)| glyphs.contains(glyph))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{subtables, FontBuilder, LayoutBuilder, SQUARE};
+    use alloc::vec;
+
+    /// Builds a font with glyphs for 'f', 'i', an 'fi' ligature and small
+    /// capitals for both letters.
+    ///
+    /// The `liga` feature forms the ligature and the `smcp` feature
+    /// substitutes the small capitals.
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::new(1000);
+        let f = builder.add_glyph(300, SQUARE);
+        let i = builder.add_glyph(250, SQUARE);
+        let fi = builder.add_glyph(500, SQUARE);
+        let f_sc = builder.add_glyph(400, SQUARE);
+        let i_sc = builder.add_glyph(300, SQUARE);
+        builder.map('f', f).map('i', i);
+        let mut gsub = LayoutBuilder::new();
+        // Ligature substitution
+        let liga = gsub.add_lookup(
+            4,
+            vec![subtables::ligature_subst(
+                subtables::coverage(&[f]),
+                &[&[(fi, &[i])]],
+            )],
+        );
+        // Single substitution
+        let smcp = gsub.add_lookup(
+            1,
+            vec![subtables::single_subst(
+                subtables::coverage(&[f, i]),
+                &[f_sc, i_sc],
+            )],
+        );
+        gsub.add_feature(Tag::new(b"liga"), &[liga]);
+        gsub.add_feature(Tag::new(b"smcp"), &[smcp]);
+        builder.add_table(Tag::new(b"GSUB"), gsub.build());
+        builder.build()
+    }
+
+    /// Builds a font where `calt` reaches a single substitution of glyph 1
+    /// to 3 through a chained contextual lookup and `ss02` substitutes
+    /// glyph 2 with 4 through an extension lookup.
+    fn build_nested_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::new(1000);
+        let a = builder.add_glyph(500, SQUARE);
+        let b = builder.add_glyph(500, SQUARE);
+        let c = builder.add_glyph(500, SQUARE);
+        let d = builder.add_glyph(500, SQUARE);
+        let mut gsub = LayoutBuilder::new();
+        // Single substitution only referenced by the contextual lookup
+        let a_to_c = gsub.add_lookup(
+            1,
+            vec![subtables::single_subst(subtables::coverage(&[a]), &[c])],
+        );
+        // Chained contextual substitution applied to a when followed by b
+        let calt = gsub.add_lookup(
+            6,
+            vec![subtables::chained_context(
+                vec![],
+                vec![subtables::coverage(&[a])],
+                vec![subtables::coverage(&[b])],
+                &[(0, a_to_c)],
+            )],
+        );
+        // Extension wrapping a single substitution
+        let ss02 = gsub.add_lookup(
+            7,
+            vec![subtables::extension(
+                1,
+                subtables::single_subst_delta(
+                    subtables::coverage(&[b]),
+                    (d.to_u16() - b.to_u16()) as i16,
+                ),
+            )],
+        );
+        gsub.add_feature(Tag::new(b"calt"), &[calt]);
+        gsub.add_feature(Tag::new(b"ss02"), &[ss02]);
+        builder.add_table(Tag::new(b"GSUB"), gsub.build());
+        builder.build()
+    }
+
+    fn closure(data: &[u8], glyphs: &[u16], features: &[&[u8; 4]]) -> Vec<u16> {
+        let font = FontRef::new(data).unwrap();
+        let features = features.iter().map(|tag| Tag::new(tag)).collect::<Vec<_>>();
+        layout_closure(&font, glyphs.iter().map(|g| GlyphId::new(*g)), &features)
+            .into_iter()
+            .map(|g| g.to_u16())
+            .collect()
+    }
+
+    #[test]
+    fn ligature_requires_all_components() {
+        let data = build_test_font();
+        assert_eq!(closure(&data, &[1], &[b"liga"]), [1]);
+        assert_eq!(closure(&data, &[1, 2], &[b"liga"]), [1, 2, 3]);
+    }
+
+    #[test]
+    fn feature_selection() {
+        let data = build_test_font();
+        assert_eq!(closure(&data, &[1], &[b"smcp"]), [1, 4]);
+        assert_eq!(closure(&data, &[1, 2], &[b"kern"]), [1, 2]);
+        assert_eq!(closure(&data, &[1, 2], &[]), [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn contextual_and_extension_lookups() {
+        let data = build_nested_test_font();
+        // The context is not evaluated so glyph 2 is not required.
+        assert_eq!(closure(&data, &[1], &[b"calt"]), [1, 3]);
+        assert_eq!(closure(&data, &[2], &[b"calt"]), [2]);
+        assert_eq!(closure(&data, &[2], &[b"ss02"]), [2, 4]);
+        assert_eq!(closure(&data, &[1, 2], &[]), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn missing_gsub() {
+        let data = FontBuilder::new(1000).build();
+        assert_eq!(closure(&data, &[0], &[]), [0]);
+    }
+}
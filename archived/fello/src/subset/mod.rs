@@ -0,0 +1,548 @@
+/*! Font subsetting.
+
+Produces a new font containing only the glyphs required to render a given
+set of characters and glyph identifiers. Glyphs are renumbered in their
+original order and composite glyphs are expanded to include their
+components.
+
+Only fonts with TrueType (`glyf`) outlines are supported. The `glyf`, `loca`,
+`hmtx` and `cmap` tables are pruned, `head`, `hhea`, `maxp` and `post` are
+updated to match and the `name`, `OS/2`, `cvt `, `fpgm`, `prep` and `gasp`
+tables are copied unchanged. All other tables, including layout and
+variation tables, are dropped so a subset of a variable font is a static
+instance at the default location.
+
+Glyphs reachable through glyph substitution can be retained by computing a
+[layout closure](layout_closure) over selected features.
+*/
+
+mod closure;
+
+use alloc::{collections::BTreeSet, vec, vec::Vec};
+use core::fmt;
+use read_fonts::{
+    tables::{
+        glyf::{CompositeGlyph, CompositeGlyphFlags, Glyf, Glyph},
+        hmtx::Hmtx,
+        loca::Loca,
+    },
+    types::{GlyphId, Tag},
+    FontRef, ReadError, TableProvider,
+};
+
+pub use closure::layout_closure;
+
+use super::{
+    meta::charmap::Charmap,
+    write::{build_font, Writer},
+};
+
+/// Tables that do not reference glyph identifiers and are copied as is.
+const PASSTHROUGH_TABLES: &[&[u8; 4]] = &[b"OS/2", b"cvt ", b"fpgm", b"gasp", b"name", b"prep"];
+
+/// Byte offset of the `indexToLocFormat` field in the `head` table.
+const HEAD_INDEX_TO_LOC_FORMAT_OFFSET: usize = 50;
+
+/// Byte offset of the `numberOfHMetrics` field in the `hhea` table.
+const HHEA_NUMBER_OF_H_METRICS_OFFSET: usize = 34;
+
+/// Byte offset of the `numGlyphs` field in the `maxp` table.
+const MAXP_NUM_GLYPHS_OFFSET: usize = 4;
+
+/// Size of the fixed header of the `post` table.
+const POST_HEADER_SIZE: usize = 32;
+
+/// Errors that may occur when subsetting a font.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The font does not contain TrueType outlines.
+    UnsupportedOutlines,
+    /// The data for the glyph was malformed.
+    InvalidGlyph(GlyphId),
+    /// Error occured when reading font data.
+    Read(ReadError),
+}
+
+impl From<ReadError> for Error {
+    fn from(e: ReadError) -> Self {
+        Self::Read(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnsupportedOutlines => write!(f, "Font does not contain TrueType outlines"),
+            Self::InvalidGlyph(gid) => write!(f, "Glyph {gid} contains invalid data"),
+            Self::Read(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Set of characters and glyphs to retain in a subset.
+///
+/// The `.notdef` glyph is always retained.
+#[derive(Clone, Default, Debug)]
+pub struct Input {
+    codepoints: BTreeSet<u32>,
+    glyphs: BTreeSet<u16>,
+    layout_features: Option<Vec<Tag>>,
+}
+
+impl Input {
+    /// Creates a new empty subset input.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the given characters to the subset.
+    pub fn add_chars<I>(&mut self, chars: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Into<u32>,
+    {
+        self.codepoints
+            .extend(chars.into_iter().map(|ch| ch.into()));
+        self
+    }
+
+    /// Adds the given glyph identifiers to the subset.
+    pub fn add_glyphs(&mut self, glyphs: impl IntoIterator<Item = GlyphId>) -> &mut Self {
+        self.glyphs
+            .extend(glyphs.into_iter().map(|glyph_id| glyph_id.to_u16()));
+        self
+    }
+
+    /// Retains all glyphs reachable through the `GSUB` lookups of the given
+    /// features.
+    ///
+    /// An empty list selects all features. See [`layout_closure`] for
+    /// details.
+    pub fn retain_layout_closure(&mut self, features: &[Tag]) -> &mut Self {
+        self.layout_features = Some(features.to_vec());
+        self
+    }
+
+    /// Returns an iterator over the characters in the subset.
+    pub fn chars(&self) -> impl Iterator<Item = u32> + '_ + Clone {
+        self.codepoints.iter().copied()
+    }
+
+    /// Returns an iterator over the glyph identifiers explicitly added to the
+    /// subset.
+    pub fn glyphs(&self) -> impl Iterator<Item = GlyphId> + '_ + Clone {
+        self.glyphs.iter().map(|gid| GlyphId::new(*gid))
+    }
+}
+
+/// Creates a new font containing only the characters and glyphs in the
+/// given input.
+pub fn subset(font: &FontRef, input: &Input) -> Result<Vec<u8>, Error> {
+    let source = SourceGlyphs::new(font)?;
+    // Collect the initial glyph set from the input characters and glyphs.
+    let charmap = Charmap::new(font);
+    let mut mappings = Vec::new();
+    let mut glyphs = BTreeSet::new();
+    glyphs.insert(0u16);
+    for codepoint in input.chars() {
+        if let Some(glyph_id) = charmap.map(codepoint) {
+            mappings.push((codepoint, glyph_id.to_u16()));
+            glyphs.insert(glyph_id.to_u16());
+        }
+    }
+    glyphs.extend(input.glyphs.iter().filter(|gid| **gid < source.num_glyphs));
+    // Add glyphs produced by substitutions.
+    if let Some(features) = &input.layout_features {
+        closure::close_over_gsub(font, &mut glyphs, features);
+        glyphs.retain(|gid| *gid < source.num_glyphs);
+    }
+    // Add all components of composite glyphs.
+    let mut stack: Vec<u16> = glyphs.iter().copied().collect();
+    while let Some(gid) = stack.pop() {
+        if let Some(Glyph::Composite(composite)) = source.glyph(gid)? {
+            for component in composite.components() {
+                let component = component.glyph.to_u16();
+                if component < source.num_glyphs && glyphs.insert(component) {
+                    stack.push(component);
+                }
+            }
+        }
+    }
+    // Glyphs are renumbered in their original order.
+    let old_gids: Vec<u16> = glyphs.into_iter().collect();
+    let new_gid = |old_gid: u16| {
+        old_gids
+            .binary_search(&old_gid)
+            .map(|index| index as u16)
+            .ok()
+    };
+    let num_glyphs = old_gids.len() as u16;
+    // Build the glyf and loca tables, remapping component glyph identifiers.
+    let mut glyf = Vec::new();
+    let mut loca = Writer::default();
+    for old_gid in &old_gids {
+        loca.u32(glyf.len() as u32);
+        let start = glyf.len();
+        match source.glyph(*old_gid)? {
+            Some(Glyph::Simple(simple)) => glyf.extend_from_slice(simple.offset_data().as_bytes()),
+            Some(Glyph::Composite(composite)) => {
+                glyf.extend_from_slice(composite.offset_data().as_bytes());
+                for (offset, component) in component_glyph_offsets(&composite) {
+                    let gid = new_gid(component.to_u16()).unwrap_or_default();
+                    write_u16(&mut glyf[start..], offset, gid)?;
+                }
+            }
+            None => {}
+        }
+        while glyf.len() % 4 != 0 {
+            glyf.push(0);
+        }
+    }
+    loca.u32(glyf.len() as u32);
+    // Always write full metrics for each glyph.
+    let mut hmtx = Writer::default();
+    for old_gid in &old_gids {
+        let (advance, lsb) = source.h_metric(*old_gid);
+        hmtx.u16(advance);
+        hmtx.i16(lsb);
+    }
+    let mut mappings: Vec<(u32, u16)> = mappings
+        .into_iter()
+        .filter_map(|(codepoint, old_gid)| Some((codepoint, new_gid(old_gid)?)))
+        .collect();
+    mappings.sort_unstable();
+    let mut head = table_bytes(font, Tag::new(b"head"))?;
+    write_u16(&mut head, HEAD_INDEX_TO_LOC_FORMAT_OFFSET, 1)?;
+    let mut hhea = table_bytes(font, Tag::new(b"hhea"))?;
+    write_u16(&mut hhea, HHEA_NUMBER_OF_H_METRICS_OFFSET, num_glyphs)?;
+    let mut maxp = table_bytes(font, Tag::new(b"maxp"))?;
+    write_u16(&mut maxp, MAXP_NUM_GLYPHS_OFFSET, num_glyphs)?;
+    let mut tables = vec![
+        (Tag::new(b"cmap"), build_cmap(&mappings)),
+        (Tag::new(b"glyf"), glyf),
+        (Tag::new(b"head"), head),
+        (Tag::new(b"hhea"), hhea),
+        (Tag::new(b"hmtx"), hmtx.0),
+        (Tag::new(b"loca"), loca.0),
+        (Tag::new(b"maxp"), maxp),
+    ];
+    // Glyph names are dropped by converting to version 3.0.
+    if let Some(post) = font.table_data(Tag::new(b"post")) {
+        if let Some(header) = post.as_bytes().get(..POST_HEADER_SIZE) {
+            let mut post = header.to_vec();
+            post[..4].copy_from_slice(&0x00030000u32.to_be_bytes());
+            tables.push((Tag::new(b"post"), post));
+        }
+    }
+    for tag in PASSTHROUGH_TABLES {
+        let tag = Tag::new(tag);
+        if let Some(data) = font.table_data(tag) {
+            tables.push((tag, data.as_bytes().to_vec()));
+        }
+    }
+    tables.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(build_font(&tables))
+}
+
+/// Access to the glyph outlines and horizontal metrics of the source font.
+struct SourceGlyphs<'a> {
+    num_glyphs: u16,
+    glyf: Glyf<'a>,
+    loca: Loca<'a>,
+    hmtx: Hmtx<'a>,
+}
+
+impl<'a> SourceGlyphs<'a> {
+    fn new(font: &FontRef<'a>) -> Result<Self, Error> {
+        if font.table_data(Tag::new(b"glyf")).is_none() {
+            return Err(Error::UnsupportedOutlines);
+        }
+        Ok(Self {
+            num_glyphs: font.maxp()?.num_glyphs(),
+            glyf: font.glyf()?,
+            loca: font.loca(None)?,
+            hmtx: font.hmtx()?,
+        })
+    }
+
+    /// Returns the glyph for the given identifier or `None` if the glyph
+    /// is empty.
+    fn glyph(&self, gid: u16) -> Result<Option<Glyph<'a>>, Error> {
+        let gid = GlyphId::new(gid);
+        self.loca
+            .get_glyf(gid, &self.glyf)
+            .map_err(|_| Error::InvalidGlyph(gid))
+    }
+
+    fn h_metric(&self, gid: u16) -> (u16, i16) {
+        let h_metrics = self.hmtx.h_metrics();
+        let index = gid as usize;
+        let advance = h_metrics
+            .get(index)
+            .or_else(|| h_metrics.last())
+            .map(|metric| metric.advance())
+            .unwrap_or_default();
+        let lsb = match h_metrics.get(index) {
+            Some(metric) => metric.side_bearing(),
+            None => self
+                .hmtx
+                .left_side_bearings()
+                .get(index - h_metrics.len())
+                .map(|lsb| lsb.get())
+                .unwrap_or_default(),
+        };
+        (advance, lsb)
+    }
+}
+
+/// Returns the byte offset and glyph identifier of each component in a
+/// composite glyph so that the identifiers can be rewritten in place.
+fn component_glyph_offsets<'a>(
+    composite: &CompositeGlyph<'a>,
+) -> impl Iterator<Item = (usize, GlyphId)> + 'a {
+    // Component records follow numberOfContours and the bounding box.
+    let mut offset = 10;
+    composite.components().map(move |component| {
+        let flags = component.flags;
+        let glyph_offset = offset + 2;
+        offset += if flags.contains(CompositeGlyphFlags::ARG_1_AND_2_ARE_WORDS) {
+            8
+        } else {
+            6
+        };
+        if flags.contains(CompositeGlyphFlags::WE_HAVE_A_SCALE) {
+            offset += 2;
+        } else if flags.contains(CompositeGlyphFlags::WE_HAVE_AN_X_AND_Y_SCALE) {
+            offset += 4;
+        } else if flags.contains(CompositeGlyphFlags::WE_HAVE_A_TWO_BY_TWO) {
+            offset += 8;
+        }
+        (glyph_offset, component.glyph)
+    })
+}
+
+/// Builds a `cmap` table with a format 4 subtable for the BMP and a format
+/// 12 subtable for the full Unicode range.
+///
+/// The format 4 subtable is omitted when the mappings require more segments
+/// than fit in its 16-bit length field.
+///
+/// Mappings must be sorted by codepoint.
+fn build_cmap(mappings: &[(u32, u16)]) -> Vec<u8> {
+    // Merge the mappings into sequential groups of (start, end, glyph).
+    let mut groups: Vec<(u32, u32, u16)> = Vec::new();
+    for (codepoint, gid) in mappings {
+        match groups.last_mut() {
+            Some(last)
+                if last.1 + 1 == *codepoint
+                    && last.2 as u32 + (last.1 - last.0) + 1 == *gid as u32 =>
+            {
+                last.1 = *codepoint;
+            }
+            _ => groups.push((*codepoint, *codepoint, *gid)),
+        }
+    }
+    // Format 4 segments are restricted to the BMP and must be terminated
+    // by a segment mapping 0xFFFF.
+    let mut segments: Vec<(u16, u16, u16)> = groups
+        .iter()
+        .filter(|group| group.0 < 0xFFFF)
+        .map(|(start, end, gid)| {
+            let end = (*end).min(0xFFFE) as u16;
+            let start = *start as u16;
+            (start, end, gid.wrapping_sub(start))
+        })
+        .collect();
+    segments.push((0xFFFF, 0xFFFF, 1));
+    let seg_count = segments.len() as u32;
+    let length = 16 + 8 * seg_count;
+    let format4 = (length <= u16::MAX as u32).then(|| {
+        let entry_selector = seg_count.ilog2();
+        let search_range = 2 * (1 << entry_selector);
+        let mut format4 = Writer::default();
+        format4.u16(4);
+        format4.u16(length as u16);
+        // language
+        format4.u16(0);
+        format4.u16((seg_count * 2) as u16);
+        format4.u16(search_range as u16);
+        format4.u16(entry_selector as u16);
+        format4.u16((seg_count * 2 - search_range) as u16);
+        for (_, end, _) in &segments {
+            format4.u16(*end);
+        }
+        // reservedPad
+        format4.u16(0);
+        for (start, _, _) in &segments {
+            format4.u16(*start);
+        }
+        for (_, _, delta) in &segments {
+            format4.u16(*delta);
+        }
+        // idRangeOffset
+        for _ in &segments {
+            format4.u16(0);
+        }
+        format4.0
+    });
+    let mut format12 = Writer::default();
+    format12.u16(12);
+    format12.u16(0);
+    format12.u32(16 + groups.len() as u32 * 12);
+    // language
+    format12.u32(0);
+    format12.u32(groups.len() as u32);
+    for (start, end, gid) in &groups {
+        format12.u32(*start);
+        format12.u32(*end);
+        format12.u32(*gid as u32);
+    }
+    let num_tables = if format4.is_some() { 2 } else { 1 };
+    let mut offset = 4 + num_tables as u32 * 8;
+    let mut w = Writer::default();
+    w.u16(0);
+    w.u16(num_tables);
+    if let Some(format4) = &format4 {
+        // Windows Unicode BMP encoding
+        w.u16(3);
+        w.u16(1);
+        w.u32(offset);
+        offset += format4.len() as u32;
+    }
+    // Windows Unicode full repertoire encoding
+    w.u16(3);
+    w.u16(10);
+    w.u32(offset);
+    if let Some(format4) = &format4 {
+        w.0.extend_from_slice(format4);
+    }
+    w.0.extend_from_slice(&format12.0);
+    w.0
+}
+
+fn table_bytes(font: &FontRef, tag: Tag) -> Result<Vec<u8>, Error> {
+    Ok(font
+        .table_data(tag)
+        .ok_or(ReadError::TableIsMissing(tag))?
+        .as_bytes()
+        .to_vec())
+}
+
+fn write_u16(data: &mut [u8], offset: usize, value: u16) -> Result<(), Error> {
+    data.get_mut(offset..offset + 2)
+        .ok_or(Error::Read(ReadError::OutOfBounds))?
+        .copy_from_slice(&value.to_be_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{meta::checksum::validate_checksums, testing::FontBuilder, MetadataProvider, Size};
+
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::new(1000);
+        let a = builder.add_glyph(600, &[&[(50, 0), (300, 700), (550, 0)]]);
+        let b = builder.add_glyph(550, &[&[(50, 0), (50, 700), (450, 700), (450, 0)]]);
+        let c = builder.add_composite_glyph(700, &[(a, 100, 0)]);
+        builder.map('A', a).map('B', b).map('C', c);
+        builder.build()
+    }
+
+    #[test]
+    fn composite_closure() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut input = Input::new();
+        input.add_chars(['C']);
+        let subset_data = subset(&font, &input).unwrap();
+        let subset_font = FontRef::new(&subset_data).unwrap();
+        // .notdef, A (as a component of C) and C
+        assert_eq!(subset_font.maxp().unwrap().num_glyphs(), 3);
+        let charmap = subset_font.charmap();
+        assert_eq!(charmap.map('C'), Some(GlyphId::new(2)));
+        assert_eq!(charmap.map('A'), None);
+        assert_eq!(charmap.map('B'), None);
+        let glyph_metrics = subset_font.glyph_metrics(Size::unscaled(), Default::default());
+        assert_eq!(glyph_metrics.advance_width(GlyphId::new(1)), Some(600.0));
+        assert_eq!(glyph_metrics.advance_width(GlyphId::new(2)), Some(700.0));
+        // The component reference must point to the renumbered glyph.
+        let Some(Glyph::Composite(glyph)) =
+            SourceGlyphs::new(&subset_font).unwrap().glyph(2).unwrap()
+        else {
+            panic!("expected a composite glyph");
+        };
+        let components: Vec<_> = component_glyph_offsets(&glyph).collect();
+        assert_eq!(components, [(12, GlyphId::new(1))]);
+        assert!(validate_checksums(&subset_font).is_valid());
+    }
+
+    #[test]
+    fn explicit_glyphs() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut input = Input::new();
+        input.add_chars(['A']).add_glyphs([GlyphId::new(2)]);
+        let subset_data = subset(&font, &input).unwrap();
+        let subset_font = FontRef::new(&subset_data).unwrap();
+        assert_eq!(subset_font.maxp().unwrap().num_glyphs(), 3);
+        let charmap = subset_font.charmap();
+        assert_eq!(charmap.map('A'), Some(GlyphId::new(1)));
+        assert_eq!(charmap.map('B'), None);
+    }
+
+    #[test]
+    fn sparse_mappings() {
+        // Every mapping produces a separate segment which overflows the
+        // length of a format 4 subtable.
+        let mut builder = FontBuilder::new(1000);
+        let a = builder.add_glyph(600, &[&[(50, 0), (300, 700), (550, 0)]]);
+        let chars: Vec<u32> = (0..9000).map(|i| 0x100 + i * 2).collect();
+        for ch in &chars {
+            builder.map(*ch, a);
+        }
+        let data = builder.build();
+        let font = FontRef::new(&data).unwrap();
+        let mut input = Input::new();
+        input.add_chars(chars.iter().copied());
+        let subset_data = subset(&font, &input).unwrap();
+        let subset_font = FontRef::new(&subset_data).unwrap();
+        let cmap = subset_font.cmap().unwrap();
+        let records = cmap.encoding_records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].encoding_id(), 10);
+        let charmap = subset_font.charmap();
+        for ch in chars {
+            assert_eq!(charmap.map(ch), Some(GlyphId::new(1)));
+            assert_eq!(charmap.map(ch + 1), None);
+        }
+        assert!(validate_checksums(&subset_font).is_valid());
+        // A smaller set keeps both subtables.
+        let mut input = Input::new();
+        input.add_chars(['\u{100}', '\u{102}']);
+        let subset_data = subset(&font, &input).unwrap();
+        let subset_font = FontRef::new(&subset_data).unwrap();
+        assert_eq!(subset_font.cmap().unwrap().encoding_records().len(), 2);
+    }
+
+    #[test]
+    fn test_data_font() {
+        let font = FontRef::new(font_test_data::VAZIRMATN_VAR).unwrap();
+        let mut input = Input::new();
+        input.add_chars(['A', 'B', '\u{0627}']);
+        let subset_data = subset(&font, &input).unwrap();
+        let subset_font = FontRef::new(&subset_data).unwrap();
+        assert!(subset_font.gvar().is_err());
+        assert!(validate_checksums(&subset_font).is_valid());
+        for ch in ['A', 'B', '\u{0627}'] {
+            assert_eq!(
+                font.charmap().map(ch).is_some(),
+                subset_font.charmap().map(ch).is_some()
+            );
+        }
+    }
+}
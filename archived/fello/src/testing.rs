@@ -0,0 +1,1007 @@
+/*! Construction of small synthetic fonts for testing.
+
+This module builds minimal TrueType fonts in memory so that tests for
+metrics, character mapping and scaling can be written without relying on
+binary font files. The generated fonts contain the `head`, `hhea`, `maxp`,
+`cmap`, `hmtx`, `loca` and `glyf` tables along with an optional `fvar`
+table and any additional raw tables.
+
+Layout tables (`GSUB` and `GPOS`) can be built with [`LayoutBuilder`] and
+the subtable serializers in [`subtables`] and added as raw tables.
+
+This module is available in unit tests and when the `testing` feature is
+enabled.
+*/
+
+use alloc::{vec, vec::Vec};
+use core::ops::{Deref, DerefMut};
+use read_fonts::{
+    tables::glyf::CompositeGlyphFlags,
+    types::{GlyphId, Tag},
+};
+
+use super::write::{build_font, Writer};
+
+/// Outline of a square glyph, 500 units on each side.
+pub const SQUARE: &[&[(i16, i16)]] = &[&[(0, 0), (0, 500), (500, 500), (500, 0)]];
+
+/// Builder for a synthetic TrueType font.
+///
+/// The builder is created with a single empty `.notdef` glyph at index 0.
+#[derive(Clone, Debug)]
+pub struct FontBuilder {
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+    glyphs: Vec<Glyph>,
+    mappings: Vec<(u32, GlyphId)>,
+    axes: Vec<Axis>,
+    extra_tables: Vec<(Tag, Vec<u8>)>,
+}
+
+#[derive(Clone, Debug)]
+struct Glyph {
+    advance_width: u16,
+    contours: Vec<Vec<(i16, i16)>>,
+    components: Vec<(GlyphId, i16, i16)>,
+    component_flags: Vec<CompositeGlyphFlags>,
+    instructions: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+struct Axis {
+    tag: Tag,
+    min: f32,
+    default: f32,
+    max: f32,
+}
+
+impl FontBuilder {
+    /// Creates a new builder for a font with the given units per em.
+    ///
+    /// The ascender and descender default to 80% and -20% of the em
+    /// respectively.
+    pub fn new(units_per_em: u16) -> Self {
+        let upem = units_per_em as i32;
+        Self {
+            units_per_em,
+            ascender: (upem * 4 / 5) as i16,
+            descender: -(upem / 5) as i16,
+            line_gap: 0,
+            glyphs: vec![Glyph {
+                advance_width: units_per_em / 2,
+                contours: vec![],
+                components: vec![],
+                component_flags: vec![],
+                instructions: vec![],
+            }],
+            mappings: vec![],
+            axes: vec![],
+            extra_tables: vec![],
+        }
+    }
+
+    /// Creates a new builder for a font with 1000 units per em containing
+    /// the given number of [`SQUARE`] glyphs with an advance width of 500
+    /// after `.notdef`.
+    pub fn with_squares(count: u16) -> Self {
+        let mut builder = Self::new(1000);
+        for _ in 0..count {
+            builder.add_glyph(500, SQUARE);
+        }
+        builder
+    }
+
+    /// Sets the vertical metrics stored in the `hhea` table.
+    pub fn vertical_metrics(&mut self, ascender: i16, descender: i16, line_gap: i16) -> &mut Self {
+        self.ascender = ascender;
+        self.descender = descender;
+        self.line_gap = line_gap;
+        self
+    }
+
+    /// Adds a glyph with the given advance width and outline and returns its
+    /// identifier.
+    ///
+    /// Each contour is a sequence of on-curve points forming a closed
+    /// polygon.
+    pub fn add_glyph(&mut self, advance_width: u16, contours: &[&[(i16, i16)]]) -> GlyphId {
+        let glyph_id = GlyphId::new(self.glyphs.len() as u16);
+        self.glyphs.push(Glyph {
+            advance_width,
+            contours: contours.iter().map(|contour| contour.to_vec()).collect(),
+            components: vec![],
+            component_flags: vec![],
+            instructions: vec![],
+        });
+        glyph_id
+    }
+
+    /// Adds a composite glyph with the given advance width and returns its
+    /// identifier.
+    ///
+    /// Each component is a glyph along with an x and y offset. Components
+    /// that refer to glyphs added later are written as given but don't
+    /// contribute to the bounds of the composite.
+    pub fn add_composite_glyph(
+        &mut self,
+        advance_width: u16,
+        components: &[(GlyphId, i16, i16)],
+    ) -> GlyphId {
+        let glyph_id = GlyphId::new(self.glyphs.len() as u16);
+        self.glyphs.push(Glyph {
+            advance_width,
+            contours: vec![],
+            components: components.to_vec(),
+            component_flags: vec![CompositeGlyphFlags::empty(); components.len()],
+            instructions: vec![],
+        });
+        glyph_id
+    }
+
+    /// Sets additional flags, such as `USE_MY_METRICS` or
+    /// `ROUND_XY_TO_GRID`, for the component at the given index of a
+    /// composite glyph.
+    pub fn component_flags(
+        &mut self,
+        glyph_id: GlyphId,
+        index: usize,
+        flags: CompositeGlyphFlags,
+    ) -> &mut Self {
+        self.glyphs[glyph_id.to_u16() as usize].component_flags[index] = flags;
+        self
+    }
+
+    /// Sets the TrueType instructions of a glyph.
+    ///
+    /// The instructions of a composite glyph run after its components have
+    /// been placed. The `maxp` table allows a stack of 32 elements when any
+    /// glyph has instructions.
+    pub fn glyph_instructions(&mut self, glyph_id: GlyphId, instructions: &[u8]) -> &mut Self {
+        self.glyphs[glyph_id.to_u16() as usize].instructions = instructions.to_vec();
+        self
+    }
+
+    /// Maps a character to the given glyph identifier.
+    pub fn map(&mut self, ch: impl Into<u32>, glyph_id: GlyphId) -> &mut Self {
+        let codepoint = ch.into();
+        match self.mappings.binary_search_by(|x| x.0.cmp(&codepoint)) {
+            Ok(index) => self.mappings[index].1 = glyph_id,
+            Err(index) => self.mappings.insert(index, (codepoint, glyph_id)),
+        }
+        self
+    }
+
+    /// Adds a variation axis with the given range to the `fvar` table.
+    pub fn add_axis(&mut self, tag: Tag, min: f32, default: f32, max: f32) -> &mut Self {
+        self.axes.push(Axis {
+            tag,
+            min,
+            default,
+            max,
+        });
+        self
+    }
+
+    /// Adds a table with the given raw data.
+    ///
+    /// This can be used to include tables that the builder does not
+    /// generate, such as layout tables.
+    pub fn add_table(&mut self, tag: Tag, data: Vec<u8>) -> &mut Self {
+        self.extra_tables.push((tag, data));
+        self
+    }
+
+    /// Builds the font and returns the resulting data.
+    pub fn build(&self) -> Vec<u8> {
+        let (glyf, loca) = self.build_glyf_loca();
+        let mut tables = vec![
+            (Tag::new(b"cmap"), self.build_cmap()),
+            (Tag::new(b"glyf"), glyf),
+            (Tag::new(b"head"), self.build_head()),
+            (Tag::new(b"hhea"), self.build_hhea()),
+            (Tag::new(b"hmtx"), self.build_hmtx()),
+            (Tag::new(b"loca"), loca),
+            (Tag::new(b"maxp"), self.build_maxp()),
+        ];
+        if !self.axes.is_empty() {
+            tables.push((Tag::new(b"fvar"), self.build_fvar()));
+        }
+        tables.extend(self.extra_tables.iter().cloned());
+        tables.sort_by(|a, b| a.0.cmp(&b.0));
+        build_font(&tables)
+    }
+
+    /// Returns the bounds of the glyph at the given index.
+    ///
+    /// Only components that precede the glyph contribute so that the
+    /// bounds of cyclic composites are well defined.
+    fn glyph_bounds(&self, index: usize) -> Option<[i16; 4]> {
+        let glyph = self.glyphs.get(index)?;
+        let simple = glyph
+            .contours
+            .iter()
+            .flatten()
+            .map(|(x, y)| [*x, *y, *x, *y]);
+        let composite = glyph.components.iter().filter_map(|(glyph_id, dx, dy)| {
+            let component_index = glyph_id.to_u16() as usize;
+            if component_index >= index {
+                return None;
+            }
+            let b = self.glyph_bounds(component_index)?;
+            Some([b[0] + dx, b[1] + dy, b[2] + dx, b[3] + dy])
+        });
+        simple.chain(composite).reduce(|a, b| {
+            [
+                a[0].min(b[0]),
+                a[1].min(b[1]),
+                a[2].max(b[2]),
+                a[3].max(b[3]),
+            ]
+        })
+    }
+
+    fn bounds(&self) -> [i16; 4] {
+        (0..self.glyphs.len())
+            .filter_map(|index| self.glyph_bounds(index))
+            .reduce(|a, b| {
+                [
+                    a[0].min(b[0]),
+                    a[1].min(b[1]),
+                    a[2].max(b[2]),
+                    a[3].max(b[3]),
+                ]
+            })
+            .unwrap_or_default()
+    }
+
+    fn build_head(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        let bounds = self.bounds();
+        w.u16(1);
+        w.u16(0);
+        // fontRevision
+        w.u32(0x00010000);
+        // checkSumAdjustment is filled in after the font is assembled
+        w.u32(0);
+        // magicNumber
+        w.u32(0x5F0F3CF5);
+        // flags
+        w.u16(0);
+        w.u16(self.units_per_em);
+        // created and modified
+        w.u32(0);
+        w.u32(0);
+        w.u32(0);
+        w.u32(0);
+        for value in bounds {
+            w.i16(value);
+        }
+        // macStyle
+        w.u16(0);
+        // lowestRecPPEM
+        w.u16(8);
+        // fontDirectionHint
+        w.i16(2);
+        // indexToLocFormat: always use long offsets
+        w.i16(1);
+        // glyphDataFormat
+        w.i16(0);
+        w.0
+    }
+
+    fn build_hhea(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        let advance_max = self
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.advance_width)
+            .max()
+            .unwrap_or_default();
+        let bounds = self.bounds();
+        w.u16(1);
+        w.u16(0);
+        w.i16(self.ascender);
+        w.i16(self.descender);
+        w.i16(self.line_gap);
+        w.u16(advance_max);
+        // minLeftSideBearing, minRightSideBearing and xMaxExtent
+        w.i16(bounds[0]);
+        w.i16(0);
+        w.i16(bounds[2]);
+        // caretSlopeRise, caretSlopeRun and caretOffset
+        w.i16(1);
+        w.i16(0);
+        w.i16(0);
+        // reserved
+        for _ in 0..4 {
+            w.i16(0);
+        }
+        // metricDataFormat
+        w.i16(0);
+        w.u16(self.glyphs.len() as u16);
+        w.0
+    }
+
+    fn build_maxp(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        let max_points = self
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.contours.iter().map(|c| c.len()).sum::<usize>())
+            .max()
+            .unwrap_or_default();
+        let max_contours = self
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.contours.len())
+            .max()
+            .unwrap_or_default();
+        w.u32(0x00010000);
+        w.u16(self.glyphs.len() as u16);
+        w.u16(max_points as u16);
+        w.u16(max_contours as u16);
+        // maxCompositePoints and maxCompositeContours
+        w.u16(0);
+        w.u16(0);
+        let max_instructions = self
+            .glyphs
+            .iter()
+            .map(|glyph| glyph.instructions.len())
+            .max()
+            .unwrap_or_default();
+        // maxZones
+        w.u16(2);
+        // maxTwilightPoints, maxStorage, maxFunctionDefs and
+        // maxInstructionDefs
+        for _ in 0..4 {
+            w.u16(0);
+        }
+        // maxStackElements and maxSizeOfInstructions
+        w.u16(if max_instructions != 0 { 32 } else { 0 });
+        w.u16(max_instructions as u16);
+        // maxComponentElements and maxComponentDepth
+        w.u16(0);
+        w.u16(0);
+        w.0
+    }
+
+    fn build_cmap(&self) -> Vec<u8> {
+        // Merge the mappings into sequential groups.
+        let mut groups: Vec<(u32, u32, u32)> = vec![];
+        for (codepoint, glyph_id) in &self.mappings {
+            let glyph_id = glyph_id.to_u16() as u32;
+            match groups.last_mut() {
+                Some(last)
+                    if last.1 + 1 == *codepoint && last.2 + (last.1 - last.0) + 1 == glyph_id =>
+                {
+                    last.1 = *codepoint;
+                }
+                _ => groups.push((*codepoint, *codepoint, glyph_id)),
+            }
+        }
+        let mut w = Writer::default();
+        w.u16(0);
+        // numTables
+        w.u16(1);
+        // Windows Unicode full repertoire encoding
+        w.u16(3);
+        w.u16(10);
+        w.u32(12);
+        // Format 12 subtable
+        w.u16(12);
+        w.u16(0);
+        w.u32(16 + groups.len() as u32 * 12);
+        // language
+        w.u32(0);
+        w.u32(groups.len() as u32);
+        for (start, end, glyph_id) in groups {
+            w.u32(start);
+            w.u32(end);
+            w.u32(glyph_id);
+        }
+        w.0
+    }
+
+    fn build_hmtx(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        for (index, glyph) in self.glyphs.iter().enumerate() {
+            w.u16(glyph.advance_width);
+            w.i16(self.glyph_bounds(index).map(|b| b[0]).unwrap_or_default());
+        }
+        w.0
+    }
+
+    fn build_glyf_loca(&self) -> (Vec<u8>, Vec<u8>) {
+        // Flag for an on-curve point with explicit 16-bit coordinates.
+        const ON_CURVE_POINT: u8 = 0x01;
+        // Component flags for composite glyphs.
+        const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+        const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+        const MORE_COMPONENTS: u16 = 0x0020;
+        const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+        let mut glyf = Writer::default();
+        let mut loca = Writer::default();
+        for (index, glyph) in self.glyphs.iter().enumerate() {
+            loca.u32(glyf.0.len() as u32);
+            let Some(bounds) = self.glyph_bounds(index) else {
+                continue;
+            };
+            if !glyph.components.is_empty() {
+                glyf.i16(-1);
+                for value in bounds {
+                    glyf.i16(value);
+                }
+                for (i, ((glyph_id, dx, dy), extra_flags)) in glyph
+                    .components
+                    .iter()
+                    .zip(&glyph.component_flags)
+                    .enumerate()
+                {
+                    let mut flags = ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES | extra_flags.bits();
+                    if i + 1 < glyph.components.len() {
+                        flags |= MORE_COMPONENTS;
+                    } else if !glyph.instructions.is_empty() {
+                        flags |= WE_HAVE_INSTRUCTIONS;
+                    }
+                    glyf.u16(flags);
+                    glyf.u16(glyph_id.to_u16());
+                    glyf.i16(*dx);
+                    glyf.i16(*dy);
+                }
+                if !glyph.instructions.is_empty() {
+                    glyf.u16(glyph.instructions.len() as u16);
+                    glyf.0.extend_from_slice(&glyph.instructions);
+                }
+                glyf.align(4);
+                continue;
+            }
+            glyf.i16(glyph.contours.len() as i16);
+            for value in bounds {
+                glyf.i16(value);
+            }
+            let mut end_point = 0;
+            for contour in &glyph.contours {
+                end_point += contour.len();
+                glyf.u16(end_point as u16 - 1);
+            }
+            glyf.u16(glyph.instructions.len() as u16);
+            glyf.0.extend_from_slice(&glyph.instructions);
+            let points = glyph.contours.iter().flatten();
+            for _ in points.clone() {
+                glyf.0.push(ON_CURVE_POINT);
+            }
+            let mut last = 0;
+            for (x, _) in points.clone() {
+                glyf.i16(x - last);
+                last = *x;
+            }
+            let mut last = 0;
+            for (_, y) in points {
+                glyf.i16(y - last);
+                last = *y;
+            }
+            glyf.align(4);
+        }
+        loca.u32(glyf.0.len() as u32);
+        (glyf.0, loca.0)
+    }
+
+    fn build_fvar(&self) -> Vec<u8> {
+        fn fixed(value: f32) -> u32 {
+            (value * 65536.0).round() as i32 as u32
+        }
+        let mut w = Writer::default();
+        let axis_count = self.axes.len() as u16;
+        w.u16(1);
+        w.u16(0);
+        // axesArrayOffset
+        w.u16(16);
+        // reserved
+        w.u16(2);
+        w.u16(axis_count);
+        // axisSize
+        w.u16(20);
+        // instanceCount and instanceSize
+        w.u16(0);
+        w.u16(4 + 4 * axis_count);
+        for axis in &self.axes {
+            w.u32(u32::from_be_bytes(axis.tag.to_be_bytes()));
+            w.u32(fixed(axis.min));
+            w.u32(fixed(axis.default));
+            w.u32(fixed(axis.max));
+            // flags and axisNameID
+            w.u16(0);
+            w.u16(256);
+        }
+        w.0
+    }
+}
+
+/// Builder for a synthetic `GSUB` or `GPOS` table.
+///
+/// Features reference lookups and scripts reference features by the
+/// indices returned when they were added. Each script only contains a
+/// default language system. Lookup subtables are serialized with the
+/// functions in [`subtables`].
+#[derive(Clone, Default, Debug)]
+pub struct LayoutBuilder {
+    scripts: Vec<(Tag, Vec<u16>)>,
+    features: Vec<(Tag, Vec<u16>)>,
+    lookups: Vec<(u16, Vec<Vec<u8>>)>,
+}
+
+impl LayoutBuilder {
+    /// Creates a new builder with empty script, feature and lookup lists.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a script with a default language system referencing the
+    /// features at the given indices.
+    pub fn add_script(&mut self, tag: Tag, features: &[u16]) -> &mut Self {
+        self.scripts.push((tag, features.to_vec()));
+        self
+    }
+
+    /// Adds a feature referencing the lookups at the given indices and
+    /// returns its index.
+    pub fn add_feature(&mut self, tag: Tag, lookups: &[u16]) -> u16 {
+        self.features.push((tag, lookups.to_vec()));
+        self.features.len() as u16 - 1
+    }
+
+    /// Adds a lookup of the given type containing the serialized subtables
+    /// and returns its index.
+    pub fn add_lookup(&mut self, lookup_type: u16, subtables: Vec<Vec<u8>>) -> u16 {
+        self.lookups.push((lookup_type, subtables));
+        self.lookups.len() as u16 - 1
+    }
+
+    /// Builds the table and returns the resulting data.
+    pub fn build(&self) -> Vec<u8> {
+        let mut script_list = TableWriter::default();
+        script_list.u16(self.scripts.len() as u16);
+        for (tag, features) in &self.scripts {
+            let mut lang_sys = Writer::default();
+            // lookupOrderOffset and requiredFeatureIndex
+            lang_sys.u16(0);
+            lang_sys.u16(0xFFFF);
+            lang_sys.u16(features.len() as u16);
+            for index in features {
+                lang_sys.u16(*index);
+            }
+            let mut script = TableWriter::default();
+            script.offset(lang_sys.0);
+            // langSysCount
+            script.u16(0);
+            script_list.u32(u32::from_be_bytes(tag.to_be_bytes()));
+            script_list.offset(script.finish());
+        }
+        let mut feature_list = TableWriter::default();
+        feature_list.u16(self.features.len() as u16);
+        for (tag, lookups) in &self.features {
+            let mut feature = Writer::default();
+            // featureParamsOffset
+            feature.u16(0);
+            feature.u16(lookups.len() as u16);
+            for index in lookups {
+                feature.u16(*index);
+            }
+            feature_list.u32(u32::from_be_bytes(tag.to_be_bytes()));
+            feature_list.offset(feature.0);
+        }
+        let mut lookup_list = TableWriter::default();
+        lookup_list.u16(self.lookups.len() as u16);
+        for (lookup_type, subtables) in &self.lookups {
+            let mut lookup = TableWriter::default();
+            lookup.u16(*lookup_type);
+            // lookupFlag
+            lookup.u16(0);
+            lookup.u16(subtables.len() as u16);
+            for subtable in subtables {
+                lookup.offset(subtable.clone());
+            }
+            lookup_list.offset(lookup.finish());
+        }
+        let mut table = TableWriter::default();
+        table.u32(0x00010000);
+        table.offset(script_list.finish());
+        table.offset(feature_list.finish());
+        table.offset(lookup_list.finish());
+        table.finish()
+    }
+}
+
+/// Serializers for layout subtables.
+///
+/// Glyph identifiers in coverage tables must be sorted.
+pub mod subtables {
+    use super::{GlyphId, TableWriter, Vec, Writer};
+
+    /// Serializes a format 1 coverage table containing the given glyphs.
+    pub fn coverage(glyphs: &[GlyphId]) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u16(1);
+        w.u16(glyphs.len() as u16);
+        for glyph_id in glyphs {
+            w.u16(glyph_id.to_u16());
+        }
+        w.0
+    }
+
+    /// Serializes a format 2 coverage table containing a single range of
+    /// glyphs.
+    pub fn range_coverage(first: GlyphId, last: GlyphId) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u16(2);
+        w.u16(1);
+        w.u16(first.to_u16());
+        w.u16(last.to_u16());
+        // startCoverageIndex
+        w.u16(0);
+        w.0
+    }
+
+    /// Serializes a format 1 single substitution that adds a delta to each
+    /// covered glyph.
+    pub fn single_subst_delta(coverage: Vec<u8>, delta: i16) -> Vec<u8> {
+        let mut w = TableWriter::default();
+        w.u16(1);
+        w.offset(coverage);
+        w.i16(delta);
+        w.finish()
+    }
+
+    /// Serializes a format 2 single substitution with one substitute per
+    /// covered glyph.
+    pub fn single_subst(coverage: Vec<u8>, substitutes: &[GlyphId]) -> Vec<u8> {
+        let mut w = TableWriter::default();
+        w.u16(2);
+        w.offset(coverage);
+        glyph_array(&mut w, substitutes);
+        w.finish()
+    }
+
+    /// Serializes an alternate substitution with one set of alternates per
+    /// covered glyph.
+    pub fn alternate_subst(coverage: Vec<u8>, alternates: &[&[GlyphId]]) -> Vec<u8> {
+        let mut w = TableWriter::default();
+        w.u16(1);
+        w.offset(coverage);
+        w.u16(alternates.len() as u16);
+        for set in alternates {
+            let mut alternate_set = Writer::default();
+            glyph_array(&mut alternate_set, set);
+            w.offset(alternate_set.0);
+        }
+        w.finish()
+    }
+
+    /// Serializes a ligature substitution with one set of ligatures per
+    /// covered glyph.
+    ///
+    /// Each ligature is the ligature glyph followed by the components that
+    /// follow the covered glyph.
+    pub fn ligature_subst(coverage: Vec<u8>, ligatures: &[&[(GlyphId, &[GlyphId])]]) -> Vec<u8> {
+        let mut w = TableWriter::default();
+        w.u16(1);
+        w.offset(coverage);
+        w.u16(ligatures.len() as u16);
+        for set in ligatures {
+            let mut ligature_set = TableWriter::default();
+            ligature_set.u16(set.len() as u16);
+            for (ligature_glyph, components) in *set {
+                let mut ligature = Writer::default();
+                ligature.u16(ligature_glyph.to_u16());
+                ligature.u16(components.len() as u16 + 1);
+                for glyph_id in *components {
+                    ligature.u16(glyph_id.to_u16());
+                }
+                ligature_set.offset(ligature.0);
+            }
+            w.offset(ligature_set.finish());
+        }
+        w.finish()
+    }
+
+    /// Serializes a format 3 chained contextual subtable with the given
+    /// coverage tables for each sequence and (sequence index, lookup index)
+    /// records.
+    pub fn chained_context(
+        backtrack: Vec<Vec<u8>>,
+        input: Vec<Vec<u8>>,
+        lookahead: Vec<Vec<u8>>,
+        lookups: &[(u16, u16)],
+    ) -> Vec<u8> {
+        let mut w = TableWriter::default();
+        w.u16(3);
+        for coverages in [backtrack, input, lookahead] {
+            w.u16(coverages.len() as u16);
+            for coverage in coverages {
+                w.offset(coverage);
+            }
+        }
+        w.u16(lookups.len() as u16);
+        for (sequence_index, lookup_index) in lookups {
+            w.u16(*sequence_index);
+            w.u16(*lookup_index);
+        }
+        w.finish()
+    }
+
+    /// Serializes an extension subtable wrapping a subtable of the given
+    /// lookup type.
+    pub fn extension(lookup_type: u16, subtable: Vec<u8>) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u16(1);
+        w.u16(lookup_type);
+        w.u32(8);
+        w.0.extend_from_slice(&subtable);
+        w.0
+    }
+
+    /// Serializes a mark-to-base attachment subtable.
+    ///
+    /// Each mark is a class and an anchor point and each base has an
+    /// anchor point for every mark class.
+    pub fn mark_base_pos(
+        mark_coverage: Vec<u8>,
+        base_coverage: Vec<u8>,
+        marks: &[(u16, (i16, i16))],
+        bases: &[&[(i16, i16)]],
+    ) -> Vec<u8> {
+        let class_count = bases
+            .first()
+            .map(|anchors| anchors.len())
+            .unwrap_or_default();
+        let mut mark_array = TableWriter::default();
+        mark_array.u16(marks.len() as u16);
+        for (class, anchor) in marks {
+            mark_array.u16(*class);
+            mark_array.offset(anchor_table(*anchor));
+        }
+        let mut base_array = TableWriter::default();
+        base_array.u16(bases.len() as u16);
+        for anchors in bases {
+            for anchor in *anchors {
+                base_array.offset(anchor_table(*anchor));
+            }
+        }
+        let mut w = TableWriter::default();
+        w.u16(1);
+        w.offset(mark_coverage);
+        w.offset(base_coverage);
+        w.u16(class_count as u16);
+        w.offset(mark_array.finish());
+        w.offset(base_array.finish());
+        w.finish()
+    }
+
+    /// Serializes a format 2 pair adjustment subtable that adjusts the
+    /// advance of the first glyph of a single pair.
+    ///
+    /// The optional device table for the adjustment is given as the start
+    /// and end sizes, the delta format and the packed deltas.
+    pub fn pair_pos(
+        first: GlyphId,
+        second: GlyphId,
+        x_advance: i16,
+        device: Option<(u16, u16, u16, &[u16])>,
+    ) -> Vec<u8> {
+        // X_ADVANCE, with X_ADVANCE_DEVICE if there is a device table
+        let value_format = if device.is_some() { 0x0044 } else { 0x0004 };
+        // All covered glyphs are in class 0 of the first class definition
+        // while the second glyph is in class 1 of the second.
+        let mut class_def1 = Writer::default();
+        class_def1.u16(1);
+        class_def1.u16(0);
+        class_def1.u16(0);
+        let mut class_def2 = Writer::default();
+        class_def2.u16(1);
+        class_def2.u16(second.to_u16());
+        class_def2.u16(1);
+        class_def2.u16(1);
+        let mut w = TableWriter::default();
+        w.u16(2);
+        w.offset(coverage(&[first]));
+        w.u16(value_format);
+        w.u16(0);
+        w.offset(class_def1.0);
+        w.offset(class_def2.0);
+        // class1Count and class2Count
+        w.u16(1);
+        w.u16(2);
+        w.i16(0);
+        if device.is_some() {
+            w.u16(0);
+        }
+        w.i16(x_advance);
+        if let Some((start_size, end_size, delta_format, deltas)) = device {
+            let mut table = Writer::default();
+            table.u16(start_size);
+            table.u16(end_size);
+            table.u16(delta_format);
+            for delta in deltas {
+                table.u16(*delta);
+            }
+            w.offset(table.0);
+        }
+        w.finish()
+    }
+
+    /// Serializes a format 1 anchor table.
+    fn anchor_table((x, y): (i16, i16)) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u16(1);
+        w.i16(x);
+        w.i16(y);
+        w.0
+    }
+
+    /// Writes a count prefixed array of glyph identifiers.
+    fn glyph_array(w: &mut Writer, glyphs: &[GlyphId]) {
+        w.u16(glyphs.len() as u16);
+        for glyph_id in glyphs {
+            w.u16(glyph_id.to_u16());
+        }
+    }
+}
+
+/// Writer for a table containing 16-bit offsets to subtables which are
+/// appended when the table is finished.
+#[derive(Default)]
+struct TableWriter {
+    data: Writer,
+    subtables: Vec<(usize, Vec<u8>)>,
+}
+
+impl TableWriter {
+    /// Writes a placeholder offset for the given subtable.
+    fn offset(&mut self, subtable: Vec<u8>) {
+        self.subtables.push((self.data.0.len(), subtable));
+        self.data.u16(0);
+    }
+
+    /// Appends the subtables and resolves their offsets.
+    fn finish(mut self) -> Vec<u8> {
+        for (position, subtable) in self.subtables {
+            let offset = self.data.0.len() as u16;
+            self.data.0[position..position + 2].copy_from_slice(&offset.to_be_bytes());
+            self.data.0.extend_from_slice(&subtable);
+        }
+        self.data.0
+    }
+}
+
+impl Deref for TableWriter {
+    type Target = Writer;
+
+    fn deref(&self) -> &Writer {
+        &self.data
+    }
+}
+
+impl DerefMut for TableWriter {
+    fn deref_mut(&mut self) -> &mut Writer {
+        &mut self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{meta::checksum::validate_checksums, MetadataProvider, Size};
+    use read_fonts::FontRef;
+
+    fn build_test_font() -> Vec<u8> {
+        let mut builder = FontBuilder::new(1000);
+        let a = builder.add_glyph(600, &[&[(50, 0), (300, 700), (550, 0)]]);
+        let b = builder.add_glyph(
+            550,
+            &[
+                &[(50, 0), (50, 700), (450, 700), (450, 0)],
+                &[(150, 100), (350, 100), (350, 600), (150, 600)],
+            ],
+        );
+        builder
+            .vertical_metrics(800, -200, 100)
+            .map('A', a)
+            .map('B', b)
+            .map('\u{1F600}', b)
+            .add_axis(Tag::new(b"wght"), 100.0, 400.0, 900.0);
+        builder.build()
+    }
+
+    #[test]
+    fn checksums() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        assert!(validate_checksums(&font).is_valid());
+    }
+
+    #[test]
+    fn charmap() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let charmap = font.charmap();
+        assert_eq!(charmap.map('A'), Some(GlyphId::new(1)));
+        assert_eq!(charmap.map('B'), Some(GlyphId::new(2)));
+        assert_eq!(charmap.map('\u{1F600}'), Some(GlyphId::new(2)));
+        assert_eq!(charmap.map('C'), None);
+    }
+
+    #[test]
+    fn metrics() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let metrics = font.metrics(Size::unscaled(), Default::default());
+        assert_eq!(metrics.units_per_em, 1000);
+        assert_eq!(metrics.glyph_count, 3);
+        assert_eq!(metrics.ascent, 800.0);
+        assert_eq!(metrics.descent, -200.0);
+        assert_eq!(metrics.leading, 100.0);
+        let glyph_metrics = font.glyph_metrics(Size::unscaled(), Default::default());
+        assert_eq!(glyph_metrics.advance_width(GlyphId::new(1)), Some(600.0));
+        assert_eq!(glyph_metrics.left_side_bearing(GlyphId::new(2)), Some(50.0));
+    }
+
+    #[test]
+    fn axes() {
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let axes = font.axes();
+        assert_eq!(axes.len(), 1);
+        let axis = axes.get(0).unwrap();
+        assert_eq!(axis.tag(), Tag::new(b"wght"));
+        assert_eq!(axis.min_value(), 100.0);
+        assert_eq!(axis.default_value(), 400.0);
+        assert_eq!(axis.max_value(), 900.0);
+    }
+
+    #[cfg(feature = "scale")]
+    #[test]
+    fn outlines() {
+        use crate::scale::{Context, Pen};
+
+        #[derive(Default)]
+        struct CountingPen {
+            move_to: usize,
+            line_to: usize,
+            close: usize,
+        }
+
+        impl Pen for CountingPen {
+            fn move_to(&mut self, _x: f32, _y: f32) {
+                self.move_to += 1;
+            }
+
+            fn line_to(&mut self, _x: f32, _y: f32) {
+                self.line_to += 1;
+            }
+
+            fn quad_to(&mut self, _x0: f32, _y0: f32, _x1: f32, _y1: f32) {
+                panic!("unexpected quad_to");
+            }
+
+            fn curve_to(&mut self, _x0: f32, _y0: f32, _x1: f32, _y1: f32, _x2: f32, _y2: f32) {
+                panic!("unexpected curve_to");
+            }
+
+            fn close(&mut self) {
+                self.close += 1;
+            }
+        }
+
+        let data = build_test_font();
+        let font = FontRef::new(&data).unwrap();
+        let mut cx = Context::new();
+        let mut scaler = cx.new_scaler().build(&font);
+        let mut pen = CountingPen::default();
+        scaler.outline(GlyphId::new(2), &mut pen).unwrap();
+        assert_eq!(pen.move_to, 2);
+        assert_eq!(pen.close, 2);
+        assert!(pen.line_to >= 6);
+    }
+}
@@ -0,0 +1,69 @@
+//! Serialization helpers for font data.
+
+use alloc::vec::Vec;
+use read_fonts::types::Tag;
+
+use super::meta::checksum::{checksum, CHECKSUM_ADJUSTMENT_MAGIC, HEAD_CHECKSUM_ADJUSTMENT_OFFSET};
+
+/// Assembles the table directory and table data into a font file and
+/// computes the `head` checksum adjustment.
+///
+/// Tables must be sorted by tag.
+pub(crate) fn build_font(tables: &[(Tag, Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = (num_tables as u32).checked_ilog2().unwrap_or(0) as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+    let range_shift = num_tables * 16 - search_range;
+    let mut w = Writer::default();
+    w.u32(0x00010000);
+    w.u16(num_tables);
+    w.u16(search_range);
+    w.u16(entry_selector);
+    w.u16(range_shift);
+    let mut offset = 12 + tables.len() * 16;
+    let mut head_offset = None;
+    for (tag, data) in tables {
+        if *tag == Tag::new(b"head") {
+            head_offset = Some(offset);
+        }
+        w.u32(u32::from_be_bytes(tag.to_be_bytes()));
+        w.u32(checksum(data));
+        w.u32(offset as u32);
+        w.u32(data.len() as u32);
+        offset += (data.len() + 3) & !3;
+    }
+    for (_, data) in tables {
+        w.0.extend_from_slice(data);
+        w.align(4);
+    }
+    if let Some(head_offset) = head_offset {
+        let start = head_offset + HEAD_CHECKSUM_ADJUSTMENT_OFFSET;
+        w.0[start..start + 4].fill(0);
+        let adjustment = CHECKSUM_ADJUSTMENT_MAGIC.wrapping_sub(checksum(&w.0));
+        w.0[start..start + 4].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    w.0
+}
+
+#[derive(Default)]
+pub(crate) struct Writer(pub Vec<u8>);
+
+impl Writer {
+    pub fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn i16(&mut self, value: i16) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub fn align(&mut self, alignment: usize) {
+        while self.0.len() % alignment != 0 {
+            self.0.push(0);
+        }
+    }
+}
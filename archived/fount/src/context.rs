@@ -12,7 +12,7 @@ use swash::text::Script;
 #[derive(Clone)]
 pub struct FontContext {
     library: Library,
-    user: RefCell<Arc<(u64, CollectionData)>>,
+    user: RefCell<Arc<(u64, UserCollectionData)>>,
 }
 
 impl FontContext {
@@ -59,6 +59,9 @@ impl FontContext {
     }
 
     /// Returns the font family entry for the specified name.
+    ///
+    /// User layers are searched in priority order before the system
+    /// collection.
     pub fn family_by_name<'a>(&'a self, name: &str) -> Option<FamilyEntry> {
         self.sync_user();
         if let Some(family) = self.user.borrow().1.family_by_name(name) {
@@ -90,11 +93,12 @@ impl FontContext {
 
     /// Loads the font data for the specified source.
     pub fn load(&self, id: SourceId) -> Option<FontData> {
+        let observer = self.library.inner.observer.as_deref();
         if id.is_user_font() {
             self.sync_user();
-            self.user.borrow().1.load(id)
+            self.user.borrow().1.load(id, observer)
         } else {
-            self.library.inner.system.load(id)
+            self.library.inner.system.load(id, observer)
         }
     }
 
@@ -118,12 +122,22 @@ impl FontContext {
 
     /// Registers the fonts contained in the specified data. Returns identifiers for
     /// the families and fonts added to the context.
+    ///
+    /// Fonts are added to the [application](UserLayer::App) layer.
     pub fn register_fonts(&self, data: Vec<u8>) -> Option<Registration> {
+        self.register_fonts_in(UserLayer::App, data)
+    }
+
+    /// Registers the fonts contained in the specified data with the given
+    /// layer. Returns identifiers for the families and fonts added to the
+    /// context.
+    pub fn register_fonts_in(&self, layer: UserLayer, data: Vec<u8>) -> Option<Registration> {
         use super::scan::FontScanner;
-        let mut scanner = FontScanner::default();
-        let mut collection = self.library.inner.user.write().unwrap();
+        let mut scanner = FontScanner::new(self.library.inner.grouping);
+        let mut user = self.library.inner.user.write().unwrap();
         let mut reg = Registration::default();
-        let count = collection
+        let count = user
+            .layer_mut(layer)
             .add_fonts(&mut scanner, FontData::new(data), Some(&mut reg), None)
             .unwrap_or(0);
         if count != 0 {
@@ -137,6 +151,23 @@ impl FontContext {
         }
     }
 
+    /// Removes all fonts registered with the given layer.
+    ///
+    /// Identifiers for families, fonts and sources in the layer become
+    /// invalid and may refer to different fonts if new fonts are later
+    /// registered with the same layer.
+    pub fn clear_layer(&self, layer: UserLayer) {
+        let mut user = self.library.inner.user.write().unwrap();
+        if user.layer(layer).sources.is_empty() {
+            return;
+        }
+        user.clear_layer(layer);
+        self.library
+            .inner
+            .user_version
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
     fn sync_user(&self) {
         let user_version = self.library.inner.user_version.load(Ordering::Relaxed);
         if self.user.borrow().0 != user_version {
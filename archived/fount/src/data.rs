@@ -1,8 +1,11 @@
 use super::font::*;
 use super::id::*;
+use super::observer::LoadObserver;
 use super::*;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use swash::text::Script;
 use swash::{Attributes, CacheKey, Stretch, Style, Weight};
 
@@ -51,7 +54,7 @@ impl Clone for SourceData {
 
 #[derive(Clone, Default)]
 pub struct CollectionData {
-    pub is_user: bool,
+    pub user_layer: Option<UserLayer>,
     pub families: Vec<Arc<FamilyData>>,
     pub fonts: Vec<FontData>,
     pub sources: Vec<SourceData>,
@@ -105,7 +108,11 @@ impl CollectionData {
         })
     }
 
-    pub fn load(&self, id: SourceId) -> Option<super::font::FontData> {
+    pub fn load(
+        &self,
+        id: SourceId,
+        observer: Option<&dyn LoadObserver>,
+    ) -> Option<super::font::FontData> {
         let index = id.to_usize();
         let source_data = self.sources.get(index)?;
         let path: &str = match &source_data.kind {
@@ -116,7 +123,7 @@ impl CollectionData {
             inner: SourcePathsInner::Static(&[]),
             pos: 0,
         };
-        load_source(paths, path, &source_data.status)
+        load_source(paths, path, &source_data.status, observer)
     }
 
     pub fn clone_into(&self, other: &mut Self) {
@@ -133,6 +140,73 @@ impl CollectionData {
     }
 }
 
+/// Collections of user registered fonts, one per layer.
+#[derive(Clone)]
+pub struct UserCollectionData {
+    pub layers: [CollectionData; UserLayer::COUNT],
+}
+
+impl Default for UserCollectionData {
+    fn default() -> Self {
+        let layer = |index| CollectionData {
+            user_layer: UserLayer::from_index(index),
+            ..Default::default()
+        };
+        Self {
+            layers: [layer(0), layer(1), layer(2)],
+        }
+    }
+}
+
+impl UserCollectionData {
+    pub fn layer(&self, layer: UserLayer) -> &CollectionData {
+        &self.layers[layer as usize]
+    }
+
+    pub fn layer_mut(&mut self, layer: UserLayer) -> &mut CollectionData {
+        &mut self.layers[layer as usize]
+    }
+
+    pub fn clear_layer(&mut self, layer: UserLayer) {
+        *self.layer_mut(layer) = CollectionData {
+            user_layer: Some(layer),
+            ..Default::default()
+        };
+    }
+
+    pub fn family(&self, id: FamilyId) -> Option<FamilyEntry> {
+        self.layer(id.user_layer()?).family(id)
+    }
+
+    pub fn family_by_name(&self, name: &str) -> Option<FamilyEntry> {
+        UserLayer::PRIORITY_ORDER
+            .iter()
+            .find_map(|layer| self.layer(*layer).family_by_name(name))
+    }
+
+    pub fn font(&self, id: FontId) -> Option<FontEntry> {
+        self.layer(id.user_layer()?).font(id)
+    }
+
+    pub fn source(&self, id: SourceId) -> Option<SourceEntry> {
+        self.layer(id.user_layer()?).source(id)
+    }
+
+    pub fn load(
+        &self,
+        id: SourceId,
+        observer: Option<&dyn LoadObserver>,
+    ) -> Option<super::font::FontData> {
+        self.layer(id.user_layer()?).load(id, observer)
+    }
+
+    pub fn clone_into(&self, other: &mut Self) {
+        for (layer, other_layer) in self.layers.iter().zip(&mut other.layers) {
+            layer.clone_into(other_layer);
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct FallbackData {
     pub default_families: Vec<FamilyId>,
@@ -277,7 +351,11 @@ impl StaticCollection {
             .map(|family| family.name)
     }
 
-    pub fn load(&self, id: SourceId) -> Option<super::font::FontData> {
+    pub fn load(
+        &self,
+        id: SourceId,
+        observer: Option<&dyn LoadObserver>,
+    ) -> Option<super::font::FontData> {
         let index = id.to_usize();
         let paths = SourcePaths {
             inner: SourcePathsInner::Static(self.data.search_paths),
@@ -287,6 +365,7 @@ impl StaticCollection {
             paths,
             self.data.sources.get(index)?.file_name,
             self.sources.get(index)?,
+            observer,
         )
     }
 }
@@ -295,6 +374,7 @@ fn load_source(
     source_paths: SourcePaths,
     path: &str,
     status: &RwLock<SourceDataStatus>,
+    observer: Option<&dyn LoadObserver>,
 ) -> Option<super::font::FontData> {
     match &*status.read().unwrap() {
         SourceDataStatus::Present(data) => {
@@ -315,16 +395,23 @@ fn load_source(
         SourceDataStatus::Error => return None,
         _ => {}
     }
+    let start = Instant::now();
     let mut pathbuf = String::default();
     for base_path in source_paths {
         pathbuf.clear();
         pathbuf.push_str(base_path);
         pathbuf.push_str(path);
         if let Ok(data) = super::font::FontData::from_file(&pathbuf) {
+            if let Some(observer) = observer {
+                observer.source_loaded(Path::new(&pathbuf), start.elapsed());
+            }
             *status = SourceDataStatus::Present(data.downgrade());
             return Some(data);
         }
     }
+    if let Some(observer) = observer {
+        observer.source_failed(Path::new(path));
+    }
     *status = SourceDataStatus::Error;
     None
 }
@@ -398,10 +485,14 @@ impl SystemCollectionData {
         }
     }
 
-    pub fn load(&self, id: SourceId) -> Option<super::font::FontData> {
+    pub fn load(
+        &self,
+        id: SourceId,
+        observer: Option<&dyn LoadObserver>,
+    ) -> Option<super::font::FontData> {
         match self {
-            Self::Static(data) => data.load(id),
-            Self::Scanned(data) => data.collection.load(id),
+            Self::Static(data) => data.load(id, observer),
+            Self::Scanned(data) => data.collection.load(id, observer),
         }
     }
 
@@ -14,7 +14,11 @@ pub struct FontId(pub(crate) u32);
 #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash, Debug)]
 pub struct SourceId(pub(crate) u32);
 
-const INDEX_MASK: u32 = 0x7FFFFFFF;
+use super::library::UserLayer;
+
+const INDEX_MASK: u32 = 0x1FFFFFFF;
+const LAYER_SHIFT: u32 = 29;
+const LAYER_MASK: u32 = 0x60000000;
 const USER_BIT: u32 = 0x80000000;
 
 macro_rules! impl_id {
@@ -24,19 +28,19 @@ macro_rules! impl_id {
                 Self(index)
             }
 
-            pub(crate) const fn new_user(index: u32) -> Self {
-                Self(index | USER_BIT)
+            pub(crate) const fn new_user(index: u32, layer: UserLayer) -> Self {
+                Self(index | USER_BIT | (layer as u32) << LAYER_SHIFT)
             }
 
-            pub(crate) fn alloc(index: usize, is_user: bool) -> Option<Self> {
-                if index >= i32::MAX as usize {
+            pub(crate) fn alloc(index: usize, layer: Option<UserLayer>) -> Option<Self> {
+                if index > INDEX_MASK as usize {
                     return None;
                 }
-                let mut id = index as u32;
-                if is_user {
-                    id |= USER_BIT;
-                }
-                Some(Self(id))
+                let id = index as u32;
+                Some(match layer {
+                    Some(layer) => Self::new_user(id, layer),
+                    None => Self::new(id),
+                })
             }
 
             /// Returns true if the identifier represents a dynamically
@@ -45,6 +49,15 @@ macro_rules! impl_id {
                 self.0 & USER_BIT != 0
             }
 
+            /// Returns the layer containing the font if the identifier
+            /// represents a dynamically registered user font.
+            pub fn user_layer(self) -> Option<UserLayer> {
+                if !self.is_user_font() {
+                    return None;
+                }
+                UserLayer::from_index(((self.0 & LAYER_MASK) >> LAYER_SHIFT) as usize)
+            }
+
             pub(crate) fn to_usize(self) -> usize {
                 (self.0 & INDEX_MASK) as usize
             }
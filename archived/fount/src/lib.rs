@@ -15,6 +15,7 @@ mod data;
 mod font;
 mod id;
 mod library;
+mod observer;
 mod scan;
 mod script_tags;
 
@@ -22,7 +23,8 @@ pub use context::FontContext;
 pub use data::SourcePaths;
 pub use font::FontData;
 pub use id::{FamilyId, FontId, SourceId};
-pub use library::{Library, LibraryBuilder};
+pub use library::{FamilyGrouping, Library, LibraryBuilder, UserLayer, FONT_PATH_ENV_VAR};
+pub use observer::LoadObserver;
 
 pub use swash::text::Language as Locale;
 
@@ -286,7 +288,7 @@ enum FontFamilyKind {
 /// Iterator over the font families in a font library.
 #[derive(Clone)]
 pub struct Families {
-    user: Arc<(u64, CollectionData)>,
+    user: Arc<(u64, UserCollectionData)>,
     library: Library,
     pos: usize,
     stage: u8,
@@ -297,15 +299,16 @@ impl Iterator for Families {
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
-            if self.stage == 0 {
-                let len = self.user.1.families.len();
+            if let Some(layer) = UserLayer::PRIORITY_ORDER.get(self.stage as usize) {
+                let len = self.user.1.layer(*layer).families.len();
                 if self.pos >= len {
-                    self.stage = 1;
+                    self.stage += 1;
+                    self.pos = 0;
                     continue;
                 }
                 let pos = self.pos;
                 self.pos += 1;
-                return self.user.1.family(FamilyId::new_user(pos as u32));
+                return self.user.1.family(FamilyId::new_user(pos as u32, *layer));
             } else {
                 let pos = self.pos;
                 self.pos += 1;
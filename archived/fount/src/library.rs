@@ -1,4 +1,5 @@
 use super::data::*;
+use super::observer::LoadObserver;
 use crate::scan::{scan_path, FontScanner};
 use std::io;
 use std::path::Path;
@@ -17,14 +18,18 @@ pub struct Library {
 }
 
 impl Library {
-    fn new(system: SystemCollectionData) -> Self {
-        let mut user = CollectionData::default();
-        user.is_user = true;
+    fn new(
+        system: SystemCollectionData,
+        observer: Option<Arc<dyn LoadObserver>>,
+        grouping: FamilyGrouping,
+    ) -> Self {
         Self {
             inner: Arc::new(Inner {
                 system,
-                user: Arc::new(RwLock::new(user)),
+                user: Default::default(),
                 user_version: Arc::new(AtomicU64::new(0)),
+                observer,
+                grouping,
             }),
         }
     }
@@ -35,14 +40,69 @@ impl Default for Library {
     fn default() -> Self {
         let system =
             SystemCollectionData::Static(StaticCollection::new(&super::platform::STATIC_DATA));
-        Self::new(system)
+        Self::new(system, None, FamilyGrouping::default())
     }
 }
 
 pub struct Inner {
     pub system: SystemCollectionData,
-    pub user: Arc<RwLock<CollectionData>>,
+    pub user: Arc<RwLock<UserCollectionData>>,
     pub user_version: Arc<AtomicU64>,
+    pub observer: Option<Arc<dyn LoadObserver>>,
+    pub grouping: FamilyGrouping,
+}
+
+/// Strategy for grouping fonts into families when scanning.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum FamilyGrouping {
+    /// Groups static fonts by family name (name ID 1) and variable fonts by
+    /// typographic family name (name ID 16). Fonts such as "Roboto
+    /// Condensed" form families separate from "Roboto".
+    #[default]
+    Legacy,
+    /// Groups fonts by WWS family name (name ID 21), falling back to the
+    /// typographic family name (name ID 16) and then the family name (name
+    /// ID 1). This matches the weight, width and slope model used by
+    /// DirectWrite: fonts that differ only in those properties share a
+    /// family and are distinguished by their attributes.
+    Typographic,
+}
+
+/// Layer of user registered fonts.
+///
+/// Each layer is an independent collection that can be cleared wholesale
+/// with [`FontContext::clear_layer`](super::FontContext::clear_layer).
+/// Lookups by name cascade through the layers in priority order
+/// (transient, then document, then application) before consulting the
+/// system collection.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, Debug)]
+#[repr(u8)]
+pub enum UserLayer {
+    /// Fonts bundled with or registered by the application. These live
+    /// for the lifetime of the library.
+    #[default]
+    App = 0,
+    /// Fonts embedded in the currently open document.
+    Document = 1,
+    /// Short lived fonts, such as those used for previews.
+    Transient = 2,
+}
+
+impl UserLayer {
+    /// Number of user layers.
+    pub(crate) const COUNT: usize = 3;
+
+    /// All layers in priority order, from highest to lowest.
+    pub const PRIORITY_ORDER: [Self; Self::COUNT] = [Self::Transient, Self::Document, Self::App];
+
+    pub(crate) fn from_index(index: usize) -> Option<Self> {
+        Some(match index {
+            0 => Self::App,
+            1 => Self::Document,
+            2 => Self::Transient,
+            _ => return None,
+        })
+    }
 }
 
 /// Builder for configuring a font library.
@@ -51,23 +111,92 @@ pub struct LibraryBuilder {
     scanner: FontScanner,
     system: CollectionData,
     fallback: FallbackData,
+    excludes: Vec<String>,
+    observer: Option<Arc<dyn LoadObserver>>,
 }
 
+/// Name of the environment variable that specifies additional font
+/// directories.
+///
+/// The value is a list of paths in the same form as the `PATH` variable
+/// and is split with [`std::env::split_paths`], so the separator and any
+/// quoting follow the conventions of the current platform. Empty entries
+/// are ignored.
+pub const FONT_PATH_ENV_VAR: &str = "FOUNT_FONT_PATH";
+
 impl LibraryBuilder {
+    /// Scans the given file or directory and adds all discovered fonts to the
+    /// system collection.
+    ///
+    /// Paths matching any pattern previously registered with
+    /// [`exclude_path`](Self::exclude_path) are skipped.
     pub fn add_system_path<T: AsRef<Path>>(&mut self, path: T) -> Result<(), io::Error> {
         scan_path(
             path.as_ref(),
+            &self.excludes,
             &mut self.scanner,
             &mut self.system,
             &mut self.fallback,
+            self.observer.as_deref(),
         )
     }
 
+    /// Scans each directory listed in the [`FOUNT_FONT_PATH`](FONT_PATH_ENV_VAR)
+    /// environment variable.
+    ///
+    /// The variable is split into paths with [`std::env::split_paths`].
+    /// Directories that do not exist or cannot be read are ignored. Returns
+    /// the number of directories that were successfully scanned.
+    pub fn add_env_paths(&mut self) -> usize {
+        let Some(value) = std::env::var_os(FONT_PATH_ENV_VAR) else {
+            return 0;
+        };
+        let mut count = 0;
+        for path in std::env::split_paths(&value) {
+            if path.as_os_str().is_empty() {
+                continue;
+            }
+            if self.add_system_path(&path).is_ok() {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Excludes all files and directories matching the given glob pattern from
+    /// subsequent scans.
+    ///
+    /// Patterns are matched against canonicalized paths. The wildcard `?`
+    /// matches any single character, `*` matches any sequence of characters
+    /// within a path component and `**` matches across path separators. A
+    /// `**/` also matches zero components and trailing separators are
+    /// ignored, so `/opt/fonts/` excludes the directory itself. For
+    /// example, `/usr/share/fonts/**/*.pcf.gz` excludes all compressed bitmap
+    /// fonts.
+    pub fn exclude_path(&mut self, pattern: impl Into<String>) -> &mut Self {
+        self.excludes.push(pattern.into());
+        self
+    }
+
+    /// Sets the strategy for grouping fonts into families for subsequent
+    /// scans and for fonts registered with the resulting library.
+    pub fn family_grouping(&mut self, grouping: FamilyGrouping) -> &mut Self {
+        self.scanner.grouping = grouping;
+        self
+    }
+
+    /// Sets an observer that receives progress and timing events for
+    /// subsequent scans and for sources loaded from the resulting library.
+    pub fn observer(&mut self, observer: impl LoadObserver + 'static) -> &mut Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
     pub fn build(self) -> Library {
         let system = SystemCollectionData::Scanned(ScannedCollectionData {
             collection: self.system,
             fallback: self.fallback,
         });
-        Library::new(system)
+        Library::new(system, self.observer, self.scanner.grouping)
     }
 }
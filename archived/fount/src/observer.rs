@@ -0,0 +1,33 @@
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Receiver for progress and timing events produced while scanning for fonts
+/// and loading font sources.
+///
+/// All methods have empty default implementations so an observer only needs
+/// to implement the events it is interested in. Observers are registered with
+/// [`LibraryBuilder::observer`](super::LibraryBuilder::observer) and may be
+/// invoked from any thread that loads font data.
+pub trait LoadObserver: Send + Sync {
+    /// Called when a font file is found during a scan, before it is read.
+    fn file_discovered(&self, path: &Path) {}
+
+    /// Called when a font file has been read and parsed with the number of
+    /// fonts that were added to the library and the time taken.
+    fn file_parsed(&self, path: &Path, font_count: u32, elapsed: Duration) {}
+
+    /// Called when a font file or directory could not be read.
+    fn file_failed(&self, path: &Path, error: &io::Error) {}
+
+    /// Called when a directory and all of its children have been scanned with
+    /// the total time taken.
+    fn directory_scanned(&self, path: &Path, elapsed: Duration) {}
+
+    /// Called when the data for a font source is loaded on demand with the
+    /// time taken to read it.
+    fn source_loaded(&self, path: &Path, elapsed: Duration) {}
+
+    /// Called when the data for a font source could not be loaded.
+    fn source_failed(&self, path: &Path) {}
+}
@@ -1,9 +1,11 @@
 use super::data::*;
 use super::id::*;
-use super::{GenericFamily, Registration};
+use super::observer::LoadObserver;
+use super::{FamilyGrouping, GenericFamily, Registration};
 use std::collections::HashSet;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use std::{fs, io};
 use swash::text::{Cjk, Script};
 use swash::{Attributes, CacheKey, FontDataRef, FontRef, Stretch, StringId};
@@ -20,11 +22,19 @@ pub struct ScannedFont {
 
 #[derive(Default)]
 pub struct FontScanner {
+    pub grouping: FamilyGrouping,
     name: String,
     font: ScannedFont,
 }
 
 impl FontScanner {
+    pub fn new(grouping: FamilyGrouping) -> Self {
+        Self {
+            grouping,
+            ..Default::default()
+        }
+    }
+
     pub fn scan(&mut self, data: &[u8], mut f: impl FnMut(&ScannedFont)) {
         if let Some(font_data) = FontDataRef::new(data) {
             let len = font_data.len();
@@ -48,6 +58,50 @@ impl FontScanner {
         self.font.attributes = Attributes::default();
         self.font.scripts.clear();
         self.name.clear();
+        if self.grouping == FamilyGrouping::Typographic {
+            // Subfamily names are not consulted since the weight, width and
+            // style attributes distinguish fonts within the family.
+            let strings = font.localized_strings();
+            for name_id in [
+                StringId::WwsFamily,
+                StringId::TypographicFamily,
+                StringId::Family,
+            ] {
+                if let Some(name) = strings
+                    .find_by_id(name_id, Some("en"))
+                    .or_else(|| strings.find_by_id(name_id, None))
+                {
+                    self.font.name.extend(name.chars());
+                }
+                if !self.font.name.is_empty() {
+                    break;
+                }
+            }
+        } else {
+            self.legacy_family_name(font);
+        }
+        if self.font.name.is_empty() {
+            return None;
+        }
+        self.font
+            .lowercase_name
+            .extend(self.font.name.chars().map(|ch| ch.to_lowercase()).flatten());
+        self.font.attributes = font.attributes();
+        self.font.cache_key = font.key;
+        for ws in font.writing_systems() {
+            let script = match (ws.script(), ws.language()) {
+                (Some(Script::Han), Some(lang)) => (Script::Han, lang.cjk()),
+                (Some(script), _) => (script, Cjk::None),
+                (_, _) => continue,
+            };
+            self.font.scripts.insert(script);
+        }
+        f(&self.font);
+        Some(())
+    }
+
+    /// Selects the family name for legacy grouping.
+    fn legacy_family_name(&mut self, font: &FontRef) {
         let strings = font.localized_strings();
         let is_var = font.variations().len() != 0;
         // Use typographic family for variable fonts that tend to encode the
@@ -86,24 +140,6 @@ impl FontScanner {
                 self.font.name.extend(name.chars());
             }
         }
-        if self.font.name.is_empty() {
-            return None;
-        }
-        self.font
-            .lowercase_name
-            .extend(self.font.name.chars().map(|ch| ch.to_lowercase()).flatten());
-        self.font.attributes = font.attributes();
-        self.font.cache_key = font.key;
-        for ws in font.writing_systems() {
-            let script = match (ws.script(), ws.language()) {
-                (Some(Script::Han), Some(lang)) => (Script::Han, lang.cjk()),
-                (Some(script), _) => (script, Cjk::None),
-                (_, _) => continue,
-            };
-            self.font.scripts.insert(script);
-        }
-        f(&self.font);
-        Some(())
     }
 }
 
@@ -115,12 +151,12 @@ impl CollectionData {
         mut reg: Option<&mut Registration>,
         mut fallback: Option<&mut FallbackData>,
     ) -> Option<u32> {
-        let is_user = self.is_user;
-        let source_id = SourceId::alloc(self.sources.len(), is_user)?;
+        let layer = self.user_layer;
+        let source_id = SourceId::alloc(self.sources.len(), layer)?;
         let mut added_source = false;
         let mut count = 0;
         scanner.scan(&*data, |font| {
-            let font_id = if let Some(font_id) = FontId::alloc(self.fonts.len(), is_user) {
+            let font_id = if let Some(font_id) = FontId::alloc(self.fonts.len(), layer) {
                 font_id
             } else {
                 return;
@@ -129,7 +165,7 @@ impl CollectionData {
                 if let Some(family_id) = self.family_map.get(font.lowercase_name.as_str()) {
                     *family_id
                 } else {
-                    if let Some(family_id) = FamilyId::alloc(self.families.len(), is_user) {
+                    if let Some(family_id) = FamilyId::alloc(self.families.len(), layer) {
                         let family = FamilyData {
                             name: font.name.as_str().into(),
                             has_stretch: false,
@@ -238,20 +274,227 @@ impl CollectionData {
 
 pub(crate) fn scan_path(
     path: &Path,
+    excludes: &[String],
     scanner: &mut FontScanner,
     collection: &mut CollectionData,
     fallback: &mut FallbackData,
+    observer: Option<&dyn LoadObserver>,
 ) -> Result<(), io::Error> {
-    let path = std::fs::canonicalize(path)?;
+    // Only report errors for operations on this path; failures of children
+    // are reported by the nested call.
+    let report = |path: &Path, e: io::Error| {
+        if let Some(observer) = observer {
+            observer.file_failed(path, &e);
+        }
+        e
+    };
+    let path = std::fs::canonicalize(path).map_err(|e| report(path, e))?;
+    if is_excluded(&path, excludes) {
+        return Ok(());
+    }
+    let start = Instant::now();
     if path.is_file() {
-        let data = crate::font::FontData::from_file(&path)?;
-        collection.add_fonts(scanner, data, None, Some(fallback));
+        if let Some(observer) = observer {
+            observer.file_discovered(&path);
+        }
+        let data = crate::font::FontData::from_file(&path).map_err(|e| report(&path, e))?;
+        let count = collection.add_fonts(scanner, data, None, Some(fallback));
+        if let Some(observer) = observer {
+            observer.file_parsed(&path, count.unwrap_or_default(), start.elapsed());
+        }
     } else {
-        for entry in fs::read_dir(&path)? {
-            let entry = entry?;
-            let path = entry.path();
-            scan_path(&path, scanner, collection, fallback)?;
+        for entry in fs::read_dir(&path).map_err(|e| report(&path, e))? {
+            let entry = entry.map_err(|e| report(&path, e))?;
+            scan_path(
+                &entry.path(),
+                excludes,
+                scanner,
+                collection,
+                fallback,
+                observer,
+            )?;
+        }
+        if let Some(observer) = observer {
+            observer.directory_scanned(&path, start.elapsed());
         }
     }
     Ok(())
 }
+
+fn is_excluded(path: &Path, excludes: &[String]) -> bool {
+    if excludes.is_empty() {
+        return false;
+    }
+    let path = path.to_string_lossy();
+    excludes
+        .iter()
+        .any(|pattern| glob_match(pattern.as_bytes(), path.as_bytes()))
+}
+
+/// Matches a path against a glob pattern.
+///
+/// Supports `?` for any single character, `*` for any sequence of characters
+/// within a path component and `**` for any sequence of characters including
+/// path separators. A `**` followed by a separator also matches zero
+/// components and trailing separators on either side are ignored.
+///
+/// This is the usual iterative matcher that keeps a single backtracking
+/// position for the most recent `*` and another for the most recent `**`,
+/// so matching takes time proportional to the product of the lengths of
+/// the pattern and the text at worst, regardless of the number of wildcards.
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let pattern = trim_separators(pattern);
+    let text = trim_separators(text);
+    let (mut p, mut t) = (0, 0);
+    // Pattern and text positions to resume from when a `*` needs to consume
+    // one more character.
+    let mut star: Option<(usize, usize)> = None;
+    // Same for `**`, along with whether it only consumes whole components.
+    let mut globstar: Option<(usize, usize, bool)> = None;
+    while p < pattern.len() || t < text.len() {
+        if p < pattern.len() {
+            match pattern[p] {
+                b'*' if pattern.get(p + 1) == Some(&b'*') => {
+                    let components = pattern.get(p + 2).is_some_and(|c| is_separator(*c));
+                    p += if components { 3 } else { 2 };
+                    globstar = Some((p, t, components));
+                    star = None;
+                    continue;
+                }
+                b'*' => {
+                    p += 1;
+                    star = Some((p, t));
+                    continue;
+                }
+                b'?' if t < text.len() && !is_separator(text[t]) => {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+                c if t < text.len()
+                    && (c == text[t] || (is_separator(c) && is_separator(text[t]))) =>
+                {
+                    p += 1;
+                    t += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        // Mismatch: let the most recent `*` consume one more character if it
+        // stays within the component, otherwise fall back to the `**`.
+        if let Some((star_p, star_t)) = star {
+            if star_t < text.len() && !is_separator(text[star_t]) {
+                star = Some((star_p, star_t + 1));
+                p = star_p;
+                t = star_t + 1;
+                continue;
+            }
+            star = None;
+        }
+        if let Some((globstar_p, globstar_t, components)) = globstar {
+            let next = if components {
+                // Skip to the start of the next component.
+                text[globstar_t..]
+                    .iter()
+                    .position(|c| is_separator(*c))
+                    .map(|i| globstar_t + i + 1)
+            } else {
+                (globstar_t < text.len()).then_some(globstar_t + 1)
+            };
+            if let Some(next) = next {
+                globstar = Some((globstar_p, next, components));
+                p = globstar_p;
+                t = next;
+                continue;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+fn trim_separators(mut path: &[u8]) -> &[u8] {
+    while let Some((last, rest)) = path.split_last() {
+        if rest.is_empty() || !is_separator(*last) {
+            break;
+        }
+        path = rest;
+    }
+    path
+}
+
+fn is_separator(c: u8) -> bool {
+    c == b'/' || (cfg!(windows) && c == b'\\')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        glob_match(pattern.as_bytes(), path.as_bytes())
+    }
+
+    #[test]
+    fn literal() {
+        assert!(matches("/usr/share/fonts", "/usr/share/fonts"));
+        assert!(!matches("/usr/share/fonts", "/usr/share/font"));
+        assert!(!matches("/usr/share/fonts", "/usr/share/fonts/truetype"));
+        assert!(!matches("", "/"));
+    }
+
+    #[test]
+    fn single_star() {
+        assert!(matches("/fonts/*.ttf", "/fonts/a.ttf"));
+        assert!(matches("/fonts/*.ttf", "/fonts/.ttf"));
+        assert!(matches("/fonts/*-*.otf", "/fonts/Noto-Bold.otf"));
+        assert!(matches("/fonts/*", "/fonts/a.ttf"));
+        assert!(!matches("/fonts/*.ttf", "/fonts/sub/a.ttf"));
+        assert!(!matches("/fonts/*.ttf", "/fonts/a.otf"));
+        assert!(!matches("/*/a.ttf", "/fonts/sub/a.ttf"));
+    }
+
+    #[test]
+    fn question_mark() {
+        assert!(matches("/fonts/?.ttf", "/fonts/a.ttf"));
+        assert!(!matches("/fonts/?.ttf", "/fonts/ab.ttf"));
+        assert!(!matches("/fonts/?.ttf", "/fonts/.ttf"));
+        assert!(!matches("/fonts?a.ttf", "/fonts/a.ttf"));
+    }
+
+    #[test]
+    fn double_star() {
+        let pattern = "/usr/share/fonts/**/*.pcf.gz";
+        assert!(matches(pattern, "/usr/share/fonts/misc/6x13.pcf.gz"));
+        assert!(matches(pattern, "/usr/share/fonts/a/b/c/6x13.pcf.gz"));
+        // "**/" also matches zero components.
+        assert!(matches(pattern, "/usr/share/fonts/6x13.pcf.gz"));
+        assert!(!matches(pattern, "/usr/share/fonts/misc/6x13.pcf"));
+        assert!(!matches(pattern, "/usr/share/fontsmisc/6x13.pcf.gz"));
+        assert!(matches("**/emoji", "/usr/share/fonts/emoji"));
+        assert!(matches("/usr/**", "/usr/share/fonts/a.ttf"));
+        assert!(matches("/usr/**.ttf", "/usr/share/fonts/a.ttf"));
+        assert!(!matches("/usr/**.ttf", "/usr/share/fonts/a.otf"));
+    }
+
+    #[test]
+    fn trailing_separators() {
+        assert!(matches("/usr/share/fonts/", "/usr/share/fonts"));
+        assert!(matches("/usr/share/fonts", "/usr/share/fonts/"));
+        assert!(matches("/usr/share/*/", "/usr/share/fonts"));
+        assert!(matches("/", "/"));
+        assert!(!matches("/usr/share/fonts/", "/usr/share"));
+    }
+
+    #[test]
+    fn many_stars() {
+        // Exponential for naive recursive matchers.
+        let pattern = "**a**a**a**a**a**a**a**a**a**a**b";
+        let path = "a".repeat(64);
+        assert!(!matches(pattern, &path));
+        assert!(matches(pattern, &(path.clone() + "b")));
+        let pattern = "/*a*a*a*a*a*a*a*a*a*a*b";
+        assert!(!matches(pattern, &format!("/{path}")));
+    }
+}
@@ -31,6 +31,54 @@ impl fmt::Display for Attributes {
     }
 }
 
+/// Ranges of stretch, style and weight spanned by a font.
+///
+/// For static fonts, each range contains the single value reported by the
+/// font. For variable fonts, the ranges cover the values reachable through
+/// the `wdth`, `ital`, `slnt` and `wght` axes.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct AttributeRanges {
+    /// Narrowest and widest stretch.
+    pub stretch: (Stretch, Stretch),
+    /// Least and most slanted style.
+    pub style: (Style, Style),
+    /// Lightest and heaviest weight.
+    pub weight: (Weight, Weight),
+}
+
+impl AttributeRanges {
+    /// Creates ranges that contain only the given attributes.
+    pub fn from_attributes(attributes: Attributes) -> Self {
+        Self {
+            stretch: (attributes.stretch, attributes.stretch),
+            style: (attributes.style, attributes.style),
+            weight: (attributes.weight, attributes.weight),
+        }
+    }
+
+    /// Returns true if any of the ranges contain more than one value.
+    pub fn is_variable(&self) -> bool {
+        self.stretch.0 != self.stretch.1
+            || self.style.0 != self.style.1
+            || self.weight.0 != self.weight.1
+    }
+}
+
+impl fmt::Display for AttributeRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stretch: {}..{}, style: {}..{}, weight: {}..{}",
+            self.stretch.0,
+            self.stretch.1,
+            self.style.0,
+            self.style.1,
+            self.weight.0,
+            self.weight.1
+        )
+    }
+}
+
 /// Visual width of a font-- a relative change from the normal aspect
 /// ratio, typically in the range 0.5 to 2.0.
 ///
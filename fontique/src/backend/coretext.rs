@@ -30,7 +30,10 @@ const DEFAULT_GENERIC_FAMILIES: &[(GenericFamily, &[&str])] = &[
 pub struct SystemFonts {
     pub name_map: Arc<FamilyNameMap>,
     pub generic_families: Arc<GenericFamilyMap>,
-    family_map: HashMap<FamilyId, FamilyInfo>,
+    scanned_families: HashMap<FamilyId, scan::ScannedFamily>,
+    shared_tables: scan::SharedTables,
+    scan_report: scan::ScanReport,
+    family_map: HashMap<FamilyId, Option<FamilyInfo>>,
 }
 
 impl SystemFonts {
@@ -50,17 +53,39 @@ impl SystemFonts {
         Self {
             name_map: Arc::new(name_map),
             generic_families: Arc::new(generic_families),
-            family_map: scanned.families,
+            scanned_families: scanned.families,
+            shared_tables: Default::default(),
+            scan_report: scanned.report,
+            family_map: Default::default(),
         }
     }
 
+    /// Returns the report of the fonts found in the system font directory
+    /// and the files that were skipped.
+    pub fn scan_report(&self) -> &scan::ScanReport {
+        &self.scan_report
+    }
+
     pub fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
-        self.family_map.get(&id).cloned()
+        if let Some(family) = self.family_map.get(&id) {
+            return family.clone();
+        }
+        // Fonts are only parsed when their family is first requested.
+        let family = self
+            .scanned_families
+            .get(&id)?
+            .load(&mut self.shared_tables);
+        self.family_map.insert(id, family.clone());
+        family
     }
 
     pub fn fallback(&mut self, key: impl Into<FallbackKey>) -> Option<FamilyId> {
         let key = key.into();
         let sample = key.script().sample()?;
+        self.fallback_for_sample(key, sample)
+    }
+
+    pub fn fallback_for_sample(&mut self, key: FallbackKey, sample: &str) -> Option<FamilyId> {
         self.fallback_for_text(sample, key.locale(), false)
     }
 }
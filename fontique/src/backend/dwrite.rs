@@ -2,20 +2,47 @@ use dwrote::{
     Font as DFont, FontCollection, FontFallback, TextAnalysisSource, TextAnalysisSourceMethods,
 };
 use hashbrown::HashMap;
-use std::{borrow::Cow, sync::Arc};
+use std::{
+    borrow::Cow,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 use winapi::{
     ctypes::wchar_t,
-    um::dwrite::{
-        IDWriteFont, DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STYLE_NORMAL,
-        DWRITE_FONT_WEIGHT_REGULAR, DWRITE_READING_DIRECTION,
-        DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
+    shared::{
+        minwindef::{FALSE, HKEY, TRUE},
+        winerror::ERROR_SUCCESS,
+    },
+    um::{
+        dwrite::{
+            IDWriteFont, DWRITE_FONT_SIMULATIONS_BOLD, DWRITE_FONT_SIMULATIONS_NONE,
+            DWRITE_FONT_SIMULATIONS_OBLIQUE, DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STYLE_NORMAL,
+            DWRITE_FONT_WEIGHT_REGULAR, DWRITE_READING_DIRECTION,
+            DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
+        },
+        handleapi::{CloseHandle, DuplicateHandle},
+        processthreadsapi::GetCurrentProcess,
+        synchapi::{CreateEventW, SetEvent, WaitForMultipleObjects},
+        winbase::{INFINITE, WAIT_OBJECT_0},
+        winnt::{
+            DUPLICATE_SAME_ACCESS, HANDLE, KEY_NOTIFY, REG_NOTIFY_CHANGE_LAST_SET,
+            REG_NOTIFY_CHANGE_NAME,
+        },
+        winreg::{
+            RegCloseKey, RegNotifyChangeKeyValue, RegOpenKeyExW, HKEY_CURRENT_USER,
+            HKEY_LOCAL_MACHINE,
+        },
     },
 };
 use wio::com::ComPtr;
 
 use super::{
+    super::{Style, Weight},
     FallbackKey, FamilyId, FamilyInfo, FamilyName, FamilyNameMap, FontInfo, GenericFamily,
-    GenericFamilyMap, SourcePathMap,
+    GenericFamilyMap, SourceKind, SourcePathMap,
 };
 
 const DEFAULT_GENERIC_FAMILIES: &[(GenericFamily, &[&str])] = &[
@@ -39,6 +66,7 @@ pub struct SystemFonts {
     collection: FontCollection,
     fallback: Option<FontFallback>,
     utf16_buf: Vec<wchar_t>,
+    watcher: Option<FontChangeWatcher>,
 }
 
 // We're only going to access this through a mutex.
@@ -48,28 +76,7 @@ unsafe impl Sync for SystemFonts {}
 impl SystemFonts {
     pub fn new() -> Self {
         let collection = FontCollection::get_system(false);
-        let mut name_map = FamilyNameMap::default();
-        for family in collection.families_iter() {
-            if let Some(names) = all_family_names(&family) {
-                let [first_name, other_names @ ..] = names.as_slice() else {
-                    continue;
-                };
-                let id = name_map.get_or_insert(first_name).id();
-                for other_name in other_names {
-                    name_map.add_alias(id, other_name);
-                }
-            }
-        }
-        let mut generic_families = GenericFamilyMap::default();
-        for (family, names) in DEFAULT_GENERIC_FAMILIES {
-            generic_families.set(
-                *family,
-                names
-                    .iter()
-                    .filter_map(|name| name_map.get(name))
-                    .map(|name| name.id()),
-            );
-        }
+        let (name_map, generic_families) = scan_collection(&collection);
         Self {
             name_map: Arc::new(name_map),
             generic_families: Arc::new(generic_families),
@@ -78,7 +85,33 @@ impl SystemFonts {
             collection,
             fallback: FontFallback::get_system_fallback(),
             utf16_buf: Default::default(),
+            watcher: FontChangeWatcher::new(),
+        }
+    }
+
+    /// Reloads the system font collection if fonts were installed or
+    /// removed since the last call.
+    ///
+    /// Returns true if the collection was reloaded, which assigns new
+    /// identifiers to all families.
+    pub fn check_for_updates(&mut self) -> bool {
+        if !self
+            .watcher
+            .as_ref()
+            .map_or(false, |watcher| watcher.take_changed())
+        {
+            return false;
         }
+        // Ask DirectWrite to pick up the new fonts rather than returning
+        // the cached collection.
+        self.collection = FontCollection::get_system(true);
+        let (name_map, generic_families) = scan_collection(&self.collection);
+        self.name_map = Arc::new(name_map);
+        self.generic_families = Arc::new(generic_families);
+        self.family_map.clear();
+        self.source_cache = Default::default();
+        self.fallback = FontFallback::get_system_fallback();
+        true
     }
 
     pub fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
@@ -95,10 +128,14 @@ impl SystemFonts {
                 if let Some(font) =
                     FontInfo::from_dwrite(family.get_font(i), &mut self.source_cache)
                 {
-                    if !fonts
-                        .iter()
-                        .any(|f| f.source().id() == font.source().id() && f.index() == font.index())
-                    {
+                    // Simulated faces share a source with their base font
+                    // so include the simulations when checking for
+                    // duplicates.
+                    if !fonts.iter().any(|f| {
+                        f.source().id() == font.source().id()
+                            && f.index() == font.index()
+                            && f.simulations() == font.simulations()
+                    }) {
                         fonts.push(font);
                     }
                 }
@@ -116,10 +153,93 @@ impl SystemFonts {
     pub fn fallback(&mut self, key: impl Into<FallbackKey>) -> Option<FamilyId> {
         let key = key.into();
         let text = key.script().sample()?;
-        let locale = key.locale();
-        self.fallback_for_text(text, locale, false)
+        self.fallback_for_sample(key, text)
+    }
+
+    pub fn fallback_for_sample(&mut self, key: FallbackKey, sample: &str) -> Option<FamilyId> {
+        self.fallback_for_text(sample, key.locale(), false)
             .map(|handle| handle.id())
     }
+
+    /// Splits the text into segments where each segment is mapped to the
+    /// first family in the system fallback cascade that supports all of its
+    /// characters.
+    ///
+    /// Segment ranges are byte offsets into the text. Segments that are not
+    /// supported by any family are mapped to `None`.
+    pub fn fallback_segments(
+        &mut self,
+        text: &str,
+        locale: Option<&str>,
+    ) -> Vec<(Range<usize>, Option<FamilyId>)> {
+        let mut segments: Vec<(Range<usize>, Option<FamilyId>)> = vec![];
+        // Byte offset for each UTF-16 code unit, plus the end of the text.
+        let mut offsets = Vec::with_capacity(text.len() + 1);
+        self.utf16_buf.clear();
+        for (offset, ch) in text.char_indices() {
+            let mut units = [0u16; 2];
+            for unit in ch.encode_utf16(&mut units) {
+                self.utf16_buf.push(*unit);
+                offsets.push(offset);
+            }
+        }
+        offsets.push(text.len());
+        let Some(fallback) = self.fallback.as_ref() else {
+            return segments;
+        };
+        let text_len = self.utf16_buf.len() as u32;
+        let text_source = TextAnalysisSource::from_text(
+            Box::new(TextAnalysisData {
+                locale,
+                len: text_len,
+            }),
+            Cow::Borrowed(&self.utf16_buf),
+        );
+        let mut i = 0u32;
+        while i < text_len {
+            let mut font: *mut IDWriteFont = std::ptr::null_mut();
+            let mut mapped_length = 0;
+            let mut scale = 0.0;
+            let hr = unsafe {
+                (*fallback.as_ptr()).MapCharacters(
+                    text_source.as_ptr(),
+                    i,
+                    text_len - i,
+                    core::ptr::null_mut(),
+                    core::ptr::null_mut(),
+                    DWRITE_FONT_WEIGHT_REGULAR,
+                    DWRITE_FONT_STYLE_NORMAL,
+                    DWRITE_FONT_STRETCH_NORMAL,
+                    &mut mapped_length,
+                    &mut font,
+                    &mut scale,
+                )
+            };
+            if hr != 0 {
+                break;
+            }
+            let family = if font.is_null() {
+                None
+            } else {
+                let font = DFont::take(unsafe { ComPtr::from_raw(font) });
+                self.name_map
+                    .get(font.family_name().as_str())
+                    .map(|name| name.id())
+            };
+            // Always make progress, even if DirectWrite reports an empty
+            // mapping.
+            let end = (i + mapped_length.max(1)).min(text_len);
+            let range = offsets[i as usize]..offsets[end as usize];
+            match segments.last_mut() {
+                Some(last) if last.1 == family && last.0.end == range.start => {
+                    last.0.end = range.end;
+                }
+                _ => segments.push((range, family)),
+            }
+            i = end;
+        }
+        segments
+    }
 }
 
 impl SystemFonts {
@@ -196,7 +316,248 @@ impl FontInfo {
         let path = files.first()?.get_font_file_path()?;
         let data = paths.get_or_insert(&path);
         let index = face.get_index();
-        Self::from_source(data, index)
+        let mut info = Self::from_source(data, index)?;
+        // Pass through simulations so that faces synthesized by DirectWrite
+        // are reported with the attributes and synthesis that GDI would
+        // apply.
+        let (simulations, weight) = unsafe {
+            (
+                (*font.as_ptr()).GetSimulations(),
+                (*font.as_ptr()).GetWeight(),
+            )
+        };
+        let weight =
+            (simulations & DWRITE_FONT_SIMULATIONS_BOLD != 0).then(|| Weight::new(weight as f32));
+        let style =
+            (simulations & DWRITE_FONT_SIMULATIONS_OBLIQUE != 0).then_some(Style::Oblique(None));
+        if weight.is_some() || style.is_some() {
+            info.set_simulations(weight, style);
+        }
+        Some(info)
+    }
+
+    /// Returns the font and glyph metrics that DirectWrite computes for this
+    /// font in GDI compatible mode at the given size in pixels per em.
+    ///
+    /// GDI compatible metrics are rounded to whole pixels in the same way
+    /// as legacy Win32 text, which is needed to reproduce its line heights
+    /// and advances exactly. If `natural` is true, advances use the GDI
+    /// natural widths of ClearType natural mode rather than the compatible
+    /// widths. Advances are returned for each of the given glyphs.
+    ///
+    /// The bold and oblique simulations of faces synthesized by DirectWrite
+    /// are applied, so the advances of simulated bold faces include the
+    /// additional width.
+    pub fn gdi_compatible_metrics(
+        &self,
+        ppem: f32,
+        glyph_ids: &[u16],
+        natural: bool,
+    ) -> Option<GdiCompatibleMetrics> {
+        let file = match self.source().kind() {
+            SourceKind::Path(path) => dwrote::FontFile::new_from_path(&**path)?,
+            SourceKind::Memory(blob) => {
+                dwrote::FontFile::new_from_data(Arc::new(blob.as_ref().to_vec()))?
+            }
+        };
+        let simulations = self.simulations();
+        let mut flags = DWRITE_FONT_SIMULATIONS_NONE;
+        if simulations.embolden {
+            flags |= DWRITE_FONT_SIMULATIONS_BOLD;
+        }
+        if simulations.skew != 0 {
+            flags |= DWRITE_FONT_SIMULATIONS_OBLIQUE;
+        }
+        let face = file.create_face(self.index(), flags).ok()?;
+        let metrics = face.get_gdi_compatible_metrics(ppem, 1.0, core::ptr::null());
+        let scale = ppem / metrics.designUnitsPerEm as f32;
+        let advances = face
+            .get_gdi_compatible_glyph_metrics(
+                ppem,
+                1.0,
+                core::ptr::null(),
+                natural,
+                glyph_ids,
+                false,
+            )
+            .iter()
+            .map(|glyph| glyph.advanceWidth as f32 * scale)
+            .collect();
+        Some(GdiCompatibleMetrics {
+            ascent: metrics.ascent as f32 * scale,
+            descent: metrics.descent as f32 * scale,
+            line_gap: metrics.lineGap as f32 * scale,
+            advances,
+        })
+    }
+}
+
+/// Font and glyph metrics computed by DirectWrite in GDI compatible mode.
+///
+/// All values are in pixels. The descent is positive below the baseline,
+/// as reported by DirectWrite.
+#[derive(Clone, Default, Debug)]
+pub struct GdiCompatibleMetrics {
+    /// Distance from the baseline to the top of the line.
+    pub ascent: f32,
+    /// Distance from the baseline to the bottom of the line.
+    pub descent: f32,
+    /// Additional space between lines.
+    pub line_gap: f32,
+    /// Advance widths of the requested glyphs.
+    pub advances: Vec<f32>,
+}
+
+/// Builds the family name map and generic families for the fonts in the
+/// collection.
+fn scan_collection(collection: &FontCollection) -> (FamilyNameMap, GenericFamilyMap) {
+    let mut name_map = FamilyNameMap::default();
+    for family in collection.families_iter() {
+        if let Some(names) = all_family_names(&family) {
+            let [first_name, other_names @ ..] = names.as_slice() else {
+                continue;
+            };
+            let id = name_map.get_or_insert(first_name).id();
+            for other_name in other_names {
+                name_map.add_alias(id, other_name);
+            }
+        }
+    }
+    let mut generic_families = GenericFamilyMap::default();
+    for (family, names) in DEFAULT_GENERIC_FAMILIES {
+        generic_families.set(
+            *family,
+            names
+                .iter()
+                .filter_map(|name| name_map.get(name))
+                .map(|name| name.id()),
+        );
+    }
+    (name_map, generic_families)
+}
+
+/// Path of the registry keys that list the installed fonts, under both
+/// `HKEY_LOCAL_MACHINE` and, for fonts installed per user,
+/// `HKEY_CURRENT_USER`.
+const FONTS_KEY: &str = "SOFTWARE\\Microsoft\\Windows NT\\CurrentVersion\\Fonts";
+
+/// Watches the registry for fonts being installed or removed.
+///
+/// DirectWrite only exposes change notifications for the system collection
+/// through `IDWriteFontCollection3`, which isn't available in our bindings,
+/// so we watch the registry keys that the font installer updates instead.
+struct FontChangeWatcher {
+    changed: Arc<AtomicBool>,
+    stop: HANDLE,
+}
+
+impl FontChangeWatcher {
+    fn new() -> Option<Self> {
+        let stop = unsafe { CreateEventW(std::ptr::null_mut(), TRUE, FALSE, std::ptr::null()) };
+        if stop.is_null() {
+            return None;
+        }
+        // The thread gets its own handle to the event so that it can close
+        // it independently.
+        let mut thread_stop: HANDLE = std::ptr::null_mut();
+        let duplicated = unsafe {
+            let process = GetCurrentProcess();
+            DuplicateHandle(
+                process,
+                stop,
+                process,
+                &mut thread_stop,
+                0,
+                FALSE,
+                DUPLICATE_SAME_ACCESS,
+            )
+        };
+        if duplicated == FALSE {
+            unsafe { CloseHandle(stop) };
+            return None;
+        }
+        let changed = Arc::new(AtomicBool::new(false));
+        let flag = changed.clone();
+        // Handles can't be sent between threads directly.
+        let thread_stop = thread_stop as usize;
+        let spawned = std::thread::Builder::new()
+            .name("fontique-font-watcher".into())
+            .spawn(move || unsafe { watch_font_keys(thread_stop as HANDLE, &flag) });
+        if spawned.is_err() {
+            unsafe {
+                CloseHandle(thread_stop as HANDLE);
+                CloseHandle(stop);
+            }
+            return None;
+        }
+        Some(Self { changed, stop })
+    }
+
+    /// Returns true if the fonts changed since the last call.
+    fn take_changed(&self) -> bool {
+        self.changed.swap(false, Ordering::AcqRel)
+    }
+}
+
+impl Drop for FontChangeWatcher {
+    fn drop(&mut self) {
+        unsafe {
+            SetEvent(self.stop);
+            CloseHandle(self.stop);
+        }
+    }
+}
+
+/// Sets `changed` whenever the font registry keys are modified, until the
+/// `stop` event is signaled.
+unsafe fn watch_font_keys(stop: HANDLE, changed: &AtomicBool) {
+    let key_name: Vec<u16> = FONTS_KEY.encode_utf16().chain(Some(0)).collect();
+    let mut keys: Vec<HKEY> = vec![];
+    for root in [HKEY_LOCAL_MACHINE, HKEY_CURRENT_USER] {
+        let mut key: HKEY = std::ptr::null_mut();
+        if RegOpenKeyExW(root, key_name.as_ptr(), 0, KEY_NOTIFY, &mut key) == ERROR_SUCCESS as i32 {
+            keys.push(key);
+        }
+    }
+    // The first handle is the stop event, followed by one event per key.
+    let mut handles = vec![stop];
+    for _ in &keys {
+        let event = CreateEventW(std::ptr::null_mut(), FALSE, FALSE, std::ptr::null());
+        if event.is_null() {
+            break;
+        }
+        handles.push(event);
+    }
+    if handles.len() == keys.len() + 1 {
+        loop {
+            // Notifications fire once, so they are requested again after
+            // each change.
+            let mut registered = true;
+            for (key, event) in keys.iter().zip(&handles[1..]) {
+                registered &= RegNotifyChangeKeyValue(
+                    *key,
+                    FALSE,
+                    REG_NOTIFY_CHANGE_NAME | REG_NOTIFY_CHANGE_LAST_SET,
+                    *event,
+                    TRUE,
+                ) == ERROR_SUCCESS as i32;
+            }
+            if !registered {
+                break;
+            }
+            let result =
+                WaitForMultipleObjects(handles.len() as u32, handles.as_ptr(), FALSE, INFINITE);
+            if result == WAIT_OBJECT_0 || result >= WAIT_OBJECT_0 + handles.len() as u32 {
+                break;
+            }
+            changed.store(true, Ordering::Release);
+        }
+    }
+    for key in keys {
+        RegCloseKey(key);
+    }
+    for handle in handles {
+        CloseHandle(handle);
     }
 }
 
@@ -1,7 +1,16 @@
-use crate::{Stretch, Style, Weight};
+use super::properties::{object_name, FontconfigProperty, FontconfigValue};
+use crate::{
+    scan::{ScanReport, SkipReason},
+    Stretch, Style, Weight,
+};
 use fontconfig_cache_parser::*;
+use hashbrown::HashSet;
+use read_fonts::types::NameId;
+use std::ffi::OsStr;
 use std::io::Read;
-use std::path::PathBuf;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
 
 impl Stretch {
     fn from_fc(width: i32) -> Self {
@@ -68,24 +77,83 @@ pub struct CachedFont {
     pub style: Style,
     pub weight: Weight,
     pub coverage: Coverage,
+    pub properties: Vec<FontconfigProperty>,
 }
 
 impl CachedFont {
+    /// Returns the reason for skipping the font if fontconfig recorded a
+    /// format that can't be loaded.
+    fn unsupported_format(&self) -> Option<SkipReason> {
+        let format = self.properties.iter().find_map(|prop| match &prop.value {
+            FontconfigValue::String(value) if prop.name == "fontformat" => Some(value),
+            _ => None,
+        })?;
+        match &**format {
+            "TrueType" | "CFF" => None,
+            "Windows FNT" => Some(SkipReason::WindowsBitmap),
+            "Type 1" => Some(SkipReason::PostScriptType1),
+            _ => Some(SkipReason::UnsupportedFormat),
+        }
+    }
+
     fn clear(&mut self) {
         self.family.clear();
         self.path.clear();
         self.index = 0;
         self.coverage.clear();
+        self.properties.clear();
+    }
+}
+
+/// Returns the standard cache directories that fontconfig searches in
+/// addition to those listed in the configuration files.
+pub fn default_cache_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    if let Ok(xdg_cache_home) = std::env::var("XDG_CACHE_HOME") {
+        dirs.push(PathBuf::from(xdg_cache_home).join("fontconfig"));
+    } else if let Ok(user_home) = std::env::var("HOME") {
+        dirs.push(PathBuf::from(user_home).join(".cache/fontconfig"));
     }
+    dirs.push(PathBuf::from("/var/cache/fontconfig"));
+    dirs
 }
 
-pub fn parse_caches(paths: &[PathBuf], mut f: impl FnMut(&CachedFont)) {
+/// Parses all cache files in the given cache directories and invokes `f`
+/// for each font.
+///
+/// As in fontconfig, a cache is only used if its checksum matches the
+/// modification time of the font directory it describes and the first valid
+/// cache found for a directory wins. Font directories that only have stale
+/// caches are scanned directly, as are the given font directories and their
+/// subdirectories when they have no usable cache at all. The latter covers
+/// caches that were written for a different architecture, since the cache
+/// format depends on the endianness and pointer size of the machine. Fonts
+/// discovered this way have empty coverage since computing it requires
+/// loading the character map.
+///
+/// Returns a report of the fonts found and the files that were skipped,
+/// including fonts listed in the caches in formats that can't be loaded.
+pub fn parse_caches(
+    paths: &[PathBuf],
+    font_dirs: &[PathBuf],
+    mut f: impl FnMut(&CachedFont),
+) -> ScanReport {
+    let mut report = ScanReport::default();
     let mut buffer = vec![];
     let mut name_free_list = vec![];
     let mut cached_font = CachedFont::default();
+    let mut visited_cache_dirs = HashSet::new();
+    let mut valid_dirs = HashSet::new();
+    let mut stale_dirs = vec![];
     for path in paths {
-        let Ok(dir) = path.canonicalize().and_then(std::fs::read_dir) else {
-            return;
+        let Ok(path) = path.canonicalize() else {
+            continue;
+        };
+        if !visited_cache_dirs.insert(path.clone()) {
+            continue;
+        }
+        let Ok(dir) = std::fs::read_dir(&path) else {
+            continue;
         };
         for path in dir.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
             buffer.clear();
@@ -96,20 +164,167 @@ pub fn parse_caches(paths: &[PathBuf], mut f: impl FnMut(&CachedFont)) {
             let Ok(mut file) = std::fs::OpenOptions::new().read(true).open(&path) else {
                 continue;
             };
-            let Ok(_) = file.read(&mut buffer) else {
+            let Ok(_) = file.read_exact(&mut buffer) else {
                 continue;
             };
+            let Some(header) = CacheHeader::parse(&buffer) else {
+                continue;
+            };
+            if valid_dirs.contains(header.dir) {
+                continue;
+            }
+            if !header.is_valid(buffer.len()) {
+                if !stale_dirs.iter().any(|dir| dir == header.dir) {
+                    stale_dirs.push(header.dir.to_owned());
+                }
+                continue;
+            }
             let Ok(set) = Cache::from_bytes(&buffer).and_then(|cache| cache.set()) else {
                 continue;
             };
             let Ok(fonts) = set.fonts() else { continue };
+            valid_dirs.insert(header.dir.to_owned());
             for font in fonts.flatten() {
-                if parse_font(&font, &mut name_free_list, &mut cached_font).is_some() {
-                    f(&cached_font);
+                if parse_font(&font, &mut name_free_list, &mut cached_font).is_none() {
+                    continue;
+                }
+                if let Some(reason) = cached_font.unsupported_format() {
+                    report.skip(&cached_font.path, reason);
+                    continue;
                 }
+                report.add_font();
+                f(&cached_font);
             }
         }
     }
+    // Fall back to scanning directories that lack an up to date cache
+    let mut name_pool = vec![];
+    stale_dirs.retain(|dir| !valid_dirs.contains(dir));
+    for dir in font_dirs {
+        if let Ok(dir) = dir.canonicalize() {
+            collect_uncached_dirs(&dir, &valid_dirs, &mut stale_dirs, 0);
+        }
+    }
+    let scanned = crate::scan::scan_paths(&stale_dirs, 0, |scanned_font| {
+        let Some(path) = scanned_font.path else {
+            return;
+        };
+        name_pool.append(&mut cached_font.family);
+        cached_font.clear();
+        // The font is already mapped so read the attributes now since they
+        // are trusted like those from a cache.
+        let (stretch, style, weight, _) = crate::font::read_attributes(&scanned_font.font);
+        cached_font.stretch = stretch;
+        cached_font.style = style;
+        cached_font.weight = weight;
+        if !crate::scan::all_names(
+            &scanned_font.name_table,
+            NameId::TYPOGRAPHIC_FAMILY_NAME,
+            &mut name_pool,
+            &mut cached_font.family,
+        ) && !crate::scan::all_names(
+            &scanned_font.name_table,
+            NameId::FAMILY_NAME,
+            &mut name_pool,
+            &mut cached_font.family,
+        ) {
+            return;
+        }
+        cached_font.path.push(path);
+        cached_font.index = scanned_font.index;
+        f(&cached_font);
+    });
+    report.append(scanned);
+    report
+}
+
+/// Maximum depth of subdirectories searched for fonts without a cache.
+const MAX_FONT_DIR_DEPTH: u32 = 8;
+
+/// Adds the given font directory and its subdirectories to `dirs` if they
+/// are not described by a valid cache.
+fn collect_uncached_dirs(
+    dir: &Path,
+    valid_dirs: &HashSet<PathBuf>,
+    dirs: &mut Vec<PathBuf>,
+    depth: u32,
+) {
+    if depth > MAX_FONT_DIR_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    if !valid_dirs.contains(dir) && !dirs.iter().any(|existing| existing == dir) {
+        dirs.push(dir.to_owned());
+    }
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        if entry.file_type().map(|ty| ty.is_dir()).unwrap_or(false) {
+            collect_uncached_dirs(&entry.path(), valid_dirs, dirs, depth + 1);
+        }
+    }
+}
+
+/// Header of a fontconfig cache file.
+///
+/// This mirrors the layout of `FcCache` for 64-bit targets which is the
+/// only layout supported by the cache parser.
+struct CacheHeader<'a> {
+    /// Size of the cache file in bytes.
+    size: u64,
+    /// Font directory described by the cache.
+    dir: &'a Path,
+    /// Modification time of the directory in seconds when the cache was
+    /// generated.
+    checksum: i32,
+    /// Nanosecond part of the directory modification time.
+    checksum_nano: i64,
+}
+
+impl<'a> CacheHeader<'a> {
+    const SIZE_OFFSET: usize = 8;
+    const DIR_OFFSET: usize = 16;
+    const CHECKSUM_OFFSET: usize = 48;
+    const CHECKSUM_NANO_OFFSET: usize = 56;
+    const LEN: usize = 64;
+
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.len() < Self::LEN {
+            return None;
+        }
+        let read =
+            |offset: usize| -> Option<[u8; 8]> { data.get(offset..offset + 8)?.try_into().ok() };
+        let size = u64::from_ne_bytes(read(Self::SIZE_OFFSET)?);
+        let dir_offset = u64::from_ne_bytes(read(Self::DIR_OFFSET)?) as usize;
+        let checksum = i32::from_ne_bytes(
+            data.get(Self::CHECKSUM_OFFSET..Self::CHECKSUM_OFFSET + 4)?
+                .try_into()
+                .ok()?,
+        );
+        let checksum_nano = i64::from_ne_bytes(read(Self::CHECKSUM_NANO_OFFSET)?);
+        let dir = data.get(dir_offset..)?;
+        let dir = &dir[..dir.iter().position(|b| *b == 0)?];
+        Some(Self {
+            size,
+            dir: Path::new(OsStr::from_bytes(dir)),
+            checksum,
+            checksum_nano,
+        })
+    }
+
+    /// Returns true if the cache is complete and the font directory has not
+    /// been modified since the cache was generated.
+    ///
+    /// This follows the `FcCacheTimeValid` function in fontconfig.
+    fn is_valid(&self, file_len: usize) -> bool {
+        if self.size != file_len as u64 {
+            return false;
+        }
+        let Ok(metadata) = self.dir.metadata() else {
+            return false;
+        };
+        metadata.mtime() as i32 == self.checksum && metadata.mtime_nsec() == self.checksum_nano
+    }
 }
 
 fn parse_font(
@@ -197,7 +412,33 @@ fn parse_font(
                     }
                 }
             }
-            _ => {}
+            obj => {
+                // Keep the remaining scalar values for ranking hooks. These
+                // are optional so malformed values are skipped rather than
+                // rejecting the font.
+                let Some(name) = object_name(obj as usize) else {
+                    continue;
+                };
+                let Ok(values) = elt.values() else {
+                    continue;
+                };
+                for val in values.flatten() {
+                    let value = match val {
+                        Value::Int(i) => FontconfigValue::Int(i as _),
+                        Value::Double(d) => FontconfigValue::Double(d as _),
+                        Value::Bool(b) => FontconfigValue::Bool(b != 0),
+                        Value::String(s) => {
+                            let Some(s) = s.str().ok().and_then(|s| core::str::from_utf8(s).ok())
+                            else {
+                                continue;
+                            };
+                            FontconfigValue::String(s.into())
+                        }
+                        _ => continue,
+                    };
+                    font.properties.push(FontconfigProperty { name, value });
+                }
+            }
         }
     }
     if !font.family.is_empty() && !font.path.as_os_str().is_empty() {
@@ -214,10 +455,23 @@ pub struct Coverage {
 }
 
 impl Coverage {
-    pub fn compute_for_str(&self, s: &str) -> usize {
-        s.chars()
-            .map(|ch| self.contains(ch as _).unwrap_or(false) as usize)
-            .sum()
+    /// Creates a coverage containing the characters of the text.
+    pub fn for_text(text: &str) -> Self {
+        let mut coverage = Self::default();
+        for ch in text.chars() {
+            let hi = (ch as u32 >> 8) as u16;
+            let idx = match coverage.numbers.binary_search(&hi) {
+                Ok(idx) => idx,
+                Err(idx) => {
+                    coverage.numbers.insert(idx, hi);
+                    coverage.leaves.insert(idx, [0; 8]);
+                    idx
+                }
+            };
+            let lo = ch as u32 & 0xff;
+            coverage.leaves[idx][(lo >> 5) as usize] |= 1 << (lo & 0x1f);
+        }
+        coverage
     }
 
     pub fn contains(&self, ch: u32) -> Option<bool> {
@@ -235,8 +489,112 @@ impl Coverage {
         }
     }
 
+    /// Returns the number of characters in the coverage.
+    pub fn len(&self) -> usize {
+        self.leaves
+            .iter()
+            .flatten()
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns true if the coverage contains no characters.
+    pub fn is_empty(&self) -> bool {
+        self.leaves.iter().flatten().all(|bits| *bits == 0)
+    }
+
+    /// Returns true if any character is present in both coverages.
+    pub fn intersects(&self, other: &Coverage) -> bool {
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.numbers.get(i), other.numbers.get(j)) {
+            match a.cmp(b) {
+                core::cmp::Ordering::Less => i += 1,
+                core::cmp::Ordering::Greater => j += 1,
+                core::cmp::Ordering::Equal => {
+                    if let (Some(a), Some(b)) = (self.leaves.get(i), other.leaves.get(j)) {
+                        if a.iter().zip(b).any(|(a, b)| a & b != 0) {
+                            return true;
+                        }
+                    }
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns an iterator over the characters in the coverage in ascending
+    /// order.
+    pub fn chars(&self) -> CoverageChars<'_> {
+        CoverageChars {
+            numbers: &self.numbers,
+            leaves: &self.leaves,
+            leaf: 0,
+            word: 0,
+            bits: 0,
+        }
+    }
+
     fn clear(&mut self) {
         self.numbers.clear();
         self.leaves.clear();
     }
 }
+
+/// Iterator over the characters in a [`Coverage`].
+#[derive(Clone)]
+pub struct CoverageChars<'a> {
+    numbers: &'a [u16],
+    leaves: &'a [[u32; 8]],
+    /// Index of the current leaf.
+    leaf: usize,
+    /// Index of the next word to load from the current leaf.
+    word: usize,
+    /// Remaining bits of the most recently loaded word.
+    bits: u32,
+}
+
+impl<'a> Iterator for CoverageChars<'a> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.bits != 0 {
+                let bit = self.bits.trailing_zeros();
+                self.bits &= self.bits - 1;
+                let hi = *self.numbers.get(self.leaf)? as u32;
+                return Some(hi << 8 | (self.word as u32 - 1) << 5 | bit);
+            }
+            let leaf = self.leaves.get(self.leaf)?;
+            if self.word == leaf.len() {
+                self.leaf += 1;
+                self.word = 0;
+                continue;
+            }
+            self.bits = leaf[self.word];
+            self.word += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Coverage;
+
+    #[test]
+    fn coverage_queries() {
+        let latin = Coverage::for_text("abcab\u{e9}");
+        assert_eq!(latin.len(), 4);
+        assert_eq!(latin.chars().collect::<Vec<_>>(), [0x61, 0x62, 0x63, 0xE9]);
+        assert_eq!(latin.contains('b' as u32), Some(true));
+        assert_eq!(latin.contains('d' as u32), Some(false));
+        let greek = Coverage::for_text("\u{3b1}\u{3b2}\u{1f600}");
+        assert_eq!(greek.chars().collect::<Vec<_>>(), [0x3B1, 0x3B2, 0x1F600]);
+        assert!(!latin.intersects(&greek));
+        assert!(latin.intersects(&Coverage::for_text("z\u{e9}")));
+        assert!(greek.intersects(&Coverage::for_text("\u{1f600}")));
+        assert!(Coverage::default().is_empty());
+        assert!(!latin.intersects(&Coverage::default()));
+    }
+}
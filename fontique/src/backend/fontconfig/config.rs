@@ -5,6 +5,7 @@ use std::path::Path;
 
 pub trait ParserSink {
     fn include_path(&mut self, path: &Path);
+    fn font_path(&mut self, path: &Path);
     fn cache_path(&mut self, path: &Path);
     fn alias(&mut self, family: &str, prefer: &[&str]);
     fn lang_map(&mut self, lang: &str, from_family: Option<&str>, family: &str);
@@ -53,6 +54,11 @@ pub fn parse_config(path: &Path, sink: &mut impl ParserSink) {
                     _ => {}
                 }
             }
+            "dir" => {
+                if let Some(path) = resolve_dir(child, path) {
+                    sink.font_path(&path);
+                }
+            }
             "cachedir" => {
                 if let Some(path) = resolve_dir(child, path) {
                     sink.cache_path(&path);
@@ -173,6 +179,7 @@ fn resolve_dir(
     let dir_path = node.text()?;
     let (xdg_env, xdg_fallback) = match node.tag_name().name() {
         "include" => ("XDG_CONFIG_HOME", "~/.config"),
+        "dir" => ("XDG_DATA_HOME", "~/.local/share"),
         "cachedir" => ("XDG_CACHE_HOME", "~/.cache"),
         _ => return None,
     };
@@ -200,6 +207,22 @@ fn resolve_dir(
     })
 }
 
+/// Returns the standard font directories, used when the configuration
+/// doesn't list any.
+pub fn default_font_dirs() -> Vec<std::path::PathBuf> {
+    let mut dirs = vec![
+        std::path::PathBuf::from("/usr/share/fonts"),
+        std::path::PathBuf::from("/usr/local/share/fonts"),
+    ];
+    if let Ok(xdg_data_home) = std::env::var("XDG_DATA_HOME") {
+        dirs.push(std::path::PathBuf::from(xdg_data_home).join("fonts"));
+    } else if let Ok(home) = config_home() {
+        dirs.push(std::path::PathBuf::from(&home).join(".local/share/fonts"));
+        dirs.push(std::path::PathBuf::from(home).join(".fonts"));
+    }
+    dirs
+}
+
 /// Get the location to user home directory.
 ///
 /// This implementation follows `FcConfigHome` function of freedesktop.org's
@@ -4,19 +4,27 @@ use std::{path::PathBuf, sync::Arc};
 use super::{
     super::{Stretch, Style, Weight},
     FallbackKey, FamilyId, FamilyInfo, FamilyName, FamilyNameMap, FontInfo, GenericFamily,
-    GenericFamilyMap, Script, SourceInfo, SourcePathMap,
+    GenericFamilyMap, ScanReport, Script, SourceInfo, SourcePathMap,
 };
 
 mod cache;
 mod config;
+mod properties;
+
+pub use properties::{FontconfigProperty, FontconfigRanking, FontconfigValue};
 
 /// Raw access to the collection of local system fonts.
+///
+/// To customize the backend, such as with a [`FontconfigRanking`], pass it
+/// to [`Collection::with_system_fonts`](crate::Collection::with_system_fonts).
 pub struct SystemFonts {
     pub name_map: Arc<FamilyNameMap>,
     pub generic_families: Arc<GenericFamilyMap>,
     raw_families: HashMap<FamilyId, RawFamily>,
     family_map: HashMap<FamilyId, Option<FamilyInfo>>,
     fallback_map: HashMap<Script, FallbackFamilies>,
+    family_ranks: HashMap<FamilyId, i32>,
+    scan_report: ScanReport,
 }
 
 impl SystemFonts {
@@ -27,9 +35,44 @@ impl SystemFonts {
             raw_families: Default::default(),
             family_map: Default::default(),
             fallback_map: Default::default(),
+            family_ranks: Default::default(),
+            scan_report: Default::default(),
         })
     }
 
+    /// Adjusts the ranking of fallback families using the given hook,
+    /// which is evaluated once for each font.
+    pub fn with_ranking(mut self, ranking: impl FontconfigRanking) -> Self {
+        self.family_ranks = self
+            .raw_families
+            .iter()
+            .filter_map(|(id, family)| {
+                let rank = family
+                    .fonts
+                    .iter()
+                    .map(|font| ranking.rank(family.name.name(), &font.properties))
+                    .max()?;
+                (rank != 0).then_some((*id, rank))
+            })
+            .collect();
+        self
+    }
+
+    /// Returns the uninterpreted fontconfig properties of the fonts in the
+    /// family with the given identifier.
+    pub fn properties(&self, id: FamilyId) -> impl Iterator<Item = &[FontconfigProperty]> + '_ {
+        self.raw_families
+            .get(&id)
+            .into_iter()
+            .flat_map(|family| family.fonts.iter().map(|font| font.properties.as_slice()))
+    }
+
+    /// Returns the report of the fonts found in the caches and font
+    /// directories and the files that were skipped.
+    pub fn scan_report(&self) -> &ScanReport {
+        &self.scan_report
+    }
+
     pub fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
         match self.family_map.get(&id) {
             Some(Some(family)) => return Some(family.clone()),
@@ -41,19 +84,21 @@ impl SystemFonts {
             // TODO: maybe catch this earlier?
             return None;
         }
-        let mut fonts: smallvec::SmallVec<[FontInfo; 4]> = Default::default();
-        fonts.reserve(raw_family.fonts.len());
-        fonts.extend(raw_family.fonts.iter().filter_map(|font| {
-            let mut info = FontInfo::from_source(font.source.clone(), font.index);
-            if let Some(info) = info.as_mut() {
-                info.maybe_override_attributes(font.stretch, font.style, font.weight);
-            }
-            info
-        }));
-        if fonts.is_empty() {
-            self.family_map.insert(id, None);
-            return None;
-        }
+        // Trust the attributes from the cache to avoid reading every font
+        // file in the family. Each file is read when first needed.
+        let fonts: smallvec::SmallVec<[FontInfo; 4]> = raw_family
+            .fonts
+            .iter()
+            .map(|font| {
+                FontInfo::from_cached(
+                    font.source.clone(),
+                    font.index,
+                    font.stretch,
+                    font.style,
+                    font.weight,
+                )
+            })
+            .collect();
         let family = FamilyInfo::new(raw_family.name.clone(), fonts);
         self.family_map.insert(id, Some(family.clone()));
         Some(family)
@@ -67,12 +112,18 @@ impl SystemFonts {
         let style = StyleClass::SansSerif;
         if let Some(locale) = locale {
             if !key.is_default() {
-                if let Some(family) = families.select_lang(locale, style) {
+                if let Some(family) = families.select_lang(locale, style, &self.family_ranks) {
                     return Some(family);
                 }
             }
         }
-        families.select_default(style)
+        families.select_default(style, &self.family_ranks)
+    }
+
+    pub fn fallback_for_sample(&mut self, key: FallbackKey, sample: &str) -> Option<FamilyId> {
+        // Configured fallbacks take precedence; otherwise pick the family
+        // that best covers the sample.
+        self.fallback(key).or_else(|| self.find_best_family(sample))
     }
 }
 
@@ -104,8 +155,19 @@ impl SystemFonts {
             );
         }
 
-        // Extract all font/family metadata from the cache files
-        cache::parse_caches(&config.cache_dirs, |font| {
+        // Caches in the standard locations are used even if the
+        // configuration doesn't list them
+        for dir in cache::default_cache_dirs() {
+            if !config.cache_dirs.contains(&dir) {
+                config.cache_dirs.push(dir);
+            }
+        }
+        if config.font_dirs.is_empty() {
+            config.font_dirs = config::default_font_dirs();
+        }
+        // Extract all font/family metadata from the cache files, scanning
+        // font directories that lack a usable cache
+        let scan_report = cache::parse_caches(&config.cache_dirs, &config.font_dirs, |font| {
             let [first_name, other_names @ ..] = font.family.as_slice() else {
                 return;
             };
@@ -133,6 +195,7 @@ impl SystemFonts {
                 style: font.style,
                 weight: font.weight,
                 coverage: font.coverage.clone(),
+                properties: font.properties.clone(),
             });
         });
         // Build the fallback map, dropping non-existent families
@@ -172,6 +235,8 @@ impl SystemFonts {
             raw_families,
             family_map: Default::default(),
             fallback_map,
+            family_ranks: Default::default(),
+            scan_report,
         };
         result.load_additional_fallbacks();
         Some(result)
@@ -242,14 +307,26 @@ fn find_best_family<'a>(
     raw_families: impl Iterator<Item = &'a RawFamily>,
     text: &str,
 ) -> Option<FamilyId> {
-    let text_len = text.len();
+    let sample = cache::Coverage::for_text(text);
+    if sample.is_empty() {
+        return None;
+    }
+    let sample_len = sample.len();
     let mut best_id = None;
     let mut best_coverage = 0;
     for family in raw_families {
         let id = family.name.id();
         for font in &family.fonts {
-            let coverage = font.coverage.compute_for_str(text);
-            if coverage == text_len {
+            // Reject fonts that share no characters with the sample before
+            // counting the covered characters.
+            if !font.coverage.intersects(&sample) {
+                continue;
+            }
+            let coverage = sample
+                .chars()
+                .filter(|ch| font.coverage.contains(*ch) == Some(true))
+                .count();
+            if coverage == sample_len {
                 return Some(id);
             }
             if coverage > best_coverage {
@@ -273,10 +350,12 @@ struct RawFont {
     style: Style,
     weight: Weight,
     coverage: cache::Coverage,
+    properties: Vec<FontconfigProperty>,
 }
 
 #[derive(Default)]
 struct Config {
+    font_dirs: Vec<PathBuf>,
     cache_dirs: Vec<PathBuf>,
     generics: [Vec<String>; 13],
     lang_maps: Vec<(String, StyleClass, String)>,
@@ -291,6 +370,10 @@ impl config::ParserSink for Config {
 
     fn include_path(&mut self, _path: &std::path::Path) {}
 
+    fn font_path(&mut self, path: &std::path::Path) {
+        self.font_dirs.push(path.into());
+    }
+
     fn cache_path(&mut self, path: &std::path::Path) {
         self.cache_dirs.push(path.into());
     }
@@ -436,42 +519,51 @@ pub struct FallbackFamilies {
 }
 
 impl FallbackFamilies {
-    fn select_default(&self, style: StyleClass) -> Option<FamilyId> {
-        let mut selected_rank = 0;
-        let mut selected_ix = 0;
-        for (i, family) in self.default.iter().enumerate() {
-            let rank = family.0.rank(style);
-            if rank > selected_rank {
-                selected_rank = rank;
-                selected_ix = i;
-            }
-        }
-        if selected_rank != 0 {
-            Some(self.default[selected_ix].1)
-        } else {
-            None
-        }
+    fn select_default(
+        &self,
+        style: StyleClass,
+        ranks: &HashMap<FamilyId, i32>,
+    ) -> Option<FamilyId> {
+        select_ranked(
+            self.default.iter().map(|(class, family)| (*class, *family)),
+            style,
+            ranks,
+        )
     }
 
-    fn select_lang(&self, lang: &str, style: StyleClass) -> Option<FamilyId> {
-        let mut selected_rank = 0;
-        let mut selected_ix = 0;
-        for (i, family) in self
-            .languages
-            .iter()
-            .enumerate()
-            .filter(|(_, family)| family.0 == lang)
-        {
-            let rank = family.1.rank(style);
-            if rank > selected_rank {
-                selected_rank = rank;
-                selected_ix = i;
-            }
-        }
-        if selected_rank != 0 {
-            Some(self.languages[selected_ix].2)
-        } else {
-            None
+    fn select_lang(
+        &self,
+        lang: &str,
+        style: StyleClass,
+        ranks: &HashMap<FamilyId, i32>,
+    ) -> Option<FamilyId> {
+        select_ranked(
+            self.languages
+                .iter()
+                .filter(|family| family.0 == lang)
+                .map(|(_, class, family)| (*class, *family)),
+            style,
+            ranks,
+        )
+    }
+}
+
+/// Selects the first family with the highest rank, where the adjustment
+/// from the ranking hook takes precedence over the style class.
+fn select_ranked(
+    families: impl Iterator<Item = (StyleClass, FamilyId)>,
+    style: StyleClass,
+    ranks: &HashMap<FamilyId, i32>,
+) -> Option<FamilyId> {
+    let mut selected: Option<((i32, u32), FamilyId)> = None;
+    for (class, family) in families {
+        let rank = (
+            ranks.get(&family).copied().unwrap_or_default(),
+            class.rank(style),
+        );
+        if selected.map_or(true, |(selected_rank, _)| rank > selected_rank) {
+            selected = Some((rank, family));
         }
     }
+    selected.map(|(_, family)| family)
 }
@@ -0,0 +1,117 @@
+//! Generic access to the properties of fontconfig font patterns.
+
+use std::sync::Arc;
+
+/// Names of the built-in fontconfig objects, indexed by object identifier.
+///
+/// See `fcobjs.h` in the fontconfig sources.
+const OBJECT_NAMES: &[&str] = &[
+    "",
+    "family",
+    "familylang",
+    "style",
+    "stylelang",
+    "fullname",
+    "fullnamelang",
+    "slant",
+    "weight",
+    "width",
+    "size",
+    "aspect",
+    "pixelsize",
+    "spacing",
+    "foundry",
+    "antialias",
+    "hintstyle",
+    "hinting",
+    "verticallayout",
+    "autohint",
+    "globaladvance",
+    "file",
+    "index",
+    "rasterizer",
+    "outline",
+    "scalable",
+    "dpi",
+    "rgba",
+    "scale",
+    "minspace",
+    "charwidth",
+    "charheight",
+    "matrix",
+    "charset",
+    "lang",
+    "fontversion",
+    "capability",
+    "fontformat",
+    "embolden",
+    "embeddedbitmap",
+    "decorative",
+    "lcdfilter",
+    "namelang",
+    "fontfeatures",
+    "prgname",
+    "hash",
+    "postscriptname",
+    "color",
+    "symbol",
+    "fontvariations",
+    "variable",
+    "fonthashint",
+    "order",
+];
+
+/// Returns the name of the built-in object with the given identifier.
+pub(super) fn object_name(id: usize) -> Option<&'static str> {
+    OBJECT_NAMES
+        .get(id)
+        .copied()
+        .filter(|name| !name.is_empty())
+}
+
+/// Value of a property in a fontconfig font pattern.
+#[derive(Clone, PartialEq, Debug)]
+pub enum FontconfigValue {
+    /// Integer value.
+    Int(i32),
+    /// Floating point value.
+    Double(f64),
+    /// Boolean value. The fontconfig "don't care" state is true.
+    Bool(bool),
+    /// String value.
+    String(Arc<str>),
+}
+
+/// Property of a font in the fontconfig cache that is not otherwise
+/// interpreted by the backend, such as `order`, `prgname` or `foundry`.
+///
+/// A property with multiple values is reported once for each value.
+#[derive(Clone, PartialEq, Debug)]
+pub struct FontconfigProperty {
+    /// Name of the fontconfig object, as used in configuration files.
+    pub name: &'static str,
+    /// Value of the property.
+    pub value: FontconfigValue,
+}
+
+/// Hook for adjusting the ranking of fallback families based on the
+/// properties that fontconfig stores for each font.
+///
+/// This allows properties stored in the font cache, such as the `order` of
+/// the font directory or values assigned by distribution specific
+/// `<match target="scan">` rules, to influence which family is selected.
+/// Attach a ranking with
+/// [`FontconfigFonts::with_ranking`](crate::FontconfigFonts::with_ranking).
+///
+/// Only properties with built-in fontconfig objects are available since
+/// the cache does not record the names of custom objects.
+pub trait FontconfigRanking {
+    /// Returns an adjustment to the rank of the family containing a font
+    /// with the given properties.
+    ///
+    /// Families with higher values are preferred, regardless of how well
+    /// they match the requested style class. Zero leaves the ranking
+    /// unchanged. The adjustment for a family is the largest of those for
+    /// its fonts.
+    fn rank(&self, family: &str, properties: &[FontconfigProperty]) -> i32;
+}
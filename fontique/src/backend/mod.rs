@@ -17,17 +17,150 @@ use super::{
     family_name::{FamilyName, FamilyNameMap},
     generic::GenericFamilyMap,
     scan, FallbackKey, FamilyId, FamilyInfo, FontInfo, GenericFamily, Script, SourceInfo,
+    SourceKind,
 };
 
 #[cfg(feature = "std")]
 #[allow(unused_imports)]
-use super::source::SourcePathMap;
+use super::{scan::ScanReport, source::SourcePathMap};
 
 #[cfg(feature = "system")]
-pub use system::SystemFonts;
+pub use system::SystemFonts as PlatformFonts;
 
 #[cfg(not(feature = "system"))]
-pub use null_backend::SystemFonts;
+pub use null_backend::SystemFonts as PlatformFonts;
+
+#[cfg(all(feature = "system", target_os = "linux"))]
+pub use system::{FontconfigProperty, FontconfigRanking, FontconfigValue};
+
+#[cfg(all(feature = "system", target_os = "windows"))]
+pub use system::GdiCompatibleMetrics;
+
+use alloc::{sync::Arc, vec::Vec};
+use core::ops::Range;
+
+/// Provider for the fonts that are available on the system.
+///
+/// By default, a [`Collection`](crate::Collection) uses the platform
+/// specific backend. Implement this trait to supply a custom set of fonts
+/// instead, such as a fixed set of fonts shipped with an application or a
+/// curated catalog on a server, and pass it to
+/// [`Collection::with_system_fonts`](crate::Collection::with_system_fonts).
+pub trait SystemFonts: Send {
+    /// Returns the map of names for all available families.
+    fn name_map(&self) -> Arc<FamilyNameMap>;
+
+    /// Returns the map of generic families to available families.
+    fn generic_families(&self) -> Arc<GenericFamilyMap>;
+
+    /// Returns the family object for the given family identifier.
+    fn family(&mut self, id: FamilyId) -> Option<FamilyInfo>;
+
+    /// Returns the preferred fallback family for the given key.
+    fn fallback(&mut self, key: FallbackKey) -> Option<FamilyId>;
+
+    /// Returns the preferred fallback family for the given key, using
+    /// `sample` in place of the built-in [`Script::sample`] text when
+    /// probing for support.
+    ///
+    /// The default implementation ignores the sample and calls
+    /// [`fallback`](Self::fallback).
+    fn fallback_for_sample(&mut self, key: FallbackKey, sample: &str) -> Option<FamilyId> {
+        let _ = sample;
+        self.fallback(key)
+    }
+
+    /// Splits the text into segments, each mapped to the first fallback
+    /// family that supports its characters.
+    ///
+    /// The default implementation returns no segments.
+    fn fallback_segments(
+        &mut self,
+        text: &str,
+        locale: Option<&str>,
+    ) -> Vec<(Range<usize>, Option<FamilyId>)> {
+        let _ = (text, locale);
+        Vec::new()
+    }
+
+    /// Rescans the fonts if the fonts installed on the system have changed
+    /// since the last call.
+    ///
+    /// Returns true if the fonts were rescanned. The name map and generic
+    /// families must then be requested again, and families and identifiers
+    /// returned previously may no longer be valid. A
+    /// [`Collection`](crate::Collection) calls this before each lookup, so
+    /// it should be cheap when nothing has changed.
+    ///
+    /// The default implementation returns false.
+    fn check_for_updates(&mut self) -> bool {
+        false
+    }
+
+    /// Returns a report of the fonts found while scanning font files and
+    /// the files that were skipped, such as Windows bitmap fonts.
+    ///
+    /// The default implementation returns `None`, which is also returned by
+    /// backends that don't read font files themselves.
+    #[cfg(feature = "std")]
+    fn scan_report(&self) -> Option<ScanReport> {
+        None
+    }
+}
+
+impl SystemFonts for PlatformFonts {
+    fn name_map(&self) -> Arc<FamilyNameMap> {
+        self.name_map.clone()
+    }
+
+    fn generic_families(&self) -> Arc<GenericFamilyMap> {
+        self.generic_families.clone()
+    }
+
+    #[cfg(feature = "system")]
+    fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
+        PlatformFonts::family(self, id)
+    }
+
+    #[cfg(not(feature = "system"))]
+    fn family(&mut self, _id: FamilyId) -> Option<FamilyInfo> {
+        None
+    }
+
+    #[cfg(feature = "system")]
+    fn fallback(&mut self, key: FallbackKey) -> Option<FamilyId> {
+        PlatformFonts::fallback(self, key)
+    }
+
+    #[cfg(not(feature = "system"))]
+    fn fallback(&mut self, _key: FallbackKey) -> Option<FamilyId> {
+        None
+    }
+
+    #[cfg(feature = "system")]
+    fn fallback_for_sample(&mut self, key: FallbackKey, sample: &str) -> Option<FamilyId> {
+        PlatformFonts::fallback_for_sample(self, key, sample)
+    }
+
+    #[cfg(all(feature = "system", target_os = "windows"))]
+    fn fallback_segments(
+        &mut self,
+        text: &str,
+        locale: Option<&str>,
+    ) -> Vec<(Range<usize>, Option<FamilyId>)> {
+        PlatformFonts::fallback_segments(self, text, locale)
+    }
+
+    #[cfg(all(feature = "system", target_os = "windows"))]
+    fn check_for_updates(&mut self) -> bool {
+        PlatformFonts::check_for_updates(self)
+    }
+
+    #[cfg(all(feature = "system", any(target_os = "linux", target_os = "macos")))]
+    fn scan_report(&self) -> Option<ScanReport> {
+        Some(PlatformFonts::scan_report(self).clone())
+    }
+}
 
 #[cfg(not(feature = "system"))]
 mod null_backend {
@@ -0,0 +1,215 @@
+//! Character coverage of a font.
+
+use super::Script;
+use alloc::vec::Vec;
+use read_fonts::{
+    tables::cmap::{Cmap, CmapSubtable, PlatformId},
+    types::GlyphId,
+    FontRef, TableProvider as _,
+};
+
+/// Number of codepoints covered by a single page.
+const PAGE_LEN: u32 = 256;
+
+/// Set of characters supported by a font.
+///
+/// Characters are stored as a sparse sequence of bitmap pages so that
+/// lookups are a binary search followed by a bit test.
+#[derive(Clone, PartialEq, Eq, Default, Debug)]
+pub struct Charset {
+    /// Pages sorted by index, where the index is the codepoint divided by
+    /// the page length.
+    pages: Vec<(u32, [u64; 4])>,
+    len: u32,
+}
+
+impl Charset {
+    /// Creates a charset from the character map of the given font.
+    ///
+    /// Prefers a full Unicode mapping, then a BMP Unicode mapping and
+    /// finally a symbol mapping, in which case the characters U+F000 to
+    /// U+F0FF are also reported at U+0000 to U+00FF.
+    pub fn from_font(font: &FontRef) -> Self {
+        let mut charset = Self::default();
+        if let Ok(cmap) = font.cmap() {
+            charset.add_cmap(&cmap);
+        }
+        charset
+    }
+
+    /// Returns true if the set contains the given character.
+    pub fn contains(&self, ch: char) -> bool {
+        let codepoint = ch as u32;
+        match self
+            .pages
+            .binary_search_by(|page| page.0.cmp(&(codepoint / PAGE_LEN)))
+        {
+            Ok(index) => {
+                let bit = codepoint % PAGE_LEN;
+                self.pages[index].1[bit as usize / 64] & (1 << (bit % 64)) != 0
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the number of characters in the set.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Returns the number of bytes allocated by the set.
+    #[cfg(feature = "std")]
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.pages.capacity() * core::mem::size_of::<(u32, [u64; 4])>()
+    }
+
+    /// Returns true if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the characters in the set in ascending
+    /// order.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.pages.iter().flat_map(|(index, bits)| {
+            (0..PAGE_LEN)
+                .filter(move |bit| bits[*bit as usize / 64] & (1 << (bit % 64)) != 0)
+                .filter_map(move |bit| char::from_u32(index * PAGE_LEN + bit))
+        })
+    }
+
+    /// Returns the scripts that are supported by the set, in ascending
+    /// order.
+    ///
+    /// Candidate scripts are determined from the Unicode script of each
+    /// character. A candidate is supported if the set contains every
+    /// character of its [`sample`](Script::sample), which rejects fonts that
+    /// only map a few stray characters of a script.
+    pub fn scripts(&self) -> Vec<Script> {
+        let mut scripts: Vec<Script> = Vec::new();
+        for ch in self.chars() {
+            let script = Script::from_char(ch);
+            if script.is_shared() {
+                continue;
+            }
+            if let Err(index) = scripts.binary_search(&script) {
+                scripts.insert(index, script);
+            }
+        }
+        scripts.retain(|script| {
+            script
+                .sample()
+                .map_or(true, |sample| sample.chars().all(|ch| self.contains(ch)))
+        });
+        scripts
+    }
+
+    fn add_cmap(&mut self, cmap: &Cmap) {
+        const ENCODING_MS_SYMBOL: u16 = 0;
+        const ENCODING_MS_UNICODE_CS: u16 = 1;
+        const ENCODING_MS_ID_UCS_4: u16 = 10;
+        let records = cmap.encoding_records();
+        let mut best: Option<(u8, CmapSubtable)> = None;
+        for record in records {
+            let priority = match (record.platform_id(), record.encoding_id()) {
+                (PlatformId::Windows, ENCODING_MS_ID_UCS_4) | (PlatformId::Unicode, 4 | 6) => 3,
+                (PlatformId::Windows, ENCODING_MS_UNICODE_CS) | (PlatformId::Unicode, _) => 2,
+                (PlatformId::Windows, ENCODING_MS_SYMBOL) => 1,
+                _ => continue,
+            };
+            if best.as_ref().map_or(false, |best| best.0 >= priority) {
+                continue;
+            }
+            if let Ok(
+                subtable @ (CmapSubtable::Format4(_)
+                | CmapSubtable::Format12(_)
+                | CmapSubtable::Format13(_)),
+            ) = record.subtable(cmap.offset_data())
+            {
+                best = Some((priority, subtable));
+            }
+        }
+        let Some((priority, subtable)) = best else {
+            return;
+        };
+        match subtable {
+            CmapSubtable::Format4(subtable) => {
+                for (start, end) in subtable.start_code().iter().zip(subtable.end_code()) {
+                    for codepoint in start.get()..=end.get() {
+                        // Segments may contain unmapped codepoints.
+                        if subtable
+                            .map_codepoint(codepoint)
+                            .map_or(false, |glyph_id| glyph_id != GlyphId::NOTDEF)
+                        {
+                            self.insert(codepoint as u32);
+                        }
+                    }
+                }
+            }
+            CmapSubtable::Format12(subtable) => {
+                for group in subtable.groups() {
+                    let end = group.end_char_code().min(char::MAX as u32);
+                    // Skip the first codepoint if it maps to the notdef glyph.
+                    let start = group
+                        .start_char_code()
+                        .saturating_add((group.start_glyph_id() == 0) as u32);
+                    for codepoint in start..=end {
+                        self.insert(codepoint);
+                    }
+                }
+            }
+            CmapSubtable::Format13(subtable) => {
+                // Each group maps the whole range to a single glyph.
+                for group in subtable.groups() {
+                    if group.glyph_id() == 0 {
+                        continue;
+                    }
+                    let end = group.end_char_code().min(char::MAX as u32);
+                    for codepoint in group.start_char_code()..=end {
+                        self.insert(codepoint);
+                    }
+                }
+            }
+            _ => {}
+        }
+        if priority == 1 {
+            // Symbol fonts are mapped in the private use area but are
+            // commonly addressed at U+0000 to U+00FF.
+            for codepoint in 0xF000..=0xF0FF {
+                if self.contains_codepoint(codepoint) {
+                    self.insert(codepoint - 0xF000);
+                }
+            }
+        }
+    }
+
+    fn contains_codepoint(&self, codepoint: u32) -> bool {
+        char::from_u32(codepoint).map_or(false, |ch| self.contains(ch))
+    }
+
+    fn insert(&mut self, codepoint: u32) {
+        if char::from_u32(codepoint).is_none() {
+            return;
+        }
+        let page_index = codepoint / PAGE_LEN;
+        // Codepoints are mostly added in ascending order so check the last
+        // page first.
+        let index = match self.pages.last() {
+            Some(last) if last.0 == page_index => self.pages.len() - 1,
+            _ => match self.pages.binary_search_by(|page| page.0.cmp(&page_index)) {
+                Ok(index) => index,
+                Err(index) => {
+                    self.pages.insert(index, (page_index, [0; 4]));
+                    index
+                }
+            },
+        };
+        let bit = codepoint % PAGE_LEN;
+        let word = &mut self.pages[index].1[bit as usize / 64];
+        let mask = 1 << (bit % 64);
+        if *word & mask == 0 {
+            *word |= mask;
+            self.len += 1;
+        }
+    }
+}
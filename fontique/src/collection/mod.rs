@@ -1,26 +1,38 @@
 //! Support for working with font collections.
 
+#[cfg(feature = "std")]
+mod prefetch;
 mod query;
+#[cfg(feature = "std")]
+mod runs;
+mod snapshot;
 
 pub use query::{Query, QueryFamily, QueryFont, QueryStatus};
+pub use snapshot::CollectionSnapshot;
 
 use crate::SourceCache;
 
 use super::{
-    backend::SystemFonts,
-    fallback::{FallbackKey, FallbackMap},
+    backend::{PlatformFonts, SystemFonts},
+    charset::Charset,
+    fallback::{ColorPreference, FallbackKey, FallbackMap},
     family::{FamilyId, FamilyInfo},
     family_name::{FamilyName, FamilyNameMap},
-    font::FontInfo,
+    font::{FontInfo, Synthesis},
     generic::GenericFamilyMap,
     source::{SourceId, SourceInfo, SourceKind},
-    Blob, GenericFamily, Script,
+    Attributes, Blob, GenericFamily, Script,
 };
-use alloc::{string::String, sync::Arc, vec::Vec};
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
 use core::sync::atomic::AtomicU64;
 use hashbrown::HashMap;
 #[cfg(feature = "std")]
-use std::sync::{atomic::Ordering, Mutex};
+use {
+    super::convert::{Converter, FontConverter},
+    super::remote::{Remote, RemoteFontProvider, RemoteFontRequest},
+    super::scan::ScanReport,
+    std::sync::{atomic::Ordering, Mutex},
+};
 
 type FamilyMap = HashMap<FamilyId, Option<FamilyInfo>>;
 
@@ -42,6 +54,24 @@ pub struct CollectionOptions {
     ///
     /// The default value is true.
     pub system_fonts: bool,
+
+    /// Policy for selecting a family when a family with the same name
+    /// exists in both the registered and system fonts.
+    ///
+    /// The default value is [`FamilyPrecedence::Registered`].
+    pub precedence: FamilyPrecedence,
+
+    /// If true, system families that previously followed a requested
+    /// family are loaded on a background thread.
+    ///
+    /// This hides the cost of parsing large families, such as the
+    /// hundreds of faces in some Noto families, at the expense of an
+    /// additional thread. Custom [`SystemFonts`] implementations must
+    /// cache loaded families to benefit. This has no effect without the
+    /// `std` feature.
+    ///
+    /// The default value is false.
+    pub prefetch: bool,
 }
 
 impl Default for CollectionOptions {
@@ -49,10 +79,41 @@ impl Default for CollectionOptions {
         Self {
             shared: false,
             system_fonts: true,
+            precedence: FamilyPrecedence::default(),
+            prefetch: false,
         }
     }
 }
 
+/// Policy for selecting between families with the same name from different
+/// sources.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum FamilyPrecedence {
+    /// Registered fonts always take precedence over system fonts.
+    #[default]
+    Registered,
+    /// System fonts always take precedence over registered fonts.
+    System,
+    /// The family containing the font with the highest revision, as
+    /// specified by the `fontRevision` field of the `head` table, takes
+    /// precedence. The version string in the `name` table is used for fonts
+    /// without a revision and to break ties. Registered fonts win remaining
+    /// ties.
+    ///
+    /// The selected family is determined when fonts are registered and when
+    /// the system fonts change, rather than on each lookup.
+    NewestRevision,
+}
+
+/// The source from which a family was selected.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum FamilyOrigin {
+    /// The family was registered with the collection.
+    Registered,
+    /// The family was provided by the system font collection.
+    System,
+}
+
 /// Collection of fonts.
 #[derive(Clone)]
 pub struct Collection {
@@ -69,6 +130,31 @@ impl Collection {
         }
     }
 
+    /// Creates a new collection with the given options that uses the
+    /// provided system fonts in place of the platform backend.
+    ///
+    /// The [`system_fonts`](CollectionOptions::system_fonts) option is
+    /// ignored.
+    #[cfg(feature = "std")]
+    pub fn with_system_fonts(
+        options: CollectionOptions,
+        system_fonts: impl SystemFonts + 'static,
+    ) -> Self {
+        let mut inner = Inner::new(CollectionOptions {
+            system_fonts: false,
+            ..options
+        });
+        let system = System::from_fonts(Box::new(system_fonts));
+        if options.prefetch {
+            inner.prefetcher = prefetch::Prefetcher::new(system.fonts.clone());
+        }
+        inner.system = Some(system);
+        Self {
+            inner,
+            query_state: Default::default(),
+        }
+    }
+
     /// Returns an iterator over all available family names in the collection.
     ///
     /// This includes both system and registered fonts.
@@ -86,6 +172,15 @@ impl Collection {
         self.inner.family_name(id)
     }
 
+    /// Returns the source of the family with the given identifier.
+    ///
+    /// This can be used to determine which family was selected by the
+    /// [`precedence`](CollectionOptions::precedence) policy when the same
+    /// family name exists in multiple sources.
+    pub fn family_origin(&mut self, id: FamilyId) -> Option<FamilyOrigin> {
+        self.inner.family_origin(id)
+    }
+
     /// Returns the family object for the given family identifier.
     pub fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
         self.inner.family(id)
@@ -133,6 +228,100 @@ impl Collection {
         self.inner.fallback_families(key)
     }
 
+    /// Returns the fallback families for the given key, ranked by the
+    /// given color preference.
+    ///
+    /// Families are considered color families if any of their fonts contain
+    /// color glyphs. The order is otherwise preserved.
+    pub fn fallback_families_by_color(
+        &mut self,
+        key: impl Into<FallbackKey>,
+        preference: ColorPreference,
+    ) -> Vec<FamilyId> {
+        self.inner.fallback_families_by_color(key, preference)
+    }
+
+    /// Splits the text into segments, each mapped to the first family in the
+    /// system fallback cascade that supports its characters.
+    ///
+    /// Segment ranges are byte offsets into the text and are suitable for
+    /// use as shaping runs. Segments that are not supported by any system
+    /// family are mapped to `None`.
+    #[cfg(all(feature = "system", target_os = "windows"))]
+    pub fn fallback_segments(
+        &mut self,
+        text: &str,
+        locale: Option<&str>,
+    ) -> Vec<(core::ops::Range<usize>, Option<FamilyId>)> {
+        self.inner.fallback_segments(text, locale)
+    }
+
+    /// Splits the text into runs, each mapped to the font from the fallback
+    /// families that best matches the attributes and supports its
+    /// characters.
+    ///
+    /// The fallback families are selected by the script of each grapheme
+    /// cluster and the locale. Characters with a shared script, such as
+    /// spaces and punctuation, take on the script of the preceding text and
+    /// stay in the current run when its font supports them.
+    ///
+    /// Run ranges are byte offsets into the text that never split a
+    /// grapheme cluster and are suitable for use as shaping runs. The runs
+    /// cover the whole text unless no fallback font is available at all.
+    /// Clusters that are not supported by any font are added to the
+    /// neighboring run.
+    #[cfg(feature = "std")]
+    pub fn fallback_runs(
+        &mut self,
+        text: &str,
+        attributes: Attributes,
+        locale: Option<&str>,
+    ) -> Vec<(core::ops::Range<usize>, FontInfo, Synthesis)> {
+        self.inner.fallback_runs(text, attributes, locale)
+    }
+
+    /// Sets the provider that is asked for fonts when a family or fallback
+    /// cannot be found in the registered or system fonts.
+    ///
+    /// Fonts arrive asynchronously, so the lookup that triggers a request
+    /// fails and a later lookup succeeds once the data has been delivered.
+    /// Enable the [`shared`](CollectionOptions::shared) option to make the
+    /// delivered fonts visible to all clones of the collection.
+    #[cfg(feature = "std")]
+    pub fn set_remote_provider(&mut self, provider: impl RemoteFontProvider + 'static) {
+        self.inner.remote = Some(Remote::new(Arc::new(provider)));
+    }
+
+    /// Sets the converter for system font files in formats that can't be
+    /// loaded directly, such as PostScript Type 1.
+    ///
+    /// Supported files in the [scan report](Self::system_scan_report) are
+    /// converted and registered immediately and new files are converted
+    /// when the system fonts are rescanned. Converted fonts are treated as
+    /// registered fonts rather than system fonts.
+    ///
+    /// Returns a list of pairs each containing the family identifier and fonts
+    /// added to that family.
+    #[cfg(feature = "std")]
+    pub fn set_font_converter(
+        &mut self,
+        converter: impl FontConverter + 'static,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.inner.converter = Some(Converter::new(Arc::new(converter)));
+        self.inner.convert_system_fonts()
+    }
+
+    /// Sets the text used to probe the system for fallback families that
+    /// support the given script, overriding the built-in
+    /// [`Script::sample`].
+    ///
+    /// This only affects keys that have not yet been resolved against the
+    /// system fonts.
+    #[cfg(feature = "std")]
+    pub fn set_script_sample(&mut self, script: impl Into<Script>, sample: &str) {
+        self.inner.set_script_sample(script.into(), sample)
+    }
+
     /// Replaces the set of family identifers associated with the fallback
     /// key.
     pub fn set_fallbacks(
@@ -152,6 +341,41 @@ impl Collection {
         self.inner.append_fallbacks(key, families)
     }
 
+    /// Returns an immutable view of the collection that can be shared
+    /// between threads.
+    ///
+    /// The snapshot includes the registered fonts, the system families and
+    /// the fallbacks resolved so far. Later changes to the collection are not
+    /// visible in the snapshot.
+    pub fn snapshot(&mut self) -> CollectionSnapshot {
+        self.inner.snapshot()
+    }
+
+    /// Returns a report of the fonts found by the system font backend and
+    /// the font files that it skipped, such as Windows bitmap fonts.
+    ///
+    /// Returns `None` if system fonts are disabled or the backend doesn't
+    /// read font files itself.
+    #[cfg(feature = "std")]
+    pub fn system_scan_report(&self) -> Option<ScanReport> {
+        self.inner
+            .system
+            .as_ref()?
+            .fonts
+            .lock()
+            .unwrap()
+            .scan_report()
+    }
+
+    /// Returns the estimated number of bytes held by the collection.
+    ///
+    /// This includes the family and fallback maps, the metadata of all
+    /// loaded families and the data of registered fonts. Memory held by the
+    /// system font backend and memory mapped font files are not included.
+    pub fn memory_usage(&mut self) -> usize {
+        self.inner.memory_usage()
+    }
+
     /// Returns an object for selecting fonts from this collection.
     pub fn query<'a>(&'a mut self, source_cache: &'a mut SourceCache) -> Query<'a> {
         Query::new(self, source_cache)
@@ -162,7 +386,27 @@ impl Collection {
     /// Returns a list of pairs each containing the family identifier and fonts
     /// added to that family.
     pub fn register_fonts(&mut self, data: Vec<u8>) -> Vec<(FamilyId, Vec<FontInfo>)> {
-        self.inner.register_fonts(data)
+        self.inner.register_fonts(data, None, None)
+    }
+
+    /// Registers all fonts that exist in the given data under the given
+    /// family name, ignoring the names in the fonts.
+    ///
+    /// This allows addressing fonts with missing or mangled names, such as
+    /// subset fonts embedded in documents with names like "ABCDEF+Times", by
+    /// their original family. If attributes are given, they replace the
+    /// stretch, style and weight read from the fonts for matching.
+    ///
+    /// Returns a list of pairs each containing the family identifier and fonts
+    /// added to that family.
+    pub fn register_fonts_as(
+        &mut self,
+        data: Vec<u8>,
+        family_name: &str,
+        attributes: Option<Attributes>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        self.inner
+            .register_fonts(data, Some(family_name), attributes)
     }
 }
 
@@ -182,19 +426,48 @@ struct Inner {
     #[allow(unused)]
     shared_version: u64,
     fallback_cache: FallbackCache,
+    precedence: FamilyPrecedence,
+    /// Family selected for each registered family that shares its name
+    /// with a system family, for the newest revision precedence.
+    newest: HashMap<FamilyId, FamilyId>,
+    #[cfg(feature = "std")]
+    script_samples: HashMap<Script, Arc<str>>,
+    #[cfg(feature = "std")]
+    remote: Option<Remote>,
+    #[cfg(feature = "std")]
+    converter: Option<Converter>,
+    #[cfg(feature = "std")]
+    prefetcher: Option<prefetch::Prefetcher>,
 }
 
 impl Inner {
     /// Creates a new collection with the given options.
     pub fn new(options: CollectionOptions) -> Self {
-        let system = options.system_fonts.then(System::new);
+        let system = options
+            .system_fonts
+            .then(|| System::from_fonts(Box::new(PlatformFonts::new())));
         let shared = options.shared.then(|| Arc::new(Shared::default()));
+        #[cfg(feature = "std")]
+        let prefetcher = system
+            .as_ref()
+            .filter(|_| options.prefetch)
+            .and_then(|system| prefetch::Prefetcher::new(system.fonts.clone()));
         Self {
             system,
             data: CommonData::default(),
             shared,
             shared_version: 0,
             fallback_cache: Default::default(),
+            precedence: options.precedence,
+            newest: Default::default(),
+            #[cfg(feature = "std")]
+            script_samples: Default::default(),
+            #[cfg(feature = "std")]
+            remote: None,
+            #[cfg(feature = "std")]
+            converter: None,
+            #[cfg(feature = "std")]
+            prefetcher,
         }
     }
 
@@ -213,15 +486,91 @@ impl Inner {
     /// Returns the family identifier for the given family name.
     pub fn family_id(&mut self, name: &str) -> Option<FamilyId> {
         self.sync_shared();
-        self.data
+        let ours = self.data.family_names.get(name).map(|n| n.id());
+        let system = self
+            .system
+            .as_ref()
+            .and_then(|sys| sys.family_names.get(name))
+            .map(|n| n.id());
+        match (ours, system) {
+            (Some(ours), Some(system)) => Some(self.select_family(ours, system)),
+            (None, None) => {
+                #[cfg(feature = "std")]
+                if let Some(remote) = &self.remote {
+                    remote.request(RemoteFontRequest::Family(name.into()));
+                }
+                None
+            }
+            (ours, system) => ours.or(system),
+        }
+    }
+
+    /// Returns the source of the family with the given identifier.
+    pub fn family_origin(&mut self, id: FamilyId) -> Option<FamilyOrigin> {
+        self.sync_shared();
+        if self.data.family_names.get_by_id(id).is_some() {
+            Some(FamilyOrigin::Registered)
+        } else if self
+            .system
+            .as_ref()
+            .and_then(|sys| sys.family_names.get_by_id(id))
+            .is_some()
+        {
+            Some(FamilyOrigin::System)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the family that takes precedence when a registered and a
+    /// system family have the same name.
+    fn select_family(&mut self, ours: FamilyId, system: FamilyId) -> FamilyId {
+        match self.precedence {
+            FamilyPrecedence::Registered => ours,
+            FamilyPrecedence::System => system,
+            FamilyPrecedence::NewestRevision => {
+                if let Some(id) = self.newest.get(&ours) {
+                    return *id;
+                }
+                let id = if self.family_revision(system) > self.family_revision(ours) {
+                    system
+                } else {
+                    ours
+                };
+                self.newest.insert(ours, id);
+                id
+            }
+        }
+    }
+
+    /// Selects the family for each registered family that shares its name
+    /// with a system family and hasn't been resolved yet.
+    fn resolve_newest(&mut self) {
+        let Some(system) = &self.system else {
+            return;
+        };
+        let conflicts = self
+            .data
             .family_names
-            .get(name)
-            .or_else(|| {
-                self.system
-                    .as_ref()
-                    .and_then(|sys| sys.family_names.get(name))
+            .iter()
+            .filter(|name| !self.newest.contains_key(&name.id()))
+            .filter_map(|name| Some((name.id(), system.family_names.get(name.name())?.id())))
+            .collect::<Vec<_>>();
+        for (ours, system) in conflicts {
+            self.select_family(ours, system);
+        }
+    }
+
+    /// Returns the highest revision key of the fonts in the given family.
+    fn family_revision(&mut self, id: FamilyId) -> (f32, f32) {
+        self.family(id)
+            .map(|family| {
+                family.fonts().iter().map(|font| font.revision_key()).fold(
+                    (0.0, 0.0),
+                    |newest, key| if key > newest { key } else { newest },
+                )
             })
-            .map(|n| n.id())
+            .unwrap_or_default()
     }
 
     /// Returns the family name for the given family identifier.
@@ -241,6 +590,10 @@ impl Inner {
     /// Returns the family object for the given family identifier.
     pub fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
         self.sync_shared();
+        #[cfg(feature = "std")]
+        if let Some(prefetcher) = &mut self.prefetcher {
+            prefetcher.record(id);
+        }
         if let Some(family) = self.data.families.get(&id) {
             family.as_ref().cloned()
         } else {
@@ -348,19 +701,80 @@ impl Inner {
                 self.fallback_cache.set(script, lang_key, families);
             } else if let Some(system) = self.system.as_ref() {
                 let mut system = system.fonts.lock().unwrap();
-                if let Some(family) = system.fallback(selector) {
+                let family = match self.script_samples.get(&script) {
+                    Some(sample) => system.fallback_for_sample(selector, sample),
+                    None => system.fallback(selector),
+                };
+                if let Some(family) = family {
                     self.data.fallbacks.set(selector, core::iter::once(family));
                     self.fallback_cache.set(script, lang_key, &[family]);
                 }
             }
+            #[cfg(feature = "std")]
+            if self.fallback_cache.families.is_empty() {
+                if let Some(remote) = &self.remote {
+                    remote.request(RemoteFontRequest::Fallback(selector));
+                }
+            }
             #[cfg(not(feature = "std"))]
             if let Some(families) = self.data.fallbacks.get(selector) {
                 self.fallback_cache.set(script, lang_key, families);
             }
+            // Without fallbacks from the system, use the registered
+            // families that cover the script.
+            if self.fallback_cache.families.is_empty() {
+                if let Some(families) = self.data.script_coverage.get(&script) {
+                    self.fallback_cache.set(script, lang_key, families);
+                }
+            }
         }
         self.fallback_cache.families.iter().copied()
     }
 
+    /// Returns the fallback families for the given key, ranked by the
+    /// given color preference.
+    pub fn fallback_families_by_color(
+        &mut self,
+        key: impl Into<FallbackKey>,
+        preference: ColorPreference,
+    ) -> Vec<FamilyId> {
+        let mut families: Vec<FamilyId> = self.fallback_families(key).collect();
+        if preference != ColorPreference::None {
+            // Stable sort to keep the order within each rank.
+            families.sort_by_cached_key(|id| {
+                let has_color = self.family(*id).map_or(false, |family| {
+                    family.fonts().iter().any(|font| font.has_color_glyphs())
+                });
+                preference.rank(has_color)
+            });
+        }
+        families
+    }
+
+    /// Splits the text into segments, each mapped to the first family in the
+    /// system fallback cascade that supports its characters.
+    #[cfg(all(feature = "system", target_os = "windows"))]
+    pub fn fallback_segments(
+        &mut self,
+        text: &str,
+        locale: Option<&str>,
+    ) -> Vec<(core::ops::Range<usize>, Option<FamilyId>)> {
+        match &self.system {
+            Some(system) => system.fonts.lock().unwrap().fallback_segments(text, locale),
+            None => Vec::new(),
+        }
+    }
+
+    /// Sets the text used to probe the system for fallbacks for the given
+    /// script.
+    #[cfg(feature = "std")]
+    pub fn set_script_sample(&mut self, script: Script, sample: &str) {
+        self.script_samples.insert(script, sample.into());
+        if self.fallback_cache.script == Some(script) {
+            self.fallback_cache.reset();
+        }
+    }
+
     /// Replaces the set of family identifers associated with the fallback
     /// key.
     pub fn set_fallbacks(
@@ -400,24 +814,173 @@ impl Inner {
         self.data.fallbacks.append(key, families)
     }
 
-    /// Registers all fonts that exist in the given data.
+    /// Registers all fonts that exist in the given data, optionally under
+    /// the given family name and with the given attributes.
     ///
     /// Returns a list of pairs each containing the family identifier and fonts
     /// added to that family.
-    pub fn register_fonts(&mut self, data: Vec<u8>) -> Vec<(FamilyId, Vec<FontInfo>)> {
+    pub fn register_fonts(
+        &mut self,
+        data: Vec<u8>,
+        family_name: Option<&str>,
+        attributes: Option<Attributes>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
         #[cfg(feature = "std")]
-        if let Some(shared) = &self.shared {
-            let result = shared.data.lock().unwrap().register_fonts(data);
+        let result = if let Some(shared) = &self.shared {
+            let result = shared
+                .data
+                .lock()
+                .unwrap()
+                .register_fonts(data, family_name, attributes);
             shared.bump_version();
             result
         } else {
-            self.data.register_fonts(data)
+            self.data.register_fonts(data, family_name, attributes)
+        };
+        #[cfg(not(feature = "std"))]
+        let result = self.data.register_fonts(data, family_name, attributes);
+        if self.precedence == FamilyPrecedence::NewestRevision {
+            // Families that gained fonts may now be newer than the system
+            // families with the same name.
+            for (id, _) in &result {
+                self.newest.remove(id);
+            }
+            self.sync_shared();
+            self.resolve_newest();
         }
+        result
+    }
+
+    /// Returns the estimated number of bytes held by the collection.
+    pub fn memory_usage(&mut self) -> usize {
+        self.sync_shared();
+        let system = self.system.as_ref().map_or(0, |system| {
+            system.family_names.memory_usage() + system.generic_families.memory_usage()
+        });
+        #[cfg(feature = "std")]
+        let script_samples = self.script_samples.capacity()
+            * core::mem::size_of::<(Script, Arc<str>)>()
+            + self
+                .script_samples
+                .values()
+                .map(|sample| sample.len())
+                .sum::<usize>();
         #[cfg(not(feature = "std"))]
-        self.data.register_fonts(data)
+        let script_samples = 0;
+        core::mem::size_of::<Self>()
+            + self.data.memory_usage()
+            + system
+            + self.fallback_cache.families.capacity() * core::mem::size_of::<FamilyId>()
+            + script_samples
+    }
+
+    /// Returns an immutable view of the current state of the collection.
+    pub fn snapshot(&mut self) -> CollectionSnapshot {
+        self.sync_shared();
+        if self.precedence == FamilyPrecedence::NewestRevision {
+            self.resolve_newest();
+        }
+        #[cfg(feature = "std")]
+        self.resolve_sample_fallbacks();
+        CollectionSnapshot::new(
+            self.data.clone(),
+            self.system.clone(),
+            self.precedence,
+            self.newest.clone(),
+            #[cfg(feature = "std")]
+            self.script_samples.clone(),
+        )
+    }
+
+    /// Requests the system fallbacks for the scripts with samples that
+    /// haven't been resolved yet, so that snapshots can answer them without
+    /// locking the system fonts.
+    #[cfg(feature = "std")]
+    fn resolve_sample_fallbacks(&mut self) {
+        let Some(system) = &self.system else {
+            return;
+        };
+        let mut fonts = system.fonts.lock().unwrap();
+        for (script, sample) in &self.script_samples {
+            let key = FallbackKey::from(*script);
+            if self.data.fallbacks.get(key).is_some() {
+                continue;
+            }
+            if let Some(family) = fonts.fallback_for_sample(key, sample) {
+                self.data.fallbacks.set(key, core::iter::once(family));
+            }
+        }
+    }
+
+    /// Registers fonts delivered by the remote provider.
+    #[cfg(feature = "std")]
+    fn sync_remote(&mut self) {
+        let Some(delivered) = self.remote.as_ref().map(|remote| remote.take_delivered()) else {
+            return;
+        };
+        if delivered.is_empty() {
+            return;
+        }
+        for (request, data) in delivered {
+            let families = self.register_fonts(data, None, None);
+            if let RemoteFontRequest::Fallback(key) = request {
+                self.append_fallbacks(key, families.iter().map(|(id, _)| *id));
+            }
+        }
+        self.fallback_cache.reset();
+    }
+
+    /// Drops the families and fallbacks loaded from the system fonts if
+    /// the fonts installed on the system have changed.
+    #[cfg(feature = "std")]
+    fn sync_system(&mut self) {
+        let Some(system) = &mut self.system else {
+            return;
+        };
+        let mut fonts = system.fonts.lock().unwrap();
+        if !fonts.check_for_updates() {
+            return;
+        }
+        let old_names = core::mem::replace(&mut system.family_names, fonts.name_map());
+        system.generic_families = fonts.generic_families();
+        drop(fonts);
+        let is_stale = |id: FamilyId| old_names.get_by_id(id).is_some();
+        self.data.families.retain(|id, _| !is_stale(*id));
+        self.data.fallbacks.retain(|id| !is_stale(id));
+        self.fallback_cache.reset();
+        self.newest.clear();
+        self.convert_system_fonts();
+    }
+
+    /// Converts and registers the system font files that the font
+    /// converter supports.
+    #[cfg(feature = "std")]
+    fn convert_system_fonts(&mut self) -> Vec<(FamilyId, Vec<FontInfo>)> {
+        let Some(converter) = self.converter.clone() else {
+            return Vec::new();
+        };
+        let Some(report) = self
+            .system
+            .as_ref()
+            .and_then(|system| system.fonts.lock().unwrap().scan_report())
+        else {
+            return Vec::new();
+        };
+        let mut families = Vec::new();
+        for data in converter.convert(&report) {
+            families.extend(self.register_fonts(data, None, None));
+        }
+        if !families.is_empty() {
+            self.fallback_cache.reset();
+        }
+        families
     }
 
     fn sync_shared(&mut self) {
+        #[cfg(feature = "std")]
+        self.sync_system();
+        #[cfg(feature = "std")]
+        self.sync_remote();
         #[cfg(feature = "std")]
         if let Some(shared) = &self.shared {
             let version = shared.version.load(Ordering::Acquire);
@@ -429,6 +992,7 @@ impl Inner {
                 self.data = shared.data.lock().unwrap().clone();
                 self.shared_version = version;
                 self.fallback_cache.reset();
+                self.newest.clear();
             }
         }
     }
@@ -500,16 +1064,15 @@ impl FallbackCache {
 #[derive(Clone)]
 struct System {
     #[cfg(feature = "std")]
-    fonts: Arc<Mutex<SystemFonts>>,
+    fonts: Arc<Mutex<Box<dyn SystemFonts>>>,
     family_names: Arc<FamilyNameMap>,
     generic_families: Arc<GenericFamilyMap>,
 }
 
 impl System {
-    fn new() -> Self {
-        let fonts = SystemFonts::new();
-        let family_names = fonts.name_map.clone();
-        let generic_families = fonts.generic_families.clone();
+    fn from_fonts(fonts: Box<dyn SystemFonts>) -> Self {
+        let family_names = fonts.name_map();
+        let generic_families = fonts.generic_families();
         #[cfg(feature = "std")]
         let fonts = Arc::new(Mutex::new(fonts));
         Self {
@@ -528,25 +1091,69 @@ struct CommonData {
     families: FamilyMap,
     generic_families: GenericFamilyMap,
     fallbacks: FallbackMap,
+    /// Registered families that support each script, computed from the
+    /// character maps of their fonts.
+    script_coverage: HashMap<Script, Vec<FamilyId>>,
 }
 
 impl CommonData {
-    fn register_fonts(&mut self, data: Vec<u8>) -> Vec<(FamilyId, Vec<FontInfo>)> {
+    /// Returns the estimated number of bytes held by the data, including
+    /// the data of registered fonts.
+    fn memory_usage(&self) -> usize {
+        use core::mem::size_of;
+        let mut blobs = hashbrown::HashSet::new();
+        let mut usage = self.family_names.memory_usage()
+            + self.families.capacity() * size_of::<(FamilyId, Option<FamilyInfo>)>()
+            + self.generic_families.memory_usage()
+            + self.fallbacks.memory_usage()
+            + self.script_coverage.capacity() * size_of::<(Script, Vec<FamilyId>)>();
+        for families in self.script_coverage.values() {
+            usage += families.capacity() * size_of::<FamilyId>();
+        }
+        for family in self.families.values().flatten() {
+            usage += family.memory_usage();
+            for font in family.fonts() {
+                match &font.source().kind {
+                    SourceKind::Memory(blob) => {
+                        if blobs.insert(blob.id()) {
+                            usage += blob.len();
+                        }
+                    }
+                    #[cfg(feature = "std")]
+                    SourceKind::Path(_) => {}
+                }
+            }
+        }
+        usage
+    }
+
+    fn register_fonts(
+        &mut self,
+        data: Vec<u8>,
+        family_name_override: Option<&str>,
+        attributes: Option<Attributes>,
+    ) -> Vec<(FamilyId, Vec<FontInfo>)> {
         let blob = Blob::new(Arc::new(data));
         let mut families: HashMap<FamilyId, (FamilyName, Vec<FontInfo>)> = Default::default();
         let mut family_name = String::default();
         let data_id = SourceId::new();
+        #[cfg(feature = "std")]
+        let mut shared_tables = super::scan::SharedTables::default();
         super::scan::scan_memory(blob.as_ref(), |scanned_font| {
             use read_fonts::types::NameId;
             family_name.clear();
-            let family_chars = scanned_font
-                .english_or_first_name(NameId::TYPOGRAPHIC_FAMILY_NAME)
-                .or_else(|| scanned_font.english_or_first_name(NameId::FAMILY_NAME))
-                .map(|name| name.chars());
-            let Some(family_chars) = family_chars else {
-                return;
-            };
-            family_name.extend(family_chars);
+            if let Some(name) = family_name_override {
+                family_name.push_str(name);
+            } else {
+                let family_chars = scanned_font
+                    .english_or_first_name(NameId::TYPOGRAPHIC_FAMILY_NAME)
+                    .or_else(|| scanned_font.english_or_first_name(NameId::FAMILY_NAME))
+                    .map(|name| name.chars());
+                let Some(family_chars) = family_chars else {
+                    return;
+                };
+                family_name.extend(family_chars);
+            }
             if family_name.is_empty() {
                 return;
             }
@@ -554,16 +1161,47 @@ impl CommonData {
                 id: data_id,
                 kind: SourceKind::Memory(blob.clone()),
             };
-            let Some(font) = FontInfo::from_font_ref(&scanned_font.font, data, scanned_font.index)
+            let Some(mut font) =
+                FontInfo::from_font_ref(&scanned_font.font, data, scanned_font.index)
             else {
                 return;
             };
+            if let Some(attributes) = attributes {
+                font.set_attributes(attributes);
+            }
             let name = self.family_names.get_or_insert(&family_name);
-            families
+            // Members of a collection that share a character map also share
+            // the character set, which is kept for later coverage queries.
+            #[cfg(feature = "std")]
+            let charset = {
+                shared_tables.share(&mut font, blob.as_ref(), &scanned_font.font);
+                font.charset()
+            };
+            #[cfg(not(feature = "std"))]
+            let charset = &Charset::from_font(&scanned_font.font);
+            for script in charset.scripts() {
+                let families = self.script_coverage.entry(script).or_default();
+                if !families.contains(&name.id()) {
+                    families.push(name.id());
+                }
+            }
+            // Skip fonts that are already registered in this family.
+            if let Some(Some(family)) = self.families.get(&name.id()) {
+                if family
+                    .fonts()
+                    .iter()
+                    .any(|f| f.fingerprint() == font.fingerprint())
+                {
+                    return;
+                }
+            }
+            let fonts = &mut families
                 .entry(name.id())
                 .or_insert_with(|| (name, Default::default()))
-                .1
-                .push(font);
+                .1;
+            if !fonts.iter().any(|f| f.fingerprint() == font.fingerprint()) {
+                fonts.push(font);
+            }
         });
         for (id, (name, fonts)) in &families {
             if let Some(Some(family)) = self.families.get_mut(id) {
@@ -597,3 +1235,166 @@ impl Shared {
         self.version.fetch_add(1, Ordering::Release);
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::testing::{TestFont, TestSystemFonts};
+
+    fn collection(system: TestSystemFonts, precedence: FamilyPrecedence) -> Collection {
+        let options = CollectionOptions {
+            precedence,
+            ..Default::default()
+        };
+        Collection::with_system_fonts(options, system)
+    }
+
+    #[test]
+    fn fixed_precedence() {
+        for precedence in [FamilyPrecedence::Registered, FamilyPrecedence::System] {
+            let mut system = TestSystemFonts::default();
+            let system_id = system.add_family("Conflict", TestFont::new("Conflict").build());
+            let mut collection = collection(system, precedence);
+            let (ours, _) = collection
+                .register_fonts(TestFont::new("Conflict").revision(2.0).build())
+                .remove(0);
+            assert_ne!(ours, system_id);
+            let (expected, origin) = match precedence {
+                FamilyPrecedence::Registered => (ours, FamilyOrigin::Registered),
+                _ => (system_id, FamilyOrigin::System),
+            };
+            assert_eq!(collection.family_id("Conflict"), Some(expected));
+            assert_eq!(collection.family_origin(expected), Some(origin));
+        }
+    }
+
+    #[test]
+    fn newest_revision_precedence() {
+        let mut system = TestSystemFonts::default();
+        let system_id =
+            system.add_family("Conflict", TestFont::new("Conflict").revision(2.0).build());
+        let system_tie =
+            system.add_family("Tie", TestFont::new("Tie").version("Version 1.005").build());
+        let loaded = system.loaded.clone();
+        let mut collection = collection(system, FamilyPrecedence::NewestRevision);
+        let (ours, _) = collection
+            .register_fonts(TestFont::new("Conflict").revision(1.5).build())
+            .remove(0);
+        // The winner is resolved when the fonts are registered.
+        assert_eq!(loaded.lock().unwrap().as_slice(), [system_id]);
+        assert_eq!(collection.family_id("Conflict"), Some(system_id));
+        assert_eq!(collection.family_id("conflict"), Some(system_id));
+        assert_eq!(loaded.lock().unwrap().len(), 1);
+        // A newer font added to the registered family takes over.
+        collection.register_fonts(TestFont::new("Conflict").revision(3.0).build());
+        assert_eq!(collection.family_id("Conflict"), Some(ours));
+        assert_eq!(collection.snapshot().family_id("Conflict"), Some(ours));
+        // The version string breaks ties between equal revisions.
+        let (tie, _) = collection
+            .register_fonts(
+                TestFont::new("Tie")
+                    .version("Version 1.010; ttfautohint (v1.8.3)")
+                    .build(),
+            )
+            .remove(0);
+        assert_eq!(collection.family_id("Tie"), Some(tie));
+        let (_, fonts) = collection
+            .register_fonts(TestFont::new("Older").version("0.9").build())
+            .remove(0);
+        assert_eq!(fonts[0].version(), 0.9);
+        assert!(loaded.lock().unwrap().contains(&system_tie));
+    }
+
+    #[test]
+    fn custom_provider() {
+        let mut system = TestSystemFonts::default();
+        let sans = system.add_family("Provided Sans", TestFont::new("Provided Sans").build());
+        let greek = system.add_family("Provided Greek", TestFont::new("Provided Greek").build());
+        system.add_fallback(Script(*b"Grek"), greek);
+        let loaded = system.loaded.clone();
+        let fallback_requests = system.fallback_requests.clone();
+        let mut collection = collection(system, FamilyPrecedence::default());
+        let mut names: Vec<_> = collection.family_names().map(String::from).collect();
+        names.sort();
+        assert_eq!(names, ["Provided Greek", "Provided Sans"]);
+        assert_eq!(collection.family_id("provided sans"), Some(sans));
+        assert!(loaded.lock().unwrap().is_empty());
+        let family = collection.family(sans).unwrap();
+        assert_eq!(family.name(), "Provided Sans");
+        assert_eq!(family.fonts().len(), 1);
+        assert_eq!(loaded.lock().unwrap().as_slice(), [sans]);
+        // Loaded families are cached by the collection.
+        assert!(collection.family_by_name("Provided Sans").is_some());
+        assert_eq!(loaded.lock().unwrap().len(), 1);
+        let fallbacks: Vec<_> = collection.fallback_families(Script(*b"Grek")).collect();
+        assert_eq!(fallbacks, [greek]);
+        assert_eq!(collection.fallback_families(Script(*b"Arab")).count(), 0);
+        assert_eq!(
+            fallback_requests.lock().unwrap().as_slice(),
+            [Script(*b"Grek"), Script(*b"Arab")]
+        );
+        assert!(collection.family_by_name("Missing").is_none());
+    }
+
+    #[test]
+    fn snapshot_caches_system_lookups() {
+        let mut system = TestSystemFonts::default();
+        let sans = system.add_family("Provided Sans", TestFont::new("Provided Sans").build());
+        let greek = system.add_family("Provided Greek", TestFont::new("Provided Greek").build());
+        system.add_fallback(Script(*b"Grek"), greek);
+        let loaded = system.loaded.clone();
+        let fallback_requests = system.fallback_requests.clone();
+        let mut collection = collection(system, FamilyPrecedence::default());
+        collection.set_script_sample(Script(*b"Grek"), "αβγ");
+        let snapshot = collection.snapshot();
+        // Fallbacks for scripts with samples are resolved up front.
+        assert_eq!(
+            fallback_requests.lock().unwrap().as_slice(),
+            [Script(*b"Grek")]
+        );
+        for _ in 0..2 {
+            let fallbacks: Vec<_> = snapshot.fallback_families(Script(*b"Grek")).collect();
+            assert_eq!(fallbacks, [greek]);
+        }
+        assert_eq!(fallback_requests.lock().unwrap().len(), 1);
+        // Families are loaded once and shared between clones.
+        let clone = snapshot.clone();
+        assert_eq!(snapshot.family(sans).unwrap().name(), "Provided Sans");
+        assert_eq!(clone.family(sans).unwrap().name(), "Provided Sans");
+        assert_eq!(loaded.lock().unwrap().as_slice(), [sans]);
+    }
+
+    #[test]
+    fn script_coverage_fallback() {
+        let mut system = TestSystemFonts::default();
+        let greek = system.add_family("System Greek", TestFont::new("System Greek").build());
+        system.add_fallback(Script(*b"Grek"), greek);
+        let mut collection = collection(system, FamilyPrecedence::default());
+        let (cyrillic, _) = collection
+            .register_fonts(
+                TestFont::new("Cyrillic")
+                    .chars('\u{0400}'..='\u{04FF}')
+                    .build(),
+            )
+            .remove(0);
+        collection.register_fonts(
+            TestFont::new("Greek")
+                .chars('\u{0370}'..='\u{03FF}')
+                .build(),
+        );
+        let snapshot = collection.snapshot();
+        // The system fallback is preferred to registered families.
+        for script in [Script(*b"Grek"), Script(*b"Cyrl"), Script(*b"Arab")] {
+            let expected: Vec<_> = collection.fallback_families(script).collect();
+            let fallbacks: Vec<_> = snapshot.fallback_families(script).collect();
+            assert_eq!(fallbacks, expected);
+        }
+        assert_eq!(
+            snapshot.fallback_families(Script(*b"Grek")).next(),
+            Some(greek)
+        );
+        let fallbacks: Vec<_> = snapshot.fallback_families(Script(*b"Cyrl")).collect();
+        assert_eq!(fallbacks, [cyrillic]);
+        assert_eq!(snapshot.fallback_families(Script(*b"Arab")).count(), 0);
+    }
+}
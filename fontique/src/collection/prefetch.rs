@@ -0,0 +1,170 @@
+//! Background loading of system font families.
+
+use super::super::{backend::SystemFonts, family::FamilyId};
+use alloc::boxed::Box;
+use hashbrown::{HashMap, HashSet};
+use smallvec::SmallVec;
+use std::sync::{mpsc::Sender, Arc, Mutex};
+
+/// Maximum number of successors remembered for each family.
+const MAX_SUCCESSORS: usize = 4;
+
+/// Loads system families on a background thread based on the order in
+/// which families have previously been requested.
+///
+/// The backend retains loaded families and the fonts in a family share
+/// the metadata read from their files, so a later request on the main
+/// thread finds the family and its fonts already loaded.
+#[derive(Clone)]
+pub(crate) struct Prefetcher {
+    state: Arc<Mutex<PrefetchState>>,
+    sender: Sender<FamilyId>,
+    last: Option<FamilyId>,
+}
+
+impl Prefetcher {
+    /// Spawns the background thread for loading families from the given
+    /// system fonts.
+    ///
+    /// The thread exits when the prefetcher and all of its clones have
+    /// been dropped.
+    pub fn new(fonts: Arc<Mutex<Box<dyn SystemFonts>>>) -> Option<Self> {
+        let (sender, receiver) = std::sync::mpsc::channel::<FamilyId>();
+        std::thread::Builder::new()
+            .name("fontique-prefetch".into())
+            .spawn(move || {
+                for id in receiver {
+                    // The lock is only held while the backend looks up
+                    // the family so that requests on other threads aren't
+                    // blocked while font files are read.
+                    let family = fonts.lock().unwrap().family(id);
+                    for font in family.iter().flat_map(|family| family.fonts()) {
+                        font.preload();
+                    }
+                }
+            })
+            .ok()?;
+        Some(Self::with_sender(sender))
+    }
+
+    fn with_sender(sender: Sender<FamilyId>) -> Self {
+        Self {
+            state: Default::default(),
+            sender,
+            last: None,
+        }
+    }
+
+    /// Notes a request for the given family and queues the families that
+    /// followed it in earlier requests.
+    pub fn record(&mut self, id: FamilyId) {
+        let mut state = self.state.lock().unwrap();
+        let PrefetchState { successors, queued } = &mut *state;
+        if let Some(last) = self.last.replace(id) {
+            if last != id {
+                let next = successors.entry(last).or_default();
+                next.retain(|next_id| *next_id != id);
+                next.insert(0, id);
+                next.truncate(MAX_SUCCESSORS);
+            }
+        }
+        if let Some(next) = successors.get(&id) {
+            for next_id in next {
+                if queued.insert(*next_id) {
+                    // The thread only exits once all senders are dropped
+                    // so this cannot fail.
+                    let _ = self.sender.send(*next_id);
+                }
+            }
+        }
+    }
+}
+
+/// Access patterns shared between clones of a collection.
+#[derive(Default)]
+struct PrefetchState {
+    /// Families requested directly after each family, most recent first.
+    successors: HashMap<FamilyId, SmallVec<[FamilyId; MAX_SUCCESSORS]>>,
+    /// Families that have been queued for loading.
+    ///
+    /// Families requested on the main thread may still be queued once
+    /// since only the fonts that were matched have been read by then.
+    queued: HashSet<FamilyId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+    use std::sync::mpsc::{channel, Receiver};
+
+    fn prefetcher() -> (Prefetcher, Receiver<FamilyId>) {
+        let (sender, receiver) = channel();
+        (Prefetcher::with_sender(sender), receiver)
+    }
+
+    fn successors(prefetcher: &Prefetcher, id: FamilyId) -> Vec<FamilyId> {
+        let state = prefetcher.state.lock().unwrap();
+        state
+            .successors
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn successors_most_recent_first() {
+        let (mut prefetcher, receiver) = prefetcher();
+        let [a, b, c] = [(); 3].map(|_| FamilyId::new());
+        for id in [a, b, a, c, a] {
+            prefetcher.record(id);
+        }
+        assert_eq!(successors(&prefetcher, a), [c, b]);
+        assert_eq!(successors(&prefetcher, b), [a]);
+        assert_eq!(successors(&prefetcher, c), [a]);
+        // Requesting the same family twice in a row isn't a succession.
+        prefetcher.record(a);
+        assert_eq!(successors(&prefetcher, a), [c, b]);
+        // The second request for `a` queues `b`, the third queues `c`.
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [b, c]);
+    }
+
+    #[test]
+    fn successors_truncated() {
+        let (mut prefetcher, _receiver) = prefetcher();
+        let a = FamilyId::new();
+        let next = [(); MAX_SUCCESSORS + 2].map(|_| FamilyId::new());
+        for id in next {
+            prefetcher.record(a);
+            prefetcher.record(id);
+        }
+        let expected = next.iter().rev().take(MAX_SUCCESSORS).copied();
+        assert_eq!(successors(&prefetcher, a), expected.collect::<Vec<_>>());
+        // A repeated successor moves to the front without growing the
+        // list.
+        prefetcher.record(a);
+        prefetcher.record(next[2]);
+        assert_eq!(successors(&prefetcher, a).len(), MAX_SUCCESSORS);
+        assert_eq!(successors(&prefetcher, a)[0], next[2]);
+    }
+
+    #[test]
+    fn families_queued_once() {
+        let (mut prefetcher, receiver) = prefetcher();
+        let [a, b, c] = [(); 3].map(|_| FamilyId::new());
+        for id in [a, b, c, a, b, c, a] {
+            prefetcher.record(id);
+        }
+        // Each family is queued by the first request of its predecessor
+        // after the succession has been seen.
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [b, c, a]);
+        // Clones share the queued families.
+        let mut clone = prefetcher.clone();
+        for id in [b, a, c] {
+            clone.record(id);
+        }
+        assert_eq!(receiver.try_iter().count(), 0);
+    }
+}
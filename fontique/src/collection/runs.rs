@@ -0,0 +1,188 @@
+//! Segmentation of text into runs of fallback fonts.
+
+use super::{
+    super::{Attributes, FallbackKey, FamilyId, FontInfo, Script, Synthesis},
+    Inner,
+};
+use alloc::vec::Vec;
+use core::ops::Range;
+use hashbrown::HashMap;
+use icu_locid::LanguageIdentifier;
+use icu_properties::{maps, sets, GraphemeClusterBreak};
+use smallvec::SmallVec;
+
+impl Inner {
+    /// Splits the text into runs of fonts from the fallback families.
+    pub fn fallback_runs(
+        &mut self,
+        text: &str,
+        attributes: Attributes,
+        locale: Option<&str>,
+    ) -> Vec<(Range<usize>, FontInfo, Synthesis)> {
+        let locale =
+            locale.and_then(|locale| LanguageIdentifier::try_from_bytes(locale.as_bytes()).ok());
+        let mut fonts: HashMap<Script, Vec<FontInfo>> = HashMap::new();
+        let mut runs: Vec<(Range<usize>, FontInfo, Synthesis)> = Vec::new();
+        let mut script = Script::COMMON;
+        for cluster in Clusters::new(text) {
+            let chars = &text[cluster.clone()];
+            // Clusters of shared characters take on the script of the
+            // preceding text.
+            if let Some(cluster_script) = chars
+                .chars()
+                .map(Script::from_char)
+                .find(|script| !script.is_shared())
+            {
+                script = cluster_script;
+            }
+            let candidates = fonts.entry(script).or_insert_with(|| {
+                let key = FallbackKey::new(script, locale.as_ref());
+                let ids: SmallVec<[FamilyId; 4]> = self.fallback_families(key).collect();
+                let mut fonts: Vec<FontInfo> = ids
+                    .into_iter()
+                    .filter_map(|id| {
+                        self.family(id)?
+                            .match_font(
+                                attributes.stretch,
+                                attributes.style,
+                                attributes.weight,
+                                true,
+                            )
+                            .cloned()
+                    })
+                    .collect();
+                // Fonts embedded in documents often only cover the text of
+                // the document so only use them as a last resort.
+                fonts.sort_by_key(|font| font.is_likely_subset());
+                // Ensure that every character is drawn with a glyph that
+                // identifies it.
+                #[cfg(feature = "last-resort")]
+                fonts.push(crate::last_resort::last_resort_font());
+                fonts
+            });
+            // Prefer extending the current run to avoid breaking on
+            // punctuation and spaces that many fonts support.
+            if let Some(run) = runs.last_mut() {
+                if supports_cluster(&run.1, chars)
+                    || !candidates.iter().any(|font| supports_cluster(font, chars))
+                {
+                    run.0.end = cluster.end;
+                    continue;
+                }
+            }
+            let font = candidates
+                .iter()
+                .find(|font| supports_cluster(font, chars))
+                .or_else(|| candidates.first());
+            if let Some(font) = font {
+                // The first run also covers any preceding clusters for which
+                // no font was found.
+                let start = if runs.is_empty() { 0 } else { cluster.start };
+                let synthesis =
+                    font.synthesis(attributes.stretch, attributes.style, attributes.weight);
+                runs.push((start..cluster.end, font.clone(), synthesis));
+            }
+        }
+        runs
+    }
+}
+
+/// Returns true if the font maps every character in the cluster that is
+/// not default ignorable.
+fn supports_cluster(font: &FontInfo, cluster: &str) -> bool {
+    let ignorable = sets::default_ignorable_code_point();
+    cluster
+        .chars()
+        .all(|ch| ignorable.contains(ch) || font.has_char(ch))
+}
+
+/// Iterator over the byte ranges of the extended grapheme clusters in a
+/// string.
+///
+/// This implements the rules of
+/// [UAX #29](https://www.unicode.org/reports/tr29/#Grapheme_Cluster_Boundary_Rules)
+/// without the Indic conjunct break extension.
+struct Clusters<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Clusters<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+}
+
+impl Iterator for Clusters<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.pos..];
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+        let breaks = maps::grapheme_cluster_break();
+        let pictographic = sets::extended_pictographic();
+        let mut prev = breaks.get(first);
+        let mut regional_count = (prev == GraphemeClusterBreak::RegionalIndicator) as usize;
+        // True when the preceding characters are an extended pictographic
+        // followed by any number of extending characters.
+        let mut in_emoji = pictographic.contains(first);
+        let mut end = rest.len();
+        for (offset, ch) in chars {
+            let next = breaks.get(ch);
+            let emoji_zwj = in_emoji && prev == GraphemeClusterBreak::ZWJ;
+            if !joins(
+                prev,
+                next,
+                regional_count,
+                emoji_zwj && pictographic.contains(ch),
+            ) {
+                end = offset;
+                break;
+            }
+            in_emoji = pictographic.contains(ch)
+                || (in_emoji
+                    && matches!(
+                        next,
+                        GraphemeClusterBreak::Extend | GraphemeClusterBreak::ZWJ
+                    )
+                    && prev != GraphemeClusterBreak::ZWJ);
+            regional_count = if next == GraphemeClusterBreak::RegionalIndicator {
+                regional_count + 1
+            } else {
+                0
+            };
+            prev = next;
+        }
+        let start = self.pos;
+        self.pos += end;
+        Some(start..self.pos)
+    }
+}
+
+/// Returns true if there is no cluster boundary between characters with the
+/// given break properties.
+///
+/// `regional_count` is the number of consecutive regional indicators ending
+/// with `prev` and `emoji_zwj` is true when `next` is an extended
+/// pictographic that follows a zero width joiner in an emoji sequence.
+fn joins(
+    prev: GraphemeClusterBreak,
+    next: GraphemeClusterBreak,
+    regional_count: usize,
+    emoji_zwj: bool,
+) -> bool {
+    use GraphemeClusterBreak as G;
+    match (prev, next) {
+        (G::CR, G::LF) => true,
+        (G::Control | G::CR | G::LF, _) | (_, G::Control | G::CR | G::LF) => false,
+        (G::L, G::L | G::V | G::LV | G::LVT) => true,
+        (G::LV | G::V, G::V | G::T) => true,
+        (G::LVT | G::T, G::T) => true,
+        (_, G::Extend | G::ZWJ | G::SpacingMark) => true,
+        (G::Prepend, _) => true,
+        (G::ZWJ, _) => emoji_zwj,
+        (G::RegionalIndicator, G::RegionalIndicator) => regional_count % 2 == 1,
+        _ => false,
+    }
+}
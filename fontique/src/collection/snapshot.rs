@@ -0,0 +1,227 @@
+//! Immutable views of a font collection.
+
+#[cfg(feature = "std")]
+use super::super::Script;
+use super::{
+    super::{
+        fallback::FallbackKey,
+        family::{FamilyId, FamilyInfo},
+        GenericFamily,
+    },
+    CommonData, FamilyNames, FamilyOrigin, FamilyPrecedence, GenericFamilies, System,
+};
+use alloc::sync::Arc;
+use hashbrown::HashMap;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+/// Immutable view of a font collection at a point in time.
+///
+/// Snapshots are cheap to clone and can be shared between threads, which
+/// makes them suitable for parallel layout passes while the collection
+/// continues to accept registrations. Changes made to the collection after
+/// the snapshot was taken are not visible.
+///
+/// System fallbacks for the scripts with samples are resolved when the
+/// snapshot is taken. Families that the collection had not yet loaded from
+/// the system fonts are loaded once on first use and shared by all clones
+/// of the snapshot. Other fallbacks are requested from the system on each
+/// lookup.
+///
+/// Created with [`Collection::snapshot`](super::Collection::snapshot).
+#[derive(Clone)]
+pub struct CollectionSnapshot(Arc<SnapshotData>);
+
+struct SnapshotData {
+    data: CommonData,
+    system: Option<System>,
+    precedence: FamilyPrecedence,
+    newest: HashMap<FamilyId, FamilyId>,
+    #[cfg(feature = "std")]
+    script_samples: HashMap<Script, Arc<str>>,
+    /// System families that the collection had not loaded, filled in on
+    /// first use.
+    #[cfg(feature = "std")]
+    system_families: HashMap<FamilyId, OnceLock<Option<FamilyInfo>>>,
+}
+
+// Snapshots are only useful if they can be sent to other threads.
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CollectionSnapshot>();
+};
+
+impl CollectionSnapshot {
+    pub(super) fn new(
+        data: CommonData,
+        system: Option<System>,
+        precedence: FamilyPrecedence,
+        newest: HashMap<FamilyId, FamilyId>,
+        #[cfg(feature = "std")] script_samples: HashMap<Script, Arc<str>>,
+    ) -> Self {
+        #[cfg(feature = "std")]
+        let system_families = system
+            .iter()
+            .flat_map(|system| system.family_names.iter())
+            .map(|name| name.id())
+            .filter(|id| !data.families.contains_key(id))
+            .map(|id| (id, OnceLock::new()))
+            .collect();
+        Self(Arc::new(SnapshotData {
+            data,
+            system,
+            precedence,
+            newest,
+            #[cfg(feature = "std")]
+            script_samples,
+            #[cfg(feature = "std")]
+            system_families,
+        }))
+    }
+
+    /// Returns an iterator over all available family names in the snapshot.
+    ///
+    /// This includes both system and registered fonts.
+    pub fn family_names(&self) -> impl Iterator<Item = &str> + '_ + Clone {
+        FamilyNames {
+            ours: self.0.data.family_names.iter(),
+            system: self.0.system.as_ref().map(|sys| sys.family_names.iter()),
+        }
+        .map(|name| name.name())
+    }
+
+    /// Returns the family identifier for the given family name.
+    pub fn family_id(&self, name: &str) -> Option<FamilyId> {
+        let ours = self.0.data.family_names.get(name).map(|n| n.id());
+        let system = self
+            .0
+            .system
+            .as_ref()
+            .and_then(|sys| sys.family_names.get(name))
+            .map(|n| n.id());
+        match (ours, system) {
+            (Some(ours), Some(system)) => Some(match self.0.precedence {
+                FamilyPrecedence::Registered => ours,
+                FamilyPrecedence::System => system,
+                // Resolved by the collection when the snapshot was taken.
+                FamilyPrecedence::NewestRevision => {
+                    self.0.newest.get(&ours).copied().unwrap_or(ours)
+                }
+            }),
+            (ours, system) => ours.or(system),
+        }
+    }
+
+    /// Returns the family name for the given family identifier.
+    pub fn family_name(&self, id: FamilyId) -> Option<&str> {
+        self.0
+            .data
+            .family_names
+            .get_by_id(id)
+            .or_else(|| {
+                self.0
+                    .system
+                    .as_ref()
+                    .and_then(|sys| sys.family_names.get_by_id(id))
+            })
+            .map(|name| name.name())
+    }
+
+    /// Returns the source of the family with the given identifier.
+    pub fn family_origin(&self, id: FamilyId) -> Option<FamilyOrigin> {
+        if self.0.data.family_names.get_by_id(id).is_some() {
+            Some(FamilyOrigin::Registered)
+        } else if self
+            .0
+            .system
+            .as_ref()
+            .and_then(|sys| sys.family_names.get_by_id(id))
+            .is_some()
+        {
+            Some(FamilyOrigin::System)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the family object for the given family identifier.
+    pub fn family(&self, id: FamilyId) -> Option<FamilyInfo> {
+        if let Some(family) = self.0.data.families.get(&id) {
+            return family.as_ref().cloned();
+        }
+        #[cfg(feature = "std")]
+        if let (Some(system), Some(family)) = (&self.0.system, self.0.system_families.get(&id)) {
+            return family
+                .get_or_init(|| system.fonts.lock().unwrap().family(id))
+                .clone();
+        }
+        None
+    }
+
+    /// Returns the family object for the given name.
+    pub fn family_by_name(&self, name: &str) -> Option<FamilyInfo> {
+        self.family(self.family_id(name)?)
+    }
+
+    /// Returns an iterator over the family identifiers for the given
+    /// generic family.
+    pub fn generic_families(
+        &self,
+        family: GenericFamily,
+    ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
+        GenericFamilies {
+            ours: self.0.data.generic_families.get(family).iter().copied(),
+            system: self
+                .0
+                .system
+                .as_ref()
+                .map(|sys| sys.generic_families.get(family).iter().copied()),
+        }
+    }
+
+    /// Returns an iterator over the fallback families for the given
+    /// key.
+    pub fn fallback_families(
+        &self,
+        key: impl Into<FallbackKey>,
+    ) -> impl Iterator<Item = FamilyId> + '_ + Clone {
+        let key = key.into();
+        let ours = self.0.data.fallbacks.get(key).unwrap_or_default();
+        #[cfg(feature = "std")]
+        let system = self
+            .0
+            .system
+            .as_ref()
+            .filter(|_| ours.is_empty())
+            .and_then(|system| {
+                let sample = self.0.script_samples.get(&key.script());
+                // Resolved when the snapshot was taken.
+                if sample.is_some() && key.locale().is_none() {
+                    return None;
+                }
+                let mut fonts = system.fonts.lock().unwrap();
+                match sample {
+                    Some(sample) => fonts.fallback_for_sample(key, sample),
+                    None => fonts.fallback(key),
+                }
+            });
+        #[cfg(not(feature = "std"))]
+        let system = None;
+        // Without fallbacks from the system, use the registered families
+        // that cover the script.
+        let coverage = if ours.is_empty() && system.is_none() {
+            self.0
+                .data
+                .script_coverage
+                .get(&key.script())
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+        } else {
+            &[]
+        };
+        ours.iter()
+            .copied()
+            .chain(system)
+            .chain(coverage.iter().copied())
+    }
+}
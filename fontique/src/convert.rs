@@ -0,0 +1,65 @@
+//! Support for system fonts in formats that can't be loaded directly.
+
+use super::scan::{ScanReport, SkipReason};
+use hashbrown::HashSet;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// Converter for fonts that are found while scanning the system but are in
+/// a format that this crate can't load.
+///
+/// The crate detects these files and lists them in the
+/// [scan report](crate::Collection::system_scan_report). A converter
+/// registered with
+/// [`Collection::set_font_converter`](crate::Collection::set_font_converter)
+/// is handed the contents of each file that it supports and the resulting
+/// OpenType data is registered with the collection.
+pub trait FontConverter: Send + Sync {
+    /// Converts a PostScript Type 1 font, in either the binary (PFB) or
+    /// ASCII (PFA) form, to an OpenType font with CFF outlines.
+    ///
+    /// The path can be used to locate companion files, such as AFM or PFM
+    /// metrics. Returns `None` if the font can't be converted.
+    fn convert_type1(&self, path: &Path, data: &[u8]) -> Option<Vec<u8>>;
+}
+
+/// State for a font converter attached to a collection.
+#[derive(Clone)]
+pub(crate) struct Converter {
+    converter: Arc<dyn FontConverter>,
+    converted: Arc<Mutex<HashSet<Arc<Path>>>>,
+}
+
+impl Converter {
+    pub fn new(converter: Arc<dyn FontConverter>) -> Self {
+        Self {
+            converter,
+            converted: Default::default(),
+        }
+    }
+
+    /// Converts the skipped files in the report that the converter supports
+    /// and returns the resulting font data.
+    ///
+    /// Each file is only attempted once, whether or not the conversion
+    /// succeeds.
+    pub fn convert(&self, report: &ScanReport) -> Vec<Vec<u8>> {
+        let mut converted = self.converted.lock().unwrap();
+        let mut result = Vec::new();
+        for file in report.skipped() {
+            if file.reason() != SkipReason::PostScriptType1 || converted.contains(file.path()) {
+                continue;
+            }
+            converted.insert(file.path().into());
+            let Ok(data) = std::fs::read(file.path()) else {
+                continue;
+            };
+            if let Some(font) = self.converter.convert_type1(file.path(), &data) {
+                result.push(font);
+            }
+        }
+        result
+    }
+}
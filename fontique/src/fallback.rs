@@ -14,6 +14,32 @@ pub struct FallbackMap {
 }
 
 impl FallbackMap {
+    /// Returns the estimated number of bytes held by the map.
+    pub(crate) fn memory_usage(&self) -> usize {
+        use core::mem::size_of;
+        let list_usage = |list: &FamilyList| {
+            if list.spilled() {
+                list.capacity() * size_of::<FamilyId>()
+            } else {
+                0
+            }
+        };
+        self.fallbacks.capacity() * size_of::<(Script, PerScript)>()
+            + self
+                .fallbacks
+                .values()
+                .map(|per_script| {
+                    per_script.default.as_ref().map_or(0, list_usage)
+                        + per_script.others.capacity() * size_of::<(&str, FamilyList)>()
+                        + per_script
+                            .others
+                            .iter()
+                            .map(|(_, list)| list_usage(list))
+                            .sum::<usize>()
+                })
+                .sum::<usize>()
+    }
+
     /// Returns the font fallback families for the given key.
     pub fn get(&self, key: impl Into<FallbackKey>) -> Option<&[FamilyId]> {
         let key = key.into();
@@ -30,6 +56,49 @@ impl FallbackMap {
         }
     }
 
+    /// Returns an iterator over the script, normalized locale and families
+    /// of each entry in the map.
+    ///
+    /// The locale is empty for the default language of the script.
+    #[cfg(feature = "std")]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (Script, &'static str, &[FamilyId])> + '_ {
+        self.fallbacks.iter().flat_map(|(script, per_script)| {
+            per_script
+                .default
+                .iter()
+                .map(|families| ("", families))
+                .chain(
+                    per_script
+                        .others
+                        .iter()
+                        .map(|(locale, families)| (*locale, families)),
+                )
+                .map(|(locale, families)| (*script, locale, families.as_slice()))
+        })
+    }
+
+    /// Removes the families for which the predicate returns false, along
+    /// with any entries that become empty.
+    #[cfg(feature = "std")]
+    pub(crate) fn retain(&mut self, mut f: impl FnMut(FamilyId) -> bool) {
+        for per_script in self.fallbacks.values_mut() {
+            if let Some(families) = &mut per_script.default {
+                families.retain(|id| f(*id));
+                if families.is_empty() {
+                    per_script.default = None;
+                }
+            }
+            for (_, families) in &mut per_script.others {
+                families.retain(|id| f(*id));
+            }
+            per_script
+                .others
+                .retain(|(_, families)| !families.is_empty());
+        }
+        self.fallbacks
+            .retain(|_, per_script| per_script.default.is_some() || !per_script.others.is_empty());
+    }
+
     /// Inserts or replaces the fallback families for the given script and
     /// language.
     ///
@@ -179,6 +248,34 @@ where
     }
 }
 
+/// Preference for color or text presentation when ranking fallback
+/// families.
+///
+/// Chat applications generally want colorful emoji while code editors and
+/// terminals prefer monochrome glyphs that match the surrounding text.
+#[derive(Copy, Clone, PartialEq, Eq, Default, Debug)]
+pub enum ColorPreference {
+    /// Keep the order of the fallback families.
+    #[default]
+    None,
+    /// Rank families with color glyphs first.
+    Color,
+    /// Rank families without color glyphs first.
+    Text,
+}
+
+impl ColorPreference {
+    /// Returns the rank of a family with the given color support, where
+    /// lower ranks are preferred.
+    pub(crate) fn rank(self, has_color: bool) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Color => !has_color as u8,
+            Self::Text => has_color as u8,
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug)]
 struct PerScript {
     default: Option<FamilyList>,
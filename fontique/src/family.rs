@@ -3,9 +3,9 @@
 use super::{
     attributes::{Stretch, Style, Weight},
     family_name::FamilyName,
-    font::FontInfo,
+    font::{AxisInfo, FontInfo},
 };
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec::Vec};
 use core::sync::atomic::{AtomicU64, Ordering};
 use smallvec::SmallVec;
 
@@ -36,8 +36,12 @@ pub struct FamilyInfo(Arc<FamilyInner>);
 impl FamilyInfo {
     /// Creates a new font family object with the given name and collection of
     /// fonts.
+    ///
+    /// Fonts with identical content (as determined by
+    /// [`FontInfo::fingerprint`]) are only included once.
     pub fn new(name: FamilyName, fonts: impl IntoIterator<Item = FontInfo>) -> Self {
-        let fonts: SmallVec<[FontInfo; 4]> = fonts.into_iter().collect();
+        let mut fonts: SmallVec<[FontInfo; 4]> = fonts.into_iter().collect();
+        dedup_fonts(&mut fonts);
         let default_font = super::matching::match_font(
             &fonts[..],
             Default::default(),
@@ -78,6 +82,75 @@ impl FamilyInfo {
         self.0.fonts.get(self.0.default_font)
     }
 
+    /// Returns the union of the variation axes of all fonts in the family.
+    ///
+    /// Axes are ordered by their first appearance. The range of each axis
+    /// covers the ranges of all fonts that have it and the default value is
+    /// taken from the default font when it has the axis.
+    pub fn axes(&self) -> Vec<AxisInfo> {
+        let mut axes: Vec<AxisInfo> = Vec::new();
+        for font in self.fonts() {
+            for axis in font.axes() {
+                if let Some(existing) = axes.iter_mut().find(|existing| existing.tag == axis.tag) {
+                    existing.min = existing.min.min(axis.min);
+                    existing.max = existing.max.max(axis.max);
+                } else {
+                    axes.push(*axis);
+                }
+            }
+        }
+        if let Some(font) = self.default_font() {
+            for axis in font.axes() {
+                if let Some(existing) = axes.iter_mut().find(|existing| existing.tag == axis.tag) {
+                    existing.default = axis.default;
+                }
+            }
+        }
+        axes
+    }
+
+    /// Returns the lightest and heaviest weights covered by the family.
+    ///
+    /// This spans the weights of static fonts and the `wght` axis ranges of
+    /// variable fonts. Returns `None` if the family has no fonts.
+    pub fn weight_range(&self) -> Option<(Weight, Weight)> {
+        self.fonts()
+            .iter()
+            .map(|font| font.attribute_ranges().weight)
+            .reduce(|range, weight| {
+                (
+                    if weight.0 < range.0 {
+                        weight.0
+                    } else {
+                        range.0
+                    },
+                    if weight.1 > range.1 {
+                        weight.1
+                    } else {
+                        range.1
+                    },
+                )
+            })
+    }
+
+    /// Returns the estimated number of bytes held by the family.
+    ///
+    /// Fonts share some data with their clones, which is included here.
+    pub(crate) fn memory_usage(&self) -> usize {
+        let fonts = if self.0.fonts.spilled() {
+            self.0.fonts.capacity() * core::mem::size_of::<FontInfo>()
+        } else {
+            0
+        };
+        core::mem::size_of::<FamilyInner>()
+            + fonts
+            + self
+                .fonts()
+                .iter()
+                .map(|font| font.memory_usage())
+                .sum::<usize>()
+    }
+
     /// Returns the index of the best font from the family for the given attributes.
     pub fn match_index(
         &self,
@@ -102,6 +175,26 @@ impl FamilyInfo {
     }
 }
 
+/// Removes fonts with duplicate fingerprints, keeping the first occurrence.
+///
+/// Simulated faces share content with their base font so they are only
+/// considered duplicates if the simulations also match.
+fn dedup_fonts(fonts: &mut SmallVec<[FontInfo; 4]>) {
+    let mut i = 1;
+    while i < fonts.len() {
+        let fingerprint = fonts[i].fingerprint();
+        let simulations = fonts[i].simulations();
+        if fonts[..i]
+            .iter()
+            .any(|font| font.fingerprint() == fingerprint && font.simulations() == simulations)
+        {
+            fonts.remove(i);
+        } else {
+            i += 1;
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct FamilyInner {
     pub(crate) name: FamilyName,
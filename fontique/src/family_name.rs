@@ -40,6 +40,18 @@ pub struct FamilyNameMap {
 }
 
 impl FamilyNameMap {
+    /// Returns the estimated number of bytes held by the map.
+    pub(crate) fn memory_usage(&self) -> usize {
+        use core::mem::size_of;
+        self.name_map.capacity() * size_of::<(Arc<[u8]>, FamilyName)>()
+            + self.id_map.capacity() * size_of::<(FamilyId, FamilyName)>()
+            + self
+                .name_map
+                .iter()
+                .map(|(key, name)| key.len() + name.name().len())
+                .sum::<usize>()
+    }
+
     /// Returns the family name object for the given name.
     pub fn get(&self, name: &str) -> Option<&FamilyName> {
         let key = NameKey::from_str(name);
@@ -70,7 +82,6 @@ impl FamilyNameMap {
     }
 
     /// Adds `name` as an alias for the given family identifier.
-    #[allow(unused)]
     pub fn add_alias(&mut self, id: FamilyId, name: &str) {
         if self.id_map.contains_key(&id) {
             let key = NameKey::from_str(name);
@@ -1,9 +1,15 @@
 //! Model for a font.
 
-use super::attributes::{Stretch, Style, Weight};
+use super::attributes::{AttributeRanges, Attributes, Stretch, Style, Weight};
 use super::source::{SourceInfo, SourceKind};
 #[cfg(feature = "std")]
-use super::{source_cache::SourceCache, Blob};
+use super::{
+    charset::Charset,
+    index::{Decoder, Encoder},
+    source_cache::SourceCache,
+    Blob,
+};
+use alloc::string::String;
 use read_fonts::{types::Tag, FontRef, TableProvider as _};
 use smallvec::SmallVec;
 
@@ -19,6 +25,20 @@ pub struct FontInfo {
     weight: Weight,
     axes: AxisVec,
     attr_axes: u8,
+    revision: f32,
+    version: f32,
+    fingerprint: u64,
+    simulations: Synthesis,
+    attrs_from_name: bool,
+    subset_flags: u8,
+    has_color: bool,
+    /// Character coverage, loaded on first use and shared between clones.
+    #[cfg(feature = "std")]
+    charset: std::sync::Arc<std::sync::OnceLock<Charset>>,
+    /// Font read from the file on first use, for fonts created from cached
+    /// metadata. Shared between clones.
+    #[cfg(feature = "std")]
+    verified: Option<std::sync::Arc<std::sync::OnceLock<Option<FontInfo>>>>,
 }
 
 impl FontInfo {
@@ -63,48 +83,266 @@ impl FontInfo {
         }
     }
 
+    /// Returns the set of characters supported by the font.
+    ///
+    /// The character map is read when this is first called for the font
+    /// or any of its clones and the result is cached. Returns an empty set
+    /// if the font cannot be loaded.
+    #[cfg(feature = "std")]
+    pub fn charset(&self) -> &Charset {
+        self.charset.get_or_init(|| {
+            self.load(None)
+                .and_then(|data| {
+                    let font = FontRef::from_index(data.as_ref(), self.index).ok()?;
+                    Some(Charset::from_font(&font))
+                })
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns true if the font supports the given character.
+    ///
+    /// This is a fast check against the cached [`charset`](Self::charset),
+    /// suitable for verifying fallback results.
+    #[cfg(feature = "std")]
+    pub fn has_char(&self, ch: char) -> bool {
+        self.charset().contains(ch)
+    }
+
+    /// Returns a human readable full name for the font at the given user
+    /// space location, such as "Roboto Flex Bold Condensed".
+    ///
+    /// The style is named from the `STAT` axis values that match the
+    /// location, falling back to the nearest named instance. Axes that are
+    /// missing from the location are set to their default values. Returns
+    /// `None` if the font cannot be loaded, lacks a family name or is not
+    /// variable.
+    #[cfg(feature = "std")]
+    pub fn instance_name(&self, location: &[(Tag, f32)]) -> Option<String> {
+        let data = self.load(None)?;
+        let font = FontRef::from_index(data.as_ref(), self.index).ok()?;
+        super::instance_name::instance_name(&font, location)
+    }
+
+    /// Returns the lazily loaded character set, which may be shared with
+    /// other fonts.
+    #[cfg(feature = "std")]
+    pub(crate) fn charset_cell(&self) -> &std::sync::Arc<std::sync::OnceLock<Charset>> {
+        &self.charset
+    }
+
+    /// Replaces the lazily loaded character set with one that is shared
+    /// with other fonts that use the same `cmap` table.
+    #[cfg(feature = "std")]
+    pub(crate) fn share_charset(&mut self, charset: std::sync::Arc<std::sync::OnceLock<Charset>>) {
+        self.charset = charset;
+    }
+
+    /// Returns the estimated number of bytes held by the font, excluding
+    /// the font data.
+    pub(crate) fn memory_usage(&self) -> usize {
+        let axes = if self.axes.spilled() {
+            self.axes.capacity() * core::mem::size_of::<AxisInfo>()
+        } else {
+            0
+        };
+        #[cfg(feature = "std")]
+        let path = match &self.source.kind {
+            SourceKind::Path(path) => path.as_os_str().len(),
+            _ => 0,
+        };
+        #[cfg(not(feature = "std"))]
+        let path = 0;
+        #[cfg(feature = "std")]
+        let charset = self
+            .charset
+            .get()
+            .map_or(0, |charset| charset.memory_usage());
+        #[cfg(not(feature = "std"))]
+        let charset = 0;
+        axes + path + charset
+    }
+
     /// Returns the visual width of the font-- a relative change from the normal
     /// aspect ratio, typically in the range 0.5 to 2.0.
     pub fn stretch(&self) -> Stretch {
-        self.stretch
+        self.attributes_font().stretch
     }
 
     /// Returns the visual style or 'slope' of the font.
     pub fn style(&self) -> Style {
-        self.style
+        self.attributes_font().style
     }
 
     /// Returns the visual weight class of the font, typically on a scale
     /// from 1.0 to 1000.0.
     pub fn weight(&self) -> Weight {
-        self.weight
+        self.attributes_font().weight
+    }
+
+    /// Returns the ranges of stretch, style and weight spanned by the font.
+    ///
+    /// For variable fonts, these are derived from the `wdth`, `ital`, `slnt`
+    /// and `wght` axes. Otherwise, each range contains only the value
+    /// reported by the corresponding accessor.
+    pub fn attribute_ranges(&self) -> AttributeRanges {
+        let font = self.details();
+        let mut ranges = AttributeRanges::from_attributes(Attributes::new(
+            font.stretch,
+            font.style,
+            font.weight,
+        ));
+        let oblique = |angle: f32| {
+            if angle == 0.0 {
+                Style::Normal
+            } else {
+                Style::Oblique(Some(angle))
+            }
+        };
+        for axis in font.axes() {
+            match &axis.tag.to_be_bytes() {
+                b"wdth" => {
+                    ranges.stretch = (
+                        Stretch::from_percentage(axis.min),
+                        Stretch::from_percentage(axis.max),
+                    );
+                }
+                b"wght" => ranges.weight = (Weight::new(axis.min), Weight::new(axis.max)),
+                // Negate the slant to match the sign convention of oblique
+                // angles in CSS.
+                b"slnt" if !font.has_italic_axis() => {
+                    ranges.style = (oblique(-axis.max), oblique(-axis.min));
+                }
+                b"ital" if axis.max >= 1.0 => {
+                    if axis.min <= 0.0 {
+                        ranges.style.0 = Style::Normal;
+                    }
+                    ranges.style.1 = Style::Italic;
+                }
+                _ => {}
+            }
+        }
+        ranges
+    }
+
+    /// Returns true if any of the stretch, style or weight attributes were
+    /// derived from the subfamily name rather than the `OS/2` or `head`
+    /// tables.
+    ///
+    /// This happens when the tables are missing or report default values
+    /// while the subfamily name, such as "Bold Italic Condensed", indicates
+    /// otherwise.
+    pub fn has_heuristic_attributes(&self) -> bool {
+        self.details().attrs_from_name
+    }
+
+    /// Returns true if the PostScript or family name of the font starts with
+    /// a subset tag of six uppercase letters followed by a plus sign, such as
+    /// "ABCDEF+Times".
+    ///
+    /// This naming convention is used for subset fonts embedded in PDF and
+    /// other documents.
+    pub fn has_subset_name(&self) -> bool {
+        self.details().subset_flags & SUBSET_NAME != 0
+    }
+
+    /// Returns true if the font contains `GSUB`, `GPOS` or `morx` tables
+    /// for text layout.
+    pub fn has_layout_tables(&self) -> bool {
+        self.details().subset_flags & NO_LAYOUT == 0
+    }
+
+    /// Returns true if the font is likely a subset that only covers the
+    /// characters of a specific document.
+    ///
+    /// This is the case for fonts with a [subset name](Self::has_subset_name)
+    /// and for fonts with fewer than 128 glyphs that lack
+    /// [layout tables](Self::has_layout_tables). Such fonts should be
+    /// avoided when selecting fallbacks for general text.
+    pub fn is_likely_subset(&self) -> bool {
+        let flags = self.details().subset_flags;
+        flags & SUBSET_NAME != 0 || flags & (FEW_GLYPHS | NO_LAYOUT) == FEW_GLYPHS | NO_LAYOUT
+    }
+
+    /// Returns true if the font contains color glyphs in a `COLR`, `CBDT`,
+    /// `sbix` or `SVG ` table.
+    pub fn has_color_glyphs(&self) -> bool {
+        self.details().has_color
+    }
+
+    /// Returns the revision of the font as specified by the `fontRevision`
+    /// field of the `head` table.
+    ///
+    /// Returns 0.0 if the font does not contain a `head` table.
+    pub fn revision(&self) -> f32 {
+        self.details().revision
+    }
+
+    /// Returns the version number parsed from the version string (name
+    /// ID 5), such as 2.013 for "Version 2.013; ttfautohint (v1.8)".
+    ///
+    /// Returns 0.0 if the font has no version string or it doesn't contain
+    /// a number.
+    pub fn version(&self) -> f32 {
+        self.details().version
+    }
+
+    /// Returns the key used to determine which of two fonts is newer.
+    ///
+    /// The `fontRevision` field is often left at 1.0 by font tools, so the
+    /// version string is used when it is missing and to break ties.
+    pub(crate) fn revision_key(&self) -> (f32, f32) {
+        let font = self.details();
+        let revision = if font.revision > 0.0 {
+            font.revision
+        } else {
+            font.version
+        };
+        (revision, font.version)
+    }
+
+    /// Returns a hash of the font content that can be used to identify
+    /// duplicate copies of the same font loaded from different sources.
+    ///
+    /// The fingerprint is computed from the table directory and the
+    /// contents of the `head` and `name` tables.
+    pub fn fingerprint(&self) -> u64 {
+        self.details().fingerprint
     }
 
     /// Returns synthesis suggestions for this font with the given attributes.
     pub fn synthesis(&self, stretch: Stretch, style: Style, weight: Weight) -> Synthesis {
+        let font = self.details();
         let mut synth = Synthesis::default();
         let mut len = 0usize;
-        if self.has_width_axis() && self.stretch != stretch {
-            synth.vars[len] = (Tag::new(b"wdth"), stretch.percentage());
+        if font.has_width_axis() && font.stretch != stretch {
+            // Select the nearest value supported by the axis.
+            let value = font
+                .axes
+                .iter()
+                .find(|axis| axis.tag == Tag::new(b"wdth"))
+                .map(|axis| stretch.percentage().clamp(axis.min, axis.max))
+                .unwrap_or(stretch.percentage());
+            synth.vars[len] = (Tag::new(b"wdth"), value);
             len += 1;
         }
-        if self.weight != weight {
-            if self.has_weight_axis() {
+        if font.weight != weight {
+            if font.has_weight_axis() {
                 synth.vars[len] = (Tag::new(b"wght"), weight.value());
                 len += 1;
-            } else if weight.value() > self.weight.value() {
+            } else if weight.value() > font.weight.value() {
                 synth.embolden = true;
             }
         }
-        if self.style != style {
+        if font.style != style {
             match style {
                 Style::Normal => {}
                 Style::Italic => {
-                    if self.style == Style::Normal {
-                        if self.has_italic_axis() {
+                    if font.style == Style::Normal {
+                        if font.has_italic_axis() {
                             synth.vars[len] = (Tag::new(b"ital"), 1.0);
                             len += 1;
-                        } else if self.has_slant_axis() {
+                        } else if font.has_slant_axis() {
                             synth.vars[len] = (Tag::new(b"slnt"), 14.0);
                             len += 1;
                         } else {
@@ -113,12 +351,12 @@ impl FontInfo {
                     }
                 }
                 Style::Oblique(angle) => {
-                    if self.style == Style::Normal {
+                    if font.style == Style::Normal {
                         let degrees = angle.unwrap_or(14.0);
-                        if self.has_slant_axis() {
+                        if font.has_slant_axis() {
                             synth.vars[len] = (Tag::new(b"slnt"), degrees);
                             len += 1;
-                        } else if self.has_italic_axis() && degrees > 0. {
+                        } else if font.has_italic_axis() && degrees > 0. {
                             synth.vars[len] = (Tag::new(b"ital"), 1.0);
                             len += 1;
                         } else {
@@ -129,43 +367,59 @@ impl FontInfo {
             }
         }
         synth.len = len as u8;
+        // Simulations are baked into the face so they must always be
+        // applied.
+        synth.embolden |= font.simulations.embolden;
+        if synth.skew == 0 {
+            synth.skew = font.simulations.skew;
+        }
         synth
     }
 
+    /// Returns the synthesis that the platform applies to this font.
+    ///
+    /// This is only non-empty for simulated faces provided by the system
+    /// font collection, such as the bold and oblique faces that DirectWrite
+    /// synthesizes for families lacking them. These are always included in
+    /// the result of [`synthesis`](Self::synthesis).
+    pub fn simulations(&self) -> Synthesis {
+        self.simulations
+    }
+
     /// Returns the variation axes for the font.
     pub fn axes(&self) -> &[AxisInfo] {
-        &self.axes
+        &self.details().axes
     }
 
     /// Returns true if the font has a `wght` axis.
     pub fn has_weight_axis(&self) -> bool {
-        self.attr_axes & WEIGHT_AXIS != 0
+        self.details().attr_axes & WEIGHT_AXIS != 0
     }
 
     /// Returns true if the font has a `wdth` axis.
     pub fn has_width_axis(&self) -> bool {
-        self.attr_axes & WIDTH_AXIS != 0
+        self.details().attr_axes & WIDTH_AXIS != 0
     }
 
     /// Returns true if the font has a `slnt` axis.
     pub fn has_slant_axis(&self) -> bool {
-        self.attr_axes & SLANT_AXIS != 0
+        self.details().attr_axes & SLANT_AXIS != 0
     }
 
     /// Returns true if the font has an `ital` axis.
     pub fn has_italic_axis(&self) -> bool {
-        self.attr_axes & ITALIC_AXIS != 0
+        self.details().attr_axes & ITALIC_AXIS != 0
     }
 
     /// Returns true if the font as an `opsz` axis.
     pub fn has_optical_size_axis(&self) -> bool {
-        self.attr_axes & OPTICAL_SIZE_AXIS != 0
+        self.details().attr_axes & OPTICAL_SIZE_AXIS != 0
     }
 }
 
 impl FontInfo {
     pub(crate) fn from_font_ref(font: &FontRef, source: SourceInfo, index: u32) -> Option<Self> {
-        let (stretch, style, weight) = read_attributes(font);
+        let (stretch, style, weight, attrs_from_name) = read_attributes(font);
         let (axes, attr_axes) = if let Ok(fvar_axes) = font.fvar().and_then(|fvar| fvar.axes()) {
             let mut axes = smallvec::SmallVec::<[AxisInfo; 1]>::with_capacity(fvar_axes.len());
             let mut attrs_axes = 0u8;
@@ -190,6 +444,16 @@ impl FontInfo {
         } else {
             (Default::default(), Default::default())
         };
+        let revision = font
+            .head()
+            .map(|head| head.font_revision().to_f64() as f32)
+            .unwrap_or_default();
+        let version = read_version(font).unwrap_or_default();
+        let fingerprint = fingerprint(font);
+        let subset_flags = read_subset_flags(font);
+        let has_color = COLOR_TABLES
+            .iter()
+            .any(|tag| font.table_data(Tag::new(tag)).is_some());
         Some(Self {
             source,
             index,
@@ -198,9 +462,196 @@ impl FontInfo {
             weight,
             axes,
             attr_axes,
+            revision,
+            version,
+            fingerprint,
+            simulations: Synthesis::default(),
+            attrs_from_name,
+            subset_flags,
+            has_color,
+            #[cfg(feature = "std")]
+            charset: Default::default(),
+            #[cfg(feature = "std")]
+            verified: None,
         })
     }
 
+    /// Creates a font from cached metadata without reading the font file.
+    ///
+    /// The file is read on first access to a property that isn't cached,
+    /// such as the variation axes. Once read, attributes found in the font
+    /// take precedence over the cached ones if they conflict, as the cache
+    /// may be out of date. If the file can't be read, the cached metadata
+    /// is used.
+    #[cfg(feature = "std")]
+    #[allow(unused)]
+    pub(crate) fn from_cached(
+        source: SourceInfo,
+        index: u32,
+        stretch: Stretch,
+        style: Style,
+        weight: Weight,
+    ) -> Self {
+        Self {
+            source,
+            index,
+            stretch,
+            style,
+            weight,
+            axes: Default::default(),
+            attr_axes: 0,
+            revision: 0.0,
+            version: 0.0,
+            fingerprint: 0,
+            simulations: Synthesis::default(),
+            attrs_from_name: false,
+            subset_flags: 0,
+            has_color: false,
+            charset: Default::default(),
+            verified: Some(Default::default()),
+        }
+    }
+
+    /// Writes the properties of the font to a font index.
+    ///
+    /// The source is written separately by the index.
+    #[cfg(feature = "std")]
+    pub(crate) fn encode(&self, encoder: &mut Encoder) {
+        let font = self.details();
+        encoder.u32(self.index);
+        encoder.f32(font.stretch.ratio());
+        match font.style {
+            Style::Normal => encoder.u8(0),
+            Style::Italic => encoder.u8(1),
+            Style::Oblique(None) => encoder.u8(2),
+            Style::Oblique(Some(angle)) => {
+                encoder.u8(3);
+                encoder.f32(angle);
+            }
+        }
+        encoder.f32(font.weight.value());
+        encoder.u32(font.axes.len() as u32);
+        for axis in &font.axes {
+            encoder.bytes(&axis.tag.to_be_bytes());
+            encoder.f32(axis.min);
+            encoder.f32(axis.max);
+            encoder.f32(axis.default);
+        }
+        encoder.u8(font.attr_axes);
+        encoder.f32(font.revision);
+        encoder.f32(font.version);
+        encoder.u64(font.fingerprint);
+        // Simulations never include variation settings.
+        encoder.u8(font.simulations.embolden as u8);
+        encoder.u8(font.simulations.skew as u8);
+        encoder.u8(font.attrs_from_name as u8);
+        encoder.u8(font.subset_flags);
+        encoder.u8(font.has_color as u8);
+    }
+
+    /// Reads a font written by [`encode`](Self::encode) with the given
+    /// source.
+    #[cfg(feature = "std")]
+    pub(crate) fn decode(source: SourceInfo, decoder: &mut Decoder) -> Option<Self> {
+        let index = decoder.u32()?;
+        let stretch = Stretch::from_ratio(decoder.f32()?);
+        let style = match decoder.u8()? {
+            0 => Style::Normal,
+            1 => Style::Italic,
+            2 => Style::Oblique(None),
+            3 => Style::Oblique(Some(decoder.f32()?)),
+            _ => return None,
+        };
+        let weight = Weight::new(decoder.f32()?);
+        let axis_count = decoder.u32()? as usize;
+        let mut axes = AxisVec::new();
+        for _ in 0..axis_count {
+            axes.push(AxisInfo {
+                tag: Tag::new(decoder.bytes(4)?.try_into().ok()?),
+                min: decoder.f32()?,
+                max: decoder.f32()?,
+                default: decoder.f32()?,
+            });
+        }
+        let attr_axes = decoder.u8()?;
+        let revision = decoder.f32()?;
+        let version = decoder.f32()?;
+        let fingerprint = decoder.u64()?;
+        let simulations = Synthesis {
+            embolden: decoder.u8()? != 0,
+            skew: decoder.u8()? as i8,
+            ..Default::default()
+        };
+        Some(Self {
+            source,
+            index,
+            stretch,
+            style,
+            weight,
+            axes,
+            attr_axes,
+            revision,
+            version,
+            fingerprint,
+            simulations,
+            attrs_from_name: decoder.u8()? != 0,
+            subset_flags: decoder.u8()?,
+            has_color: decoder.u8()? != 0,
+            charset: Default::default(),
+            verified: None,
+        })
+    }
+
+    /// Returns the font with the properties read from the font file,
+    /// reading it if this font was created from cached metadata.
+    fn details(&self) -> &FontInfo {
+        #[cfg(feature = "std")]
+        if let Some(verified) = &self.verified {
+            let verified = verified.get_or_init(|| {
+                let mut font = FontInfo::from_source(self.source.clone(), self.index)?;
+                // Cached attributes only fill in defaults, matching fonts
+                // that are read eagerly.
+                font.maybe_override_attributes(self.stretch, self.style, self.weight);
+                font.simulations = self.simulations;
+                font.charset = self.charset.clone();
+                Some(font)
+            });
+            if let Some(verified) = verified {
+                return verified;
+            }
+        }
+        self
+    }
+
+    /// Reads the font file now if this font was created from cached
+    /// metadata, so that later accesses don't have to.
+    #[cfg(feature = "std")]
+    pub(crate) fn preload(&self) {
+        self.details();
+    }
+
+    /// Returns the font that provides the stretch, style and weight
+    /// attributes.
+    ///
+    /// Cached attributes are trusted until the font file has been read for
+    /// other reasons.
+    fn attributes_font(&self) -> &FontInfo {
+        #[cfg(feature = "std")]
+        if let Some(Some(verified)) = self.verified.as_ref().and_then(|verified| verified.get()) {
+            return verified;
+        }
+        self
+    }
+
+    /// Replaces the attributes read from the font, for fonts registered
+    /// with explicit attributes.
+    pub(crate) fn set_attributes(&mut self, attributes: Attributes) {
+        self.stretch = attributes.stretch;
+        self.style = attributes.style;
+        self.weight = attributes.weight;
+        self.attrs_from_name = false;
+    }
+
     #[allow(unused)]
     pub(crate) fn maybe_override_attributes(
         &mut self,
@@ -218,6 +669,19 @@ impl FontInfo {
             self.weight = weight;
         }
     }
+
+    /// Marks this font as a simulated face with the given attributes.
+    #[cfg(all(feature = "system", target_os = "windows"))]
+    pub(crate) fn set_simulations(&mut self, weight: Option<Weight>, style: Option<Style>) {
+        if let Some(weight) = weight {
+            self.weight = weight;
+            self.simulations.embolden = true;
+        }
+        if let Some(style) = style {
+            self.style = style;
+            self.simulations.skew = 14;
+        }
+    }
 }
 
 const WEIGHT_AXIS: u8 = 0x01;
@@ -226,6 +690,14 @@ const SLANT_AXIS: u8 = 0x04;
 const ITALIC_AXIS: u8 = 0x08;
 const OPTICAL_SIZE_AXIS: u8 = 0x10;
 
+const SUBSET_NAME: u8 = 0x01;
+const NO_LAYOUT: u8 = 0x02;
+const FEW_GLYPHS: u8 = 0x04;
+
+/// Fonts with fewer glyphs than this are considered small enough to be
+/// subsets.
+const MIN_FULL_GLYPH_COUNT: u16 = 128;
+
 /// An axis of variation for a variable font.
 #[derive(Copy, Clone, Default, Debug)]
 pub struct AxisInfo {
@@ -241,7 +713,7 @@ pub struct AxisInfo {
 
 /// Suggestions for sythesizing a set of font attributes for a given
 /// font.
-#[derive(Copy, Clone, Default, Debug)]
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
 pub struct Synthesis {
     vars: [(Tag, f32); 3],
     len: u8,
@@ -276,13 +748,108 @@ impl Synthesis {
     }
 }
 
-fn read_attributes(font: &FontRef) -> (Stretch, Style, Weight) {
+/// Computes a 64-bit FNV-1a hash over the table directory and the `head`
+/// and `name` tables.
+fn fingerprint(font: &FontRef) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+    fn hash(state: u64, bytes: &[u8]) -> u64 {
+        bytes.iter().fold(state, |state, byte| {
+            (state ^ *byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
+    let mut state = FNV_OFFSET;
+    for record in font.table_directory.table_records() {
+        state = hash(state, &record.tag().to_be_bytes());
+        state = hash(state, &record.checksum().to_be_bytes());
+        state = hash(state, &record.length().to_be_bytes());
+    }
+    for tag in [Tag::new(b"head"), Tag::new(b"name")] {
+        if let Some(data) = font.table_data(tag) {
+            state = hash(state, data.as_bytes());
+        }
+    }
+    state
+}
+
+/// Parses the version number from the version string of the font.
+fn read_version(font: &FontRef) -> Option<f32> {
+    use read_fonts::types::NameId;
+    let name = font.name().ok()?;
+    let string = super::scan::english_or_first(&name, NameId::VERSION_STRING)?;
+    parse_version(string.chars())
+}
+
+/// Parses the first number in a version string.
+///
+/// The specification requires the string to begin with "Version" followed
+/// by the number, but many fonts omit the prefix or use other cases.
+fn parse_version(chars: impl Iterator<Item = char>) -> Option<f32> {
+    let mut number = String::new();
+    for ch in chars {
+        match ch {
+            '0'..='9' => number.push(ch),
+            '.' if !number.is_empty() && !number.contains('.') => number.push(ch),
+            _ if !number.is_empty() => break,
+            _ => {}
+        }
+    }
+    number.trim_end_matches('.').parse().ok()
+}
+
+/// Tables that contain color glyphs.
+const COLOR_TABLES: [&[u8; 4]; 4] = [b"COLR", b"CBDT", b"sbix", b"SVG "];
+
+fn read_subset_flags(font: &FontRef) -> u8 {
+    use read_fonts::types::NameId;
+    let mut flags = 0;
+    if let Ok(name) = font.name() {
+        let has_subset_prefix = |id| {
+            super::scan::english_or_first(&name, id)
+                .map(|name| is_subset_name(name.chars()))
+                .unwrap_or_default()
+        };
+        if has_subset_prefix(NameId::POSTSCRIPT_NAME) || has_subset_prefix(NameId::FAMILY_NAME) {
+            flags |= SUBSET_NAME;
+        }
+    }
+    let has_layout = [b"GSUB", b"GPOS", b"morx"]
+        .iter()
+        .any(|tag| font.table_data(Tag::new(tag)).is_some());
+    if !has_layout {
+        flags |= NO_LAYOUT;
+    }
+    if font
+        .maxp()
+        .map(|maxp| maxp.num_glyphs() < MIN_FULL_GLYPH_COUNT)
+        .unwrap_or_default()
+    {
+        flags |= FEW_GLYPHS;
+    }
+    flags
+}
+
+/// Returns true if the name starts with six uppercase letters followed by a
+/// plus sign.
+fn is_subset_name(mut chars: impl Iterator<Item = char>) -> bool {
+    chars
+        .by_ref()
+        .take(6)
+        .filter(|ch| ch.is_ascii_uppercase())
+        .count()
+        == 6
+        && chars.next() == Some('+')
+}
+
+pub(crate) fn read_attributes(font: &FontRef) -> (Stretch, Style, Weight, bool) {
     use read_fonts::{
         tables::{
             head::{Head, MacStyle},
+            name::Name,
             os2::{Os2, SelectionFlags},
             post::Post,
         },
+        types::NameId,
         TableProvider,
     };
 
@@ -335,7 +902,76 @@ fn read_attributes(font: &FontRef) -> (Stretch, Style, Weight) {
         (Stretch::default(), style, Weight::new(weight))
     }
 
-    if let Ok(os2) = font.os2() {
+    /// Parses the typographic subfamily name, or the subfamily name if that
+    /// is missing, into attributes that are explicitly specified.
+    fn from_subfamily_name(name: &Name) -> (Option<Stretch>, Option<Style>, Option<Weight>) {
+        const STRETCHES: &[(&str, Stretch)] = &[
+            ("ultracondensed", Stretch::ULTRA_CONDENSED),
+            ("extracondensed", Stretch::EXTRA_CONDENSED),
+            ("semicondensed", Stretch::SEMI_CONDENSED),
+            ("condensed", Stretch::CONDENSED),
+            ("narrow", Stretch::CONDENSED),
+            ("ultraexpanded", Stretch::ULTRA_EXPANDED),
+            ("extraexpanded", Stretch::EXTRA_EXPANDED),
+            ("semiexpanded", Stretch::SEMI_EXPANDED),
+            ("expanded", Stretch::EXPANDED),
+            ("extended", Stretch::EXPANDED),
+            ("wide", Stretch::EXPANDED),
+        ];
+        const STYLES: &[(&str, Style)] = &[
+            ("italic", Style::Italic),
+            ("oblique", Style::Oblique(None)),
+            ("slanted", Style::Oblique(None)),
+        ];
+        // Longer names come first so that, for example, "extrabold" is not
+        // matched as "bold".
+        const WEIGHTS: &[(&str, Weight)] = &[
+            ("extrablack", Weight::EXTRA_BLACK),
+            ("ultrablack", Weight::EXTRA_BLACK),
+            ("extrabold", Weight::EXTRA_BOLD),
+            ("ultrabold", Weight::EXTRA_BOLD),
+            ("semibold", Weight::SEMI_BOLD),
+            ("demibold", Weight::SEMI_BOLD),
+            ("extralight", Weight::EXTRA_LIGHT),
+            ("ultralight", Weight::EXTRA_LIGHT),
+            ("semilight", Weight::SEMI_LIGHT),
+            ("demilight", Weight::SEMI_LIGHT),
+            ("hairline", Weight::THIN),
+            ("thin", Weight::THIN),
+            ("light", Weight::LIGHT),
+            ("medium", Weight::MEDIUM),
+            ("demi", Weight::SEMI_BOLD),
+            ("bold", Weight::BOLD),
+            ("black", Weight::BLACK),
+            ("heavy", Weight::BLACK),
+        ];
+        let Some(subfamily) =
+            super::scan::english_or_first(name, NameId::TYPOGRAPHIC_SUBFAMILY_NAME)
+                .or_else(|| super::scan::english_or_first(name, NameId::SUBFAMILY_NAME))
+        else {
+            return (None, None, None);
+        };
+        // Ignore case and separators so that "Bold Italic", "Bold-Italic"
+        // and "BoldItalic" are treated the same.
+        let key: String = subfamily
+            .chars()
+            .filter(|ch| ch.is_alphabetic())
+            .flat_map(|ch| ch.to_lowercase())
+            .collect();
+        fn find<T: Copy>(key: &str, table: &[(&str, T)]) -> Option<T> {
+            table
+                .iter()
+                .find(|(name, _)| key.contains(name))
+                .map(|(_, value)| *value)
+        }
+        (
+            find(&key, STRETCHES),
+            find(&key, STYLES),
+            find(&key, WEIGHTS),
+        )
+    }
+
+    let (mut stretch, mut style, mut weight) = if let Ok(os2) = font.os2() {
         // Prefer values from the OS/2 table if it exists. We also use
         // the post table to extract the angle for oblique styles.
         from_os2_post(os2, font.post().ok())
@@ -344,5 +980,41 @@ fn read_attributes(font: &FontRef) -> (Stretch, Style, Weight) {
         from_head(head)
     } else {
         (Stretch::default(), Style::Normal, Weight::default())
+    };
+    // Many older fonts leave the tables at their default values, so fill in
+    // any attributes that the subfamily name specifies.
+    let mut from_name = false;
+    if let Ok(name) = font.name() {
+        let (name_stretch, name_style, name_weight) = from_subfamily_name(&name);
+        if let Some(name_stretch) = name_stretch.filter(|_| stretch == Stretch::NORMAL) {
+            stretch = name_stretch;
+            from_name = true;
+        }
+        if let Some(name_style) = name_style.filter(|_| style == Style::Normal) {
+            style = name_style;
+            from_name = true;
+        }
+        let weight_unset = weight == Weight::NORMAL || weight.value() <= 0.0;
+        if let Some(name_weight) = name_weight.filter(|_| weight_unset) {
+            weight = name_weight;
+            from_name = true;
+        }
+    }
+    (stretch, style, weight, from_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_version;
+
+    #[test]
+    fn version_strings() {
+        let parse = |s: &str| parse_version(s.chars());
+        assert_eq!(parse("Version 2.013; ttfautohint (v1.8)"), Some(2.013));
+        assert_eq!(parse("version 1.10"), Some(1.1));
+        assert_eq!(parse("1.000;PS 1.0;hotconv 1.0.88"), Some(1.0));
+        assert_eq!(parse("Version 3."), Some(3.0));
+        assert_eq!(parse("Version .5"), Some(5.0));
+        assert_eq!(parse("Version"), None);
     }
 }
@@ -128,6 +128,17 @@ pub struct GenericFamilyMap {
 }
 
 impl GenericFamilyMap {
+    /// Returns the estimated number of bytes held by the map.
+    pub(crate) fn memory_usage(&self) -> usize {
+        core::mem::size_of::<Self>()
+            + self
+                .map
+                .iter()
+                .filter(|families| families.spilled())
+                .map(|families| families.capacity() * core::mem::size_of::<FamilyId>())
+                .sum::<usize>()
+    }
+
     /// Returns the associated family identifiers for the given generic family.
     pub fn get(&self, generic: GenericFamily) -> &[FamilyId] {
         &self.map[generic as usize]
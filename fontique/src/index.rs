@@ -0,0 +1,314 @@
+//! Prebuilt indexes of system fonts.
+
+use super::{
+    backend::SystemFonts,
+    fallback::{FallbackKey, FallbackMap},
+    family::{FamilyId, FamilyInfo},
+    family_name::FamilyNameMap,
+    font::FontInfo,
+    generic::GenericFamilyMap,
+    source::SourcePathMap,
+    GenericFamily, Script,
+};
+use alloc::{sync::Arc, vec::Vec};
+use hashbrown::HashMap;
+use std::path::Path;
+
+/// Identifies the binary format of an index.
+const MAGIC: [u8; 4] = *b"FQIX";
+
+/// Version of the binary format, incremented on incompatible changes.
+const VERSION: u16 = 2;
+
+/// Snapshot of a system font collection that can be saved and loaded
+/// without scanning the system.
+///
+/// Scanning the fonts installed on a system can take a noticeable amount of
+/// time on first use. Applications that are packaged for known operating
+/// system images can capture the families, generic families and fallbacks
+/// of the system once with [`capture`](Self::capture), ship the result of
+/// [`to_bytes`](Self::to_bytes) and load it at startup with
+/// [`from_bytes`](Self::from_bytes). The loaded index is passed to
+/// [`Collection::with_system_fonts`](crate::Collection::with_system_fonts)
+/// and serves as a fixed set of system fonts.
+///
+/// Fonts are stored by path along with all of the metadata read from the
+/// font files, so no files are read when the index is loaded. Fonts that
+/// are backed by memory or whose paths are not valid UTF-8 are omitted.
+/// The index is not updated when the fonts on the system change.
+#[derive(Clone, Default)]
+pub struct FontIndex {
+    name_map: Arc<FamilyNameMap>,
+    generic_families: Arc<GenericFamilyMap>,
+    families: HashMap<FamilyId, FamilyInfo>,
+    fallbacks: FallbackMap,
+}
+
+impl FontIndex {
+    /// Captures the families of the given system fonts.
+    ///
+    /// Fallbacks are resolved for the default locale of each script with
+    /// [sample text](Script::sample) and for each of the additional keys,
+    /// which can be used to include locale specific fallbacks such as those
+    /// for Japanese and Chinese variants of Han characters.
+    pub fn capture(
+        fonts: &mut impl SystemFonts,
+        fallback_keys: impl IntoIterator<Item = FallbackKey>,
+    ) -> Self {
+        let name_map = fonts.name_map();
+        let generic_families = fonts.generic_families();
+        let mut families = HashMap::new();
+        for name in name_map.iter() {
+            if families.contains_key(&name.id()) {
+                continue;
+            }
+            if let Some(family) = fonts.family(name.id()) {
+                families.insert(name.id(), family);
+            }
+        }
+        let mut fallbacks = FallbackMap::default();
+        let keys = Script::all_samples()
+            .iter()
+            .map(|(script, _)| FallbackKey::from(*script))
+            .chain(fallback_keys);
+        for key in keys {
+            if fallbacks.get(key).is_some() {
+                continue;
+            }
+            let family = match key.script().sample() {
+                Some(sample) => fonts.fallback_for_sample(key, sample),
+                None => fonts.fallback(key),
+            };
+            if let Some(family) = family.filter(|id| families.contains_key(id)) {
+                fallbacks.set(key, core::iter::once(family));
+            }
+        }
+        Self {
+            name_map,
+            generic_families,
+            families,
+            fallbacks,
+        }
+    }
+
+    /// Returns the number of families in the index.
+    pub fn len(&self) -> usize {
+        self.families.len()
+    }
+
+    /// Returns true if the index contains no families.
+    pub fn is_empty(&self) -> bool {
+        self.families.is_empty()
+    }
+
+    /// Encodes the index in a compact binary format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::default();
+        encoder.bytes(&MAGIC);
+        encoder.u16(VERSION);
+        // Families are referenced by their position in the index since
+        // identifiers are only unique within a process.
+        let mut ids: Vec<FamilyId> = self.families.keys().copied().collect();
+        ids.sort();
+        let index_of = |id: FamilyId| ids.binary_search(&id).ok();
+        encoder.u32(ids.len() as u32);
+        for id in &ids {
+            let family = &self.families[id];
+            encoder.str(family.name());
+            let fonts: Vec<_> = family
+                .fonts()
+                .iter()
+                .filter_map(|font| Some((font, font_path(font)?)))
+                .collect();
+            encoder.u32(fonts.len() as u32);
+            for (font, path) in fonts {
+                encoder.str(path);
+                font.encode(&mut encoder);
+            }
+        }
+        for generic in GenericFamily::all() {
+            let families: Vec<_> = self
+                .generic_families
+                .get(*generic)
+                .iter()
+                .filter_map(|id| index_of(*id))
+                .collect();
+            encoder.u32(families.len() as u32);
+            for family in families {
+                encoder.u32(family as u32);
+            }
+        }
+        let fallbacks: Vec<_> = self
+            .fallbacks
+            .iter()
+            .filter_map(|(script, locale, families)| {
+                Some((script, locale, index_of(*families.first()?)?))
+            })
+            .collect();
+        encoder.u32(fallbacks.len() as u32);
+        for (script, locale, family) in fallbacks {
+            encoder.bytes(&script.0);
+            encoder.str(locale);
+            encoder.u32(family as u32);
+        }
+        encoder.0
+    }
+
+    /// Decodes an index from data produced by [`to_bytes`](Self::to_bytes).
+    ///
+    /// Returns `None` if the data is malformed or was produced by an
+    /// incompatible version of this crate.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut decoder = Decoder(data);
+        if decoder.bytes(4)? != MAGIC || decoder.u16()? != VERSION {
+            return None;
+        }
+        let mut name_map = FamilyNameMap::default();
+        let mut source_map = SourcePathMap::default();
+        let family_count = decoder.u32()? as usize;
+        let mut ids = Vec::with_capacity(family_count.min(data.len()));
+        let mut families = HashMap::new();
+        for _ in 0..family_count {
+            let name = name_map.get_or_insert(decoder.str()?);
+            let font_count = decoder.u32()? as usize;
+            let mut fonts = Vec::with_capacity(font_count.min(data.len()));
+            for _ in 0..font_count {
+                let source = source_map.get_or_insert(Path::new(decoder.str()?));
+                fonts.push(FontInfo::decode(source, &mut decoder)?);
+            }
+            ids.push(name.id());
+            if !fonts.is_empty() {
+                families.insert(name.id(), FamilyInfo::new(name, fonts));
+            }
+        }
+        let family_id = |decoder: &mut Decoder| ids.get(decoder.u32()? as usize).copied();
+        let mut generic_families = GenericFamilyMap::default();
+        for generic in GenericFamily::all() {
+            let count = decoder.u32()? as usize;
+            let mut families = Vec::with_capacity(count.min(data.len()));
+            for _ in 0..count {
+                families.push(family_id(&mut decoder)?);
+            }
+            generic_families.set(*generic, families.into_iter());
+        }
+        let mut fallbacks = FallbackMap::default();
+        for _ in 0..decoder.u32()? {
+            let script = Script(decoder.bytes(4)?.try_into().ok()?);
+            let locale = decoder.str()?;
+            let family = family_id(&mut decoder)?;
+            let key = if locale.is_empty() {
+                FallbackKey::from(script)
+            } else {
+                FallbackKey::from((script, locale))
+            };
+            fallbacks.set(key, core::iter::once(family));
+        }
+        Some(Self {
+            name_map: Arc::new(name_map),
+            generic_families: Arc::new(generic_families),
+            families,
+            fallbacks,
+        })
+    }
+}
+
+impl SystemFonts for FontIndex {
+    fn name_map(&self) -> Arc<FamilyNameMap> {
+        self.name_map.clone()
+    }
+
+    fn generic_families(&self) -> Arc<GenericFamilyMap> {
+        self.generic_families.clone()
+    }
+
+    fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
+        self.families.get(&id).cloned()
+    }
+
+    fn fallback(&mut self, key: FallbackKey) -> Option<FamilyId> {
+        self.fallbacks
+            .get(key)
+            .and_then(|families| families.first().copied())
+    }
+}
+
+fn font_path(font: &FontInfo) -> Option<&str> {
+    match font.source().kind() {
+        super::SourceKind::Path(path) => path.to_str(),
+        super::SourceKind::Memory(_) => None,
+    }
+}
+
+/// Writes values in the binary index format.
+#[derive(Default)]
+pub(crate) struct Encoder(Vec<u8>);
+
+impl Encoder {
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.0.extend_from_slice(bytes);
+    }
+
+    pub fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    pub fn u16(&mut self, value: u16) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u32(&mut self, value: u32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn u64(&mut self, value: u64) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn f32(&mut self, value: f32) {
+        self.bytes(&value.to_le_bytes());
+    }
+
+    pub fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.bytes(value.as_bytes());
+    }
+}
+
+/// Reads values in the binary index format.
+pub(crate) struct Decoder<'a>(&'a [u8]);
+
+impl<'a> Decoder<'a> {
+    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.0.len() {
+            return None;
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(bytes)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    pub fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+
+    pub fn f32(&mut self) -> Option<f32> {
+        Some(f32::from_le_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    pub fn str(&mut self) -> Option<&'a str> {
+        let len = self.u32()? as usize;
+        core::str::from_utf8(self.bytes(len)?).ok()
+    }
+}
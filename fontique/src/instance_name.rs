@@ -0,0 +1,208 @@
+//! Names for locations in the design space of variable fonts.
+
+use super::scan::english_or_first;
+use alloc::{string::String, vec::Vec};
+use read_fonts::{
+    tables::{
+        name::Name,
+        stat::{AxisValue, AxisValueTableFlags, Stat},
+    },
+    types::{NameId, Tag},
+    FontRef, TableProvider as _,
+};
+
+/// Maximum difference between a location and an axis value for them to be
+/// considered equal.
+const EPSILON: f32 = 0.01;
+
+/// Returns the full name of the font at the given user space location.
+///
+/// Axes that are missing from the location are set to their default
+/// values. The style part of the name is built from the `STAT` axis value
+/// names when every axis is named at the location and otherwise comes from
+/// the nearest named instance.
+pub(crate) fn instance_name(font: &FontRef, location: &[(Tag, f32)]) -> Option<String> {
+    let names = font.name().ok()?;
+    let mut name = english_or_first(&names, NameId::TYPOGRAPHIC_FAMILY_NAME)
+        .or_else(|| english_or_first(&names, NameId::FAMILY_NAME))?
+        .chars()
+        .collect::<String>();
+    let fvar = font.fvar().ok()?;
+    let axes = fvar.axes().ok()?;
+    let coords: Vec<f32> = axes
+        .iter()
+        .map(|axis| {
+            let value = location
+                .iter()
+                .find(|(tag, _)| *tag == axis.axis_tag())
+                .map(|(_, value)| *value)
+                .unwrap_or_else(|| axis.default_value().to_f32());
+            value.clamp(axis.min_value().to_f32(), axis.max_value().to_f32())
+        })
+        .collect();
+    let tags: Vec<Tag> = axes.iter().map(|axis| axis.axis_tag()).collect();
+    let style = font
+        .stat()
+        .ok()
+        .and_then(|stat| stat_style_name(&stat, &names, &tags, &coords))
+        .or_else(|| {
+            // Choose the named instance with the smallest distance,
+            // measured relative to the extent of each axis.
+            let instances = fvar.instances().ok()?;
+            let mut best: Option<(f32, NameId)> = None;
+            for instance in instances.iter().flatten() {
+                let distance: f32 = axes
+                    .iter()
+                    .zip(instance.coordinates)
+                    .zip(&coords)
+                    .map(|((axis, value), coord)| {
+                        let extent = axis.max_value().to_f32() - axis.min_value().to_f32();
+                        let delta = value.get().to_f32() - coord;
+                        if extent > 0.0 {
+                            (delta / extent) * (delta / extent)
+                        } else {
+                            0.0
+                        }
+                    })
+                    .sum();
+                if best.map_or(true, |best| distance < best.0) {
+                    best = Some((distance, instance.subfamily_name_id));
+                }
+            }
+            let id = best?.1;
+            Some(english_or_first(&names, id)?.chars().collect())
+        })
+        .unwrap_or_default();
+    if !style.is_empty() {
+        name.push(' ');
+        name.push_str(&style);
+    }
+    Some(name)
+}
+
+/// Builds a style name from the `STAT` axis values that match the location.
+///
+/// Returns `None` unless every design axis that is also a variation axis is
+/// named.
+fn stat_style_name(stat: &Stat, names: &Name, tags: &[Tag], coords: &[f32]) -> Option<String> {
+    let design_axes = stat.design_axes().ok()?;
+    let values = stat.offset_to_axis_values()?.ok()?;
+    // Location of each design axis, if it is also a variation axis.
+    let design_coords: Vec<Option<f32>> = design_axes
+        .iter()
+        .map(|axis| {
+            let index = tags.iter().position(|tag| *tag == axis.axis_tag())?;
+            Some(coords[index])
+        })
+        .collect();
+    let matches = |axis_index: u16, value: f32| {
+        design_coords
+            .get(axis_index as usize)
+            .copied()
+            .flatten()
+            .map_or(false, |coord| (coord - value).abs() < EPSILON)
+    };
+    let ordering = |axis_index: u16| {
+        design_axes
+            .get(axis_index as usize)
+            .map_or(u16::MAX, |axis| axis.axis_ordering())
+    };
+    // Selected name for each design axis, along with whether it is elidable.
+    let mut selected: Vec<Option<(NameId, bool)>> = vec![None; design_axes.len()];
+    let mut multi_axis = false;
+    for value in values.axis_values().iter().flatten() {
+        let elidable = value
+            .flags()
+            .contains(AxisValueTableFlags::ELIDABLE_AXIS_VALUE_NAME);
+        let name = (value.value_name_id(), elidable);
+        match &value {
+            AxisValue::Format1(value) => {
+                let index = value.axis_index();
+                if matches(index, value.value().to_f32()) {
+                    select(&mut selected, index, name);
+                }
+            }
+            AxisValue::Format2(value) => {
+                let index = value.axis_index();
+                let min = value.range_min_value().to_f32();
+                let max = value.range_max_value().to_f32();
+                if design_coords
+                    .get(index as usize)
+                    .copied()
+                    .flatten()
+                    .map_or(false, |coord| coord >= min && coord <= max)
+                {
+                    select(&mut selected, index, name);
+                }
+            }
+            AxisValue::Format3(value) => {
+                let index = value.axis_index();
+                if matches(index, value.value().to_f32()) {
+                    select(&mut selected, index, name);
+                }
+            }
+            AxisValue::Format4(value) => {
+                // Values that cover several axes take precedence, so only
+                // the first match is used.
+                let records = value.axis_values();
+                if !multi_axis
+                    && !records.is_empty()
+                    && records
+                        .iter()
+                        .all(|record| matches(record.axis_index(), record.value().to_f32()))
+                {
+                    multi_axis = true;
+                    // The name is placed at the first of the covered axes
+                    // and elided from the others.
+                    let first = records
+                        .iter()
+                        .map(|record| record.axis_index())
+                        .min_by_key(|&index| ordering(index));
+                    for record in records {
+                        let index = record.axis_index();
+                        if let Some(slot) = selected.get_mut(index as usize) {
+                            *slot = Some(if Some(index) == first {
+                                name
+                            } else {
+                                (name.0, true)
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let mut order: Vec<usize> = (0..design_axes.len()).collect();
+    order.sort_by_key(|&index| ordering(index as u16));
+    let mut style = String::new();
+    for index in order {
+        if design_coords[index].is_none() {
+            continue;
+        }
+        let (id, elidable) = selected[index]?;
+        if elidable {
+            continue;
+        }
+        let name = english_or_first(names, id)?;
+        if !style.is_empty() {
+            style.push(' ');
+        }
+        style.extend(name.chars());
+    }
+    if style.is_empty() {
+        if let Some(id) = stat.elided_fallback_name_id() {
+            style.extend(english_or_first(names, id)?.chars());
+        }
+    }
+    Some(style)
+}
+
+/// Selects a name for the axis unless one was already chosen by a value
+/// that covers several axes.
+fn select(selected: &mut [Option<(NameId, bool)>], axis_index: u16, name: (NameId, bool)) {
+    if let Some(slot) = selected.get_mut(axis_index as usize) {
+        if slot.is_none() {
+            *slot = Some(name);
+        }
+    }
+}
@@ -0,0 +1,467 @@
+//! Generated last resort font.
+//!
+//! The font maps every Unicode scalar value to a box glyph that identifies
+//! the character, so that text never renders as invisible or anonymous
+//! notdef glyphs. Characters in the Basic Multilingual Plane are drawn as a
+//! box containing the four hexadecimal digits of the codepoint in two rows.
+//! Characters in the supplementary planes are drawn as a box containing the
+//! two digit plane number, since the glyph count of a font cannot cover
+//! each of them.
+//!
+//! The font is generated on first use rather than embedded as a file and
+//! occupies a few megabytes.
+
+use super::{
+    font::FontInfo,
+    source::{SourceId, SourceInfo, SourceKind},
+    Blob,
+};
+use alloc::{sync::Arc, vec::Vec};
+use std::sync::OnceLock;
+
+/// Family name of the last resort font.
+pub const LAST_RESORT_FAMILY_NAME: &str = "Last Resort";
+
+/// Returns the data of the last resort font.
+pub fn last_resort_data() -> Blob<u8> {
+    static DATA: OnceLock<Blob<u8>> = OnceLock::new();
+    DATA.get_or_init(|| Blob::new(Arc::new(build()))).clone()
+}
+
+/// Returns the last resort font.
+pub fn last_resort_font() -> FontInfo {
+    static FONT: OnceLock<FontInfo> = OnceLock::new();
+    FONT.get_or_init(|| {
+        let source = SourceInfo::new(SourceId::new(), SourceKind::Memory(last_resort_data()));
+        // The generated font is always valid.
+        FontInfo::from_source(source, 0).unwrap()
+    })
+    .clone()
+}
+
+const UNITS_PER_EM: u16 = 1000;
+const ADVANCE: u16 = 600;
+const ASCENT: i16 = 800;
+const DESCENT: i16 = -200;
+
+/// Bounds of the box around the digits.
+const BOX: [i16; 4] = [40, -120, 560, 780];
+/// Thickness of the box outline.
+const BOX_STROKE: i16 = 40;
+
+/// Size and stroke of a seven segment digit.
+const DIGIT_WIDTH: i16 = 160;
+const DIGIT_HEIGHT: i16 = 280;
+const DIGIT_STROKE: i16 = 40;
+/// Offsets of the digit positions in the upper and lower rows.
+const DIGIT_COLUMNS: [i16; 2] = [110, 330];
+const DIGIT_ROWS: [i16; 2] = [400, 20];
+
+/// Segments of each hexadecimal digit as bits of `abcdefg`, where `a` is
+/// the top segment and the rest follow clockwise with `g` in the middle.
+const DIGIT_SEGMENTS: [u8; 16] = [
+    0b1111110, 0b0110000, 0b1101101, 0b1111001, 0b0110011, 0b1011011, 0b1011111, 0b1110000,
+    0b1111111, 0b1111011, 0b1110111, 0b0011111, 0b1001110, 0b0111101, 0b1001111, 0b1000111,
+];
+
+/// Glyph identifiers of the building blocks. The glyphs for each
+/// codepoint follow.
+const FIRST_DIGIT_GLYPH: u16 = 1;
+const BOX_GLYPH: u16 = FIRST_DIGIT_GLYPH + 16;
+const FIRST_PAIR_GLYPH: u16 = BOX_GLYPH + 1;
+const FIRST_PLANE_GLYPH: u16 = FIRST_PAIR_GLYPH + 256;
+const FIRST_BMP_GLYPH: u16 = FIRST_PLANE_GLYPH + 16;
+
+/// Simple or composite glyph outline.
+enum Glyph {
+    /// Rectangles given as `[x_min, y_min, x_max, y_max]`. Counters are
+    /// wound in the opposite direction.
+    Rects(Vec<([i16; 4], bool)>),
+    /// Components given as glyph identifier and offset, along with the
+    /// bounds of the glyph.
+    Components(Vec<(u16, i16, i16)>, [i16; 4]),
+}
+
+fn build() -> Vec<u8> {
+    let mut glyphs = Vec::new();
+    // The notdef glyph is an empty box.
+    glyphs.push(box_glyph());
+    for segments in DIGIT_SEGMENTS {
+        glyphs.push(digit_glyph(segments));
+    }
+    glyphs.push(box_glyph());
+    // Pairs of digits to keep the glyphs of each codepoint small.
+    for pair in 0..=255u16 {
+        glyphs.push(Glyph::Components(
+            vec![
+                (FIRST_DIGIT_GLYPH + (pair >> 4), DIGIT_COLUMNS[0], 0),
+                (FIRST_DIGIT_GLYPH + (pair & 0xF), DIGIT_COLUMNS[1], 0),
+            ],
+            [
+                DIGIT_COLUMNS[0],
+                0,
+                DIGIT_COLUMNS[1] + DIGIT_WIDTH,
+                DIGIT_HEIGHT,
+            ],
+        ));
+    }
+    for plane in 1..=16u16 {
+        glyphs.push(Glyph::Components(
+            vec![
+                (BOX_GLYPH, 0, 0),
+                (FIRST_PAIR_GLYPH + plane, 0, DIGIT_ROWS[0]),
+            ],
+            BOX,
+        ));
+    }
+    let mut groups = Vec::new();
+    for codepoint in (0..0xD800u32).chain(0xE000..=0xFFFF) {
+        let glyph_id = glyphs.len() as u32;
+        glyphs.push(Glyph::Components(
+            vec![
+                (BOX_GLYPH, 0, 0),
+                (FIRST_PAIR_GLYPH + (codepoint >> 8) as u16, 0, DIGIT_ROWS[0]),
+                (
+                    FIRST_PAIR_GLYPH + (codepoint & 0xFF) as u16,
+                    0,
+                    DIGIT_ROWS[1],
+                ),
+            ],
+            BOX,
+        ));
+        groups.push((codepoint, codepoint, glyph_id));
+    }
+    for plane in 1..=16u32 {
+        let glyph_id = (FIRST_PLANE_GLYPH as u32) + plane - 1;
+        groups.push((plane << 16, (plane << 16) | 0xFFFF, glyph_id));
+    }
+    debug_assert_eq!(glyphs.len(), FIRST_BMP_GLYPH as usize + 0xF800);
+    let (glyf, loca) = build_glyf_loca(&glyphs);
+    let num_glyphs = glyphs.len() as u16;
+    let mut tables = [
+        (*b"cmap", build_cmap(&groups)),
+        (*b"glyf", glyf),
+        (*b"head", build_head()),
+        (*b"hhea", build_hhea()),
+        (*b"hmtx", build_hmtx()),
+        (*b"loca", loca),
+        (*b"maxp", build_maxp(num_glyphs)),
+        (*b"name", build_name()),
+        (*b"post", build_post()),
+    ];
+    tables.sort_by_key(|(tag, _)| *tag);
+    let mut font = Writer::default();
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = (1 << entry_selector) * 16;
+    font.u32(0x00010000);
+    font.u16(num_tables);
+    font.u16(search_range);
+    font.u16(entry_selector);
+    font.u16(num_tables * 16 - search_range);
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in &tables {
+        font.0.extend_from_slice(tag);
+        font.u32(checksum(data));
+        font.u32(offset as u32);
+        font.u32(data.len() as u32);
+        offset += (data.len() + 3) & !3;
+    }
+    for (_, data) in &tables {
+        font.0.extend_from_slice(data);
+        font.pad();
+    }
+    font.0
+}
+
+fn box_glyph() -> Glyph {
+    let [x_min, y_min, x_max, y_max] = BOX;
+    let s = BOX_STROKE;
+    Glyph::Rects(vec![
+        (BOX, false),
+        ([x_min + s, y_min + s, x_max - s, y_max - s], true),
+    ])
+}
+
+fn digit_glyph(segments: u8) -> Glyph {
+    let (w, h, t) = (DIGIT_WIDTH, DIGIT_HEIGHT, DIGIT_STROKE);
+    let mid = h / 2;
+    let rects = [
+        [0, h - t, w, h],
+        [w - t, mid, w, h],
+        [w - t, 0, w, mid],
+        [0, 0, w, t],
+        [0, 0, t, mid],
+        [0, mid, t, h],
+        [0, mid - t / 2, w, mid + t / 2],
+    ];
+    Glyph::Rects(
+        rects
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| segments & (0b1000000 >> i) != 0)
+            .map(|(_, rect)| (*rect, false))
+            .collect(),
+    )
+}
+
+fn build_glyf_loca(glyphs: &[Glyph]) -> (Vec<u8>, Vec<u8>) {
+    let mut glyf = Writer::default();
+    let mut loca = Writer::default();
+    for glyph in glyphs {
+        loca.u32(glyf.0.len() as u32);
+        match glyph {
+            Glyph::Rects(rects) => {
+                if rects.is_empty() {
+                    continue;
+                }
+                glyf.u16(rects.len() as u16);
+                let bounds =
+                    rects
+                        .iter()
+                        .fold([i16::MAX, i16::MAX, i16::MIN, i16::MIN], |b, (r, _)| {
+                            [
+                                b[0].min(r[0]),
+                                b[1].min(r[1]),
+                                b[2].max(r[2]),
+                                b[3].max(r[3]),
+                            ]
+                        });
+                for value in bounds {
+                    glyf.i16(value);
+                }
+                for i in 0..rects.len() {
+                    glyf.u16(i as u16 * 4 + 3);
+                }
+                // No instructions
+                glyf.u16(0);
+                let points: Vec<(i16, i16)> = rects
+                    .iter()
+                    .flat_map(|([x0, y0, x1, y1], is_counter)| {
+                        // Clockwise for outer contours.
+                        let points = [(*x0, *y0), (*x0, *y1), (*x1, *y1), (*x1, *y0)];
+                        let mut points = points.to_vec();
+                        if *is_counter {
+                            points.reverse();
+                        }
+                        points
+                    })
+                    .collect();
+                // All points are on curve with 16-bit coordinates.
+                for _ in &points {
+                    glyf.0.push(1);
+                }
+                let mut last = 0;
+                for (x, _) in &points {
+                    glyf.i16(x - last);
+                    last = *x;
+                }
+                let mut last = 0;
+                for (_, y) in &points {
+                    glyf.i16(y - last);
+                    last = *y;
+                }
+            }
+            Glyph::Components(components, bounds) => {
+                glyf.i16(-1);
+                for value in bounds {
+                    glyf.i16(value);
+                }
+                for (i, (glyph_id, dx, dy)) in components.iter().enumerate() {
+                    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+                    const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+                    const MORE_COMPONENTS: u16 = 0x0020;
+                    let mut flags = ARG_1_AND_2_ARE_WORDS | ARGS_ARE_XY_VALUES;
+                    if i + 1 < components.len() {
+                        flags |= MORE_COMPONENTS;
+                    }
+                    glyf.u16(flags);
+                    glyf.u16(*glyph_id);
+                    glyf.i16(*dx);
+                    glyf.i16(*dy);
+                }
+            }
+        }
+        glyf.pad();
+    }
+    loca.u32(glyf.0.len() as u32);
+    (glyf.0, loca.0)
+}
+
+fn build_cmap(groups: &[(u32, u32, u32)]) -> Vec<u8> {
+    let mut w = Writer::default();
+    w.u16(0);
+    w.u16(1);
+    // Windows, full Unicode repertoire
+    w.u16(3);
+    w.u16(10);
+    w.u32(12);
+    // Format 13 maps each range to a single glyph.
+    w.u16(13);
+    w.u16(0);
+    w.u32(16 + groups.len() as u32 * 12);
+    w.u32(0);
+    w.u32(groups.len() as u32);
+    for (start, end, glyph_id) in groups {
+        w.u32(*start);
+        w.u32(*end);
+        w.u32(*glyph_id);
+    }
+    w.0
+}
+
+fn build_head() -> Vec<u8> {
+    let mut w = Writer::default();
+    w.u32(0x00010000);
+    // Font revision
+    w.u32(0x00010000);
+    // Checksum adjustment
+    w.u32(0);
+    w.u32(0x5F0F3CF5);
+    w.u16(0);
+    w.u16(UNITS_PER_EM);
+    // Created and modified dates
+    w.u32(0);
+    w.u32(0);
+    w.u32(0);
+    w.u32(0);
+    for value in BOX {
+        w.i16(value);
+    }
+    // Mac style, lowest rec ppem and font direction hint
+    w.u16(0);
+    w.u16(8);
+    w.i16(2);
+    // Long loca offsets
+    w.i16(1);
+    w.i16(0);
+    w.0
+}
+
+fn build_hhea() -> Vec<u8> {
+    let mut w = Writer::default();
+    w.u32(0x00010000);
+    w.i16(ASCENT);
+    w.i16(DESCENT);
+    w.i16(0);
+    w.u16(ADVANCE);
+    w.i16(BOX[0]);
+    w.i16(ADVANCE as i16 - BOX[2]);
+    w.i16(BOX[2]);
+    // Caret slope rise, run and offset
+    w.i16(1);
+    w.i16(0);
+    w.i16(0);
+    for _ in 0..4 {
+        w.i16(0);
+    }
+    // Metric data format
+    w.i16(0);
+    // All glyphs share a single long metric.
+    w.u16(1);
+    w.0
+}
+
+fn build_hmtx() -> Vec<u8> {
+    let mut w = Writer::default();
+    w.u16(ADVANCE);
+    w.i16(BOX[0]);
+    w.0
+}
+
+fn build_maxp(num_glyphs: u16) -> Vec<u8> {
+    let mut w = Writer::default();
+    w.u32(0x00010000);
+    w.u16(num_glyphs);
+    // Max points and contours for simple glyphs
+    w.u16(28);
+    w.u16(7);
+    // Max points and contours for composite glyphs
+    w.u16(120);
+    w.u16(30);
+    // Max zones, twilight points, storage, function defs, instruction defs
+    // and stack elements
+    w.u16(2);
+    w.u16(0);
+    w.u16(0);
+    w.u16(0);
+    w.u16(0);
+    w.u16(0);
+    // Max size of instructions, component elements and component depth
+    w.u16(0);
+    w.u16(3);
+    w.u16(2);
+    w.0
+}
+
+fn build_name() -> Vec<u8> {
+    let names = [(1, LAST_RESORT_FAMILY_NAME), (2, "Regular")];
+    let mut w = Writer::default();
+    w.u16(0);
+    w.u16(names.len() as u16);
+    w.u16(6 + names.len() as u16 * 12);
+    let mut offset = 0;
+    for (name_id, name) in names {
+        // Windows, Unicode BMP, English (United States)
+        w.u16(3);
+        w.u16(1);
+        w.u16(0x409);
+        w.u16(name_id);
+        w.u16(name.len() as u16 * 2);
+        w.u16(offset);
+        offset += name.len() as u16 * 2;
+    }
+    for (_, name) in names {
+        for unit in name.encode_utf16() {
+            w.u16(unit);
+        }
+    }
+    w.0
+}
+
+fn build_post() -> Vec<u8> {
+    let mut w = Writer::default();
+    // Version 3.0 has no glyph names.
+    w.u32(0x00030000);
+    w.u32(0);
+    w.i16(-100);
+    w.i16(50);
+    w.u32(0);
+    for _ in 0..4 {
+        w.u32(0);
+    }
+    w.0
+}
+
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut bytes = [0u8; 4];
+        bytes[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(bytes))
+    })
+}
+
+/// Big endian writer for font data.
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn i16(&mut self, value: i16) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    /// Pads to a four byte boundary.
+    fn pad(&mut self) {
+        while self.0.len() % 4 != 0 {
+            self.0.push(0);
+        }
+    }
+}
@@ -6,6 +6,7 @@ extern crate alloc;
 
 mod attributes;
 mod backend;
+mod charset;
 mod collection;
 mod fallback;
 mod family;
@@ -15,22 +16,64 @@ mod generic;
 mod matching;
 mod scan;
 mod script;
+mod script_data;
 mod source;
 
+#[cfg(feature = "std")]
+mod convert;
+#[cfg(feature = "std")]
+mod index;
+#[cfg(feature = "std")]
+mod instance_name;
+#[cfg(feature = "last-resort")]
+mod last_resort;
+#[cfg(feature = "std")]
+mod remote;
 #[cfg(feature = "std")]
 mod source_cache;
+#[cfg(all(test, feature = "std"))]
+mod testing;
 
 pub use icu_locid::LanguageIdentifier as Language;
 pub use peniko::Blob;
 
-pub use attributes::{Attributes, Stretch, Style, Weight};
-pub use collection::{Collection, CollectionOptions, Query, QueryFamily, QueryFont, QueryStatus};
-pub use fallback::FallbackKey;
+pub use attributes::{AttributeRanges, Attributes, Stretch, Style, Weight};
+pub use charset::Charset;
+pub use collection::{
+    Collection, CollectionOptions, CollectionSnapshot, FamilyOrigin, FamilyPrecedence, Query,
+    QueryFamily, QueryFont, QueryStatus,
+};
+pub use fallback::{ColorPreference, FallbackKey};
 pub use family::{FamilyId, FamilyInfo};
 pub use font::{AxisInfo, FontInfo, Synthesis};
 pub use generic::GenericFamily;
 pub use script::Script;
 pub use source::{SourceId, SourceInfo, SourceKind};
 
+// Custom system font providers are shared behind a mutex so they can only
+// be installed with std.
+#[cfg(feature = "std")]
+pub use backend::SystemFonts;
+#[cfg(feature = "std")]
+pub use family_name::{FamilyName, FamilyNameMap};
+#[cfg(feature = "std")]
+pub use generic::GenericFamilyMap;
+
+#[cfg(all(feature = "system", target_os = "windows"))]
+pub use backend::GdiCompatibleMetrics;
+#[cfg(all(feature = "system", target_os = "linux"))]
+pub use backend::{
+    FontconfigProperty, FontconfigRanking, FontconfigValue, PlatformFonts as FontconfigFonts,
+};
+#[cfg(feature = "std")]
+pub use convert::FontConverter;
+#[cfg(feature = "std")]
+pub use index::FontIndex;
+#[cfg(feature = "last-resort")]
+pub use last_resort::{last_resort_data, last_resort_font, LAST_RESORT_FAMILY_NAME};
+#[cfg(feature = "std")]
+pub use remote::{RemoteFontDelivery, RemoteFontProvider, RemoteFontRequest};
+#[cfg(feature = "std")]
+pub use scan::{ScanReport, SkipReason, SkippedFile};
 #[cfg(feature = "std")]
 pub use source_cache::{SourceCache, SourceCacheOptions};
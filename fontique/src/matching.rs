@@ -22,7 +22,9 @@ pub fn match_font(
     #[derive(Copy, Clone)]
     struct Candidate {
         index: usize,
-        stretch: i32,
+        /// Range of stretch percentages, which spans the `wdth` axis for
+        /// variable fonts.
+        stretch: (i32, i32),
         style: Style,
         weight: f32,
         has_slnt: bool,
@@ -32,7 +34,10 @@ pub fn match_font(
         .enumerate()
         .map(|(i, font)| Candidate {
             index: i,
-            stretch: (font.stretch().ratio() * 100.0) as i32,
+            stretch: {
+                let (min, max) = font.attribute_ranges().stretch;
+                ((min.ratio() * 100.0) as i32, (max.ratio() * 100.0) as i32)
+            },
             style: font.style(),
             weight: font.weight().value(),
             has_slnt: font.has_slant_axis(),
@@ -40,55 +45,41 @@ pub fn match_font(
         .collect();
     let stretch = (stretch.ratio() * 100.0) as i32;
     let weight = weight.value();
-    // font-stretch is tried first:
-    let mut use_stretch = set[0].stretch;
-    if !set.iter().any(|f| f.stretch == stretch) {
-        // If the desired stretch value is less than or equal to 100%...
-        if stretch <= 100 {
-            // stretch values below the desired stretch value are checked in
-            // descending order...
-            if let Some(found) = set
-                .iter()
-                .filter(|f| f.stretch < stretch)
-                .max_by_key(|f| f.stretch)
-            {
-                use_stretch = found.stretch;
-            }
-            // followed by stretch values above the desired stretch value in
-            // ascending order until a match is found.
-            else if let Some(found) = set
-                .iter()
-                .filter(|f| f.stretch > stretch)
-                .min_by_key(|f| f.stretch)
-            {
-                use_stretch = found.stretch;
-            }
-        }
-        // Otherwise, ...
-        else {
-            // stretch values above the desired stretch value are checked in
-            // ascending order...
-            if let Some(found) = set
-                .iter()
-                .filter(|f| f.stretch > stretch)
-                .min_by_key(|f| f.stretch)
-            {
-                use_stretch = found.stretch;
-            }
-            // followed by stretch values below the desired stretch value in
-            // descending order until a match is found.
-            else if let Some(found) = set
-                .iter()
-                .filter(|f| f.stretch < stretch)
-                .max_by_key(|f| f.stretch)
-            {
-                use_stretch = found.stretch;
-            }
+    // font-stretch is tried first. Fonts with a width axis match any
+    // stretch value within the range of the axis, so the nearest value of
+    // a range is its endpoint on the side of the desired value.
+    let covers = |f: &Candidate, stretch: i32| f.stretch.0 <= stretch && stretch <= f.stretch.1;
+    let mut use_stretch = set[0].stretch.0;
+    if !set.iter().any(|f| covers(f, stretch)) {
+        let below = set
+            .iter()
+            .filter(|f| f.stretch.1 < stretch)
+            .map(|f| f.stretch.1)
+            .max();
+        let above = set
+            .iter()
+            .filter(|f| f.stretch.0 > stretch)
+            .map(|f| f.stretch.0)
+            .min();
+        // If the desired stretch value is less than or equal to 100%,
+        // stretch values below the desired stretch value are checked in
+        // descending order followed by stretch values above the desired
+        // stretch value in ascending order until a match is found.
+        // Otherwise, stretch values above the desired stretch value are
+        // checked in ascending order followed by stretch values below the
+        // desired stretch value in descending order until a match is found.
+        let found = if stretch <= 100 {
+            below.or(above)
+        } else {
+            above.or(below)
+        };
+        if let Some(found) = found {
+            use_stretch = found;
         }
     } else {
         use_stretch = stretch;
     }
-    set.retain(|f| f.stretch == use_stretch);
+    set.retain(|f| covers(f, use_stretch));
     use core::cmp::Ordering::*;
     let oblique_fonts = set.iter().filter_map(|f| oblique_style(f.style));
     // font-style is tried next:
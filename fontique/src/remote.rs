@@ -0,0 +1,96 @@
+//! Support for fonts that are downloaded on demand.
+
+use super::fallback::FallbackKey;
+use std::sync::{Arc, Mutex};
+
+/// Description of the fonts requested from a [`RemoteFontProvider`].
+#[derive(Clone, PartialEq, Debug)]
+pub enum RemoteFontRequest {
+    /// Fonts for the family with the given name.
+    Family(Arc<str>),
+    /// Fonts for any family that supports the script and locale of the
+    /// given key.
+    Fallback(FallbackKey),
+}
+
+/// Provider for fonts that are not available locally but can be fetched
+/// on demand, such as from a web font service.
+///
+/// A provider is consulted as the final tier of lookup, after registered
+/// and system fonts, and is registered with
+/// [`Collection::set_remote_provider`](crate::Collection::set_remote_provider).
+/// Each distinct request is made at most once per collection.
+pub trait RemoteFontProvider: Send + Sync {
+    /// Begins fetching fonts that satisfy the given request.
+    ///
+    /// Font data can be handed to the collection at any later time, and
+    /// from any thread, with [`RemoteFontDelivery::deliver`]. Returns false
+    /// if the provider has no fonts that satisfy the request.
+    fn request(&self, request: &RemoteFontRequest, delivery: RemoteFontDelivery) -> bool;
+}
+
+/// Handle for delivering the font data for a request to a collection.
+///
+/// Delivered fonts are registered when the collection is next queried.
+/// Fonts delivered for a fallback request are also appended to the
+/// fallback families for the requested key.
+#[derive(Clone)]
+pub struct RemoteFontDelivery {
+    request: RemoteFontRequest,
+    queue: Arc<Mutex<Vec<(RemoteFontRequest, Vec<u8>)>>>,
+}
+
+impl RemoteFontDelivery {
+    /// Returns the request that this delivery satisfies.
+    pub fn request(&self) -> &RemoteFontRequest {
+        &self.request
+    }
+
+    /// Hands the given font data to the collection.
+    ///
+    /// The data may be a single font or a collection.
+    pub fn deliver(&self, data: Vec<u8>) {
+        self.queue
+            .lock()
+            .unwrap()
+            .push((self.request.clone(), data));
+    }
+}
+
+/// State for a remote provider attached to a collection.
+#[derive(Clone)]
+pub(crate) struct Remote {
+    provider: Arc<dyn RemoteFontProvider>,
+    requested: Arc<Mutex<Vec<RemoteFontRequest>>>,
+    queue: Arc<Mutex<Vec<(RemoteFontRequest, Vec<u8>)>>>,
+}
+
+impl Remote {
+    pub fn new(provider: Arc<dyn RemoteFontProvider>) -> Self {
+        Self {
+            provider,
+            requested: Default::default(),
+            queue: Default::default(),
+        }
+    }
+
+    /// Sends the request to the provider unless it was made before.
+    pub fn request(&self, request: RemoteFontRequest) {
+        let mut requested = self.requested.lock().unwrap();
+        if requested.contains(&request) {
+            return;
+        }
+        requested.push(request.clone());
+        drop(requested);
+        let delivery = RemoteFontDelivery {
+            request: request.clone(),
+            queue: self.queue.clone(),
+        };
+        self.provider.request(&request, delivery);
+    }
+
+    /// Removes and returns all font data delivered so far.
+    pub fn take_delivered(&self) -> Vec<(RemoteFontRequest, Vec<u8>)> {
+        core::mem::take(&mut *self.queue.lock().unwrap())
+    }
+}
@@ -6,13 +6,27 @@ use super::{
     family::{FamilyId, FamilyInfo},
     family_name::{FamilyName, FamilyNameMap},
     font::FontInfo,
+    source::SourceInfo,
 };
 use alloc::string::String;
 use hashbrown::HashMap;
-use read_fonts::{tables::name, types::NameId, FileRef, FontRef, TableProvider as _};
+use read_fonts::{
+    tables::name,
+    types::{NameId, Tag},
+    FileRef, FontRef, TableProvider as _,
+};
 use smallvec::SmallVec;
 #[cfg(feature = "std")]
-use {super::source::SourcePathMap, std::path::Path};
+use {
+    super::{
+        charset::Charset,
+        source::{SourceId, SourceKind, SourcePathMap},
+    },
+    std::{
+        path::Path,
+        sync::{Arc, OnceLock},
+    },
+};
 
 #[cfg(feature = "std")]
 /// Font collection generated by scanning the file system.
@@ -21,7 +35,8 @@ pub struct ScannedCollection {
     pub family_names: FamilyNameMap,
     pub postscript_names: HashMap<String, FamilyId>,
     pub data_paths: SourcePathMap,
-    pub families: HashMap<FamilyId, FamilyInfo>,
+    pub families: HashMap<FamilyId, ScannedFamily>,
+    pub report: ScanReport,
 }
 
 #[cfg(feature = "std")]
@@ -33,6 +48,101 @@ impl ScannedCollection {
     }
 }
 
+#[cfg(feature = "std")]
+/// Family discovered while scanning the file system.
+///
+/// Only the location of each font is recorded; attributes are parsed
+/// when the family is first materialized.
+pub struct ScannedFamily {
+    pub name: FamilyName,
+    pub fonts: SmallVec<[(SourceInfo, u32); 4]>,
+}
+
+#[cfg(feature = "std")]
+impl ScannedFamily {
+    /// Loads the fonts of the family and returns the family object.
+    ///
+    /// Each file is mapped once for all of the fonts of the family that it
+    /// contains, and work for tables that are shared by the members of
+    /// collection files is reused through `shared`.
+    ///
+    /// Returns `None` if none of the fonts could be loaded.
+    pub fn load(&self, shared: &mut SharedTables) -> Option<FamilyInfo> {
+        let mut fonts: SmallVec<[FontInfo; 4]> = SmallVec::with_capacity(self.fonts.len());
+        let mut mapped: Option<(SourceId, Option<memmap2::Mmap>)> = None;
+        for (source, index) in &self.fonts {
+            let SourceKind::Path(path) = &source.kind else {
+                fonts.extend(FontInfo::from_source(source.clone(), *index));
+                continue;
+            };
+            if mapped.as_ref().map(|(id, _)| *id) != Some(source.id()) {
+                let data = std::fs::File::open(&**path)
+                    .ok()
+                    .and_then(|file| unsafe { memmap2::Mmap::map(&file) }.ok());
+                mapped = Some((source.id(), data));
+            }
+            let Some(data) = mapped.as_ref().and_then(|(_, data)| data.as_deref()) else {
+                continue;
+            };
+            let Ok(font_ref) = FontRef::from_index(data, *index) else {
+                continue;
+            };
+            if let Some(mut font) = FontInfo::from_font_ref(&font_ref, source.clone(), *index) {
+                shared.share(&mut font, data, &font_ref);
+                fonts.push(font);
+            }
+        }
+        (!fonts.is_empty()).then(|| FamilyInfo::new(self.name.clone(), fonts))
+    }
+}
+
+#[cfg(feature = "std")]
+/// Work that is shared by the members of font collection files.
+///
+/// The members of a collection often refer to the same table data. The
+/// regional fonts in CJK collections, for example, share their glyphs and
+/// character maps. Character sets are read once for each shared `cmap`
+/// table and reused by all of the members that refer to it, even across
+/// families.
+#[derive(Default)]
+pub struct SharedTables {
+    charsets: HashMap<(SourceId, u32), Arc<OnceLock<Charset>>>,
+}
+
+#[cfg(feature = "std")]
+impl SharedTables {
+    /// Makes the font reuse the work for tables that it shares with
+    /// previously seen members of the same collection.
+    ///
+    /// `data` is the content of the file containing the font. This does
+    /// nothing for fonts that are not members of a collection.
+    pub fn share(&mut self, font: &mut FontInfo, data: &[u8], font_ref: &FontRef) {
+        if !data.starts_with(b"ttcf") {
+            return;
+        }
+        let Some(offset) = table_offset(font_ref, Tag::new(b"cmap")) else {
+            return;
+        };
+        let charset = self
+            .charsets
+            .entry((font.source().id(), offset))
+            .or_insert_with(|| font.charset_cell().clone())
+            .clone();
+        font.share_charset(charset);
+    }
+}
+
+/// Returns the offset of the table with the given tag from the start of the
+/// file, which is the same for all members of a collection that share the
+/// table.
+pub(crate) fn table_offset(font: &FontRef, tag: Tag) -> Option<u32> {
+    font.table_directory
+        .table_records()
+        .iter()
+        .find(|record| record.tag() == tag)
+        .map(|record| record.offset())
+}
+
 /// Font generated by scanning the file system or a memory buffer.
 pub struct ScannedFont<'a> {
     pub font: FontRef<'a>,
@@ -51,18 +161,25 @@ impl<'a> ScannedFont<'a> {
 
 #[cfg(feature = "std")]
 /// Scans paths and invokes the given function for each font discovered.
+///
+/// Returns a report of the number of fonts found and the files that were
+/// skipped.
+#[cfg_attr(feature = "trace", tracing::instrument(level = "debug", skip_all))]
 pub fn scan_paths(
     paths: impl IntoIterator<Item = impl AsRef<Path>>,
     max_depth: u32,
     mut f: impl FnMut(&ScannedFont),
-) {
+) -> ScanReport {
+    let mut report = ScanReport::default();
     for path in paths {
-        scan_path_impl(path.as_ref(), max_depth, &mut f, 0);
+        scan_path_impl(path.as_ref(), max_depth, &mut f, &mut report, 0);
     }
+    report
 }
 
 /// Scans a memory buffer and invokes the given function for each font
 /// discovered.
+#[cfg_attr(feature = "trace", tracing::instrument(level = "debug", skip_all))]
 pub fn scan_memory<'a>(buf: &'a [u8], mut f: impl FnMut(&ScannedFont<'a>)) {
     #[allow(clippy::unit_arg)]
     scan_memory_impl(buf, Default::default(), &mut f);
@@ -74,11 +191,10 @@ fn scan_collection(
     max_depth: u32,
 ) -> ScannedCollection {
     let mut collection = ScannedCollection::default();
-    let mut families: HashMap<FamilyId, (FamilyName, SmallVec<[FontInfo; 4]>)> = Default::default();
     let mut postscript_name = String::default();
     let mut name_pool = vec![];
     let mut names = vec![];
-    scan_paths(paths, max_depth, |scanned_font| {
+    collection.report = scan_paths(paths, max_depth, |scanned_font| {
         let Some(path) = &scanned_font.path else {
             return;
         };
@@ -105,11 +221,6 @@ fn scan_collection(
         } else {
             return;
         }
-        let data = collection.data_paths.get_or_insert(path);
-        let Some(font) = FontInfo::from_font_ref(&scanned_font.font, data, scanned_font.index)
-        else {
-            return;
-        };
         let [first_name, other_names @ ..] = names.as_slice() else {
             return;
         };
@@ -120,17 +231,17 @@ fn scan_collection(
         collection
             .postscript_names
             .insert(postscript_name.clone(), name.id());
-        families
+        let data = collection.data_paths.get_or_insert(path);
+        collection
+            .families
             .entry(name.id())
-            .or_insert_with(|| (name.clone(), Default::default()))
-            .1
-            .push(font);
+            .or_insert_with(|| ScannedFamily {
+                name: name.clone(),
+                fonts: Default::default(),
+            })
+            .fonts
+            .push((data, scanned_font.index));
     });
-    collection.families.extend(
-        families
-            .drain()
-            .map(|(id, (name, fonts))| (id, FamilyInfo::new(name, fonts))),
-    );
     collection
 }
 
@@ -139,6 +250,7 @@ fn scan_path_impl(
     path: &Path,
     max_depth: u32,
     f: &mut impl FnMut(&ScannedFont),
+    report: &mut ScanReport,
     depth: u32,
 ) -> Option<()> {
     let metadata = path.metadata().ok()?;
@@ -147,16 +259,180 @@ fn scan_path_impl(
             return None;
         }
         for entry in std::fs::read_dir(path).ok()?.filter_map(|entry| entry.ok()) {
-            scan_path_impl(entry.path().as_path(), max_depth, f, depth + 1);
+            scan_path_impl(entry.path().as_path(), max_depth, f, report, depth + 1);
         }
     } else {
-        let file = std::fs::File::open(path).ok()?;
-        let mapped = unsafe { memmap2::Mmap::map(&file) }.ok()?;
-        scan_memory_impl(&mapped, Some(path), f);
+        #[cfg(feature = "trace")]
+        tracing::trace!(path = %path.display(), "scanning file");
+        let mapped = std::fs::File::open(path)
+            .ok()
+            .and_then(|file| unsafe { memmap2::Mmap::map(&file) }.ok());
+        let Some(mapped) = mapped else {
+            report.skip(path, SkipReason::Unreadable);
+            return None;
+        };
+        match scan_memory_impl(&mapped, Some(path), f) {
+            Some(0) => report.skip(path, SkipReason::MissingNames),
+            Some(count) => report.font_count += count as usize,
+            None => {
+                if let Some(reason) = unsupported_format(&mapped) {
+                    report.skip(path, reason);
+                }
+            }
+        }
     }
     Some(())
 }
 
+#[cfg(feature = "std")]
+/// Reason that a file was skipped while scanning for fonts.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum SkipReason {
+    /// The file could not be opened or mapped into memory.
+    Unreadable,
+    /// The file contains Windows bitmap fonts (`.fon` or `.fnt`), which
+    /// are not supported.
+    WindowsBitmap,
+    /// The file contains a PostScript Type 1 font (`.pfb` or `.pfa`), which
+    /// can be loaded if a [`FontConverter`](crate::FontConverter) is set.
+    PostScriptType1,
+    /// The file contains fonts in another format that is not supported,
+    /// such as BDF, PCF or WOFF.
+    UnsupportedFormat,
+    /// The file has the signature of an OpenType font or collection but
+    /// could not be parsed.
+    Malformed,
+    /// The file contains no fonts with a naming table.
+    MissingNames,
+}
+
+#[cfg(feature = "std")]
+/// File that was skipped while scanning for fonts.
+#[derive(Clone, Debug)]
+pub struct SkippedFile {
+    path: Arc<Path>,
+    reason: SkipReason,
+}
+
+#[cfg(feature = "std")]
+impl SkippedFile {
+    /// Returns the path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the reason that the file was skipped.
+    pub fn reason(&self) -> SkipReason {
+        self.reason
+    }
+}
+
+#[cfg(feature = "std")]
+/// Summary of scanning the file system for fonts.
+///
+/// Files that are not fonts are ignored. Font files that can't be used are
+/// listed with the reason they were skipped so that applications can tell
+/// users about fonts that won't be available.
+#[derive(Clone, Default, Debug)]
+pub struct ScanReport {
+    font_count: usize,
+    skipped: Vec<SkippedFile>,
+}
+
+#[cfg(feature = "std")]
+impl ScanReport {
+    /// Returns the number of fonts found.
+    pub fn font_count(&self) -> usize {
+        self.font_count
+    }
+
+    /// Returns the files that were skipped.
+    pub fn skipped(&self) -> &[SkippedFile] {
+        &self.skipped
+    }
+
+    /// Returns the number of skipped files that contain fonts in an
+    /// unsupported format.
+    pub fn unsupported_count(&self) -> usize {
+        self.skipped
+            .iter()
+            .filter(|file| {
+                matches!(
+                    file.reason,
+                    SkipReason::WindowsBitmap
+                        | SkipReason::PostScriptType1
+                        | SkipReason::UnsupportedFormat
+                )
+            })
+            .count()
+    }
+
+    pub(crate) fn append(&mut self, other: ScanReport) {
+        self.font_count += other.font_count;
+        self.skipped.extend(other.skipped);
+    }
+
+    pub(crate) fn add_font(&mut self) {
+        self.font_count += 1;
+    }
+
+    pub(crate) fn skip(&mut self, path: &Path, reason: SkipReason) {
+        // Collections are reported once rather than for each member.
+        if self
+            .skipped
+            .last()
+            .map_or(false, |file| &*file.path == path)
+        {
+            return;
+        }
+        #[cfg(feature = "trace")]
+        tracing::debug!(path = %path.display(), ?reason, "skipped font file");
+        self.skipped.push(SkippedFile {
+            path: path.into(),
+            reason,
+        });
+    }
+}
+
+#[cfg(feature = "std")]
+/// Returns the reason for skipping data that is not an OpenType font or
+/// collection, or `None` if the data is not a font at all.
+pub(crate) fn unsupported_format(data: &[u8]) -> Option<SkipReason> {
+    let read_u32 = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            data.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+    match data.get(..4)? {
+        [0, 1, 0, 0] | b"OTTO" | b"true" | b"typ1" | b"ttcf" => Some(SkipReason::Malformed),
+        b"wOFF" | b"wOF2" | [1, b'f', b'c', b'p'] => Some(SkipReason::UnsupportedFormat),
+        // Binary (PFB) fonts are split into segments that start with 0x80
+        // followed by the segment type, 1 for the ASCII header.
+        [0x80, 1, ..] => Some(SkipReason::PostScriptType1),
+        _ if is_type1_ascii(data) => Some(SkipReason::PostScriptType1),
+        _ if data.starts_with(b"STARTFONT") => Some(SkipReason::UnsupportedFormat),
+        [b'M', b'Z', ..] => {
+            // Font resource files are executables with a 16-bit (NE)
+            // header, whose offset is stored at 0x3C.
+            let header = read_u32(0x3C)? as usize;
+            (data.get(header..header + 2)? == b"NE").then_some(SkipReason::WindowsBitmap)
+        }
+        // Raw font resources start with the version, 2.0 or 3.0, followed
+        // by the size of the file.
+        [0, 2 | 3, ..] => {
+            (read_u32(2)? as usize == data.len()).then_some(SkipReason::WindowsBitmap)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(feature = "std")]
+/// Returns true if the data begins with the header of an ASCII (PFA)
+/// PostScript Type 1 font.
+fn is_type1_ascii(data: &[u8]) -> bool {
+    data.starts_with(b"%!PS-AdobeFont") || data.starts_with(b"%!FontType1")
+}
+
 #[cfg(feature = "std")]
 type ScanMemoryPathType<'a> = Option<&'a Path>;
 
@@ -167,22 +443,23 @@ fn scan_memory_impl<'a>(
     data: &'a [u8],
     path: ScanMemoryPathType<'a>,
     f: &mut impl FnMut(&ScannedFont<'a>),
-) -> Option<()> {
+) -> Option<u32> {
     let font_file = FileRef::new(data).ok()?;
+    let mut count = 0;
     match font_file {
         FileRef::Font(font) => {
-            scan_font(font, path, 0, f);
+            count += scan_font(font, path, 0, f).is_some() as u32;
         }
         FileRef::Collection(collection) => {
             for i in 0..collection.len() {
                 let Ok(font) = collection.get(i) else {
                     continue;
                 };
-                scan_font(font, path, i, f);
+                count += scan_font(font, path, i, f).is_some() as u32;
             }
         }
     }
-    Some(())
+    Some(count)
 }
 
 fn scan_font<'a>(
@@ -202,7 +479,7 @@ fn scan_font<'a>(
     Some(())
 }
 
-fn all_names(
+pub(crate) fn all_names(
     name_table: &name::Name,
     id: NameId,
     pool: &mut Vec<String>,
@@ -271,7 +548,10 @@ fn all_names(
     !result.is_empty()
 }
 
-fn english_or_first<'a>(names: &name::Name<'a>, id: NameId) -> Option<name::NameString<'a>> {
+pub(crate) fn english_or_first<'a>(
+    names: &name::Name<'a>,
+    id: NameId,
+) -> Option<name::NameString<'a>> {
     let mut best_rank = -1;
     let mut best_record = None;
     for (i, record) in names
@@ -1,5 +1,6 @@
 //! Support for working with Unicode scripts.
 
+use super::script_data::{SCRIPTS, SCRIPT_EXTENSIONS, SCRIPT_RANGES};
 use core::fmt;
 
 /// Four byte tag representing a Unicode script.
@@ -7,6 +8,51 @@ use core::fmt;
 pub struct Script(pub [u8; 4]);
 
 impl Script {
+    /// Script for characters that are shared by many scripts.
+    pub const COMMON: Self = Self(*b"Zyyy");
+
+    /// Script for combining marks that inherit the script of the preceding
+    /// character.
+    pub const INHERITED: Self = Self(*b"Zinh");
+
+    /// Script for unassigned characters.
+    pub const UNKNOWN: Self = Self(*b"Zzzz");
+
+    /// Returns the script of the given character.
+    pub fn from_char(ch: char) -> Self {
+        SCRIPTS[script_index(ch as u32)]
+    }
+
+    /// Returns the set of scripts that commonly use the given character.
+    ///
+    /// This is the Unicode `Script_Extensions` property. For most characters,
+    /// it contains only the result of [`Script::from_char`] but characters
+    /// such as punctuation and digits may be shared by a specific set of
+    /// scripts.
+    pub fn extensions(ch: char) -> &'static [Self] {
+        let cp = ch as u32;
+        if let Ok(ix) = SCRIPT_EXTENSIONS.binary_search_by(|entry| {
+            if entry.1 < cp {
+                core::cmp::Ordering::Less
+            } else if entry.0 > cp {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        }) {
+            return SCRIPT_EXTENSIONS[ix].2;
+        }
+        core::slice::from_ref(&SCRIPTS[script_index(cp)])
+    }
+
+    /// Returns true if this script is common, inherited or unknown.
+    ///
+    /// Characters with these scripts should take on the script of the
+    /// surrounding text during itemization.
+    pub fn is_shared(self) -> bool {
+        matches!(self, Self::COMMON | Self::INHERITED | Self::UNKNOWN)
+    }
+
     /// Returns a mapping of scripts to sample text.
     pub fn all_samples() -> &'static [(Script, &'static str)] {
         SCRIPT_SAMPLES
@@ -34,6 +80,13 @@ impl Script {
     }
 }
 
+/// Returns the index in [`SCRIPTS`] of the script for the given codepoint.
+fn script_index(cp: u32) -> usize {
+    // The first range starts at zero so this never underflows.
+    let ix = SCRIPT_RANGES.partition_point(|entry| entry >> 8 <= cp);
+    (SCRIPT_RANGES[ix - 1] & 0xFF) as usize
+}
+
 impl fmt::Debug for Script {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", core::str::from_utf8(&self.0).unwrap_or_default())
@@ -82,6 +135,8 @@ impl From<unicode_script::Script> for Script {
     }
 }
 
+/// Sample text for every script in Unicode 17.0.0 other than the common,
+/// inherited and unknown scripts, sorted by script.
 pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Adlm"), "𞤀𞤁𞤂𞤃𞤄𞤅𞤆𞤇𞤈𞤉𞤊𞤋𞤌𞤍𞤎𞤏"),
     (Script(*b"Aghb"), "𐔰𐔱𐔲𐔳𐔴𐔵𐔶𐔷𐔸𐔹𐔺𐔻𐔼𐔽𐔾𐔿"),
@@ -95,6 +150,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Bass"), "𖫐𖫑𖫒𖫓𖫔𖫕𖫖𖫗𖫘𖫙𖫚𖫛𖫜𖫝𖫞𖫟"),
     (Script(*b"Batk"), "ᯀᯁᯂᯃᯄᯅᯆᯇᯈᯉᯊᯋᯌᯍᯎᯏ"),
     (Script(*b"Beng"), "ঀ\u{981}ংঃঅআইঈউঊঋঌএঐওঔ"),
+    (Script(*b"Berf"), "𖺠𖺡𖺢𖺣𖺤𖺥𖺦𖺧𖺨𖺩𖺪𖺫𖺬𖺭𖺮𖺯"),
     (Script(*b"Bhks"), "𑰀𑰁𑰂𑰃𑰄𑰅𑰆𑰇𑰈𑰊𑰋𑰌𑰍𑰎𑰏𑰐"),
     (Script(*b"Bopo"), "˪˫ㄅㄆㄇㄈㄉㄊㄋㄌㄍㄎㄏㄐㄑㄒ"),
     (Script(*b"Brah"), "𑀀\u{11001}𑀂𑀃𑀄𑀅𑀆𑀇𑀈𑀉𑀊𑀋𑀌𑀍𑀎𑀏"),
@@ -120,6 +176,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Elba"), "𐔀𐔁𐔂𐔃𐔄𐔅𐔆𐔇𐔈𐔉𐔊𐔋𐔌𐔍𐔎𐔏"),
     (Script(*b"Elym"), "𐿠𐿡𐿢𐿣𐿤𐿥𐿦𐿧𐿨𐿩𐿪𐿫𐿬𐿭𐿮𐿯"),
     (Script(*b"Ethi"), "ሀሁሂሃሄህሆሇለሉሊላሌልሎሏ"),
+    (Script(*b"Gara"), "𐵀𐵁𐵂𐵃𐵄𐵅𐵆𐵇𐵈𐵉𐵊𐵋𐵌𐵍𐵎𐵏"),
     (Script(*b"Geor"), "ႠႡႢႣႤႥႦႧႨႩႪႫႬႭႮႯ"),
     (Script(*b"Glag"), "ⰀⰁⰂⰃⰄⰅⰆⰇⰈⰉⰊⰋⰌⰍⰎⰏ"),
     (Script(*b"Gong"), "𑵠𑵡𑵢𑵣𑵤𑵥𑵧𑵨𑵪𑵫𑵬𑵭𑵮𑵯𑵰𑵱"),
@@ -128,6 +185,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Gran"), "\u{11300}\u{11301}𑌂𑌃𑌅𑌆𑌇𑌈𑌉𑌊𑌋𑌌𑌏𑌐𑌓𑌔"),
     (Script(*b"Grek"), "ͰͱͲͳ͵ͶͷͺͻͼͽͿ΄ΆΈΉ"),
     (Script(*b"Gujr"), "\u{a81}\u{a82}ઃઅઆઇઈઉઊઋઌઍએઐઑઓ"),
+    (Script(*b"Gukh"), "𖄀𖄁𖄂𖄃𖄄𖄅𖄆𖄇𖄈𖄉𖄊𖄋𖄌𖄍𖄎𖄏"),
     (Script(*b"Guru"), "\u{a01}\u{a02}ਃਅਆਇਈਉਊਏਐਓਔਕਖਗ"),
     (Script(*b"Hang"), "가"),
     (Script(*b"Hani"), "今"),
@@ -149,6 +207,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Khoj"), "𑈀𑈁𑈂𑈃𑈄𑈅𑈆𑈇𑈈𑈉𑈊𑈋𑈌𑈍𑈎𑈏"),
     (Script(*b"Kits"), "\u{16fe4}𘬀𘬁𘬂𘬃𘬄𘬅𘬆𘬇𘬈𘬉𘬊𘬋𘬌𘬍𘬎"),
     (Script(*b"Knda"), "ಀ\u{c81}ಂಃ಄ಅಆಇಈಉಊಋಌಎಏಐ"),
+    (Script(*b"Krai"), "𖵀𖵁𖵂𖵃𖵄𖵅𖵆𖵇𖵈𖵉𖵊𖵋𖵌𖵍𖵎𖵏"),
     (Script(*b"Kthi"), "\u{11080}\u{11081}𑂂𑂃𑂄𑂅𑂆𑂇𑂈𑂉𑂊𑂋𑂌𑂍𑂎𑂏"),
     (Script(*b"Lana"), "ᨠᨡᨢᨣᨤᨥᨦᨧᨨᨩᨪᨫᨬᨭᨮᨯ"),
     (Script(*b"Laoo"), "ກຂຄຆງຈຉຊຌຍຎຏຐຑຒຓ"),
@@ -185,6 +244,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Nshu"), "𖿡𛅰𛅱𛅲𛅳𛅴𛅵𛅶𛅷𛅸𛅹𛅺𛅻𛅼𛅽𛅾"),
     (Script(*b"Ogam"), "\u{1680}ᚁᚂᚃᚄᚅᚆᚇᚈᚉᚊᚋᚌᚍᚎᚏ"),
     (Script(*b"Olck"), "᱐᱑᱒᱓᱔᱕᱖᱗᱘᱙ᱚᱛᱜᱝᱞᱟ"),
+    (Script(*b"Onao"), "𞗐𞗑𞗒𞗓𞗔𞗕𞗖𞗗𞗘𞗙𞗚𞗛𞗜𞗝𞗞𞗟"),
     (Script(*b"Orkh"), "𐰀𐰁𐰂𐰃𐰄𐰅𐰆𐰇𐰈𐰉𐰊𐰋𐰌𐰍𐰎𐰏"),
     (Script(*b"Orya"), "\u{b01}ଂଃଅଆଇଈଉଊଋଌଏଐଓଔକ"),
     (Script(*b"Osge"), "𐒰𐒱𐒲𐒳𐒴𐒵𐒶𐒷𐒸𐒹𐒺𐒻𐒼𐒽𐒾𐒿"),
@@ -209,6 +269,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Shaw"), "𐑐𐑑𐑒𐑓𐑔𐑕𐑖𐑗𐑘𐑙𐑚𐑛𐑜𐑝𐑞𐑟"),
     (Script(*b"Shrd"), "\u{11180}\u{11181}𑆂𑆃𑆄𑆅𑆆𑆇𑆈𑆉𑆊𑆋𑆌𑆍𑆎𑆏"),
     (Script(*b"Sidd"), "𑖀𑖁𑖂𑖃𑖄𑖅𑖆𑖇𑖈𑖉𑖊𑖋𑖌𑖍𑖎𑖏"),
+    (Script(*b"Sidt"), "𐥀𐥁𐥂𐥃𐥄𐥅𐥆𐥇𐥈𐥉𐥊𐥋𐥌𐥍𐥎𐥏"),
     (Script(*b"Sind"), "𑊰𑊱𑊲𑊳𑊴𑊵𑊶𑊷𑊸𑊹𑊺𑊻𑊼𑊽𑊾𑊿"),
     (Script(*b"Sinh"), "\u{d81}ංඃඅආඇඈඉඊඋඌඍඎඏඐඑ"),
     (Script(*b"Sogd"), "𐼰𐼱𐼲𐼳𐼴𐼵𐼶𐼷𐼸𐼹𐼺𐼻𐼼𐼽𐼾𐼿"),
@@ -216,6 +277,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Sora"), "𑃐𑃑𑃒𑃓𑃔𑃕𑃖𑃗𑃘𑃙𑃚𑃛𑃜𑃝𑃞𑃟"),
     (Script(*b"Soyo"), "𑩐\u{11a51}\u{11a52}\u{11a53}\u{11a54}\u{11a55}\u{11a56}𑩗𑩘\u{11a59}\u{11a5a}\u{11a5b}𑩜𑩝𑩞𑩟"),
     (Script(*b"Sund"), "\u{1b80}\u{1b81}ᮂᮃᮄᮅᮆᮇᮈᮉᮊᮋᮌᮍᮎᮏ"),
+    (Script(*b"Sunu"), "𑯀𑯁𑯂𑯃𑯄𑯅𑯆𑯇𑯈𑯉𑯊𑯋𑯌𑯍𑯎𑯏"),
     (Script(*b"Sylo"), "ꠀꠁ\u{a802}ꠃꠄꠅ\u{a806}ꠇꠈꠉꠊ\u{a80b}ꠌꠍꠎꠏ"),
     (Script(*b"Syrc"), "܀܁܂܃܄܅܆܇܈܉܊܋܌܍\u{70f}ܐ"),
     (Script(*b"Tagb"), "ᝠᝡᝢᝣᝤᝥᝦᝧᝨᝩᝪᝫᝬᝮᝯᝰ"),
@@ -225,6 +287,7 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Taml"), "\u{b82}ஃஅஆஇஈஉஊஎஏஐஒஓஔகங"),
     (Script(*b"Tang"), "𖿠𗀀𗀁𗀂𗀃𗀄𗀅𗀆𗀇𗀈𗀉𗀊𗀋𗀌𗀍𗀎"),
     (Script(*b"Tavt"), "ꪀꪁꪂꪃꪄꪅꪆꪇꪈꪉꪊꪋꪌꪍꪎꪏ"),
+    (Script(*b"Tayo"), "𞛀𞛁𞛂𞛃𞛄𞛅𞛆𞛇𞛈𞛉𞛊𞛋𞛌𞛍𞛎𞛏"),
     (Script(*b"Telu"), "\u{c00}ఁంః\u{c04}అఆఇఈఉఊఋఌఎఏఐ"),
     (Script(*b"Tfng"), "ⴰⴱⴲⴳⴴⴵⴶⴷⴸⴹⴺⴻⴼⴽⴾⴿ"),
     (Script(*b"Tglg"), "ᜀᜁᜂᜃᜄᜅᜆᜇᜈᜉᜊᜋᜌᜍᜎᜏ"),
@@ -233,7 +296,10 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Tibt"), "ༀ༁༂༃༄༅༆༇༈༉༊་༌།༎༏"),
     (Script(*b"Tirh"), "𑒀𑒁𑒂𑒃𑒄𑒅𑒆𑒇𑒈𑒉𑒊𑒋𑒌𑒍𑒎𑒏"),
     (Script(*b"Tnsa"), "𖩰𖩱𖩲𖩳𖩴𖩵𖩶𖩷𖩸𖩹𖩺𖩻𖩼𖩽𖩾𖩿"),
+    (Script(*b"Todr"), "𐗀𐗁𐗂𐗃𐗄𐗅𐗆𐗇𐗈𐗉𐗊𐗋𐗌𐗍𐗎𐗏"),
+    (Script(*b"Tols"), "𑶰𑶱𑶲𑶳𑶴𑶵𑶶𑶷𑶸𑶹𑶺𑶻𑶼𑶽𑶾𑶿"),
     (Script(*b"Toto"), "𞊐𞊑𞊒𞊓𞊔𞊕𞊖𞊗𞊘𞊙𞊚𞊛𞊜𞊝𞊞𞊟"),
+    (Script(*b"Tutg"), "𑎀𑎁𑎂𑎃𑎄𑎅𑎆𑎇𑎈𑎉𑎋𑎎𑎐𑎑𑎒𑎓"),
     (Script(*b"Ugar"), "𐎀𐎁𐎂𐎃𐎄𐎅𐎆𐎇𐎈𐎉𐎊𐎋𐎌𐎍𐎎𐎏"),
     (Script(*b"Vaii"), "ꔀꔁꔂꔃꔄꔅꔆꔇꔈꔉꔊꔋꔌꔍꔎꔏ"),
     (Script(*b"Vith"), "𐕰𐕱𐕲𐕳𐕴𐕵𐕶𐕷𐕸𐕹𐕺𐕼𐕽𐕾𐕿𐖀"),
@@ -245,3 +311,66 @@ pub const SCRIPT_SAMPLES: &[(Script, &str)] = &[
     (Script(*b"Yiii"), "ꀀꀁꀂꀃꀄꀅꀆꀇꀈꀉꀊꀋꀌꀍꀎꀏ"),
     (Script(*b"Zanb"), "𑨀\u{11a01}\u{11a02}\u{11a03}\u{11a04}\u{11a05}\u{11a06}\u{11a07}\u{11a08}\u{11a09}\u{11a0a}𑨋𑨌𑨍𑨎𑨏"),    
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_of_char() {
+        assert_eq!(Script::from_char('A'), Script(*b"Latn"));
+        assert_eq!(Script::from_char('\u{E9}'), Script(*b"Latn"));
+        assert_eq!(Script::from_char('\u{4E2D}'), Script(*b"Hani"));
+        assert_eq!(Script::from_char('\u{20000}'), Script(*b"Hani"));
+        assert_eq!(Script::from_char('\u{915}'), Script(*b"Deva"));
+        assert_eq!(Script::from_char(' '), Script::COMMON);
+        assert_eq!(Script::from_char('\u{301}'), Script::INHERITED);
+    }
+
+    #[test]
+    fn unassigned() {
+        for ch in ['\u{378}', '\u{E0000}', '\u{10FFFF}'] {
+            assert_eq!(Script::from_char(ch), Script::UNKNOWN);
+            assert_eq!(Script::extensions(ch), [Script::UNKNOWN]);
+        }
+    }
+
+    #[test]
+    fn extensions() {
+        assert_eq!(Script::extensions('A'), [Script(*b"Latn")]);
+        assert_eq!(Script::extensions('!'), [Script::COMMON]);
+        // DEVANAGARI DANDA is common but shared by a specific set of
+        // scripts.
+        let danda = '\u{964}';
+        assert_eq!(Script::from_char(danda), Script::COMMON);
+        let scripts = Script::extensions(danda);
+        assert!(scripts.contains(&Script(*b"Deva")));
+        assert!(scripts.contains(&Script(*b"Beng")));
+        assert!(!scripts.contains(&Script::COMMON));
+        assert!(!scripts.contains(&Script(*b"Latn")));
+    }
+
+    #[test]
+    fn range_boundaries() {
+        assert_eq!(SCRIPT_RANGES[0] >> 8, 0);
+        for pair in SCRIPT_RANGES.windows(2) {
+            let (prev, start) = (pair[0], pair[1]);
+            assert!(prev >> 8 < start >> 8);
+            // Adjacent ranges always have different scripts.
+            assert_ne!(prev & 0xFF, start & 0xFF);
+            let cp = start >> 8;
+            assert_eq!(script_index(cp), (start & 0xFF) as usize);
+            assert_eq!(script_index(cp - 1), (prev & 0xFF) as usize);
+        }
+        let last = SCRIPT_RANGES[SCRIPT_RANGES.len() - 1];
+        assert_eq!(script_index(0x10FFFF), (last & 0xFF) as usize);
+    }
+
+    #[test]
+    fn extension_ranges_sorted() {
+        for pair in SCRIPT_EXTENSIONS.windows(2) {
+            assert!(pair[0].0 <= pair[0].1);
+            assert!(pair[0].1 < pair[1].0);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+//! Unicode script property data.
+//!
+//! Generated from the Unicode 17.0.0 `Scripts.txt` and `ScriptExtensions.txt`
+//! data files by `cargo run -p xtask -- script-data <ucd-dir>`. Do not edit
+//! by hand.
+
+use super::script::Script;
+
+/// Scripts referenced by index from [`SCRIPT_RANGES`].
+#[rustfmt::skip]
+pub static SCRIPTS: [Script; 175] = [
+    Script(*b"Adlm"), Script(*b"Aghb"), Script(*b"Ahom"), Script(*b"Arab"), Script(*b"Armi"),
+    Script(*b"Armn"), Script(*b"Avst"), Script(*b"Bali"), Script(*b"Bamu"), Script(*b"Bass"),
+    Script(*b"Batk"), Script(*b"Beng"), Script(*b"Berf"), Script(*b"Bhks"), Script(*b"Bopo"),
+    Script(*b"Brah"), Script(*b"Brai"), Script(*b"Bugi"), Script(*b"Buhd"), Script(*b"Cakm"),
+    Script(*b"Cans"), Script(*b"Cari"), Script(*b"Cham"), Script(*b"Cher"), Script(*b"Chrs"),
+    Script(*b"Copt"), Script(*b"Cpmn"), Script(*b"Cprt"), Script(*b"Cyrl"), Script(*b"Deva"),
+    Script(*b"Diak"), Script(*b"Dogr"), Script(*b"Dsrt"), Script(*b"Dupl"), Script(*b"Egyp"),
+    Script(*b"Elba"), Script(*b"Elym"), Script(*b"Ethi"), Script(*b"Gara"), Script(*b"Geor"),
+    Script(*b"Glag"), Script(*b"Gong"), Script(*b"Gonm"), Script(*b"Goth"), Script(*b"Gran"),
+    Script(*b"Grek"), Script(*b"Gujr"), Script(*b"Gukh"), Script(*b"Guru"), Script(*b"Hang"),
+    Script(*b"Hani"), Script(*b"Hano"), Script(*b"Hatr"), Script(*b"Hebr"), Script(*b"Hira"),
+    Script(*b"Hluw"), Script(*b"Hmng"), Script(*b"Hmnp"), Script(*b"Hung"), Script(*b"Ital"),
+    Script(*b"Java"), Script(*b"Kali"), Script(*b"Kana"), Script(*b"Kawi"), Script(*b"Khar"),
+    Script(*b"Khmr"), Script(*b"Khoj"), Script(*b"Kits"), Script(*b"Knda"), Script(*b"Krai"),
+    Script(*b"Kthi"), Script(*b"Lana"), Script(*b"Laoo"), Script(*b"Latn"), Script(*b"Lepc"),
+    Script(*b"Limb"), Script(*b"Lina"), Script(*b"Linb"), Script(*b"Lisu"), Script(*b"Lyci"),
+    Script(*b"Lydi"), Script(*b"Mahj"), Script(*b"Maka"), Script(*b"Mand"), Script(*b"Mani"),
+    Script(*b"Marc"), Script(*b"Medf"), Script(*b"Mend"), Script(*b"Merc"), Script(*b"Mero"),
+    Script(*b"Mlym"), Script(*b"Modi"), Script(*b"Mong"), Script(*b"Mroo"), Script(*b"Mtei"),
+    Script(*b"Mult"), Script(*b"Mymr"), Script(*b"Nagm"), Script(*b"Nand"), Script(*b"Narb"),
+    Script(*b"Nbat"), Script(*b"Newa"), Script(*b"Nkoo"), Script(*b"Nshu"), Script(*b"Ogam"),
+    Script(*b"Olck"), Script(*b"Onao"), Script(*b"Orkh"), Script(*b"Orya"), Script(*b"Osge"),
+    Script(*b"Osma"), Script(*b"Ougr"), Script(*b"Palm"), Script(*b"Pauc"), Script(*b"Perm"),
+    Script(*b"Phag"), Script(*b"Phli"), Script(*b"Phlp"), Script(*b"Phnx"), Script(*b"Plrd"),
+    Script(*b"Prti"), Script(*b"Rjng"), Script(*b"Rohg"), Script(*b"Runr"), Script(*b"Samr"),
+    Script(*b"Sarb"), Script(*b"Saur"), Script(*b"Sgnw"), Script(*b"Shaw"), Script(*b"Shrd"),
+    Script(*b"Sidd"), Script(*b"Sidt"), Script(*b"Sind"), Script(*b"Sinh"), Script(*b"Sogd"),
+    Script(*b"Sogo"), Script(*b"Sora"), Script(*b"Soyo"), Script(*b"Sund"), Script(*b"Sunu"),
+    Script(*b"Sylo"), Script(*b"Syrc"), Script(*b"Tagb"), Script(*b"Takr"), Script(*b"Tale"),
+    Script(*b"Talu"), Script(*b"Taml"), Script(*b"Tang"), Script(*b"Tavt"), Script(*b"Tayo"),
+    Script(*b"Telu"), Script(*b"Tfng"), Script(*b"Tglg"), Script(*b"Thaa"), Script(*b"Thai"),
+    Script(*b"Tibt"), Script(*b"Tirh"), Script(*b"Tnsa"), Script(*b"Todr"), Script(*b"Tols"),
+    Script(*b"Toto"), Script(*b"Tutg"), Script(*b"Ugar"), Script(*b"Vaii"), Script(*b"Vith"),
+    Script(*b"Wara"), Script(*b"Wcho"), Script(*b"Xpeo"), Script(*b"Xsux"), Script(*b"Yezi"),
+    Script(*b"Yiii"), Script(*b"Zanb"), Script(*b"Zinh"), Script(*b"Zyyy"), Script(*b"Zzzz"),
+];
+
+/// Contiguous ranges of codepoints with the same script, sorted by
+/// codepoint.
+///
+/// Each entry holds the first codepoint of the range in the upper 24 bits
+/// and an index into [`SCRIPTS`] in the lower 8 bits. A range ends where the
+/// next one begins.
+#[rustfmt::skip]
+pub static SCRIPT_RANGES: [u32; 1717] = [
+    0x00000AD, 0x0004149, 0x0005BAD, 0x0006149, 0x0007BAD, 0x000AA49, 0x000ABAD, 0x000BA49,
+    0x000BBAD, 0x000C049, 0x000D7AD, 0x000D849, 0x000F7AD, 0x000F849, 0x002B9AD, 0x002E049,
+    0x002E5AD, 0x002EA0E, 0x002ECAD, 0x00300AC, 0x003702D, 0x00374AD, 0x003752D, 0x00378AE,
+    0x0037A2D, 0x0037EAD, 0x0037F2D, 0x00380AE, 0x003842D, 0x00385AD, 0x003862D, 0x00387AD,
+    0x003882D, 0x0038BAE, 0x0038C2D, 0x0038DAE, 0x0038E2D, 0x003A2AE, 0x003A32D, 0x003E219,
+    0x003F02D, 0x004001C, 0x00485AC, 0x004871C, 0x00530AE, 0x0053105, 0x00557AE, 0x0055905,
+    0x0058BAE, 0x0058D05, 0x00590AE, 0x0059135, 0x005C8AE, 0x005D035, 0x005EBAE, 0x005EF35,
+    0x005F5AE, 0x0060003, 0x00605AD, 0x0060603, 0x0060CAD, 0x0060D03, 0x0061BAD, 0x0061C03,
+    0x0061FAD, 0x0062003, 0x00640AD, 0x0064103, 0x0064BAC, 0x0065603, 0x00670AC, 0x0067103,
+    0x006DDAD, 0x006DE03, 0x007008D, 0x0070EAE, 0x0070F8D, 0x0074BAE, 0x0074D8D, 0x0075003,
+    0x0078099, 0x007B2AE, 0x007C066, 0x007FBAE, 0x007FD66, 0x008007C, 0x0082EAE, 0x008307C,
+    0x0083FAE, 0x0084053, 0x0085CAE, 0x0085E53, 0x0085FAE, 0x008608D, 0x0086BAE, 0x0087003,
+    0x00892AE, 0x0089703, 0x008E2AD, 0x008E303, 0x009001D, 0x00951AC, 0x009551D, 0x00964AD,
+    0x009661D, 0x009800B, 0x00984AE, 0x009850B, 0x0098DAE, 0x0098F0B, 0x00991AE, 0x009930B,
+    0x009A9AE, 0x009AA0B, 0x009B1AE, 0x009B20B, 0x009B3AE, 0x009B60B, 0x009BAAE, 0x009BC0B,
+    0x009C5AE, 0x009C70B, 0x009C9AE, 0x009CB0B, 0x009CFAE, 0x009D70B, 0x009D8AE, 0x009DC0B,
+    0x009DEAE, 0x009DF0B, 0x009E4AE, 0x009E60B, 0x009FFAE, 0x00A0130, 0x00A04AE, 0x00A0530,
+    0x00A0BAE, 0x00A0F30, 0x00A11AE, 0x00A1330, 0x00A29AE, 0x00A2A30, 0x00A31AE, 0x00A3230,
+    0x00A34AE, 0x00A3530, 0x00A37AE, 0x00A3830, 0x00A3AAE, 0x00A3C30, 0x00A3DAE, 0x00A3E30,
+    0x00A43AE, 0x00A4730, 0x00A49AE, 0x00A4B30, 0x00A4EAE, 0x00A5130, 0x00A52AE, 0x00A5930,
+    0x00A5DAE, 0x00A5E30, 0x00A5FAE, 0x00A6630, 0x00A77AE, 0x00A812E, 0x00A84AE, 0x00A852E,
+    0x00A8EAE, 0x00A8F2E, 0x00A92AE, 0x00A932E, 0x00AA9AE, 0x00AAA2E, 0x00AB1AE, 0x00AB22E,
+    0x00AB4AE, 0x00AB52E, 0x00ABAAE, 0x00ABC2E, 0x00AC6AE, 0x00AC72E, 0x00ACAAE, 0x00ACB2E,
+    0x00ACEAE, 0x00AD02E, 0x00AD1AE, 0x00AE02E, 0x00AE4AE, 0x00AE62E, 0x00AF2AE, 0x00AF92E,
+    0x00B00AE, 0x00B016C, 0x00B04AE, 0x00B056C, 0x00B0DAE, 0x00B0F6C, 0x00B11AE, 0x00B136C,
+    0x00B29AE, 0x00B2A6C, 0x00B31AE, 0x00B326C, 0x00B34AE, 0x00B356C, 0x00B3AAE, 0x00B3C6C,
+    0x00B45AE, 0x00B476C, 0x00B49AE, 0x00B4B6C, 0x00B4EAE, 0x00B556C, 0x00B58AE, 0x00B5C6C,
+    0x00B5EAE, 0x00B5F6C, 0x00B64AE, 0x00B666C, 0x00B78AE, 0x00B8292, 0x00B84AE, 0x00B8592,
+    0x00B8BAE, 0x00B8E92, 0x00B91AE, 0x00B9292, 0x00B96AE, 0x00B9992, 0x00B9BAE, 0x00B9C92,
+    0x00B9DAE, 0x00B9E92, 0x00BA0AE, 0x00BA392, 0x00BA5AE, 0x00BA892, 0x00BABAE, 0x00BAE92,
+    0x00BBAAE, 0x00BBE92, 0x00BC3AE, 0x00BC692, 0x00BC9AE, 0x00BCA92, 0x00BCEAE, 0x00BD092,
+    0x00BD1AE, 0x00BD792, 0x00BD8AE, 0x00BE692, 0x00BFBAE, 0x00C0096, 0x00C0DAE, 0x00C0E96,
+    0x00C11AE, 0x00C1296, 0x00C29AE, 0x00C2A96, 0x00C3AAE, 0x00C3C96, 0x00C45AE, 0x00C4696,
+    0x00C49AE, 0x00C4A96, 0x00C4EAE, 0x00C5596, 0x00C57AE, 0x00C5896, 0x00C5BAE, 0x00C5C96,
+    0x00C5EAE, 0x00C6096, 0x00C64AE, 0x00C6696, 0x00C70AE, 0x00C7796, 0x00C8044, 0x00C8DAE,
+    0x00C8E44, 0x00C91AE, 0x00C9244, 0x00CA9AE, 0x00CAA44, 0x00CB4AE, 0x00CB544, 0x00CBAAE,
+    0x00CBC44, 0x00CC5AE, 0x00CC644, 0x00CC9AE, 0x00CCA44, 0x00CCEAE, 0x00CD544, 0x00CD7AE,
+    0x00CDC44, 0x00CDFAE, 0x00CE044, 0x00CE4AE, 0x00CE644, 0x00CF0AE, 0x00CF144, 0x00CF4AE,
+    0x00D005A, 0x00D0DAE, 0x00D0E5A, 0x00D11AE, 0x00D125A, 0x00D45AE, 0x00D465A, 0x00D49AE,
+    0x00D4A5A, 0x00D50AE, 0x00D545A, 0x00D64AE, 0x00D665A, 0x00D80AE, 0x00D8185, 0x00D84AE,
+    0x00D8585, 0x00D97AE, 0x00D9A85, 0x00DB2AE, 0x00DB385, 0x00DBCAE, 0x00DBD85, 0x00DBEAE,
+    0x00DC085, 0x00DC7AE, 0x00DCA85, 0x00DCBAE, 0x00DCF85, 0x00DD5AE, 0x00DD685, 0x00DD7AE,
+    0x00DD885, 0x00DE0AE, 0x00DE685, 0x00DF0AE, 0x00DF285, 0x00DF5AE, 0x00E019A, 0x00E3BAE,
+    0x00E3FAD, 0x00E409A, 0x00E5CAE, 0x00E8148, 0x00E83AE, 0x00E8448, 0x00E85AE, 0x00E8648,
+    0x00E8BAE, 0x00E8C48, 0x00EA4AE, 0x00EA548, 0x00EA6AE, 0x00EA748, 0x00EBEAE, 0x00EC048,
+    0x00EC5AE, 0x00EC648, 0x00EC7AE, 0x00EC848, 0x00ECFAE, 0x00ED048, 0x00EDAAE, 0x00EDC48,
+    0x00EE0AE, 0x00F009B, 0x00F48AE, 0x00F499B, 0x00F6DAE, 0x00F719B, 0x00F98AE, 0x00F999B,
+    0x00FBDAE, 0x00FBE9B, 0x00FCDAE, 0x00FCE9B, 0x00FD5AD, 0x00FD99B, 0x00FDBAE, 0x0100060,
+    0x010A027, 0x010C6AE, 0x010C727, 0x010C8AE, 0x010CD27, 0x010CEAE, 0x010D027, 0x010FBAD,
+    0x010FC27, 0x0110031, 0x0120025, 0x01249AE, 0x0124A25, 0x0124EAE, 0x0125025, 0x01257AE,
+    0x0125825, 0x01259AE, 0x0125A25, 0x0125EAE, 0x0126025, 0x01289AE, 0x0128A25, 0x0128EAE,
+    0x0129025, 0x012B1AE, 0x012B225, 0x012B6AE, 0x012B825, 0x012BFAE, 0x012C025, 0x012C1AE,
+    0x012C225, 0x012C6AE, 0x012C825, 0x012D7AE, 0x012D825, 0x01311AE, 0x0131225, 0x01316AE,
+    0x0131825, 0x0135BAE, 0x0135D25, 0x0137DAE, 0x0138025, 0x0139AAE, 0x013A017, 0x013F6AE,
+    0x013F817, 0x013FEAE, 0x0140014, 0x0168068, 0x0169DAE, 0x016A07B, 0x016EBAD, 0x016EE7B,
+    0x016F9AE, 0x0170098, 0x01716AE, 0x0171F98, 0x0172033, 0x01735AD, 0x01737AE, 0x0174012,
+    0x01754AE, 0x017608E, 0x0176DAE, 0x0176E8E, 0x01771AE, 0x017728E, 0x01774AE, 0x0178041,
+    0x017DEAE, 0x017E041, 0x017EAAE, 0x017F041, 0x017FAAE, 0x018005C, 0x01802AD, 0x018045C,
+    0x01805AD, 0x018065C, 0x0181AAE, 0x018205C, 0x01879AE, 0x018805C, 0x018ABAE, 0x018B014,
+    0x018F6AE, 0x019004B, 0x0191FAE, 0x019204B, 0x0192CAE, 0x019304B, 0x0193CAE, 0x019404B,
+    0x01941AE, 0x019444B, 0x0195090, 0x0196EAE, 0x0197090, 0x01975AE, 0x0198091, 0x019ACAE,
+    0x019B091, 0x019CAAE, 0x019D091, 0x019DBAE, 0x019DE91, 0x019E041, 0x01A0011, 0x01A1CAE,
+    0x01A1E11, 0x01A2047, 0x01A5FAE, 0x01A6047, 0x01A7DAE, 0x01A7F47, 0x01A8AAE, 0x01A9047,
+    0x01A9AAE, 0x01AA047, 0x01AAEAE, 0x01AB0AC, 0x01ADEAE, 0x01AE0AC, 0x01AECAE, 0x01B0007,
+    0x01B4DAE, 0x01B4E07, 0x01B808A, 0x01BC00A, 0x01BF4AE, 0x01BFC0A, 0x01C004A, 0x01C38AE,
+    0x01C3B4A, 0x01C4AAE, 0x01C4D4A, 0x01C5069, 0x01C801C, 0x01C8BAE, 0x01C9027, 0x01CBBAE,
+    0x01CBD27, 0x01CC08A, 0x01CC8AE, 0x01CD0AC, 0x01CD3AD, 0x01CD4AC, 0x01CE1AD, 0x01CE2AC,
+    0x01CE9AD, 0x01CEDAC, 0x01CEEAD, 0x01CF4AC, 0x01CF5AD, 0x01CF8AC, 0x01CFAAD, 0x01CFBAE,
+    0x01D0049, 0x01D262D, 0x01D2B1C, 0x01D2C49, 0x01D5D2D, 0x01D6249, 0x01D662D, 0x01D6B49,
+    0x01D781C, 0x01D7949, 0x01DBF2D, 0x01DC0AC, 0x01E0049, 0x01F002D, 0x01F16AE, 0x01F182D,
+    0x01F1EAE, 0x01F202D, 0x01F46AE, 0x01F482D, 0x01F4EAE, 0x01F502D, 0x01F58AE, 0x01F592D,
+    0x01F5AAE, 0x01F5B2D, 0x01F5CAE, 0x01F5D2D, 0x01F5EAE, 0x01F5F2D, 0x01F7EAE, 0x01F802D,
+    0x01FB5AE, 0x01FB62D, 0x01FC5AE, 0x01FC62D, 0x01FD4AE, 0x01FD62D, 0x01FDCAE, 0x01FDD2D,
+    0x01FF0AE, 0x01FF22D, 0x01FF5AE, 0x01FF62D, 0x01FFFAE, 0x02000AD, 0x0200CAC, 0x0200EAD,
+    0x02065AE, 0x02066AD, 0x0207149, 0x02072AE, 0x02074AD, 0x0207F49, 0x02080AD, 0x0208FAE,
+    0x0209049, 0x0209DAE, 0x020A0AD, 0x020C2AE, 0x020D0AC, 0x020F1AE, 0x02100AD, 0x021262D,
+    0x02127AD, 0x0212A49, 0x0212CAD, 0x0213249, 0x02133AD, 0x0214E49, 0x0214FAD, 0x0216049,
+    0x02189AD, 0x0218CAE, 0x02190AD, 0x0242AAE, 0x02440AD, 0x0244BAE, 0x02460AD, 0x0280010,
+    0x02900AD, 0x02B74AE, 0x02B76AD, 0x02C0028, 0x02C6049, 0x02C8019, 0x02CF4AE, 0x02CF919,
+    0x02D0027, 0x02D26AE, 0x02D2727, 0x02D28AE, 0x02D2D27, 0x02D2EAE, 0x02D3097, 0x02D68AE,
+    0x02D6F97, 0x02D71AE, 0x02D7F97, 0x02D8025, 0x02D97AE, 0x02DA025, 0x02DA7AE, 0x02DA825,
+    0x02DAFAE, 0x02DB025, 0x02DB7AE, 0x02DB825, 0x02DBFAE, 0x02DC025, 0x02DC7AE, 0x02DC825,
+    0x02DCFAE, 0x02DD025, 0x02DD7AE, 0x02DD825, 0x02DDFAE, 0x02DE01C, 0x02E00AD, 0x02E5EAE,
+    0x02E8032, 0x02E9AAE, 0x02E9B32, 0x02EF4AE, 0x02F0032, 0x02FD6AE, 0x02FF0AD, 0x0300532,
+    0x03006AD, 0x0300732, 0x03008AD, 0x0302132, 0x0302AAC, 0x0302E31, 0x03030AD, 0x0303832,
+    0x0303CAD, 0x03040AE, 0x0304136, 0x03097AE, 0x03099AC, 0x0309BAD, 0x0309D36, 0x030A0AD,
+    0x030A13E, 0x030FBAD, 0x030FD3E, 0x03100AE, 0x031050E, 0x03130AE, 0x0313131, 0x0318FAE,
+    0x03190AD, 0x031A00E, 0x031C0AD, 0x031E6AE, 0x031EFAD, 0x031F03E, 0x0320031, 0x0321FAE,
+    0x03220AD, 0x0326031, 0x0327FAD, 0x032D03E, 0x032FFAD, 0x033003E, 0x03358AD, 0x0340032,
+    0x04DC0AD, 0x04E0032, 0x0A000AA, 0x0A48DAE, 0x0A490AA, 0x0A4C7AE, 0x0A4D04E, 0x0A500A3,
+    0x0A62CAE, 0x0A6401C, 0x0A6A008, 0x0A6F8AE, 0x0A700AD, 0x0A72249, 0x0A788AD, 0x0A78B49,
+    0x0A7DDAE, 0x0A7F149, 0x0A8008C, 0x0A82DAE, 0x0A830AD, 0x0A83AAE, 0x0A84073, 0x0A878AE,
+    0x0A8807E, 0x0A8C6AE, 0x0A8CE7E, 0x0A8DAAE, 0x0A8E01D, 0x0A9003D, 0x0A92EAD, 0x0A92F3D,
+    0x0A93079, 0x0A954AE, 0x0A95F79, 0x0A96031, 0x0A97DAE, 0x0A9803C, 0x0A9CEAE, 0x0A9CFAD,
+    0x0A9D03C, 0x0A9DAAE, 0x0A9DE3C, 0x0A9E060, 0x0A9FFAE, 0x0AA0016, 0x0AA37AE, 0x0AA4016,
+    0x0AA4EAE, 0x0AA5016, 0x0AA5AAE, 0x0AA5C16, 0x0AA6060, 0x0AA8094, 0x0AAC3AE, 0x0AADB94,
+    0x0AAE05E, 0x0AAF7AE, 0x0AB0125, 0x0AB07AE, 0x0AB0925, 0x0AB0FAE, 0x0AB1125, 0x0AB17AE,
+    0x0AB2025, 0x0AB27AE, 0x0AB2825, 0x0AB2FAE, 0x0AB3049, 0x0AB5BAD, 0x0AB5C49, 0x0AB652D,
+    0x0AB6649, 0x0AB6AAD, 0x0AB6CAE, 0x0AB7017, 0x0ABC05E, 0x0ABEEAE, 0x0ABF05E, 0x0ABFAAE,
+    0x0AC0031, 0x0D7A4AE, 0x0D7B031, 0x0D7C7AE, 0x0D7CB31, 0x0D7FCAE, 0x0F90032, 0x0FA6EAE,
+    0x0FA7032, 0x0FADAAE, 0x0FB0049, 0x0FB07AE, 0x0FB1305, 0x0FB18AE, 0x0FB1D35, 0x0FB37AE,
+    0x0FB3835, 0x0FB3DAE, 0x0FB3E35, 0x0FB3FAE, 0x0FB4035, 0x0FB42AE, 0x0FB4335, 0x0FB45AE,
+    0x0FB4635, 0x0FB5003, 0x0FD3EAD, 0x0FD4003, 0x0FDD0AE, 0x0FDF003, 0x0FE00AC, 0x0FE10AD,
+    0x0FE1AAE, 0x0FE20AC, 0x0FE2E1C, 0x0FE30AD, 0x0FE53AE, 0x0FE54AD, 0x0FE67AE, 0x0FE68AD,
+    0x0FE6CAE, 0x0FE7003, 0x0FE75AE, 0x0FE7603, 0x0FEFDAE, 0x0FEFFAD, 0x0FF00AE, 0x0FF01AD,
+    0x0FF2149, 0x0FF3BAD, 0x0FF4149, 0x0FF5BAD, 0x0FF663E, 0x0FF70AD, 0x0FF713E, 0x0FF9EAD,
+    0x0FFA031, 0x0FFBFAE, 0x0FFC231, 0x0FFC8AE, 0x0FFCA31, 0x0FFD0AE, 0x0FFD231, 0x0FFD8AE,
+    0x0FFDA31, 0x0FFDDAE, 0x0FFE0AD, 0x0FFE7AE, 0x0FFE8AD, 0x0FFEFAE, 0x0FFF9AD, 0x0FFFEAE,
+    0x100004D, 0x1000CAE, 0x1000D4D, 0x10027AE, 0x100284D, 0x1003BAE, 0x1003C4D, 0x1003EAE,
+    0x1003F4D, 0x1004EAE, 0x100504D, 0x1005EAE, 0x100804D, 0x100FBAE, 0x10100AD, 0x10103AE,
+    0x10107AD, 0x10134AE, 0x10137AD, 0x101402D, 0x1018FAE, 0x10190AD, 0x1019DAE, 0x101A02D,
+    0x101A1AE, 0x101D0AD, 0x101FDAC, 0x101FEAE, 0x102804F, 0x1029DAE, 0x102A015, 0x102D1AE,
+    0x102E0AC, 0x102E1AD, 0x102FCAE, 0x103003B, 0x10324AE, 0x1032D3B, 0x103302B, 0x1034BAE,
+    0x1035072, 0x1037BAE, 0x10380A2, 0x1039EAE, 0x1039FA2, 0x103A0A7, 0x103C4AE, 0x103C8A7,
+    0x103D6AE, 0x1040020, 0x1045080, 0x104806E, 0x1049EAE, 0x104A06E, 0x104AAAE, 0x104B06D,
+    0x104D4AE, 0x104D86D, 0x104FCAE, 0x1050023, 0x10528AE, 0x1053001, 0x10564AE, 0x1056F01,
+    0x10570A4, 0x1057BAE, 0x1057CA4, 0x1058BAE, 0x1058CA4, 0x10593AE, 0x10594A4, 0x10596AE,
+    0x10597A4, 0x105A2AE, 0x105A3A4, 0x105B2AE, 0x105B3A4, 0x105BAAE, 0x105BBA4, 0x105BDAE,
+    0x105C09E, 0x105F4AE, 0x106004C, 0x10737AE, 0x107404C, 0x10756AE, 0x107604C, 0x10768AE,
+    0x1078049, 0x10786AE, 0x1078749, 0x107B1AE, 0x107B249, 0x107BBAE, 0x108001B, 0x10806AE,
+    0x108081B, 0x10809AE, 0x1080A1B, 0x10836AE, 0x108371B, 0x10839AE, 0x1083C1B, 0x1083DAE,
+    0x1083F1B, 0x1084004, 0x10856AE, 0x1085704, 0x1086070, 0x1088064, 0x1089FAE, 0x108A764,
+    0x108B0AE, 0x108E034, 0x108F3AE, 0x108F434, 0x108F6AE, 0x108FB34, 0x1090076, 0x1091CAE,
+    0x1091F76, 0x1092050, 0x1093AAE, 0x1093F50, 0x1094083, 0x1095AAE, 0x1098059, 0x109A058,
+    0x109B8AE, 0x109BC58, 0x109D0AE, 0x109D258, 0x10A0040, 0x10A04AE, 0x10A0540, 0x10A07AE,
+    0x10A0C40, 0x10A14AE, 0x10A1540, 0x10A18AE, 0x10A1940, 0x10A36AE, 0x10A3840, 0x10A3BAE,
+    0x10A3F40, 0x10A49AE, 0x10A5040, 0x10A59AE, 0x10A607D, 0x10A8063, 0x10AA0AE, 0x10AC054,
+    0x10AE7AE, 0x10AEB54, 0x10AF7AE, 0x10B0006, 0x10B36AE, 0x10B3906, 0x10B4078, 0x10B56AE,
+    0x10B5878, 0x10B6074, 0x10B73AE, 0x10B7874, 0x10B8075, 0x10B92AE, 0x10B9975, 0x10B9DAE,
+    0x10BA975, 0x10BB0AE, 0x10C006B, 0x10C49AE, 0x10C803A, 0x10CB3AE, 0x10CC03A, 0x10CF3AE,
+    0x10CFA3A, 0x10D007A, 0x10D28AE, 0x10D307A, 0x10D3AAE, 0x10D4026, 0x10D66AE, 0x10D6926,
+    0x10D86AE, 0x10D8E26, 0x10D90AE, 0x10E6003, 0x10E7FAE, 0x10E80A9, 0x10EAAAE, 0x10EABA9,
+    0x10EAEAE, 0x10EB0A9, 0x10EB2AE, 0x10EC203, 0x10EC8AE, 0x10ED003, 0x10ED9AE, 0x10EFA03,
+    0x10F0087, 0x10F28AE, 0x10F3086, 0x10F5AAE, 0x10F706F, 0x10F8AAE, 0x10FB018, 0x10FCCAE,
+    0x10FE024, 0x10FF7AE, 0x110000F, 0x1104EAE, 0x110520F, 0x11076AE, 0x1107F0F, 0x1108046,
+    0x110C3AE, 0x110CD46, 0x110CEAE, 0x110D088, 0x110E9AE, 0x110F088, 0x110FAAE, 0x1110013,
+    0x11135AE, 0x1113613, 0x11148AE, 0x1115051, 0x11177AE, 0x1118081, 0x111E0AE, 0x111E185,
+    0x111F5AE, 0x1120042, 0x11212AE, 0x1121342, 0x11242AE, 0x112805F, 0x11287AE, 0x112885F,
+    0x11289AE, 0x1128A5F, 0x1128EAE, 0x1128F5F, 0x1129EAE, 0x1129F5F, 0x112AAAE, 0x112B084,
+    0x112EBAE, 0x112F084, 0x112FAAE, 0x113002C, 0x11304AE, 0x113052C, 0x1130DAE, 0x1130F2C,
+    0x11311AE, 0x113132C, 0x11329AE, 0x1132A2C, 0x11331AE, 0x113322C, 0x11334AE, 0x113352C,
+    0x1133AAE, 0x1133BAC, 0x1133C2C, 0x11345AE, 0x113472C, 0x11349AE, 0x1134B2C, 0x1134EAE,
+    0x113502C, 0x11351AE, 0x113572C, 0x11358AE, 0x1135D2C, 0x11364AE, 0x113662C, 0x1136DAE,
+    0x113702C, 0x11375AE, 0x11380A1, 0x1138AAE, 0x1138BA1, 0x1138CAE, 0x1138EA1, 0x1138FAE,
+    0x11390A1, 0x113B6AE, 0x113B7A1, 0x113C1AE, 0x113C2A1, 0x113C3AE, 0x113C5A1, 0x113C6AE,
+    0x113C7A1, 0x113CBAE, 0x113CCA1, 0x113D6AE, 0x113D7A1, 0x113D9AE, 0x113E1A1, 0x113E3AE,
+    0x1140065, 0x1145CAE, 0x1145D65, 0x11462AE, 0x114809C, 0x114C8AE, 0x114D09C, 0x114DAAE,
+    0x1158082, 0x115B6AE, 0x115B882, 0x115DEAE, 0x116005B, 0x11645AE, 0x116505B, 0x1165AAE,
+    0x116605C, 0x1166DAE, 0x116808F, 0x116BAAE, 0x116C08F, 0x116CAAE, 0x116D060, 0x116E4AE,
+    0x1170002, 0x1171BAE, 0x1171D02, 0x1172CAE, 0x1173002, 0x11747AE, 0x118001F, 0x1183CAE,
+    0x118A0A5, 0x118F3AE, 0x118FFA5, 0x119001E, 0x11907AE, 0x119091E, 0x1190AAE, 0x1190C1E,
+    0x11914AE, 0x119151E, 0x11917AE, 0x119181E, 0x11936AE, 0x119371E, 0x11939AE, 0x1193B1E,
+    0x11947AE, 0x119501E, 0x1195AAE, 0x119A062, 0x119A8AE, 0x119AA62, 0x119D8AE, 0x119DA62,
+    0x119E5AE, 0x11A00AB, 0x11A48AE, 0x11A5089, 0x11AA3AE, 0x11AB014, 0x11AC071, 0x11AF9AE,
+    0x11B001D, 0x11B0AAE, 0x11B6081, 0x11B68AE, 0x11BC08B, 0x11BE2AE, 0x11BF08B, 0x11BFAAE,
+    0x11C000D, 0x11C09AE, 0x11C0A0D, 0x11C37AE, 0x11C380D, 0x11C46AE, 0x11C500D, 0x11C6DAE,
+    0x11C7055, 0x11C90AE, 0x11C9255, 0x11CA8AE, 0x11CA955, 0x11CB7AE, 0x11D002A, 0x11D07AE,
+    0x11D082A, 0x11D0AAE, 0x11D0B2A, 0x11D37AE, 0x11D3A2A, 0x11D3BAE, 0x11D3C2A, 0x11D3EAE,
+    0x11D3F2A, 0x11D48AE, 0x11D502A, 0x11D5AAE, 0x11D6029, 0x11D66AE, 0x11D6729, 0x11D69AE,
+    0x11D6A29, 0x11D8FAE, 0x11D9029, 0x11D92AE, 0x11D9329, 0x11D99AE, 0x11DA029, 0x11DAAAE,
+    0x11DB09F, 0x11DDCAE, 0x11DE09F, 0x11DEAAE, 0x11EE052, 0x11EF9AE, 0x11F003F, 0x11F11AE,
+    0x11F123F, 0x11F3BAE, 0x11F3E3F, 0x11F5BAE, 0x11FB04E, 0x11FB1AE, 0x11FC092, 0x11FF2AE,
+    0x11FFF92, 0x12000A8, 0x1239AAE, 0x12400A8, 0x1246FAE, 0x12470A8, 0x12475AE, 0x12480A8,
+    0x12544AE, 0x12F901A, 0x12FF3AE, 0x1300022, 0x13456AE, 0x1346022, 0x143FBAE, 0x1440037,
+    0x14647AE, 0x161002F, 0x1613AAE, 0x1680008, 0x16A39AE, 0x16A405D, 0x16A5FAE, 0x16A605D,
+    0x16A6AAE, 0x16A6E5D, 0x16A709D, 0x16ABFAE, 0x16AC09D, 0x16ACAAE, 0x16AD009, 0x16AEEAE,
+    0x16AF009, 0x16AF6AE, 0x16B0038, 0x16B46AE, 0x16B5038, 0x16B5AAE, 0x16B5B38, 0x16B62AE,
+    0x16B6338, 0x16B78AE, 0x16B7D38, 0x16B90AE, 0x16D4045, 0x16D7AAE, 0x16E4056, 0x16E9BAE,
+    0x16EA00C, 0x16EB9AE, 0x16EBB0C, 0x16ED4AE, 0x16F0077, 0x16F4BAE, 0x16F4F77, 0x16F88AE,
+    0x16F8F77, 0x16FA0AE, 0x16FE093, 0x16FE167, 0x16FE232, 0x16FE443, 0x16FE5AE, 0x16FF032,
+    0x16FF7AE, 0x1700093, 0x18B0043, 0x18CD6AE, 0x18CFF43, 0x18D0093, 0x18D1FAE, 0x18D8093,
+    0x18DF3AE, 0x1AFF03E, 0x1AFF4AE, 0x1AFF53E, 0x1AFFCAE, 0x1AFFD3E, 0x1AFFFAE, 0x1B0003E,
+    0x1B00136, 0x1B1203E, 0x1B123AE, 0x1B13236, 0x1B133AE, 0x1B15036, 0x1B153AE, 0x1B1553E,
+    0x1B156AE, 0x1B1643E, 0x1B168AE, 0x1B17067, 0x1B2FCAE, 0x1BC0021, 0x1BC6BAE, 0x1BC7021,
+    0x1BC7DAE, 0x1BC8021, 0x1BC89AE, 0x1BC9021, 0x1BC9AAE, 0x1BC9C21, 0x1BCA0AD, 0x1BCA4AE,
+    0x1CC00AD, 0x1CCFDAE, 0x1CD00AD, 0x1CEB4AE, 0x1CEBAAD, 0x1CED1AE, 0x1CEE0AD, 0x1CEF1AE,
+    0x1CF00AC, 0x1CF2EAE, 0x1CF30AC, 0x1CF47AE, 0x1CF50AD, 0x1CFC4AE, 0x1D000AD, 0x1D0F6AE,
+    0x1D100AD, 0x1D127AE, 0x1D129AD, 0x1D167AC, 0x1D16AAD, 0x1D17BAC, 0x1D183AD, 0x1D185AC,
+    0x1D18CAD, 0x1D1AAAC, 0x1D1AEAD, 0x1D1EBAE, 0x1D2002D, 0x1D246AE, 0x1D2C0AD, 0x1D2D4AE,
+    0x1D2E0AD, 0x1D2F4AE, 0x1D300AD, 0x1D357AE, 0x1D360AD, 0x1D379AE, 0x1D400AD, 0x1D455AE,
+    0x1D456AD, 0x1D49DAE, 0x1D49EAD, 0x1D4A0AE, 0x1D4A2AD, 0x1D4A3AE, 0x1D4A5AD, 0x1D4A7AE,
+    0x1D4A9AD, 0x1D4ADAE, 0x1D4AEAD, 0x1D4BAAE, 0x1D4BBAD, 0x1D4BCAE, 0x1D4BDAD, 0x1D4C4AE,
+    0x1D4C5AD, 0x1D506AE, 0x1D507AD, 0x1D50BAE, 0x1D50DAD, 0x1D515AE, 0x1D516AD, 0x1D51DAE,
+    0x1D51EAD, 0x1D53AAE, 0x1D53BAD, 0x1D53FAE, 0x1D540AD, 0x1D545AE, 0x1D546AD, 0x1D547AE,
+    0x1D54AAD, 0x1D551AE, 0x1D552AD, 0x1D6A6AE, 0x1D6A8AD, 0x1D7CCAE, 0x1D7CEAD, 0x1D8007F,
+    0x1DA8CAE, 0x1DA9B7F, 0x1DAA0AE, 0x1DAA17F, 0x1DAB0AE, 0x1DF0049, 0x1DF1FAE, 0x1DF2549,
+    0x1DF2BAE, 0x1E00028, 0x1E007AE, 0x1E00828, 0x1E019AE, 0x1E01B28, 0x1E022AE, 0x1E02328,
+    0x1E025AE, 0x1E02628, 0x1E02BAE, 0x1E0301C, 0x1E06EAE, 0x1E08F1C, 0x1E090AE, 0x1E10039,
+    0x1E12DAE, 0x1E13039, 0x1E13EAE, 0x1E14039, 0x1E14AAE, 0x1E14E39, 0x1E150AE, 0x1E290A0,
+    0x1E2AFAE, 0x1E2C0A6, 0x1E2FAAE, 0x1E2FFA6, 0x1E300AE, 0x1E4D061, 0x1E4FAAE, 0x1E5D06A,
+    0x1E5FBAE, 0x1E5FF6A, 0x1E600AE, 0x1E6C095, 0x1E6DFAE, 0x1E6E095, 0x1E6F6AE, 0x1E6FE95,
+    0x1E700AE, 0x1E7E025, 0x1E7E7AE, 0x1E7E825, 0x1E7ECAE, 0x1E7ED25, 0x1E7EFAE, 0x1E7F025,
+    0x1E7FFAE, 0x1E80057, 0x1E8C5AE, 0x1E8C757, 0x1E8D7AE, 0x1E90000, 0x1E94CAE, 0x1E95000,
+    0x1E95AAE, 0x1E95E00, 0x1E960AE, 0x1EC71AD, 0x1ECB5AE, 0x1ED01AD, 0x1ED3EAE, 0x1EE0003,
+    0x1EE04AE, 0x1EE0503, 0x1EE20AE, 0x1EE2103, 0x1EE23AE, 0x1EE2403, 0x1EE25AE, 0x1EE2703,
+    0x1EE28AE, 0x1EE2903, 0x1EE33AE, 0x1EE3403, 0x1EE38AE, 0x1EE3903, 0x1EE3AAE, 0x1EE3B03,
+    0x1EE3CAE, 0x1EE4203, 0x1EE43AE, 0x1EE4703, 0x1EE48AE, 0x1EE4903, 0x1EE4AAE, 0x1EE4B03,
+    0x1EE4CAE, 0x1EE4D03, 0x1EE50AE, 0x1EE5103, 0x1EE53AE, 0x1EE5403, 0x1EE55AE, 0x1EE5703,
+    0x1EE58AE, 0x1EE5903, 0x1EE5AAE, 0x1EE5B03, 0x1EE5CAE, 0x1EE5D03, 0x1EE5EAE, 0x1EE5F03,
+    0x1EE60AE, 0x1EE6103, 0x1EE63AE, 0x1EE6403, 0x1EE65AE, 0x1EE6703, 0x1EE6BAE, 0x1EE6C03,
+    0x1EE73AE, 0x1EE7403, 0x1EE78AE, 0x1EE7903, 0x1EE7DAE, 0x1EE7E03, 0x1EE7FAE, 0x1EE8003,
+    0x1EE8AAE, 0x1EE8B03, 0x1EE9CAE, 0x1EEA103, 0x1EEA4AE, 0x1EEA503, 0x1EEAAAE, 0x1EEAB03,
+    0x1EEBCAE, 0x1EEF003, 0x1EEF2AE, 0x1F000AD, 0x1F02CAE, 0x1F030AD, 0x1F094AE, 0x1F0A0AD,
+    0x1F0AFAE, 0x1F0B1AD, 0x1F0C0AE, 0x1F0C1AD, 0x1F0D0AE, 0x1F0D1AD, 0x1F0F6AE, 0x1F100AD,
+    0x1F1AEAE, 0x1F1E6AD, 0x1F20036, 0x1F201AD, 0x1F203AE, 0x1F210AD, 0x1F23CAE, 0x1F240AD,
+    0x1F249AE, 0x1F250AD, 0x1F252AE, 0x1F260AD, 0x1F266AE, 0x1F300AD, 0x1F6D9AE, 0x1F6DCAD,
+    0x1F6EDAE, 0x1F6F0AD, 0x1F6FDAE, 0x1F700AD, 0x1F7DAAE, 0x1F7E0AD, 0x1F7ECAE, 0x1F7F0AD,
+    0x1F7F1AE, 0x1F800AD, 0x1F80CAE, 0x1F810AD, 0x1F848AE, 0x1F850AD, 0x1F85AAE, 0x1F860AD,
+    0x1F888AE, 0x1F890AD, 0x1F8AEAE, 0x1F8B0AD, 0x1F8BCAE, 0x1F8C0AD, 0x1F8C2AE, 0x1F8D0AD,
+    0x1F8D9AE, 0x1F900AD, 0x1FA58AE, 0x1FA60AD, 0x1FA6EAE, 0x1FA70AD, 0x1FA7DAE, 0x1FA80AD,
+    0x1FA8BAE, 0x1FA8EAD, 0x1FAC7AE, 0x1FAC8AD, 0x1FAC9AE, 0x1FACDAD, 0x1FADDAE, 0x1FADFAD,
+    0x1FAEBAE, 0x1FAEFAD, 0x1FAF9AE, 0x1FB00AD, 0x1FB93AE, 0x1FB94AD, 0x1FBFBAE, 0x2000032,
+    0x2A6E0AE, 0x2A70032, 0x2B81EAE, 0x2B82032, 0x2CEAEAE, 0x2CEB032, 0x2EBE1AE, 0x2EBF032,
+    0x2EE5EAE, 0x2F80032, 0x2FA1EAE, 0x3000032, 0x3134BAE, 0x3135032, 0x3347AAE, 0xE0001AD,
+    0xE0002AE, 0xE0020AD, 0xE0080AE, 0xE0100AC, 0xE01F0AE,
+];
+
+/// Ranges of codepoints that are used by more than one script, sorted by
+/// codepoint.
+///
+/// Each entry holds the first and last codepoint of the range along with
+/// the scripts that commonly use them.
+#[rustfmt::skip]
+pub static SCRIPT_EXTENSIONS: &[(u32, u32, &[Script])] = &[
+    (0x00B7, 0x00B7, &[Script(*b"Avst"), Script(*b"Cari"), Script(*b"Copt"), Script(*b"Dupl"), Script(*b"Elba"), Script(*b"Geor"), Script(*b"Glag"), Script(*b"Gong"), Script(*b"Goth"), Script(*b"Grek"), Script(*b"Hani"), Script(*b"Latn"), Script(*b"Lydi"), Script(*b"Mahj"), Script(*b"Perm"), Script(*b"Shaw")]),
+    (0x02BC, 0x02BC, &[Script(*b"Beng"), Script(*b"Cyrl"), Script(*b"Deva"), Script(*b"Latn"), Script(*b"Lisu"), Script(*b"Thai"), Script(*b"Toto")]),
+    (0x02C7, 0x02C7, &[Script(*b"Bopo"), Script(*b"Latn")]),
+    (0x02C9, 0x02CB, &[Script(*b"Bopo"), Script(*b"Latn")]),
+    (0x02CD, 0x02CD, &[Script(*b"Latn"), Script(*b"Lisu")]),
+    (0x02D7, 0x02D7, &[Script(*b"Latn"), Script(*b"Thai")]),
+    (0x02D9, 0x02D9, &[Script(*b"Bopo"), Script(*b"Latn")]),
+    (0x0300, 0x0300, &[Script(*b"Cher"), Script(*b"Copt"), Script(*b"Cyrl"), Script(*b"Grek"), Script(*b"Latn"), Script(*b"Perm"), Script(*b"Sunu"), Script(*b"Tale")]),
+    (0x0301, 0x0301, &[Script(*b"Cher"), Script(*b"Cyrl"), Script(*b"Grek"), Script(*b"Latn"), Script(*b"Osge"), Script(*b"Sunu"), Script(*b"Tale"), Script(*b"Todr")]),
+    (0x0302, 0x0302, &[Script(*b"Cher"), Script(*b"Cyrl"), Script(*b"Latn"), Script(*b"Tfng")]),
+    (0x0303, 0x0303, &[Script(*b"Glag"), Script(*b"Latn"), Script(*b"Sunu"), Script(*b"Syrc"), Script(*b"Thai")]),
+    (0x0304, 0x0304, &[Script(*b"Aghb"), Script(*b"Cher"), Script(*b"Copt"), Script(*b"Cyrl"), Script(*b"Goth"), Script(*b"Grek"), Script(*b"Latn"), Script(*b"Osge"), Script(*b"Syrc"), Script(*b"Tfng"), Script(*b"Todr")]),
+    (0x0305, 0x0305, &[Script(*b"Copt"), Script(*b"Elba"), Script(*b"Glag"), Script(*b"Goth"), Script(*b"Kana"), Script(*b"Latn")]),
+    (0x0306, 0x0306, &[Script(*b"Cyrl"), Script(*b"Grek"), Script(*b"Latn"), Script(*b"Perm"), Script(*b"Tfng")]),
+    (0x0307, 0x0307, &[Script(*b"Copt"), Script(*b"Dupl"), Script(*b"Hebr"), Script(*b"Latn"), Script(*b"Perm"), Script(*b"Syrc"), Script(*b"Tale"), Script(*b"Tfng"), Script(*b"Todr")]),
+    (0x0308, 0x0308, &[Script(*b"Armn"), Script(*b"Cyrl"), Script(*b"Dupl"), Script(*b"Goth"), Script(*b"Grek"), Script(*b"Hebr"), Script(*b"Latn"), Script(*b"Perm"), Script(*b"Syrc"), Script(*b"Tale"), Script(*b"Tfng")]),
+    (0x0309, 0x0309, &[Script(*b"Latn"), Script(*b"Tfng")]),
+    (0x030A, 0x030A, &[Script(*b"Dupl"), Script(*b"Latn"), Script(*b"Syrc")]),
+    (0x030B, 0x030B, &[Script(*b"Cher"), Script(*b"Cyrl"), Script(*b"Latn"), Script(*b"Osge")]),
+    (0x030C, 0x030C, &[Script(*b"Cher"), Script(*b"Latn"), Script(*b"Tale")]),
+    (0x030D, 0x030D, &[Script(*b"Latn"), Script(*b"Sunu")]),
+    (0x030E, 0x030E, &[Script(*b"Ethi"), Script(*b"Latn")]),
+    (0x0310, 0x0310, &[Script(*b"Latn"), Script(*b"Sunu")]),
+    (0x0311, 0x0311, &[Script(*b"Cyrl"), Script(*b"Latn"), Script(*b"Todr")]),
+    (0x0313, 0x0313, &[Script(*b"Grek"), Script(*b"Latn"), Script(*b"Perm"), Script(*b"Todr")]),
+    (0x0323, 0x0323, &[Script(*b"Cher"), Script(*b"Dupl"), Script(*b"Kana"), Script(*b"Latn"), Script(*b"Syrc"), Script(*b"Tfng")]),
+    (0x0324, 0x0324, &[Script(*b"Cher"), Script(*b"Dupl"), Script(*b"Latn"), Script(*b"Syrc")]),
+    (0x0325, 0x0325, &[Script(*b"Latn"), Script(*b"Syrc")]),
+    (0x032D, 0x032D, &[Script(*b"Latn"), Script(*b"Sunu"), Script(*b"Syrc")]),
+    (0x032E, 0x032E, &[Script(*b"Latn"), Script(*b"Syrc")]),
+    (0x0330, 0x0330, &[Script(*b"Cher"), Script(*b"Latn"), Script(*b"Syrc")]),
+    (0x0331, 0x0331, &[Script(*b"Aghb"), Script(*b"Cher"), Script(*b"Goth"), Script(*b"Latn"), Script(*b"Sunu"), Script(*b"Syrc"), Script(*b"Thai")]),
+    (0x0342, 0x0342, &[Script(*b"Grek")]),
+    (0x0345, 0x0345, &[Script(*b"Grek")]),
+    (0x0358, 0x0358, &[Script(*b"Latn"), Script(*b"Osge")]),
+    (0x035E, 0x035E, &[Script(*b"Aghb"), Script(*b"Latn"), Script(*b"Todr")]),
+    (0x0363, 0x036F, &[Script(*b"Latn")]),
+    (0x0374, 0x0375, &[Script(*b"Copt"), Script(*b"Grek")]),
+    (0x0483, 0x0483, &[Script(*b"Cyrl"), Script(*b"Perm")]),
+    (0x0484, 0x0484, &[Script(*b"Cyrl"), Script(*b"Glag")]),
+    (0x0485, 0x0486, &[Script(*b"Cyrl"), Script(*b"Latn")]),
+    (0x0487, 0x0487, &[Script(*b"Cyrl"), Script(*b"Glag")]),
+    (0x0589, 0x0589, &[Script(*b"Armn"), Script(*b"Geor"), Script(*b"Glag")]),
+    (0x060C, 0x060C, &[Script(*b"Arab"), Script(*b"Gara"), Script(*b"Nkoo"), Script(*b"Rohg"), Script(*b"Syrc"), Script(*b"Thaa"), Script(*b"Yezi")]),
+    (0x061B, 0x061B, &[Script(*b"Arab"), Script(*b"Gara"), Script(*b"Nkoo"), Script(*b"Rohg"), Script(*b"Syrc"), Script(*b"Thaa"), Script(*b"Yezi")]),
+    (0x061C, 0x061C, &[Script(*b"Arab"), Script(*b"Syrc"), Script(*b"Thaa")]),
+    (0x061F, 0x061F, &[Script(*b"Adlm"), Script(*b"Arab"), Script(*b"Gara"), Script(*b"Nkoo"), Script(*b"Rohg"), Script(*b"Syrc"), Script(*b"Thaa"), Script(*b"Yezi")]),
+    (0x0640, 0x0640, &[Script(*b"Adlm"), Script(*b"Arab"), Script(*b"Mand"), Script(*b"Mani"), Script(*b"Ougr"), Script(*b"Phlp"), Script(*b"Rohg"), Script(*b"Sogd"), Script(*b"Syrc")]),
+    (0x064B, 0x0655, &[Script(*b"Arab"), Script(*b"Syrc")]),
+    (0x0660, 0x0669, &[Script(*b"Arab"), Script(*b"Thaa"), Script(*b"Yezi")]),
+    (0x0670, 0x0670, &[Script(*b"Arab"), Script(*b"Syrc")]),
+    (0x06D4, 0x06D4, &[Script(*b"Arab"), Script(*b"Rohg")]),
+    (0x0951, 0x0951, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Gran"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Knda"), Script(*b"Latn"), Script(*b"Mlym"), Script(*b"Nand"), Script(*b"Newa"), Script(*b"Orya"), Script(*b"Shrd"), Script(*b"Taml"), Script(*b"Telu"), Script(*b"Tirh")]),
+    (0x0952, 0x0952, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Gran"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Knda"), Script(*b"Latn"), Script(*b"Mlym"), Script(*b"Newa"), Script(*b"Orya"), Script(*b"Taml"), Script(*b"Telu"), Script(*b"Tirh")]),
+    (0x0964, 0x0964, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gong"), Script(*b"Gonm"), Script(*b"Gran"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Knda"), Script(*b"Mahj"), Script(*b"Mlym"), Script(*b"Nand"), Script(*b"Onao"), Script(*b"Orya"), Script(*b"Sind"), Script(*b"Sinh"), Script(*b"Sylo"), Script(*b"Takr"), Script(*b"Taml"), Script(*b"Telu"), Script(*b"Tirh")]),
+    (0x0965, 0x0965, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gong"), Script(*b"Gonm"), Script(*b"Gran"), Script(*b"Gujr"), Script(*b"Gukh"), Script(*b"Guru"), Script(*b"Knda"), Script(*b"Limb"), Script(*b"Mahj"), Script(*b"Mlym"), Script(*b"Nand"), Script(*b"Onao"), Script(*b"Orya"), Script(*b"Sind"), Script(*b"Sinh"), Script(*b"Sylo"), Script(*b"Takr"), Script(*b"Taml"), Script(*b"Telu"), Script(*b"Tirh")]),
+    (0x0966, 0x096F, &[Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Kthi"), Script(*b"Mahj")]),
+    (0x09E6, 0x09EF, &[Script(*b"Beng"), Script(*b"Cakm"), Script(*b"Sylo")]),
+    (0x0A66, 0x0A6F, &[Script(*b"Guru"), Script(*b"Mult")]),
+    (0x0AE6, 0x0AEF, &[Script(*b"Gujr"), Script(*b"Khoj")]),
+    (0x0BE6, 0x0BF3, &[Script(*b"Gran"), Script(*b"Taml")]),
+    (0x0CE6, 0x0CEF, &[Script(*b"Knda"), Script(*b"Nand"), Script(*b"Tutg")]),
+    (0x1040, 0x1049, &[Script(*b"Cakm"), Script(*b"Mymr"), Script(*b"Tale")]),
+    (0x10FB, 0x10FB, &[Script(*b"Geor"), Script(*b"Glag"), Script(*b"Latn")]),
+    (0x16EB, 0x16ED, &[Script(*b"Runr")]),
+    (0x1735, 0x1736, &[Script(*b"Buhd"), Script(*b"Hano"), Script(*b"Tagb"), Script(*b"Tglg")]),
+    (0x1802, 0x1803, &[Script(*b"Mong"), Script(*b"Phag")]),
+    (0x1805, 0x1805, &[Script(*b"Mong"), Script(*b"Phag")]),
+    (0x1CD0, 0x1CD0, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Gran"), Script(*b"Knda")]),
+    (0x1CD1, 0x1CD1, &[Script(*b"Deva")]),
+    (0x1CD2, 0x1CD2, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Gran"), Script(*b"Knda")]),
+    (0x1CD3, 0x1CD3, &[Script(*b"Deva"), Script(*b"Gran"), Script(*b"Knda")]),
+    (0x1CD4, 0x1CD4, &[Script(*b"Deva")]),
+    (0x1CD5, 0x1CD5, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Newa"), Script(*b"Telu"), Script(*b"Tirh")]),
+    (0x1CD6, 0x1CD6, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Telu")]),
+    (0x1CD7, 0x1CD7, &[Script(*b"Deva"), Script(*b"Newa"), Script(*b"Shrd")]),
+    (0x1CD8, 0x1CD8, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Newa"), Script(*b"Telu")]),
+    (0x1CD9, 0x1CD9, &[Script(*b"Deva"), Script(*b"Shrd")]),
+    (0x1CDA, 0x1CDA, &[Script(*b"Deva"), Script(*b"Knda"), Script(*b"Mlym"), Script(*b"Orya"), Script(*b"Taml"), Script(*b"Telu")]),
+    (0x1CDB, 0x1CDB, &[Script(*b"Deva")]),
+    (0x1CDC, 0x1CDD, &[Script(*b"Deva"), Script(*b"Shrd")]),
+    (0x1CDE, 0x1CDF, &[Script(*b"Deva")]),
+    (0x1CE0, 0x1CE0, &[Script(*b"Deva"), Script(*b"Shrd")]),
+    (0x1CE1, 0x1CE1, &[Script(*b"Beng"), Script(*b"Deva")]),
+    (0x1CE2, 0x1CE2, &[Script(*b"Deva"), Script(*b"Newa"), Script(*b"Tirh")]),
+    (0x1CE3, 0x1CE8, &[Script(*b"Deva")]),
+    (0x1CE9, 0x1CE9, &[Script(*b"Deva"), Script(*b"Nand"), Script(*b"Newa")]),
+    (0x1CEA, 0x1CEA, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Shrd")]),
+    (0x1CEB, 0x1CEB, &[Script(*b"Deva"), Script(*b"Newa")]),
+    (0x1CEC, 0x1CEC, &[Script(*b"Deva")]),
+    (0x1CED, 0x1CED, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Newa"), Script(*b"Shrd")]),
+    (0x1CEE, 0x1CF1, &[Script(*b"Deva")]),
+    (0x1CF2, 0x1CF2, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Gran"), Script(*b"Knda"), Script(*b"Mlym"), Script(*b"Nand"), Script(*b"Orya"), Script(*b"Sinh"), Script(*b"Telu"), Script(*b"Tirh"), Script(*b"Tutg")]),
+    (0x1CF3, 0x1CF3, &[Script(*b"Deva"), Script(*b"Gran")]),
+    (0x1CF4, 0x1CF4, &[Script(*b"Deva"), Script(*b"Gran"), Script(*b"Knda"), Script(*b"Tutg")]),
+    (0x1CF5, 0x1CF6, &[Script(*b"Beng"), Script(*b"Deva")]),
+    (0x1CF7, 0x1CF7, &[Script(*b"Beng")]),
+    (0x1CF8, 0x1CF9, &[Script(*b"Deva"), Script(*b"Gran")]),
+    (0x1CFA, 0x1CFA, &[Script(*b"Nand")]),
+    (0x1DC0, 0x1DC1, &[Script(*b"Grek")]),
+    (0x1DF8, 0x1DF8, &[Script(*b"Cyrl"), Script(*b"Latn"), Script(*b"Syrc")]),
+    (0x1DFA, 0x1DFA, &[Script(*b"Syrc")]),
+    (0x202F, 0x202F, &[Script(*b"Latn"), Script(*b"Mong"), Script(*b"Phag")]),
+    (0x204F, 0x204F, &[Script(*b"Adlm"), Script(*b"Arab")]),
+    (0x205A, 0x205A, &[Script(*b"Cari"), Script(*b"Geor"), Script(*b"Glag"), Script(*b"Hung"), Script(*b"Lyci"), Script(*b"Orkh")]),
+    (0x205D, 0x205D, &[Script(*b"Cari"), Script(*b"Grek"), Script(*b"Hung"), Script(*b"Mero")]),
+    (0x20F0, 0x20F0, &[Script(*b"Deva"), Script(*b"Gran"), Script(*b"Latn")]),
+    (0x2E17, 0x2E17, &[Script(*b"Copt"), Script(*b"Latn")]),
+    (0x2E30, 0x2E30, &[Script(*b"Avst"), Script(*b"Orkh")]),
+    (0x2E31, 0x2E31, &[Script(*b"Avst"), Script(*b"Cari"), Script(*b"Geor"), Script(*b"Hung"), Script(*b"Kthi"), Script(*b"Lydi"), Script(*b"Samr")]),
+    (0x2E3C, 0x2E3C, &[Script(*b"Dupl")]),
+    (0x2E41, 0x2E41, &[Script(*b"Adlm"), Script(*b"Arab"), Script(*b"Hung")]),
+    (0x2E43, 0x2E43, &[Script(*b"Cyrl"), Script(*b"Glag")]),
+    (0x2FF0, 0x2FFF, &[Script(*b"Hani"), Script(*b"Tang")]),
+    (0x3001, 0x3001, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Mong"), Script(*b"Yiii")]),
+    (0x3002, 0x3002, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Mong"), Script(*b"Phag"), Script(*b"Yiii")]),
+    (0x3003, 0x3003, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0x3006, 0x3006, &[Script(*b"Hani")]),
+    (0x3008, 0x3009, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Mong"), Script(*b"Tibt"), Script(*b"Yiii")]),
+    (0x300A, 0x300B, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Lisu"), Script(*b"Mong"), Script(*b"Tibt"), Script(*b"Yiii")]),
+    (0x300C, 0x3011, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Yiii")]),
+    (0x3013, 0x3013, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0x3014, 0x301B, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Yiii")]),
+    (0x301C, 0x301F, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0x302A, 0x302D, &[Script(*b"Bopo"), Script(*b"Hani")]),
+    (0x3030, 0x3030, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0x3031, 0x3035, &[Script(*b"Hira"), Script(*b"Kana")]),
+    (0x3037, 0x3037, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0x303C, 0x303D, &[Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0x303E, 0x303F, &[Script(*b"Hani")]),
+    (0x3099, 0x309C, &[Script(*b"Hira"), Script(*b"Kana")]),
+    (0x30A0, 0x30A0, &[Script(*b"Hira"), Script(*b"Kana")]),
+    (0x30FB, 0x30FB, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Yiii")]),
+    (0x30FC, 0x30FC, &[Script(*b"Hira"), Script(*b"Kana")]),
+    (0x3190, 0x319F, &[Script(*b"Hani")]),
+    (0x31C0, 0x31E5, &[Script(*b"Hani")]),
+    (0x31EF, 0x31EF, &[Script(*b"Hani"), Script(*b"Tang")]),
+    (0x3220, 0x3247, &[Script(*b"Hani")]),
+    (0x3280, 0x32B0, &[Script(*b"Hani")]),
+    (0x32C0, 0x32CB, &[Script(*b"Hani")]),
+    (0x32FF, 0x32FF, &[Script(*b"Hani")]),
+    (0x3358, 0x3370, &[Script(*b"Hani")]),
+    (0x337B, 0x337F, &[Script(*b"Hani")]),
+    (0x33E0, 0x33FE, &[Script(*b"Hani")]),
+    (0xA66F, 0xA66F, &[Script(*b"Cyrl"), Script(*b"Glag")]),
+    (0xA700, 0xA707, &[Script(*b"Hani"), Script(*b"Latn")]),
+    (0xA830, 0xA832, &[Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Khoj"), Script(*b"Knda"), Script(*b"Kthi"), Script(*b"Mahj"), Script(*b"Mlym"), Script(*b"Modi"), Script(*b"Nand"), Script(*b"Shrd"), Script(*b"Sind"), Script(*b"Takr"), Script(*b"Tirh"), Script(*b"Tutg")]),
+    (0xA833, 0xA835, &[Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Khoj"), Script(*b"Knda"), Script(*b"Kthi"), Script(*b"Mahj"), Script(*b"Modi"), Script(*b"Nand"), Script(*b"Shrd"), Script(*b"Sind"), Script(*b"Takr"), Script(*b"Tirh"), Script(*b"Tutg")]),
+    (0xA836, 0xA837, &[Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Khoj"), Script(*b"Kthi"), Script(*b"Mahj"), Script(*b"Modi"), Script(*b"Sind"), Script(*b"Takr"), Script(*b"Tirh")]),
+    (0xA838, 0xA838, &[Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Khoj"), Script(*b"Kthi"), Script(*b"Mahj"), Script(*b"Modi"), Script(*b"Shrd"), Script(*b"Sind"), Script(*b"Takr"), Script(*b"Tirh")]),
+    (0xA839, 0xA839, &[Script(*b"Deva"), Script(*b"Dogr"), Script(*b"Gujr"), Script(*b"Guru"), Script(*b"Khoj"), Script(*b"Kthi"), Script(*b"Mahj"), Script(*b"Modi"), Script(*b"Sind"), Script(*b"Takr"), Script(*b"Tirh")]),
+    (0xA8F1, 0xA8F1, &[Script(*b"Beng"), Script(*b"Deva"), Script(*b"Tutg")]),
+    (0xA8F3, 0xA8F3, &[Script(*b"Deva"), Script(*b"Taml")]),
+    (0xA92E, 0xA92E, &[Script(*b"Kali"), Script(*b"Latn"), Script(*b"Mymr")]),
+    (0xA9CF, 0xA9CF, &[Script(*b"Bugi"), Script(*b"Java")]),
+    (0xFD3E, 0xFD3F, &[Script(*b"Arab"), Script(*b"Nkoo")]),
+    (0xFDF2, 0xFDF2, &[Script(*b"Arab"), Script(*b"Thaa")]),
+    (0xFDFD, 0xFDFD, &[Script(*b"Arab"), Script(*b"Thaa")]),
+    (0xFE45, 0xFE46, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana")]),
+    (0xFF61, 0xFF65, &[Script(*b"Bopo"), Script(*b"Hang"), Script(*b"Hani"), Script(*b"Hira"), Script(*b"Kana"), Script(*b"Yiii")]),
+    (0xFF70, 0xFF70, &[Script(*b"Hira"), Script(*b"Kana")]),
+    (0xFF9E, 0xFF9F, &[Script(*b"Hira"), Script(*b"Kana")]),
+    (0x10100, 0x10101, &[Script(*b"Cpmn"), Script(*b"Cprt"), Script(*b"Linb")]),
+    (0x10102, 0x10102, &[Script(*b"Cprt"), Script(*b"Linb")]),
+    (0x10107, 0x10133, &[Script(*b"Cprt"), Script(*b"Lina"), Script(*b"Linb")]),
+    (0x10137, 0x1013F, &[Script(*b"Cprt"), Script(*b"Linb")]),
+    (0x102E0, 0x102FB, &[Script(*b"Arab"), Script(*b"Copt")]),
+    (0x10AF2, 0x10AF2, &[Script(*b"Mani"), Script(*b"Ougr")]),
+    (0x11301, 0x11301, &[Script(*b"Gran"), Script(*b"Taml")]),
+    (0x11303, 0x11303, &[Script(*b"Gran"), Script(*b"Taml")]),
+    (0x1133B, 0x1133C, &[Script(*b"Gran"), Script(*b"Taml")]),
+    (0x11FD0, 0x11FD1, &[Script(*b"Gran"), Script(*b"Taml")]),
+    (0x11FD3, 0x11FD3, &[Script(*b"Gran"), Script(*b"Taml")]),
+    (0x1BCA0, 0x1BCA3, &[Script(*b"Dupl")]),
+    (0x1D360, 0x1D371, &[Script(*b"Hani")]),
+    (0x1F250, 0x1F251, &[Script(*b"Hani")]),
+];
@@ -108,6 +108,22 @@ impl SourceCache {
         }
     }
 
+    /// Returns the estimated number of bytes held by the cache.
+    ///
+    /// This includes the size of all loaded font data, which is usually
+    /// memory mapped and only partially resident.
+    pub fn memory_usage(&self) -> usize {
+        self.cache.capacity() * core::mem::size_of::<(SourceId, Entry<Blob<u8>>)>()
+            + self
+                .cache
+                .values()
+                .map(|entry| match entry {
+                    Entry::Loaded(data) => data.font_data.len(),
+                    Entry::Failed => 0,
+                })
+                .sum::<usize>()
+    }
+
     /// Removes all cached blobs that have not been accessed in the last
     /// `max_age` times `prune` has been called.
     pub fn prune(&mut self, max_age: u64, prune_failed: bool) {
@@ -178,6 +194,10 @@ struct EntryData<T> {
     serial: u64,
 }
 
+#[cfg_attr(
+    feature = "trace",
+    tracing::instrument(level = "debug", fields(path = %path.display()), skip_all)
+)]
 pub(crate) fn load_blob(path: &Path) -> Option<Blob<u8>> {
     let file = std::fs::File::open(path).ok()?;
     let mapped = unsafe { memmap2::Mmap::map(&file).ok()? };
@@ -0,0 +1,223 @@
+//! Synthetic fonts and system font providers for tests.
+
+use super::{
+    backend::SystemFonts, Blob, FallbackKey, FamilyId, FamilyInfo, FamilyNameMap, FontInfo,
+    GenericFamilyMap, Script, SourceId, SourceInfo, SourceKind,
+};
+use hashbrown::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Builder for a minimal font containing only the tables read when
+/// registering fonts.
+pub(crate) struct TestFont {
+    family: String,
+    revision: f32,
+    version: Option<String>,
+    chars: Vec<core::ops::RangeInclusive<u32>>,
+}
+
+impl TestFont {
+    pub fn new(family: &str) -> Self {
+        Self {
+            family: family.into(),
+            revision: 1.0,
+            version: None,
+            chars: Vec::new(),
+        }
+    }
+
+    /// Maps the given range of codepoints in the character map.
+    pub fn chars(mut self, range: core::ops::RangeInclusive<char>) -> Self {
+        self.chars.push(*range.start() as u32..=*range.end() as u32);
+        self
+    }
+
+    /// Sets the `fontRevision` field of the `head` table.
+    pub fn revision(mut self, revision: f32) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// Sets the version string (name ID 5).
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut tables = [
+            (*b"cmap", self.build_cmap()),
+            (*b"head", self.build_head()),
+            (*b"maxp", self.build_maxp()),
+            (*b"name", self.build_name()),
+        ];
+        tables.sort_by_key(|(tag, _)| *tag);
+        let mut font = Writer::default();
+        let num_tables = tables.len() as u16;
+        let entry_selector = 15 - num_tables.leading_zeros() as u16;
+        let search_range = (1 << entry_selector) * 16;
+        font.u32(0x00010000);
+        font.u16(num_tables);
+        font.u16(search_range);
+        font.u16(entry_selector);
+        font.u16(num_tables * 16 - search_range);
+        let mut offset = 12 + tables.len() * 16;
+        for (tag, data) in &tables {
+            font.0.extend_from_slice(tag);
+            // Checksums are not verified when loading.
+            font.u32(0);
+            font.u32(offset as u32);
+            font.u32(data.len() as u32);
+            offset += (data.len() + 3) & !3;
+        }
+        for (_, data) in &tables {
+            font.0.extend_from_slice(data);
+            while font.0.len() % 4 != 0 {
+                font.0.push(0);
+            }
+        }
+        font.0
+    }
+
+    fn build_cmap(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u16(0);
+        w.u16(1);
+        // Windows, full Unicode repertoire
+        w.u16(3);
+        w.u16(10);
+        w.u32(12);
+        w.u16(12);
+        w.u16(0);
+        w.u32(16 + self.chars.len() as u32 * 12);
+        w.u32(0);
+        w.u32(self.chars.len() as u32);
+        // All characters map to the first glyph after notdef.
+        for range in &self.chars {
+            w.u32(*range.start());
+            w.u32(*range.end());
+            w.u32(1);
+        }
+        w.0
+    }
+
+    fn build_head(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        w.u32(0x00010000);
+        w.u32((self.revision * 65536.0) as u32);
+        // Checksum adjustment, magic number, flags and units per em
+        w.u32(0);
+        w.u32(0x5F0F3CF5);
+        w.u16(0);
+        w.u16(1000);
+        // Created and modified dates, bounding box, mac style, lowest rec
+        // ppem, font direction hint and loca and glyph data formats
+        w.0.resize(w.0.len() + 16 + 8 + 10, 0);
+        w.0
+    }
+
+    fn build_maxp(&self) -> Vec<u8> {
+        let mut w = Writer::default();
+        // Version 0.5 only contains the glyph count.
+        w.u32(0x00005000);
+        w.u16(1);
+        w.0
+    }
+
+    fn build_name(&self) -> Vec<u8> {
+        let mut names = vec![(1u16, self.family.as_str()), (2, "Regular")];
+        if let Some(version) = &self.version {
+            names.push((5, version.as_str()));
+        }
+        let mut w = Writer::default();
+        w.u16(0);
+        w.u16(names.len() as u16);
+        w.u16(6 + names.len() as u16 * 12);
+        let mut offset = 0;
+        for (name_id, name) in &names {
+            let len = name.encode_utf16().count() as u16 * 2;
+            // Windows, Unicode BMP, English (United States)
+            w.u16(3);
+            w.u16(1);
+            w.u16(0x409);
+            w.u16(*name_id);
+            w.u16(len);
+            w.u16(offset);
+            offset += len;
+        }
+        for (_, name) in &names {
+            for unit in name.encode_utf16() {
+                w.u16(unit);
+            }
+        }
+        w.0
+    }
+}
+
+/// Big endian writer for font data.
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u16(&mut self, value: u16) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_be_bytes());
+    }
+}
+
+/// System font provider backed by fonts in memory that records the
+/// requests made by a collection.
+#[derive(Default)]
+pub(crate) struct TestSystemFonts {
+    name_map: Arc<FamilyNameMap>,
+    generic_families: Arc<GenericFamilyMap>,
+    families: HashMap<FamilyId, FamilyInfo>,
+    fallbacks: HashMap<Script, FamilyId>,
+    /// Families requested with [`SystemFonts::family`].
+    pub loaded: Arc<Mutex<Vec<FamilyId>>>,
+    /// Scripts requested with [`SystemFonts::fallback`].
+    pub fallback_requests: Arc<Mutex<Vec<Script>>>,
+}
+
+impl TestSystemFonts {
+    /// Adds a family containing the given font data.
+    pub fn add_family(&mut self, name: &str, data: Vec<u8>) -> FamilyId {
+        let name = Arc::make_mut(&mut self.name_map).get_or_insert(name);
+        let source = SourceInfo::new(
+            SourceId::new(),
+            SourceKind::Memory(Blob::new(Arc::new(data))),
+        );
+        let font = FontInfo::from_source(source, 0).unwrap();
+        let id = name.id();
+        self.families.insert(id, FamilyInfo::new(name, [font]));
+        id
+    }
+
+    /// Sets the fallback family for the given script.
+    pub fn add_fallback(&mut self, script: Script, family: FamilyId) {
+        self.fallbacks.insert(script, family);
+    }
+}
+
+impl SystemFonts for TestSystemFonts {
+    fn name_map(&self) -> Arc<FamilyNameMap> {
+        self.name_map.clone()
+    }
+
+    fn generic_families(&self) -> Arc<GenericFamilyMap> {
+        self.generic_families.clone()
+    }
+
+    fn family(&mut self, id: FamilyId) -> Option<FamilyInfo> {
+        self.loaded.lock().unwrap().push(id);
+        self.families.get(&id).cloned()
+    }
+
+    fn fallback(&mut self, key: FallbackKey) -> Option<FamilyId> {
+        self.fallback_requests.lock().unwrap().push(key.script());
+        self.fallbacks.get(&key.script()).copied()
+    }
+}
@@ -0,0 +1,120 @@
+//! Generator for `archived/fello/src/meta/decomposition.rs`.
+
+use super::{ucd, write_wrapped, Result};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+};
+
+pub fn generate(dir: &str) -> Result<String> {
+    let unicode_data = ucd::read(dir, "UnicodeData.txt")?;
+    let exclusions_txt = ucd::read(dir, "CompositionExclusions.txt")?;
+    let mut combining_classes = HashMap::new();
+    // Canonical decompositions keyed by codepoint. Compatibility
+    // decompositions start with a tag such as `<compat>`. Hangul syllables
+    // are listed as a range without decompositions so they are skipped.
+    let mut decompositions = BTreeMap::new();
+    for fields in ucd::records(&unicode_data) {
+        let [codepoint, _, _, class, _, decomposition, ..] = fields[..] else {
+            return Err(format!("invalid UnicodeData.txt record {fields:?}").into());
+        };
+        let codepoint = ucd::parse_codepoint(codepoint)?;
+        combining_classes.insert(codepoint, class.parse::<u8>()?);
+        if decomposition.is_empty() || decomposition.starts_with('<') {
+            continue;
+        }
+        let parts = decomposition
+            .split_whitespace()
+            .map(ucd::parse_codepoint)
+            .collect::<Result<Vec<_>>>()?;
+        let (first, second) = match parts[..] {
+            [first] => (first, 0),
+            [first, second] => (first, second),
+            _ => return Err(format!("invalid decomposition for {codepoint:04X}").into()),
+        };
+        decompositions.insert(codepoint, (first, second));
+    }
+    let mut exclusions = HashSet::new();
+    for fields in ucd::records(&exclusions_txt) {
+        exclusions.extend(ucd::parse_range(fields[0])?);
+    }
+    let class = |codepoint| combining_classes.get(&codepoint).copied().unwrap_or(0);
+    // Primary composites are the pairs that are not excluded explicitly and
+    // do not start with a non-starter.
+    let mut compositions: Vec<_> = decompositions
+        .iter()
+        .filter(|(codepoint, (first, second))| {
+            *second != 0
+                && !exclusions.contains(*codepoint)
+                && class(**codepoint) == 0
+                && class(*first) == 0
+        })
+        .map(|(codepoint, (first, second))| (*first, *second, *codepoint))
+        .collect();
+    compositions.sort_unstable();
+    let max_len = decompositions
+        .keys()
+        .map(|codepoint| full_len(&decompositions, *codepoint))
+        .max()
+        .unwrap_or(1);
+    let version = ucd::version(&exclusions_txt)?;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "//! Unicode canonical decomposition data.
+//!
+//! Generated from the Unicode {version} `UnicodeData.txt` and
+//! `CompositionExclusions.txt` data files by
+//! `cargo run -p xtask -- decomposition <ucd-dir>`. Hangul syllables are
+//! excluded since they decompose algorithmically. Do not edit by hand.
+
+/// Maximum length of a full canonical decomposition.
+pub const MAX_DECOMPOSITION_LEN: usize = {max_len};
+
+/// Single step canonical decompositions as (codepoint, first, second),
+/// sorted by codepoint. The second element is zero for singleton
+/// decompositions.
+#[rustfmt::skip]
+pub static DECOMPOSITIONS: [(u32, u32, u32); {}] = [",
+        decompositions.len()
+    )?;
+    write_wrapped(
+        &mut out,
+        decompositions
+            .iter()
+            .map(|(codepoint, (first, second))| entry(*codepoint, *first, *second)),
+    );
+    writeln!(
+        out,
+        "];
+
+/// Primary composites as (first, second, composite), sorted by the pair
+/// of decomposed codepoints.
+#[rustfmt::skip]
+pub static COMPOSITIONS: [(u32, u32, u32); {}] = [",
+        compositions.len()
+    )?;
+    write_wrapped(
+        &mut out,
+        compositions
+            .iter()
+            .map(|(first, second, codepoint)| entry(*first, *second, *codepoint)),
+    );
+    out.push_str("];\n");
+    Ok(out)
+}
+
+/// Returns the length of the full canonical decomposition of the codepoint.
+fn full_len(decompositions: &BTreeMap<u32, (u32, u32)>, codepoint: u32) -> usize {
+    match decompositions.get(&codepoint) {
+        Some((first, 0)) => full_len(decompositions, *first),
+        Some((first, second)) => {
+            full_len(decompositions, *first) + full_len(decompositions, *second)
+        }
+        None => 1,
+    }
+}
+
+fn entry(a: u32, b: u32, c: u32) -> String {
+    format!("(0x{a:X}, 0x{b:X}, 0x{c:X})")
+}
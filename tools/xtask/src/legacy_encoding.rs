@@ -0,0 +1,112 @@
+//! Generator for `archived/fello/src/meta/legacy_encoding.rs`.
+
+use super::{ucd, Result};
+use std::{collections::BTreeMap, fmt::Write};
+
+/// Mac OS script encodings as (table name, description, mapping file).
+const MAC_ENCODINGS: &[(&str, &str, &str)] = &[
+    ("MAC_ROMAN", "Roman", "ROMAN.TXT"),
+    ("MAC_ICELANDIC", "Icelandic", "ICELAND.TXT"),
+    ("MAC_TURKISH", "Turkish", "TURKISH.TXT"),
+    ("MAC_CROATIAN", "Croatian", "CROATIAN.TXT"),
+    ("MAC_ROMANIAN", "Romanian", "ROMANIAN.TXT"),
+    ("MAC_GREEK", "Greek", "GREEK.TXT"),
+    ("MAC_CYRILLIC", "Cyrillic", "CYRILLIC.TXT"),
+    ("MAC_CENTRAL_EUROPEAN", "Central European", "CENTEURO.TXT"),
+];
+
+pub fn generate(dir: &str) -> Result<String> {
+    let mut out = String::from(
+        "//! Mappings between Unicode and legacy character encodings.
+//!
+//! Generated from the Unicode mapping files for Shift-JIS (JIS X 0208), Big5
+//! and the Mac OS script encodings by
+//! `cargo run -p xtask -- legacy-encoding <mappings-dir>`. Code points below
+//! U+0080 map to themselves and are excluded. Do not edit by hand.
+
+/// Unicode to Shift-JIS mappings as (codepoint, code), sorted by codepoint.
+/// Single byte codes are stored in the low byte.
+",
+    );
+    write_encoding(&mut out, "SHIFT_JIS", &read_mappings(dir, "SHIFTJIS.TXT")?)?;
+    out.push_str("\n/// Unicode to Big5 mappings as (codepoint, code), sorted by codepoint.\n");
+    write_encoding(&mut out, "BIG5", &read_mappings(dir, "BIG5.TXT")?)?;
+    for (name, description, file) in MAC_ENCODINGS {
+        let mut decoding = [0u16; 128];
+        for (code, codepoint) in read_mappings(dir, file)? {
+            if let Some(slot) = code
+                .checked_sub(0x80)
+                .and_then(|i| decoding.get_mut(i as usize))
+            {
+                *slot = codepoint;
+            }
+        }
+        if let Some(byte) = decoding.iter().position(|codepoint| *codepoint == 0) {
+            return Err(format!("{file}: missing mapping for 0x{:02X}", byte + 0x80).into());
+        }
+        writeln!(
+            out,
+            "
+/// Mac OS {description} to Unicode mappings for the codes 0x80 to 0xFF.
+#[rustfmt::skip]
+pub static {name}: [u16; 128] = ["
+        )?;
+        for row in decoding.chunks(8) {
+            let row: Vec<String> = row.iter().map(|cp| format!("0x{cp:04X},")).collect();
+            writeln!(out, "    {}", row.join(" "))?;
+        }
+        out.push_str("];\n");
+    }
+    Ok(out)
+}
+
+/// Reads the (code, codepoint) pairs from a mapping file.
+///
+/// Each data line holds a hexadecimal code and codepoint with a `0x` prefix
+/// followed by an optional comment. Codes without a codepoint are
+/// undefined and skipped.
+fn read_mappings(dir: &str, name: &str) -> Result<Vec<(u16, u16)>> {
+    let text = ucd::read(dir, name)?;
+    let mut mappings = Vec::new();
+    for line in text.lines() {
+        let data = line.split('#').next().unwrap_or_default();
+        let mut fields = data.split_whitespace();
+        let (Some(code), Some(codepoint)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let parse = |field: &str| {
+            field
+                .strip_prefix("0x")
+                .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| format!("{name}: invalid mapping {:?}", line.trim()))
+        };
+        mappings.push((parse(code)?, parse(codepoint)?));
+    }
+    Ok(mappings)
+}
+
+/// Writes a table mapping codepoints to codes. Where several codes map to
+/// the same codepoint, the lowest code is used.
+fn write_encoding(out: &mut String, name: &str, mappings: &[(u16, u16)]) -> Result<()> {
+    let mut encoding = BTreeMap::new();
+    for (code, codepoint) in mappings {
+        if *codepoint >= 0x80 {
+            let entry = encoding.entry(*codepoint).or_insert(*code);
+            *entry = (*entry).min(*code);
+        }
+    }
+    writeln!(
+        out,
+        "#[rustfmt::skip]\npub static {name}: [(u16, u16); {}] = [",
+        encoding.len()
+    )?;
+    let entries: Vec<String> = encoding
+        .iter()
+        .map(|(codepoint, code)| format!("(0x{codepoint:04X}, 0x{code:04X}),"))
+        .collect();
+    for row in entries.chunks(5) {
+        writeln!(out, "    {}", row.join(" "))?;
+    }
+    out.push_str("];\n");
+    Ok(())
+}
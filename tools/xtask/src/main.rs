@@ -0,0 +1,88 @@
+//! Generators for the data tables that are checked in to the workspace.
+//!
+//! Each task reads the source data files from a directory and overwrites
+//! the corresponding generated module:
+//!
+//! ```text
+//! cargo run -p xtask -- script-data <ucd-dir>
+//! cargo run -p xtask -- decomposition <ucd-dir>
+//! cargo run -p xtask -- legacy-encoding <mappings-dir>
+//! ```
+//!
+//! The Unicode Character Database can be downloaded from
+//! <https://www.unicode.org/Public/UCD/latest/ucd/>. The legacy encoding
+//! task expects `SHIFTJIS.TXT` and `BIG5.TXT` from
+//! <https://www.unicode.org/Public/MAPPINGS/OBSOLETE/EASTASIA/> and the Mac OS
+//! mapping files from <https://www.unicode.org/Public/MAPPINGS/VENDORS/APPLE/>
+//! in a single directory.
+
+mod decomposition;
+mod legacy_encoding;
+mod script_data;
+mod ucd;
+
+use std::{env, error::Error, fs, path::Path, process::ExitCode};
+
+const USAGE: &str = "\
+usage: cargo run -p xtask -- <task> <data-dir>
+
+tasks:
+    script-data <ucd-dir>    fontique/src/script_data.rs from Scripts.txt,
+                             ScriptExtensions.txt and PropertyValueAliases.txt
+    decomposition <ucd-dir>  archived/fello/src/meta/decomposition.rs from
+                             UnicodeData.txt and CompositionExclusions.txt
+    legacy-encoding <dir>    archived/fello/src/meta/legacy_encoding.rs from
+                             the Shift-JIS, Big5 and Mac OS mapping files";
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let (path, source) = match args[..] {
+        ["script-data", dir] => ("fontique/src/script_data.rs", script_data::generate(dir)),
+        ["decomposition", dir] => (
+            "archived/fello/src/meta/decomposition.rs",
+            decomposition::generate(dir),
+        ),
+        ["legacy-encoding", dir] => (
+            "archived/fello/src/meta/legacy_encoding.rs",
+            legacy_encoding::generate(dir),
+        ),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(path);
+    match source.and_then(|source| Ok(fs::write(&path, source)?)) {
+        Ok(()) => {
+            println!("wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Appends the items to the output as a comma separated list with an
+/// indent of four spaces, wrapping lines before they exceed 100 columns.
+fn write_wrapped(out: &mut String, items: impl IntoIterator<Item = String>) {
+    let mut line = String::from("   ");
+    for item in items {
+        if line.len() + item.len() + 2 > 100 {
+            out.push_str(&line);
+            out.push('\n');
+            line = String::from("   ");
+        }
+        line.push(' ');
+        line.push_str(&item);
+        line.push(',');
+    }
+    out.push_str(&line);
+    out.push('\n');
+}
@@ -0,0 +1,131 @@
+//! Generator for `fontique/src/script_data.rs`.
+
+use super::{ucd, write_wrapped, Result};
+use std::{collections::HashMap, fmt::Write};
+
+/// Script assigned to codepoints that are not listed in `Scripts.txt`.
+const UNKNOWN: &str = "Zzzz";
+
+pub fn generate(dir: &str) -> Result<String> {
+    let aliases = ucd::read(dir, "PropertyValueAliases.txt")?;
+    let scripts_txt = ucd::read(dir, "Scripts.txt")?;
+    let extensions_txt = ucd::read(dir, "ScriptExtensions.txt")?;
+    // Map long script names to the four letter short names.
+    let mut short_names = HashMap::new();
+    for fields in ucd::records(&aliases) {
+        if let ["sc", short, long, ..] = fields[..] {
+            short_names.insert(long, short);
+        }
+    }
+    let mut scripts = vec![UNKNOWN; ucd::CODEPOINT_COUNT];
+    for fields in ucd::records(&scripts_txt) {
+        let [range, name] = fields[..] else {
+            return Err(format!("invalid Scripts.txt record {fields:?}").into());
+        };
+        let short = *short_names
+            .get(name)
+            .ok_or_else(|| format!("unknown script {name:?}"))?;
+        for cp in ucd::parse_range(range)? {
+            scripts[cp as usize] = short;
+        }
+    }
+    let mut names = scripts.clone();
+    names.sort_unstable();
+    names.dedup();
+    let index: HashMap<&str, usize> = names.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+    // Ranges start wherever the script changes.
+    let mut ranges = Vec::new();
+    for (cp, script) in scripts.iter().enumerate() {
+        if cp == 0 || scripts[cp - 1] != *script {
+            ranges.push(((cp as u32) << 8) | index[script] as u32);
+        }
+    }
+    let mut extensions = vec![None; ucd::CODEPOINT_COUNT];
+    for fields in ucd::records(&extensions_txt) {
+        let [range, list] = fields[..] else {
+            return Err(format!("invalid ScriptExtensions.txt record {fields:?}").into());
+        };
+        let mut list: Vec<&str> = list.split_whitespace().collect();
+        list.sort_unstable();
+        for cp in ucd::parse_range(range)? {
+            extensions[cp as usize] = Some(list.clone());
+        }
+    }
+    // Merge adjacent codepoints that share the same extensions, skipping
+    // any that only contain the script of the codepoint.
+    let mut shared: Vec<(usize, usize, Vec<&str>)> = Vec::new();
+    for (cp, list) in extensions.into_iter().enumerate() {
+        let Some(list) = list else {
+            continue;
+        };
+        if list == [scripts[cp]] {
+            continue;
+        }
+        match shared.last_mut() {
+            Some(last) if last.1 + 1 == cp && last.2 == list => last.1 = cp,
+            _ => shared.push((cp, cp, list)),
+        }
+    }
+    let version = ucd::version(&scripts_txt)?;
+    let mut out = String::new();
+    writeln!(
+        out,
+        "//! Unicode script property data.
+//!
+//! Generated from the Unicode {version} `Scripts.txt` and `ScriptExtensions.txt`
+//! data files by `cargo run -p xtask -- script-data <ucd-dir>`. Do not edit
+//! by hand.
+
+use super::script::Script;
+
+/// Scripts referenced by index from [`SCRIPT_RANGES`].
+#[rustfmt::skip]
+pub static SCRIPTS: [Script; {}] = [",
+        names.len()
+    )?;
+    write_wrapped(&mut out, names.iter().map(|name| script(name)));
+    writeln!(
+        out,
+        "];
+
+/// Contiguous ranges of codepoints with the same script, sorted by
+/// codepoint.
+///
+/// Each entry holds the first codepoint of the range in the upper 24 bits
+/// and an index into [`SCRIPTS`] in the lower 8 bits. A range ends where the
+/// next one begins.
+#[rustfmt::skip]
+pub static SCRIPT_RANGES: [u32; {}] = [",
+        ranges.len()
+    )?;
+    write_wrapped(
+        &mut out,
+        ranges.iter().map(|range| format!("0x{range:07X}")),
+    );
+    out.push_str(
+        "];
+
+/// Ranges of codepoints that are used by more than one script, sorted by
+/// codepoint.
+///
+/// Each entry holds the first and last codepoint of the range along with
+/// the scripts that commonly use them.
+#[rustfmt::skip]
+pub static SCRIPT_EXTENSIONS: &[(u32, u32, &[Script])] = &[
+",
+    );
+    for (first, last, list) in shared {
+        let list: Vec<String> = list.iter().map(|name| script(name)).collect();
+        writeln!(
+            out,
+            "    (0x{first:04X}, 0x{last:04X}, &[{}]),",
+            list.join(", ")
+        )?;
+    }
+    out.push_str("];\n");
+    Ok(out)
+}
+
+fn script(name: &str) -> String {
+    format!("Script(*b\"{name}\")")
+}
@@ -0,0 +1,60 @@
+//! Parsing for the Unicode Character Database file format.
+
+use super::Result;
+use std::{fs, ops::RangeInclusive, path::Path};
+
+/// Number of codepoints in the Unicode codespace.
+pub const CODEPOINT_COUNT: usize = 0x110000;
+
+/// Reads the data file with the given name from a directory.
+pub fn read(dir: &str, name: &str) -> Result<String> {
+    let path = Path::new(dir).join(name);
+    fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()).into())
+}
+
+/// Returns the Unicode version from the header line of a data file such as
+/// `# Scripts-17.0.0.txt`.
+pub fn version(text: &str) -> Result<&str> {
+    text.lines()
+        .next()
+        .and_then(|line| line.rsplit_once('-'))
+        .and_then(|(_, file)| file.strip_suffix(".txt"))
+        .ok_or_else(|| "missing version header".into())
+}
+
+/// Returns the semicolon separated fields of each data line with comments
+/// and surrounding whitespace removed.
+pub fn records(text: &str) -> impl Iterator<Item = Vec<&str>> {
+    text.lines().filter_map(|line| {
+        let data = line.split('#').next().unwrap_or_default().trim();
+        (!data.is_empty()).then(|| data.split(';').map(str::trim).collect())
+    })
+}
+
+/// Parses a codepoint in the form `XXXX` or a range in the form
+/// `XXXX..YYYY`.
+pub fn parse_range(field: &str) -> Result<RangeInclusive<u32>> {
+    let (first, last) = field.split_once("..").unwrap_or((field, field));
+    Ok(parse_codepoint(first)?..=parse_codepoint(last)?)
+}
+
+/// Parses a hexadecimal codepoint.
+pub fn parse_codepoint(field: &str) -> Result<u32> {
+    u32::from_str_radix(field, 16).map_err(|e| format!("invalid codepoint {field:?}: {e}").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_records() {
+        let text = "# Scripts-17.0.0.txt\n\n0041..005A    ; Latin # L&  [26]\n00AA ; Latin\n";
+        assert_eq!(version(text).unwrap(), "17.0.0");
+        let records: Vec<_> = records(text).collect();
+        assert_eq!(records, [["0041..005A", "Latin"], ["00AA", "Latin"]]);
+        assert_eq!(parse_range(records[0][0]).unwrap(), 0x41..=0x5A);
+        assert_eq!(parse_range(records[1][0]).unwrap(), 0xAA..=0xAA);
+        assert!(parse_range("12G4").is_err());
+    }
+}